@@ -0,0 +1,22 @@
+#version 300 es
+precision mediump float;
+in vec3 norm;
+in vec2 texCoord;
+in vec3 worldPos;
+in float occlusion;
+out vec4 fragment;
+
+uniform vec3 camera_position; // Set for user materials in perspective views, like the lighting uniforms
+uniform float time;           // Seconds since startup, set for every user material
+uniform vec3 tint;
+uniform float scanline_density;
+uniform float scroll_speed;
+
+void main()
+{
+    // Bright rim where the surface turns away from the camera, cut by scrolling scanlines
+    vec3 view = normalize(camera_position - worldPos);
+    float rim = pow(1.0 - abs(dot(normalize(norm), view)), 2.0);
+    float scanline = 0.6 + 0.4 * step(0.5, fract(worldPos.y * scanline_density - time * scroll_speed));
+    fragment = vec4(tint * (0.25 + rim) * scanline, 1.0);
+}
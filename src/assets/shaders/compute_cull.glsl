@@ -0,0 +1,49 @@
+#version 430 core
+layout(local_size_x = 64) in;
+
+layout(std430, row_major, binding = 4) readonly buffer Instances { mat4 world_txfms[]; };
+layout(std430, binding = 6) writeonly buffer Visible { uint visible[]; };
+// Indexed draw command for the skinned draw; instance_count is reset to 0 before this pass
+layout(std430, binding = 7) buffer Command {
+    uint index_count;
+    uint instance_count;
+    uint first_index;
+    int base_vertex;
+    uint base_instance;
+};
+
+uniform mat4 viewport_txfm;
+uniform int total_instances;
+uniform vec3 bounds_min; // Local AABB of the rest pose
+uniform vec3 bounds_max;
+
+void main()
+{
+    uint instance = gl_GlobalInvocationID.x;
+    if (instance >= uint(total_instances)) {
+        return;
+    }
+
+    // Culled when all eight corners are outside the same clip plane
+    mat4 clip_txfm = viewport_txfm * world_txfms[instance];
+    bvec3 all_below = bvec3(true);
+    bvec3 all_above = bvec3(true);
+    for (int i = 0; i < 8; ++i) {
+        vec3 corner = vec3(
+            (i & 1) == 0 ? bounds_min.x : bounds_max.x,
+            (i & 2) == 0 ? bounds_min.y : bounds_max.y,
+            (i & 4) == 0 ? bounds_min.z : bounds_max.z
+        );
+        vec4 clip = clip_txfm * vec4(corner, 1.0);
+        bvec3 below = lessThan(clip.xyz, vec3(-clip.w));
+        bvec3 above = greaterThan(clip.xyz, vec3(clip.w));
+        all_below = bvec3(all_below.x && below.x, all_below.y && below.y, all_below.z && below.z);
+        all_above = bvec3(all_above.x && above.x, all_above.y && above.y, all_above.z && above.z);
+    }
+    if (any(all_below) || any(all_above)) {
+        return;
+    }
+
+    uint slot = atomicAdd(instance_count, 1u);
+    visible[slot] = instance;
+}
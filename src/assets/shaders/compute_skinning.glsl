@@ -0,0 +1,66 @@
+#version 430 core
+layout(local_size_x = 64) in;
+
+// Rest pose, the same buffers the mesh's vertex array reads
+layout(std430, binding = 0) readonly buffer Positions { float positions[]; };
+layout(std430, binding = 1) readonly buffer Normals { float normals[]; };
+layout(std430, binding = 2) readonly buffer Joints { uint joints[]; }; // Four u8 joint indices per vertex
+layout(std430, binding = 3) readonly buffer Weights { vec4 weights[]; };
+
+layout(std430, row_major, binding = 4) readonly buffer Instances { mat4 world_txfms[]; };
+layout(std430, row_major, binding = 5) readonly buffer Palettes { mat4 palettes[]; }; // MAX_JOINTS per instance
+layout(std430, binding = 6) readonly buffer Visible { uint visible[]; };
+layout(std430, binding = 7) readonly buffer Command {
+    uint index_count;
+    uint instance_count;
+    uint first_index;
+    int base_vertex;
+    uint base_instance;
+};
+// World-space position and normal of each vertex of each visible instance
+layout(std430, binding = 8) writeonly buffer Skinned { vec4 skinned[]; };
+// MAX_MORPH_TARGETS weights per instance; read when the mesh has morph targets
+layout(std430, binding = 9) readonly buffer MorphWeights { float instance_morph_weights[]; };
+
+uniform highp sampler2D morph_texture; // Same layout as morph_targets.glsl
+uniform int morph_target_count;
+
+uniform int vertex_count;
+
+void main()
+{
+    // One row of work groups per instance; rows past the visible ones have nothing to do
+    uint vertex = gl_GlobalInvocationID.x;
+    uint slot = gl_WorkGroupID.y;
+    if (vertex >= uint(vertex_count) || slot >= instance_count) {
+        return;
+    }
+    uint instance = visible[slot];
+
+    vec3 position = vec3(positions[vertex * 3u], positions[vertex * 3u + 1u], positions[vertex * 3u + 2u]);
+    vec3 normal = vec3(normals[vertex * 3u], normals[vertex * 3u + 1u], normals[vertex * 3u + 2u]);
+    // Blend the morph targets into the rest pose, as morph_targets.glsl does
+    for (int target = 0; target < morph_target_count; ++target) {
+        float morph_weight = instance_morph_weights[instance * uint(MAX_MORPH_TARGETS) + uint(target)];
+        if (morph_weight != 0.0) {
+            int texel = (int(vertex) * morph_target_count + target) * 2;
+            position += morph_weight * texelFetch(morph_texture, ivec2(texel % MORPH_ROW, texel / MORPH_ROW), 0).xyz;
+            texel += 1;
+            normal += morph_weight * texelFetch(morph_texture, ivec2(texel % MORPH_ROW, texel / MORPH_ROW), 0).xyz;
+        }
+    }
+    uint packed_joints = joints[vertex];
+    vec4 weight = weights[vertex];
+
+    // Same blend as vertex_animated.glsl, with bone * inverse bind premultiplied per joint
+    mat4 skin = mat4(0.0);
+    for (uint i = 0u; i < 4u; ++i) {
+        uint joint = min((packed_joints >> (8u * i)) & 0xffu, uint(MAX_JOINTS) - 1u);
+        skin += weight[i] * palettes[instance * uint(MAX_JOINTS) + joint];
+    }
+
+    mat4 world_txfm = world_txfms[instance];
+    uint out_index = (slot * uint(vertex_count) + vertex) * 2u;
+    skinned[out_index] = vec4((world_txfm * skin * vec4(position, 1.0)).xyz, 1.0);
+    skinned[out_index + 1u] = vec4(normalize(mat3(world_txfm) * (mat3(skin) * normal)), 0.0);
+}
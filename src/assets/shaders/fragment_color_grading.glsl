@@ -0,0 +1,54 @@
+#version 300 es
+precision highp float;
+in vec2 uv;
+out vec4 fragment;
+
+uniform sampler2D scene_color;       // Copy of the frame to grade
+uniform sampler2D adapted_luminance; // 1x1 r: moving average of the frame's brightness
+uniform sampler2D lut;               // Strip of lut_size slices of lut_size x lut_size, blue picking the slice
+
+uniform int auto_exposure;
+uniform float exposure;   // Stops
+uniform float contrast;
+uniform float saturation;
+uniform int has_lut;
+uniform float lut_strength;
+uniform float lut_size;
+
+const float MIDDLE_GREY = 0.5;
+const vec3 LUMA = vec3(0.2126, 0.7152, 0.0722);
+
+vec3 sample_lut(vec3 color)
+{
+    // Red across a slice, green down it; blue blends the two nearest slices
+    float blue = color.b * (lut_size - 1.0);
+    float slice = floor(blue);
+    vec2 texel = vec2(color.r, color.g) * (lut_size - 1.0) + 0.5;
+    vec2 first = vec2((slice * lut_size + texel.x) / (lut_size * lut_size), texel.y / lut_size);
+    vec2 second = first + vec2(min(1.0, lut_size - 1.0 - slice) / lut_size, 0.0);
+    // Level 0: slice edges would pick a smaller mip
+    return mix(textureLod(lut, first, 0.0).rgb, textureLod(lut, second, 0.0).rgb, blue - slice);
+}
+
+void main()
+{
+    vec3 color = textureLod(scene_color, uv, 0.0).rgb;
+
+    float stops = exposure;
+    if (auto_exposure != 0) {
+        // Towards middle grey, at most two stops either way so night stays dark
+        float average = max(texture(adapted_luminance, vec2(0.5)).r, 0.0001);
+        stops += clamp(log2(MIDDLE_GREY / average), -2.0, 2.0);
+    }
+    color *= exp2(stops);
+
+    color = (color - MIDDLE_GREY) * contrast + MIDDLE_GREY;
+    color = mix(vec3(dot(color, LUMA)), color, saturation);
+    color = clamp(color, 0.0, 1.0);
+
+    if (has_lut != 0) {
+        color = mix(color, sample_lut(color), lut_strength);
+    }
+    // Alpha 1 over the frame: replaces it while leaving the depth buffer to the editor overlays
+    fragment = vec4(color, 1.0);
+}
@@ -0,0 +1,7 @@
+#version 300 es
+precision mediump float;
+
+void main()
+{
+    // Depth pre-pass: only the depth test and write matter, color writes are masked off
+}
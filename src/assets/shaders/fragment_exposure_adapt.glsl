@@ -0,0 +1,16 @@
+#version 300 es
+precision highp float;
+in vec2 uv;
+out vec4 fragment;
+
+uniform sampler2D scene_color; // Copy of the frame with mipmaps
+uniform float mip_level;       // Its 1x1 level, the average color
+uniform float adaptation;      // Share of the new brightness this frame, from the frame time and adaptation speed
+
+void main()
+{
+    // Blended over the 1x1 target with the adaptation as alpha: a moving average of the frame's brightness
+    vec3 average = textureLod(scene_color, uv, mip_level).rgb;
+    float luminance = dot(average, vec3(0.2126, 0.7152, 0.0722));
+    fragment = vec4(luminance, 0.0, 0.0, adaptation);
+}
@@ -0,0 +1,55 @@
+#version 300 es
+precision highp float;
+in vec2 uv;
+out vec4 fragment;
+
+uniform sampler2D scene_color; // Copy of the finished frame
+uniform vec2 texel_size;       // 1 / viewport size
+
+const vec3 LUMA = vec3(0.299, 0.587, 0.114);
+const float EDGE_THRESHOLD = 0.125;     // Local contrast below this (relative to the brightest neighbour) is no edge
+const float EDGE_THRESHOLD_MIN = 0.0312; // Nor is any contrast in the darks below this
+const float REDUCE_MIN = 1.0 / 128.0;
+const float REDUCE_MUL = 1.0 / 8.0;
+const float SPAN_MAX = 8.0;
+
+float luma(vec2 at)
+{
+    return dot(textureLod(scene_color, at, 0.0).rgb, LUMA);
+}
+
+void main()
+{
+    vec3 center = textureLod(scene_color, uv, 0.0).rgb;
+    float luma_center = dot(center, LUMA);
+    float luma_nw = luma(uv + vec2(-1.0, -1.0) * texel_size);
+    float luma_ne = luma(uv + vec2(1.0, -1.0) * texel_size);
+    float luma_sw = luma(uv + vec2(-1.0, 1.0) * texel_size);
+    float luma_se = luma(uv + vec2(1.0, 1.0) * texel_size);
+
+    float luma_min = min(luma_center, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    float luma_max = max(luma_center, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+    if (luma_max - luma_min < max(EDGE_THRESHOLD_MIN, luma_max * EDGE_THRESHOLD)) {
+        fragment = vec4(center, 1.0);
+        return;
+    }
+
+    // Blur along the edge: perpendicular to the luma gradient, longer for softer edges
+    vec2 direction = vec2(-((luma_nw + luma_ne) - (luma_sw + luma_se)), (luma_nw + luma_sw) - (luma_ne + luma_se));
+    float reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    float scale = 1.0 / (min(abs(direction.x), abs(direction.y)) + reduce);
+    direction = clamp(direction * scale, vec2(-SPAN_MAX), vec2(SPAN_MAX)) * texel_size;
+
+    vec3 near = 0.5 * (
+        textureLod(scene_color, uv + direction * (1.0 / 3.0 - 0.5), 0.0).rgb +
+        textureLod(scene_color, uv + direction * (2.0 / 3.0 - 0.5), 0.0).rgb);
+    vec3 far = near * 0.5 + 0.25 * (
+        textureLod(scene_color, uv - direction * 0.5, 0.0).rgb +
+        textureLod(scene_color, uv + direction * 0.5, 0.0).rgb);
+
+    // The wide tap crossed into another edge if it left the local luma range; keep the near one then
+    float luma_far = dot(far, LUMA);
+    vec3 color = (luma_far < luma_min || luma_far > luma_max) ? near : far;
+    // Alpha 1 over the frame: replaces it while leaving the depth buffer to the editor overlays
+    fragment = vec4(color, 1.0);
+}
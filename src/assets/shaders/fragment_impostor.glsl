@@ -0,0 +1,18 @@
+#version 300 es
+precision mediump float;
+in vec2 texCoord;
+out vec4 fragment;
+
+uniform sampler2D impostor; // The instance's meshes baked from the side, transparent around them
+uniform float fade;         // Opacity while the impostor fades in over the meshes
+
+#include "lighting.glsl"
+
+void main()
+{
+    vec4 color = texture(impostor, texCoord);
+    if (color.a < 0.5) {
+        discard;
+    }
+    fragment = vec4(applyFog(color.rgb), fade);
+}
@@ -0,0 +1,19 @@
+#version 300 es
+precision highp float;
+in vec2 uv;
+out vec4 fragment;
+
+uniform sampler2D accumulation; // rgb: weighted color sum, a: revealage (product of 1 - alpha)
+uniform sampler2D weights;      // r: weighted alpha sum
+
+void main()
+{
+    vec4 accum = texture(accumulation, uv);
+    float revealage = accum.a;
+    if (revealage >= 1.0) {
+        discard; // No transparent surface here
+    }
+    // Average color of the surfaces, covering all but the revealed part of the scene
+    float weight = texture(weights, uv).r;
+    fragment = vec4(accum.rgb / max(weight, 1e-5), 1.0 - revealage);
+}
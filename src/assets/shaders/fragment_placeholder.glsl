@@ -0,0 +1,12 @@
+#version 300 es
+precision mediump float;
+in vec3 norm;
+out vec4 fragment;
+
+void main()
+{
+    // Flat magenta so missing assets stand out in the viewport
+    vec3 light_dir = normalize(vec3(0.0, -1.0, 0.0));
+    float lighting = 0.4 + max(dot(norm, -light_dir), 0.0) * 0.6;
+    fragment = vec4(lighting * vec3(1.0, 0.0, 1.0), 1.0);
+}
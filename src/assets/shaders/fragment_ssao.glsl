@@ -0,0 +1,81 @@
+#version 300 es
+precision highp float;
+in vec2 uv;
+out vec4 fragment;
+
+uniform sampler2D scene_depth;
+uniform float near;
+uniform float far;
+uniform float tan_half_fov;
+uniform float aspect;
+uniform float radius;    // View-space reach of the samples
+uniform float intensity; // Occlusion scale, 1 darkens fully enclosed points to black
+
+const int SAMPLES = 16;
+const float BIAS = 0.025;
+const float GOLDEN_ANGLE = 2.39996323;
+
+// View-space position of the surface at a screen position, rebuilt from the depth buffer
+vec3 viewPosition(vec2 coord)
+{
+    float ndcDepth = texture(scene_depth, coord).r * 2.0 - 1.0;
+    float viewDepth = 2.0 * near * far / (far + near - ndcDepth * (far - near));
+    vec2 ndc = coord * 2.0 - 1.0;
+    return vec3(ndc * vec2(tan_half_fov * aspect, tan_half_fov) * viewDepth, -viewDepth);
+}
+
+vec2 screenPosition(vec3 viewPos)
+{
+    vec2 ndc = viewPos.xy / (-viewPos.z * vec2(tan_half_fov * aspect, tan_half_fov));
+    return ndc * 0.5 + 0.5;
+}
+
+float hash(vec2 p)
+{
+    return fract(sin(dot(p, vec2(12.9898, 78.233))) * 43758.5453);
+}
+
+void main()
+{
+    if (texture(scene_depth, uv).r >= 1.0) {
+        fragment = vec4(1.0); // Sky
+        return;
+    }
+    vec3 position = viewPosition(uv);
+
+    // Normal from the neighbours on the side nearer in depth, so silhouettes don't mix two surfaces
+    vec2 texel = 1.0 / vec2(textureSize(scene_depth, 0));
+    vec3 right = viewPosition(uv + vec2(texel.x, 0.0)) - position;
+    vec3 left = position - viewPosition(uv - vec2(texel.x, 0.0));
+    vec3 up = viewPosition(uv + vec2(0.0, texel.y)) - position;
+    vec3 down = position - viewPosition(uv - vec2(0.0, texel.y));
+    vec3 dx = abs(right.z) < abs(left.z) ? right : left;
+    vec3 dy = abs(up.z) < abs(down.z) ? up : down;
+    vec3 normal = normalize(cross(dx, dy));
+
+    // Hemisphere around the normal, turned by a per-pixel angle that the composite blur averages out
+    float angle = hash(gl_FragCoord.xy) * 6.2831853;
+    vec3 random = vec3(cos(angle), sin(angle), 0.0);
+    vec3 tangent = normalize(random - normal * dot(random, normal));
+    vec3 bitangent = cross(normal, tangent);
+
+    float occlusion = 0.0;
+    for (int i = 0; i < SAMPLES; i++) {
+        // Cosine-weighted spiral of directions, with more samples close to the point
+        float t = (float(i) + 0.5) / float(SAMPLES);
+        float phi = float(i) * GOLDEN_ANGLE;
+        vec3 direction = vec3(cos(phi) * sqrt(t), sin(phi) * sqrt(t), sqrt(1.0 - t));
+        float scale = mix(0.1, 1.0, t * t);
+        vec3 samplePos = position + (tangent * direction.x + bitangent * direction.y + normal * direction.z) * radius * scale;
+
+        vec2 sampleUv = screenPosition(samplePos);
+        if (any(lessThan(sampleUv, vec2(0.0))) || any(greaterThan(sampleUv, vec2(1.0)))) {
+            continue;
+        }
+        // Occluded when the visible surface there is in front of the sample; far-away surfaces fade out
+        float sceneZ = viewPosition(sampleUv).z;
+        float inRange = smoothstep(0.0, 1.0, radius / abs(position.z - sceneZ));
+        occlusion += (sceneZ >= samplePos.z + BIAS ? 1.0 : 0.0) * inRange;
+    }
+    fragment = vec4(clamp(1.0 - occlusion / float(SAMPLES) * intensity, 0.0, 1.0), 0.0, 0.0, 1.0);
+}
@@ -0,0 +1,19 @@
+#version 300 es
+precision highp float;
+in vec2 uv;
+out vec4 fragment;
+
+uniform sampler2D occlusion; // r: unblurred ambient visibility from fragment_ssao.glsl
+
+void main()
+{
+    // 4x4 box blur hides the per-pixel sample rotation; the result multiplies the scene
+    vec2 texel = 1.0 / vec2(textureSize(occlusion, 0));
+    float sum = 0.0;
+    for (int x = -2; x < 2; x++) {
+        for (int y = -2; y < 2; y++) {
+            sum += texture(occlusion, uv + (vec2(float(x), float(y)) + 0.5) * texel).r;
+        }
+    }
+    fragment = vec4(vec3(sum / 16.0), 1.0);
+}
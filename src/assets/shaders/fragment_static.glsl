@@ -2,33 +2,72 @@
 precision mediump float;
 in vec3 norm;
 in vec2 texCoord;
+in vec4 vertexColor; // White unless the mesh was imported with vertex colors
+in vec3 worldPos;
+in float occlusion; // Baked ambient occlusion, 1 where open
 out vec4 fragment;
 
 uniform sampler2D baseColorTexture;
 uniform bool hasTexture;
+uniform bool hasVertexColors;
+uniform samplerCube probe_cubemap; // Reflection probe whose box holds this mesh, see ReflectionProbeSystem
+uniform bool has_probe;
+uniform vec3 probe_position;
+uniform vec3 probe_box_min;
+uniform vec3 probe_box_max;
+uniform bool box_projection;       // Reflect off the probe box's walls (rooms) instead of a distant surrounding
+uniform float probe_mip_levels;
+uniform vec3 camera_position;
+uniform float metallic;
+uniform float roughness;
+
+#include "lighting.glsl"
+
+// Mix in the probe's reflection of the view ray, blurrier on rough surfaces and tinted by the base color on metals
+vec3 applyReflection(vec3 litColor, vec3 baseColor, vec3 normal)
+{
+    vec3 view = normalize(worldPos - camera_position);
+    vec3 direction = reflect(view, normal);
+    if (box_projection) {
+        // Where the reflected ray leaves the box, seen from the probe
+        vec3 exits = max((probe_box_max - worldPos) / direction, (probe_box_min - worldPos) / direction);
+        float travel = min(min(exits.x, exits.y), exits.z);
+        direction = worldPos + direction * travel - probe_position;
+    }
+    vec3 environment = textureLod(probe_cubemap, direction, roughness * (probe_mip_levels - 1.0)).rgb;
+
+    // Schlick fresnel with roughness; dielectrics reflect 4% head-on
+    float facing = clamp(dot(normal, -view), 0.0, 1.0);
+    vec3 f0 = mix(vec3(0.04), baseColor, metallic);
+    vec3 fresnel = f0 + (max(vec3(1.0 - roughness), f0) - f0) * pow(1.0 - facing, 5.0);
+    return litColor * (1.0 - metallic) + environment * fresnel;
+}
 
 void main()
 {
-    // Top-down directional light
-    vec3 light_dir = normalize(vec3(0.0, -1.0, 0.0)); // Pure top-down light
-    float diffuse = max(dot(norm, -light_dir), 0.0);
-    float ambient = 0.2; // Slightly higher ambient for top-down lighting
+    // Directional sun/moon light
+    float diffuse = directionalDiffuse(norm);
+    float ambient = ambient_strength * occlusion;
     
-    // Default brown/wood color for static objects
-    vec3 baseColor = vec3(0.6, 0.4, 0.2);
+    // Default brown/wood color for static objects, unless vertex colors paint them
+    vec3 baseColor = hasVertexColors ? vertexColor.rgb : vec3(0.6, 0.4, 0.2);
     if (hasTexture) {
-        vec4 texColor = texture(baseColorTexture, texCoord);
+        vec4 texColor = texture(baseColorTexture, texCoord) * vertexColor;
         baseColor = texColor.rgb;
         
         // Preserve very dark colors (black regions)
         if (texColor.r < 0.1 && texColor.g < 0.1 && texColor.b < 0.1) {
             // For very dark pixels, use minimal lighting to preserve black colors
-            fragment = vec4(texColor.rgb * (ambient + diffuse * 0.1), 1.0);
+            fragment = vec4(applyFog(texColor.rgb * (ambient + diffuse * 0.1) * light_color), 1.0);
             return;
         }
     }
     
     // Apply dynamic lighting that responds to surface orientation
-    float lighting = ambient + diffuse * 0.8;
-    fragment = vec4(lighting * baseColor, 1.0);
+    vec3 lighting = sceneLighting(ambient, worldPos, norm);
+    vec3 color = lighting * baseColor;
+    if (has_probe) {
+        color = applyReflection(color, baseColor, normalize(norm));
+    }
+    fragment = vec4(applyFog(color), 1.0);
 }
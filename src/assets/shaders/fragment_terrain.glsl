@@ -0,0 +1,22 @@
+#version 300 es
+precision mediump float;
+in vec3 norm;
+in float height;
+in vec3 worldPos;
+out vec4 fragment;
+
+#include "lighting.glsl"
+
+void main()
+{
+    // Grass on flat ground, rock on slopes, lighter near the peaks
+    vec3 grass = vec3(0.3, 0.5, 0.2);
+    vec3 rock = vec3(0.45, 0.42, 0.38);
+    vec3 peak = vec3(0.8, 0.8, 0.78);
+    float slope = 1.0 - clamp(norm.y, 0.0, 1.0);
+    vec3 baseColor = mix(grass, rock, smoothstep(0.2, 0.5, slope));
+    baseColor = mix(baseColor, peak, smoothstep(6.0, 10.0, height));
+
+    vec3 lighting = sceneLighting(ambient_strength, worldPos, norm);
+    fragment = vec4(applyFog(lighting * baseColor), 1.0);
+}
@@ -0,0 +1,25 @@
+#version 300 es
+precision highp float;
+in vec3 norm;
+in vec2 texCoord;
+in vec4 vertexColor; // White unless the mesh was imported with vertex colors
+in vec3 worldPos;
+
+uniform sampler2D baseColorTexture;
+uniform bool hasTexture;
+uniform bool hasVertexColors;
+uniform float opacity;
+
+#include "lighting.glsl"
+#include "weighted_blended.glsl"
+
+void main()
+{
+    vec4 baseColor = hasVertexColors ? vertexColor : vec4(0.6, 0.4, 0.2, 1.0);
+    if (hasTexture) {
+        baseColor = texture(baseColorTexture, texCoord) * vertexColor;
+    }
+
+    vec3 lighting = sceneLighting(ambient_strength, worldPos, norm);
+    writeTransparent(applyFog(lighting * baseColor.rgb), clamp(baseColor.a * opacity, 0.0, 1.0));
+}
@@ -0,0 +1,63 @@
+#version 300 es
+precision highp float;
+in vec3 worldPos;
+
+uniform mat4 viewport_txfm;
+uniform vec3 camera_position;
+uniform float time;
+uniform float wave_scale;
+uniform float reflectivity;
+uniform vec3 water_color;
+uniform float opacity;         // Where reflections are weak; 1 is opaque
+
+#include "lighting.glsl"
+#include "weighted_blended.glsl"
+
+uniform sampler2D scene_color; // Copy of the frame rendered so far
+uniform int has_scene_color;
+
+// Slope of a sum of travelling sine waves, used as an animated normal
+vec3 waveNormal(vec2 p)
+{
+    vec2 slope = vec2(0.0);
+    slope += vec2(0.8, 0.6) * cos(dot(p, vec2(0.8, 0.6)) * 1.3 + time * 1.1) * 0.5;
+    slope += vec2(-0.4, 0.9) * cos(dot(p, vec2(-0.4, 0.9)) * 2.7 + time * 1.7) * 0.3;
+    slope += vec2(0.95, -0.3) * cos(dot(p, vec2(0.95, -0.3)) * 5.1 + time * 2.3) * 0.2;
+    return normalize(vec3(-slope.x * wave_scale, 1.0, -slope.y * wave_scale));
+}
+
+void main()
+{
+    vec3 normal = waveNormal(worldPos.xz);
+    vec3 toCamera = normalize(camera_position - worldPos);
+
+    // Schlick fresnel with the reflectance of water at normal incidence
+    float cosTheta = clamp(dot(normal, toCamera), 0.0, 1.0);
+    float fresnel = 0.02 + 0.98 * pow(1.0 - cosTheta, 5.0);
+
+    // Screen-space reflection approximation: project a point along the reflected ray
+    // and reuse the already rendered frame there, falling back to the sky colour
+    vec3 reflection = sky_color;
+    if (has_scene_color == 1) {
+        vec3 reflected = reflect(-toCamera, normal);
+        vec4 clip = viewport_txfm * vec4(worldPos + reflected * 10.0, 1.0);
+        if (clip.w > 0.0) {
+            vec2 uv = clip.xy / clip.w * 0.5 + 0.5;
+            if (all(greaterThanEqual(uv, vec2(0.0))) && all(lessThanEqual(uv, vec2(1.0)))) {
+                vec2 edge = min(uv, 1.0 - uv);
+                float fade = clamp(min(edge.x, edge.y) * 10.0, 0.0, 1.0);
+                reflection = mix(sky_color, texture(scene_color, uv).rgb, fade);
+            }
+        }
+    }
+
+    float diffuse = directionalDiffuse(normal);
+    vec3 body = water_color * (ambient_strength + diffuse * 0.6) * light_color;
+
+    vec3 halfVector = normalize(toCamera - normalize(light_direction));
+    float specular = pow(max(dot(normal, halfVector), 0.0), 128.0);
+
+    float reflected = clamp(fresnel * reflectivity, 0.0, 1.0);
+    vec3 color = mix(body, reflection, reflected) + specular * light_color;
+    writeTransparent(applyFog(color), clamp(mix(opacity, 1.0, reflected) + specular, 0.0, 1.0));
+}
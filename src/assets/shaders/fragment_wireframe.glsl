@@ -1,9 +1,10 @@
 #version 300 es
 precision mediump float;
+uniform vec3 line_color;
 out vec4 fragment;
 
 void main()
 {
-    // Output solid green color for wireframe
-    fragment = vec4(0.0, 1.0, 0.0, 1.0);
+    // Solid line color set by the caller (navmesh debug, category icons)
+    fragment = vec4(line_color, 1.0);
 }
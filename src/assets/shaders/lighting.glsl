@@ -0,0 +1,29 @@
+// Directional sun/moon light and distance fog of the scene TimeOfDay, set by RenderSystem, plus the clustered
+// point lights. Spliced into lit fragment shaders at their #include line.
+uniform vec3 light_direction;
+uniform vec3 light_color;
+uniform float ambient_strength;
+uniform float fog_density; // Exponential squared distance fog towards sky_color, 0 disables it
+uniform vec3 sky_color;
+
+#include "point_lights.glsl"
+
+// How squarely a surface faces the directional light
+float directionalDiffuse(vec3 normal)
+{
+    return max(dot(normal, -normalize(light_direction)), 0.0);
+}
+
+// Ambient, directional and point light reaching a surface; `ambient` is ambient_strength, darkened by any occlusion
+vec3 sceneLighting(float ambient, highp vec3 position, vec3 normal)
+{
+    return (ambient + directionalDiffuse(normal) * 0.8) * light_color + pointLights(position, normal);
+}
+
+// Blend towards the sky colour with view distance (1/gl_FragCoord.w is the clip-space w)
+vec3 applyFog(vec3 color)
+{
+    float viewDistance = 1.0 / gl_FragCoord.w;
+    float fog = 1.0 - exp(-pow(fog_density * viewDistance, 2.0));
+    return mix(color, sky_color, clamp(fog, 0.0, 1.0));
+}
@@ -0,0 +1,19 @@
+// Morph targets of animated meshes, set by RenderSystem and GpuSkinningSystem. MAX_MORPH_TARGETS and MORPH_ROW are
+// defined by the assets manager to match gpu_skinning_system.rs. Spliced into shaders at their #include line.
+uniform highp sampler2D morph_texture;             // Per vertex, per target: position then normal displacement
+uniform int morph_target_count;                    // 0 without targets
+uniform vec4 morph_weights[MAX_MORPH_TARGETS / 4]; // Four target weights per element
+
+// Displacement of `vertex` for `attribute` (0 position, 1 normal) blended by the target weights
+vec3 morphDelta(int vertex, int attribute)
+{
+    vec3 delta = vec3(0.0);
+    for (int target = 0; target < morph_target_count; ++target) {
+        float weight = morph_weights[target / 4][target % 4];
+        if (weight != 0.0) {
+            int texel = (vertex * morph_target_count + target) * 2 + attribute;
+            delta += weight * texelFetch(morph_texture, ivec2(texel % MORPH_ROW, texel / MORPH_ROW), 0).xyz;
+        }
+    }
+    return delta;
+}
@@ -0,0 +1,17 @@
+// Screen-space outline thickness, set by RenderSystem from the editor's outline settings. Spliced into the outline
+// vertex shaders at their #include line.
+uniform float outline_width; // Pixels, 0 draws the mesh at its own size
+uniform vec2 viewport_size;  // Framebuffer size in pixels
+
+// Push a clip-space vertex `outline_width` pixels away from the object's origin on screen, so the outline is as
+// thick up close as far away
+vec4 outlineOffset(vec4 clip_pos, vec4 clip_origin)
+{
+    vec2 direction = clip_pos.xy / clip_pos.w - clip_origin.xy / clip_origin.w;
+    if (outline_width <= 0.0 || dot(direction, direction) == 0.0) {
+        return clip_pos;
+    }
+    // NDC spans two units across the viewport; scaled by w to undo the perspective divide
+    clip_pos.xy += normalize(direction * viewport_size) * outline_width * 2.0 / viewport_size * clip_pos.w;
+    return clip_pos;
+}
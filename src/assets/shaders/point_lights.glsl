@@ -0,0 +1,48 @@
+// Point lights, assigned by LightClusterSystem to clusters: screen tiles split into exponential depth slices.
+// Included by lighting.glsl; the constants match light_cluster_system.rs.
+const int CLUSTERS_X = 16;
+const int CLUSTERS_Y = 9;
+const int CLUSTERS_Z = 24;
+const int LIGHT_INDEX_ROW = 2048;
+
+uniform highp sampler2D point_lights;    // Two texels per light: position and range, color times intensity
+uniform highp usampler2D light_clusters; // Texel (tile x + tile y * CLUSTERS_X, slice): first index, light count
+uniform highp usampler2D light_indices;  // Lights of every cluster, LIGHT_INDEX_ROW per row
+uniform int point_light_count;           // 0 skips the lookup
+uniform float cluster_tile_width;        // Pixels per screen tile
+uniform float cluster_tile_height;
+uniform float cluster_near;              // View depth of the first and last slice boundary
+uniform float cluster_far;
+
+// Slice of a view depth: exponential between the range ends, so near clusters stay small
+int clusterSlice(highp float depth)
+{
+    highp float slice = log(max(depth, cluster_near) / cluster_near) / log(cluster_far / cluster_near);
+    return clamp(int(slice * float(CLUSTERS_Z)), 0, CLUSTERS_Z - 1);
+}
+
+// Diffuse light of the cluster's point lights, falling off smoothly to zero at each light's range
+vec3 pointLights(highp vec3 position, vec3 normal)
+{
+    if (point_light_count == 0) {
+        return vec3(0.0);
+    }
+    ivec2 tile = min(ivec2(gl_FragCoord.xy / vec2(cluster_tile_width, cluster_tile_height)), ivec2(CLUSTERS_X - 1, CLUSTERS_Y - 1));
+    int slice = clusterSlice(1.0 / gl_FragCoord.w);
+    uvec2 cluster = texelFetch(light_clusters, ivec2(tile.x + tile.y * CLUSTERS_X, slice), 0).rg;
+
+    vec3 total = vec3(0.0);
+    for (uint i = 0u; i < cluster.y; ++i) {
+        int index = int(cluster.x + i);
+        int light = int(texelFetch(light_indices, ivec2(index % LIGHT_INDEX_ROW, index / LIGHT_INDEX_ROW), 0).r);
+        highp vec4 positionRange = texelFetch(point_lights, ivec2(light * 2, 0), 0);
+        vec3 radiance = texelFetch(point_lights, ivec2(light * 2 + 1, 0), 0).rgb;
+
+        highp vec3 toLight = positionRange.xyz - position;
+        highp float lightDistance = length(toLight);
+        float window = clamp(1.0 - pow(lightDistance / positionRange.w, 4.0), 0.0, 1.0);
+        float attenuation = window * window / (lightDistance * lightDistance + 1.0);
+        total += radiance * attenuation * max(dot(normal, toLight / max(lightDistance, 0.0001)), 0.0);
+    }
+    return total;
+}
@@ -0,0 +1,14 @@
+// Bone palette of vertex-shader skinning, set by RenderSystem; MAX_JOINTS is defined by the assets manager to
+// match gpu_skinning_system.rs. Spliced into animated vertex shaders at their #include line.
+uniform mat4 inverse_bone_matrix[MAX_JOINTS];
+uniform mat4 bone_matrix[MAX_JOINTS];
+
+// Blend of the vertex's four joints from bind pose to their current pose
+mat4 skinMatrix(uvec4 joints, vec4 weights)
+{
+    mat4 skin = mat4(0.0);
+    for (int i = 0; i < 4; ++i) {
+        skin += weights[i] * (bone_matrix[joints[i]] * inverse_bone_matrix[joints[i]]);
+    }
+    return skin;
+}
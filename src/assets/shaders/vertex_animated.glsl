@@ -4,35 +4,34 @@ layout(location = 1) in vec3 vPos;
 layout(location = 2) in uvec4 vJoints;
 layout(location = 3) in vec4 vWeights;
 layout(location = 4) in vec2 vTexCoord;
+layout(location = 5) in vec4 vColor;
 
 uniform mat4 world_txfm;
 uniform mat4 viewport_txfm;
 uniform int preview_joint;
-uniform mat4 inverse_bone_matrix[20];
-uniform mat4 bone_matrix[20];
+
+#include "skinning.glsl"
+#include "morph_targets.glsl"
 
 out vec3 norm;
 out vec2 texCoord;
+out vec4 vertexColor;
 out float joint_color;
+out vec3 worldPos;
 
 void main()
 {
-    // Transform vertex position with skeletal animation
-    gl_Position = vec4(0.0);
+    // Blend the morph targets into the rest pose, then transform it with skeletal animation
+    vec3 position = vPos + morphDelta(gl_VertexID, 0);
+    vec3 normal = vNorm + morphDelta(gl_VertexID, 1);
+    mat4 skin = skinMatrix(vJoints, vWeights);
+    vec4 world_position = world_txfm * skin * vec4(position, 1.0);
     joint_color = 0.0;
-    for (int i = 0; i < 4; ++i) { 
-        gl_Position += vWeights[i] * (viewport_txfm * world_txfm * bone_matrix[vJoints[i]] * inverse_bone_matrix[vJoints[i]] * vec4(vPos, 1.0));
-    }
-    
-    // Transform normals with skeletal animation (same bone matrices as vertices)
-    vec3 transformed_normal = vec3(0.0);
-    for (int i = 0; i < 4; ++i) {
-        // Transform normal by bone matrix (3x3 part only)
-        mat3 bone_normal_matrix = mat3(bone_matrix[vJoints[i]] * inverse_bone_matrix[vJoints[i]]);
-        transformed_normal += vWeights[i] * (bone_normal_matrix * vNorm);
-    }
-    
-    // Apply world transform to the animated normal
-    norm = normalize(mat3(world_txfm) * transformed_normal);
+    gl_Position = viewport_txfm * world_position;
+    worldPos = world_position.xyz;
+
+    // Normals follow the same bone matrices (3x3 part only), then the world transform
+    norm = normalize(mat3(world_txfm) * mat3(skin) * normal);
     texCoord = vTexCoord;
+    vertexColor = vColor;
 }
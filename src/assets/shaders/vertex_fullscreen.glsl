@@ -0,0 +1,10 @@
+#version 300 es
+out vec2 uv;
+
+void main()
+{
+    // One triangle covering the screen, built from the vertex index
+    vec2 corner = vec2(float((gl_VertexID << 1) & 2), float(gl_VertexID & 2));
+    uv = corner;
+    gl_Position = vec4(corner * 2.0 - 1.0, 0.0, 1.0);
+}
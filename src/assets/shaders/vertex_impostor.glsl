@@ -0,0 +1,26 @@
+#version 300 es
+
+uniform mat4 viewport_txfm;
+uniform vec3 center;          // Middle of the instance's bounds
+uniform vec2 half_size;       // Horizontal radius and half height of the bounds
+uniform vec3 camera_position;
+
+out vec2 texCoord;
+
+void main()
+{
+    // Two triangles of a quad turned about the vertical towards the camera, generated from gl_VertexID
+    const vec2 corners[6] = vec2[6](
+        vec2(-1.0, -1.0), vec2(1.0, -1.0), vec2(-1.0, 1.0),
+        vec2(-1.0, 1.0), vec2(1.0, -1.0), vec2(1.0, 1.0)
+    );
+    vec2 corner = corners[gl_VertexID];
+
+    vec2 toCamera = camera_position.xz - center.xz;
+    vec2 facing = length(toCamera) > 0.0001 ? normalize(toCamera) : vec2(0.0, 1.0);
+    vec3 right = vec3(facing.y, 0.0, -facing.x);
+
+    vec3 world = center + right * corner.x * half_size.x + vec3(0.0, corner.y * half_size.y, 0.0);
+    texCoord = corner * 0.5 + 0.5;
+    gl_Position = viewport_txfm * vec4(world, 1.0);
+}
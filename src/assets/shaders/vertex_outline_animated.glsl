@@ -7,18 +7,15 @@ layout(location = 4) in vec2 vTexCoord;
 
 uniform mat4 world_txfm;
 uniform mat4 viewport_txfm;
-uniform float outline_scale;
-uniform mat4 inverse_bone_matrix[20];
-uniform mat4 bone_matrix[20];
+
+#include "skinning.glsl"
+#include "morph_targets.glsl"
+#include "outline.glsl"
 
 void main()
 {
-    // Scale the vertex position for outline effect
-    vec3 scaled_pos = vPos * outline_scale;
-    
-    // Transform vertex position with skeletal animation (using scaled position)
-    gl_Position = vec4(0.0);
-    for (int i = 0; i < 4; ++i) { 
-        gl_Position += vWeights[i] * (viewport_txfm * world_txfm * bone_matrix[vJoints[i]] * inverse_bone_matrix[vJoints[i]] * vec4(scaled_pos, 1.0));
-    }
+    // Morph and skin the vertex like vertex_animated.glsl, then push it away from the object's origin on screen
+    vec3 morphed_pos = vPos + morphDelta(gl_VertexID, 0);
+    vec4 clip_pos = viewport_txfm * world_txfm * skinMatrix(vJoints, vWeights) * vec4(morphed_pos, 1.0);
+    gl_Position = outlineOffset(clip_pos, viewport_txfm * world_txfm[3]);
 }
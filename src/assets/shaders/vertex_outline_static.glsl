@@ -5,13 +5,11 @@ layout(location = 4) in vec2 vTexCoord;
 
 uniform mat4 world_txfm;
 uniform mat4 viewport_txfm;
-uniform float outline_scale;
+
+#include "outline.glsl"
 
 void main()
 {
-    // Scale the vertex position for outline effect
-    vec3 scaled_pos = vPos * outline_scale;
-    
-    // Transform the scaled position
-    gl_Position = viewport_txfm * world_txfm * vec4(scaled_pos, 1.0);
+    // Push the vertex away from the object's origin on screen for outline effect
+    gl_Position = outlineOffset(viewport_txfm * world_txfm * vec4(vPos, 1.0), viewport_txfm * world_txfm[3]);
 }
@@ -0,0 +1,15 @@
+#version 300 es
+layout(location = 0) in vec3 vNorm;
+layout(location = 1) in vec3 vPos;
+
+uniform mat4 world_txfm;
+uniform mat4 viewport_txfm;
+
+out vec3 norm;
+
+void main()
+{
+    // Placeholder for assets/shaders that failed to load: no skinning, no texture
+    gl_Position = viewport_txfm * world_txfm * vec4(vPos, 1.0);
+    norm = normalize(mat3(world_txfm) * vNorm);
+}
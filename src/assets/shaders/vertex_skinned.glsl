@@ -0,0 +1,27 @@
+#version 430 core
+layout(location = 4) in vec2 vTexCoord;
+layout(location = 5) in vec4 vColor;
+
+// Written by compute_skinning.glsl: world position and normal per vertex of each visible instance
+layout(std430, binding = 8) readonly buffer Skinned { vec4 skinned[]; };
+
+uniform mat4 viewport_txfm;
+uniform int vertex_count;
+
+out vec3 norm;
+out vec2 texCoord;
+out vec4 vertexColor;
+out float joint_color;
+out vec3 worldPos;
+
+void main()
+{
+    // Instances are the visible ones in the order the culling pass found them; gl_VertexID is the mesh index
+    int vertex = (gl_InstanceID * vertex_count + gl_VertexID) * 2;
+    gl_Position = viewport_txfm * skinned[vertex];
+    worldPos = skinned[vertex].xyz;
+    norm = skinned[vertex + 1].xyz;
+    texCoord = vTexCoord;
+    vertexColor = vColor;
+    joint_color = 0.0;
+}
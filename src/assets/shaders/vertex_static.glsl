@@ -2,19 +2,42 @@
 layout(location = 0) in vec3 vNorm;
 layout(location = 1) in vec3 vPos;
 layout(location = 4) in vec2 vTexCoord;
+layout(location = 5) in vec4 vColor;
 
 uniform mat4 world_txfm;
 uniform mat4 viewport_txfm;
+uniform highp sampler2D occlusion_texture; // Baked per-vertex ambient occlusion, OCCLUSION_ROW vertices per row
+uniform float occlusion_strength;          // 0 without a bake
+
+const int OCCLUSION_ROW = 1024;
 
 out vec3 norm;
 out vec2 texCoord;
+out vec4 vertexColor;
+out vec3 worldPos;
+out float occlusion;
+
+// The depth pre-pass draws with this shader too, and the lit pass must land on exactly the same depths
+invariant gl_Position;
 
 void main()
 {
     // Simple vertex transformation without skeletal animation
-    gl_Position = viewport_txfm * world_txfm * vec4(vPos, 1.0);
-    
+    vec4 world_position = world_txfm * vec4(vPos, 1.0);
+    gl_Position = viewport_txfm * world_position;
+    worldPos = world_position.xyz;
+
+#ifndef DEPTH_ONLY // The depth pre-pass variant only needs the position
     // Transform normal with world matrix
     norm = normalize(mat3(world_txfm) * vNorm);
     texCoord = vTexCoord;
+    vertexColor = vColor;
+
+    // Indexed draws, so gl_VertexID is the mesh vertex the bake stored
+    occlusion = 1.0;
+    if (occlusion_strength > 0.0) {
+        float baked = texelFetch(occlusion_texture, ivec2(gl_VertexID % OCCLUSION_ROW, gl_VertexID / OCCLUSION_ROW), 0).r;
+        occlusion = mix(1.0, baked, occlusion_strength);
+    }
+#endif
 }
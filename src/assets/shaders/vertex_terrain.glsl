@@ -0,0 +1,19 @@
+#version 300 es
+layout(location = 0) in vec3 vPos;
+layout(location = 1) in vec3 vNorm;
+
+uniform mat4 world_txfm;
+uniform mat4 viewport_txfm;
+
+out vec3 norm;
+out float height;
+out vec3 worldPos;
+
+void main()
+{
+    vec4 world_position = world_txfm * vec4(vPos, 1.0);
+    gl_Position = viewport_txfm * world_position;
+    worldPos = world_position.xyz;
+    norm = normalize(mat3(world_txfm) * vNorm);
+    height = vPos.y;
+}
@@ -0,0 +1,21 @@
+#version 300 es
+
+uniform mat4 world_txfm;
+uniform mat4 viewport_txfm;
+uniform vec2 size;
+
+out vec3 worldPos;
+
+void main()
+{
+    // Two triangles covering the water plane, generated from gl_VertexID
+    const vec2 corners[6] = vec2[6](
+        vec2(-0.5, -0.5), vec2(-0.5, 0.5), vec2(0.5, -0.5),
+        vec2(0.5, -0.5), vec2(-0.5, 0.5), vec2(0.5, 0.5)
+    );
+    vec2 corner = corners[gl_VertexID] * size;
+
+    vec4 world = world_txfm * vec4(corner.x, 0.0, corner.y, 1.0);
+    worldPos = world.xyz;
+    gl_Position = viewport_txfm * world;
+}
@@ -0,0 +1,21 @@
+// Output of blended surfaces (transparent meshes, water), declaring both render targets of the transparency pass.
+// Spliced into their fragment shaders at the #include line.
+layout(location = 0) out vec4 fragment;
+layout(location = 1) out vec4 weight; // Weighted blended only
+
+uniform bool weighted_blended; // Drawing into the order-independent accumulation targets
+
+// Straight alpha for sorted blending, or weighted sums for the composite pass. The weight favours nearby
+// surfaces (McGuire & Bavoil 2013, eq. 7)
+void writeTransparent(vec3 color, float alpha)
+{
+    if (!weighted_blended) {
+        fragment = vec4(color, alpha);
+        weight = vec4(0.0);
+        return;
+    }
+    float viewDistance = 1.0 / gl_FragCoord.w;
+    float w = alpha * clamp(10.0 / (1e-5 + pow(viewDistance / 5.0, 2.0) + pow(viewDistance / 200.0, 6.0)), 1e-2, 3e3);
+    fragment = vec4(color * alpha * w, alpha);
+    weight = vec4(alpha * w);
+}
@@ -0,0 +1,64 @@
+use serde::{ Serialize, Deserialize };
+
+/// A single configurable behavior; an agent combines any number of them
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum AiBehavior {
+    Patrol { waypoints: Vec<[f32; 3]>, wait_time: f32 },      // Walk the waypoints in a loop, pausing at each
+    Chase { radius: f32, give_up_radius: f32 },               // Follow the player once within `radius`
+    LookAt { head_node: u32, radius: f32, max_yaw: f32 },     // Turn an animated head node towards the player
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum AiState {
+    #[default]
+    Idle,
+    Patrolling,
+    Chasing,
+}
+
+/// Drives the entity's NavAgent (and head bone) from its behaviors each tick
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AiAgent {
+    pub behaviors: Vec<AiBehavior>,
+    #[serde(skip)]
+    pub state: AiState,
+    #[serde(skip)]
+    pub patrol_index: usize,
+    #[serde(skip)]
+    pub wait_timer: f32,
+    #[serde(skip)]
+    pub head_yaw: f32, // Current head offset, eased towards the target each tick
+}
+
+impl AiAgent {
+    pub fn new(behaviors: Vec<AiBehavior>) -> Self {
+        Self {
+            behaviors,
+            state: AiState::Idle,
+            patrol_index: 0,
+            wait_timer: 0.0,
+            head_yaw: 0.0,
+        }
+    }
+
+    pub fn patrol(&self) -> Option<(&[[f32; 3]], f32)> {
+        self.behaviors.iter().find_map(|behavior| match behavior {
+            AiBehavior::Patrol { waypoints, wait_time } if !waypoints.is_empty() => Some((waypoints.as_slice(), *wait_time)),
+            _ => None,
+        })
+    }
+
+    pub fn chase(&self) -> Option<(f32, f32)> {
+        self.behaviors.iter().find_map(|behavior| match behavior {
+            AiBehavior::Chase { radius, give_up_radius } => Some((*radius, *give_up_radius)),
+            _ => None,
+        })
+    }
+
+    pub fn look_at(&self) -> Option<(u32, f32, f32)> {
+        self.behaviors.iter().find_map(|behavior| match behavior {
+            AiBehavior::LookAt { head_node, radius, max_yaw } => Some((*head_node, *radius, *max_yaw)),
+            _ => None,
+        })
+    }
+}
@@ -18,27 +18,32 @@ mod animator_mod {
 
 pub use skeleton_mod::*;
 pub use animation_mod::*;
-pub use animator_mod::Animator;
+pub use animator_mod::{ Animator, AnimationMarker, AnimationMarkerEvent };
 
 #[derive(Serialize, Clone, Debug)]
 pub struct AnimatedObject3D {
     pub asset_type: Assets, // Serializable asset identifier
-    #[serde(skip)]
+    #[serde(rename = "bounds", serialize_with = "Mesh::serialize_bounds")]
     pub mesh: Mesh,
     #[serde(skip)]
     pub material: Material, // Required, no Option
     #[serde(skip)]
     pub skeleton: Skeleton, // Required, no Option
     #[serde(skip)]
-    pub animation_channels: Vec<AnimationChannel>, // Required
-    #[serde(skip)]
+    pub clips: Vec<AnimationClip>, // The asset's animations, in file order
     pub animator: Animator, // Required, now public for system access
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub morph_weights: Vec<f32>, // One per morph target of the mesh, keyed by clips or set from code
 }
 
 // Helper struct for deserialization
 #[derive(Deserialize)]
 struct AnimatedObject3DHelper {
     asset_type: Assets,
+    #[serde(default)]
+    animator: Option<Animator>, // Scenes saved before playback settings were kept have none
+    #[serde(default)]
+    morph_weights: Vec<f32>,
 }
 
 // Custom deserialization that properly initializes from AssetManager
@@ -51,7 +56,15 @@ impl<'de> Deserialize<'de> for AnimatedObject3D {
         let helper = AnimatedObject3DHelper::deserialize(deserializer)?;
         
         // Use AssetManager to get the properly initialized object
-        Ok(get_animated_object_copy(helper.asset_type))
+        let mut object = get_animated_object_copy(helper.asset_type);
+        if let Some(animator) = helper.animator {
+            object.animator = animator;
+        }
+        // Weights saved for another version of the mesh don't apply
+        if helper.morph_weights.len() == object.morph_weights.len() {
+            object.morph_weights = helper.morph_weights;
+        }
+        Ok(object)
     }
 }
 
@@ -60,16 +73,28 @@ impl AnimatedObject3D {
         mesh: Mesh,
         material: Material,
         skeleton: Skeleton,
-        animation_channels: Vec<AnimationChannel>,
+        clips: Vec<AnimationClip>,
         asset_type: Assets
     ) -> Self {
+        let morph_weights = mesh.morph.as_ref().map(|morph| morph.default_weights.clone()).unwrap_or_default();
         Self {
             asset_type,
             mesh,
             material,
             skeleton,
-            animation_channels,
+            clips,
             animator: Animator::new(),
+            morph_weights,
         }
     }
+
+    /// Set the weight of one of the mesh's morph targets; a playing clip that keys the weights overrides it
+    pub fn set_morph_weight(&mut self, target: usize, weight: f32) -> Result<(), String> {
+        let count = self.morph_weights.len();
+        let slot = self.morph_weights.get_mut(target).ok_or_else(|| {
+            format!("Morph target {} out of range, the mesh has {}", target, count)
+        })?;
+        *slot = weight;
+        Ok(())
+    }
 }
@@ -3,6 +3,7 @@ pub enum AnimationType {
     Translation = 0,
     Rotation = 1,
     Scale = 2,
+    Weights = 3, // Morph target weights of the object's mesh
 }
 
 #[derive(Debug, Clone)]
@@ -19,6 +20,22 @@ impl AnimationChannel {
         match self.animation_type {
             AnimationType::Translation | AnimationType::Scale => 3,
             AnimationType::Rotation => 4,
+            AnimationType::Weights => self.data.len() / self.num_timesteps.max(1), // One per morph target
         }
     }
 }
+
+/// One glTF animation: the channels it keys, played from 0 to `duration` seconds
+#[derive(Debug, Clone)]
+pub struct AnimationClip {
+    pub name: String,
+    pub channels: Vec<AnimationChannel>,
+    pub duration: f32, // Last keyframe time of any channel
+}
+
+impl AnimationClip {
+    pub fn new(name: String, channels: Vec<AnimationChannel>) -> Self {
+        let duration = channels.iter().filter_map(|channel| channel.times.last().copied()).fold(0.0, f32::max);
+        Self { name, channels, duration }
+    }
+}
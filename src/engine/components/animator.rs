@@ -1,12 +1,38 @@
-use crate::index::engine::components::AnimatedObject3D::{Skeleton, AnimationChannel, AnimationType};
+use crate::index::engine::components::AnimatedObject3D::{Skeleton, AnimationChannel, AnimationClip, AnimationType};
+use crate::index::engine::modules::EntityId;
 use crate::index::engine::utils::math::lerp;
 use serde::{Serialize, Deserialize};
 
+/// Named point on a clip's timeline; playback passing it sends EventType::AnimationMarker
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AnimationMarker {
+    pub clip: String,
+    pub time: f32, // Seconds into the clip
+    pub name: String,
+}
+
+/// Payload of EventType::AnimationMarker, sent by the AnimationSystem
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct AnimationMarkerEvent {
+    pub entity_id: EntityId,
+    pub clip: String,
+    pub marker: String,
+}
+
+/// Playback state of an animated object's clips; everything but the playhead saves with the scene
 #[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
 pub struct Animator {
-    time_since_start: f32,
+    #[serde(skip)]
+    time_since_start: f32, // Seconds into the current clip
     animation_speed: f32, // FPS - default 30.0
+    #[serde(skip)]
     frame_count: u64,     // Internal frame counter for timing
+    clip: String,         // Name of the current clip; the first clip when no clip has it
+    playing: bool,
+    looping: bool,        // Wrap at the end of the clip instead of stopping there
+    markers: Vec<AnimationMarker>,
 }
 
 impl Animator {
@@ -15,28 +41,102 @@ impl Animator {
             time_since_start: 0.0,
             animation_speed: 30.0, // Default 30 FPS
             frame_count: 0,
+            clip: String::new(),
+            playing: true,
+            looping: true,
+            markers: Vec::new(),
         }
     }
 
-    pub fn update_with_data(&mut self, animation_channels: &[AnimationChannel], skeleton: &mut Skeleton) {
-        self.advance_time();
-        self.apply_animation_with_data(animation_channels, skeleton);
+    /// Advance the current clip by `delta_time` seconds while playing and pose the skeleton and morph weights;
+    /// returns the names of the markers playback passed
+    pub fn update_with_data(
+        &mut self,
+        delta_time: f32,
+        clips: &[AnimationClip],
+        skeleton: &mut Skeleton,
+        morph_weights: &mut [f32]
+    ) -> Vec<String> {
+        let Some(clip) = self.current_clip(clips) else {
+            return Vec::new();
+        };
+        let passed = if self.playing { self.advance_time(clip, delta_time) } else { Vec::new() };
+        self.apply_animation_with_data(&clip.channels, skeleton, morph_weights);
+        passed
     }
 
-    fn advance_time(&mut self) {
+    /// Pose the skeleton and morph weights at the playhead without advancing, e.g. after scrubbing a paused clip
+    pub fn pose(&self, clips: &[AnimationClip], skeleton: &mut Skeleton, morph_weights: &mut [f32]) {
+        if let Some(clip) = self.current_clip(clips) {
+            self.apply_animation_with_data(&clip.channels, skeleton, morph_weights);
+        }
+    }
+
+    fn advance_time(&mut self, clip: &AnimationClip, delta_time: f32) -> Vec<String> {
         self.frame_count += 1;
-        // Assume 60 FPS base rate, then apply speed multiplier
-        let base_frame_time = 1.0 / 60.0; // 60 FPS = ~0.0167 seconds per frame
         let speed_multiplier = self.animation_speed / 30.0; // 30 FPS is "normal" speed
-        let effective_frame_time = base_frame_time * speed_multiplier;
-        self.time_since_start += effective_frame_time;
+        let effective_frame_time = delta_time * speed_multiplier;
+        let previous = self.time_since_start;
+        let time = previous + effective_frame_time;
+
+        let mut passed = self.markers_between(&clip.name, previous, time.min(clip.duration));
+        if time < clip.duration {
+            self.time_since_start = time;
+        } else if self.looping && clip.duration > 0.0 {
+            self.time_since_start = time % clip.duration;
+            passed.extend(self.markers_between(&clip.name, f32::NEG_INFINITY, self.time_since_start));
+        } else {
+            self.time_since_start = clip.duration;
+            self.playing = false;
+        }
+        passed
+    }
+
+    /// Names of the clip's markers after `from` up to and including `to`
+    fn markers_between(&self, clip: &str, from: f32, to: f32) -> Vec<String> {
+        self.markers
+            .iter()
+            .filter(|marker| marker.clip == clip && marker.time > from && marker.time <= to)
+            .map(|marker| marker.name.clone())
+            .collect()
+    }
+
+    /// The clip playing: the one named `clip`, or the first
+    pub fn current_clip<'a>(&self, clips: &'a [AnimationClip]) -> Option<&'a AnimationClip> {
+        clips.iter().find(|clip| clip.name == self.clip).or(clips.first())
+    }
+
+    /// Switch clips, starting the new one from its beginning
+    pub fn set_clip(&mut self, name: &str) {
+        self.clip = name.to_string();
+        self.time_since_start = 0.0;
     }
 
-    #[allow(dead_code)]
     pub fn get_time(&self) -> f32 {
         self.time_since_start
     }
 
+    /// Move the playhead, in seconds into the current clip
+    pub fn seek(&mut self, time: f32) {
+        self.time_since_start = time.max(0.0);
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    pub fn set_playing(&mut self, playing: bool) {
+        self.playing = playing;
+    }
+
+    pub fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
     pub fn set_animation_speed(&mut self, speed: f32) {
         self.animation_speed = speed;
     }
@@ -45,18 +145,49 @@ impl Animator {
         self.animation_speed
     }
 
+    /// Speed as a multiple of normal playback (30 FPS)
+    pub fn playback_rate(&self) -> f32 {
+        self.get_animation_speed() / 30.0
+    }
+
+    pub fn set_playback_rate(&mut self, rate: f32) {
+        self.set_animation_speed(rate * 30.0);
+    }
+
     #[allow(dead_code)]
     pub fn set_fps(&mut self, fps: f32) {
         self.animation_speed = fps;
     }
 
-    fn apply_animation_with_data(&self, animation_channels: &[AnimationChannel], skeleton: &mut Skeleton) {
+    pub fn markers(&self) -> &[AnimationMarker] {
+        &self.markers
+    }
+
+    pub fn add_marker(&mut self, marker: AnimationMarker) {
+        self.markers.push(marker);
+    }
+
+    pub fn remove_marker(&mut self, index: usize) {
+        if index < self.markers.len() {
+            self.markers.remove(index);
+        }
+    }
+
+    /// Set what the clip's channels key at the playhead; morph weights keep their value, e.g. one set from code,
+    /// unless the clip keys them
+    fn apply_animation_with_data(
+        &self,
+        animation_channels: &[AnimationChannel],
+        skeleton: &mut Skeleton,
+        morph_weights: &mut [f32]
+    ) {
         for channel in animation_channels {
             if channel.times.is_empty() {
                 continue;
             }
 
-            let rel_time_since_start = self.time_since_start % channel.times[channel.num_timesteps - 1];
+            // Channels shorter than their clip hold their last key
+            let rel_time_since_start = self.time_since_start.min(channel.times[channel.num_timesteps - 1]);
 
             let mut last_timestep = 0;
             for (i, &time) in channel.times.iter().enumerate().rev() {
@@ -89,6 +220,14 @@ impl Animator {
                 out[i] = lerp(last_data[i], next_data[i], t);
             }
 
+            // The object has one mesh, so weights apply to it whichever node the channel targets
+            if let AnimationType::Weights = channel.animation_type {
+                for (weight, value) in morph_weights.iter_mut().zip(&out) {
+                    *weight = *value;
+                }
+                continue;
+            }
+
             if let Some(node) = skeleton.nodes.get_mut(channel.target as usize) {
                 match channel.animation_type {
                     AnimationType::Translation => {
@@ -107,6 +246,7 @@ impl Animator {
                         node.scale[1] = out[1];
                         node.scale[2] = out[2];
                     }
+                    AnimationType::Weights => {}
                 }
             }
         }
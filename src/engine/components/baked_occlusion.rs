@@ -0,0 +1,98 @@
+use std::f32::consts::TAU;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::SharedComponents::MeshData;
+use crate::index::engine::utils::{ cross, normalize, Mat4x4, TriangleBvh, Vec3 };
+
+/// Hemisphere rays cast per vertex
+const OCCLUSION_RAYS: usize = 32;
+/// Rays start this far off the surface so they don't hit the triangles around their own vertex
+const SURFACE_BIAS: f32 = 0.01;
+
+/// Ambient occlusion baked per vertex of a static mesh entity, 0 fully occluded to 255 open; RenderSystem dims
+/// the ambient light with it. Rebake after moving the entity or its surroundings.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct BakedOcclusion {
+    pub values: Vec<u8>,
+}
+
+impl BakedOcclusion {
+    /// Cast cosine-weighted hemisphere rays from each vertex of `mesh` placed by `world_txfm`; hits on `scene`
+    /// within `distance` occlude
+    pub fn bake(mesh: &MeshData, world_txfm: &Mat4x4, scene: &TriangleBvh, distance: f32) -> Self {
+        let values = mesh.positions
+            .chunks_exact(3)
+            .zip(mesh.normals.chunks_exact(3))
+            .enumerate()
+            .map(|(vertex, (position, normal))| {
+                let position = transform_point(world_txfm, [position[0], position[1], position[2]]);
+                let normal = normalize(transform_direction(world_txfm, [normal[0], normal[1], normal[2]]));
+                let open = Self::openness(scene, position, normal, distance, vertex);
+                (open * 255.0).round() as u8
+            })
+            .collect();
+        Self { values }
+    }
+
+    /// World-space triangles of a mesh placed by `world_txfm`, the occluders of a bake
+    pub fn world_triangles(mesh: &MeshData, world_txfm: &Mat4x4) -> Vec<[Vec3; 3]> {
+        let vertex = |index: u32| {
+            let start = index as usize * 3;
+            transform_point(world_txfm, [mesh.positions[start], mesh.positions[start + 1], mesh.positions[start + 2]])
+        };
+        mesh.indices
+            .chunks_exact(3)
+            .map(|triangle| [vertex(triangle[0]), vertex(triangle[1]), vertex(triangle[2])])
+            .collect()
+    }
+
+    /// Fraction of cosine-weighted rays that escape
+    fn openness(scene: &TriangleBvh, position: Vec3, normal: Vec3, distance: f32, vertex: usize) -> f32 {
+        if normal == [0.0; 3] {
+            return 1.0;
+        }
+        let helper = if normal[1].abs() < 0.99 { [0.0, 1.0, 0.0] } else { [1.0, 0.0, 0.0] };
+        let tangent = normalize(cross(helper, normal));
+        let bitangent = cross(normal, tangent);
+        let origin = [
+            position[0] + normal[0] * SURFACE_BIAS,
+            position[1] + normal[1] * SURFACE_BIAS,
+            position[2] + normal[2] * SURFACE_BIAS,
+        ];
+
+        // Stratified directions, turned by a golden-ratio step per vertex so neighbours don't band alike
+        let rotation = (vertex as f32 * 0.618_034).fract();
+        let escaped = (0..OCCLUSION_RAYS)
+            .filter(|&ray| {
+                let height = (ray as f32 + 0.5) / OCCLUSION_RAYS as f32;
+                let angle = TAU * ((ray as u32).reverse_bits() as f32 / 4_294_967_296.0 + rotation);
+                let radius = height.sqrt();
+                let (x, y, z) = (radius * angle.cos(), radius * angle.sin(), (1.0 - height).sqrt());
+                let dir = normalize([
+                    tangent[0] * x + bitangent[0] * y + normal[0] * z,
+                    tangent[1] * x + bitangent[1] * y + normal[1] * z,
+                    tangent[2] * x + bitangent[2] * y + normal[2] * z,
+                ]);
+                !scene.occluded(origin, dir, 0.0, distance)
+            })
+            .count();
+        escaped as f32 / OCCLUSION_RAYS as f32
+    }
+}
+
+fn transform_point(matrix: &Mat4x4, point: Vec3) -> Vec3 {
+    [
+        matrix[0] * point[0] + matrix[1] * point[1] + matrix[2] * point[2] + matrix[3],
+        matrix[4] * point[0] + matrix[5] * point[1] + matrix[6] * point[2] + matrix[7],
+        matrix[8] * point[0] + matrix[9] * point[1] + matrix[10] * point[2] + matrix[11],
+    ]
+}
+
+/// Rotation and scale only, as the shaders transform normals with mat3(world_txfm)
+fn transform_direction(matrix: &Mat4x4, direction: Vec3) -> Vec3 {
+    [
+        matrix[0] * direction[0] + matrix[1] * direction[1] + matrix[2] * direction[2],
+        matrix[4] * direction[0] + matrix[5] * direction[1] + matrix[6] * direction[2],
+        matrix[8] * direction[0] + matrix[9] * direction[1] + matrix[10] * direction[2],
+    ]
+}
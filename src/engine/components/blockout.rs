@@ -0,0 +1,202 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::utils::math::Vec3;
+use crate::index::engine::utils::mesh_simplify::MeshAttributes;
+
+/// Local units one repeat of the blockout texture spans on generated faces
+const TEXTURE_REPEAT: f32 = 2.0;
+/// Box sides closer than this are treated as flush
+const EPSILON: f32 = 1e-4;
+
+/// Axis-aligned box in the entity's local space
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct BlockoutBox {
+    pub min: Vec3,
+    pub max: Vec3,
+}
+
+/// Blockout geometry edited with boolean operations (`csg union|subtract`): the union of non-overlapping boxes in
+/// the entity's local space. The BlockoutSystem meshes it and gives the entity a compound collider of the boxes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Blockout {
+    pub boxes: Vec<BlockoutBox>,
+}
+
+/// Boxes cut along every box side into a grid of cells, each inside the result or not
+struct CellGrid {
+    planes: [Vec<f32>; 3], // Sorted cell boundaries on each axis
+    filled: Vec<bool>,     // x fastest, then y, then z
+}
+
+impl CellGrid {
+    /// Cells inside a solid and outside every cut
+    fn new(solids: &[BlockoutBox], cuts: &[BlockoutBox]) -> Self {
+        let planes = [0, 1, 2].map(|axis| {
+            let mut planes: Vec<f32> = solids
+                .iter()
+                .chain(cuts)
+                .flat_map(|part| [part.min[axis], part.max[axis]])
+                .collect();
+            planes.sort_by(f32::total_cmp);
+            planes.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+            planes
+        });
+        let counts = planes.each_ref().map(|planes| planes.len().saturating_sub(1));
+        let contains = |part: &BlockoutBox, point: Vec3| {
+            (0..3).all(|axis| part.min[axis] < point[axis] && point[axis] < part.max[axis])
+        };
+        let mut filled = Vec::with_capacity(counts[0] * counts[1] * counts[2]);
+        for z in 0..counts[2] {
+            for y in 0..counts[1] {
+                for x in 0..counts[0] {
+                    let cell = [x, y, z];
+                    let point = [0, 1, 2].map(|axis| (planes[axis][cell[axis]] + planes[axis][cell[axis] + 1]) * 0.5);
+                    let solid = solids.iter().any(|solid| contains(solid, point));
+                    filled.push(solid && !cuts.iter().any(|cut| contains(cut, point)));
+                }
+            }
+        }
+        Self { planes, filled }
+    }
+
+    fn counts(&self) -> [usize; 3] {
+        self.planes.each_ref().map(|planes| planes.len().saturating_sub(1))
+    }
+
+    /// Whether the cell at `cell` is inside; cells off the grid aren't
+    fn is_filled(&self, cell: [i64; 3]) -> bool {
+        let counts = self.counts();
+        if (0..3).any(|axis| cell[axis] < 0 || cell[axis] >= counts[axis] as i64) {
+            return false;
+        }
+        self.filled[self.index(cell.map(|c| c as usize))]
+    }
+
+    fn index(&self, [x, y, z]: [usize; 3]) -> usize {
+        let counts = self.counts();
+        (z * counts[1] + y) * counts[0] + x
+    }
+
+    /// The filled cells merged greedily into as few boxes as runs along x, then y, then z allow
+    fn boxes(&self) -> Vec<BlockoutBox> {
+        let counts = self.counts();
+        let mut taken = vec![false; self.filled.len()];
+        let free = |taken: &[bool], cell: [usize; 3]| self.filled[self.index(cell)] && !taken[self.index(cell)];
+        let mut boxes = Vec::new();
+        for z in 0..counts[2] {
+            for y in 0..counts[1] {
+                for x in 0..counts[0] {
+                    if !free(&taken, [x, y, z]) {
+                        continue;
+                    }
+                    let mut end = [x + 1, y + 1, z + 1];
+                    while end[0] < counts[0] && free(&taken, [end[0], y, z]) {
+                        end[0] += 1;
+                    }
+                    while end[1] < counts[1] && (x..end[0]).all(|cx| free(&taken, [cx, end[1], z])) {
+                        end[1] += 1;
+                    }
+                    while end[2] < counts[2] &&
+                        (y..end[1]).all(|cy| (x..end[0]).all(|cx| free(&taken, [cx, cy, end[2]])))
+                    {
+                        end[2] += 1;
+                    }
+                    for cz in z..end[2] {
+                        for cy in y..end[1] {
+                            for cx in x..end[0] {
+                                taken[self.index([cx, cy, cz])] = true;
+                            }
+                        }
+                    }
+                    boxes.push(BlockoutBox {
+                        min: [self.planes[0][x], self.planes[1][y], self.planes[2][z]],
+                        max: [self.planes[0][end[0]], self.planes[1][end[1]], self.planes[2][end[2]]],
+                    });
+                }
+            }
+        }
+        boxes
+    }
+}
+
+impl Blockout {
+    pub fn new(boxes: Vec<BlockoutBox>) -> Self {
+        Self { boxes }
+    }
+
+    /// This blockout with `boxes` added
+    pub fn union(&self, boxes: &[BlockoutBox]) -> Self {
+        let solids: Vec<BlockoutBox> = self.boxes.iter().chain(boxes).copied().collect();
+        Self::new(CellGrid::new(&solids, &[]).boxes())
+    }
+
+    /// This blockout with `boxes` cut out of it
+    pub fn subtract(&self, boxes: &[BlockoutBox]) -> Self {
+        Self::new(CellGrid::new(&self.boxes, boxes).boxes())
+    }
+
+    /// Local (min, max) of the boxes, as Shape::Compound takes them
+    pub fn collider_boxes(&self) -> Vec<[Vec3; 2]> {
+        self.boxes.iter().map(|part| [part.min, part.max]).collect()
+    }
+
+    /// Outer surface of the boxes: a quad for every cell side between the inside and the outside, so faces where
+    /// boxes touch aren't drawn. UVs are planar along each face, TEXTURE_REPEAT units to a repeat
+    pub fn mesh(&self) -> MeshAttributes {
+        let grid = CellGrid::new(&self.boxes, &[]);
+        let counts = grid.counts();
+        let mut mesh = MeshAttributes {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            tex_coords: Vec::new(),
+            colors: None,
+            indices: Vec::new(),
+        };
+        for z in 0..counts[2] {
+            for y in 0..counts[1] {
+                for x in 0..counts[0] {
+                    let cell = [x as i64, y as i64, z as i64];
+                    if !grid.is_filled(cell) {
+                        continue;
+                    }
+                    for axis in 0..3 {
+                        for side in [-1i64, 1] {
+                            let mut neighbour = cell;
+                            neighbour[axis] += side;
+                            if !grid.is_filled(neighbour) {
+                                push_face(&mut mesh, &grid, [x, y, z], axis, side > 0);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        mesh
+    }
+}
+
+/// Quad on one side of a cell, wound counter-clockwise seen from outside
+fn push_face(mesh: &mut MeshAttributes, grid: &CellGrid, cell: [usize; 3], axis: usize, positive: bool) {
+    let (u, v) = ((axis + 1) % 3, (axis + 2) % 3);
+    let plane = grid.planes[axis][cell[axis] + usize::from(positive)];
+    let [u0, u1] = [grid.planes[u][cell[u]], grid.planes[u][cell[u] + 1]];
+    let [v0, v1] = [grid.planes[v][cell[v]], grid.planes[v][cell[v] + 1]];
+    let mut corners = [[u0, v0], [u1, v0], [u1, v1], [u0, v1]];
+    if !positive {
+        corners.reverse();
+    }
+
+    let first = (mesh.positions.len() / 3) as u32;
+    let mut normal = [0.0; 3];
+    normal[axis] = if positive { 1.0 } else { -1.0 };
+    for [cu, cv] in corners {
+        let mut position = [0.0; 3];
+        position[axis] = plane;
+        position[u] = cu;
+        position[v] = cv;
+        mesh.positions.extend(position);
+        mesh.normals.extend(normal);
+        mesh.tex_coords.extend([cu / TEXTURE_REPEAT, cv / TEXTURE_REPEAT]);
+    }
+    mesh.indices.extend([first, first + 1, first + 2, first, first + 2, first + 3]);
+}
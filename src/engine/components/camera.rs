@@ -1,11 +1,34 @@
-use crate::index::engine::utils::math::{Mat4x4, build_view_matrix, mat4x4_extract_translation};
+use crate::index::engine::utils::math::{Mat4x4, build_view_matrix};
+use crate::index::engine::modules::TransformInterpolation;
 use crate::index::engine::components::SharedComponents::Transform;
 use serde::{Serialize, Deserialize};
 
+/// How the camera projects the scene; the editor view presets switch to Orthographic
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CameraProjection {
+    #[default]
+    Perspective,
+    Orthographic,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Camera {
     pub pitch: f32,
     pub yaw: f32,
+    #[serde(default = "default_culling_mask")]
+    pub culling_mask: u32, // Bit per render layer this camera draws (editor-only layers are also dropped in Play mode)
+    #[serde(default)]
+    pub projection: CameraProjection, // Play mode always renders in perspective
+    #[serde(default = "default_ortho_size")]
+    pub ortho_size: f32,   // Half the view height in world units while orthographic
+}
+
+fn default_culling_mask() -> u32 {
+    u32::MAX
+}
+
+fn default_ortho_size() -> f32 {
+    10.0
 }
 
 impl Camera {
@@ -13,6 +36,9 @@ impl Camera {
         Self {
             pitch: 0.0,
             yaw: 0.0,
+            culling_mask: default_culling_mask(),
+            projection: CameraProjection::Perspective,
+            ortho_size: default_ortho_size(),
         }
     }
 
@@ -22,8 +48,8 @@ impl Camera {
         let mut position = [0.0, 0.0, 0.0];
         let entity_id_string = entity_id.to_string();
         crate::query_by_id!(entity_id_string, (Transform), |transform| {
-            let translation = mat4x4_extract_translation(&transform.compute_matrix());
-            position = translation;
+            // Drawn between ticks like every other moving entity
+            position = TransformInterpolation::position(entity_id, transform);
         });
 
         build_view_matrix(position, self.pitch, self.yaw)
@@ -50,6 +76,20 @@ impl Camera {
         (forward, right, up)
     }
 
+    /// Look straight along an axis-aligned direction; unlike set_pitch this allows exactly straight up or down
+    pub fn set_view_angles(&mut self, pitch: f32, yaw: f32) {
+        self.pitch = pitch.clamp(-std::f32::consts::FRAC_PI_2, std::f32::consts::FRAC_PI_2);
+        self.yaw = yaw;
+    }
+
+    /// Switch between perspective and orthographic projection
+    pub fn toggle_projection(&mut self) {
+        self.projection = match self.projection {
+            CameraProjection::Perspective => CameraProjection::Orthographic,
+            CameraProjection::Orthographic => CameraProjection::Perspective,
+        };
+    }
+
     /// Set pitch in radians
     pub fn set_pitch(&mut self, pitch: f32) {
         self.pitch = pitch.clamp(-1.5, 1.5);
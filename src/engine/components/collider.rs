@@ -1,4 +1,14 @@
-use crate::index::engine::components::{Shape, Transform};
+use crate::index::engine::components::{
+    AnimatedObject3DComponent,
+    PhysicsMaterial,
+    Shape,
+    StaticObject3DComponent,
+    Transform,
+};
+use crate::index::engine::components::terrain::sample_heightfield;
+use crate::index::engine::modules::ecs::insert;
+use crate::index::engine::modules::{ InterfaceSystem, Selection };
+use crate::get_query_by_id;
 use serde::{Serialize, Deserialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -13,6 +23,8 @@ pub struct Collider {
     pub is_hidden: bool,
     pub layer: ColliderLayer,
     pub ignored_layers: Vec<ColliderLayer>,
+    #[serde(default)]
+    pub material: PhysicsMaterial,
 }
 
 impl Collider {
@@ -22,53 +34,308 @@ impl Collider {
             layer,
             ignored_layers,
             is_hidden: false,
+            material: PhysicsMaterial::default(),
+        }
+    }
+
+    pub fn with_material(mut self, material: PhysicsMaterial) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Lowest world-space point of the shape, where it rests on surfaces
+    pub fn lowest_point(&self, transform: &Transform) -> Vec3 {
+        shape_lowest_point(&self.shape, transform)
+    }
+
+    /// Console command `fit_collider`: give the selected entities a box collider around their mesh's import bounds,
+    /// keeping the layers and material of a collider they have. Colliders are centred on the entity, so a mesh
+    /// whose bounds are off its origin gets a box reaching as far on the other side
+    pub fn fit_command(_args: &[&str]) -> Result<String, String> {
+        let entity_ids = Selection::ids();
+        if entity_ids.is_empty() {
+            return Err("Select the entities to fit colliders to".to_string());
+        }
+
+        let (mut fitted, mut off_center) = (0, 0);
+        for entity_id in &entity_ids {
+            let mesh = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent))
+                .map(|object| object.mesh)
+                .or_else(|| get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent)).map(|object| object.mesh));
+            let Some((min, max)) = mesh.filter(|mesh| mesh.index_count > 0).map(|mesh| mesh.bounds) else {
+                continue;
+            };
+            let existing = get_query_by_id!(entity_id.clone(), (Collider));
+            if existing.as_ref().is_some_and(|collider| matches!(collider.shape, Shape::Heightfield { .. })) {
+                continue; // Terrain keeps its heightfield
+            }
+
+            let shape = Shape::Box { half_extents: [0, 1, 2].map(|axis| min[axis].abs().max(max[axis].abs())) };
+            if (0..3).any(|axis| (min[axis] + max[axis]).abs() > (max[axis] - min[axis]) * 0.01) {
+                off_center += 1;
+            }
+            let collider = match existing {
+                Some(collider) => Collider { shape, ..collider },
+                None => Collider::new(shape, ColliderLayer::Environment, Vec::new()),
+            };
+            insert(entity_id, collider);
+            fitted += 1;
+        }
+        if fitted == 0 {
+            return Err("None of the selected entities has a mesh".to_string());
         }
+
+        InterfaceSystem::refresh_selection();
+        let mut summary = format!("Fitted box colliders to {} entities", fitted);
+        if off_center > 0 {
+            summary.push_str(&format!(" ({} with meshes off their origin, boxed on both sides)", off_center));
+        }
+        Ok(summary)
+    }
+
+    /// Box colliders of a compound shape with the transforms that place them as the compound is placed; other
+    /// shapes are their own only part
+    pub fn parts(&self, transform: &Transform) -> Vec<(Collider, Transform)> {
+        let Shape::Compound { boxes } = &self.shape else {
+            return vec![(self.clone(), transform.clone())];
+        };
+        compound_parts(boxes, transform)
+            .into_iter()
+            .map(|(shape, part_transform)| (Collider::new(shape, self.layer.clone(), Vec::new()), part_transform))
+            .collect()
+    }
+
+    /// World-space AABB (min, max) of the shape, what the physics broadphase sorts colliders by
+    pub fn world_bounds(&self, transform: &Transform) -> (Vec3, Vec3) {
+        let matrix = transform.compute_matrix();
+        let center = mat4x4_extract_translation(&matrix);
+        let scale = mat4x4_extract_scale(&matrix);
+
+        let extent = match &self.shape {
+            Shape::Sphere { radius } => [*radius; 3],
+            Shape::Capsule { radius, height } => [*radius, height * 0.5 * scale[1] + radius, *radius],
+            Shape::Cylinder { radius, height } => [*radius, height * 0.5 * scale[1], *radius],
+            Shape::Box { .. } => {
+                let obb = compute_world_obb(&self.shape, transform);
+                let axes = obb.axes.iter().zip(obb.half_extents.iter());
+                [0, 1, 2].map(|i| axes.clone().map(|(axis, he)| axis[i].abs() * he).sum())
+            }
+            Shape::Heightfield { size, heights, .. } => {
+                let half = size * 0.5;
+                let lowest = heights.iter().copied().fold(f32::MAX, f32::min).min(0.0);
+                let highest = heights.iter().copied().fold(f32::MIN, f32::max).max(0.0);
+                return (
+                    [center[0] - half, center[1] + lowest, center[2] - half],
+                    [center[0] + half, center[1] + highest, center[2] + half],
+                );
+            }
+            Shape::Compound { .. } => {
+                let bounds = self
+                    .parts(transform)
+                    .iter()
+                    .map(|(part, part_transform)| part.world_bounds(part_transform))
+                    .reduce(|(min, max), (part_min, part_max)| {
+                        ([0, 1, 2].map(|i| min[i].min(part_min[i])), [0, 1, 2].map(|i| max[i].max(part_max[i])))
+                    });
+                return bounds.unwrap_or((center, center));
+            }
+        };
+        ([0, 1, 2].map(|i| center[i] - extent[i]), [0, 1, 2].map(|i| center[i] + extent[i]))
     }
 
+    /// Height of the shape's top surface above a world x/z position, or None if the shape isn't there.
+    /// Boxes use their world-aligned bounds
+    pub fn surface_height(&self, transform: &Transform, x: f32, z: f32) -> Option<f32> {
+        let matrix = transform.compute_matrix();
+        let center = mat4x4_extract_translation(&matrix);
+        let scale = mat4x4_extract_scale(&matrix);
+        let (dx, dz) = (x - center[0], z - center[2]);
+        let cap = |radius: f32| {
+            let d2 = dx * dx + dz * dz;
+            (d2 <= radius * radius).then(|| (radius * radius - d2).sqrt())
+        };
+
+        match &self.shape {
+            Shape::Heightfield { size, resolution, heights } => {
+                sample_heightfield(*size, *resolution, heights, dx, dz).map(|height| center[1] + height)
+            }
+            Shape::Compound { .. } => self
+                .parts(transform)
+                .iter()
+                .filter_map(|(part, part_transform)| part.surface_height(part_transform, x, z))
+                .reduce(f32::max),
+            Shape::Box { .. } => {
+                let obb = compute_world_obb(&self.shape, transform);
+                let extent = |component: usize| {
+                    obb.axes.iter().zip(obb.half_extents.iter()).map(|(axis, he)| axis[component].abs() * he).sum::<f32>()
+                };
+                (dx.abs() <= extent(0) && dz.abs() <= extent(2)).then(|| center[1] + extent(1))
+            }
+            Shape::Sphere { radius } => cap(*radius).map(|top| center[1] + top),
+            Shape::Capsule { radius, height } => cap(*radius).map(|top| center[1] + height * 0.5 * scale[1] + top),
+            Shape::Cylinder { radius, height } => cap(*radius).map(|_| center[1] + height * 0.5 * scale[1]),
+        }
+    }
+
+    /// Signed distance from a world point to the shape's surface (negative inside).
+    /// Heightfields return the vertical distance, which is only a bound on gentle slopes
+    pub fn signed_distance(&self, transform: &Transform, point: Vec3) -> f32 {
+        let matrix = transform.compute_matrix();
+        let center = mat4x4_extract_translation(&matrix);
+        let scale = mat4x4_extract_scale(&matrix);
+        let offset = [point[0] - center[0], point[1] - center[1], point[2] - center[2]];
+
+        match &self.shape {
+            Shape::Sphere { radius } => len2(offset).sqrt() - radius,
+            Shape::Capsule { radius, height } => {
+                let half = height * 0.5 * scale[1];
+                let axis_y = offset[1].clamp(-half, half);
+                len2([offset[0], offset[1] - axis_y, offset[2]]).sqrt() - radius
+            }
+            Shape::Cylinder { radius, height } => {
+                let radial = (offset[0] * offset[0] + offset[2] * offset[2]).sqrt() - radius;
+                let vertical = offset[1].abs() - height * 0.5 * scale[1];
+                let outside = len2([radial.max(0.0), vertical.max(0.0), 0.0]).sqrt();
+                outside + radial.max(vertical).min(0.0)
+            }
+            Shape::Box { .. } => {
+                let obb = compute_world_obb(&self.shape, transform);
+                let local = [
+                    dot(offset, obb.axes[0]).abs() - obb.half_extents[0],
+                    dot(offset, obb.axes[1]).abs() - obb.half_extents[1],
+                    dot(offset, obb.axes[2]).abs() - obb.half_extents[2],
+                ];
+                let outside = len2([local[0].max(0.0), local[1].max(0.0), local[2].max(0.0)]).sqrt();
+                outside + local[0].max(local[1]).max(local[2]).min(0.0)
+            }
+            Shape::Heightfield { size, resolution, heights } => {
+                match sample_heightfield(*size, *resolution, heights, offset[0], offset[2]) {
+                    Some(height) => offset[1] - height,
+                    None => f32::MAX,
+                }
+            }
+            // The union of the boxes: exact outside, a bound inside
+            Shape::Compound { .. } => self
+                .parts(transform)
+                .iter()
+                .map(|(part, part_transform)| part.signed_distance(part_transform, point))
+                .fold(f32::MAX, f32::min),
+        }
+    }
+
+    /// First hit of a ray (normalized `direction`) within `max_distance`: (distance, point, surface normal).
+    /// Sphere-traces the signed distance, so thin shapes are never tunneled through
+    pub fn raycast(&self, transform: &Transform, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<(f32, Vec3, Vec3)> {
+        const HIT_EPSILON: f32 = 1e-3;
+        let at = |t: f32| [origin[0] + direction[0] * t, origin[1] + direction[1] * t, origin[2] + direction[2] * t];
+        // Heightfield distances overestimate on slopes, so step more carefully
+        let step_scale = if matches!(self.shape, Shape::Heightfield { .. }) { 0.5 } else { 1.0 };
+
+        let mut t = 0.0;
+        for _ in 0..128 {
+            let point = at(t);
+            let distance = self.signed_distance(transform, point);
+            if distance < HIT_EPSILON {
+                return Some((t, point, self.surface_normal(transform, point)));
+            }
+            if distance == f32::MAX {
+                // Off the heightfield's footprint, creep forward in case the ray enters it later
+                t += max_distance / 64.0;
+            } else {
+                t += distance * step_scale;
+            }
+            if t > max_distance {
+                return None;
+            }
+        }
+        None
+    }
+
+    /// Outward normal at a point on (or near) the surface, from the signed distance gradient
+    pub fn surface_normal(&self, transform: &Transform, point: Vec3) -> Vec3 {
+        const H: f32 = 1e-3;
+        let sample = |dx: f32, dy: f32, dz: f32| self.signed_distance(transform, [point[0] + dx, point[1] + dy, point[2] + dz]);
+        let gradient = [
+            sample(H, 0.0, 0.0) - sample(-H, 0.0, 0.0),
+            sample(0.0, H, 0.0) - sample(0.0, -H, 0.0),
+            sample(0.0, 0.0, H) - sample(0.0, 0.0, -H),
+        ];
+        let length = len2(gradient).sqrt();
+        if length > 1e-8 && length.is_finite() {
+            [gradient[0] / length, gradient[1] / length, gradient[2] / length]
+        } else {
+            [0.0, 1.0, 0.0]
+        }
+    }
+
+    #[allow(dead_code)]
     pub fn is_collides(self, other: Collider, self_txfm: Transform, other_txfm: Transform) -> bool {
+        self.contact(&other, &self_txfm, &other_txfm).is_some()
+    }
+
+    /// How this collider overlaps `other`, if it does; the manifold's normal points from this collider into the
+    /// other one
+    pub fn contact(&self, other: &Collider, self_txfm: &Transform, other_txfm: &Transform) -> Option<ContactManifold> {
+        let (a, b) = (self.shape.clone(), other.shape.clone());
+        let (a_txfm, b_txfm) = (self_txfm.clone(), other_txfm.clone());
         match (&self.shape, &other.shape) {
-            (Shape::Box { .. }, Shape::Box { .. }) =>
-                collision_check_box_box(self.shape, other.shape, self_txfm, other_txfm),
-            (Shape::Box { .. }, Shape::Capsule { .. }) =>
-                collision_check_box_capsule(self.shape, other.shape, self_txfm, other_txfm),
-            (Shape::Box { .. }, Shape::Cylinder { .. }) =>
-                collision_check_box_cylinder(self.shape, other.shape, self_txfm, other_txfm),
-            (Shape::Box { .. }, Shape::Sphere { .. }) =>
-                collision_check_box_sphere(self.shape, other.shape, self_txfm, other_txfm),
+            // The deepest contact of any of the compound's boxes
+            (Shape::Compound { .. }, _) => self
+                .parts(self_txfm)
+                .iter()
+                .filter_map(|(part, part_transform)| part.contact(other, part_transform, other_txfm))
+                .max_by(|a, b| a.depth.total_cmp(&b.depth)),
+            (_, Shape::Compound { .. }) => other.contact(self, other_txfm, self_txfm).map(ContactManifold::flipped),
+            (Shape::Heightfield { .. }, Shape::Heightfield { .. }) => None,
+            (Shape::Heightfield { .. }, _) => contact_heightfield(a, b, a_txfm, b_txfm),
+            (_, Shape::Heightfield { .. }) => contact_heightfield(b, a, b_txfm, a_txfm).map(ContactManifold::flipped),
+            (Shape::Box { .. }, Shape::Box { .. }) => contact_box_box(a, b, a_txfm, b_txfm),
+            (Shape::Box { .. }, Shape::Capsule { .. }) => contact_box_capsule(a, b, a_txfm, b_txfm),
+            (Shape::Box { .. }, Shape::Cylinder { .. }) => contact_box_cylinder(a, b, a_txfm, b_txfm),
+            (Shape::Box { .. }, Shape::Sphere { .. }) => contact_box_sphere(a, b, a_txfm, b_txfm),
             (Shape::Capsule { .. }, Shape::Box { .. }) =>
-                collision_check_box_capsule(other.shape, self.shape, other_txfm, self_txfm),
-            (Shape::Capsule { .. }, Shape::Capsule { .. }) =>
-                collision_check_capsule_capsule(self.shape, other.shape, self_txfm, other_txfm),
-            (Shape::Capsule { .. }, Shape::Cylinder { .. }) =>
-                collision_check_capsule_cylinder(self.shape, other.shape, self_txfm, other_txfm),
-            (Shape::Capsule { .. }, Shape::Sphere { .. }) =>
-                collision_check_capsule_sphere(self.shape, other.shape, self_txfm, other_txfm),
+                contact_box_capsule(b, a, b_txfm, a_txfm).map(ContactManifold::flipped),
+            (Shape::Capsule { .. }, Shape::Capsule { .. }) => contact_capsule_capsule(a, b, a_txfm, b_txfm),
+            (Shape::Capsule { .. }, Shape::Cylinder { .. }) => contact_capsule_cylinder(a, b, a_txfm, b_txfm),
+            (Shape::Capsule { .. }, Shape::Sphere { .. }) => contact_capsule_sphere(a, b, a_txfm, b_txfm),
             (Shape::Cylinder { .. }, Shape::Box { .. }) =>
-                collision_check_box_cylinder(other.shape, self.shape, other_txfm, self_txfm),
+                contact_box_cylinder(b, a, b_txfm, a_txfm).map(ContactManifold::flipped),
             (Shape::Cylinder { .. }, Shape::Capsule { .. }) =>
-                collision_check_capsule_cylinder(other.shape, self.shape, other_txfm, self_txfm),
-            (Shape::Cylinder { .. }, Shape::Cylinder { .. }) =>
-                collision_check_cylinder_cylinder(other.shape, self.shape, other_txfm, self_txfm),
-            (Shape::Cylinder { .. }, Shape::Sphere { .. }) =>
-                collision_check_cylinder_sphere(self.shape, other.shape, self_txfm, other_txfm),
+                contact_capsule_cylinder(b, a, b_txfm, a_txfm).map(ContactManifold::flipped),
+            (Shape::Cylinder { .. }, Shape::Cylinder { .. }) => contact_cylinder_cylinder(a, b, a_txfm, b_txfm),
+            (Shape::Cylinder { .. }, Shape::Sphere { .. }) => contact_cylinder_sphere(a, b, a_txfm, b_txfm),
             (Shape::Sphere { .. }, Shape::Box { .. }) =>
-                collision_check_box_sphere(other.shape, self.shape, other_txfm, self_txfm),
+                contact_box_sphere(b, a, b_txfm, a_txfm).map(ContactManifold::flipped),
             (Shape::Sphere { .. }, Shape::Capsule { .. }) =>
-                collision_check_capsule_sphere(other.shape, self.shape, other_txfm, self_txfm),
+                contact_capsule_sphere(b, a, b_txfm, a_txfm).map(ContactManifold::flipped),
             (Shape::Sphere { .. }, Shape::Cylinder { .. }) =>
-                collision_check_cylinder_sphere(other.shape, self.shape, other_txfm, self_txfm),
-            (Shape::Sphere { .. }, Shape::Sphere { .. }) =>
-                collision_check_sphere_sphere(self.shape, other.shape, self_txfm, other_txfm),
+                contact_cylinder_sphere(b, a, b_txfm, a_txfm).map(ContactManifold::flipped),
+            (Shape::Sphere { .. }, Shape::Sphere { .. }) => contact_sphere_sphere(a, b, a_txfm, b_txfm),
         }
     }
 }
 
+/// Overlap of two colliders: moving the second one by `normal * depth` separates them
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ContactManifold {
+    pub normal: Vec3, // Unit world-space direction from the first collider into the second
+    pub depth: f32,   // Penetration along the normal
+    pub point: Vec3,  // World-space point where the shapes touch, roughly the middle of the overlap
+}
+
+impl ContactManifold {
+    /// The same contact seen from the other collider
+    pub fn flipped(self) -> Self {
+        Self { normal: [-self.normal[0], -self.normal[1], -self.normal[2]], ..self }
+    }
+}
+
 // ================================================================================================
 // COLLISION DETECTION IMPLEMENTATION
 // ================================================================================================
 
 use crate::index::engine::utils::math::{
-    Vec3, dot, cross, len2, dist2, dist_point_segment2, segment_segment_distance2,
+    Vec3, dot, cross, len2, closest_point_segment, segment_segment_closest,
     mat4x4_extract_translation, mat4x4_extract_scale
 };
 
@@ -79,6 +346,40 @@ struct OBB {
     half_extents: Vec3,  // local half-extents * world scale
 }
 
+impl OBB {
+    /// Box-local coordinates of a world point
+    fn local(&self, point: Vec3) -> Vec3 {
+        let offset = [point[0] - self.center[0], point[1] - self.center[1], point[2] - self.center[2]];
+        [dot(offset, self.axes[0]), dot(offset, self.axes[1]), dot(offset, self.axes[2])]
+    }
+
+    /// World point of box-local coordinates
+    fn world(&self, local: Vec3) -> Vec3 {
+        [0, 1, 2].map(|i| {
+            self.center[i] + self.axes[0][i] * local[0] + self.axes[1][i] * local[1] + self.axes[2][i] * local[2]
+        })
+    }
+
+    /// Closest point of the box (inside or on its surface) to a world point
+    fn closest_point(&self, point: Vec3) -> Vec3 {
+        let local = self.local(point);
+        self.world([0, 1, 2].map(|i| local[i].clamp(-self.half_extents[i], self.half_extents[i])))
+    }
+
+    fn corners(&self) -> [Vec3; 8] {
+        let he = self.half_extents;
+        [0, 1, 2, 3, 4, 5, 6, 7].map(|corner| {
+            let sign = |bit: usize| if corner & (1 << bit) == 0 { -1.0 } else { 1.0 };
+            self.world([he[0] * sign(0), he[1] * sign(1), he[2] * sign(2)])
+        })
+    }
+
+    /// Half the box's extent projected on an axis
+    fn projected_radius(&self, axis: Vec3) -> f32 {
+        self.axes.iter().zip(self.half_extents.iter()).map(|(ax, he)| dot(*ax, axis).abs() * he).sum::<f32>()
+    }
+}
+
 /// Extracts the world-space OBB data from a Box shape and its Transform
 fn compute_world_obb(shape: &Shape, txfm: &Transform) -> OBB {
     if let Shape::Box { half_extents } = shape {
@@ -122,8 +423,9 @@ fn compute_world_obb(shape: &Shape, txfm: &Transform) -> OBB {
     }
 }
 
-/// 3D separating-axis test for two OBBs using up to 15 axes
-fn obb_obb_sat(a: &OBB, b: &OBB) -> bool {
+/// 3D separating-axis test for two OBBs using up to 15 axes; on overlap, the axis of least penetration (pointing
+/// from `a` to `b`) and the penetration along it
+fn obb_obb_sat(a: &OBB, b: &OBB) -> Option<(Vec3, f32)> {
     let mut axes: Vec<Vec3> = Vec::with_capacity(15);
     
     // Add face normals of both OBBs
@@ -144,321 +446,336 @@ fn obb_obb_sat(a: &OBB, b: &OBB) -> bool {
     
     let d = [b.center[0] - a.center[0], b.center[1] - a.center[1], b.center[2] - a.center[2]];
     
-    // Test separation on each axis
+    // Test separation on each axis, keeping the one the boxes overlap least on
+    let mut least: Option<(Vec3, f32)> = None;
     for axis in axes {
         // Project distance between centers
-        let dist = dot(d, axis).abs();
-        
-        // Project extents of A
-        let proj_a = a.axes.iter()
-            .zip(a.half_extents.iter())
-            .map(|(ax, he)| dot(*ax, axis).abs() * he)
-            .sum::<f32>();
-            
-        // Project extents of B
-        let proj_b = b.axes.iter()
-            .zip(b.half_extents.iter())
-            .map(|(ax, he)| dot(*ax, axis).abs() * he)
-            .sum::<f32>();
+        let projected = dot(d, axis);
+        let overlap = a.projected_radius(axis) + b.projected_radius(axis) - projected.abs();
             
         // If separated on this axis, no collision
-        if dist > proj_a + proj_b {
-            return false;
+        if overlap < 0.0 {
+            return None;
+        }
+        if least.is_none_or(|(_, depth)| overlap < depth) {
+            let axis = if projected < 0.0 { [-axis[0], -axis[1], -axis[2]] } else { axis };
+            least = Some((axis, overlap));
         }
     }
     
-    true // No separating axis found, collision detected
+    least // No separating axis found, collision detected
+}
+
+/// Contact of spheres (or of the closest points of two segments, with their radii)
+fn contact_spheres(a_center: Vec3, a_radius: f32, b_center: Vec3, b_radius: f32) -> Option<ContactManifold> {
+    let offset = [b_center[0] - a_center[0], b_center[1] - a_center[1], b_center[2] - a_center[2]];
+    let sum_radii = a_radius + b_radius;
+
+    // Use squared distance to skip the sqrt when apart
+    let dist_sq = len2(offset);
+    if dist_sq > sum_radii * sum_radii {
+        return None;
+    }
+    let dist = dist_sq.sqrt();
+    // Concentric spheres push apart vertically
+    let normal = if dist > 1e-6 { [offset[0] / dist, offset[1] / dist, offset[2] / dist] } else { [0.0, 1.0, 0.0] };
+    let depth = sum_radii - dist;
+    let reach = a_radius - depth * 0.5;
+    let point = [0, 1, 2].map(|i| a_center[i] + normal[i] * reach);
+    Some(ContactManifold { normal, depth, point })
+}
+
+/// Contact of a box and a sphere, normal pointing out of the box
+fn contact_obb_sphere(obb: &OBB, center: Vec3, radius: f32) -> Option<ContactManifold> {
+    let local = obb.local(center);
+    let inside = (0..3).all(|i| local[i].abs() <= obb.half_extents[i]);
+
+    if inside {
+        // Center inside the box: push out through the nearest face
+        let axis = (0..3)
+            .min_by(|&i, &j| {
+                (obb.half_extents[i] - local[i].abs()).total_cmp(&(obb.half_extents[j] - local[j].abs()))
+            })
+            .unwrap_or(1);
+        let sign = if local[axis] < 0.0 { -1.0 } else { 1.0 };
+        let normal = obb.axes[axis].map(|component| component * sign);
+        let face_distance = obb.half_extents[axis] - local[axis].abs();
+        let point = [0, 1, 2].map(|i| center[i] + normal[i] * face_distance);
+        return Some(ContactManifold { normal, depth: radius + face_distance, point });
+    }
+
+    // Check if distance to the closest point is within the sphere radius
+    let closest = obb.closest_point(center);
+    let offset = [center[0] - closest[0], center[1] - closest[1], center[2] - closest[2]];
+    let dist_sq = len2(offset);
+    if dist_sq > radius * radius {
+        return None;
+    }
+    let dist = dist_sq.sqrt().max(1e-6);
+    let normal = offset.map(|component| component / dist);
+    Some(ContactManifold { normal, depth: radius - dist, point: closest })
 }
 
-fn collision_check_box_box(
+/// World endpoints of the axis of a capsule or cylinder (assuming Y-axis alignment)
+fn axis_segment(height: f32, transform: &Transform) -> (Vec3, Vec3) {
+    let matrix = transform.compute_matrix();
+    let center = mat4x4_extract_translation(&matrix);
+    let half_height = height * 0.5 * mat4x4_extract_scale(&matrix)[1];
+    ([center[0], center[1] - half_height, center[2]], [center[0], center[1] + half_height, center[2]])
+}
+
+fn contact_box_box(
     a_shape: Shape,
     b_shape: Shape,
     a_transform: Transform,
     b_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     let obb_a = compute_world_obb(&a_shape, &a_transform);
     let obb_b = compute_world_obb(&b_shape, &b_transform);
-    obb_obb_sat(&obb_a, &obb_b)
+    let (normal, depth) = obb_obb_sat(&obb_a, &obb_b)?;
+
+    // B's corners that sunk into A along the normal, averaged (a face or edge contact lands in its middle); or B's
+    // deepest corner when only edges cross
+    let a_face = dot(obb_a.center, normal) + obb_a.projected_radius(normal);
+    let corners = obb_b.corners();
+    let sunk: Vec<Vec3> = corners.iter().copied().filter(|corner| dot(*corner, normal) <= a_face).collect();
+    let point = if sunk.is_empty() {
+        corners.into_iter().min_by(|p, q| dot(*p, normal).total_cmp(&dot(*q, normal))).unwrap_or(obb_b.center)
+    } else {
+        let count = sunk.len() as f32;
+        [0, 1, 2].map(|i| sunk.iter().map(|corner| corner[i]).sum::<f32>() / count)
+    };
+    Some(ContactManifold { normal, depth, point })
 }
 
-fn collision_check_sphere_sphere(
+fn contact_sphere_sphere(
     a_shape: Shape,
     b_shape: Shape,
     a_transform: Transform,
     b_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Sphere { radius: ra }, Shape::Sphere { radius: rb }) = (a_shape, b_shape) {
         let ca = mat4x4_extract_translation(&a_transform.compute_matrix());
         let cb = mat4x4_extract_translation(&b_transform.compute_matrix());
-        let sum_radii = ra + rb;
-        
-        // Use squared distance to avoid sqrt
-        let dist_sq = (cb[0] - ca[0]).powi(2) + (cb[1] - ca[1]).powi(2) + (cb[2] - ca[2]).powi(2);
-        dist_sq <= sum_radii * sum_radii
+        contact_spheres(ca, ra, cb, rb)
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_box_sphere(
+fn contact_box_sphere(
     box_shape: Shape,
     sphere_shape: Shape,
     box_transform: Transform,
     sphere_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Box { half_extents }, Shape::Sphere { radius }) = (box_shape, sphere_shape) {
         let obb = compute_world_obb(&Shape::Box { half_extents }, &box_transform);
         let sphere_center = mat4x4_extract_translation(&sphere_transform.compute_matrix());
-        
-        // Transform sphere center to box local space
-        let to_sphere = [
-            sphere_center[0] - obb.center[0],
-            sphere_center[1] - obb.center[1],
-            sphere_center[2] - obb.center[2]
-        ];
-        
-        // Project to box local coordinates
-        let mut local = [
-            dot(to_sphere, obb.axes[0]),
-            dot(to_sphere, obb.axes[1]),
-            dot(to_sphere, obb.axes[2])
-        ];
-        
-        // Clamp to box bounds
-        for i in 0..3 {
-            local[i] = local[i].max(-obb.half_extents[i]).min(obb.half_extents[i]);
-        }
-        
-        // Transform back to world space
-        let closest_point = [
-            obb.center[0] + obb.axes[0][0] * local[0] + obb.axes[1][0] * local[1] + obb.axes[2][0] * local[2],
-            obb.center[1] + obb.axes[0][1] * local[0] + obb.axes[1][1] * local[1] + obb.axes[2][1] * local[2],
-            obb.center[2] + obb.axes[0][2] * local[0] + obb.axes[1][2] * local[1] + obb.axes[2][2] * local[2]
-        ];
-        
-        // Check if distance to closest point is within sphere radius
-        let dist_sq = dist2(sphere_center, closest_point);
-        dist_sq <= radius * radius
+        contact_obb_sphere(&obb, sphere_center, radius)
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_capsule_sphere(
+fn contact_capsule_sphere(
     capsule_shape: Shape,
     sphere_shape: Shape,
     capsule_transform: Transform,
     sphere_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Capsule { radius: cap_radius, height }, Shape::Sphere { radius: sphere_radius }) = (capsule_shape, sphere_shape) {
-        let cap_center = mat4x4_extract_translation(&capsule_transform.compute_matrix());
-        let cap_scale = mat4x4_extract_scale(&capsule_transform.compute_matrix());
+        let (p0, p1) = axis_segment(height, &capsule_transform);
         let sphere_center = mat4x4_extract_translation(&sphere_transform.compute_matrix());
         
-        // Capsule segment endpoints (assuming Y-axis alignment)
-        let half_height = height * 0.5 * cap_scale[1];
-        let p0 = [cap_center[0], cap_center[1] - half_height, cap_center[2]];
-        let p1 = [cap_center[0], cap_center[1] + half_height, cap_center[2]];
-        
-        // Distance from sphere center to capsule segment
-        let dist_sq = dist_point_segment2(sphere_center, p0, p1);
-        let sum_radii = cap_radius + sphere_radius;
-        
-        dist_sq <= sum_radii * sum_radii
+        // Sphere against the capsule's closest point on its segment
+        let closest = closest_point_segment(sphere_center, p0, p1);
+        contact_spheres(closest, cap_radius, sphere_center, sphere_radius)
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_capsule_capsule(
+fn contact_capsule_capsule(
     a_shape: Shape,
     b_shape: Shape,
     a_transform: Transform,
     b_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Capsule { radius: ra, height: ha }, Shape::Capsule { radius: rb, height: hb }) = (a_shape, b_shape) {
-        let ca = mat4x4_extract_translation(&a_transform.compute_matrix());
-        let sa = mat4x4_extract_scale(&a_transform.compute_matrix());
-        let cb = mat4x4_extract_translation(&b_transform.compute_matrix());
-        let sb = mat4x4_extract_scale(&b_transform.compute_matrix());
-        
-        // Capsule A segment endpoints
-        let half_ha = ha * 0.5 * sa[1];
-        let a1 = [ca[0], ca[1] - half_ha, ca[2]];
-        let a2 = [ca[0], ca[1] + half_ha, ca[2]];
-        
-        // Capsule B segment endpoints
-        let half_hb = hb * 0.5 * sb[1];
-        let b1 = [cb[0], cb[1] - half_hb, cb[2]];
-        let b2 = [cb[0], cb[1] + half_hb, cb[2]];
+        let (a1, a2) = axis_segment(ha, &a_transform);
+        let (b1, b2) = axis_segment(hb, &b_transform);
         
-        // Segment-segment distance
-        let dist_sq = segment_segment_distance2(a1, a2, b1, b2);
-        let sum_radii = ra + rb;
-        
-        dist_sq <= sum_radii * sum_radii
+        // Closest points of the two segments
+        let (ca, cb) = segment_segment_closest(a1, a2, b1, b2);
+        contact_spheres(ca, ra, cb, rb)
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_cylinder_sphere(
+fn contact_cylinder_sphere(
     cylinder_shape: Shape,
     sphere_shape: Shape,
     cylinder_transform: Transform,
     sphere_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Cylinder { radius: cyl_radius, height }, Shape::Sphere { radius: sphere_radius }) = (cylinder_shape, sphere_shape) {
-        let cyl_center = mat4x4_extract_translation(&cylinder_transform.compute_matrix());
-        let cyl_scale = mat4x4_extract_scale(&cylinder_transform.compute_matrix());
+        let (p0, p1) = axis_segment(height, &cylinder_transform);
         let sphere_center = mat4x4_extract_translation(&sphere_transform.compute_matrix());
         
-        // Cylinder segment endpoints (assuming Y-axis alignment)
-        let half_height = height * 0.5 * cyl_scale[1];
-        let p0 = [cyl_center[0], cyl_center[1] - half_height, cyl_center[2]];
-        let p1 = [cyl_center[0], cyl_center[1] + half_height, cyl_center[2]];
-        
-        // Distance from sphere center to cylinder axis
-        let dist_sq = dist_point_segment2(sphere_center, p0, p1);
-        let sum_radii = cyl_radius + sphere_radius;
-        
-        dist_sq <= sum_radii * sum_radii
+        // Sphere against the closest point of the cylinder axis
+        let closest = closest_point_segment(sphere_center, p0, p1);
+        contact_spheres(closest, cyl_radius, sphere_center, sphere_radius)
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_box_capsule(
+fn contact_box_capsule(
     box_shape: Shape,
     capsule_shape: Shape,
     box_transform: Transform,
     capsule_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Box { half_extents }, Shape::Capsule { radius, height }) = (box_shape, capsule_shape) {
         let obb = compute_world_obb(&Shape::Box { half_extents }, &box_transform);
-        let cap_center = mat4x4_extract_translation(&capsule_transform.compute_matrix());
-        let cap_scale = mat4x4_extract_scale(&capsule_transform.compute_matrix());
-        
-        // Capsule segment endpoints
-        let half_height = height * 0.5 * cap_scale[1];
-        let p0 = [cap_center[0], cap_center[1] - half_height, cap_center[2]];
-        let p1 = [cap_center[0], cap_center[1] + half_height, cap_center[2]];
-        
-        // Approximate by checking both endpoints against the OBB
-        let dist_sq_0 = {
-            let to_p0 = [p0[0] - obb.center[0], p0[1] - obb.center[1], p0[2] - obb.center[2]];
-            let mut local = [dot(to_p0, obb.axes[0]), dot(to_p0, obb.axes[1]), dot(to_p0, obb.axes[2])];
-            for i in 0..3 {
-                local[i] = local[i].max(-obb.half_extents[i]).min(obb.half_extents[i]);
-            }
-            let closest = [
-                obb.center[0] + obb.axes[0][0] * local[0] + obb.axes[1][0] * local[1] + obb.axes[2][0] * local[2],
-                obb.center[1] + obb.axes[0][1] * local[0] + obb.axes[1][1] * local[1] + obb.axes[2][1] * local[2],
-                obb.center[2] + obb.axes[0][2] * local[0] + obb.axes[1][2] * local[1] + obb.axes[2][2] * local[2]
-            ];
-            dist2(p0, closest)
-        };
+        let (p0, p1) = axis_segment(height, &capsule_transform);
         
-        let dist_sq_1 = {
-            let to_p1 = [p1[0] - obb.center[0], p1[1] - obb.center[1], p1[2] - obb.center[2]];
-            let mut local = [dot(to_p1, obb.axes[0]), dot(to_p1, obb.axes[1]), dot(to_p1, obb.axes[2])];
-            for i in 0..3 {
-                local[i] = local[i].max(-obb.half_extents[i]).min(obb.half_extents[i]);
-            }
-            let closest = [
-                obb.center[0] + obb.axes[0][0] * local[0] + obb.axes[1][0] * local[1] + obb.axes[2][0] * local[2],
-                obb.center[1] + obb.axes[0][1] * local[0] + obb.axes[1][1] * local[1] + obb.axes[2][1] * local[2],
-                obb.center[2] + obb.axes[0][2] * local[0] + obb.axes[1][2] * local[1] + obb.axes[2][2] * local[2]
-            ];
-            dist2(p1, closest)
-        };
-        
-        let min_dist_sq = dist_sq_0.min(dist_sq_1);
-        min_dist_sq <= radius * radius
+        // Approximate the segment point closest to the box by alternating closest-point projections
+        let mut closest = closest_point_segment(obb.center, p0, p1);
+        for _ in 0..4 {
+            closest = closest_point_segment(obb.closest_point(closest), p0, p1);
+        }
+        contact_obb_sphere(&obb, closest, radius)
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_box_cylinder(
+fn contact_box_cylinder(
     box_shape: Shape,
     cylinder_shape: Shape,
     box_transform: Transform,
     cylinder_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     // Treat cylinder like capsule for box collision
     if let (Shape::Box { half_extents }, Shape::Cylinder { radius, height }) = (box_shape, cylinder_shape) {
-        collision_check_box_capsule(
+        contact_box_capsule(
             Shape::Box { half_extents },
             Shape::Capsule { radius, height },
             box_transform,
             cylinder_transform
         )
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_cylinder_cylinder(
+fn contact_cylinder_cylinder(
     a_shape: Shape,
     b_shape: Shape,
     a_transform: Transform,
     b_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Cylinder { radius: ra, height: ha }, Shape::Cylinder { radius: rb, height: hb }) = (a_shape, b_shape) {
-        let ca = mat4x4_extract_translation(&a_transform.compute_matrix());
-        let sa = mat4x4_extract_scale(&a_transform.compute_matrix());
-        let cb = mat4x4_extract_translation(&b_transform.compute_matrix());
-        let sb = mat4x4_extract_scale(&b_transform.compute_matrix());
-        
-        // Cylinder A segment endpoints
-        let half_ha = ha * 0.5 * sa[1];
-        let a1 = [ca[0], ca[1] - half_ha, ca[2]];
-        let a2 = [ca[0], ca[1] + half_ha, ca[2]];
-        
-        // Cylinder B segment endpoints
-        let half_hb = hb * 0.5 * sb[1];
-        let b1 = [cb[0], cb[1] - half_hb, cb[2]];
-        let b2 = [cb[0], cb[1] + half_hb, cb[2]];
+        let (a1, a2) = axis_segment(ha, &a_transform);
+        let (b1, b2) = axis_segment(hb, &b_transform);
         
-        // Segment-segment distance
-        let dist_sq = segment_segment_distance2(a1, a2, b1, b2);
-        let sum_radii = ra + rb;
-        
-        dist_sq <= sum_radii * sum_radii
+        // Closest points of the two axes
+        let (ca, cb) = segment_segment_closest(a1, a2, b1, b2);
+        contact_spheres(ca, ra, cb, rb)
     } else {
-        false
+        None
     }
 }
 
-fn collision_check_capsule_cylinder(
+fn contact_capsule_cylinder(
     capsule_shape: Shape,
     cylinder_shape: Shape,
     capsule_transform: Transform,
     cylinder_transform: Transform
-) -> bool {
+) -> Option<ContactManifold> {
     if let (Shape::Capsule { radius: cap_radius, height: cap_height }, Shape::Cylinder { radius: cyl_radius, height: cyl_height }) = (capsule_shape, cylinder_shape) {
-        let cap_center = mat4x4_extract_translation(&capsule_transform.compute_matrix());
-        let cap_scale = mat4x4_extract_scale(&capsule_transform.compute_matrix());
-        let cyl_center = mat4x4_extract_translation(&cylinder_transform.compute_matrix());
-        let cyl_scale = mat4x4_extract_scale(&cylinder_transform.compute_matrix());
-        
-        // Capsule segment endpoints
-        let cap_half_height = cap_height * 0.5 * cap_scale[1];
-        let cap1 = [cap_center[0], cap_center[1] - cap_half_height, cap_center[2]];
-        let cap2 = [cap_center[0], cap_center[1] + cap_half_height, cap_center[2]];
-        
-        // Cylinder segment endpoints
-        let cyl_half_height = cyl_height * 0.5 * cyl_scale[1];
-        let cyl1 = [cyl_center[0], cyl_center[1] - cyl_half_height, cyl_center[2]];
-        let cyl2 = [cyl_center[0], cyl_center[1] + cyl_half_height, cyl_center[2]];
+        let (cap1, cap2) = axis_segment(cap_height, &capsule_transform);
+        let (cyl1, cyl2) = axis_segment(cyl_height, &cylinder_transform);
         
-        // Segment-segment distance
-        let dist_sq = segment_segment_distance2(cap1, cap2, cyl1, cyl2);
-        let sum_radii = cap_radius + cyl_radius;
-        
-        dist_sq <= sum_radii * sum_radii
+        // Closest points of the capsule segment and cylinder axis
+        let (cap_closest, cyl_closest) = segment_segment_closest(cap1, cap2, cyl1, cyl2);
+        contact_spheres(cap_closest, cap_radius, cyl_closest, cyl_radius)
+    } else {
+        None
+    }
+}
+
+/// Lowest world-space point of a convex shape, used against heightfields
+fn shape_lowest_point(shape: &Shape, transform: &Transform) -> Vec3 {
+    if let Shape::Compound { boxes } = shape {
+        return compound_parts(boxes, transform)
+            .iter()
+            .map(|(part, part_transform)| shape_lowest_point(part, part_transform))
+            .min_by(|a, b| a[1].total_cmp(&b[1]))
+            .unwrap_or_else(|| transform.get_position());
+    }
+    let matrix = transform.compute_matrix();
+    let center = mat4x4_extract_translation(&matrix);
+    let scale = mat4x4_extract_scale(&matrix);
+
+    let drop = match shape {
+        Shape::Sphere { radius } => *radius,
+        Shape::Capsule { radius, height } => height * 0.5 * scale[1] + radius,
+        Shape::Cylinder { height, .. } => height * 0.5 * scale[1],
+        Shape::Box { half_extents } => {
+            let obb = compute_world_obb(shape, transform);
+            obb.axes.iter().zip(half_extents.iter().zip(scale.iter()))
+                .map(|(axis, (he, s))| axis[1].abs() * he * s)
+                .sum::<f32>()
+        }
+        Shape::Heightfield { .. } | Shape::Compound { .. } => 0.0,
+    };
+
+    [center[0], center[1] - drop, center[2]]
+}
+
+/// Box shapes of a compound's local (min, max) boxes, with the transforms that place them as `transform` places the
+/// compound
+fn compound_parts(boxes: &[[Vec3; 2]], transform: &Transform) -> Vec<(Shape, Transform)> {
+    let matrix = transform.compute_matrix();
+    boxes
+        .iter()
+        .map(|[min, max]| {
+            let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) * 0.5);
+            let world = [0, 1, 2].map(|row| {
+                (0..3).map(|column| matrix[row * 4 + column] * center[column]).sum::<f32>() + matrix[row * 4 + 3]
+            });
+            let mut part_transform = transform.clone();
+            part_transform.set_position(world[0], world[1], world[2]);
+            (Shape::Box { half_extents: [0, 1, 2].map(|axis| (max[axis] - min[axis]) * 0.5) }, part_transform)
+        })
+        .collect()
+}
+
+/// Contact of a heightfield with the lowest point of another shape; the normal is straight up, since the depth is
+/// measured vertically
+fn contact_heightfield(
+    heightfield_shape: Shape,
+    other_shape: Shape,
+    heightfield_transform: Transform,
+    other_transform: Transform
+) -> Option<ContactManifold> {
+    if let Shape::Heightfield { size, resolution, heights } = heightfield_shape {
+        // Heightfields only follow their Transform's translation
+        let origin = mat4x4_extract_translation(&heightfield_transform.compute_matrix());
+        let lowest = shape_lowest_point(&other_shape, &other_transform);
+
+        let height = sample_heightfield(size, resolution, &heights, lowest[0] - origin[0], lowest[2] - origin[2])?;
+        let surface = origin[1] + height;
+        (lowest[1] <= surface).then_some(ContactManifold {
+            normal: [0.0, 1.0, 0.0],
+            depth: surface - lowest[1],
+            point: [lowest[0], surface, lowest[2]],
+        })
     } else {
-        false
+        None
     }
 }
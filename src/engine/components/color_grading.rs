@@ -0,0 +1,28 @@
+use serde::{ Serialize, Deserialize };
+
+/// Scene-wide exposure and color grading of the finished frame; the first ColorGrading in the world applies
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct ColorGrading {
+    pub exposure: f32,         // Stops of brightness, on top of auto exposure when that's on
+    pub auto_exposure: bool,   // Brighten dark frames and darken bright ones towards middle grey
+    pub adaptation_speed: f32, // How quickly auto exposure follows brightness changes, per second
+    pub contrast: f32,         // 1 unchanged, pivoting around middle grey
+    pub saturation: f32,       // 0 greyscale, 1 unchanged
+    pub lut: Option<String>,   // Lookup strip of N slices of N x N side by side (e.g. 256x16 PNG)
+    pub lut_strength: f32,     // Blend of the LUT's colors over the graded ones
+}
+
+impl Default for ColorGrading {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            auto_exposure: false,
+            adaptation_speed: 1.5,
+            contrast: 1.0,
+            saturation: 1.0,
+            lut: None,
+            lut_strength: 1.0,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use std::collections::BTreeMap;
+use serde::{ Serialize, Deserialize };
+
+/// Value of a material parameter: a float, or a vec2 to vec4 as a list
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum MaterialValue {
+    Float(f32),
+    Vector(Vec<f32>),
+}
+
+/// Static mesh drawn with a user material from a `.material.toml` definition instead of its own shader. The
+/// parameters sit next to `definition` in the scene file, so the inspector lists each one as a field.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CustomMaterial {
+    pub definition: String, // Name of the MaterialLibrary definition
+    #[serde(flatten)]
+    pub parameters: BTreeMap<String, MaterialValue>, // Uniform values; missing ones take the definition's default
+}
@@ -0,0 +1,82 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::modules::EntityId;
+
+/// What happens to an entity when its health reaches zero
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub enum DeathBehavior {
+    #[default]
+    Despawn,
+    Ragdoll, // Handed to the registered ragdoll hook, the entity stays in the world
+    Nothing, // Only the Death event is sent
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+    pub invulnerable: bool,
+    #[serde(default)]
+    pub on_death: DeathBehavior,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            invulnerable: false,
+            on_death: DeathBehavior::Despawn,
+        }
+    }
+
+    pub fn with_death_behavior(mut self, on_death: DeathBehavior) -> Self {
+        self.on_death = on_death;
+        self
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current <= 0.0
+    }
+
+    /// Subtract damage (negative heals) and return whether this hit killed the entity
+    pub fn apply(&mut self, amount: f32) -> bool {
+        if self.is_dead() || (self.invulnerable && amount > 0.0) {
+            return false;
+        }
+        self.current = (self.current - amount).clamp(0.0, self.max);
+        self.is_dead()
+    }
+}
+
+/// Team tag; entities on the same team don't damage each other
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Faction {
+    pub team: String,
+}
+
+impl Faction {
+    pub fn new(team: &str) -> Self {
+        Self { team: team.to_string() }
+    }
+
+    pub fn is_hostile_to(&self, other: &Faction) -> bool {
+        self.team != other.team
+    }
+}
+
+/// Payload of EventType::Damage
+#[derive(Clone, Debug)]
+pub struct DamageEvent {
+    pub target: EntityId,
+    pub amount: f32,
+    pub source: Option<EntityId>,
+}
+
+/// Payload of EventType::Death, sent after the killing blow and before despawning
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct DeathEvent {
+    pub entity: EntityId,
+    pub killer: Option<EntityId>,
+}
@@ -0,0 +1,25 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::modules::EntityId;
+
+/// Something the player can aim at (through its Collider) and use with E in Play mode
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Interactable {
+    pub prompt: String, // Shown on the HUD as "[E] <prompt>"
+    pub range: f32,     // Max distance from the camera
+}
+
+impl Interactable {
+    #[allow(dead_code)]
+    pub fn new(prompt: &str, range: f32) -> Self {
+        Self { prompt: prompt.to_string(), range }
+    }
+}
+
+/// Payload of EventType::Interacted
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct InteractEvent {
+    pub target: EntityId,
+    pub user: EntityId,
+}
@@ -0,0 +1,136 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::modules::EntityId;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MoverEasing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+}
+
+impl MoverEasing {
+    /// Map linear segment progress (0..1) to eased progress
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            MoverEasing::Linear => t,
+            MoverEasing::EaseIn => t * t,
+            MoverEasing::EaseOut => t * (2.0 - t),
+            MoverEasing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// What happens after the last waypoint
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum MoverLoop {
+    Loop,     // Back to the first waypoint
+    PingPong, // Retrace the waypoints in reverse
+    Once,     // Stop at the last waypoint
+}
+
+/// Moves the entity through world-space waypoints, or along a Spline entity's curve from one end to the other,
+/// carrying whatever stands on its collider
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KinematicMover {
+    pub waypoints: Vec<[f32; 3]>,
+    #[serde(default)]
+    pub path: Option<EntityId>, // Spline entity to follow instead of the waypoints; Loop starts over from its start
+    pub speed: f32,     // Units per second along a segment (before easing)
+    pub wait_time: f32, // Pause at each waypoint in seconds
+    pub easing: MoverEasing,
+    pub loop_mode: MoverLoop,
+    #[serde(skip)]
+    pub segment: usize, // Waypoint the current segment starts at
+    #[serde(skip)]
+    pub progress: f32,  // 0..1 along the current segment
+    #[serde(skip)]
+    pub wait_timer: f32,
+    #[serde(skip)]
+    pub reverse: bool,  // PingPong is heading back towards the first waypoint
+}
+
+impl KinematicMover {
+    pub fn new(waypoints: Vec<[f32; 3]>, speed: f32) -> Self {
+        Self {
+            waypoints,
+            path: None,
+            speed,
+            wait_time: 1.0,
+            easing: MoverEasing::EaseInOut,
+            loop_mode: MoverLoop::PingPong,
+            segment: 0,
+            progress: 0.0,
+            wait_timer: 0.0,
+            reverse: false,
+        }
+    }
+
+    /// Waypoint the current segment ends at, or None once a `Once` mover has arrived
+    fn next_index(&self, count: usize) -> Option<usize> {
+        match self.loop_mode {
+            MoverLoop::Loop => Some((self.segment + 1) % count),
+            MoverLoop::Once => (self.segment + 1 < count).then_some(self.segment + 1),
+            MoverLoop::PingPong if self.reverse => Some(self.segment.saturating_sub(1)),
+            MoverLoop::PingPong => Some((self.segment + 1).min(count - 1)),
+        }
+    }
+
+    /// Advance by one tick and return where the entity should be, or None while it stands still
+    pub fn advance(&mut self, delta_time: f32) -> Option<[f32; 3]> {
+        let waypoints = self.waypoints.clone();
+        let (from, to, t) = self.step(delta_time, waypoints.len(), |from, to| {
+            let (start, end) = (waypoints[from], waypoints[to]);
+            ((end[0] - start[0]).powi(2) + (end[1] - start[1]).powi(2) + (end[2] - start[2]).powi(2)).sqrt()
+        })?;
+        let (start, end) = (waypoints[from], waypoints[to]);
+        Some([0, 1, 2].map(|axis| start[axis] + (end[axis] - start[axis]) * t))
+    }
+
+    /// Advance along a path `length` long and return the distance along it the entity should be at, or None
+    /// while it stands still
+    pub fn advance_path(&mut self, delta_time: f32, length: f32) -> Option<f32> {
+        // The path is one segment from its start (0) to its end (1); looping starts it over
+        if self.loop_mode == MoverLoop::Loop && self.segment == 1 && self.wait_timer <= 0.0 {
+            self.segment = 0;
+        }
+        let (from, to, t) = self.step(delta_time, 2, |_, _| length)?;
+        let (start, end) = (from as f32 * length, to as f32 * length);
+        Some(start + (end - start) * t)
+    }
+
+    /// Move through segments of `length(from, to)` and return the segment's ends and eased progress along it
+    fn step(
+        &mut self,
+        delta_time: f32,
+        count: usize,
+        length: impl Fn(usize, usize) -> f32
+    ) -> Option<(usize, usize, f32)> {
+        if count < 2 || self.speed <= 0.0 {
+            return None;
+        }
+        if self.wait_timer > 0.0 {
+            self.wait_timer -= delta_time;
+            return None;
+        }
+        self.segment = self.segment.min(count - 1);
+        let to = self.next_index(count)?;
+        self.progress += self.speed * delta_time / length(self.segment, to).max(1e-4);
+
+        if self.progress >= 1.0 {
+            let from = self.segment;
+            self.progress = 0.0;
+            self.segment = to;
+            self.wait_timer = self.wait_time;
+            if to == count - 1 {
+                self.reverse = true;
+            } else if to == 0 {
+                self.reverse = false;
+            }
+            return Some((from, to, 1.0));
+        }
+        Some((self.segment, to, self.easing.apply(self.progress)))
+    }
+}
@@ -1,35 +1,39 @@
-use glow::HasContext;
+use crate::index::engine::rhi::{ Pipeline, Texture };
 
 #[derive(Debug, Clone)]
 pub struct Material {
-    pub shader_program: glow::Program,
-    pub base_color_texture: Option<glow::Texture>,
-    #[allow(dead_code)]
-    pub metallic_factor: f32,
-    #[allow(dead_code)]
-    pub roughness_factor: f32,
+    pub pipeline: Pipeline,
+    pub base_color_texture: Option<Texture>,
+    pub metallic_factor: f32,  // Reflections from probes take the base color as metals do
+    pub roughness_factor: f32, // Blurs probe reflections
     #[allow(dead_code)]
     pub double_sided: bool,
+    pub transparent: bool, // glTF alphaMode BLEND: drawn by the transparency pass instead of with the opaque meshes
+    pub opacity: f32,      // Base color alpha factor, multiplied with the texture's alpha when transparent
 }
 
 impl Material {
-    pub fn new(shader_program: glow::Program) -> Self {
+    pub fn new(pipeline: Pipeline) -> Self {
         Self {
-            shader_program,
+            pipeline,
             base_color_texture: None,
             metallic_factor: 0.0,
             roughness_factor: 0.5,
             double_sided: false,
+            transparent: false,
+            opacity: 1.0,
         }
     }
 
-    pub fn with_texture(shader_program: glow::Program, texture: glow::Texture) -> Self {
+    pub fn with_texture(pipeline: Pipeline, texture: Texture) -> Self {
         Self {
-            shader_program,
+            pipeline,
             base_color_texture: Some(texture),
             metallic_factor: 0.0,
             roughness_factor: 0.5,
             double_sided: false,
+            transparent: false,
+            opacity: 1.0,
         }
     }
 
@@ -38,21 +42,10 @@ impl Material {
         self.base_color_texture.is_some()
     }
 
-    pub fn bind(&self, gl: &glow::Context) {
-        if let Some(texture) = self.base_color_texture {
-            unsafe {
-                gl.active_texture(glow::TEXTURE0);
-                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
-            }
-        }
-    }
-
     #[allow(dead_code)]
     pub fn cleanup(&self, gl: &glow::Context) {
         if let Some(texture) = self.base_color_texture {
-            unsafe {
-                gl.delete_texture(texture);
-            }
+            texture.delete(gl);
         }
     }
 }
@@ -61,11 +54,13 @@ impl Default for Material {
     fn default() -> Self {
         Self {
             #[allow(invalid_value)]
-            shader_program: unsafe { std::mem::MaybeUninit::zeroed().assume_init() },
+            pipeline: unsafe { std::mem::MaybeUninit::zeroed().assume_init() },
             base_color_texture: None,
             metallic_factor: 0.0,
             roughness_factor: 0.5,
             double_sided: false,
+            transparent: false,
+            opacity: 1.0,
         }
     }
 }
@@ -1,10 +1,51 @@
+use std::sync::Arc;
+use serde::{ Serialize, Serializer };
+
+use crate::index::engine::rhi::{ Buffer, Geometry, Texture };
+
 // Mesh component for 3D objects
 #[derive(Clone, Debug)]
 pub struct Mesh {
-    pub vao: glow::VertexArray,
+    pub geometry: Geometry,
     pub index_count: usize,
     #[allow(dead_code)]
     pub vertex_count: usize,
+    pub bounds: ([f32; 3], [f32; 3]), // Local-space AABB (min, max) of the vertex positions, computed at import
+    pub skin: Option<SkinBuffers>,      // Skinned meshes only
+    pub data: Option<Arc<MeshData>>,    // CPU copy of the vertices for bakes, shared by every copy of the mesh
+    pub morph: Option<Arc<MorphTargets>>, // Meshes with blend shapes only
+    pub lod: Option<Arc<Mesh>>,           // Decimated copy (LOD 1) of a mesh imported with a simplify ratio
+    pub vertex_colors: bool,              // Imported with COLOR_0; without, the color attribute is white
+}
+
+/// Screen coverage (bounding radius over distance from the camera) below which a mesh draws its simplified LOD,
+/// so heavy source models can stand in for blockout geometry without drawing every triangle far away
+const LOD_SCREEN_SIZE: f32 = 0.15;
+
+/// Local-space triangles of a mesh: packed xyz positions and normals, three indices per triangle
+#[derive(Debug)]
+pub struct MeshData {
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub indices: Vec<u32>,
+}
+
+/// Rest-pose vertex buffers of a skinned mesh, read back by the GPU skinning pass: packed xyz positions and
+/// normals, four u8 joint indices and four f32 weights per vertex
+#[derive(Clone, Copy, Debug)]
+pub struct SkinBuffers {
+    pub positions: Buffer,
+    pub normals: Buffer,
+    pub joints: Buffer,
+    pub weights: Buffer,
+}
+
+/// Blend shapes of a mesh: vertex displacements the animated shaders add in proportion to per-entity weights
+#[derive(Debug)]
+pub struct MorphTargets {
+    pub texture: Texture, // Per vertex, per target: position then normal displacement texels, MORPH_ROW to a row
+    pub count: usize,
+    pub default_weights: Vec<f32>, // The file's weights, one per target
 }
 
 impl Mesh {
@@ -13,12 +54,82 @@ impl Mesh {
         // Using a dummy non-zero value to avoid the zero-initialization warning
         Self {
             #[allow(invalid_value)]
-            vao: unsafe { std::mem::MaybeUninit::zeroed().assume_init() }, // Will be properly initialized when loading model
+            geometry: unsafe { std::mem::MaybeUninit::zeroed().assume_init() }, // Will be properly initialized when loading model
             index_count: 0,
             vertex_count: 0,
+            bounds: ([0.0; 3], [0.0; 3]),
+            skin: None,
+            data: None,
+            morph: None,
+            lod: None,
+            vertex_colors: false,
         }
     }
 
+    /// Local AABB of packed xyz positions
+    pub fn compute_bounds(positions: &[f32]) -> ([f32; 3], [f32; 3]) {
+        if positions.len() < 3 {
+            return ([0.0; 3], [0.0; 3]);
+        }
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for position in positions.chunks_exact(3) {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(position[axis]);
+                max[axis] = max[axis].max(position[axis]);
+            }
+        }
+        (min, max)
+    }
+
+    /// Serialize a mesh as its local bounds, the read-only `bounds` field StaticObject3D and AnimatedObject3D show
+    /// in the inspector; loading ignores it, the asset's mesh brings its own
+    pub fn serialize_bounds<S: Serializer>(mesh: &Mesh, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Bounds {
+            min: [f32; 3],
+            max: [f32; 3],
+        }
+        let (min, max) = mesh.bounds;
+        Bounds { min, max }.serialize(serializer)
+    }
+
+    /// World-space AABB of the local bounds under a row-major world transform
+    pub fn world_bounds(&self, world_txfm: &[f32; 16]) -> ([f32; 3], [f32; 3]) {
+        let (local_min, local_max) = self.bounds;
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for corner in 0..8 {
+            let local = [
+                if corner & 1 == 0 { local_min[0] } else { local_max[0] },
+                if corner & 2 == 0 { local_min[1] } else { local_max[1] },
+                if corner & 4 == 0 { local_min[2] } else { local_max[2] },
+            ];
+            for axis in 0..3 {
+                let row = axis * 4;
+                let world = world_txfm[row] * local[0] +
+                    world_txfm[row + 1] * local[1] +
+                    world_txfm[row + 2] * local[2] +
+                    world_txfm[row + 3];
+                min[axis] = min[axis].min(world);
+                max[axis] = max[axis].max(world);
+            }
+        }
+        (min, max)
+    }
+
+    /// The mesh to draw under `world_txfm` seen from `eye`: the simplified LOD once the mesh is small on screen
+    pub fn lod_at(&self, world_txfm: &[f32; 16], eye: Option<[f32; 3]>) -> &Mesh {
+        let (Some(lod), Some(eye)) = (&self.lod, eye) else {
+            return self;
+        };
+        let (min, max) = self.world_bounds(world_txfm);
+        let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) * 0.5);
+        let radius = (0..3).map(|axis| (max[axis] - min[axis]) * 0.5).map(|half| half * half).sum::<f32>().sqrt();
+        let distance = (0..3).map(|axis| (center[axis] - eye[axis]).powi(2)).sum::<f32>().sqrt();
+        if distance > radius && radius / distance < LOD_SCREEN_SIZE { lod } else { self }
+    }
+
     #[allow(dead_code)]
     pub fn is_valid(&self) -> bool {
         self.index_count > 0 && self.vertex_count > 0
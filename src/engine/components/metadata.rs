@@ -1,10 +1,82 @@
 use serde::{Serialize, Deserialize};
 
+/// Render layer for regular scene content
+#[allow(dead_code)]
+pub const LAYER_DEFAULT: u32 = 0;
+/// Collider wireframes, navmesh debug lines and transform gizmos
+pub const LAYER_GIZMOS: u32 = 1;
+/// Editor notes and helper markers placed in the scene
+#[allow(dead_code)]
+pub const LAYER_ANNOTATIONS: u32 = 2;
+/// Stand-in meshes for otherwise invisible entities (spawners, lights, triggers)
+pub const LAYER_EDITOR_ICONS: u32 = 3;
+/// Layers drawn only by the editor viewport, never by the Play camera or captures
+pub const EDITOR_ONLY_LAYERS: u32 = layer_bit(LAYER_GIZMOS) | layer_bit(LAYER_ANNOTATIONS) | layer_bit(LAYER_EDITOR_ICONS);
+
+/// Mask bit of a render layer (layers past 31 fall outside every mask)
+pub const fn layer_bit(layer: u32) -> u32 {
+    if layer < 32 { 1 << layer } else { 0 }
+}
+
+/// Editor category of an entity, shown as a colored icon in the outliner and the viewport
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EntityCategory {
+    #[default]
+    Uncategorized,
+    Light,
+    Prop,
+    Gameplay,
+    Volume,
+}
+
+impl EntityCategory {
+    /// Icon tint, matching the outliner colors in colors.slint
+    pub fn color(&self) -> [f32; 3] {
+        match self {
+            EntityCategory::Uncategorized => [0.6, 0.6, 0.6],
+            EntityCategory::Light => [1.0, 0.84, 0.31],
+            EntityCategory::Prop => [0.56, 0.64, 0.68],
+            EntityCategory::Gameplay => [0.4, 0.73, 0.42],
+            EntityCategory::Volume => [0.31, 0.76, 0.97],
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            EntityCategory::Uncategorized => "Uncategorized",
+            EntityCategory::Light => "Light",
+            EntityCategory::Prop => "Prop",
+            EntityCategory::Gameplay => "Gameplay",
+            EntityCategory::Volume => "Volume",
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Metadata {
     pub title: String,
     pub role: Option<String>, // Entity role for global variable binding
     pub is_persist: bool,     // Whether this entity should be saved to JSON
+    #[serde(default)]
+    pub tags: Vec<String>,    // Free-form labels for lookups and filtering
+    #[serde(default)]
+    pub render_layer: u32,    // Render layer index (0 = default), matched against the camera's culling mask
+    #[serde(default = "default_visible")]
+    pub is_visible: bool,     // Hidden entities are skipped by the render passes
+    #[serde(default)]
+    pub locked: bool,         // Locked entities can't be selected or edited in the editor
+    #[serde(default = "default_enabled")]
+    pub is_enabled: bool,     // Disabled entities keep their data but are skipped by every system
+    #[serde(default)]
+    pub category: EntityCategory, // Outliner and viewport icon
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+fn default_enabled() -> bool {
+    true
 }
 
 impl Metadata {
@@ -13,9 +85,20 @@ impl Metadata {
             title: title.to_string(),
             role: role.map(|r| r.to_string()),
             is_persist: is_persist.unwrap_or(true), // Default to persistent
+            tags: Vec::new(),
+            render_layer: 0,
+            is_visible: true,
+            locked: false,
+            is_enabled: true,
+            category: EntityCategory::Uncategorized,
         }
     }
 
+    pub fn with_category(mut self, category: EntityCategory) -> Self {
+        self.category = category;
+        self
+    }
+
     pub fn title(&self) -> &str {
         &self.title
     }
@@ -23,4 +106,8 @@ impl Metadata {
     pub fn role(&self) -> Option<&str> {
         self.role.as_deref()
     }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
 }
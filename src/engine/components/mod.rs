@@ -14,15 +14,65 @@ pub mod static_object3d;
 pub mod system;
 pub mod transform;
 pub mod rigid_body;
+pub mod navmesh;
+pub mod nav_agent;
+pub mod ai_agent;
+pub mod health;
+pub mod spawner;
+pub mod time_of_day;
+pub mod terrain;
+pub mod water;
+pub mod point_light;
+pub mod baked_occlusion;
+pub mod reflection_probe;
+pub mod color_grading;
+pub mod custom_material;
+pub mod property_animation;
+pub mod ragdoll;
+pub mod physics_material;
+pub mod kinematic_mover;
+pub mod projectile;
+pub mod interactable;
+pub mod player_controller;
+pub mod plugin_component;
+pub mod blockout;
+pub mod spline;
+pub mod volume;
 
 // Re-export commonly used types for convenience
+pub use ai_agent::{ AiAgent, AiBehavior, AiState };
+pub use baked_occlusion::BakedOcclusion;
+pub use blockout::{ Blockout, BlockoutBox };
 pub use camera::Camera as CameraComponent;
+pub use camera::CameraProjection;
 pub use collider::{ Collider, ColliderLayer };
+pub use color_grading::ColorGrading;
 pub use component_types::ComponentType;
-pub use metadata::Metadata;
+pub use custom_material::{ CustomMaterial, MaterialValue };
+pub use kinematic_mover::KinematicMover;
+pub use interactable::{ Interactable, InteractEvent };
+pub use health::{ DamageEvent, DeathBehavior, DeathEvent, Faction, Health };
+pub use metadata::{ layer_bit, EntityCategory, Metadata, EDITOR_ONLY_LAYERS, LAYER_EDITOR_ICONS, LAYER_GIZMOS };
+pub use navmesh::{ NavMesh, NavMeshSettings };
+pub use ragdoll::Ragdoll;
+pub use reflection_probe::ReflectionProbe;
+pub use rigid_body::RigidBody;
+pub use nav_agent::NavAgent;
+pub use physics_material::{ PhysicsMaterial, SurfaceType };
+pub use player_controller::{ CharacterInput, FootstepEvent, PlayerController };
+pub use plugin_component::PluginComponent;
+pub use point_light::PointLight;
+pub use projectile::{ Projectile, ProjectileHitEvent };
+pub use property_animation::{ AnimatedProperty, Curve, PropertyAnimation, PropertyTrack };
 pub use shapes::Shape;
+pub use spawner::{ SpawnArea, SpawnMode, Spawner };
+pub use spline::{ Spline, SplinePoint, SplineProfile };
 pub use system::SystemTrait;
+pub use terrain::{ Terrain, TerrainBrush };
+pub use time_of_day::{ Lighting, TimeOfDay };
 pub use transform::Transform;
+pub use volume::{ Volume, VolumeBlend, VolumeShape };
+pub use water::Water;
 #[allow(dead_code)]
 pub type Material = self::material::Material;
 #[allow(dead_code)]
@@ -0,0 +1,46 @@
+use serde::{ Serialize, Deserialize };
+
+/// Steers an entity along navmesh paths towards `destination`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NavAgent {
+    pub speed: f32,                   // Units per second
+    pub arrival_radius: f32,          // Distance at which a waypoint counts as reached
+    pub ground_offset: f32,           // Height of the entity origin above the walkable surface
+    pub destination: Option<[f32; 3]>,
+    #[serde(skip)]
+    pub path: Vec<[f32; 3]>,
+    #[serde(skip)]
+    pub path_index: usize,
+    #[serde(skip)]
+    pub path_destination: Option<[f32; 3]>, // Destination the current path was planned for
+}
+
+impl NavAgent {
+    pub fn new(speed: f32) -> Self {
+        Self {
+            speed,
+            arrival_radius: 0.2,
+            ground_offset: 0.0,
+            destination: None,
+            path: Vec::new(),
+            path_index: 0,
+            path_destination: None,
+        }
+    }
+
+    /// Request a new destination; the path is planned on the next navigation tick
+    pub fn set_destination(&mut self, destination: [f32; 3]) {
+        self.destination = Some(destination);
+    }
+
+    pub fn stop(&mut self) {
+        self.destination = None;
+        self.path.clear();
+        self.path_index = 0;
+        self.path_destination = None;
+    }
+
+    pub fn has_arrived(&self) -> bool {
+        self.destination.is_none()
+    }
+}
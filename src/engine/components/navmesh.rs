@@ -0,0 +1,523 @@
+use std::cmp::Ordering;
+use std::collections::{ BinaryHeap, HashMap, VecDeque };
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::{ Collider, Shape, Transform };
+use crate::index::engine::components::terrain::sample_heightfield;
+use crate::index::engine::utils::math::{ Vec3, dot, mat4x4_extract_translation };
+
+/// Upper bound on voxels sampled during a bake, so a huge level doesn't stall the editor
+const MAX_BAKE_VOXELS: usize = 8_000_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NavMeshSettings {
+    pub cell_size: f32,       // Horizontal voxel size
+    pub cell_height: f32,     // Vertical voxel size
+    pub agent_height: f32,    // Free space required above a walkable surface
+    pub agent_radius: f32,    // Walkable area is eroded by this distance from edges
+    pub max_step_height: f32, // Largest height difference between neighbouring cells
+}
+
+impl Default for NavMeshSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: 0.5,
+            cell_height: 0.25,
+            agent_height: 2.0,
+            agent_radius: 0.5,
+            max_step_height: 0.5,
+        }
+    }
+}
+
+/// One walkable column sample: grid coordinates, surface height and connected region
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct NavCell {
+    pub x: i32,
+    pub z: i32,
+    pub height: f32,
+    pub region: u32,
+}
+
+/// Walkable-surface heightfield baked from static colliders
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct NavMesh {
+    pub settings: NavMeshSettings,
+    pub origin: [f32; 2], // World x/z of the corner of cell (0, 0)
+    pub cells: Vec<NavCell>,
+    pub region_count: u32,
+}
+
+type ColumnIndex = HashMap<(i32, i32), Vec<usize>>;
+
+impl NavMesh {
+    /// Voxelize the obstacles, keep surfaces with enough clearance, erode by the agent radius
+    /// and flood-fill connected regions
+    pub fn bake(settings: NavMeshSettings, obstacles: &[(Collider, Transform)]) -> NavMesh {
+        let volumes: Vec<ShapeVolume> = obstacles
+            .iter()
+            .flat_map(|(collider, transform)| collider.parts(transform))
+            .map(|(collider, transform)| ShapeVolume::new(&collider.shape, &transform))
+            .collect();
+
+        let Some((min, max)) = volumes
+            .iter()
+            .map(|volume| volume.world_bounds())
+            .reduce(|(a_min, a_max), (b_min, b_max)| {
+                (
+                    [a_min[0].min(b_min[0]), a_min[1].min(b_min[1]), a_min[2].min(b_min[2])],
+                    [a_max[0].max(b_max[0]), a_max[1].max(b_max[1]), a_max[2].max(b_max[2])],
+                )
+            }) else {
+            return NavMesh { settings, ..Default::default() };
+        };
+
+        let cs = settings.cell_size;
+        let ch = settings.cell_height;
+        let width = ((max[0] - min[0]) / cs).ceil().max(1.0) as i32;
+        let depth = ((max[2] - min[2]) / cs).ceil().max(1.0) as i32;
+        let layers = ((max[1] - min[1]) / ch).ceil().max(1.0) as usize;
+
+        if (width as usize) * (depth as usize) * layers > MAX_BAKE_VOXELS {
+            eprintln!(
+                "⚠️ NavMesh bake skipped: {}x{}x{} voxels exceeds the limit, increase cell size",
+                width,
+                depth,
+                layers
+            );
+            return NavMesh { settings, origin: [min[0], min[2]], ..Default::default() };
+        }
+
+        let clearance_layers = (settings.agent_height / ch).ceil() as usize;
+        let mut cells = Vec::new();
+        let mut solid = vec![false; layers];
+
+        for x in 0..width {
+            for z in 0..depth {
+                let px = min[0] + ((x as f32) + 0.5) * cs;
+                let pz = min[2] + ((z as f32) + 0.5) * cs;
+
+                let candidates: Vec<&ShapeVolume> = volumes
+                    .iter()
+                    .filter(|volume| volume.overlaps_column(px, pz))
+                    .collect();
+                if candidates.is_empty() {
+                    continue;
+                }
+
+                for (layer, is_solid) in solid.iter_mut().enumerate() {
+                    let py = min[1] + ((layer as f32) + 0.5) * ch;
+                    *is_solid = candidates.iter().any(|volume| volume.contains([px, py, pz]));
+                }
+
+                // A walkable surface is the top of a solid span with enough open layers above it
+                for layer in 0..layers {
+                    if !solid[layer] || (layer + 1 < layers && solid[layer + 1]) {
+                        continue;
+                    }
+                    let open_layers = solid[layer + 1..]
+                        .iter()
+                        .take_while(|is_solid| !**is_solid)
+                        .count();
+                    let reaches_sky = layer + 1 + open_layers == layers;
+                    if reaches_sky || open_layers >= clearance_layers {
+                        cells.push(NavCell {
+                            x,
+                            z,
+                            height: min[1] + ((layer + 1) as f32) * ch,
+                            region: 0,
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut navmesh = NavMesh {
+            settings,
+            origin: [min[0], min[2]],
+            cells,
+            region_count: 0,
+        };
+        navmesh.erode();
+        navmesh.build_regions();
+
+        println!(
+            "🧭 Baked navmesh: {} walkable cells in {} regions",
+            navmesh.cells.len(),
+            navmesh.region_count
+        );
+        navmesh
+    }
+
+    /// World-space position of a cell's surface center
+    pub fn cell_position(&self, cell: &NavCell) -> Vec3 {
+        [
+            self.origin[0] + ((cell.x as f32) + 0.5) * self.settings.cell_size,
+            cell.height,
+            self.origin[1] + ((cell.z as f32) + 0.5) * self.settings.cell_size,
+        ]
+    }
+
+    /// Shortest walkable route between two world points; returns the waypoints after `from`
+    /// (the last one is `to` snapped onto the surface), or None if they aren't connected
+    pub fn find_path(&self, from: Vec3, to: Vec3) -> Option<Vec<Vec3>> {
+        let columns = self.column_index();
+        let start = self.locate(from, &columns)?;
+        let goal = self.locate(to, &columns)?;
+
+        if self.cells[start].region != self.cells[goal].region {
+            return None;
+        }
+
+        let cell_path = self.a_star(start, goal, &columns)?;
+
+        // Line-of-sight smoothing: skip every cell that can be reached in a straight line
+        let mut waypoints = Vec::new();
+        let mut anchor = 0;
+        while anchor < cell_path.len() - 1 {
+            let mut next = anchor + 1;
+            for candidate in (anchor + 2..cell_path.len()).rev() {
+                if self.has_line_of_sight(cell_path[anchor], cell_path[candidate], &columns) {
+                    next = candidate;
+                    break;
+                }
+            }
+            waypoints.push(self.cell_position(&self.cells[cell_path[next]]));
+            anchor = next;
+        }
+
+        let goal_height = self.cells[goal].height;
+        match waypoints.last_mut() {
+            Some(last) => {
+                *last = [to[0], goal_height, to[2]];
+            }
+            None => waypoints.push([to[0], goal_height, to[2]]),
+        }
+        Some(waypoints)
+    }
+
+    fn column_index(&self) -> ColumnIndex {
+        let mut columns: ColumnIndex = HashMap::new();
+        for (index, cell) in self.cells.iter().enumerate() {
+            columns.entry((cell.x, cell.z)).or_default().push(index);
+        }
+        columns
+    }
+
+    /// Cell in the neighbouring column that can be stepped onto from `index`
+    fn step_neighbor(&self, index: usize, dx: i32, dz: i32, columns: &ColumnIndex) -> Option<usize> {
+        let cell = &self.cells[index];
+        columns
+            .get(&(cell.x + dx, cell.z + dz))?
+            .iter()
+            .copied()
+            .filter(|other| (self.cells[*other].height - cell.height).abs() <= self.settings.max_step_height)
+            .min_by(|a, b| {
+                let da = (self.cells[*a].height - cell.height).abs();
+                let db = (self.cells[*b].height - cell.height).abs();
+                da.partial_cmp(&db).unwrap_or(Ordering::Equal)
+            })
+    }
+
+    /// Walkable neighbours (8-connected; diagonals only when both adjacent edges are walkable)
+    fn neighbors(&self, index: usize, columns: &ColumnIndex) -> Vec<usize> {
+        let mut result = Vec::with_capacity(8);
+        let orthogonal = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+        for (dx, dz) in orthogonal {
+            if let Some(neighbor) = self.step_neighbor(index, dx, dz, columns) {
+                result.push(neighbor);
+            }
+        }
+        for (dx, dz) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+            if
+                self.step_neighbor(index, dx, 0, columns).is_some() &&
+                self.step_neighbor(index, 0, dz, columns).is_some()
+            {
+                if let Some(neighbor) = self.step_neighbor(index, dx, dz, columns) {
+                    result.push(neighbor);
+                }
+            }
+        }
+        result
+    }
+
+    /// Remove cells within agent_radius of an edge so agents don't clip walls or fall off ledges
+    fn erode(&mut self) {
+        let iterations = (self.settings.agent_radius / self.settings.cell_size).ceil() as usize;
+        for _ in 0..iterations {
+            let columns = self.column_index();
+            let keep: Vec<bool> = (0..self.cells.len())
+                .map(|index| {
+                    [(1, 0), (-1, 0), (0, 1), (0, -1)]
+                        .iter()
+                        .all(|(dx, dz)| self.step_neighbor(index, *dx, *dz, &columns).is_some())
+                })
+                .collect();
+            let mut keep_iter = keep.into_iter();
+            self.cells.retain(|_| keep_iter.next().unwrap_or(false));
+        }
+    }
+
+    /// Flood-fill step-connected cells into regions (pathfinding only succeeds within a region)
+    fn build_regions(&mut self) {
+        let columns = self.column_index();
+        let mut region_count = 0;
+        let mut regions = vec![0u32; self.cells.len()];
+
+        for seed in 0..self.cells.len() {
+            if regions[seed] != 0 {
+                continue;
+            }
+            region_count += 1;
+            regions[seed] = region_count;
+
+            let mut queue = VecDeque::from([seed]);
+            while let Some(index) = queue.pop_front() {
+                for (dx, dz) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
+                    if let Some(neighbor) = self.step_neighbor(index, dx, dz, &columns) {
+                        if regions[neighbor] == 0 {
+                            regions[neighbor] = region_count;
+                            queue.push_back(neighbor);
+                        }
+                    }
+                }
+            }
+        }
+
+        for (cell, region) in self.cells.iter_mut().zip(regions) {
+            cell.region = region;
+        }
+        self.region_count = region_count;
+    }
+
+    /// Cell under a world point (closest surface below head height), falling back to the nearest cell
+    fn locate(&self, point: Vec3, columns: &ColumnIndex) -> Option<usize> {
+        let x = ((point[0] - self.origin[0]) / self.settings.cell_size).floor() as i32;
+        let z = ((point[2] - self.origin[1]) / self.settings.cell_size).floor() as i32;
+
+        let in_column = columns.get(&(x, z)).and_then(|column| {
+            column
+                .iter()
+                .copied()
+                .filter(|index| self.cells[*index].height <= point[1] + self.settings.agent_height)
+                .min_by(|a, b| {
+                    let da = (self.cells[*a].height - point[1]).abs();
+                    let db = (self.cells[*b].height - point[1]).abs();
+                    da.partial_cmp(&db).unwrap_or(Ordering::Equal)
+                })
+        });
+
+        in_column.or_else(|| {
+            (0..self.cells.len()).min_by(|a, b| {
+                let da = distance2(self.cell_position(&self.cells[*a]), point);
+                let db = distance2(self.cell_position(&self.cells[*b]), point);
+                da.partial_cmp(&db).unwrap_or(Ordering::Equal)
+            })
+        })
+    }
+
+    fn a_star(&self, start: usize, goal: usize, columns: &ColumnIndex) -> Option<Vec<usize>> {
+        let goal_position = self.cell_position(&self.cells[goal]);
+        let heuristic = |index: usize| distance2(self.cell_position(&self.cells[index]), goal_position).sqrt();
+
+        let mut best_cost = vec![f32::INFINITY; self.cells.len()];
+        let mut came_from = vec![usize::MAX; self.cells.len()];
+        let mut open = BinaryHeap::new();
+
+        best_cost[start] = 0.0;
+        open.push(OpenNode { priority: heuristic(start), index: start });
+
+        while let Some(OpenNode { index, .. }) = open.pop() {
+            if index == goal {
+                let mut path = vec![goal];
+                let mut current = goal;
+                while current != start {
+                    current = came_from[current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let position = self.cell_position(&self.cells[index]);
+            for neighbor in self.neighbors(index, columns) {
+                let cost =
+                    best_cost[index] +
+                    distance2(position, self.cell_position(&self.cells[neighbor])).sqrt();
+                if cost < best_cost[neighbor] {
+                    best_cost[neighbor] = cost;
+                    came_from[neighbor] = index;
+                    open.push(OpenNode { priority: cost + heuristic(neighbor), index: neighbor });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Walk the straight line between two cells and check every column along it stays walkable
+    fn has_line_of_sight(&self, from: usize, to: usize, columns: &ColumnIndex) -> bool {
+        let (fx, fz) = (self.cells[from].x as f32, self.cells[from].z as f32);
+        let (tx, tz) = (self.cells[to].x as f32, self.cells[to].z as f32);
+        let steps = ((tx - fx).abs().max((tz - fz).abs()) * 2.0).ceil() as i32;
+
+        let mut current = from;
+        for step in 1..=steps {
+            let t = (step as f32) / (steps as f32);
+            let x = (fx + (tx - fx) * t).round() as i32;
+            let z = (fz + (tz - fz) * t).round() as i32;
+            let (dx, dz) = (x - self.cells[current].x, z - self.cells[current].z);
+            if dx == 0 && dz == 0 {
+                continue;
+            }
+            if dx != 0 && dz != 0 {
+                // Corner crossing: both edge-adjacent cells must be walkable too
+                if
+                    self.step_neighbor(current, dx, 0, columns).is_none() ||
+                    self.step_neighbor(current, 0, dz, columns).is_none()
+                {
+                    return false;
+                }
+            }
+            match self.step_neighbor(current, dx, dz, columns) {
+                Some(next) => {
+                    current = next;
+                }
+                None => {
+                    return false;
+                }
+            }
+        }
+        current == to
+    }
+}
+
+fn distance2(a: Vec3, b: Vec3) -> f32 {
+    (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)
+}
+
+/// Min-heap entry for A* (BinaryHeap is a max-heap, so the ordering is reversed)
+struct OpenNode {
+    priority: f32,
+    index: usize,
+}
+
+impl PartialEq for OpenNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.partial_cmp(&self.priority).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// Collider shape placed in the world, used for voxelization
+struct ShapeVolume {
+    shape: Shape,
+    center: Vec3,
+    axes: [Vec3; 3],
+    scale: Vec3,
+}
+
+impl ShapeVolume {
+    fn new(shape: &Shape, transform: &Transform) -> Self {
+        let matrix = transform.compute_matrix();
+        let center = mat4x4_extract_translation(&matrix);
+
+        let mut axes = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+        let mut scale = [1.0; 3];
+        for i in 0..3 {
+            let column = [matrix[i], matrix[4 + i], matrix[8 + i]];
+            let length = dot(column, column).sqrt();
+            if length > 1e-8 {
+                axes[i] = [column[0] / length, column[1] / length, column[2] / length];
+                scale[i] = length;
+            }
+        }
+
+        Self { shape: shape.clone(), center, axes, scale }
+    }
+
+    fn local_half_extents(&self) -> Vec3 {
+        match self.shape {
+            Shape::Box { half_extents } => half_extents,
+            Shape::Sphere { radius } => [radius, radius, radius],
+            Shape::Capsule { radius, height } => [radius, height * 0.5 + radius, radius],
+            Shape::Cylinder { radius, height } => [radius, height * 0.5, radius],
+            Shape::Heightfield { size, .. } => [size * 0.5, 0.0, size * 0.5],
+            Shape::Compound { .. } => [0.0; 3], // Voxelized as its boxes
+        }
+    }
+
+    /// World-space AABB of the oriented local bounds
+    fn world_bounds(&self) -> (Vec3, Vec3) {
+        // Heightfields only follow translation; their vertical span comes from the height data
+        if let Shape::Heightfield { size, heights, .. } = &self.shape {
+            let half = size * 0.5;
+            let lowest = heights.iter().copied().fold(f32::INFINITY, f32::min).min(0.0);
+            let highest = heights.iter().copied().fold(f32::NEG_INFINITY, f32::max).max(0.0);
+            return (
+                [self.center[0] - half, self.center[1] + lowest - 1.0, self.center[2] - half],
+                [self.center[0] + half, self.center[1] + highest, self.center[2] + half],
+            );
+        }
+
+        let half = self.local_half_extents();
+        let mut extent = [0.0; 3];
+        for (axis, (local_half, scale)) in self.axes.iter().zip(half.iter().zip(self.scale.iter())) {
+            for world in 0..3 {
+                extent[world] += (axis[world] * local_half * scale).abs();
+            }
+        }
+        (
+            [self.center[0] - extent[0], self.center[1] - extent[1], self.center[2] - extent[2]],
+            [self.center[0] + extent[0], self.center[1] + extent[1], self.center[2] + extent[2]],
+        )
+    }
+
+    fn overlaps_column(&self, x: f32, z: f32) -> bool {
+        let (min, max) = self.world_bounds();
+        x >= min[0] && x <= max[0] && z >= min[2] && z <= max[2]
+    }
+
+    fn contains(&self, point: Vec3) -> bool {
+        let offset = [point[0] - self.center[0], point[1] - self.center[1], point[2] - self.center[2]];
+        let local = [
+            dot(offset, self.axes[0]) / self.scale[0],
+            dot(offset, self.axes[1]) / self.scale[1],
+            dot(offset, self.axes[2]) / self.scale[2],
+        ];
+
+        match &self.shape {
+            Shape::Box { half_extents } =>
+                local
+                    .iter()
+                    .zip(half_extents.iter())
+                    .all(|(value, half)| value.abs() <= *half),
+            Shape::Sphere { radius } => dot(local, local) <= radius * radius,
+            Shape::Cylinder { radius, height } =>
+                local[1].abs() <= height * 0.5 &&
+                    local[0] * local[0] + local[2] * local[2] <= radius * radius,
+            Shape::Capsule { radius, height } => {
+                let axis_y = local[1].clamp(-height * 0.5, height * 0.5);
+                let dy = local[1] - axis_y;
+                local[0] * local[0] + dy * dy + local[2] * local[2] <= radius * radius
+            }
+            Shape::Heightfield { size, resolution, heights } =>
+                sample_heightfield(*size, *resolution, heights, offset[0], offset[2])
+                    .is_some_and(|height| offset[1] <= height),
+            Shape::Compound { .. } => false,
+        }
+    }
+}
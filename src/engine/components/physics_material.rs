@@ -0,0 +1,48 @@
+use serde::{ Serialize, Deserialize };
+
+/// What a surface is made of, for footstep and impact sounds
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SurfaceType {
+    #[default]
+    Default,
+    Stone,
+    Wood,
+    Metal,
+    Grass,
+    Dirt,
+    Sand,
+    Snow,
+    Water,
+}
+
+/// Surface response of a collider in contacts
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PhysicsMaterial {
+    pub friction: f32,    // 0 = ice, 1 = rubber on concrete
+    pub restitution: f32, // Bounciness, 0..1 of the impact speed kept
+    pub density: f32,     // Relative to water (below 1 floats)
+    #[serde(default)]
+    pub surface: SurfaceType,
+}
+
+impl PhysicsMaterial {
+    pub fn new(friction: f32, restitution: f32, density: f32) -> Self {
+        Self { friction, restitution, density, surface: SurfaceType::Default }
+    }
+
+    /// Material of a contact between two surfaces: the geometric mean of the frictions and the bouncier restitution
+    pub fn combine(&self, other: &PhysicsMaterial) -> PhysicsMaterial {
+        PhysicsMaterial {
+            friction: (self.friction.max(0.0) * other.friction.max(0.0)).sqrt(),
+            restitution: self.restitution.max(other.restitution).clamp(0.0, 1.0),
+            density: self.density,
+            surface: self.surface,
+        }
+    }
+}
+
+impl Default for PhysicsMaterial {
+    fn default() -> Self {
+        Self::new(0.5, 0.0, 1.0)
+    }
+}
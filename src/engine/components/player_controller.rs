@@ -0,0 +1,91 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::SurfaceType;
+use crate::index::engine::modules::EntityId;
+
+/// Actions held during the current tick, written by the MovementSystem and consumed by the CharacterSystem
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CharacterInput {
+    pub jump: bool,
+    pub crouch: bool,
+    pub sprint: bool,
+}
+
+/// Payload of EventType::Footstep, sent by the CharacterSystem each stride and on landing
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct FootstepEvent {
+    pub entity_id: EntityId,
+    pub surface: SurfaceType,
+    pub position: [f32; 3], // Where the foot lands
+    pub sprinting: bool,
+    pub landing: bool,      // The step ends a fall rather than a stride
+}
+
+/// Movement tuning of the entity the player drives; speeds are per second and scaled by the tick's delta time.
+/// In Play mode the player walks: gravity pulls it onto the ground, Space jumps, Ctrl/C crouches and Shift sprints.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct PlayerController {
+    pub move_speed: Option<f32>, // Units per second; None follows the `move_speed` cvar
+    pub sprint_multiplier: f32,
+    pub crouch_multiplier: f32,  // Speed factor while crouched
+    pub jump_speed: f32,         // Upward launch speed in units/s
+    pub crouch_height: f32,      // Collider height while crouched
+    pub slope_limit: f32,        // Steepest walkable surface in degrees; steeper ground slides the player down
+    pub step_height: f32,        // Ledges up to this high are stepped onto instead of blocking
+    pub stride_length: f32,      // Ground distance between footsteps
+    #[serde(skip)]
+    pub input: CharacterInput,
+    #[serde(skip)]
+    pub vertical_velocity: f32,
+    #[serde(skip)]
+    pub grounded: bool,
+    #[serde(skip)]
+    pub stand_height: Option<f32>, // Collider height to restore; Some while crouched
+    #[serde(skip)]
+    pub slide_speed: f32, // Speed down a too-steep slope
+    #[serde(skip)]
+    pub stride_distance: f32, // Walked since the last footstep
+    #[serde(skip)]
+    pub last_position: Option<[f32; 3]>, // Where the previous tick left the player, to undo blocked moves
+}
+
+impl Default for PlayerController {
+    fn default() -> Self {
+        Self {
+            move_speed: None,
+            sprint_multiplier: 1.8,
+            crouch_multiplier: 0.5,
+            jump_speed: 5.0,
+            crouch_height: 1.0,
+            slope_limit: 45.0,
+            step_height: 0.4,
+            stride_length: 1.6,
+            input: CharacterInput::default(),
+            vertical_velocity: 0.0,
+            grounded: false,
+            stand_height: None,
+            slide_speed: 0.0,
+            stride_distance: 0.0,
+            last_position: None,
+        }
+    }
+}
+
+impl PlayerController {
+    pub fn is_crouched(&self) -> bool {
+        self.stand_height.is_some()
+    }
+
+    /// Horizontal speed for the held actions
+    pub fn speed(&self, base_speed: f32) -> f32 {
+        if self.is_crouched() {
+            base_speed * self.crouch_multiplier
+        } else if self.input.sprint {
+            base_speed * self.sprint_multiplier
+        } else {
+            base_speed
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use serde::{ Serialize, Deserialize };
+use serde::de::DeserializeOwned;
+
+use crate::index::engine::modules::ecs::{ get_all_components, insert, Component };
+use crate::index::engine::modules::{ App, EntityId, InterfaceSystem, Selection };
+
+/// Component of a kind registered by a plugin (`App::add_component`), kept as the JSON of the plugin's own type so
+/// the closed Component enum can hold it; an entity has at most one of each kind
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PluginComponent {
+    pub kind: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+impl PluginComponent {
+    pub fn new<T: Serialize>(kind: &str, value: &T) -> Self {
+        Self { kind: kind.to_string(), data: serde_json::to_value(value).unwrap_or_default() }
+    }
+
+    /// The entity's component of `kind` read as the plugin's type
+    #[allow(dead_code)]
+    pub fn get<T: DeserializeOwned>(entity_id: &EntityId, kind: &str) -> Option<T> {
+        get_all_components(entity_id).into_iter().find_map(|component| match component {
+            Component::Plugin(plugin) if plugin.kind == kind => serde_json::from_value(plugin.data).ok(),
+            _ => None,
+        })
+    }
+
+    /// Add or replace the entity's component of `kind`
+    #[allow(dead_code)]
+    pub fn set<T: Serialize>(entity_id: &EntityId, kind: &str, value: &T) {
+        insert(entity_id, Self::new(kind, value));
+    }
+
+    /// Console: `add_component <kind>` adds a registered kind with its default fields to the selected entities
+    pub fn add_command(args: &[&str]) -> Result<String, String> {
+        let kinds = App::component_kinds();
+        let [kind] = args else {
+            return Err(format!("Usage: add_component <kind> (one of {:?})", kinds));
+        };
+        let data = App::component_default(kind)
+            .ok_or_else(|| format!("Unknown component kind: {} (expected one of {:?})", kind, kinds))?;
+        let entity_ids = Selection::ids();
+        if entity_ids.is_empty() {
+            return Err("Select the entities to add the component to".to_string());
+        }
+        for entity_id in &entity_ids {
+            insert(entity_id, Self { kind: kind.to_string(), data: data.clone() });
+        }
+        InterfaceSystem::refresh_selection();
+        Ok(format!("Added {} to {} entities", kind, entity_ids.len()))
+    }
+}
@@ -0,0 +1,21 @@
+use serde::{ Serialize, Deserialize };
+
+/// Omnidirectional light at the entity's Transform position, fading out to nothing at `range`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PointLight {
+    pub color: [f32; 3],  // Linear rgb
+    pub intensity: f32,   // Multiplies the color
+    pub range: f32,       // World distance where the light reaches zero, also the extent used for clustering
+    pub enabled: bool,
+}
+
+impl PointLight {
+    pub fn new(color: [f32; 3], intensity: f32, range: f32) -> Self {
+        Self { color, intensity, range, enabled: true }
+    }
+
+    /// Color scaled by intensity, as the shaders use it
+    pub fn radiance(&self) -> [f32; 3] {
+        self.color.map(|channel| channel * self.intensity)
+    }
+}
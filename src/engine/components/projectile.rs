@@ -0,0 +1,54 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::{ ColliderLayer, PhysicsMaterial };
+use crate::index::engine::modules::EntityId;
+
+/// Ballistic projectile moved by swept raycasts, so fast shots can't tunnel through thin colliders
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Projectile {
+    pub velocity: [f32; 3],
+    pub gravity_factor: f32,           // 0 flies straight, 1 drops like a thrown object
+    pub lifetime: f32,                 // Seconds before it despawns without hitting anything
+    pub hit_layers: Vec<ColliderLayer>,
+    pub damage: f32,                   // Applied to the hit entity's Health, if any
+    pub owner: Option<EntityId>,       // Never hit, and credited for the damage
+    #[serde(skip)]
+    pub age: f32,
+}
+
+impl Projectile {
+    /// Projectile launched along `direction` (normalized here) at `speed` units per second
+    #[allow(dead_code)]
+    pub fn new(direction: [f32; 3], speed: f32) -> Self {
+        let length = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt().max(1e-6);
+        Self {
+            velocity: [direction[0] / length * speed, direction[1] / length * speed, direction[2] / length * speed],
+            gravity_factor: 1.0,
+            lifetime: 5.0,
+            hit_layers: vec![ColliderLayer::Environment, ColliderLayer::Player],
+            damage: 10.0,
+            owner: None,
+            age: 0.0,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn with_owner(mut self, owner: &EntityId) -> Self {
+        self.owner = Some(owner.clone());
+        self
+    }
+}
+
+/// Payload of EventType::ProjectileHit, sent before the projectile despawns
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct ProjectileHitEvent {
+    pub projectile: EntityId,
+    pub target: EntityId,
+    pub owner: Option<EntityId>,
+    pub point: [f32; 3],
+    pub normal: [f32; 3],           // Surface normal at the hit point
+    pub layer: ColliderLayer,
+    pub material: PhysicsMaterial,  // Surface material, e.g. for impact effects
+    pub velocity: [f32; 3],         // Projectile velocity at impact
+}
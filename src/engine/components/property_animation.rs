@@ -0,0 +1,270 @@
+use serde::{ Serialize, Deserialize };
+
+/// Bisection steps solving a bezier segment for a time; 20 halvings is well below a frame
+const SOLVE_STEPS: usize = 20;
+/// Closest two keys may be in time
+const KEY_SPACING: f32 = 1e-3;
+
+/// Key of a Curve: a value at a time, with bezier handles as (time, value) offsets from the key
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct CurveKey {
+    pub time: f32,
+    pub value: f32,
+    #[serde(default)]
+    pub in_handle: [f32; 2],  // Towards the previous key, time <= 0
+    #[serde(default)]
+    pub out_handle: [f32; 2], // Towards the next key, time >= 0
+}
+
+/// Float keyframes joined by cubic beziers; flat before the first key and after the last
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Curve {
+    pub keys: Vec<CurveKey>, // Sorted by time
+}
+
+impl Curve {
+    /// Value at `time`. Handle times are clamped into their segment, so each segment is a function of time.
+    pub fn evaluate(&self, time: f32) -> f32 {
+        let (Some(first), Some(last)) = (self.keys.first(), self.keys.last()) else {
+            return 0.0;
+        };
+        if time <= first.time {
+            return first.value;
+        }
+        if time >= last.time {
+            return last.value;
+        }
+        let next = self.keys.partition_point(|key| key.time <= time);
+        let (start, end) = (&self.keys[next - 1], &self.keys[next]);
+        let span = end.time - start.time;
+        if span <= f32::EPSILON {
+            return end.value;
+        }
+
+        let x1 = start.time + start.out_handle[0].clamp(0.0, span);
+        let x2 = end.time + end.in_handle[0].clamp(-span, 0.0);
+        let y1 = start.value + start.out_handle[1];
+        let y2 = end.value + end.in_handle[1];
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..SOLVE_STEPS {
+            let s = (low + high) * 0.5;
+            if bezier(start.time, x1, x2, end.time, s) < time {
+                low = s;
+            } else {
+                high = s;
+            }
+        }
+        bezier(start.value, y1, y2, end.value, (low + high) * 0.5)
+    }
+
+    /// Seconds to the last key
+    pub fn duration(&self) -> f32 {
+        self.keys.last().map_or(0.0, |key| key.time)
+    }
+
+    /// Add a key with flat handles a third of the way to its neighbours (an ease in and out), replacing a key at
+    /// the same time; returns its index
+    pub fn insert(&mut self, time: f32, value: f32) -> usize {
+        let time = time.max(0.0);
+        if let Some(index) = self.keys.iter().position(|key| (key.time - time).abs() < KEY_SPACING) {
+            self.keys[index].value = value;
+            return index;
+        }
+        let index = self.keys.partition_point(|key| key.time < time);
+        let before = index.checked_sub(1).map(|previous| time - self.keys[previous].time);
+        let after = self.keys.get(index).map(|next| next.time - time);
+        let reach = |gap: Option<f32>, other: Option<f32>| gap.or(other).unwrap_or(0.75) / 3.0;
+        self.keys.insert(index, CurveKey {
+            time,
+            value,
+            in_handle: [-reach(before, after), 0.0],
+            out_handle: [reach(after, before), 0.0],
+        });
+        index
+    }
+
+    /// Move a key, staying between its neighbours so indices don't change under a drag
+    pub fn move_key(&mut self, index: usize, time: f32, value: f32) {
+        if index >= self.keys.len() {
+            return;
+        }
+        let earliest = index.checked_sub(1).map_or(0.0, |previous| self.keys[previous].time + KEY_SPACING);
+        let latest = self.keys.get(index + 1).map_or(f32::MAX, |next| next.time - KEY_SPACING);
+        let key = &mut self.keys[index];
+        key.time = time.clamp(earliest, latest.max(earliest));
+        key.value = value;
+    }
+
+    /// Set one handle of a key (`out` for the one towards the next key); the opposite handle is mirrored so the
+    /// curve stays smooth through the key
+    pub fn set_handle(&mut self, index: usize, out: bool, offset: [f32; 2]) {
+        let Some(key) = self.keys.get_mut(index) else {
+            return;
+        };
+        let (handle, opposite) = if out {
+            (&mut key.out_handle, &mut key.in_handle)
+        } else {
+            (&mut key.in_handle, &mut key.out_handle)
+        };
+        let time = if out { offset[0].max(0.0) } else { offset[0].min(0.0) };
+        *handle = [time, offset[1]];
+        let length = opposite[0].abs().max(1e-3);
+        let slope = if time.abs() > 1e-6 { offset[1] / time } else { 0.0 };
+        let direction = if out { -1.0 } else { 1.0 };
+        *opposite = [direction * length, direction * length * slope];
+    }
+
+    /// Point a key's handles straight at its neighbours, so the segments around it are linear
+    pub fn make_linear(&mut self, index: usize) {
+        let Some(key) = self.keys.get(index).copied() else {
+            return;
+        };
+        let towards = |other: Option<&CurveKey>| {
+            other.map_or([0.0, 0.0], |other| [(other.time - key.time) / 3.0, (other.value - key.value) / 3.0])
+        };
+        let in_handle = towards(index.checked_sub(1).and_then(|previous| self.keys.get(previous)));
+        let out_handle = towards(self.keys.get(index + 1));
+        let key = &mut self.keys[index];
+        key.in_handle = in_handle;
+        key.out_handle = out_handle;
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.keys.len() {
+            self.keys.remove(index);
+        }
+    }
+}
+
+fn bezier(p0: f32, p1: f32, p2: f32, p3: f32, s: f32) -> f32 {
+    let inverse = 1.0 - s;
+    inverse * inverse * inverse * p0 + 3.0 * inverse * inverse * s * p1 + 3.0 * inverse * s * s * p2 + s * s * s * p3
+}
+
+/// Value a PropertyTrack drives; camera paths are tracks on the camera's position, pitch and yaw
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnimatedProperty {
+    PositionX,
+    PositionY,
+    PositionZ,
+    RotationX,
+    RotationY,
+    RotationZ,
+    ScaleX,
+    ScaleY,
+    ScaleZ,
+    CameraPitch,
+    CameraYaw,
+    LightIntensity,
+}
+
+impl AnimatedProperty {
+    pub const ALL: [AnimatedProperty; 12] = [
+        AnimatedProperty::PositionX,
+        AnimatedProperty::PositionY,
+        AnimatedProperty::PositionZ,
+        AnimatedProperty::RotationX,
+        AnimatedProperty::RotationY,
+        AnimatedProperty::RotationZ,
+        AnimatedProperty::ScaleX,
+        AnimatedProperty::ScaleY,
+        AnimatedProperty::ScaleZ,
+        AnimatedProperty::CameraPitch,
+        AnimatedProperty::CameraYaw,
+        AnimatedProperty::LightIntensity,
+    ];
+
+    /// Name in the curve editor and console, the component field it drives
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimatedProperty::PositionX => "position_x",
+            AnimatedProperty::PositionY => "position_y",
+            AnimatedProperty::PositionZ => "position_z",
+            AnimatedProperty::RotationX => "rotation_x",
+            AnimatedProperty::RotationY => "rotation_y",
+            AnimatedProperty::RotationZ => "rotation_z",
+            AnimatedProperty::ScaleX => "scale_x",
+            AnimatedProperty::ScaleY => "scale_y",
+            AnimatedProperty::ScaleZ => "scale_z",
+            AnimatedProperty::CameraPitch => "pitch",
+            AnimatedProperty::CameraYaw => "yaw",
+            AnimatedProperty::LightIntensity => "intensity",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|property| property.name() == name)
+    }
+}
+
+/// One property over time
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PropertyTrack {
+    pub property: AnimatedProperty,
+    pub curve: Curve,
+}
+
+/// Keyframed motion of the entity's own properties, edited in the curve editor and saved with the scene
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PropertyAnimation {
+    pub tracks: Vec<PropertyTrack>,
+    #[serde(default = "default_true")]
+    pub playing: bool,
+    #[serde(default = "default_true")]
+    pub looping: bool, // Wrap at the end instead of holding the last keys
+    #[serde(default = "default_speed")]
+    pub speed: f32,    // Multiple of real time
+    #[serde(skip)]
+    pub time: f32,     // Playhead in seconds
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_speed() -> f32 {
+    1.0
+}
+
+impl Default for PropertyAnimation {
+    fn default() -> Self {
+        Self { tracks: Vec::new(), playing: true, looping: true, speed: 1.0, time: 0.0 }
+    }
+}
+
+impl PropertyAnimation {
+    /// Seconds to the last key of any track
+    pub fn duration(&self) -> f32 {
+        self.tracks.iter().map(|track| track.curve.duration()).fold(0.0, f32::max)
+    }
+
+    /// Advance the playhead by `delta_time` seconds while playing
+    pub fn advance(&mut self, delta_time: f32) {
+        if !self.playing {
+            return;
+        }
+        let duration = self.duration();
+        self.time += delta_time * self.speed;
+        if self.time >= duration {
+            if self.looping && duration > 0.0 {
+                self.time %= duration;
+            } else {
+                self.time = duration;
+                self.playing = false;
+            }
+        }
+    }
+
+    /// Every keyed track's value at the playhead
+    pub fn sample(&self) -> Vec<(AnimatedProperty, f32)> {
+        self.tracks
+            .iter()
+            .filter(|track| !track.curve.keys.is_empty())
+            .map(|track| (track.property, track.curve.evaluate(self.time)))
+            .collect()
+    }
+
+    pub fn track_mut(&mut self, property: AnimatedProperty) -> Option<&mut Curve> {
+        self.tracks.iter_mut().find(|track| track.property == property).map(|track| &mut track.curve)
+    }
+}
@@ -0,0 +1,433 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::AnimatedObject3D::{ Node, Skeleton };
+use crate::index::engine::components::Shape;
+use crate::index::engine::utils::math::{
+    cross,
+    dot,
+    mat4x4_extract_translation,
+    node_world_txfm,
+    normalize,
+    quat_conjugate,
+    quat_from_to,
+    quat_mul,
+    quat_rotate,
+    Mat4x4,
+    Vec3,
+};
+
+/// Bones shorter than this fraction of the longest bone (fingers, toes, twist bones) follow their parent
+const MAJOR_BONE_FRACTION: f32 = 0.15;
+/// Capsule radius relative to the bone length
+const BONE_RADIUS_FRACTION: f32 = 0.2;
+/// Default swing limit between a bone and its parent bone
+const DEFAULT_SWING_LIMIT: f32 = 1.2;
+
+/// Capsule collider along one major bone, from its skeleton node to the child node it points at
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RagdollBone {
+    pub node: u32,
+    pub end_node: u32,
+    pub radius: f32,
+    pub length: f32,
+}
+
+impl RagdollBone {
+    /// Capsule along the bone's local Y axis, see `Ragdoll::bone_colliders`
+    pub fn shape(&self) -> Shape {
+        Shape::Capsule { radius: self.radius, height: self.length }
+    }
+}
+
+/// Ball joint between two bones, limiting how far the child may swing away from the parent's direction
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RagdollJoint {
+    pub parent: u32,      // Index into `bones`
+    pub child: u32,       // Index into `bones`
+    pub swing_limit: f32, // Radians
+}
+
+/// Point mass at a skeleton node while the ragdoll simulates (world space)
+#[derive(Clone, Debug)]
+pub struct RagdollParticle {
+    pub node: u32,
+    pub position: Vec3,
+    pub previous: Vec3,
+}
+
+/// Distance constraint between two particles, measured from the pose at activation
+#[derive(Clone, Debug)]
+pub struct RagdollLink {
+    pub a: usize,
+    pub b: usize,
+    pub length: f32,
+}
+
+/// Capsules and joints over an animated skeleton; once active the skeleton is driven by the
+/// simulation instead of the animator
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Ragdoll {
+    pub bones: Vec<RagdollBone>, // Parents before children
+    pub joints: Vec<RagdollJoint>,
+    pub gravity: f32,
+    pub damping: f32, // Fraction of the velocity kept every tick
+    pub iterations: u32,
+    #[serde(skip)]
+    pub active: bool,
+    #[serde(skip)]
+    pub particles: Vec<RagdollParticle>,
+    #[serde(skip)]
+    pub links: Vec<RagdollLink>,
+    #[serde(skip)]
+    pub floor: f32, // Height of the ground the entity stood on when it went limp
+}
+
+impl Ragdoll {
+    /// Build a capsule per major bone of the skeleton's current pose and a joint to each bone's parent bone
+    pub fn generate(skeleton: &Skeleton) -> Self {
+        let nodes = &skeleton.nodes;
+        let skinned: Vec<u32> = if skeleton.joint_ids.is_empty() {
+            (0..nodes.len() as u32).collect()
+        } else {
+            skeleton.joint_ids.clone()
+        };
+        let positions: Vec<Vec3> = (0..nodes.len())
+            .map(|index| mat4x4_extract_translation(&node_world_txfm(nodes, index)))
+            .collect();
+        let distance = |a: u32, b: u32| {
+            let (a, b) = (positions[a as usize], positions[b as usize]);
+            dot([b[0] - a[0], b[1] - a[1], b[2] - a[2]], [b[0] - a[0], b[1] - a[1], b[2] - a[2]]).sqrt()
+        };
+
+        // Each skinned node points at its farthest skinned child
+        let mut candidates: Vec<(u32, u32, f32)> = skinned
+            .iter()
+            .filter_map(|&node| {
+                skinned
+                    .iter()
+                    .filter(|&&child| nodes[child as usize].parent == node)
+                    .map(|&child| (node, child, distance(node, child)))
+                    .max_by(|a, b| a.2.total_cmp(&b.2))
+            })
+            .collect();
+        let longest = candidates.iter().map(|candidate| candidate.2).fold(0.0, f32::max);
+        candidates.retain(|candidate| candidate.2 > 1e-4 && candidate.2 >= longest * MAJOR_BONE_FRACTION);
+        candidates.sort_by_key(|candidate| depth(nodes, candidate.0));
+
+        let bones: Vec<RagdollBone> = candidates
+            .into_iter()
+            .map(|(node, end_node, length)| RagdollBone {
+                node,
+                end_node,
+                radius: length * BONE_RADIUS_FRACTION,
+                length,
+            })
+            .collect();
+
+        // Joint each bone to the bone of its nearest ancestor
+        let mut joints = Vec::new();
+        for (child, bone) in bones.iter().enumerate() {
+            let mut ancestor = nodes[bone.node as usize].parent;
+            while ancestor != u32::MAX {
+                if let Some(parent) = bones.iter().position(|other| other.node == ancestor) {
+                    joints.push(RagdollJoint {
+                        parent: parent as u32,
+                        child: child as u32,
+                        swing_limit: DEFAULT_SWING_LIMIT,
+                    });
+                    break;
+                }
+                ancestor = nodes[ancestor as usize].parent;
+            }
+        }
+
+        Self {
+            bones,
+            joints,
+            gravity: 9.81,
+            damping: 0.98,
+            iterations: 8,
+            active: false,
+            particles: Vec::new(),
+            links: Vec::new(),
+            floor: 0.0,
+        }
+    }
+
+    /// World-space capsule per bone (local Y runs along the bone), for debug drawing
+    pub fn bone_colliders(&self, skeleton: &Skeleton, entity_matrix: &Mat4x4) -> Vec<(Shape, Mat4x4)> {
+        self.bones
+            .iter()
+            .filter(|bone| (bone.node.max(bone.end_node) as usize) < skeleton.nodes.len())
+            .map(|bone| {
+                let start = node_world_position(skeleton, entity_matrix, bone.node);
+                let end = node_world_position(skeleton, entity_matrix, bone.end_node);
+                let up = normalize([end[0] - start[0], end[1] - start[1], end[2] - start[2]]);
+                let helper = if up[1].abs() < 0.9 { [0.0, 1.0, 0.0] } else { [1.0, 0.0, 0.0] };
+                let right = normalize(cross(helper, up));
+                let forward = cross(right, up);
+                let center = [(start[0] + end[0]) * 0.5, (start[1] + end[1]) * 0.5, (start[2] + end[2]) * 0.5];
+                let matrix = [
+                    right[0], up[0], forward[0], center[0],
+                    right[1], up[1], forward[1], center[1],
+                    right[2], up[2], forward[2], center[2],
+                    0.0,      0.0,   0.0,        1.0,
+                ];
+                (bone.shape(), matrix)
+            })
+            .collect()
+    }
+
+    /// Switch from animation to simulation, starting from the skeleton's current pose at rest.
+    /// Returns false if the bones don't fit the skeleton (e.g. generated for a different asset)
+    pub fn activate(&mut self, skeleton: &Skeleton, entity_matrix: &Mat4x4) -> bool {
+        let node_count = skeleton.nodes.len() as u32;
+        if self.bones.is_empty() || self.bones.iter().any(|bone| bone.node >= node_count || bone.end_node >= node_count) {
+            return false;
+        }
+
+        self.particles.clear();
+        for bone in &self.bones {
+            for node in [bone.node, bone.end_node] {
+                if !self.particles.iter().any(|particle| particle.node == node) {
+                    let position = node_world_position(skeleton, entity_matrix, node);
+                    self.particles.push(RagdollParticle { node, position, previous: position });
+                }
+            }
+        }
+
+        self.links.clear();
+        let bone_nodes: Vec<(u32, u32)> = self.bones.iter().map(|bone| (bone.node, bone.end_node)).collect();
+        for (node, end_node) in bone_nodes {
+            self.add_link(node, end_node);
+        }
+        // Bones branching off the middle of their parent (shoulders off the spine) are braced to both ends
+        for joint in &self.joints.clone() {
+            let (Some(parent), Some(child)) = (self.bones.get(joint.parent as usize), self.bones.get(joint.child as usize)) else {
+                continue;
+            };
+            if child.node != parent.node && child.node != parent.end_node {
+                let (child_node, parent_node, parent_end) = (child.node, parent.node, parent.end_node);
+                self.add_link(child_node, parent_node);
+                self.add_link(child_node, parent_end);
+            }
+        }
+
+        self.floor = self.particles
+            .iter()
+            .zip(particle_radii(&self.bones, &self.particles))
+            .map(|(particle, radius)| particle.position[1] - radius)
+            .fold(f32::MAX, f32::min);
+        self.active = true;
+        true
+    }
+
+    fn add_link(&mut self, node_a: u32, node_b: u32) {
+        if let (Some(a), Some(b)) = (self.particle_index(node_a), self.particle_index(node_b)) {
+            let (pa, pb) = (self.particles[a].position, self.particles[b].position);
+            let length = dot([pb[0] - pa[0], pb[1] - pa[1], pb[2] - pa[2]], [pb[0] - pa[0], pb[1] - pa[1], pb[2] - pa[2]]).sqrt();
+            if length > 1e-4 {
+                self.links.push(RagdollLink { a, b, length });
+            }
+        }
+    }
+
+    fn particle_index(&self, node: u32) -> Option<usize> {
+        self.particles.iter().position(|particle| particle.node == node)
+    }
+
+    /// Advance the particles one tick (Verlet) and relax links, joint limits and ground contact.
+    /// `ground` returns the terrain height under a world x/z position, if any
+    pub fn step(&mut self, delta_time: f32, ground: impl Fn(f32, f32) -> Option<f32>) {
+        let drop = self.gravity * delta_time * delta_time;
+        let radii = particle_radii(&self.bones, &self.particles);
+        for particle in &mut self.particles {
+            let velocity = [
+                (particle.position[0] - particle.previous[0]) * self.damping,
+                (particle.position[1] - particle.previous[1]) * self.damping,
+                (particle.position[2] - particle.previous[2]) * self.damping,
+            ];
+            particle.previous = particle.position;
+            particle.position = [
+                particle.position[0] + velocity[0],
+                particle.position[1] + velocity[1] - drop,
+                particle.position[2] + velocity[2],
+            ];
+        }
+
+        for _ in 0..self.iterations.max(1) {
+            for link in &self.links {
+                let (pa, pb) = (self.particles[link.a].position, self.particles[link.b].position);
+                let delta = [pb[0] - pa[0], pb[1] - pa[1], pb[2] - pa[2]];
+                let current = dot(delta, delta).sqrt();
+                if current < 1e-6 {
+                    continue;
+                }
+                let correction = (current - link.length) / current * 0.5;
+                let offset = [delta[0] * correction, delta[1] * correction, delta[2] * correction];
+                self.particles[link.a].position = [pa[0] + offset[0], pa[1] + offset[1], pa[2] + offset[2]];
+                self.particles[link.b].position = [pb[0] - offset[0], pb[1] - offset[1], pb[2] - offset[2]];
+            }
+
+            for index in 0..self.joints.len() {
+                let joint = self.joints[index].clone();
+                self.limit_swing(&joint);
+            }
+
+            for (particle, radius) in self.particles.iter_mut().zip(radii.iter().copied()) {
+                let terrain = ground(particle.position[0], particle.position[2]).unwrap_or(f32::MIN);
+                let surface = self.floor.max(terrain) + radius;
+                if particle.position[1] < surface {
+                    particle.position[1] = surface;
+                    // Friction: cancel most of the sliding on contact
+                    particle.previous[0] = particle.position[0] - (particle.position[0] - particle.previous[0]) * 0.5;
+                    particle.previous[2] = particle.position[2] - (particle.position[2] - particle.previous[2]) * 0.5;
+                }
+            }
+        }
+    }
+
+    /// Pull the child bone's end back into a cone around its parent bone's direction
+    fn limit_swing(&mut self, joint: &RagdollJoint) {
+        let (Some(parent), Some(child)) = (self.bones.get(joint.parent as usize), self.bones.get(joint.child as usize)) else {
+            return;
+        };
+        let indices = (
+            self.particle_index(parent.node),
+            self.particle_index(parent.end_node),
+            self.particle_index(child.node),
+            self.particle_index(child.end_node),
+        );
+        let (Some(parent_start), Some(parent_end), Some(child_start), Some(child_end)) = indices else {
+            return;
+        };
+        let position = |index: usize| self.particles[index].position;
+        let (ps, pe, cs, ce) = (position(parent_start), position(parent_end), position(child_start), position(child_end));
+
+        let axis = normalize([pe[0] - ps[0], pe[1] - ps[1], pe[2] - ps[2]]);
+        let offset = [ce[0] - cs[0], ce[1] - cs[1], ce[2] - cs[2]];
+        let length = dot(offset, offset).sqrt();
+        let direction = normalize(offset);
+        if length < 1e-6 || dot(axis, direction) >= joint.swing_limit.cos() {
+            return;
+        }
+
+        // Rotate towards the axis until the bone sits on the cone's edge
+        let perpendicular = normalize([
+            direction[0] - axis[0] * dot(axis, direction),
+            direction[1] - axis[1] * dot(axis, direction),
+            direction[2] - axis[2] * dot(axis, direction),
+        ]);
+        let (sin, cos) = joint.swing_limit.sin_cos();
+        let limited = [
+            (axis[0] * cos + perpendicular[0] * sin) * length,
+            (axis[1] * cos + perpendicular[1] * sin) * length,
+            (axis[2] * cos + perpendicular[2] * sin) * length,
+        ];
+        self.particles[child_end].position = [cs[0] + limited[0], cs[1] + limited[1], cs[2] + limited[2]];
+    }
+
+    /// Rotate (and for root bones, move) the skeleton nodes so every bone follows its particles
+    pub fn apply_pose(&self, skeleton: &mut Skeleton, entity_matrix: &Mat4x4) {
+        let to_model = inverse_rigid(entity_matrix);
+        let target = |node: u32| -> Option<Vec3> {
+            let world = self.particles[self.particle_index(node)?].position;
+            Some(transform_point(&to_model, world))
+        };
+
+        for (index, bone) in self.bones.iter().enumerate() {
+            let (Some(start_target), Some(end_target)) = (target(bone.node), target(bone.end_node)) else {
+                continue;
+            };
+            let parent = skeleton.nodes[bone.node as usize].parent;
+            let parent_rotation = node_model_rotation(&skeleton.nodes, parent);
+
+            // Only bones without a parent bone carry the body's position
+            if !self.joints.iter().any(|joint| joint.child as usize == index) {
+                let current = mat4x4_extract_translation(&node_world_txfm(&skeleton.nodes, bone.node as usize));
+                let delta = [start_target[0] - current[0], start_target[1] - current[1], start_target[2] - current[2]];
+                let local = quat_rotate(quat_conjugate(parent_rotation), delta);
+                let translation = &mut skeleton.nodes[bone.node as usize].translation;
+                *translation = [translation[0] + local[0], translation[1] + local[1], translation[2] + local[2]];
+            }
+
+            let start = mat4x4_extract_translation(&node_world_txfm(&skeleton.nodes, bone.node as usize));
+            let end = mat4x4_extract_translation(&node_world_txfm(&skeleton.nodes, bone.end_node as usize));
+            let swing = quat_from_to(
+                [end[0] - start[0], end[1] - start[1], end[2] - start[2]],
+                [end_target[0] - start[0], end_target[1] - start[1], end_target[2] - start[2]],
+            );
+            // Apply the model-space swing in the parent's space
+            let local_swing = quat_mul(quat_conjugate(parent_rotation), quat_mul(swing, parent_rotation));
+            let node = &mut skeleton.nodes[bone.node as usize];
+            node.rotation = quat_mul(local_swing, node.rotation);
+        }
+    }
+}
+
+/// Number of ancestors above a node
+fn depth(nodes: &[Node], node: u32) -> u32 {
+    let mut depth = 0;
+    let mut current = nodes[node as usize].parent;
+    while current != u32::MAX {
+        depth += 1;
+        current = nodes[current as usize].parent;
+    }
+    depth
+}
+
+/// Model-space rotation of a node (u32::MAX is the model root)
+fn node_model_rotation(nodes: &[Node], node: u32) -> [f32; 4] {
+    if node == u32::MAX {
+        return [0.0, 0.0, 0.0, 1.0];
+    }
+    let own = nodes[node as usize].rotation;
+    quat_mul(node_model_rotation(nodes, nodes[node as usize].parent), own)
+}
+
+fn node_world_position(skeleton: &Skeleton, entity_matrix: &Mat4x4, node: u32) -> Vec3 {
+    let local = mat4x4_extract_translation(&node_world_txfm(&skeleton.nodes, node as usize));
+    transform_point(entity_matrix, local)
+}
+
+fn transform_point(matrix: &Mat4x4, point: Vec3) -> Vec3 {
+    [
+        matrix[0] * point[0] + matrix[1] * point[1] + matrix[2] * point[2] + matrix[3],
+        matrix[4] * point[0] + matrix[5] * point[1] + matrix[6] * point[2] + matrix[7],
+        matrix[8] * point[0] + matrix[9] * point[1] + matrix[10] * point[2] + matrix[11],
+    ]
+}
+
+/// Inverse of a rotation/translation/scale matrix (row-major, no shear)
+fn inverse_rigid(matrix: &Mat4x4) -> Mat4x4 {
+    let mut inverse = [0.0; 16];
+    for column in 0..3 {
+        let axis = [matrix[column], matrix[4 + column], matrix[8 + column]];
+        let scale2 = dot(axis, axis).max(1e-8);
+        for row in 0..3 {
+            inverse[column * 4 + row] = axis[row] / scale2;
+        }
+    }
+    let translation = [matrix[3], matrix[7], matrix[11]];
+    for row in 0..3 {
+        inverse[row * 4 + 3] = -(inverse[row * 4] * translation[0]
+            + inverse[row * 4 + 1] * translation[1]
+            + inverse[row * 4 + 2] * translation[2]);
+    }
+    inverse[15] = 1.0;
+    inverse
+}
+
+/// Collision radius of each particle: the thickest capsule touching it
+fn particle_radii(bones: &[RagdollBone], particles: &[RagdollParticle]) -> Vec<f32> {
+    particles
+        .iter()
+        .map(|particle| {
+            bones
+                .iter()
+                .filter(|bone| bone.node == particle.node || bone.end_node == particle.node)
+                .map(|bone| bone.radius)
+                .fold(0.0, f32::max)
+        })
+        .collect()
+}
@@ -0,0 +1,22 @@
+use serde::{ Serialize, Deserialize };
+
+/// Captures a cubemap of its surroundings at the entity's Transform position, which static meshes inside its box
+/// reflect. Captured when placed or loaded and again on "Bake Probes", so moving it needs a rebake.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReflectionProbe {
+    pub extent: [f32; 3],     // Half size of the box around the probe that meshes take reflections from
+    pub box_projection: bool, // Reflect off the box's walls instead of an infinitely distant surrounding (rooms)
+}
+
+impl ReflectionProbe {
+    pub fn new(extent: [f32; 3]) -> Self {
+        Self { extent, box_projection: false }
+    }
+
+    /// Influence box around the probe position
+    pub fn bounds(&self, position: [f32; 3]) -> ([f32; 3], [f32; 3]) {
+        let min = [position[0] - self.extent[0], position[1] - self.extent[1], position[2] - self.extent[2]];
+        let max = [position[0] + self.extent[0], position[1] + self.extent[1], position[2] + self.extent[2]];
+        (min, max)
+    }
+}
@@ -1,12 +1,35 @@
 use serde::{ Deserialize, Serialize };
 
+/// Makes a collider entity respond to gravity and contacts
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct RigidBody {
-    pub momentum: i32,
+    #[serde(default = "default_gravity_scale")]
+    pub gravity_scale: f32,
+    #[serde(default)]
+    pub is_kinematic: bool, // Moved by code only (player controller, movers); the solver leaves it alone
+    #[serde(skip)]
+    pub velocity: [f32; 3],
+}
+
+fn default_gravity_scale() -> f32 {
+    1.0
 }
 
 impl RigidBody {
+    #[allow(dead_code)]
     pub fn new() -> Self {
-        Self { momentum: 1 }
+        Self {
+            gravity_scale: default_gravity_scale(),
+            is_kinematic: false,
+            velocity: [0.0, 0.0, 0.0],
+        }
+    }
+
+    pub fn kinematic() -> Self {
+        Self {
+            gravity_scale: 0.0,
+            is_kinematic: true,
+            velocity: [0.0, 0.0, 0.0],
+        }
     }
 }
@@ -18,6 +18,16 @@ pub enum Shape {
         radius: f32,
         height: f32,
     },
+    Heightfield {
+        size: f32,
+        resolution: u32,
+        #[serde(skip)]
+        heights: Vec<f32>, // Copied from the entity's Terrain by the TerrainSystem
+    },
+    Compound {
+        #[serde(skip)]
+        boxes: Vec<[Vec3; 2]>, // Local (min, max) of each box, copied from the entity's Blockout by the BlockoutSystem
+    },
 }
 
 impl Shape {
@@ -27,6 +37,8 @@ impl Shape {
             Shape::Capsule { radius, height } => format!("Capsule (r: {:.2}, h: {:.2})", radius, height),
             Shape::Box { half_extents } => format!("Box ({:.2}, {:.2}, {:.2})", half_extents[0], half_extents[1], half_extents[2]),
             Shape::Cylinder { radius, height } => format!("Cylinder (r: {:.2}, h: {:.2})", radius, height),
+            Shape::Heightfield { size, resolution, .. } => format!("Heightfield ({:.2}, {}x{})", size, resolution, resolution),
+            Shape::Compound { boxes } => format!("Compound ({} boxes)", boxes.len()),
         }
     }
 }
@@ -0,0 +1,76 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::modules::EntityId;
+use crate::index::engine::modules::random::Rng;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SpawnMode {
+    Interval { seconds: f32 }, // Spawn repeatedly while below the alive limit
+    OneShot,                   // Spawn `max_alive` entities once, then stop
+    Triggered { radius: f32 }, // Spawn when the player enters the radius or SpawnSystem::trigger is called
+}
+
+/// Area around the spawner's Transform where entities are placed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SpawnArea {
+    Point,
+    Box { half_extents: [f32; 3] },
+    Circle { radius: f32 }, // Horizontal disc
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Spawner {
+    pub prefab: String, // Name resolved by the game's prefab table (e.g. "TestingDoll")
+    pub mode: SpawnMode,
+    pub area: SpawnArea,
+    pub max_alive: u32,
+    pub enabled: bool,
+    #[serde(default)]
+    pub pool_size: u32, // Despawned spawns kept in an EntityPool for reuse, 0 spawns each one anew
+    #[serde(skip)]
+    pub alive: Vec<EntityId>,
+    #[serde(skip)]
+    pub timer: f32,
+    #[serde(skip)]
+    pub has_fired: bool, // OneShot: already spawned; Triggered: player currently inside the radius
+    #[serde(skip)]
+    pub pending_triggers: u32,
+    #[serde(skip)]
+    pub rng: Option<Rng>, // Placement stream, seeded by the SpawnSystem from the world seed
+}
+
+impl Spawner {
+    pub fn new(prefab: &str, mode: SpawnMode, area: SpawnArea, max_alive: u32) -> Self {
+        Self {
+            prefab: prefab.to_string(),
+            mode,
+            area,
+            max_alive,
+            enabled: true,
+            pool_size: 0,
+            alive: Vec::new(),
+            timer: 0.0,
+            has_fired: false,
+            pending_triggers: 0,
+            rng: None,
+        }
+    }
+
+    /// Random offset from the spawner origin inside the spawn area
+    pub fn sample_offset(&mut self) -> [f32; 3] {
+        let rng = self.rng.get_or_insert_with(|| Rng::new(0));
+        match self.area {
+            SpawnArea::Point => [0.0, 0.0, 0.0],
+            SpawnArea::Box { half_extents } => [
+                rng.range(-1.0, 1.0) * half_extents[0],
+                rng.range(-1.0, 1.0) * half_extents[1],
+                rng.range(-1.0, 1.0) * half_extents[2],
+            ],
+            SpawnArea::Circle { radius } => {
+                let angle = rng.next_f32() * std::f32::consts::TAU;
+                let distance = rng.next_f32().sqrt() * radius;
+                [angle.cos() * distance, 0.0, angle.sin() * distance]
+            }
+        }
+    }
+}
@@ -0,0 +1,317 @@
+use std::f32::consts::TAU;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::Transform;
+use crate::index::engine::utils::math::{ cross, dist2, len2, normalize, Vec3 };
+use crate::index::engine::utils::mesh_simplify::MeshAttributes;
+
+/// Samples per curve segment in the table distances along the spline are looked up in
+const SAMPLES_PER_SEGMENT: usize = 32;
+/// Local units one repeat of the texture spans on extruded faces
+const TEXTURE_REPEAT: f32 = 2.0;
+/// Length of the straight pieces a new spline's mesh is swept in
+const DEFAULT_STEP: f32 = 0.5;
+/// Shortest piece, so a zero step can't sweep forever
+const MIN_STEP: f32 = 0.05;
+/// Width of a railing's rail and posts
+const RAIL_THICKNESS: f32 = 0.08;
+
+/// Control point in the entity's local space; the handles are offsets from it that the curve leaves towards
+/// (out) and arrives from (in)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct SplinePoint {
+    pub position: Vec3,
+    pub in_handle: Vec3,
+    pub out_handle: Vec3,
+}
+
+impl SplinePoint {
+    /// Point the curve passes through smoothly along `handle`, which points the way it leaves
+    pub fn smooth(position: Vec3, handle: Vec3) -> Self {
+        Self { position, in_handle: handle.map(|value| -value), out_handle: handle }
+    }
+}
+
+/// Cross-section swept along a spline into its mesh
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum SplineProfile {
+    None,                                       // Only a path, for movers and camera paths
+    Road { width: f32, thickness: f32 },        // Slab with its top on the curve, level across
+    Pipe { radius: f32, sides: u32 },           // Tube around the curve
+    Railing { height: f32, post_spacing: f32 }, // Rail at the height above the curve, on posts
+}
+
+/// Where the curve is at a distance along it and which way it runs, with the level sideways and up axes
+/// profiles are swept in
+#[derive(Clone, Copy, Debug)]
+pub struct SplineSample {
+    pub position: Vec3,
+    pub tangent: Vec3,
+    pub side: Vec3,
+    pub up: Vec3,
+}
+
+/// Cubic Bézier spline through its points in the entity's local space, optionally swept into a mesh (roads,
+/// pipes, railings). The SplineSystem meshes it and edits its points and handles in the viewport; movers follow
+/// it through KinematicMover.path and the editor camera through `spline camera`. Distances along it are in
+/// local units
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Spline {
+    pub points: Vec<SplinePoint>,
+    pub closed: bool, // The last point joins back to the first
+    pub profile: SplineProfile,
+    pub step: f32,    // Length of the straight pieces the mesh is swept in
+}
+
+impl Spline {
+    pub fn new(points: Vec<SplinePoint>) -> Self {
+        Self { points, closed: false, profile: SplineProfile::None, step: DEFAULT_STEP }
+    }
+
+    /// Curve pieces between consecutive points (and from the last back to the first when closed)
+    pub fn segment_count(&self) -> usize {
+        match self.points.len() {
+            0 | 1 => 0,
+            count if self.closed => count,
+            count => count - 1,
+        }
+    }
+
+    /// Start, handles and end of a segment
+    pub fn controls(&self, segment: usize) -> [Vec3; 4] {
+        let start = self.points[segment];
+        let end = self.points[(segment + 1) % self.points.len()];
+        let add = |a: Vec3, b: Vec3| [a[0] + b[0], a[1] + b[1], a[2] + b[2]];
+        [start.position, add(start.position, start.out_handle), add(end.position, end.in_handle), end.position]
+    }
+
+    /// Point `t` (0..1) of the way through a segment
+    pub fn point(&self, segment: usize, t: f32) -> Vec3 {
+        let [p0, p1, p2, p3] = self.controls(segment);
+        let u = 1.0 - t;
+        [0, 1, 2].map(|i| u * u * u * p0[i] + 3.0 * u * u * t * p1[i] + 3.0 * u * t * t * p2[i] + t * t * t * p3[i])
+    }
+
+    fn derivative(&self, segment: usize, t: f32) -> Vec3 {
+        let [p0, p1, p2, p3] = self.controls(segment);
+        let u = 1.0 - t;
+        [0, 1, 2].map(|i| {
+            3.0 * u * u * (p1[i] - p0[i]) + 6.0 * u * t * (p2[i] - p1[i]) + 3.0 * t * t * (p3[i] - p2[i])
+        })
+    }
+
+    /// (segment, t, distance from the start) at SAMPLES_PER_SEGMENT steps through every segment, then the end
+    fn distance_table(&self) -> Vec<(usize, f32, f32)> {
+        let segment_count = self.segment_count();
+        let mut table = Vec::with_capacity(segment_count * SAMPLES_PER_SEGMENT + 1);
+        let mut distance = 0.0;
+        let mut previous: Option<Vec3> = None;
+        let steps = (0..segment_count)
+            .flat_map(|segment| {
+                (0..SAMPLES_PER_SEGMENT).map(move |step| (segment, step as f32 / SAMPLES_PER_SEGMENT as f32))
+            })
+            .chain(segment_count.checked_sub(1).map(|last| (last, 1.0)));
+        for (segment, t) in steps {
+            let point = self.point(segment, t);
+            if let Some(previous) = previous {
+                distance += dist2(previous, point).sqrt();
+            }
+            previous = Some(point);
+            table.push((segment, t, distance));
+        }
+        table
+    }
+
+    /// Segment and `t` at a distance along the curve, clamped to its ends
+    fn lookup(table: &[(usize, f32, f32)], distance: f32) -> Option<(usize, f32)> {
+        let (_, _, length) = *table.last()?;
+        let distance = distance.clamp(0.0, length);
+        let index = table.partition_point(|entry| entry.2 < distance);
+        if index == 0 {
+            return Some((table[0].0, table[0].1));
+        }
+        let (before, after) = (table[index - 1], table[index]);
+        let fraction = if after.2 > before.2 { (distance - before.2) / (after.2 - before.2) } else { 0.0 };
+        // The entry after a segment's last step is the next segment's start, its own end
+        let after_t = if after.0 == before.0 { after.1 } else { 1.0 };
+        Some((before.0, before.1 + (after_t - before.1) * fraction))
+    }
+
+    pub fn length(&self) -> f32 {
+        self.distance_table().last().map_or(0.0, |entry| entry.2)
+    }
+
+    /// The curve at a distance along it from the first point; None with fewer than two points
+    pub fn sample(&self, distance: f32) -> Option<SplineSample> {
+        let (segment, t) = Self::lookup(&self.distance_table(), distance)?;
+        Some(self.sample_at(segment, t))
+    }
+
+    /// `sample` in world space, through the entity's transform
+    pub fn world_sample(&self, transform: &Transform, distance: f32) -> Option<SplineSample> {
+        let sample = self.sample(distance)?;
+        let matrix = transform.compute_matrix();
+        let apply = |v: Vec3, w: f32| {
+            [0, 1, 2].map(|row| {
+                (0..3).map(|column| matrix[row * 4 + column] * v[column]).sum::<f32>() + matrix[row * 4 + 3] * w
+            })
+        };
+        Some(SplineSample {
+            position: apply(sample.position, 1.0),
+            tangent: normalize(apply(sample.tangent, 0.0)),
+            side: normalize(apply(sample.side, 0.0)),
+            up: normalize(apply(sample.up, 0.0)),
+        })
+    }
+
+    fn sample_at(&self, segment: usize, t: f32) -> SplineSample {
+        let position = self.point(segment, t);
+        let derivative = self.derivative(segment, t);
+        // A handle left on its point has no direction at that end; the chord does
+        let tangent = if len2(derivative) > 1e-10 {
+            normalize(derivative)
+        } else {
+            let [start, _, _, end] = self.controls(segment);
+            normalize([end[0] - start[0], end[1] - start[1], end[2] - start[2]])
+        };
+        let level = cross([0.0, 1.0, 0.0], tangent);
+        let side = if len2(level) > 1e-8 { normalize(level) } else { [1.0, 0.0, 0.0] };
+        SplineSample { position, tangent, side, up: cross(tangent, side) }
+    }
+
+    /// The profile swept along the curve, with end caps unless it's closed; empty for SplineProfile::None. UVs run
+    /// around the profile and along the curve, TEXTURE_REPEAT units to a repeat
+    pub fn mesh(&self) -> MeshAttributes {
+        let mut mesh = MeshAttributes {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            tex_coords: Vec::new(),
+            colors: None,
+            indices: Vec::new(),
+        };
+        let table = self.distance_table();
+        let length = table.last().map_or(0.0, |entry| entry.2);
+        if length <= 0.0 {
+            return mesh;
+        }
+        let sample = |distance: f32| Self::lookup(&table, distance).map(|(segment, t)| self.sample_at(segment, t));
+
+        let half = RAIL_THICKNESS * 0.5;
+        let (outline, smooth) = match self.profile {
+            SplineProfile::None => return mesh,
+            SplineProfile::Road { width, thickness } => {
+                let (left, right) = (-width * 0.5, width * 0.5);
+                (vec![[left, -thickness], [right, -thickness], [right, 0.0], [left, 0.0]], false)
+            }
+            SplineProfile::Pipe { radius, sides } => {
+                let sides = sides.max(3);
+                let corner = |i: u32| {
+                    let angle = i as f32 / sides as f32 * TAU;
+                    [angle.cos() * radius, angle.sin() * radius]
+                };
+                ((0..sides).map(corner).collect(), true)
+            }
+            SplineProfile::Railing { height, .. } => {
+                let (bottom, top) = (height - half, height + half);
+                (vec![[-half, bottom], [half, bottom], [half, top], [-half, top]], false)
+            }
+        };
+
+        let steps = (length / self.step.max(MIN_STEP)).ceil().max(1.0) as usize;
+        let frames: Vec<(SplineSample, f32)> = (0..=steps)
+            .filter_map(|i| {
+                let distance = length * i as f32 / steps as f32;
+                sample(distance).map(|frame| (frame, distance))
+            })
+            .collect();
+        sweep(&mut mesh, &frames, &outline, smooth, !self.closed);
+
+        if let SplineProfile::Railing { height, post_spacing } = self.profile {
+            let post = [[-half, -half], [half, -half], [half, half], [-half, half]];
+            let count = (length / post_spacing.max(MIN_STEP)).floor() as usize;
+            for i in 0..=count {
+                let distance = i as f32 * post_spacing.max(MIN_STEP);
+                // A closed curve's end is its start, which has a post already
+                if self.closed && length - distance < MIN_STEP {
+                    break;
+                }
+                let Some(frame) = sample(distance) else {
+                    continue;
+                };
+                // Swept upwards: the curve's side stays sideways and runs back along the post's up axis
+                let base = SplineSample {
+                    position: frame.position,
+                    tangent: frame.up,
+                    side: frame.side,
+                    up: frame.tangent.map(|value| -value),
+                };
+                let top = [0, 1, 2].map(|axis| frame.position[axis] + frame.up[axis] * height);
+                let top = SplineSample { position: top, ..base };
+                sweep(&mut mesh, &[(base, 0.0), (top, height)], &post, false, true);
+            }
+        }
+        mesh
+    }
+}
+
+/// Sweep a counter-clockwise outline (x along the frame's side, y along its up axis) through the frames, each
+/// with its distance along the sweep for the UVs. Smooth outlines get normals pointing away from their origin,
+/// others one normal per face
+fn sweep(mesh: &mut MeshAttributes, frames: &[(SplineSample, f32)], outline: &[[f32; 2]], smooth: bool, caps: bool) {
+    if frames.len() < 2 || outline.len() < 3 {
+        return;
+    }
+    let direction = |frame: &SplineSample, [x, y]: [f32; 2]| [0, 1, 2].map(|i| frame.side[i] * x + frame.up[i] * y);
+    let at = |frame: &SplineSample, corner: [f32; 2]| {
+        let offset = direction(frame, corner);
+        [0, 1, 2].map(|i| frame.position[i] + offset[i])
+    };
+    let normalize2 = |[x, y]: [f32; 2]| {
+        let length = (x * x + y * y).sqrt().max(1e-8);
+        [x / length, y / length]
+    };
+
+    let count = outline.len();
+    let mut around = 0.0;
+    for edge in 0..count {
+        let (a, b) = (outline[edge], outline[(edge + 1) % count]);
+        let edge_length = ((b[0] - a[0]).powi(2) + (b[1] - a[1]).powi(2)).sqrt();
+        // Outward for a counter-clockwise outline
+        let face = normalize2([b[1] - a[1], a[0] - b[0]]);
+        let (normal_a, normal_b) = if smooth { (normalize2(a), normalize2(b)) } else { (face, face) };
+
+        let first = (mesh.positions.len() / 3) as u32;
+        for (frame, distance) in frames {
+            for (corner, normal, u) in [(a, normal_a, around), (b, normal_b, around + edge_length)] {
+                mesh.positions.extend(at(frame, corner));
+                mesh.normals.extend(direction(frame, normal));
+                mesh.tex_coords.extend([u / TEXTURE_REPEAT, distance / TEXTURE_REPEAT]);
+            }
+        }
+        for ring in 0..frames.len() as u32 - 1 {
+            let [a0, b0, a1, b1] = [0, 1, 2, 3].map(|i| first + ring * 2 + i);
+            mesh.indices.extend([a0, b0, b1, a0, b1, a1]);
+        }
+        around += edge_length;
+    }
+
+    if !caps {
+        return;
+    }
+    for (frame, facing_back) in [(&frames[0].0, true), (&frames[frames.len() - 1].0, false)] {
+        let first = (mesh.positions.len() / 3) as u32;
+        let normal = if facing_back { frame.tangent.map(|value| -value) } else { frame.tangent };
+        for corner in outline {
+            mesh.positions.extend(at(frame, *corner));
+            mesh.normals.extend(normal);
+            mesh.tex_coords.extend([corner[0] / TEXTURE_REPEAT, corner[1] / TEXTURE_REPEAT]);
+        }
+        for i in 1..count as u32 - 1 {
+            if facing_back {
+                mesh.indices.extend([first, first + i + 1, first + i]);
+            } else {
+                mesh.indices.extend([first, first + i, first + i + 1]);
+            }
+        }
+    }
+}
@@ -8,7 +8,7 @@ use serde::{Serialize, Deserialize, Deserializer};
 #[derive(Serialize, Clone, Debug)]
 pub struct StaticObject3D {
     pub asset_type: Assets, // Serializable asset identifier
-    #[serde(skip)]
+    #[serde(rename = "bounds", serialize_with = "Mesh::serialize_bounds")]
     pub mesh: Mesh,
     #[serde(skip)]
     pub material: Material, // Required, no Option
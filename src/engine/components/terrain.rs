@@ -0,0 +1,206 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::utils::math::Vec3;
+
+/// Editing brushes applied in the viewport
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum TerrainBrush {
+    Raise,
+    Lower,
+    Smooth,
+}
+
+/// Square heightfield centred on the entity's Transform position (rotation and scale are ignored)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Terrain {
+    pub size: f32,              // World width/depth
+    pub resolution: u32,        // Vertices per side
+    pub chunk_cells: u32,       // Cells per side of a render chunk (culled individually)
+    pub heightmap: Option<String>, // Grayscale image, imported when `heights` doesn't match `resolution`
+    pub height_scale: f32,      // Height of a white heightmap pixel
+    pub heights: Vec<f32>,      // Row-major, resolution * resolution
+    #[serde(skip)]
+    pub revision: u64,          // Bumped on every edit so meshes and colliders can resync
+}
+
+impl Terrain {
+    pub fn new_flat(size: f32, resolution: u32) -> Self {
+        let resolution = resolution.max(2);
+        Self {
+            size,
+            resolution,
+            chunk_cells: 32,
+            heightmap: None,
+            height_scale: 10.0,
+            heights: vec![0.0; (resolution * resolution) as usize],
+            revision: 0,
+        }
+    }
+
+    /// Replace the heights with the `heightmap` image; the resolution follows the image size
+    pub fn load_heightmap(&mut self) -> EngineResult<()> {
+        let Some(path) = self.heightmap.clone() else {
+            return Err(EngineError::texture("terrain", "No heightmap set"));
+        };
+        let image = image::open(&path)
+            .map_err(|e| EngineError::texture(&path, format!("Failed to read heightmap: {}", e)))?
+            .to_luma16();
+        if image.width() != image.height() || image.width() < 2 {
+            return Err(EngineError::texture(&path, "Heightmap must be square and at least 2x2"));
+        }
+
+        self.resolution = image.width();
+        self.heights = image
+            .pixels()
+            .map(|pixel| (pixel.0[0] as f32 / u16::MAX as f32) * self.height_scale)
+            .collect();
+        self.revision += 1;
+        Ok(())
+    }
+
+    /// Make `heights` match `resolution` after scene edits (import the heightmap or reset to flat)
+    pub fn ensure_heights(&mut self) -> EngineResult<()> {
+        self.resolution = self.resolution.max(2);
+        if self.heights.len() == (self.resolution * self.resolution) as usize {
+            return Ok(());
+        }
+        if self.heightmap.is_some() {
+            return self.load_heightmap();
+        }
+        self.heights = vec![0.0; (self.resolution * self.resolution) as usize];
+        self.revision += 1;
+        Ok(())
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.size / (self.resolution - 1) as f32
+    }
+
+    pub fn height(&self, x: u32, z: u32) -> f32 {
+        self.heights[(z * self.resolution + x) as usize]
+    }
+
+    /// Local-space position of a grid vertex
+    pub fn vertex_position(&self, x: u32, z: u32) -> Vec3 {
+        let half = self.size * 0.5;
+        [x as f32 * self.cell_size() - half, self.height(x, z), z as f32 * self.cell_size() - half]
+    }
+
+    pub fn vertex_normal(&self, x: u32, z: u32) -> Vec3 {
+        let last = self.resolution - 1;
+        let left = self.height(x.saturating_sub(1), z);
+        let right = self.height((x + 1).min(last), z);
+        let down = self.height(x, z.saturating_sub(1));
+        let up = self.height(x, (z + 1).min(last));
+        let normal = [left - right, 2.0 * self.cell_size(), down - up];
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+        [normal[0] / length, normal[1] / length, normal[2] / length]
+    }
+
+    /// Bilinear height at a local x/z position, or None outside the terrain
+    pub fn sample_height(&self, local_x: f32, local_z: f32) -> Option<f32> {
+        sample_heightfield(self.size, self.resolution, &self.heights, local_x, local_z)
+    }
+
+    /// March a local-space ray across the heightfield and return the first hit
+    pub fn raycast(&self, origin: Vec3, direction: Vec3, max_distance: f32) -> Option<Vec3> {
+        let step = self.cell_size() * 0.5;
+        let mut previous = 0.0;
+        let mut distance = 0.0;
+        while distance <= max_distance {
+            let point = [
+                origin[0] + direction[0] * distance,
+                origin[1] + direction[1] * distance,
+                origin[2] + direction[2] * distance,
+            ];
+            if let Some(height) = self.sample_height(point[0], point[2]) {
+                if point[1] <= height {
+                    // Refine between the last point above and this one below the surface
+                    let (mut low, mut high) = (previous, distance);
+                    for _ in 0..8 {
+                        let mid = (low + high) * 0.5;
+                        let probe = [origin[0] + direction[0] * mid, origin[1] + direction[1] * mid, origin[2] + direction[2] * mid];
+                        match self.sample_height(probe[0], probe[2]) {
+                            Some(height) if probe[1] <= height => high = mid,
+                            _ => low = mid,
+                        }
+                    }
+                    return Some([origin[0] + direction[0] * high, origin[1] + direction[1] * high, origin[2] + direction[2] * high]);
+                }
+            }
+            previous = distance;
+            distance += step;
+        }
+        None
+    }
+
+    /// Apply a brush with smooth falloff around a local x/z position
+    pub fn apply_brush(&mut self, brush: TerrainBrush, center: [f32; 2], radius: f32, strength: f32) {
+        let half = self.size * 0.5;
+        let cell = self.cell_size();
+        let min_x = (((center[0] - radius + half) / cell).floor().max(0.0)) as u32;
+        let max_x = (((center[0] + radius + half) / cell).ceil().max(0.0) as u32).min(self.resolution - 1);
+        let min_z = (((center[1] - radius + half) / cell).floor().max(0.0)) as u32;
+        let max_z = (((center[1] + radius + half) / cell).ceil().max(0.0) as u32).min(self.resolution - 1);
+        if min_x > max_x || min_z > max_z {
+            return;
+        }
+
+        let source = self.heights.clone();
+        for z in min_z..=max_z {
+            for x in min_x..=max_x {
+                let dx = x as f32 * cell - half - center[0];
+                let dz = z as f32 * cell - half - center[1];
+                let falloff = 1.0 - (dx * dx + dz * dz).sqrt() / radius;
+                if falloff <= 0.0 {
+                    continue;
+                }
+                let weight = falloff * falloff * strength;
+                let index = (z * self.resolution + x) as usize;
+
+                self.heights[index] = match brush {
+                    TerrainBrush::Raise => source[index] + weight,
+                    TerrainBrush::Lower => source[index] - weight,
+                    TerrainBrush::Smooth => {
+                        let mut sum = 0.0;
+                        let mut count = 0.0;
+                        for nz in z.saturating_sub(1)..=(z + 1).min(self.resolution - 1) {
+                            for nx in x.saturating_sub(1)..=(x + 1).min(self.resolution - 1) {
+                                sum += source[(nz * self.resolution + nx) as usize];
+                                count += 1.0;
+                            }
+                        }
+                        let average = sum / count;
+                        source[index] + (average - source[index]) * weight.min(1.0)
+                    }
+                };
+            }
+        }
+        self.revision += 1;
+    }
+}
+
+/// Bilinear height of a row-major heightfield centred on the origin, shared with Shape::Heightfield
+pub fn sample_heightfield(size: f32, resolution: u32, heights: &[f32], local_x: f32, local_z: f32) -> Option<f32> {
+    if resolution < 2 || heights.len() < (resolution * resolution) as usize {
+        return None;
+    }
+    let half = size * 0.5;
+    let cell = size / (resolution - 1) as f32;
+    let grid_x = (local_x + half) / cell;
+    let grid_z = (local_z + half) / cell;
+    let last = (resolution - 1) as f32;
+    if !(0.0..=last).contains(&grid_x) || !(0.0..=last).contains(&grid_z) {
+        return None;
+    }
+
+    let height = |x: u32, z: u32| heights[(z * resolution + x) as usize];
+    let x0 = (grid_x.floor() as u32).min(resolution - 2);
+    let z0 = (grid_z.floor() as u32).min(resolution - 2);
+    let tx = grid_x - x0 as f32;
+    let tz = grid_z - z0 as f32;
+    let top = height(x0, z0) * (1.0 - tx) + height(x0 + 1, z0) * tx;
+    let bottom = height(x0, z0 + 1) * (1.0 - tx) + height(x0 + 1, z0 + 1) * tx;
+    Some(top * (1.0 - tz) + bottom * tz)
+}
@@ -0,0 +1,93 @@
+use serde::{ Serialize, Deserialize };
+
+/// Light and sky values fed to the renderer each frame
+#[derive(Clone, Copy, Debug)]
+pub struct Lighting {
+    pub light_direction: [f32; 3], // Direction the light travels (towards the scene)
+    pub light_color: [f32; 3],
+    pub ambient: f32,
+    pub sky_color: [f32; 3],
+    pub fog_density: f32, // Exponential squared distance fog towards sky_color, 0 for none
+}
+
+impl Default for Lighting {
+    /// The fixed top-down light used when the scene has no TimeOfDay
+    fn default() -> Self {
+        Self {
+            light_direction: [0.0, -1.0, 0.0],
+            light_color: [1.0, 1.0, 1.0],
+            ambient: 0.2,
+            sky_color: [0.1, 0.1, 0.1],
+            fog_density: 0.0,
+        }
+    }
+}
+
+/// Scene-wide day/night clock; the first TimeOfDay in the world drives the lighting
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimeOfDay {
+    pub hour: f32,       // 0..24, 12 is noon
+    pub day_length: f32, // Real seconds for a full 24h cycle
+    pub running: bool,
+}
+
+const NIGHT_SKY: [f32; 3] = [0.02, 0.02, 0.06];
+const SUNSET_SKY: [f32; 3] = [0.75, 0.4, 0.25];
+const DAY_SKY: [f32; 3] = [0.45, 0.65, 0.9];
+const MOON_LIGHT: [f32; 3] = [0.25, 0.3, 0.5];
+const SUNSET_LIGHT: [f32; 3] = [1.0, 0.55, 0.3];
+const NOON_LIGHT: [f32; 3] = [1.0, 0.97, 0.9];
+
+impl TimeOfDay {
+    pub fn new(hour: f32, day_length: f32) -> Self {
+        Self { hour: hour.rem_euclid(24.0), day_length, running: true }
+    }
+
+    /// Advance the clock by `delta_time` real seconds
+    pub fn advance(&mut self, delta_time: f32) {
+        if self.running && self.day_length > 0.0 {
+            self.hour = (self.hour + (delta_time / self.day_length) * 24.0).rem_euclid(24.0);
+        }
+    }
+
+    pub fn lighting(&self) -> Lighting {
+        // Sun rises in +x at 06:00, peaks at noon and sets in -x at 18:00
+        let angle = ((self.hour - 6.0) / 24.0) * std::f32::consts::TAU;
+        let elevation = angle.sin();
+        let sun_position = normalize([angle.cos(), elevation, 0.3]);
+
+        let daylight = smoothstep(-0.1, 0.15, elevation);
+        let noon = smoothstep(0.0, 0.5, elevation);
+
+        // The moon lights the scene from the opposite side at night
+        let light_source = if elevation >= 0.0 {
+            sun_position
+        } else {
+            [-sun_position[0], -sun_position[1], sun_position[2]]
+        };
+        let sun_color = mix(SUNSET_LIGHT, NOON_LIGHT, noon);
+        let sky_day = mix(SUNSET_SKY, DAY_SKY, noon);
+
+        Lighting {
+            light_direction: [-light_source[0], -light_source[1], -light_source[2]],
+            light_color: mix(MOON_LIGHT, sun_color, daylight),
+            ambient: 0.08 + 0.17 * daylight,
+            sky_color: mix(NIGHT_SKY, sky_day, daylight),
+            fog_density: 0.0,
+        }
+    }
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn mix(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t, a[2] + (b[2] - a[2]) * t]
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    [v[0] / length, v[1] / length, v[2] / length]
+}
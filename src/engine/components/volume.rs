@@ -0,0 +1,127 @@
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::{ Shape, Transform };
+use crate::index::engine::utils::math::{ dist2, Vec3 };
+
+/// Region a Volume covers, in the entity's local space around its position
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum VolumeShape {
+    Box { half_extents: Vec3 },
+    Sphere { radius: f32 },
+}
+
+impl VolumeShape {
+    /// Collider shape of the same size, for the editor wireframe
+    pub fn wireframe(&self) -> Shape {
+        match *self {
+            VolumeShape::Box { half_extents } => Shape::Box { half_extents },
+            VolumeShape::Sphere { radius } => Shape::Sphere { radius },
+        }
+    }
+}
+
+/// Settings a volume overrides while the camera is in it; unset ones stay as the scene and other volumes have them
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+#[serde(default)]
+pub struct VolumeSettings {
+    pub exposure: Option<f32>,         // Color grading, see ColorGrading
+    pub contrast: Option<f32>,
+    pub saturation: Option<f32>,
+    pub fog_density: Option<f32>,
+    pub gravity: Option<f32>,          // Downward acceleration in units/s²
+    pub reverb: Option<f32>,           // Wet mix of the audio reverb, 0 dry to 1
+    pub ambient_sound: Option<String>, // Looped sound for the audio mixer
+}
+
+/// Box or sphere that changes post-processing, fog, gravity and audio settings while the player camera is inside,
+/// fading them in over `blend_distance` outside its edge. Where volumes overlap, higher priorities are applied
+/// over lower ones; the VolumeSystem blends them every frame
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Volume {
+    pub shape: VolumeShape,
+    pub blend_distance: f32, // World units outside the shape over which the settings fade out; 0 for a hard edge
+    pub priority: i32,
+    pub settings: VolumeSettings,
+}
+
+impl Volume {
+    pub fn new(shape: VolumeShape) -> Self {
+        Self { shape, blend_distance: 2.0, priority: 0, settings: VolumeSettings::default() }
+    }
+
+    /// How far outside the shape a world point is (0 inside), through the entity's transform
+    pub fn distance(&self, transform: &Transform, point: Vec3) -> f32 {
+        let position = transform.get_position();
+        let scale = transform.get_scale();
+        match self.shape {
+            VolumeShape::Sphere { radius } => {
+                let largest_scale = scale.iter().fold(0.0f32, |largest, axis| largest.max(axis.abs()));
+                (dist2(point, position).sqrt() - radius * largest_scale).max(0.0)
+            }
+            VolumeShape::Box { half_extents } => {
+                // Into the box's unscaled axes by the inverse rotation (the transpose)
+                let mut rotation = transform.clone();
+                rotation.set_position(0.0, 0.0, 0.0);
+                rotation.set_scale(1.0, 1.0, 1.0);
+                let rotation = rotation.compute_matrix();
+                let offset = [0, 1, 2].map(|axis| point[axis] - position[axis]);
+                let outside = [0, 1, 2].map(|axis| {
+                    let along = (0..3).map(|row| rotation[row * 4 + axis] * offset[row]).sum::<f32>();
+                    (along.abs() - half_extents[axis] * scale[axis].abs()).max(0.0)
+                });
+                dist2(outside, [0.0; 3]).sqrt()
+            }
+        }
+    }
+
+    /// How strongly the settings apply at a world point: 1 inside, fading to 0 at blend_distance outside
+    pub fn weight(&self, transform: &Transform, point: Vec3) -> f32 {
+        let distance = self.distance(transform, point);
+        if distance <= 0.0 {
+            1.0
+        } else if self.blend_distance > 0.0 {
+            (1.0 - distance / self.blend_distance).max(0.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// What the volumes around the camera make of one setting: `base * keep + add`, so each system applies them to its
+/// own value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VolumeBlend {
+    keep: f32,
+    add: f32,
+}
+
+impl VolumeBlend {
+    pub const NONE: Self = Self { keep: 1.0, add: 0.0 };
+
+    /// Blend towards `target` by `weight`, over what's blended so far
+    pub fn towards(self, target: Option<f32>, weight: f32) -> Self {
+        match target {
+            Some(target) => Self { keep: self.keep * (1.0 - weight), add: self.add * (1.0 - weight) + target * weight },
+            None => self,
+        }
+    }
+
+    pub fn apply(&self, base: f32) -> f32 {
+        base * self.keep + self.add
+    }
+
+    /// Share of the setting the volumes decide, 0 to 1
+    pub fn weight(&self) -> f32 {
+        1.0 - self.keep
+    }
+
+    /// The volumes' own value for the setting, as it would be fully inside them
+    pub fn value(&self) -> f32 {
+        if self.weight() > 0.0 { self.add / self.weight() } else { 0.0 }
+    }
+
+    /// Whether any volume changes the setting
+    pub fn is_active(&self) -> bool {
+        *self != Self::NONE
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{ Serialize, Deserialize };
+
+/// Flat animated water plane centred on the entity's Transform position, facing +y
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Water {
+    pub size: [f32; 2],      // World width (x) and depth (z)
+    pub color: [f32; 3],     // Deep water tint seen when looking straight down
+    pub wave_scale: f32,     // Strength of the animated normal perturbation
+    pub wave_speed: f32,     // Animation speed multiplier
+    pub reflectivity: f32,   // 0..1, scales the fresnel reflection
+    #[serde(default = "opaque")]
+    pub opacity: f32,        // 0..1 where reflections are weak; below 1 the water is drawn by the transparency pass
+    #[serde(skip)]
+    pub time: f32,           // Animation clock, advanced by the simulation so it pauses with it
+}
+
+impl Water {
+    pub fn new(width: f32, depth: f32) -> Self {
+        Self {
+            size: [width, depth],
+            color: [0.05, 0.2, 0.3],
+            wave_scale: 0.3,
+            wave_speed: 1.0,
+            reflectivity: 0.8,
+            opacity: 1.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn is_transparent(&self) -> bool {
+        self.opacity < 1.0
+    }
+
+    pub fn advance(&mut self, delta_time: f32) {
+        // Wrap to keep the shader's sine inputs precise over long sessions
+        self.time = (self.time + delta_time * self.wave_speed) % 1000.0;
+    }
+}
+
+fn opaque() -> f32 {
+    1.0
+}
@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::index::engine::modules::{ EngineError, EngineResult };
+
+/// First bytes of every pack file
+const PACK_MAGIC: &[u8; 4] = b"WCPK";
+const PACK_VERSION: u32 = 1;
+/// Deflate level used when packing with compression (miniz scale, 0-10)
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Pack read by every asset and scene load once the project mounts it
+static MOUNTED_PACK: RwLock<Option<AssetPack>> = RwLock::new(None);
+
+/// Where an entry's bytes sit in the pack
+#[derive(Debug, Clone, Copy)]
+struct PackEntry {
+    offset: u64,
+    stored_size: u64, // Bytes in the pack (deflated size when compressed)
+    size: u64,
+    compressed: bool,
+}
+
+/// Archive of asset files with an offset index:
+/// `WCPK`, version, entry count, then per entry path length (u16), path, offset, stored size, size (u64) and
+/// a compression flag (u8), followed by the entry data. Integers are little endian and offsets count from
+/// the start of the file. The whole pack is held in memory so the same reader works on wasm, where it
+/// arrives as fetched bytes instead of a file.
+#[derive(Debug)]
+pub struct AssetPack {
+    data: Vec<u8>,
+    entries: BTreeMap<String, PackEntry>,
+}
+
+impl AssetPack {
+    /// Write `files` (pack path and contents) to a pack; with `compress` each entry is deflated if that saves space
+    pub fn write(path: &str, files: &[(String, Vec<u8>)], compress: bool) -> EngineResult<()> {
+        let stored: Vec<(String, Vec<u8>, u64, bool)> = files
+            .iter()
+            .map(|(name, contents)| {
+                let deflated = compress.then(|| miniz_oxide::deflate::compress_to_vec(contents, COMPRESSION_LEVEL));
+                match deflated {
+                    Some(deflated) if deflated.len() < contents.len() => (Self::normalize(name), deflated, contents.len() as u64, true),
+                    _ => (Self::normalize(name), contents.clone(), contents.len() as u64, false),
+                }
+            })
+            .collect();
+
+        let index_size: usize = stored.iter().map(|(name, ..)| 2 + name.len() + 8 * 3 + 1).sum();
+        let mut offset = (PACK_MAGIC.len() + 4 + 4 + index_size) as u64;
+        let mut pack = Vec::new();
+        pack.extend_from_slice(PACK_MAGIC);
+        pack.extend_from_slice(&PACK_VERSION.to_le_bytes());
+        pack.extend_from_slice(&(stored.len() as u32).to_le_bytes());
+        for (name, bytes, size, compressed) in &stored {
+            let name_length = u16::try_from(name.len()).map_err(|_| EngineError::asset(name, "Pack path is too long"))?;
+            pack.extend_from_slice(&name_length.to_le_bytes());
+            pack.extend_from_slice(name.as_bytes());
+            pack.extend_from_slice(&offset.to_le_bytes());
+            pack.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            pack.extend_from_slice(&size.to_le_bytes());
+            pack.push(*compressed as u8);
+            offset += bytes.len() as u64;
+        }
+        for (_, bytes, ..) in &stored {
+            pack.extend_from_slice(bytes);
+        }
+
+        std::fs::write(path, &pack).map_err(|e| EngineError::io(path, e.to_string()))?;
+        println!("📦 Packed {} files into {} ({} bytes)", stored.len(), path, pack.len());
+        Ok(())
+    }
+
+    /// Read a pack file from disk
+    pub fn open(path: &str) -> EngineResult<Self> {
+        let data = std::fs::read(path).map_err(|e| EngineError::io(path, e.to_string()))?;
+        Self::from_bytes(data).map_err(|message| EngineError::asset(path, message))
+    }
+
+    /// Parse a pack already in memory (a fetched file on wasm)
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, String> {
+        let mut cursor = 0usize;
+        let mut take = |length: usize| -> Result<&[u8], String> {
+            let bytes = data.get(cursor..cursor + length).ok_or("Pack index is truncated")?;
+            cursor += length;
+            Ok(bytes)
+        };
+
+        if take(4)? != PACK_MAGIC {
+            return Err("Not an asset pack".to_string());
+        }
+        let version = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        if version > PACK_VERSION {
+            return Err(format!("Pack version {} is newer than {}", version, PACK_VERSION));
+        }
+        let count = u32::from_le_bytes(take(4)?.try_into().unwrap());
+
+        let mut entries = BTreeMap::new();
+        for _ in 0..count {
+            let name_length = u16::from_le_bytes(take(2)?.try_into().unwrap()) as usize;
+            let name = String::from_utf8(take(name_length)?.to_vec()).map_err(|_| "Pack path isn't UTF-8")?;
+            let offset = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let stored_size = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let size = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let compressed = take(1)?[0] != 0;
+            entries.insert(name, PackEntry { offset, stored_size, size, compressed });
+        }
+
+        let end = entries.values().map(|entry| entry.offset + entry.stored_size).max().unwrap_or(0);
+        if end > data.len() as u64 {
+            return Err("Pack data is truncated".to_string());
+        }
+        Ok(Self { data, entries })
+    }
+
+    /// Contents of a packed file
+    pub fn read(&self, name: &str) -> Option<Result<Vec<u8>, String>> {
+        let entry = self.entries.get(&Self::normalize(name))?;
+        let stored = &self.data[entry.offset as usize..(entry.offset + entry.stored_size) as usize];
+        if !entry.compressed {
+            return Some(Ok(stored.to_vec()));
+        }
+        Some(
+            miniz_oxide::inflate
+                ::decompress_to_vec_with_limit(stored, entry.size as usize)
+                .map_err(|e| format!("Failed to inflate {}: {:?}", name, e.status))
+        )
+    }
+
+    /// Packed paths under a directory (non-recursive)
+    pub fn list(&self, directory: &str) -> Vec<String> {
+        let prefix = format!("{}/", Self::normalize(directory).trim_end_matches('/'));
+        self.entries
+            .keys()
+            .filter(|name| name.strip_prefix(&prefix).is_some_and(|rest| !rest.contains('/')))
+            .cloned()
+            .collect()
+    }
+
+    /// Pack paths use forward slashes without a leading `./`
+    fn normalize(name: &str) -> String {
+        let name = name.replace('\\', "/");
+        name.trim_start_matches("./").to_string()
+    }
+
+    /// Make later loads read from this pack before the filesystem
+    pub fn mount(pack: AssetPack) {
+        println!("📦 Mounted asset pack with {} files", pack.entries.len());
+        *MOUNTED_PACK.write().unwrap() = Some(pack);
+    }
+
+    /// Read a file from the mounted pack, or from disk when it isn't packed
+    pub fn read_file(path: impl AsRef<Path>) -> EngineResult<Vec<u8>> {
+        let path = path.as_ref().to_string_lossy().to_string();
+        if let Some(result) = MOUNTED_PACK.read().unwrap().as_ref().and_then(|pack| pack.read(&path)) {
+            return result.map_err(|message| EngineError::asset(&path, message));
+        }
+        std::fs::read(&path).map_err(|e| EngineError::io(&path, e.to_string()))
+    }
+
+    /// Text file from the mounted pack or disk
+    pub fn read_text(path: impl AsRef<Path>) -> EngineResult<String> {
+        let path_text = path.as_ref().to_string_lossy().to_string();
+        let bytes = Self::read_file(path)?;
+        String::from_utf8(bytes).map_err(|_| EngineError::asset(&path_text, "File isn't UTF-8"))
+    }
+
+    /// Files in a directory of the mounted pack, if a pack is mounted
+    pub fn list_mounted(directory: &str) -> Option<Vec<String>> {
+        MOUNTED_PACK.read().unwrap().as_ref().map(|pack| pack.list(directory))
+    }
+}
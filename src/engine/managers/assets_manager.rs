@@ -1,30 +1,128 @@
-use std::collections::HashMap;
+use std::collections::{ HashMap, HashSet };
 use std::cell::RefCell;
-use glow::HasContext;
+use std::sync::Mutex;
+use std::time::{ Duration, Instant, SystemTime };
+use once_cell::sync::Lazy;
 
 // Import required components - using the new module structure
 use crate::index::engine::components::{ StaticObject3DComponent, AnimatedObject3DComponent };
-use crate::index::engine::components::SharedComponents::{ Transform };
+use crate::index::engine::components::SharedComponents::{ Transform, Mesh, Material };
 use crate::index::engine::utils::gltf_loader_utils::*;
+use crate::index::engine::managers::asset_pack::AssetPack;
+use crate::index::engine::managers::PrefabLibrary;
+use crate::index::engine::modules::{
+    Console,
+    EngineError,
+    EngineResult,
+    EntityPool,
+    FindReplace,
+    GraphicsQuality,
+    PlaySession,
+    Project,
+    TaskSystem,
+};
+use crate::index::engine::modules::task_system::TaskId;
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::modules::ecs::{ insert, Component, World };
+use crate::index::engine::rhi::{ Pipeline, Texture };
+use crate::index::game::systems::gpu_skinning_system::{ MAX_JOINTS, MAX_MORPH_TARGETS, MORPH_ROW };
+use crate::query_get_all;
+
+/// Default for the `shader_hot_reload` cvar; off, as it reads the shader directory every second
+pub const SHADER_HOT_RELOAD: f32 = 0.0;
+/// Where shader hot reload reads the sources from: the tree the binary was built from. Desktop only, wasm builds
+/// have no file system to watch
+const SHADER_DIRECTORY: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/assets/shaders");
+/// How often shader hot reload looks at the shader directory for changes
+const SHADER_HOT_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A shader file's name in assets/shaders and its source as embedded at build time
+type ShaderFile = (&'static str, &'static str);
+
+macro_rules! shader_file {
+    ($file:literal) => {
+        ($file, include_str!(concat!("../../assets/shaders/", $file)))
+    };
+}
+
+/// What a program was compiled from, so hot reload can compile it again
+struct ShaderBuild {
+    name: &'static str,
+    vertex: ShaderFile,
+    fragment: ShaderFile,
+    defines: &'static [(&'static str, &'static str)],
+}
+
+/// The shader directory as hot reload last saw it
+#[derive(Default)]
+struct ShaderWatch {
+    last_check: Option<Instant>,
+    modified: HashMap<String, SystemTime>, // File name to its modification time
+    sources: HashMap<String, String>,      // Files read back after they changed, compiled instead of the embedded ones
+}
+
+static SHADER_WATCH: Lazy<Mutex<ShaderWatch>> = Lazy::new(|| Mutex::new(ShaderWatch::default()));
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Assets {
     TestingDoll,
     Chair,
     BlockoutPlatform,
+    Imported(u64), // Registered at runtime (e.g. `--import model.gltf`), keyed by its path
+}
+
+impl Assets {
+    /// Id of the model imported from `path`: a hash of the path, so scenes saved with it find the same model
+    /// whatever order the imports run in
+    pub fn imported(path: &str) -> Self {
+        let path = std::path::Path::new(path)
+            .components()
+            .filter(|component| !matches!(component, std::path::Component::CurDir))
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let hash = path.bytes().fold(0xcbf29ce484222325u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+        Assets::Imported(hash)
+    }
+}
+
+/// A .gltf or .glb file and everything it references, read off the main thread and waiting for upload
+pub struct GltfSource {
+    path: String,
+    gltf: gltf::Gltf,
+    buffers: Vec<gltf::buffer::Data>,
+    png_data: Vec<u8>,
 }
 
 pub struct AssetsManager {
     static_assets: HashMap<Assets, StaticObject3DComponent>,
     animated_assets: HashMap<Assets, AnimatedObject3DComponent>,
-    static_shader_program: Option<glow::Program>,
-    animated_shader_program: Option<glow::Program>,
-    static_outline_shader_program: Option<glow::Program>,
-    animated_outline_shader_program: Option<glow::Program>,
-    box_shader_program: Option<glow::Program>,
-    sphere_shader_program: Option<glow::Program>,
-    capsule_shader_program: Option<glow::Program>,
-    cylinder_shader_program: Option<glow::Program>,
+    static_shader_program: Option<Pipeline>,
+    animated_shader_program: Option<Pipeline>,
+    static_outline_shader_program: Option<Pipeline>,
+    animated_outline_shader_program: Option<Pipeline>,
+    box_shader_program: Option<Pipeline>,
+    sphere_shader_program: Option<Pipeline>,
+    capsule_shader_program: Option<Pipeline>,
+    cylinder_shader_program: Option<Pipeline>,
+    wireframe_shader_program: Option<Pipeline>,
+    terrain_shader_program: Option<Pipeline>,
+    water_shader_program: Option<Pipeline>,
+    impostor_shader_program: Option<Pipeline>,
+    transparent_shader_program: Option<Pipeline>,
+    oit_composite_shader_program: Option<Pipeline>,
+    ssao_shader_program: Option<Pipeline>,
+    ssao_composite_shader_program: Option<Pipeline>,
+    depth_only_shader_program: Option<Pipeline>,
+    exposure_adapt_shader_program: Option<Pipeline>,
+    color_grading_shader_program: Option<Pipeline>,
+    fxaa_shader_program: Option<Pipeline>,
+    placeholder_shader_program: Option<Pipeline>,
+    placeholder_mesh: Mesh,
+    shader_builds: Vec<ShaderBuild>,
+    import_paths: HashMap<Assets, String>, // Imports started, to the path each was read from
     initialized: bool,
 }
 
@@ -41,72 +139,174 @@ impl AssetsManager {
             sphere_shader_program: None,
             capsule_shader_program: None,
             cylinder_shader_program: None,
+            wireframe_shader_program: None,
+            terrain_shader_program: None,
+            water_shader_program: None,
+            impostor_shader_program: None,
+            transparent_shader_program: None,
+            oit_composite_shader_program: None,
+            ssao_shader_program: None,
+            ssao_composite_shader_program: None,
+            depth_only_shader_program: None,
+            exposure_adapt_shader_program: None,
+            color_grading_shader_program: None,
+            fxaa_shader_program: None,
+            placeholder_shader_program: None,
+            placeholder_mesh: Mesh::default(),
+            shader_builds: Vec::new(),
+            import_paths: HashMap::new(),
             initialized: false,
         }
     }
 
-    fn initialize_asset_manager(&mut self, gl: &glow::Context) {
+    fn initialize_asset_manager(&mut self, gl: &glow::Context) -> EngineResult<()> {
         if self.initialized {
             println!("⚠️  AssetsManager already initialized");
-            return;
+            return Ok(());
         }
 
         println!("🔄 Initializing AssetsManager and loading all assets...");
 
+        // Placeholders substitute for anything that fails below, so they are the only hard requirement
+        self.placeholder_shader_program = Some(
+            Pipeline::new(
+                gl,
+                "placeholder",
+                include_str!("../../assets/shaders/vertex_placeholder.glsl"),
+                include_str!("../../assets/shaders/fragment_placeholder.glsl")
+            )?
+        );
+        self.placeholder_mesh = create_placeholder_cube_mesh(gl)?;
+
         // Create shader programs first
-        let static_shader = create_shader_program(
+        let static_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_static.glsl"),
-            include_str!("../../assets/shaders/fragment_static.glsl"),
+            shader_file!("vertex_static.glsl"),
+            shader_file!("fragment_static.glsl"),
             "static"
         );
-        let animated_shader = create_shader_program(
+        let animated_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_animated.glsl"),
-            include_str!("../../assets/shaders/fragment_animated.glsl"),
+            shader_file!("vertex_animated.glsl"),
+            shader_file!("fragment_animated.glsl"),
             "animated"
         );
 
         // Create outline shader programs
-        let static_outline_shader = create_shader_program(
+        let static_outline_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_outline_static.glsl"),
-            include_str!("../../assets/shaders/fragment_outline.glsl"),
+            shader_file!("vertex_outline_static.glsl"),
+            shader_file!("fragment_outline.glsl"),
             "static_outline"
         );
-        let animated_outline_shader = create_shader_program(
+        let animated_outline_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_outline_animated.glsl"),
-            include_str!("../../assets/shaders/fragment_outline.glsl"),
+            shader_file!("vertex_outline_animated.glsl"),
+            shader_file!("fragment_outline.glsl"),
             "animated_outline"
         );
 
         // Create shape-specific shader programs
-        let box_shader = create_shader_program(
+        let box_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_box.glsl"),
-            include_str!("../../assets/shaders/fragment_box.glsl"),
+            shader_file!("vertex_box.glsl"),
+            shader_file!("fragment_box.glsl"),
             "box"
         );
-        let sphere_shader = create_shader_program(
+        let sphere_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_sphere.glsl"),
-            include_str!("../../assets/shaders/fragment_sphere.glsl"),
+            shader_file!("vertex_sphere.glsl"),
+            shader_file!("fragment_sphere.glsl"),
             "sphere"
         );
-        let capsule_shader = create_shader_program(
+        let capsule_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_capsule.glsl"),
-            include_str!("../../assets/shaders/fragment_capsule.glsl"),
+            shader_file!("vertex_capsule.glsl"),
+            shader_file!("fragment_capsule.glsl"),
             "capsule"
         );
-        let cylinder_shader = create_shader_program(
+        let cylinder_shader = self.shader_or_placeholder(
             gl,
-            include_str!("../../assets/shaders/vertex_cylinder.glsl"),
-            include_str!("../../assets/shaders/fragment_cylinder.glsl"),
+            shader_file!("vertex_cylinder.glsl"),
+            shader_file!("fragment_cylinder.glsl"),
             "cylinder"
         );
 
+        // Debug line shader (navmesh visualization)
+        let wireframe_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_wireframe.glsl"),
+            shader_file!("fragment_wireframe.glsl"),
+            "wireframe"
+        );
+        let terrain_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_terrain.glsl"),
+            shader_file!("fragment_terrain.glsl"),
+            "terrain"
+        );
+        let water_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_water.glsl"),
+            shader_file!("fragment_water.glsl"),
+            "water"
+        );
+        let impostor_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_impostor.glsl"),
+            shader_file!("fragment_impostor.glsl"),
+            "impostor"
+        );
+        let transparent_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_static.glsl"),
+            shader_file!("fragment_transparent.glsl"),
+            "transparent"
+        );
+        let oit_composite_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_fullscreen.glsl"),
+            shader_file!("fragment_oit_composite.glsl"),
+            "oit_composite"
+        );
+        let ssao_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_fullscreen.glsl"),
+            shader_file!("fragment_ssao.glsl"),
+            "ssao"
+        );
+        let ssao_composite_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_fullscreen.glsl"),
+            shader_file!("fragment_ssao_composite.glsl"),
+            "ssao_composite"
+        );
+        let depth_only_shader = self.shader_variant_or_placeholder(
+            gl,
+            shader_file!("vertex_static.glsl"),
+            shader_file!("fragment_depth_only.glsl"),
+            "depth_only",
+            &[("DEPTH_ONLY", "1")]
+        );
+        let exposure_adapt_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_fullscreen.glsl"),
+            shader_file!("fragment_exposure_adapt.glsl"),
+            "exposure_adapt"
+        );
+        let color_grading_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_fullscreen.glsl"),
+            shader_file!("fragment_color_grading.glsl"),
+            "color_grading"
+        );
+        let fxaa_shader = self.shader_or_placeholder(
+            gl,
+            shader_file!("vertex_fullscreen.glsl"),
+            shader_file!("fragment_fxaa.glsl"),
+            "fxaa"
+        );
+
         self.static_shader_program = Some(static_shader);
         self.animated_shader_program = Some(animated_shader);
         self.static_outline_shader_program = Some(static_outline_shader);
@@ -115,9 +315,21 @@ impl AssetsManager {
         self.sphere_shader_program = Some(sphere_shader);
         self.capsule_shader_program = Some(capsule_shader);
         self.cylinder_shader_program = Some(cylinder_shader);
+        self.wireframe_shader_program = Some(wireframe_shader);
+        self.terrain_shader_program = Some(terrain_shader);
+        self.water_shader_program = Some(water_shader);
+        self.impostor_shader_program = Some(impostor_shader);
+        self.transparent_shader_program = Some(transparent_shader);
+        self.oit_composite_shader_program = Some(oit_composite_shader);
+        self.ssao_shader_program = Some(ssao_shader);
+        self.ssao_composite_shader_program = Some(ssao_composite_shader);
+        self.depth_only_shader_program = Some(depth_only_shader);
+        self.exposure_adapt_shader_program = Some(exposure_adapt_shader);
+        self.color_grading_shader_program = Some(color_grading_shader);
+        self.fxaa_shader_program = Some(fxaa_shader);
 
         // Load animated asset (TestingDoll)
-        self.load_animated_gltf(
+        let testing_doll = Self::load_animated_gltf(
             include_str!("../../assets/meshes/guy.gltf"),
             include_bytes!("../../assets/meshes/guy.bin"),
             include_bytes!("../../assets/textures/Material Base Color.png"),
@@ -125,9 +337,10 @@ impl AssetsManager {
             animated_shader,
             gl
         );
+        self.register_animated(Assets::TestingDoll, testing_doll);
 
         // Load static asset (Chair)
-        self.load_static_gltf(
+        let chair = Self::load_static_gltf(
             include_str!("../../assets/meshes/chair.gltf"),
             include_bytes!("../../assets/meshes/chair.bin"),
             include_bytes!("../../assets/textures/wood-texture.png"),
@@ -135,8 +348,9 @@ impl AssetsManager {
             static_shader,
             gl
         );
+        self.register_static(Assets::Chair, chair);
 
-        self.load_static_gltf(
+        let blockout_platform = Self::load_static_gltf(
             include_str!("../../assets/meshes/blockout_platform.gltf"),
             include_bytes!("../../assets/meshes/blockout_platform.bin"),
             include_bytes!("../../assets/textures/orange-blueprint.png"),
@@ -144,66 +358,374 @@ impl AssetsManager {
             static_shader,
             gl
         );
+        self.register_static(Assets::BlockoutPlatform, blockout_platform);
 
         self.initialized = true;
         let total_assets = self.static_assets.len() + self.animated_assets.len();
         println!("✅ AssetsManager initialization complete. Loaded {} assets.", total_assets);
+        Ok(())
+    }
+
+    /// Compile a shader program, falling back to the magenta placeholder shader on failure
+    fn shader_or_placeholder(
+        &mut self,
+        gl: &glow::Context,
+        vertex_shader: ShaderFile,
+        fragment_shader: ShaderFile,
+        program_name: &'static str
+    ) -> Pipeline {
+        self.shader_variant_or_placeholder(gl, vertex_shader, fragment_shader, program_name, &[])
+    }
+
+    /// `shader_or_placeholder` with feature flags `#define`d in both stages
+    fn shader_variant_or_placeholder(
+        &mut self,
+        gl: &glow::Context,
+        vertex_shader: ShaderFile,
+        fragment_shader: ShaderFile,
+        program_name: &'static str,
+        defines: &'static [(&'static str, &'static str)]
+    ) -> Pipeline {
+        let build = ShaderBuild { name: program_name, vertex: vertex_shader, fragment: fragment_shader, defines };
+        let pipeline = build.compile(gl);
+        self.shader_builds.push(build);
+        match pipeline {
+            Ok(pipeline) => pipeline,
+            Err(e) => {
+                InterfaceSystem::report_error(&e);
+                // A placeholder program of its own, so hot reload can tell where it stands in for this one and swap
+                // the fixed program in; the shared placeholder if even that fails
+                Pipeline::new(
+                    gl,
+                    "placeholder",
+                    include_str!("../../assets/shaders/vertex_placeholder.glsl"),
+                    include_str!("../../assets/shaders/fragment_placeholder.glsl")
+                ).unwrap_or_else(|_| self.placeholder_material().pipeline)
+            }
+        }
+    }
+
+    /// Each program's name and where it's kept, for hot reload to swap
+    fn shader_programs(&mut self) -> [(&'static str, &mut Option<Pipeline>); 20] {
+        [
+            ("static", &mut self.static_shader_program),
+            ("animated", &mut self.animated_shader_program),
+            ("static_outline", &mut self.static_outline_shader_program),
+            ("animated_outline", &mut self.animated_outline_shader_program),
+            ("box", &mut self.box_shader_program),
+            ("sphere", &mut self.sphere_shader_program),
+            ("capsule", &mut self.capsule_shader_program),
+            ("cylinder", &mut self.cylinder_shader_program),
+            ("wireframe", &mut self.wireframe_shader_program),
+            ("terrain", &mut self.terrain_shader_program),
+            ("water", &mut self.water_shader_program),
+            ("impostor", &mut self.impostor_shader_program),
+            ("transparent", &mut self.transparent_shader_program),
+            ("oit_composite", &mut self.oit_composite_shader_program),
+            ("ssao", &mut self.ssao_shader_program),
+            ("ssao_composite", &mut self.ssao_composite_shader_program),
+            ("depth_only", &mut self.depth_only_shader_program),
+            ("exposure_adapt", &mut self.exposure_adapt_shader_program),
+            ("color_grading", &mut self.color_grading_shader_program),
+            ("fxaa", &mut self.fxaa_shader_program),
+        ]
+    }
+
+    /// Recompile the programs built from changed files (all of them when an include chunk changed) and swap them
+    /// into the cached assets and the world's meshes, deleting the programs they replace. A program that failed at
+    /// startup is swapped in where its placeholder stood. A program that fails keeps its previous one, with the
+    /// compile log in the shader error overlay until it's fixed
+    fn reload_shaders(&mut self, gl: &glow::Context, changed: &[String]) {
+        let changed_include = SHADER_INCLUDES
+            .iter()
+            .any(|(chunk_name, _)| changed.iter().any(|file| file == chunk_name));
+        let uses_changed = |build: &ShaderBuild| {
+            changed_include || changed.iter().any(|file| file == build.vertex.0 || file == build.fragment.0)
+        };
+        let rebuilt: Vec<(&'static str, EngineResult<Pipeline>)> = self.shader_builds
+            .iter()
+            .filter(|build| uses_changed(build))
+            .map(|build| (build.name, build.compile(gl)))
+            .collect();
+
+        let placeholder = self.placeholder_shader_program;
+        for (name, pipeline) in rebuilt {
+            let pipeline = match pipeline {
+                Ok(pipeline) => pipeline,
+                Err(e) => {
+                    InterfaceSystem::report_error(&e);
+                    continue;
+                }
+            };
+            let programs = self.shader_programs();
+            let Some((_, slot)) = programs.into_iter().find(|(program_name, _)| *program_name == name) else {
+                continue;
+            };
+            let previous = slot.replace(pipeline);
+            // Only when its own placeholder couldn't be compiled does a program share the placeholder with others
+            if let Some(previous) = previous.filter(|previous| Some(*previous) != placeholder) {
+                self.swap_pipeline(previous, pipeline);
+                previous.delete(gl);
+            }
+            println!("🔄 Reloaded shader '{}'", name);
+        }
+    }
+
+    /// Point the materials using `previous` at `pipeline`: the cached assets', the world's, and every other holder of
+    /// components (prefab templates, submitted commands, the EntityPool, the PlaySession and Find & Replace's undo),
+    /// since `previous` is deleted afterwards
+    fn swap_pipeline(&mut self, previous: Pipeline, pipeline: Pipeline) {
+        let materials = self.static_assets
+            .values_mut()
+            .map(|object| &mut object.material)
+            .chain(self.animated_assets.values_mut().map(|object| &mut object.material));
+        for material in materials.filter(|material| material.pipeline == previous) {
+            material.pipeline = pipeline;
+        }
+        for (entity_id, mut object) in query_get_all!(StaticObject3DComponent) {
+            if object.material.pipeline == previous {
+                object.material.pipeline = pipeline;
+                insert(&entity_id, object);
+            }
+        }
+        for (entity_id, mut object) in query_get_all!(AnimatedObject3DComponent) {
+            if object.material.pipeline == previous {
+                object.material.pipeline = pipeline;
+                insert(&entity_id, object);
+            }
+        }
+        let retarget = |component: &mut Component| {
+            let material = match component {
+                Component::StaticObject3D(object) => &mut object.material,
+                Component::AnimatedObject3D(object) => &mut object.material,
+                _ => return,
+            };
+            if material.pipeline == previous {
+                material.pipeline = pipeline;
+            }
+        };
+        PrefabLibrary::for_each_component_mut(retarget);
+        World::for_each_pending_component_mut(retarget);
+        EntityPool::for_each_component_mut(retarget);
+        PlaySession::for_each_component_mut(retarget);
+        FindReplace::for_each_component_mut(retarget);
+    }
+
+    /// Magenta material used for assets or shaders that failed to load
+    fn placeholder_material(&self) -> Material {
+        self.placeholder_shader_program.map(Material::new).unwrap_or_default()
+    }
+
+    fn placeholder_static_object(&self, asset_name: Assets) -> StaticObject3DComponent {
+        StaticObject3DComponent::new(self.placeholder_mesh.clone(), self.placeholder_material(), asset_name)
+    }
+
+    fn placeholder_animated_object(&self, asset_name: Assets) -> AnimatedObject3DComponent {
+        AnimatedObject3DComponent::new(
+            self.placeholder_mesh.clone(),
+            self.placeholder_material(),
+            Default::default(),
+            Vec::new(),
+            asset_name
+        )
+    }
+
+    /// Cache a loaded static asset, substituting the placeholder cube if loading failed
+    fn register_static(&mut self, asset_name: Assets, result: EngineResult<StaticObject3DComponent>) {
+        let object = result.unwrap_or_else(|e| {
+            InterfaceSystem::report_error(&e);
+            self.placeholder_static_object(asset_name)
+        });
+        self.static_assets.insert(asset_name, object);
+    }
+
+    /// Cache a loaded animated asset, substituting the placeholder cube if loading failed
+    fn register_animated(&mut self, asset_name: Assets, result: EngineResult<AnimatedObject3DComponent>) {
+        let object = result.unwrap_or_else(|e| {
+            InterfaceSystem::report_error(&e);
+            self.placeholder_animated_object(asset_name)
+        });
+        self.animated_assets.insert(asset_name, object);
+    }
+
+    /// Register CPU-side asset data only (no GL context), used by headless runs
+    fn initialize_headless(&mut self) {
+        if self.initialized {
+            println!("⚠️  AssetsManager already initialized");
+            return;
+        }
+
+        println!("🔄 Initializing AssetsManager in headless mode (no GPU resources)...");
+
+        let asset_name_str = format!("{:?}", Assets::TestingDoll);
+        let testing_doll = gltf::Gltf
+            ::from_slice(include_bytes!("../../assets/meshes/guy.gltf"))
+            .map_err(|e| EngineError::asset(&asset_name_str, format!("Failed to parse GLTF: {}", e)))
+            .and_then(|gltf| {
+                let buffers = vec![gltf::buffer::Data(include_bytes!("../../assets/meshes/guy.bin").to_vec())];
+                Ok(
+                    AnimatedObject3DComponent::new(
+                        Mesh::default(),
+                        Material::default(),
+                        extract_skeleton(&gltf, &buffers, &asset_name_str)?,
+                        extract_animation_clips(&gltf, &buffers, &asset_name_str),
+                        Assets::TestingDoll
+                    )
+                )
+            });
+        self.register_animated(Assets::TestingDoll, testing_doll);
+
+        for asset_name in [Assets::Chair, Assets::BlockoutPlatform] {
+            self.static_assets.insert(
+                asset_name,
+                StaticObject3DComponent::new(Mesh::default(), Material::default(), asset_name)
+            );
+        }
+
+        self.initialized = true;
+        let total_assets = self.static_assets.len() + self.animated_assets.len();
+        println!("✅ AssetsManager headless initialization complete. Registered {} assets.", total_assets);
+    }
+
+    /// `Assets::Imported` id of a path, recorded when its import starts so scenes loading meanwhile know it
+    fn reserve_import_id(&mut self, path: &str) -> Assets {
+        let asset_name = Assets::imported(path);
+        if let Some(previous) = self.import_paths.insert(asset_name, path.to_string()) {
+            if previous != path {
+                println!("⚠️ {} and {} import as the same asset {:?}", previous, path, asset_name);
+            }
+        }
+        asset_name
+    }
+
+    /// Read and parse a .gltf file from the mounted pack or disk (buffers and base color image resolved relative
+    /// to it), or a .glb with them embedded; touches neither GL nor the manager, so it runs on a task worker
+    fn read_gltf(path: &str) -> EngineResult<GltfSource> {
+        println!("🔄 Importing GLTF asset from {}", path);
+
+        let gltf_path = std::path::Path::new(path);
+        let base_dir = gltf_path.parent().unwrap_or(std::path::Path::new("."));
+        let gltf_data = AssetPack::read_file(gltf_path)?;
+        let gltf = gltf::Gltf
+            ::from_slice(&gltf_data)
+            .map_err(|e| EngineError::asset(path, format!("Failed to parse GLTF: {}", e)))?;
+
+        let read_uri = |uri: &str| AssetPack::read_file(base_dir.join(uri));
+        let buffers = read_buffers(&gltf, read_uri, path)?;
+        let png_data = read_base_color_image(&gltf, &buffers, read_uri, path)?;
+
+        Ok(GltfSource { path: path.to_string(), gltf, buffers, png_data })
+    }
+
+    /// Upload a read .gltf (on the main thread) and register it under its path's id, with a LOD decimated to the
+    /// `simplify` triangle ratio if given
+    fn register_gltf(
+        &mut self,
+        asset_name: Assets,
+        source: GltfSource,
+        simplify: Option<f32>,
+        gl: Option<&glow::Context>
+    ) -> EngineResult<()> {
+        let GltfSource { path, gltf, buffers, png_data } = source;
+        let asset_name_str = format!("{:?}", asset_name);
+        let is_animated = gltf.skins().next().is_some() && gltf.animations().next().is_some();
+
+        let shader_program = if is_animated {
+            self.animated_shader_program
+        } else {
+            self.static_shader_program
+        };
+        let (mesh, material) = match (gl, shader_program) {
+            (Some(gl), Some(shader_program)) =>
+                (
+                    extract_mesh(gl, &gltf, &buffers, simplify, &asset_name_str)?,
+                    extract_material(gl, &gltf, &buffers, &png_data, shader_program, &asset_name_str)?,
+                ),
+            _ => (Mesh::default(), Material::default()),
+        };
+        if let (Some(gl), Some(texture)) = (gl, material.base_color_texture) {
+            texture.set_anisotropy(gl, GraphicsQuality::current().anisotropy);
+        }
+
+        if is_animated {
+            let skeleton = extract_skeleton(&gltf, &buffers, &asset_name_str)?;
+            let animation_clips = extract_animation_clips(&gltf, &buffers, &asset_name_str);
+            self.animated_assets.insert(
+                asset_name,
+                AnimatedObject3DComponent::new(mesh, material, skeleton, animation_clips, asset_name)
+            );
+        } else {
+            self.static_assets.insert(
+                asset_name,
+                StaticObject3DComponent::new(mesh, material, asset_name)
+            );
+        }
+
+        println!("✅ Imported {} as {:?}", path, asset_name);
+        Ok(())
     }
 
     pub fn get_static_object_copy(&self, asset_name: Assets) -> StaticObject3DComponent {
         if !self.initialized {
-            panic!("❌ AssetsManager not initialized! Call initialize_asset_manager() first.");
+            InterfaceSystem::report_error(&EngineError::NotInitialized("AssetsManager"));
+            return self.placeholder_static_object(asset_name);
         }
 
         if let Some(object) = self.static_assets.get(&asset_name) {
             println!("✅ Retrieved static copy of asset: {:?} from cache", asset_name);
             object.clone()
         } else {
-            panic!("❌ Static asset {:?} not found in cache", asset_name);
+            InterfaceSystem::report_error(
+                &EngineError::asset(&format!("{:?}", asset_name), "Static asset not found in cache")
+            );
+            self.placeholder_static_object(asset_name)
         }
     }
 
     pub fn get_animated_object_copy(&self, asset_name: Assets) -> AnimatedObject3DComponent {
         if !self.initialized {
-            panic!("❌ AssetsManager not initialized! Call initialize_asset_manager() first.");
+            InterfaceSystem::report_error(&EngineError::NotInitialized("AssetsManager"));
+            return self.placeholder_animated_object(asset_name);
         }
 
         if let Some(object) = self.animated_assets.get(&asset_name) {
             println!("✅ Retrieved animated copy of asset: {:?} from cache", asset_name);
             object.clone()
         } else {
-            panic!("❌ Animated asset {:?} not found in cache", asset_name);
+            InterfaceSystem::report_error(
+                &EngineError::asset(&format!("{:?}", asset_name), "Animated asset not found in cache")
+            );
+            self.placeholder_animated_object(asset_name)
         }
     }
 
     fn load_static_gltf(
-        &mut self,
         gltf_data: &str,
         bin_data: &[u8],
         png_data: &[u8],
         asset_name: Assets,
-        shader_program: glow::Program,
+        pipeline: Pipeline,
         gl: &glow::Context
-    ) {
+    ) -> EngineResult<StaticObject3DComponent> {
         println!("🔄 Loading static GLTF asset: {:?}", asset_name);
 
         // Parse asset data
+        let asset_name_str = format!("{:?}", asset_name);
         let gltf = gltf::Gltf
             ::from_slice(gltf_data.as_bytes())
-            .unwrap_or_else(|e| panic!("Failed to parse GLTF for {:?}: {}", asset_name, e));
+            .map_err(|e| EngineError::asset(&asset_name_str, format!("Failed to parse GLTF: {}", e)))?;
         let buffers = vec![gltf::buffer::Data(bin_data.to_vec())];
 
-        // Extract components - all error handling is internal
-        let asset_name_str = format!("{:?}", asset_name);
-        let mesh = extract_mesh(gl, &gltf, &buffers, &asset_name_str);
+        // Extract components - failures are returned to the caller, which substitutes a placeholder
+        let mesh = extract_mesh(gl, &gltf, &buffers, None, &asset_name_str)?;
         let material = extract_material(
             gl,
             &gltf,
             &buffers,
             png_data,
-            shader_program,
+            pipeline,
             &asset_name_str
-        );
+        )?;
 
         // Create static object with default transform
         let mut transform = Transform::new(0.0, 0.0, 0.0);
@@ -211,41 +733,39 @@ impl AssetsManager {
 
         let static_object = StaticObject3DComponent::new(mesh, material, asset_name);
 
-        // Store in static assets map
-        self.static_assets.insert(asset_name, static_object);
-        println!("✅ Loaded and cached static asset: {:?}", asset_name);
+        println!("✅ Loaded static asset: {:?}", asset_name);
+        Ok(static_object)
     }
 
     fn load_animated_gltf(
-        &mut self,
         gltf_data: &str,
         bin_data: &[u8],
         png_data: &[u8],
         asset_name: Assets,
-        shader_program: glow::Program,
+        pipeline: Pipeline,
         gl: &glow::Context
-    ) {
+    ) -> EngineResult<AnimatedObject3DComponent> {
         println!("🔄 Loading animated GLTF asset: {:?}", asset_name);
 
         // Parse asset data
+        let asset_name_str = format!("{:?}", asset_name);
         let gltf = gltf::Gltf
             ::from_slice(gltf_data.as_bytes())
-            .unwrap_or_else(|e| panic!("Failed to parse GLTF for {:?}: {}", asset_name, e));
+            .map_err(|e| EngineError::asset(&asset_name_str, format!("Failed to parse GLTF: {}", e)))?;
         let buffers = vec![gltf::buffer::Data(bin_data.to_vec())];
 
-        // Extract components - all error handling is internal
-        let asset_name_str = format!("{:?}", asset_name);
-        let mesh = extract_mesh(gl, &gltf, &buffers, &asset_name_str);
+        // Extract components - failures are returned to the caller, which substitutes a placeholder
+        let mesh = extract_mesh(gl, &gltf, &buffers, None, &asset_name_str)?;
         let material = extract_material(
             gl,
             &gltf,
             &buffers,
             png_data,
-            shader_program,
+            pipeline,
             &asset_name_str
-        );
-        let skeleton = extract_skeleton(&gltf, &buffers, &asset_name_str);
-        let animation_channels = extract_animation_channels(&gltf, &buffers, &asset_name_str);
+        )?;
+        let skeleton = extract_skeleton(&gltf, &buffers, &asset_name_str)?;
+        let animation_clips = extract_animation_clips(&gltf, &buffers, &asset_name_str);
 
         // Create animated object with default transform
         let mut transform = Transform::new(0.0, 0.0, 0.0);
@@ -255,83 +775,105 @@ impl AssetsManager {
             mesh,
             material,
             skeleton,
-            animation_channels,
+            animation_clips,
             asset_name
         );
 
-        // Store in animated assets map
-        self.animated_assets.insert(asset_name, animated_object);
-        println!("✅ Loaded and cached animated asset: {:?}", asset_name);
+        println!("✅ Loaded animated asset: {:?}", asset_name);
+        Ok(animated_object)
     }
 }
 
-// Shader creation functions
-fn compile_shader(
-    gl: &glow::Context,
-    shader_type: u32,
-    source: String
-) -> Result<glow::Shader, String> {
-    unsafe {
-        let shader = gl.create_shader(shader_type)?;
-        gl.shader_source(shader, &source);
-        gl.compile_shader(shader);
-
-        if !gl.get_shader_compile_status(shader) {
-            let log = gl.get_shader_info_log(shader);
-            gl.delete_shader(shader);
-            return Err(format!("Shader compile error: {}", log));
+// Global singleton instance - single-threaded
+thread_local! {
+    static ASSETS_MANAGER: RefCell<AssetsManager> = RefCell::new(AssetsManager::new());
+}
+
+// Public API
+pub fn initialize_asset_manager(gl: &glow::Context) -> EngineResult<()> {
+    ASSETS_MANAGER.with(|manager| { manager.borrow_mut().initialize_asset_manager(gl) })
+}
+
+pub fn initialize_headless_asset_manager() {
+    ASSETS_MANAGER.with(|manager| { manager.borrow_mut().initialize_headless() })
+}
+
+/// Import a .gltf or .glb in the background, optionally simplified (see `extract_mesh`); `on_imported` runs on the main
+/// thread once it's registered (or failed)
+pub fn import_gltf_asset(
+    path: &str,
+    simplify: Option<f32>,
+    on_imported: impl FnOnce(EngineResult<Assets>) + 'static
+) -> TaskId {
+    let asset_name = ASSETS_MANAGER.with(|manager| manager.borrow_mut().reserve_import_id(path));
+    let source_path = path.to_string();
+    TaskSystem::spawn(
+        &format!("import {}", path),
+        move || AssetsManager::read_gltf(&source_path),
+        move |result, gl| {
+            let registered = result.and_then(|source| {
+                ASSETS_MANAGER.with(|manager| manager.borrow_mut().register_gltf(asset_name, source, simplify, gl))
+            });
+            on_imported(registered.map(|()| asset_name));
         }
-        Ok(shader)
-    }
+    )
 }
 
-fn create_shader_program(
-    gl: &glow::Context,
-    vertex_shader_source: &str,
-    fragment_shader_source: &str,
-    program_name: &str
-) -> glow::Program {
-    unsafe {
-        // Use shader sources directly (no version replacement needed)
-        let vs = compile_shader(
-            gl,
-            glow::VERTEX_SHADER,
-            vertex_shader_source.to_string()
-        ).unwrap_or_else(|e| panic!("Failed to compile {} vertex shader: {}", program_name, e));
-        let fs = compile_shader(
-            gl,
-            glow::FRAGMENT_SHADER,
-            fragment_shader_source.to_string()
-        ).unwrap_or_else(|e| panic!("Failed to compile {} fragment shader: {}", program_name, e));
-
-        let program = gl
-            .create_program()
-            .unwrap_or_else(|e| panic!("Failed to create {} shader program: {}", program_name, e));
-        gl.attach_shader(program, vs);
-        gl.attach_shader(program, fs);
-        gl.link_program(program);
-
-        if !gl.get_program_link_status(program) {
-            let log = gl.get_program_info_log(program);
-            panic!("{} shader program link error: {}", program_name, log);
+/// Asset loader for the project's `.gltf` and `.glb` files, simplified as the project file asks; imports finish in the
+/// background and report their own failures
+pub fn import_project_asset(_gl: Option<&glow::Context>, path: &str) -> EngineResult<()> {
+    import_gltf_asset(path, Project::get().simplify_ratio(path), |result| {
+        if let Err(e) = result {
+            InterfaceSystem::report_error(&e);
         }
+    });
+    Ok(())
+}
 
-        gl.delete_shader(vs);
-        gl.delete_shader(fs);
+pub fn is_animated_asset(asset_name: Assets) -> bool {
+    ASSETS_MANAGER.with(|manager| { manager.borrow().animated_assets.contains_key(&asset_name) })
+}
 
-        println!("✅ Created {} shader program", program_name);
-        program
-    }
+/// Whether an asset is loaded or an import of it has started; scenes referencing anything else get placeholders
+pub fn is_known_asset(asset_name: Assets) -> bool {
+    ASSETS_MANAGER.with(|manager| {
+        let manager = manager.borrow();
+        manager.static_assets.contains_key(&asset_name) ||
+            manager.animated_assets.contains_key(&asset_name) ||
+            manager.import_paths.contains_key(&asset_name)
+    })
 }
 
-// Global singleton instance - single-threaded
-thread_local! {
-    static ASSETS_MANAGER: RefCell<AssetsManager> = RefCell::new(AssetsManager::new());
+/// Base color textures of every loaded asset, which entities share
+pub fn material_textures() -> Vec<Texture> {
+    ASSETS_MANAGER.with(|manager| {
+        let manager = manager.borrow();
+        manager.static_assets
+            .values()
+            .map(|object| &object.material)
+            .chain(manager.animated_assets.values().map(|object| &object.material))
+            .filter_map(|material| material.base_color_texture)
+            .collect()
+    })
 }
 
-// Public API
-pub fn initialize_asset_manager(gl: &glow::Context) {
-    ASSETS_MANAGER.with(|manager| { manager.borrow_mut().initialize_asset_manager(gl) })
+/// Render system: with the `shader_hot_reload` cvar on, recompile the programs whose .glsl files changed in the
+/// source tree since it was turned on, so shaders can be iterated on without rebuilding
+pub fn hot_reload_shaders(gl: &glow::Context, _width: u32, _height: u32) {
+    if Console::cvar("shader_hot_reload").unwrap_or(SHADER_HOT_RELOAD) == 0.0 {
+        return;
+    }
+    let changed = {
+        let mut watch = SHADER_WATCH.lock().unwrap();
+        if watch.last_check.is_some_and(|last| last.elapsed() < SHADER_HOT_RELOAD_INTERVAL) {
+            return;
+        }
+        watch.last_check = Some(Instant::now());
+        watch.changed_files()
+    };
+    if !changed.is_empty() {
+        ASSETS_MANAGER.with(|manager| manager.borrow_mut().reload_shaders(gl, &changed));
+    }
 }
 
 pub fn get_static_object_copy(asset_name: Assets) -> StaticObject3DComponent {
@@ -342,44 +884,241 @@ pub fn get_animated_object_copy(asset_name: Assets) -> AnimatedObject3DComponent
     ASSETS_MANAGER.with(|manager| { manager.borrow().get_animated_object_copy(asset_name) })
 }
 
-pub fn get_static_outline_shader() -> glow::Program {
+pub fn get_static_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().static_shader_program
+            .expect("Static shader not initialized")
+    })
+}
+
+pub fn get_static_outline_shader() -> Pipeline {
     ASSETS_MANAGER.with(|manager| {
         manager.borrow().static_outline_shader_program
             .expect("Static outline shader not initialized")
     })
 }
 
-pub fn get_animated_outline_shader() -> glow::Program {
+pub fn get_animated_outline_shader() -> Pipeline {
     ASSETS_MANAGER.with(|manager| {
         manager.borrow().animated_outline_shader_program
             .expect("Animated outline shader not initialized")
     })
 }
 
-pub fn get_box_shader() -> glow::Program {
+pub fn get_box_shader() -> Pipeline {
     ASSETS_MANAGER.with(|manager| {
         manager.borrow().box_shader_program
             .expect("Box shader not initialized")
     })
 }
 
-pub fn get_sphere_shader() -> glow::Program {
+pub fn get_sphere_shader() -> Pipeline {
     ASSETS_MANAGER.with(|manager| {
         manager.borrow().sphere_shader_program
             .expect("Sphere shader not initialized")
     })
 }
 
-pub fn get_capsule_shader() -> glow::Program {
+pub fn get_capsule_shader() -> Pipeline {
     ASSETS_MANAGER.with(|manager| {
         manager.borrow().capsule_shader_program
             .expect("Capsule shader not initialized")
     })
 }
 
-pub fn get_cylinder_shader() -> glow::Program {
+pub fn get_cylinder_shader() -> Pipeline {
     ASSETS_MANAGER.with(|manager| {
         manager.borrow().cylinder_shader_program
             .expect("Cylinder shader not initialized")
     })
 }
+
+pub fn get_wireframe_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().wireframe_shader_program
+            .expect("Wireframe shader not initialized")
+    })
+}
+
+pub fn get_terrain_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().terrain_shader_program
+            .expect("Terrain shader not initialized")
+    })
+}
+
+pub fn get_water_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().water_shader_program
+            .expect("Water shader not initialized")
+    })
+}
+
+pub fn get_impostor_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().impostor_shader_program
+            .expect("Impostor shader not initialized")
+    })
+}
+
+pub fn get_transparent_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().transparent_shader_program
+            .expect("Transparent shader not initialized")
+    })
+}
+
+pub fn get_oit_composite_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().oit_composite_shader_program
+            .expect("OIT composite shader not initialized")
+    })
+}
+
+pub fn get_ssao_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().ssao_shader_program
+            .expect("SSAO shader not initialized")
+    })
+}
+
+pub fn get_ssao_composite_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().ssao_composite_shader_program
+            .expect("SSAO composite shader not initialized")
+    })
+}
+
+pub fn get_depth_only_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().depth_only_shader_program
+            .expect("Depth-only shader not initialized")
+    })
+}
+
+pub fn get_exposure_adapt_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().exposure_adapt_shader_program
+            .expect("Exposure adaptation shader not initialized")
+    })
+}
+
+pub fn get_color_grading_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().color_grading_shader_program
+            .expect("Color grading shader not initialized")
+    })
+}
+
+pub fn get_fxaa_shader() -> Pipeline {
+    ASSETS_MANAGER.with(|manager| {
+        manager.borrow().fxaa_shader_program
+            .expect("FXAA shader not initialized")
+    })
+}
+
+impl ShaderBuild {
+    fn compile(&self, gl: &glow::Context) -> EngineResult<Pipeline> {
+        let vertex_source = preprocess_shader(self.name, &shader_source(self.vertex), self.defines)?;
+        let fragment_source = preprocess_shader(self.name, &shader_source(self.fragment), self.defines)?;
+        Pipeline::new(gl, self.name, &vertex_source, &fragment_source)
+    }
+}
+
+impl ShaderWatch {
+    /// Files in the shader directory modified since the last look, read into `sources`; the first look only
+    /// notes the times
+    fn changed_files(&mut self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(SHADER_DIRECTORY) else {
+            return Vec::new();
+        };
+        let first_look = self.modified.is_empty();
+        let mut changed = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|extension| extension != "glsl") {
+                continue;
+            }
+            let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) else {
+                continue;
+            };
+            let file = entry.file_name().to_string_lossy().to_string();
+            if self.modified.insert(file.clone(), modified) == Some(modified) || first_look {
+                continue;
+            }
+            match std::fs::read_to_string(&path) {
+                Ok(source) => {
+                    self.sources.insert(file.clone(), source);
+                    changed.push(file);
+                }
+                Err(e) => InterfaceSystem::report_error(&EngineError::asset(&file, format!("Failed to read: {}", e))),
+            }
+        }
+        changed
+    }
+}
+
+/// A shader file's source: as hot reload last read it, or as embedded
+fn shader_source((file, embedded): ShaderFile) -> String {
+    SHADER_WATCH.lock()
+        .unwrap()
+        .sources.get(file)
+        .cloned()
+        .unwrap_or_else(|| embedded.to_string())
+}
+
+/// Shader chunks that `#include "name"` lines splice in, since GLSL has no includes of its own
+const SHADER_INCLUDES: &[(&str, &str)] = &[
+    ("lighting.glsl", include_str!("../../assets/shaders/lighting.glsl")),
+    ("point_lights.glsl", include_str!("../../assets/shaders/point_lights.glsl")),
+    ("skinning.glsl", include_str!("../../assets/shaders/skinning.glsl")),
+    ("morph_targets.glsl", include_str!("../../assets/shaders/morph_targets.glsl")),
+    ("outline.glsl", include_str!("../../assets/shaders/outline.glsl")),
+    ("weighted_blended.glsl", include_str!("../../assets/shaders/weighted_blended.glsl")),
+];
+
+/// Sizes shared between Rust and GLSL, `#define`d in every shader so they're only written down here
+fn shared_shader_defines() -> [(&'static str, String); 3] {
+    [
+        ("MAX_JOINTS", MAX_JOINTS.to_string()),
+        ("MAX_MORPH_TARGETS", MAX_MORPH_TARGETS.to_string()),
+        ("MORPH_ROW", MORPH_ROW.to_string()),
+    ]
+}
+
+/// Expand a shader source for compiling: `#include` lines are replaced with the chunks they name (each chunk once
+/// per shader, so chunks may include the chunks they build on), and the shared sizes plus `defines`, e.g. feature
+/// flags, are `#define`d after the `#version` line. `name` labels errors.
+pub fn preprocess_shader(name: &str, source: &str, defines: &[(&str, &str)]) -> EngineResult<String> {
+    let body = expand_shader_includes(name, source, &mut HashSet::new())?;
+    let shared = shared_shader_defines();
+    let define_lines: String = shared
+        .iter()
+        .map(|(define, value)| (*define, value.as_str()))
+        .chain(defines.iter().copied())
+        .map(|(define, value)| format!("#define {} {}\n", define, value))
+        .collect();
+    Ok(match body.split_once('\n') {
+        Some((version, rest)) if version.trim_start().starts_with("#version") => format!("{}\n{}{}", version, define_lines, rest),
+        _ => format!("{}{}", define_lines, body),
+    })
+}
+
+fn expand_shader_includes(name: &str, source: &str, included: &mut HashSet<&'static str>) -> EngineResult<String> {
+    let mut expanded = String::with_capacity(source.len());
+    for line in source.lines() {
+        let Some(include) = line.trim_start().strip_prefix("#include") else {
+            expanded.push_str(line);
+            expanded.push('\n');
+            continue;
+        };
+        let include = include.trim().trim_matches('"');
+        let Some(&(chunk_name, chunk)) = SHADER_INCLUDES.iter().find(|(chunk_name, _)| *chunk_name == include) else {
+            return Err(EngineError::shader(name, format!("Unknown include \"{}\"", include)));
+        };
+        if included.insert(chunk_name) {
+            expanded.push_str(&expand_shader_includes(name, &shader_source((chunk_name, chunk)), included)?);
+        }
+    }
+    Ok(expanded)
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::path::{ Path, PathBuf };
+use std::sync::{ Arc, Mutex, RwLock };
+use std::time::{ Duration, Instant, SystemTime };
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+
+use crate::index::engine::components::{ CustomMaterial, MaterialValue, StaticObject3DComponent };
+use crate::index::engine::managers::AssetPack;
+use crate::index::engine::managers::assets_manager::preprocess_shader;
+use crate::index::engine::modules::{ EngineError, EngineResult, EntityId, InterfaceSystem, Selection };
+use crate::index::engine::modules::ecs::insert;
+use crate::index::engine::rhi::{ DrawCall, Pipeline, Uniform };
+use crate::get_query_by_id;
+
+/// Vertex stage of definitions without their own: the static mesh shader, whose outputs (norm, texCoord,
+/// worldPos, occlusion) the fragment stage may read
+const DEFAULT_VERTEX_SHADER: &str = include_str!("../../assets/shaders/vertex_static.glsl");
+/// How often `hot_reload` looks at the definition files for changes
+const HOT_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+/// Fields of CustomMaterial itself (and the scene's component tag), which parameters can't be named
+const RESERVED_NAMES: [&str; 2] = ["definition", "type"];
+
+/// `.material.toml` file: shader sources relative to it and the parameters they declare as uniforms
+#[derive(Deserialize)]
+struct MaterialFile {
+    name: Option<String>,   // Defaults to the file name without .material.toml
+    vertex: Option<String>, // Defaults to the static mesh vertex shader
+    fragment: String,
+    #[serde(default)]
+    parameters: BTreeMap<String, MaterialValue>, // Uniform name to default value; the value's size sets the type
+}
+
+/// Compiled user material
+#[derive(Debug)]
+pub struct MaterialDefinition {
+    pub name: String,
+    pub path: String,
+    pub pipeline: Pipeline,
+    pub parameters: BTreeMap<String, MaterialValue>,
+}
+
+impl MaterialDefinition {
+    /// A CustomMaterial of this definition with every parameter at its default
+    pub fn instantiate(&self) -> CustomMaterial {
+        CustomMaterial { definition: self.name.clone(), parameters: self.parameters.clone() }
+    }
+}
+
+/// Files a definition was read from, and the newest modification among them when it was last compiled
+struct WatchedFiles {
+    files: Vec<PathBuf>,
+    modified: Option<SystemTime>,
+}
+
+static DEFINITIONS: Lazy<RwLock<BTreeMap<String, Arc<MaterialDefinition>>>> = Lazy::new(|| RwLock::new(BTreeMap::new()));
+/// Definition path to its files, including definitions that failed, so fixing them on disk loads them
+static WATCHED: Lazy<Mutex<BTreeMap<String, WatchedFiles>>> = Lazy::new(|| Mutex::new(BTreeMap::new()));
+static LAST_HOT_RELOAD: Mutex<Option<Instant>> = Mutex::new(None);
+/// Start of the `time` uniform user shaders animate with
+static STARTED: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// User materials: shader definitions loaded from the project's asset directories, which CustomMaterial components
+/// draw static meshes with, so effects need no engine changes
+pub struct MaterialLibrary;
+
+impl MaterialLibrary {
+    /// Asset loader for the project's `.material.toml` files; compiling needs a GL context, so headless runs
+    /// skip them
+    pub fn load_asset(gl: Option<&glow::Context>, path: &str) -> EngineResult<()> {
+        let Some(gl) = gl else {
+            return Ok(());
+        };
+        let name = Self::register(gl, path)?;
+        println!("🎨 Registered material '{}' from {}", name, path);
+        Ok(())
+    }
+
+    /// Compile a `.material.toml` definition and register it under its name, replacing one of the same name; on
+    /// failure one of the same name stays registered, so its meshes keep drawing with the last good program
+    pub fn register(gl: &glow::Context, path: &str) -> EngineResult<String> {
+        Self::watch(path, vec![PathBuf::from(path)]);
+        let file: MaterialFile = toml::from_str(&AssetPack::read_text(path)?)
+            .map_err(|e| EngineError::asset(path, format!("Invalid material definition: {}", e)))?;
+        let name = file.name.unwrap_or_else(|| {
+            let file_name = Path::new(path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            file_name.trim_end_matches(".material.toml").to_string()
+        });
+        for (parameter, value) in &file.parameters {
+            if RESERVED_NAMES.contains(&parameter.as_str()) {
+                return Err(EngineError::asset(path, format!("Parameter name '{}' is reserved", parameter)));
+            }
+            if let MaterialValue::Vector(components) = value {
+                if !(2..=4).contains(&components.len()) {
+                    return Err(EngineError::asset(path, format!("Parameter '{}' must have 2 to 4 components", parameter)));
+                }
+            }
+        }
+
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+        let mut files = vec![PathBuf::from(path), directory.join(&file.fragment)];
+        files.extend(file.vertex.as_ref().map(|vertex| directory.join(vertex)));
+        Self::watch(path, files);
+        let vertex_source = match &file.vertex {
+            Some(vertex) => AssetPack::read_text(directory.join(vertex))?,
+            None => DEFAULT_VERTEX_SHADER.to_string(),
+        };
+        let fragment_source = AssetPack::read_text(directory.join(&file.fragment))?;
+        let pipeline = Pipeline::new(
+            gl,
+            &name,
+            &preprocess_shader(&name, &vertex_source, &[])?,
+            &preprocess_shader(&name, &fragment_source, &[])?
+        )?;
+
+        let definition = MaterialDefinition { name: name.clone(), path: path.to_string(), pipeline, parameters: file.parameters };
+        DEFINITIONS.write().unwrap().insert(name.clone(), Arc::new(definition));
+        Ok(name)
+    }
+
+    /// Render system: recompile definitions whose files changed on disk since they were last compiled. A failed
+    /// reload leaves the previous program in use, and its compile log stays in the shader error overlay until fixed.
+    pub fn hot_reload(gl: &glow::Context, _width: u32, _height: u32) {
+        {
+            let mut last = LAST_HOT_RELOAD.lock().unwrap();
+            if last.is_some_and(|last| last.elapsed() < HOT_RELOAD_INTERVAL) {
+                return;
+            }
+            *last = Some(Instant::now());
+        }
+
+        let changed: Vec<String> = WATCHED.lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(path, watched)| {
+                let modified = Self::newest_modification(&watched.files);
+                (modified != watched.modified).then(|| path.clone())
+            })
+            .collect();
+        for path in changed {
+            match Self::register(gl, &path) {
+                Ok(name) => println!("🔄 Reloaded material '{}' from {}", name, path),
+                Err(e) => InterfaceSystem::report_error(&e),
+            }
+        }
+    }
+
+    /// Track a definition's files from their current state
+    fn watch(path: &str, files: Vec<PathBuf>) {
+        let modified = Self::newest_modification(&files);
+        WATCHED.lock().unwrap().insert(path.to_string(), WatchedFiles { files, modified });
+    }
+
+    /// None when no file is on disk (packed builds, wasm)
+    fn newest_modification(files: &[PathBuf]) -> Option<SystemTime> {
+        files
+            .iter()
+            .filter_map(|file| std::fs::metadata(file).and_then(|metadata| metadata.modified()).ok())
+            .max()
+    }
+
+    pub fn get(name: &str) -> Option<Arc<MaterialDefinition>> {
+        DEFINITIONS.read().unwrap().get(name).cloned()
+    }
+
+    /// An entity's CustomMaterial with its definition, if it has one whose definition is registered
+    pub fn material_of(entity_id: &EntityId) -> Option<(CustomMaterial, Arc<MaterialDefinition>)> {
+        let material = get_query_by_id!(entity_id.clone(), (CustomMaterial))?;
+        let definition = Self::get(&material.definition)?;
+        Some((material, definition))
+    }
+
+    /// Set a CustomMaterial's parameters, the seconds since startup as `time` and the camera position (none in
+    /// orthographic views) on a draw with its definition's pipeline; parameters it doesn't set, or sets with the
+    /// wrong size, keep the definition's default
+    pub fn bind<'a>(
+        draw: DrawCall<'a>,
+        material: &'a CustomMaterial,
+        definition: &'a MaterialDefinition,
+        camera_position: Option<&'a [f32; 3]>
+    ) -> DrawCall<'a> {
+        let mut draw = draw.uniform("time", Uniform::Float(STARTED.elapsed().as_secs_f32()));
+        if let Some(camera_position) = camera_position {
+            draw = draw.uniform("camera_position", Uniform::Vec3(camera_position));
+        }
+        definition.parameters.iter().fold(draw, |draw, (name, default)| {
+            let value = material.parameters.get(name).filter(|value| Self::same_size(value, default)).unwrap_or(default);
+            let uniform = match value {
+                MaterialValue::Float(value) => Uniform::Float(*value),
+                MaterialValue::Vector(components) => {
+                    let components = components.as_slice();
+                    if let Ok(vector) = components.try_into() {
+                        Uniform::Vec2(vector)
+                    } else if let Ok(vector) = components.try_into() {
+                        Uniform::Vec3(vector)
+                    } else if let Ok(vector) = components.try_into() {
+                        Uniform::Vec4(vector)
+                    } else {
+                        return draw;
+                    }
+                }
+            };
+            draw.uniform(name, uniform)
+        })
+    }
+
+    fn same_size(value: &MaterialValue, default: &MaterialValue) -> bool {
+        match (value, default) {
+            (MaterialValue::Float(_), MaterialValue::Float(_)) => true,
+            (MaterialValue::Vector(value), MaterialValue::Vector(default)) => value.len() == default.len(),
+            _ => false,
+        }
+    }
+
+    /// Console: `materials` lists the registered material definitions and their parameters
+    pub fn materials_command(_args: &[&str]) -> Result<String, String> {
+        let definitions = DEFINITIONS.read().unwrap();
+        if definitions.is_empty() {
+            return Ok("No materials registered; add .material.toml files to an asset directory".to_string());
+        }
+        let lines: Vec<String> = definitions
+            .values()
+            .map(|definition| {
+                let parameters: Vec<&str> = definition.parameters.keys().map(String::as_str).collect();
+                format!("  {} ({}) [{}]", definition.name, definition.path, parameters.join(", "))
+            })
+            .collect();
+        Ok(format!("Materials:\n{}", lines.join("\n")))
+    }
+
+    /// Console: `material <name>` gives the selected static meshes the material at its default parameters
+    pub fn material_command(args: &[&str]) -> Result<String, String> {
+        let name = args.first().ok_or("Usage: material <name>")?;
+        let definition = Self::get(name).ok_or_else(|| format!("No material named {} (see `materials`)", name))?;
+        let entity_ids: Vec<_> = Selection::ids()
+            .into_iter()
+            .filter(|entity_id| get_query_by_id!(entity_id.clone(), (StaticObject3DComponent)).is_some())
+            .collect();
+        if entity_ids.is_empty() {
+            return Err("Select the static meshes to apply the material to".to_string());
+        }
+        for entity_id in &entity_ids {
+            insert(entity_id, definition.instantiate());
+        }
+        InterfaceSystem::refresh_selection();
+        Ok(format!("Applied {} to {} meshes", definition.name, entity_ids.len()))
+    }
+}
@@ -1,4 +1,16 @@
 pub mod assets_manager;
+pub mod asset_pack;
+pub mod material_library;
+pub mod prefab_library;
+pub mod texture_streaming;
 
 // Re-export commonly used types
-pub use assets_manager::initialize_asset_manager;
+pub use assets_manager::{
+    initialize_asset_manager,
+    initialize_headless_asset_manager,
+    import_gltf_asset,
+};
+pub use asset_pack::AssetPack;
+pub use material_library::MaterialLibrary;
+pub use prefab_library::PrefabLibrary;
+pub use texture_streaming::TextureStreaming;
@@ -0,0 +1,315 @@
+use std::collections::{ BTreeMap, HashMap };
+use std::path::Path;
+use std::sync::{ Arc, RwLock };
+use once_cell::sync::Lazy;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::{ Metadata, Transform };
+use crate::index::engine::managers::AssetPack;
+use crate::index::engine::modules::{
+    Component,
+    EngineError,
+    EngineResult,
+    EntityId,
+    InterfaceSystem,
+    OutlinerGroups,
+    Project,
+    Selection,
+};
+use crate::index::engine::modules::ecs::{ delete_entity, get_all_components, insert, spawn };
+use crate::index::game::entities::prefabs::PREFAB_NAMES;
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query_by_id, query_get_all };
+
+/// File name ending of prefab assets in the asset directories
+pub const PREFAB_EXTENSION: &str = ".prefab.json";
+/// Where new prefabs are saved when the project has no asset directory
+const DEFAULT_PREFAB_DIRECTORY: &str = "src/assets/prefabs";
+/// Metadata tag on an instance's root, followed by the prefab name
+const INSTANCE_TAG: &str = "prefab:";
+/// Metadata tag on an instance's other entities, followed by the root's entity id
+const PART_TAG: &str = "prefab_root:";
+
+/// Entities saved as a reusable template. The root sits at the origin facing yaw 0 and the other entities are
+/// placed relative to its position and heading, so instances can be put anywhere and turned about the vertical.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PrefabAsset {
+    pub name: String,
+    pub entities: Vec<Vec<Component>>, // Root first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impostor_distance: Option<f32>, // Camera distance past which instances draw as a baked billboard
+}
+
+static PREFABS: Lazy<RwLock<BTreeMap<String, Arc<PrefabAsset>>>> = Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+/// User prefabs: `.prefab.json` templates from the project's asset directories, or made from a selection in the
+/// editor, spawned by name next to the built-in prefabs
+pub struct PrefabLibrary;
+
+impl PrefabLibrary {
+    /// Asset loader for the project's `.prefab.json` files; a broken one is reported and the others still load
+    pub fn load_asset(_gl: Option<&glow::Context>, path: &str) -> EngineResult<()> {
+        let name = Self::load(path)?;
+        println!("🧩 Registered prefab '{}' from {}", name, path);
+        Ok(())
+    }
+
+    /// Read a prefab asset and register it under its name, replacing one of the same name
+    pub fn load(path: &str) -> EngineResult<String> {
+        let prefab: PrefabAsset = serde_json::from_str(&AssetPack::read_text(path)?)
+            .map_err(|e| EngineError::asset(path, format!("Invalid prefab: {}", e)))?;
+        if prefab.entities.is_empty() {
+            return Err(EngineError::asset(path, "Prefab has no entities"));
+        }
+        let name = prefab.name.clone();
+        PREFABS.write().unwrap().insert(name.clone(), Arc::new(prefab));
+        Ok(name)
+    }
+
+    pub fn get(name: &str) -> Option<Arc<PrefabAsset>> {
+        PREFABS.read().unwrap().get(name).cloned()
+    }
+
+    pub fn names() -> Vec<String> {
+        PREFABS.read().unwrap().keys().cloned().collect()
+    }
+
+    /// Visit the components of every template; instances handed out before keep the components they were spawned with
+    pub fn for_each_component_mut(mut visit: impl FnMut(&mut Component)) {
+        for prefab in PREFABS.write().unwrap().values_mut() {
+            Arc::make_mut(prefab).entities.iter_mut().flatten().for_each(&mut visit);
+        }
+    }
+
+    /// Spawn an instance of a user prefab at the origin and return its entities, root first
+    pub fn instantiate(name: &str) -> Option<Vec<EntityId>> {
+        let prefab = Self::get(name)?;
+        let root_id = spawn();
+        let mut entity_ids = vec![root_id.clone()];
+        for (index, components) in prefab.entities.iter().enumerate() {
+            let entity_id = if index == 0 { root_id.clone() } else { spawn() };
+            for component in components {
+                insert(&entity_id, component.clone());
+            }
+            let tag = if index == 0 { format!("{}{}", INSTANCE_TAG, name) } else { format!("{}{}", PART_TAG, root_id) };
+            if get_query_by_id!(entity_id.clone(), (Metadata)).is_none() {
+                insert(&entity_id, Metadata::new(&prefab.name, None, None));
+            }
+            query_by_id!(entity_id.clone(), (Metadata), |metadata| {
+                metadata.tags.retain(|tag| !tag.starts_with(INSTANCE_TAG) && !tag.starts_with(PART_TAG));
+                metadata.tags.push(tag);
+            });
+            if index > 0 {
+                entity_ids.push(entity_id);
+            }
+        }
+        Some(entity_ids)
+    }
+
+    /// The other entities of the instance `root_id` is the root of; none for built-in prefabs
+    pub fn parts(root_id: &EntityId) -> Vec<EntityId> {
+        let tag = format!("{}{}", PART_TAG, root_id);
+        query_get_all!(Metadata)
+            .into_iter()
+            .filter(|(_, metadata)| metadata.has_tag(&tag))
+            .map(|(entity_id, _)| entity_id)
+            .collect()
+    }
+
+    /// Every user prefab instance in the world: its prefab's name and its entities, root first
+    pub fn instances() -> Vec<(String, Vec<EntityId>)> {
+        let mut roots = Vec::new();
+        let mut parts: HashMap<String, Vec<EntityId>> = HashMap::new();
+        for (entity_id, metadata) in query_get_all!(Metadata) {
+            for tag in &metadata.tags {
+                if let Some(name) = tag.strip_prefix(INSTANCE_TAG) {
+                    roots.push((name.to_string(), entity_id.clone()));
+                } else if let Some(root_id) = tag.strip_prefix(PART_TAG) {
+                    parts.entry(root_id.to_string()).or_default().push(entity_id.clone());
+                }
+            }
+        }
+        roots
+            .into_iter()
+            .map(|(name, root_id)| {
+                let instance_parts = parts.remove(&root_id).unwrap_or_default();
+                (name, std::iter::once(root_id).chain(instance_parts).collect())
+            })
+            .collect()
+    }
+
+    /// Opt a prefab in to impostors past `distance` (or out with None) and save it; returns the saved path
+    pub fn set_impostor_distance(name: &str, distance: Option<f32>) -> EngineResult<String> {
+        let prefab = Self::get(name).ok_or_else(|| EngineError::asset(name, "No user prefab with this name"))?;
+        Self::save(PrefabAsset { impostor_distance: distance, ..(*prefab).clone() })
+    }
+
+    /// Move an instance's root to `position`, turning the whole instance by `yaw` radians about the root and
+    /// scaling it by `scale`; its other entities keep their place relative to the root
+    pub fn place(root_id: &EntityId, position: [f32; 3], yaw: f32, scale: f32) {
+        let Some(root) = get_query_by_id!(root_id.clone(), (Transform)) else {
+            return;
+        };
+        let origin = root.get_position();
+        let (sin, cos) = yaw.sin_cos();
+        for entity_id in std::iter::once(root_id.clone()).chain(Self::parts(root_id)) {
+            query_by_id!(entity_id, (Transform), |transform| {
+                let (old_position, rotation, old_scale) =
+                    (transform.get_position(), transform.get_rotation(), transform.get_scale());
+                let offset = [0, 1, 2].map(|axis| (old_position[axis] - origin[axis]) * scale);
+                let new_position = [
+                    position[0] + cos * offset[0] - sin * offset[2],
+                    position[1] + offset[1],
+                    position[2] + sin * offset[0] + cos * offset[2],
+                ];
+                transform.set_from_components(
+                    new_position,
+                    [rotation[0], rotation[1] + yaw, rotation[2]],
+                    old_scale.map(|axis| axis * scale)
+                );
+            });
+        }
+    }
+
+    /// Delete an instance's root and its other entities; returns whether the root existed
+    pub fn delete_instance(root_id: &EntityId) -> bool {
+        for part_id in Self::parts(root_id) {
+            delete_entity(&part_id);
+            Selection::remove(&part_id);
+        }
+        Selection::remove(root_id);
+        delete_entity(root_id)
+    }
+
+    /// Template of entities relative to `root_id`'s position and heading (yaw). The root keeps its pitch, roll and
+    /// scale; instance and part tags of earlier instances are dropped.
+    pub fn from_entities(name: &str, root_id: &EntityId, entity_ids: &[EntityId]) -> EngineResult<PrefabAsset> {
+        let root = get_query_by_id!(root_id.clone(), (Transform))
+            .ok_or_else(|| EngineError::asset(name, format!("Prefab root {} has no transform", root_id)))?;
+        let (origin, root_yaw) = (root.get_position(), root.get_rotation()[1]);
+        let (sin, cos) = (-root_yaw).sin_cos();
+
+        let ordered = std::iter::once(root_id).chain(entity_ids.iter().filter(|entity_id| *entity_id != root_id));
+        let entities = ordered
+            .map(|entity_id| {
+                get_all_components(entity_id)
+                    .into_iter()
+                    .map(|component| match component {
+                        Component::Transform(mut transform) => {
+                            let (position, rotation, scale) =
+                                (transform.get_position(), transform.get_rotation(), transform.get_scale());
+                            let offset = [0, 1, 2].map(|axis| position[axis] - origin[axis]);
+                            let local =
+                                [cos * offset[0] - sin * offset[2], offset[1], sin * offset[0] + cos * offset[2]];
+                            let rotation = [rotation[0], rotation[1] - root_yaw, rotation[2]];
+                            transform.set_from_components(local, rotation, scale);
+                            Component::Transform(transform)
+                        }
+                        Component::Metadata(mut metadata) => {
+                            metadata.tags.retain(|tag| !tag.starts_with(INSTANCE_TAG) && !tag.starts_with(PART_TAG));
+                            Component::Metadata(metadata)
+                        }
+                        component => component,
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(PrefabAsset { name: name.to_string(), entities, impostor_distance: None })
+    }
+
+    /// Save a prefab in the project's first asset directory, so it loads on the next start, and register it
+    pub fn save(prefab: PrefabAsset) -> EngineResult<String> {
+        let directory = Project::get()
+            .asset_directories
+            .first()
+            .map_or(DEFAULT_PREFAB_DIRECTORY, |directory| directory.as_str());
+        let path = Path::new(directory)
+            .join(format!("{}{}", prefab.name, PREFAB_EXTENSION))
+            .to_string_lossy()
+            .to_string();
+        let json = serde_json::to_string_pretty(&prefab)
+            .map_err(|e| EngineError::asset(&path, format!("Failed to serialize prefab: {}", e)))?;
+        std::fs::create_dir_all(directory).map_err(|e| EngineError::io(directory, e.to_string()))?;
+        std::fs::write(&path, json).map_err(|e| EngineError::io(&path, e.to_string()))?;
+        PREFABS.write().unwrap().insert(prefab.name.clone(), Arc::new(prefab));
+        Ok(path)
+    }
+
+    /// Turn the selection into a prefab with `root_id` as its root, then replace it with an instance in the same
+    /// place, grouped in the outliner under the prefab's name. Returns the saved path.
+    pub fn create_from_selection(name: &str, root_id: Option<EntityId>) -> EngineResult<String> {
+        let valid_name = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid_name {
+            return Err(EngineError::asset(name, "Prefab names may only use letters, digits, '_' and '-'"));
+        }
+        if PREFAB_NAMES.contains(&name) {
+            return Err(EngineError::asset(name, "A built-in prefab already has this name"));
+        }
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone();
+        let selected: Vec<EntityId> = Selection::ids()
+            .into_iter()
+            .filter(|entity_id| player_id.as_ref() != Some(entity_id))
+            .collect();
+        let root_id = root_id
+            .or_else(|| selected.first().cloned())
+            .ok_or_else(|| EngineError::asset(name, "Select the entities to make a prefab of"))?;
+        if !selected.contains(&root_id) {
+            return Err(EngineError::asset(name, format!("Prefab root {} isn't selected", root_id)));
+        }
+        let locked = selected.iter().find(|entity_id| {
+            get_query_by_id!((*entity_id).clone(), (Metadata)).is_some_and(|metadata| metadata.locked)
+        });
+        if let Some(entity_id) = locked {
+            return Err(EngineError::asset(name, format!("Entity {} is locked, unlock it first", entity_id)));
+        }
+
+        let root = get_query_by_id!(root_id.clone(), (Transform))
+            .ok_or_else(|| EngineError::asset(name, format!("Prefab root {} has no transform", root_id)))?;
+        let (position, yaw) = (root.get_position(), root.get_rotation()[1]);
+        let prefab = Self::from_entities(name, &root_id, &selected)?;
+        let path = Self::save(prefab)?;
+
+        for entity_id in &selected {
+            OutlinerGroups::remove_member(entity_id);
+            delete_entity(entity_id);
+        }
+        let instance = Self::instantiate(name).ok_or_else(|| EngineError::asset(name, "Prefab wasn't registered"))?;
+        Self::place(&instance[0], position, yaw, 1.0);
+        if instance.len() > 1 {
+            let group_id = OutlinerGroups::create();
+            OutlinerGroups::rename(&group_id, name);
+            for entity_id in &instance {
+                OutlinerGroups::add_member(&group_id, entity_id);
+            }
+        }
+        InterfaceSystem::select_entities(instance, false);
+        InterfaceSystem::update_entities_list();
+        Ok(path)
+    }
+
+    /// Console: `prefab <name> [root id]` makes a prefab of the selected entities, rooted at the given entity or
+    /// the first selected one, and replaces them with an instance
+    pub fn create_command(args: &[&str]) -> Result<String, String> {
+        let name = args.first().ok_or("Usage: prefab <name> [root entity id]")?;
+        let root_id = args.get(1).map(|root_id| root_id.to_string());
+        let path = Self::create_from_selection(name, root_id).map_err(|e| e.to_string())?;
+        let in_asset_directory = !Project::get().asset_directories.is_empty();
+        let note = if in_asset_directory { "" } else { " (add its directory to asset_directories to load it)" };
+        Ok(format!("Saved prefab {} to {}{}", name, path, note))
+    }
+
+    /// Console: `prefabs` lists the built-in and user prefabs
+    pub fn prefabs_command(_args: &[&str]) -> Result<String, String> {
+        let prefabs = PREFABS.read().unwrap();
+        let user: Vec<String> = prefabs
+            .values()
+            .map(|prefab| {
+                let impostor = prefab.impostor_distance.map_or(String::new(), |distance| {
+                    format!(", impostor past {}", distance)
+                });
+                format!("  {} ({} entities{})", prefab.name, prefab.entities.len(), impostor)
+            })
+            .collect();
+        Ok(format!("Built-in prefabs: {}\nUser prefabs:\n{}", PREFAB_NAMES.join(", "), user.join("\n")))
+    }
+}
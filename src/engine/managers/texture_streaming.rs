@@ -0,0 +1,244 @@
+use std::cell::{ Cell, RefCell };
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::index::engine::modules::{ EngineResult, GraphicsQuality };
+use crate::index::engine::rhi::{ Texture, TextureFilter, TextureWrap };
+
+/// Largest side of the mip tail uploaded when a texture loads; the finer levels stream in as they're needed
+const TAIL_SIZE: u32 = 64;
+/// Texture data uploaded per frame before streaming waits for the next one; levels go up in strips of rows, so a
+/// large one spreads over several frames instead of stalling one
+const UPLOAD_BYTES_PER_FRAME: usize = 1 << 20;
+const BYTES_PER_TEXEL: usize = 4;
+const MEGABYTE: usize = 1 << 20;
+
+/// Mesh texture whose finer mip levels are uploaded as the camera gets close and freed as it leaves
+struct StreamedTexture {
+    texture: Texture,
+    levels: Vec<Vec<u8>>,          // RGBA pixels of every mip level, finest first
+    tail: u32,                     // Level uploaded on load; it and every coarser one stay on the GPU
+    resident: u32,                 // Finest level uploaded, which the texture samples from
+    uploading: Option<(u32, u32)>, // Level being uploaded (allocated, not sampled yet) and its rows written so far
+    wanted: u32,                   // Finest level the last `stream` asked for, after the budget's bias
+}
+
+impl StreamedTexture {
+    fn level_bytes(&self, level: u32) -> usize {
+        let [width, height] = self.texture.level_size(level);
+        width as usize * height as usize * BYTES_PER_TEXEL
+    }
+
+    /// GPU memory with `finest` and every coarser level uploaded
+    fn bytes_from(&self, finest: u32) -> usize {
+        (finest..self.levels.len() as u32).map(|level| self.level_bytes(level)).sum()
+    }
+
+    fn resident_bytes(&self) -> usize {
+        self.bytes_from(self.resident) + self.uploading.map_or(0, |(level, _)| self.level_bytes(level))
+    }
+
+    /// Free the levels finer than the wanted one, including one still being uploaded
+    fn release_unwanted(&mut self, gl: &glow::Context) {
+        if let Some((level, _)) = self.uploading.filter(|(level, _)| *level < self.wanted) {
+            self.texture.release_level(gl, level);
+            self.uploading = None;
+        }
+        if self.resident < self.wanted {
+            self.texture.set_base_level(gl, self.wanted);
+            for level in self.resident..self.wanted {
+                self.texture.release_level(gl, level);
+            }
+            self.resident = self.wanted;
+        }
+    }
+
+    /// Write rows of the next finer level, at most `allowance` bytes but at least one row, and sample it once it's
+    /// complete; returns the bytes written
+    fn upload(&mut self, gl: &glow::Context, allowance: usize) -> usize {
+        let (level, first_row) = self.uploading.unwrap_or_else(|| {
+            let level = self.resident - 1;
+            self.texture.write_level(gl, level, None);
+            (level, 0)
+        });
+        let [width, height] = self.texture.level_size(level);
+        let row_bytes = width as usize * BYTES_PER_TEXEL;
+        let rows = ((allowance / row_bytes) as u32).clamp(1, height - first_row);
+        let start = first_row as usize * row_bytes;
+        let pixels = &self.levels[level as usize][start..start + rows as usize * row_bytes];
+        self.texture.write_rows(gl, level, first_row, rows, pixels);
+
+        if first_row + rows == height {
+            self.texture.set_base_level(gl, level);
+            self.resident = level;
+            self.uploading = None;
+        } else {
+            self.uploading = Some((level, first_row + rows));
+        }
+        rows as usize * row_bytes
+    }
+}
+
+thread_local! {
+    static STREAMED: RefCell<Vec<StreamedTexture>> = const { RefCell::new(Vec::new()) };
+    // Levels every texture was made coarser by in the last `stream` to fit the budget
+    static MIP_BIAS: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Texture streaming for mesh textures: only the mip tail is uploaded when an asset loads, so loading doesn't stall
+/// on large uploads (the hitches of the web build), and the finer levels follow from memory as objects come close,
+/// within the `texture_budget_mb` graphics setting. The full mip chain of every texture stays in memory to stream
+/// from
+pub struct TextureStreaming;
+
+impl TextureStreaming {
+    /// Make a streamed RGBA8 texture from tightly packed pixels, top row first, uploading the levels up to TAIL_SIZE
+    pub fn load(
+        gl: &glow::Context,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        filter: TextureFilter,
+        wrap: TextureWrap
+    ) -> EngineResult<Texture> {
+        let texture = Texture::rgba8_streamed(gl, width, height, filter, wrap)?;
+        let levels = mip_chain(width, height, pixels);
+        let last = levels.len() as u32 - 1;
+        let tail = (0..last)
+            .find(|level| texture.level_size(*level).into_iter().max() <= Some(TAIL_SIZE))
+            .unwrap_or(last);
+        for level in (tail..=last).rev() {
+            texture.write_level(gl, level, Some(&levels[level as usize]));
+        }
+        texture.set_base_level(gl, tail);
+
+        STREAMED.with(|streamed| {
+            let texture = StreamedTexture { texture, levels, tail, resident: tail, uploading: None, wanted: tail };
+            streamed.borrow_mut().push(texture);
+        });
+        Ok(texture)
+    }
+
+    /// Finest mip level of a texture that still has `texels` texels across its larger side
+    pub fn level_for(texture: &Texture, texels: f32) -> u32 {
+        let [width, height] = texture.size();
+        let ratio = width.max(height) as f32 / texels.max(1.0);
+        (ratio.log2().floor().max(0.0) as u32).min(texture.mip_levels() - 1)
+    }
+
+    /// Stream every texture toward the finest level `wanted` asks for it (by GL name); the ones it doesn't name drop
+    /// back to their tail. When that doesn't fit the budget, all of them are made coarser by the same bias until it
+    /// does. Unwanted levels are freed at once; finer ones are uploaded up to UPLOAD_BYTES_PER_FRAME a frame, the
+    /// blurriest textures first
+    pub fn stream(gl: &glow::Context, wanted: &HashMap<glow::Texture, u32>) {
+        let budget = GraphicsQuality::current().texture_budget_mb as usize * MEGABYTE;
+        STREAMED.with(|streamed| {
+            let mut streamed = streamed.borrow_mut();
+            let asked: Vec<u32> = streamed
+                .iter()
+                .map(|texture| {
+                    wanted.get(&texture.texture.raw()).map_or(texture.tail, |level| *level.min(&texture.tail))
+                })
+                .collect();
+            // The tails stay even when they alone don't fit
+            let max_bias = streamed.iter().map(|texture| texture.tail).max().unwrap_or(0);
+            let bias = (0..=max_bias)
+                .find(|bias| {
+                    let bytes: usize = streamed
+                        .iter()
+                        .zip(&asked)
+                        .map(|(texture, level)| texture.bytes_from((level + bias).min(texture.tail)))
+                        .sum();
+                    bytes <= budget
+                })
+                .unwrap_or(max_bias);
+            MIP_BIAS.with(|current| current.set(bias));
+
+            for (texture, level) in streamed.iter_mut().zip(&asked) {
+                texture.wanted = (level + bias).min(texture.tail);
+                texture.release_unwanted(gl);
+            }
+
+            let mut pending: Vec<usize> = (0..streamed.len())
+                .filter(|index| streamed[*index].resident > streamed[*index].wanted)
+                .collect();
+            pending.sort_by_key(|index| Reverse(streamed[*index].resident - streamed[*index].wanted));
+            let mut uploaded = 0;
+            for index in pending {
+                if uploaded >= UPLOAD_BYTES_PER_FRAME {
+                    break;
+                }
+                uploaded += streamed[index].upload(gl, UPLOAD_BYTES_PER_FRAME - uploaded);
+            }
+        });
+    }
+
+    /// Console: `textures` lists the streamed textures with the levels they have and want, against the budget
+    pub fn textures_command(_args: &[&str]) -> Result<String, String> {
+        let budget = GraphicsQuality::current().texture_budget_mb;
+        STREAMED.with(|streamed| {
+            let streamed = streamed.borrow();
+            if streamed.is_empty() {
+                return Ok("No streamed textures loaded".to_string());
+            }
+            let resident: usize = streamed.iter().map(StreamedTexture::resident_bytes).sum();
+            let mut lines = vec![
+                format!(
+                    "{} textures, {:.1} of {} MB on the GPU, mip bias {}",
+                    streamed.len(),
+                    resident as f32 / MEGABYTE as f32,
+                    budget,
+                    MIP_BIAS.with(Cell::get)
+                )
+            ];
+            for texture in streamed.iter() {
+                let [width, height] = texture.texture.size();
+                let [resident_width, resident_height] = texture.texture.level_size(texture.resident);
+                let [wanted_width, wanted_height] = texture.texture.level_size(texture.wanted);
+                let mut line = format!(
+                    "  {}x{}: {}x{} uploaded, {}x{} wanted",
+                    width,
+                    height,
+                    resident_width,
+                    resident_height,
+                    wanted_width,
+                    wanted_height
+                );
+                if let Some((level, rows)) = texture.uploading {
+                    let [_, level_height] = texture.texture.level_size(level);
+                    line.push_str(&format!(" ({}% of the next level)", rows * 100 / level_height));
+                }
+                lines.push(line);
+            }
+            Ok(lines.join("\n"))
+        })
+    }
+}
+
+/// Every mip level of an RGBA8 image down to 1x1, finest first, each a 2x2 box filter of the one above
+fn mip_chain(width: u32, height: u32, pixels: &[u8]) -> Vec<Vec<u8>> {
+    let mut levels = vec![pixels.to_vec()];
+    let (mut width, mut height) = (width as usize, height as usize);
+    while width > 1 || height > 1 {
+        let (next_width, next_height) = ((width / 2).max(1), (height / 2).max(1));
+        let source = levels.last().unwrap();
+        let mut level = Vec::with_capacity(next_width * next_height * BYTES_PER_TEXEL);
+        for y in 0..next_height {
+            let rows = [(y * 2).min(height - 1), (y * 2 + 1).min(height - 1)];
+            for x in 0..next_width {
+                let columns = [(x * 2).min(width - 1), (x * 2 + 1).min(width - 1)];
+                for channel in 0..BYTES_PER_TEXEL {
+                    let sum: u32 = rows
+                        .iter()
+                        .flat_map(|row| columns.map(|column| (row * width + column) * BYTES_PER_TEXEL + channel))
+                        .map(|index| u32::from(source[index]))
+                        .sum();
+                    level.push(((sum + 2) / 4) as u8);
+                }
+            }
+        }
+        levels.push(level);
+        (width, height) = (next_width, next_height);
+    }
+    levels
+}
@@ -4,7 +4,10 @@ pub mod components;
 pub mod managers;
 pub mod editor_ui;
 pub mod modules;
+pub mod rhi;
+pub mod plugin;
 
 // Re-export all commonly used items for easy access
 pub use modules::*;
 pub use managers::*;
+pub use plugin::EnginePlugin;
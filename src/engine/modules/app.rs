@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::sync::{ Arc, RwLock };
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+use crate::index::engine::components::SystemTrait;
+use crate::index::engine::modules::console::Console;
+use crate::index::engine::modules::engine_error::EngineResult;
+use crate::index::engine::modules::event_system::{ EventSystem, EventType };
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::modules::project::Project;
+use crate::index::engine::modules::scene_bench::SceneBench;
+
+/// Per-tick simulation system (e.g. `PhysicsSystem::update`)
+pub type FixedSystemFn = fn();
+/// Per-frame render system or pass (e.g. `RenderSystem::update`)
+pub type RenderSystemFn = fn(&glow::Context, u32, u32);
+/// One-off system run after the scene is loaded
+pub type StartupSystemFn = fn();
+/// Loads one project asset file with the extension it was registered for; headless runs have no GL context
+pub type AssetLoaderFn = fn(Option<&glow::Context>, &str) -> EngineResult<()>;
+/// Fields of a new plugin component of a registered kind
+pub type ComponentDefaultFn = fn() -> serde_json::Value;
+/// Label and value rows of an editor panel, rebuilt whenever the Plugins panel is shown
+pub type EditorPanelFn = fn() -> Vec<(String, String)>;
+
+/// Implemented by the engine, the game and third-party crates to extend wet-crab at startup
+pub trait Plugin {
+    fn name(&self) -> &str;
+    fn build(&self, app: &mut App);
+}
+
+/// A plugin's section of the editor's Plugins panel
+#[derive(Clone)]
+pub struct EditorPanel {
+    pub title: String,
+    pub rows: EditorPanelFn,
+    pub actions: Vec<(String, String)>, // Button label and the console line it runs
+    pub row_action: Option<(String, String)>, // Button on each row, running the line followed by the row's number
+}
+
+impl EditorPanel {
+    pub fn new(title: &str, rows: EditorPanelFn) -> Self {
+        Self { title: title.to_string(), rows, actions: Vec::new(), row_action: None }
+    }
+
+    /// Add a button that runs `line` in the console
+    pub fn with_action(mut self, label: &str, line: &str) -> Self {
+        self.actions.push((label.to_string(), line.to_string()));
+        self
+    }
+
+    /// Add a button to every row that runs `line` with the row's number (from 1) appended, e.g. `play_changes apply 2`
+    pub fn with_row_action(mut self, label: &str, line: &str) -> Self {
+        self.row_action = Some((label.to_string(), line.to_string()));
+        self
+    }
+}
+
+/// Everything plugins registered, in registration order; replaces hard-coded calls in Program
+#[derive(Default)]
+pub struct App {
+    plugins: Vec<(String, String)>, // Name and what it registered
+    startup_systems: Vec<(String, StartupSystemFn)>,
+    fixed_systems: Vec<(String, FixedSystemFn)>,
+    render_systems: Vec<(String, RenderSystemFn)>,
+    asset_loaders: Vec<(String, AssetLoaderFn)>, // File extension and its loader
+    components: BTreeMap<String, ComponentDefaultFn>,
+    editor_panels: Vec<EditorPanel>,
+}
+
+static APP: Lazy<RwLock<App>> = Lazy::new(|| RwLock::new(App::default()));
+
+impl App {
+    /// Let a plugin register its systems, passes, loaders, components and panels (systems run in registration order)
+    pub fn add_plugin(plugin: &dyn Plugin) {
+        let mut app = APP.write().unwrap();
+        if app.plugins.iter().any(|(name, _)| name == plugin.name()) {
+            println!("⚠️ Plugin {} already registered", plugin.name());
+            return;
+        }
+
+        let before = app.counts();
+        plugin.build(&mut app);
+        let after = app.counts();
+        let summary = format!(
+            "{} startup, {} fixed, {} render systems, {} asset loaders, {} components, {} panels",
+            after[0] - before[0],
+            after[1] - before[1],
+            after[2] - before[2],
+            after[3] - before[3],
+            after[4] - before[4],
+            after[5] - before[5]
+        );
+        println!("🧩 Registered plugin {} ({})", plugin.name(), summary);
+        app.plugins.push((plugin.name().to_string(), summary));
+    }
+
+    fn counts(&self) -> [usize; 6] {
+        [
+            self.startup_systems.len(),
+            self.fixed_systems.len(),
+            self.render_systems.len(),
+            self.asset_loaders.len(),
+            self.components.len(),
+            self.editor_panels.len(),
+        ]
+    }
+
+    pub fn add_startup_system(&mut self, name: &str, system: StartupSystemFn) -> &mut Self {
+        self.startup_systems.push((name.to_string(), system));
+        self
+    }
+
+    pub fn add_fixed_system(&mut self, name: &str, system: FixedSystemFn) -> &mut Self {
+        self.fixed_systems.push((name.to_string(), system));
+        self
+    }
+
+    pub fn add_render_system(&mut self, name: &str, system: RenderSystemFn) -> &mut Self {
+        self.render_systems.push((name.to_string(), system));
+        self
+    }
+
+    /// Add a render pass ahead of an already registered one (e.g. to draw before "outlines"), or last if there is
+    /// none of that name
+    #[allow(dead_code)]
+    pub fn add_render_system_before(&mut self, before: &str, name: &str, system: RenderSystemFn) -> &mut Self {
+        let index = self.render_systems
+            .iter()
+            .position(|(registered, _)| registered == before)
+            .unwrap_or(self.render_systems.len());
+        self.render_systems.insert(index, (name.to_string(), system));
+        self
+    }
+
+    /// Subscribe an event-driven system (forwards to the EventSystem)
+    pub fn add_event_system(&mut self, event_type: EventType, system: Arc<dyn SystemTrait>) -> &mut Self {
+        EventSystem::subscribe(event_type, system);
+        self
+    }
+
+    /// Subscribe an event-driven system ahead of (higher) or behind (lower) the default priority 0
+    pub fn add_event_system_with_priority(&mut self, event_type: EventType, priority: i32, system: Arc<dyn SystemTrait>) -> &mut Self {
+        EventSystem::subscribe_with_priority(event_type, priority, system);
+        self
+    }
+
+    /// Add an editor console command (forwards to the Console)
+    pub fn add_console_command(
+        &mut self,
+        name: &str,
+        handler: impl Fn(&[&str]) -> Result<String, String> + Send + Sync + 'static
+    ) -> &mut Self {
+        Console::register_command(name, handler);
+        self
+    }
+
+    /// Add a console-tweakable value that systems read with `Console::cvar`
+    pub fn add_cvar(&mut self, name: &str, default: f32, min: f32, max: f32, help: &str) -> &mut Self {
+        Console::register_cvar(name, default, min, max, help);
+        self
+    }
+
+    /// Load the project's files ending in `extension` with `loader` on startup, before the scene
+    pub fn add_asset_loader(&mut self, extension: &str, loader: AssetLoaderFn) -> &mut Self {
+        self.asset_loaders.push((extension.to_string(), loader));
+        self
+    }
+
+    /// Register a component kind for `PluginComponent`s; `T::default()` is what `add_component <kind>` adds, and
+    /// scenes with kinds no plugin registered open in safe mode
+    #[allow(dead_code)]
+    pub fn add_component<T: Default + Serialize>(&mut self, kind: &str) -> &mut Self {
+        self.components.insert(kind.to_string(), default_fields::<T>);
+        self
+    }
+
+    /// Add a section to the editor's Plugins panel
+    pub fn add_editor_panel(&mut self, panel: EditorPanel) -> &mut Self {
+        self.editor_panels.push(panel);
+        self
+    }
+
+    /// Run all startup systems once
+    pub fn run_startup() {
+        for system in Self::collect(|app| &app.startup_systems) {
+            system();
+        }
+    }
+
+    /// Run all fixed-timestep systems for one simulation tick, timed while a SceneBench runs
+    pub fn run_fixed_update() {
+        for (name, system) in Self::collect_named(|app| &app.fixed_systems) {
+            SceneBench::time("fixed", &name, system);
+        }
+    }
+
+    /// Run all render systems for the current frame, timed while a SceneBench runs
+    pub fn run_render(gl: &glow::Context, width: u32, height: u32) {
+        for (name, system) in Self::collect_named(|app| &app.render_systems) {
+            SceneBench::time("render", &name, || system(gl, width, height));
+        }
+    }
+
+    /// Run every asset loader over the project's asset directories, in registration order
+    pub fn load_project_assets(gl: Option<&glow::Context>) {
+        let loaders = APP.read().unwrap().asset_loaders.clone();
+        for (extension, loader) in loaders {
+            for path in Project::get().files_with_extension(&extension) {
+                if let Err(e) = loader(gl, &path) {
+                    InterfaceSystem::report_error(&e);
+                }
+            }
+        }
+    }
+
+    /// Fields of a new component of a registered kind
+    pub fn component_default(kind: &str) -> Option<serde_json::Value> {
+        let default = *APP.read().unwrap().components.get(kind)?;
+        Some(default())
+    }
+
+    pub fn component_kinds() -> Vec<String> {
+        APP.read().unwrap().components.keys().cloned().collect()
+    }
+
+    pub fn editor_panels() -> Vec<EditorPanel> {
+        APP.read().unwrap().editor_panels.clone()
+    }
+
+    /// Rows of the built-in "Loaded plugins" panel: each plugin and what it registered
+    pub fn plugin_rows() -> Vec<(String, String)> {
+        APP.read().unwrap().plugins.clone()
+    }
+
+    /// Copy the function pointers out so systems can register or query freely while running
+    fn collect<F: Copy>(list: impl Fn(&App) -> &Vec<(String, F)>) -> Vec<F> {
+        let app = APP.read().unwrap();
+        list(&app).iter().map(|(_, system)| *system).collect()
+    }
+
+    /// As `collect`, with the names the systems were registered under
+    fn collect_named<F: Copy>(list: impl Fn(&App) -> &Vec<(String, F)>) -> Vec<(String, F)> {
+        let app = APP.read().unwrap();
+        list(&app).clone()
+    }
+}
+
+fn default_fields<T: Default + Serialize>() -> serde_json::Value {
+    serde_json::to_value(T::default()).unwrap_or_default()
+}
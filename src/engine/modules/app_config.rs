@@ -0,0 +1,413 @@
+use std::collections::BTreeMap;
+use std::ffi::{ c_void, CStr };
+use std::sync::OnceLock;
+use serde::{ Serialize, Deserialize };
+use slint::winit_030::winit;
+
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::modules::editor_settings::{ EditorSettings, EditorTheme };
+use crate::index::engine::modules::graphics_quality::{ AntialiasingMode, GraphicsQuality, QualityPreset };
+
+/// Default location of the application config, relative to the working directory
+pub const DEFAULT_CONFIG_PATH: &str = "app_config.toml";
+
+/// Config file the app was started with, where tweaked cvars are saved
+static CONFIG_PATH: OnceLock<String> = OnceLock::new();
+
+/// Window/application settings loaded from `app_config.toml` and overridden by CLI flags
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct AppConfig {
+    pub title: String,
+    pub width: u32,
+    pub height: u32,
+    pub position: Option<[i32; 2]>, // Outer window position in physical pixels
+    pub monitor: Option<usize>,     // Index into the available monitors
+    pub fullscreen: bool,           // Borderless fullscreen on the chosen monitor
+    pub vsync: bool,                // Off sets the swap interval to 0 where the platform exposes it
+    pub graphics: GraphicsQuality,    // Saved by the Graphics panel
+    pub editor: EditorSettings,       // Theme and UI scale, saved by the Graphics panel
+    pub cvars: BTreeMap<String, f32>, // Saved console cvar values (Tweaks panel)
+    pub crash_reports: bool,          // Opt in to local crash reports (Diagnostics)
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            title: "wet-crab".to_string(),
+            width: 1280,
+            height: 720,
+            position: None,
+            monitor: None,
+            fullscreen: false,
+            vsync: true,
+            graphics: GraphicsQuality::default(),
+            editor: EditorSettings::default(),
+            cvars: BTreeMap::new(),
+            crash_reports: false,
+        }
+    }
+}
+
+impl AppConfig {
+    /// Load the config file (path may be overridden with `--config`) and apply CLI overrides
+    pub fn load(args: &[String]) -> Self {
+        let path = Self::flag_value(args, "--config").unwrap_or(DEFAULT_CONFIG_PATH);
+        let _ = CONFIG_PATH.set(path.to_string());
+
+        let mut config = match std::fs::read_to_string(path) {
+            Ok(contents) =>
+                match toml::from_str::<AppConfig>(&contents) {
+                    Ok(config) => {
+                        println!("⚙️ Loaded app config from {}", path);
+                        config
+                    }
+                    Err(e) => {
+                        eprintln!("❌ Failed to parse app config {}: {}", path, e);
+                        AppConfig::default()
+                    }
+                }
+            Err(_) => AppConfig::default(),
+        };
+
+        config.apply_cli_args(args);
+        config
+    }
+
+    /// Rewrite the `[cvars]` table of the config file, keeping the other settings and comments as written
+    pub fn save_cvars(values: &BTreeMap<String, f32>) -> EngineResult<()> {
+        let mut table = String::new();
+        for (name, value) in values {
+            // Display keeps 0.002 from being written as 0.0020000000949949026; toml floats need the fraction
+            let value = if value.fract() == 0.0 { format!("{:.1}", value) } else { value.to_string() };
+            table.push_str(&format!("{} = {}\n", name, value));
+        }
+        let path = Self::save_table("cvars", &table)?;
+        println!("⚙️ Saved {} cvars to {}", values.len(), path);
+        Ok(())
+    }
+
+    /// Rewrite the `[graphics]` table of the config file, keeping the other settings and comments as written
+    pub fn save_graphics(graphics: &GraphicsQuality) -> EngineResult<()> {
+        let table = toml::to_string(graphics).map_err(|e| EngineError::io(DEFAULT_CONFIG_PATH, e.to_string()))?;
+        let path = Self::save_table("graphics", &table)?;
+        println!("⚙️ Saved graphics settings ({:?}) to {}", graphics.preset, path);
+        Ok(())
+    }
+
+    /// Rewrite the `[editor]` table of the config file, keeping the other settings and comments as written
+    pub fn save_editor(editor: &EditorSettings) -> EngineResult<()> {
+        let table = toml::to_string(editor).map_err(|e| EngineError::io(DEFAULT_CONFIG_PATH, e.to_string()))?;
+        let path = Self::save_table("editor", &table)?;
+        println!("⚙️ Saved editor settings ({:?} theme, {}x UI scale) to {}", editor.theme, editor.ui_scale, path);
+        Ok(())
+    }
+
+    /// Replace a table of the config file with `contents`, moved to the end; returns the path written. Sub-tables
+    /// in `contents` (e.g. `[outline]` of the editor settings) are written as `[name.outline]`
+    fn save_table(name: &str, contents: &str) -> EngineResult<&'static str> {
+        let path = CONFIG_PATH.get().map(String::as_str).unwrap_or(DEFAULT_CONFIG_PATH);
+        let existing = std::fs::read_to_string(path).unwrap_or_default();
+        let header = format!("[{}]", name);
+        let sub_table_prefix = format!("[{}.", name);
+
+        // Drop the old table and its sub-tables, up to the next other table header
+        let mut kept: Vec<&str> = Vec::new();
+        let mut in_table = false;
+        for line in existing.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') {
+                in_table = trimmed == header || trimmed.starts_with(&sub_table_prefix);
+            }
+            if !in_table {
+                kept.push(line);
+            }
+        }
+        while kept.last().is_some_and(|line| line.trim().is_empty()) {
+            kept.pop();
+        }
+
+        let mut output = kept.join("\n");
+        if !output.is_empty() {
+            output.push_str("\n\n");
+        }
+        output.push_str(&header);
+        output.push('\n');
+        for line in contents.lines() {
+            match line.strip_prefix('[') {
+                Some(sub_table) => output.push_str(&format!("{}{}", sub_table_prefix, sub_table)),
+                None => output.push_str(line),
+            }
+            output.push('\n');
+        }
+
+        std::fs::write(path, output).map_err(|e| EngineError::io(path, e.to_string()))?;
+        Ok(path)
+    }
+
+    /// Override config values with command line flags
+    pub fn apply_cli_args(&mut self, args: &[String]) {
+        let mut msaa_samples = None;
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--title" => {
+                    if let Some(value) = iter.next() {
+                        self.title = value.clone();
+                    }
+                }
+                "--width" => {
+                    if let Some(value) = iter.next().and_then(|v| v.parse().ok()) {
+                        self.width = value;
+                    }
+                }
+                "--height" => {
+                    if let Some(value) = iter.next().and_then(|v| v.parse().ok()) {
+                        self.height = value;
+                    }
+                }
+                "--position" => {
+                    // Expected format: X,Y
+                    if let Some((x, y)) = iter.next().and_then(|v| v.split_once(',')) {
+                        if let (Ok(x), Ok(y)) = (x.trim().parse(), y.trim().parse()) {
+                            self.position = Some([x, y]);
+                        }
+                    }
+                }
+                "--monitor" => {
+                    if let Some(value) = iter.next().and_then(|v| v.parse().ok()) {
+                        self.monitor = Some(value);
+                    }
+                }
+                "--msaa" => {
+                    msaa_samples = iter.next().and_then(|v| v.parse().ok()).or(msaa_samples);
+                }
+                "--quality" => {
+                    if let Some(quality) = iter.next().and_then(|v| QualityPreset::parse(v)).and_then(GraphicsQuality::preset) {
+                        self.graphics = quality;
+                    }
+                }
+                "--theme" => {
+                    if let Some(theme) = iter.next().and_then(|v| EditorTheme::parse(v)) {
+                        self.editor.theme = theme;
+                    }
+                }
+                "--ui-scale" => {
+                    if let Some(value) = iter.next().and_then(|v| v.parse().ok()) {
+                        self.editor.ui_scale = value;
+                    }
+                }
+                "--fullscreen" => {
+                    self.fullscreen = true;
+                }
+                "--windowed" => {
+                    self.fullscreen = false;
+                }
+                "--vsync" => {
+                    self.vsync = true;
+                }
+                "--no-vsync" => {
+                    self.vsync = false;
+                }
+                "--crash-reports" => {
+                    self.crash_reports = true;
+                }
+                _ => {}
+            }
+        }
+
+        // --msaa refines the preset wherever it is on the command line; 0 or 1 sample turns antialiasing off
+        if let Some(samples) = msaa_samples {
+            let antialiasing = if samples >= 2 { AntialiasingMode::Msaa } else { AntialiasingMode::Off };
+            let graphics = GraphicsQuality { antialiasing, msaa_samples: samples, ..self.graphics };
+            if graphics != self.graphics {
+                self.graphics = GraphicsQuality { preset: QualityPreset::Custom, ..graphics };
+            }
+        }
+    }
+
+    /// Apply the initial window parameters to winit attributes before the window is created
+    pub fn apply_to_window_attributes(
+        &self,
+        attributes: winit::window::WindowAttributes
+    ) -> winit::window::WindowAttributes {
+        let mut attributes = attributes
+            .with_title(self.title.clone())
+            .with_inner_size(winit::dpi::PhysicalSize::new(self.width, self.height));
+
+        if let Some([x, y]) = self.position {
+            attributes = attributes.with_position(winit::dpi::PhysicalPosition::new(x, y));
+        }
+
+        if self.fullscreen {
+            // Monitor handles are only available once the event loop runs (see apply_monitor)
+            attributes = attributes.with_fullscreen(
+                Some(winit::window::Fullscreen::Borderless(None))
+            );
+        }
+
+        attributes
+    }
+
+    /// Move the window onto the configured monitor (requires a live winit window)
+    pub fn apply_monitor(&self, winit_window: &winit::window::Window) {
+        let Some(index) = self.monitor else {
+            return;
+        };
+        let Some(monitor) = winit_window.available_monitors().nth(index) else {
+            eprintln!("⚠️ Monitor {} not found, keeping the default monitor", index);
+            return;
+        };
+
+        if self.fullscreen {
+            winit_window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(Some(monitor))));
+        } else {
+            let origin = monitor.position();
+            let [x, y] = self.position.unwrap_or([0, 0]);
+            winit_window.set_outer_position(
+                winit::dpi::PhysicalPosition::new(origin.x + x, origin.y + y)
+            );
+        }
+    }
+
+    /// Apply `vsync` to Slint's GL surface, which Slint always creates waiting for vsync: turning it off sets the
+    /// swap interval through the platform's extension (EGL, GLX or WGL) on the current context. Call with the
+    /// context current, i.e. from the rendering setup.
+    pub fn apply_vsync(&self, get_proc_address: &dyn Fn(&CStr) -> *const c_void) {
+        if self.vsync {
+            return;
+        }
+        match unsafe { Self::set_swap_interval(get_proc_address, 0) } {
+            Some(api) => println!("⚙️ Vsync off (swap interval 0 through {})", api),
+            None => eprintln!("⚠️ Can't turn vsync off: this platform has no swap interval control"),
+        }
+    }
+
+    /// Set the current surface's swap interval; the API that took it, None if none is available or it failed
+    unsafe fn set_swap_interval(
+        get_proc_address: &dyn Fn(&CStr) -> *const c_void,
+        interval: i32
+    ) -> Option<&'static str> {
+        // EGL first: a GLX loader doesn't hand out egl* functions, while an EGL loader may stub unknown gl* ones
+        let get_display = get_proc_address(c"eglGetCurrentDisplay");
+        let egl_swap_interval = get_proc_address(c"eglSwapInterval");
+        if !get_display.is_null() && !egl_swap_interval.is_null() {
+            let get_display: extern "system" fn() -> *mut c_void = std::mem::transmute(get_display);
+            let swap_interval: extern "system" fn(*mut c_void, i32) -> u32 = std::mem::transmute(egl_swap_interval);
+            let display = get_display();
+            return (!display.is_null() && swap_interval(display, interval) != 0).then_some("EGL");
+        }
+
+        let wgl_swap_interval = get_proc_address(c"wglSwapIntervalEXT");
+        if !wgl_swap_interval.is_null() {
+            let swap_interval: extern "system" fn(i32) -> i32 = std::mem::transmute(wgl_swap_interval);
+            return (swap_interval(interval) != 0).then_some("WGL");
+        }
+
+        let glx_swap_interval = get_proc_address(c"glXSwapIntervalMESA");
+        if !glx_swap_interval.is_null() {
+            let swap_interval: extern "C" fn(u32) -> i32 = std::mem::transmute(glx_swap_interval);
+            return (swap_interval(interval as u32) == 0).then_some("GLX");
+        }
+        None
+    }
+
+    fn flag_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|index| args.get(index + 1))
+            .map(|value| value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    fn with_args(line: &str) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.apply_cli_args(&args(line));
+        config
+    }
+
+    #[test]
+    fn flags_override_the_window_settings() {
+        let config = with_args("scene.json --title Crab --width 800 --height 600 --position 10,-20 --monitor 1");
+        assert_eq!(config.title, "Crab");
+        assert_eq!((config.width, config.height), (800, 600));
+        assert_eq!(config.position, Some([10, -20]));
+        assert_eq!(config.monitor, Some(1));
+
+        let config = with_args("--fullscreen --windowed --no-vsync --crash-reports");
+        assert!(!config.fullscreen);
+        assert!(!config.vsync);
+        assert!(config.crash_reports);
+    }
+
+    #[test]
+    fn bad_values_keep_the_config() {
+        let config = with_args("--width wide --position 10 --monitor -1 --quality ultra --theme neon --height");
+        let default = AppConfig::default();
+        assert_eq!((config.width, config.height), (default.width, default.height));
+        assert_eq!(config.position, None);
+        assert_eq!(config.monitor, None);
+        assert_eq!(config.graphics, default.graphics);
+        assert_eq!(config.editor.theme, default.editor.theme);
+    }
+
+    #[test]
+    fn quality_sets_the_whole_preset() {
+        assert_eq!(with_args("--quality LOW").graphics, GraphicsQuality::LOW);
+        assert_eq!(with_args("--quality medium").graphics, GraphicsQuality::MEDIUM);
+    }
+
+    #[test]
+    fn msaa_refines_the_preset_wherever_it_is_given() {
+        let graphics = with_args("--msaa 8 --quality low").graphics;
+        assert_eq!(graphics.antialiasing, AntialiasingMode::Msaa);
+        assert_eq!(graphics.msaa(), Some(8));
+        assert_eq!(graphics.preset, QualityPreset::Custom);
+        assert_eq!(graphics.shadow_resolution, GraphicsQuality::LOW.shadow_resolution);
+
+        let graphics = with_args("--quality high --msaa 0").graphics;
+        assert_eq!(graphics.antialiasing, AntialiasingMode::Off);
+        assert_eq!(graphics.msaa(), None);
+
+        // What the preset has already doesn't make it custom
+        let graphics = with_args("--quality high --msaa 4").graphics;
+        assert_eq!(graphics, GraphicsQuality::HIGH);
+    }
+
+    #[test]
+    fn the_config_file_fills_in_missing_values() {
+        let config: AppConfig = toml::from_str(
+            r#"
+            title = "Crab"
+            vsync = false
+
+            [graphics]
+            antialiasing = "fxaa"
+
+            [editor]
+            theme = "light"
+            "#
+        ).unwrap();
+        assert_eq!(config.title, "Crab");
+        assert!(!config.vsync);
+        assert_eq!(config.width, AppConfig::default().width);
+        assert_eq!(config.graphics.antialiasing, AntialiasingMode::Fxaa);
+        assert_eq!(config.graphics.msaa_samples, GraphicsQuality::default().msaa_samples);
+        assert_eq!(config.editor.theme, EditorTheme::Light);
+    }
+
+    #[test]
+    fn flag_values_follow_their_flag() {
+        let args = args("--config custom.toml --config other.toml");
+        assert_eq!(AppConfig::flag_value(&args, "--config"), Some("custom.toml"));
+        assert_eq!(AppConfig::flag_value(&args[..1], "--config"), None);
+    }
+}
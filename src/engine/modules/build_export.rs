@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use crate::index::engine::managers::AssetPack;
+use crate::index::engine::modules::app_config::DEFAULT_CONFIG_PATH;
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::modules::project::{ Project, DEFAULT_PROJECT_PATH };
+use crate::index::engine::modules::scene_library::SceneLibrary;
+
+/// Folder builds go to when the command doesn't name one
+const DEFAULT_BUILD_DIRECTORY: &str = "build";
+/// Archive in the build folder holding its scenes and assets
+const PACK_FILE_NAME: &str = "assets.pack";
+
+/// Packages the running binary, the project's scenes and its assets (as one pack) into a folder that starts straight into Play mode
+pub struct BuildExport;
+
+impl BuildExport {
+    /// Export a native build into `output`: the executable, the app config, `assets.pack` holding the scenes
+    /// (`scenes/`) and asset directories (`assets/<dir>/`), and a runtime project.toml mounting that pack.
+    /// Returns the number of files written.
+    pub fn export_native(output: &str, compress: bool) -> EngineResult<usize> {
+        let project = Project::get();
+        let output_path = Path::new(output);
+        std::fs::create_dir_all(output_path).map_err(|e| EngineError::io(output, e.to_string()))?;
+        let mut written = 0;
+
+        // The editor UI is compiled in, the runtime project keeps it hidden
+        let executable = std::env::current_exe().map_err(|e| EngineError::io("current executable", e.to_string()))?;
+        let executable_name = executable.file_name().map(|name| name.to_os_string()).unwrap_or_else(|| "wet-crab".into());
+        copy_file(&executable, &output_path.join(executable_name))?;
+        written += 1;
+
+        let mut packed_files = Vec::new();
+        let scenes = if project.scenes.is_empty() { vec![SceneLibrary::current()] } else { project.scenes.clone() };
+        let mut exported_scenes = Vec::new();
+        for scene in &scenes {
+            let Some(file_name) = Path::new(scene).file_name() else {
+                continue;
+            };
+            let packed_path = format!("scenes/{}", file_name.to_string_lossy());
+            packed_files.push((packed_path.clone(), AssetPack::read_file(scene)?));
+            exported_scenes.push(packed_path);
+        }
+
+        // Whole asset directories, since GLTF files reference their .bin and textures by relative path
+        let mut exported_directories = Vec::new();
+        for directory in &project.asset_directories {
+            let Some(directory_name) = Path::new(directory).file_name() else {
+                continue;
+            };
+            let packed_directory = format!("assets/{}", directory_name.to_string_lossy());
+            let entries = std::fs::read_dir(directory).map_err(|e| EngineError::io(directory, e.to_string()))?;
+            for entry in entries.filter_map(|entry| entry.ok()).filter(|entry| entry.path().is_file()) {
+                let contents = AssetPack::read_file(entry.path())?;
+                packed_files.push((format!("{}/{}", packed_directory, entry.file_name().to_string_lossy()), contents));
+            }
+            exported_directories.push(packed_directory);
+        }
+
+        let pack_path = output_path.join(PACK_FILE_NAME);
+        AssetPack::write(&pack_path.to_string_lossy(), &packed_files, compress)?;
+        written += 1;
+
+        if Path::new(DEFAULT_CONFIG_PATH).exists() {
+            copy_file(Path::new(DEFAULT_CONFIG_PATH), &output_path.join(DEFAULT_CONFIG_PATH))?;
+            written += 1;
+        }
+
+        let startup_scene = project.startup_scene
+            .as_ref()
+            .and_then(|startup| Path::new(startup).file_name())
+            .map(|file_name| format!("scenes/{}", file_name.to_string_lossy()))
+            .or_else(|| exported_scenes.first().cloned());
+        let runtime_project = Project {
+            name: project.name.clone(),
+            scenes: exported_scenes,
+            startup_scene,
+            asset_directories: exported_directories,
+            layers: project.layers.clone(),
+            runtime: true,
+            pack: Some(PACK_FILE_NAME.to_string()),
+            transparency: project.transparency,
+            simplify: project.simplify.clone(), // Exported asset paths change, so file name keys carry over
+        };
+        let project_path = output_path.join(DEFAULT_PROJECT_PATH);
+        let toml = toml::to_string_pretty(&runtime_project)
+            .map_err(|e| EngineError::io(&project_path.to_string_lossy(), e.to_string()))?;
+        std::fs::write(&project_path, toml).map_err(|e| EngineError::io(&project_path.to_string_lossy(), e.to_string()))?;
+        written += 1;
+
+        println!("📦 Exported build to {} ({} files, {} packed); run it from that folder", output, written, packed_files.len());
+        Ok(written)
+    }
+
+    /// Console: `export_build [folder] [native|web] [compressed|stored]`
+    pub fn export_command(args: &[&str]) -> Result<String, String> {
+        let output = args.first().copied().unwrap_or(DEFAULT_BUILD_DIRECTORY);
+        let compress = match args.get(2).copied().unwrap_or("compressed") {
+            "compressed" => true,
+            "stored" => false,
+            packing => return Err(format!("Unknown packing {} (compressed or stored)", packing)),
+        };
+        match args.get(1).copied().unwrap_or("native") {
+            "native" => {
+                let written = Self::export_native(output, compress).map_err(|e| e.to_string())?;
+                Ok(format!("Exported {} files to {}", written, output))
+            }
+            "web" => Err("Web bundles need a wasm build of the runtime, which this engine doesn't have yet".to_string()),
+            target => Err(format!("Unknown build target {} (native or web)", target)),
+        }
+    }
+}
+
+fn copy_file(from: &Path, to: &Path) -> EngineResult<()> {
+    std::fs::copy(from, to).map(|_| ()).map_err(|e| EngineError::io(&from.to_string_lossy(), e.to_string()))
+}
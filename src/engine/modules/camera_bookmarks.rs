@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use serde::{ Serialize, Deserialize };
+
+/// Saved editor camera view
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct CameraBookmark {
+    pub position: [f32; 3],
+    pub pitch: f32,
+    pub yaw: f32,
+}
+
+/// Bookmarks by slot (1-9), saved in the editor sidecar
+static BOOKMARKS: Lazy<RwLock<BTreeMap<u8, CameraBookmark>>> = Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+/// Numbered editor camera bookmarks of the open scene
+pub struct CameraBookmarks;
+
+impl CameraBookmarks {
+    pub fn get(slot: u8) -> Option<CameraBookmark> {
+        BOOKMARKS.read().unwrap().get(&slot).copied()
+    }
+
+    pub fn set(slot: u8, bookmark: CameraBookmark) {
+        BOOKMARKS.write().unwrap().insert(slot, bookmark);
+    }
+
+    pub fn all() -> BTreeMap<u8, CameraBookmark> {
+        BOOKMARKS.read().unwrap().clone()
+    }
+
+    /// Replace every bookmark, e.g. with the ones read from the editor sidecar
+    pub fn replace(bookmarks: BTreeMap<u8, CameraBookmark>) {
+        *BOOKMARKS.write().unwrap() = bookmarks;
+    }
+}
@@ -0,0 +1,180 @@
+use std::sync::OnceLock;
+
+use crate::index::engine::modules::project::Project;
+use crate::index::engine::modules::scene_bench::{ default_report_path, DEFAULT_BENCH_FRAMES };
+
+/// Scene opened when neither the command line nor the project file names one
+pub const DEFAULT_SCENE_PATH: &str = "src/assets/scenes/test_world.json";
+
+/// Flags owned by AppConfig and Project that consume the following argument
+const VALUE_FLAGS: [&str; 9] = [
+    "--config",
+    "--project",
+    "--title",
+    "--width",
+    "--height",
+    "--position",
+    "--monitor",
+    "--msaa",
+    "--quality",
+];
+
+static CLI_ARGS: OnceLock<CliArgs> = OnceLock::new();
+
+/// Startup options: `wet-crab [scene.json] [--play] [--safe-mode] [--headless --run-ticks N] [--import model.gltf [--import-simplify ratio]] [--import-colliders file.colliders.json] [--seed N] [--audit-record|--audit-compare baseline.json] [--bench scene.json [--frames N] [--bench-report report.json|.csv]]`
+#[derive(Debug, Clone, Default)]
+pub struct CliArgs {
+    pub scene: Option<String>,
+    pub play: bool,           // Skip the editor UI and go straight into play mode
+    pub headless: bool,       // Run without a window (CI)
+    pub run_ticks: u64,       // Fixed ticks to simulate in headless mode before exiting
+    pub imports: Vec<String>, // GLTF/GLB models to register as assets on startup
+    pub import_simplify: Option<f32>, // Triangle ratio the imports are also decimated to, as a LOD
+    pub collider_imports: Vec<String>, // Collision files applied after the scene loads
+    pub seed: Option<u64>,    // World seed for gameplay randomness (Random)
+    pub safe_mode: bool,      // Start in safe mode as after a crash (SafeMode)
+    pub audit_record: Option<String>,  // Headless: write a determinism baseline of the run (DeterminismAudit)
+    pub audit_compare: Option<String>, // Headless: check the run against a determinism baseline, failing on divergence
+    pub bench: bool,                   // Benchmark the scene and exit (SceneBench)
+    pub bench_frames: Option<u64>,
+    pub bench_report: Option<String>,  // JSON, or CSV for a .csv path
+}
+
+impl CliArgs {
+    /// Parse the process arguments (without the binary name)
+    pub fn parse(args: &[String]) -> Self {
+        let mut cli_args = CliArgs::default();
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--play" => {
+                    cli_args.play = true;
+                }
+                "--headless" => {
+                    cli_args.headless = true;
+                }
+                "--safe-mode" => {
+                    cli_args.safe_mode = true;
+                }
+                "--run-ticks" => {
+                    match iter.next().map(|value| value.parse()) {
+                        Some(Ok(ticks)) => {
+                            cli_args.run_ticks = ticks;
+                        }
+                        _ => eprintln!("⚠️ --run-ticks expects a tick count"),
+                    }
+                }
+                "--import" => {
+                    match iter.next() {
+                        Some(path) => cli_args.imports.push(path.clone()),
+                        None => eprintln!("⚠️ --import expects a path to a .gltf or .glb file"),
+                    }
+                }
+                "--import-simplify" => {
+                    match iter.next().map(|value| value.parse::<f32>()) {
+                        Some(Ok(ratio)) if ratio > 0.0 && ratio < 1.0 => {
+                            cli_args.import_simplify = Some(ratio);
+                        }
+                        _ => eprintln!("⚠️ --import-simplify expects a triangle ratio between 0 and 1"),
+                    }
+                }
+                "--import-colliders" => {
+                    match iter.next() {
+                        Some(path) => cli_args.collider_imports.push(path.clone()),
+                        None => eprintln!("⚠️ --import-colliders expects a path to a collision file"),
+                    }
+                }
+                "--seed" => {
+                    match iter.next().map(|value| value.parse()) {
+                        Some(Ok(seed)) => {
+                            cli_args.seed = Some(seed);
+                        }
+                        _ => eprintln!("⚠️ --seed expects a number"),
+                    }
+                }
+                "--audit-record" => {
+                    match iter.next() {
+                        Some(path) => cli_args.audit_record = Some(path.clone()),
+                        None => eprintln!("⚠️ --audit-record expects a path to write the baseline to"),
+                    }
+                }
+                "--audit-compare" => {
+                    match iter.next() {
+                        Some(path) => cli_args.audit_compare = Some(path.clone()),
+                        None => eprintln!("⚠️ --audit-compare expects a path to a recorded baseline"),
+                    }
+                }
+                "--bench" => {
+                    match iter.next() {
+                        Some(path) => {
+                            cli_args.bench = true;
+                            cli_args.scene = Some(path.clone());
+                        }
+                        None => eprintln!("⚠️ --bench expects a path to a scene"),
+                    }
+                }
+                "--frames" => {
+                    match iter.next().map(|value| value.parse()) {
+                        Some(Ok(frames)) if frames > 0 => {
+                            cli_args.bench_frames = Some(frames);
+                        }
+                        _ => eprintln!("⚠️ --frames expects a frame count"),
+                    }
+                }
+                "--bench-report" => {
+                    match iter.next() {
+                        Some(path) => cli_args.bench_report = Some(path.clone()),
+                        None => eprintln!("⚠️ --bench-report expects a path to write the report to"),
+                    }
+                }
+                flag if VALUE_FLAGS.contains(&flag) => {
+                    // Window settings are handled by AppConfig, the project path by Project
+                    iter.next();
+                }
+                flag if flag.starts_with("--") => {
+                    // Boolean window flags (--fullscreen, --no-vsync, ...) are handled by AppConfig
+                }
+                path => {
+                    if cli_args.scene.is_some() {
+                        eprintln!("⚠️ Ignoring extra scene argument: {}", path);
+                    } else {
+                        cli_args.scene = Some(path.to_string());
+                    }
+                }
+            }
+        }
+
+        cli_args
+    }
+
+    /// Store the parsed arguments for the rest of the engine
+    pub fn initialize(cli_args: CliArgs) {
+        if CLI_ARGS.set(cli_args).is_err() {
+            panic!("CliArgs should only be initialized once");
+        }
+    }
+
+    /// Parsed startup arguments (defaults if the binary was started without any)
+    pub fn get() -> &'static CliArgs {
+        CLI_ARGS.get_or_init(CliArgs::default)
+    }
+
+    /// Scene to load and save, falling back to the project's startup scene and then the bundled test world
+    pub fn scene_path(&self) -> &str {
+        self.scene
+            .as_deref()
+            .or(Project::get().startup_scene.as_deref())
+            .unwrap_or(DEFAULT_SCENE_PATH)
+    }
+
+    /// Frames `--bench` runs for
+    pub fn bench_frames(&self) -> u64 {
+        self.bench_frames.unwrap_or(DEFAULT_BENCH_FRAMES)
+    }
+
+    /// Where `--bench` writes its report
+    pub fn bench_report_path(&self) -> String {
+        self.bench_report.clone().unwrap_or_else(|| default_report_path(self.scene_path()))
+    }
+}
@@ -0,0 +1,794 @@
+use std::collections::{ BTreeMap, HashMap, HashSet, VecDeque };
+use std::io::{ BufRead, BufReader, ErrorKind, Write };
+use std::net::{ TcpListener, TcpStream };
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::sync::mpsc::{ channel, Receiver, Sender };
+use std::sync::{ Arc, Mutex };
+use std::time::{ Duration, Instant };
+use serde::{ Serialize, Deserialize };
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::index::engine::components::Metadata;
+use crate::index::engine::modules::ecs::{
+    change_count,
+    component_kind,
+    delete_entity,
+    get_all_components,
+    get_persistent_entities,
+    insert,
+    persistent_changes_since,
+    restore_entity,
+    Component,
+    EntityId,
+};
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::modules::selection::Selection;
+use crate::index::engine::utils::math::Vec3;
+use crate::index::game::systems::RenderSystem;
+use crate::get_query_by_id;
+
+/// Port `collab host` listens on unless given one
+pub const DEFAULT_PORT: u16 = 7878;
+/// How often local edits are sent and incoming ones applied
+const SYNC_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a connection's writer thread may block on a collaborator before the connection is dropped
+const WRITE_TIMEOUT: Duration = Duration::from_secs(2);
+/// How often the host's accept thread checks for new connections and whether the session ended
+const ACCEPT_POLL: Duration = Duration::from_millis(50);
+/// Ops `collab log` can show
+const LOG_LENGTH: usize = 200;
+
+/// Lamport time of an op; equal clocks are ordered by peer ID, so every editor orders concurrent ops the same way
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Stamp {
+    pub clock: u64,
+    pub peer: String,
+}
+
+/// An edit to the shared world
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "edit")]
+pub enum Edit {
+    /// One top-level field of a component
+    Field { component: String, field: String, value: Value },
+    /// A whole component added or replaced, or removed (None); a new entity arrives as its components
+    Component { component: String, value: Option<Value> },
+    Despawn,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CollabOp {
+    pub stamp: Stamp,
+    pub entity_id: EntityId,
+    pub edit: Edit,
+}
+
+impl CollabOp {
+    /// The component the op writes to (none for a despawn), whose stamps decide whether it's the latest
+    fn key(&self) -> (EntityId, String) {
+        match &self.edit {
+            Edit::Field { component, .. } | Edit::Component { component, .. } => {
+                (self.entity_id.clone(), component.clone())
+            }
+            Edit::Despawn => (self.entity_id.clone(), String::new()),
+        }
+    }
+
+    /// The field a field op writes; other ops write the whole component
+    fn field(&self) -> Option<&str> {
+        match &self.edit {
+            Edit::Field { field, .. } => Some(field),
+            _ => None,
+        }
+    }
+}
+
+/// Stamps of the latest ops written to one component of an entity: the last whole-component op and the field ops
+/// since. The last writer wins per field, so a field op loses to a newer component op, and a component op leaves
+/// the fields written after it as they are
+#[derive(Default, Debug)]
+struct ComponentStamps {
+    component: Option<Stamp>,
+    fields: HashMap<String, Stamp>,
+}
+
+impl ComponentStamps {
+    /// Whether a newer op already wrote what an op at `stamp` writes: `field`, or the whole component for None
+    fn supersedes(&self, stamp: &Stamp, field: Option<&str>) -> bool {
+        let newer = |written: &Stamp| written > stamp;
+        self.component.as_ref().is_some_and(newer) || field.and_then(|field| self.fields.get(field)).is_some_and(newer)
+    }
+
+    /// Fields written after a component op at `stamp`, which keep their values over the op's
+    fn newer_fields<'a>(&'a self, stamp: &'a Stamp) -> impl Iterator<Item = &'a String> {
+        self.fields.iter().filter(move |(_, written)| *written > stamp).map(|(field, _)| field)
+    }
+
+    fn record(&mut self, stamp: &Stamp, field: Option<&str>) {
+        match field {
+            Some(field) => {
+                self.fields.insert(field.to_string(), stamp.clone());
+            }
+            None => {
+                // Field ops older than the component are superseded by it
+                self.fields.retain(|_, written| *written > *stamp);
+                self.component = Some(stamp.clone());
+            }
+        }
+    }
+}
+
+/// Where a collaborator is looking and what they have selected
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Presence {
+    pub name: String,
+    pub camera: Option<(Vec3, Vec3)>, // Position and view direction
+    pub selection: Vec<EntityId>,
+}
+
+/// One line of the session protocol: newline-separated JSON over TCP
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "message")]
+enum Message {
+    Hello { peer: String },
+    /// The host's world and clock, which a joining editor takes over
+    Welcome { clock: u64, world: BTreeMap<EntityId, BTreeMap<String, Value>> },
+    Op(CollabOp),
+    Presence { peer: String, presence: Presence },
+    Bye { peer: String },
+}
+
+enum Incoming {
+    Line(u64, String),
+    Closed(u64),
+}
+
+/// A collaborator's socket: read and written on threads of its own, so a slow peer never stalls the editor
+struct Connection {
+    id: u64,
+    outgoing: Sender<String>, // Lines for the writer thread; dropping it flushes them and closes the socket
+    peer: Option<String>,
+}
+
+/// Saved entities as component type to JSON, as the session last agreed on them
+type SharedWorld = BTreeMap<EntityId, BTreeMap<String, Value>>;
+
+struct Session {
+    peer: String,
+    name: String,
+    hosting: bool,
+    awaiting_welcome: bool, // Joined, but the host's world hasn't arrived; local edits wait for it
+    clock: u64,
+    synced: SharedWorld,
+    synced_changes: u64, // ECS change count `synced` was last brought up to date at
+    stamps: HashMap<(EntityId, String), ComponentStamps>,
+    despawned: HashSet<EntityId>, // Edits to despawned entities lose to the despawn, whatever their time
+    log: VecDeque<CollabOp>,
+    peers: BTreeMap<String, Presence>,
+    presence: Option<Presence>, // Last sent
+    connections: Vec<Connection>,
+    next_connection: u64,
+    incoming: Receiver<Incoming>,
+    incoming_sender: Sender<Incoming>,
+    accepted: Option<Receiver<TcpStream>>, // Hosts only
+    stop: Arc<AtomicBool>,
+    last_sync: Option<Instant>,
+}
+
+static SESSION: Mutex<Option<Session>> = Mutex::new(None);
+
+/// Collaborative editing: editors connect to one hosting editor and share the saved entities. Each editor sends
+/// its own edits as ops on component fields, stamped with a Lamport clock; the host relays them, and every editor
+/// keeps the last op per field (last writer wins), so all of them end up with the same world. Despawns win over
+/// edits to the same entity. Collaborators' cameras and selections are drawn in the viewport by the
+/// CollabSystem. Desktop only; the protocol is newline-separated JSON over TCP, without authentication, for
+/// trusted networks
+pub struct CollabSession;
+
+impl CollabSession {
+    /// Host a session on `port`; the open scene is what joining editors get
+    pub fn host(port: u16, name: &str) -> Result<String, String> {
+        Self::check_idle()?;
+        let listener = TcpListener::bind(("0.0.0.0", port)).map_err(|e| format!("Can't listen on {}: {}", port, e))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let mut session = Session::new(name, true);
+        let (sender, accepted) = channel();
+        let stop = session.stop.clone();
+        std::thread::spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if sender.send(stream).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => std::thread::sleep(ACCEPT_POLL),
+                    Err(_) => return,
+                }
+            }
+        });
+        session.accepted = Some(accepted);
+        session.take_world();
+        *SESSION.lock().unwrap() = Some(session);
+        Ok(format!("Hosting a collaboration session on port {}", port))
+    }
+
+    /// Join the session hosted at `address` (host:port); the local scene is replaced by the host's
+    pub fn join(address: &str, name: &str) -> Result<String, String> {
+        Self::check_idle()?;
+        let stream = TcpStream::connect(address).map_err(|e| format!("Can't connect to {}: {}", address, e))?;
+        let mut session = Session::new(name, false);
+        // What's on screen until the Welcome replaces it, so nothing local is sent as an edit meanwhile
+        session.take_world();
+        session.add_connection(stream);
+        let hello = Message::Hello { peer: session.peer.clone() };
+        session.broadcast(&hello, None);
+        *SESSION.lock().unwrap() = Some(session);
+        Ok(format!("Joining the collaboration session at {}", address))
+    }
+
+    /// Leave the session, telling the others; the world stays as it is
+    pub fn leave() -> Result<String, String> {
+        let mut session = SESSION.lock().unwrap().take().ok_or("Not in a collaboration session")?;
+        let bye = Message::Bye { peer: session.peer.clone() };
+        session.broadcast(&bye, None);
+        session.close();
+        Ok("Left the collaboration session".to_string())
+    }
+
+    fn check_idle() -> Result<(), String> {
+        if cfg!(target_arch = "wasm32") {
+            return Err("Collaboration needs the desktop editor".to_string());
+        }
+        if SESSION.lock().unwrap().is_some() {
+            return Err("Already in a collaboration session, `collab leave` first".to_string());
+        }
+        Ok(())
+    }
+
+    /// Render system: send local edits and presence, apply the others', at SYNC_INTERVAL. Nothing is synced while
+    /// playing, as Stop puts the world back (see PlaySession)
+    pub fn update(_gl: &glow::Context, _width: u32, _height: u32) {
+        if InterfaceSystem::is_play_mode() {
+            return;
+        }
+        let (world_changed, ended) = {
+            let mut guard = SESSION.lock().unwrap();
+            let Some(session) = guard.as_mut() else {
+                return;
+            };
+            if session.last_sync.is_some_and(|last| last.elapsed() < SYNC_INTERVAL) {
+                return;
+            }
+            session.last_sync = Some(Instant::now());
+            session.accept();
+            if !session.awaiting_welcome {
+                session.send_local_edits();
+            }
+            let world_changed = session.receive();
+            session.send_presence();
+            let ended = !session.hosting && session.connections.is_empty();
+            if ended {
+                session.close();
+                *guard = None;
+            }
+            (world_changed, ended)
+        };
+        if ended {
+            println!("🤝 The collaboration host closed the session");
+        }
+        if world_changed {
+            InterfaceSystem::update_entities_list();
+            InterfaceSystem::refresh_selection();
+        }
+    }
+
+    /// Every collaborator's presence, by peer ID
+    pub fn peers() -> Vec<(String, Presence)> {
+        SESSION.lock()
+            .unwrap()
+            .as_ref()
+            .map(|session| session.peers.iter().map(|(peer, presence)| (peer.clone(), presence.clone())).collect())
+            .unwrap_or_default()
+    }
+
+    /// Rows of the Collaboration panel: each collaborator and what they have selected
+    pub fn panel_rows() -> Vec<(String, String)> {
+        let guard = SESSION.lock().unwrap();
+        let Some(session) = guard.as_ref() else {
+            return vec![("Not connected".to_string(), "collab host [port] | collab join <address>".to_string())];
+        };
+        let role = if session.hosting { "Hosting" } else { "Joined" };
+        let mut rows = vec![(format!("{} as {}", role, session.name), format!("{} ops", session.clock))];
+        rows.extend(session.peers.values().map(|presence| {
+            let titles: Vec<String> = presence.selection.iter().map(title).collect();
+            let selection = if titles.is_empty() { "nothing selected".to_string() } else { titles.join(", ") };
+            (presence.name.clone(), selection)
+        }));
+        rows
+    }
+
+    /// Console: `collab host [port] [name]` starts a session, `collab join <host:port> [name]` joins one,
+    /// `collab leave` leaves, `collab log` shows the latest ops and `collab` alone who's connected
+    pub fn collab_command(args: &[&str]) -> Result<String, String> {
+        let default_name = std::env::var("USER").unwrap_or_else(|_| "editor".to_string());
+        match args {
+            [] => {
+                let rows = Self::panel_rows();
+                let lines: Vec<String> = rows.iter().map(|(label, value)| format!("  {}: {}", label, value)).collect();
+                Ok(format!("Collaboration:\n{}", lines.join("\n")))
+            }
+            ["host", rest @ ..] => {
+                let port = match rest.first() {
+                    Some(port) => port.parse::<u16>().map_err(|_| format!("Not a port: {}", port))?,
+                    None => DEFAULT_PORT,
+                };
+                Self::host(port, rest.get(1).copied().unwrap_or(&default_name))
+            }
+            ["join", address, rest @ ..] => Self::join(address, rest.first().copied().unwrap_or(&default_name)),
+            ["leave"] => Self::leave(),
+            ["log"] => {
+                let guard = SESSION.lock().unwrap();
+                let session = guard.as_ref().ok_or("Not in a collaboration session")?;
+                let lines: Vec<String> = session.log
+                    .iter()
+                    .rev()
+                    .take(20)
+                    .map(|op| format!("  {} {} {:?}", op.stamp.clock, title(&op.entity_id), op.edit))
+                    .collect();
+                Ok(format!("Latest ops:\n{}", lines.join("\n")))
+            }
+            _ => Err("Usage: collab [host [port] [name] | join <host:port> [name] | leave | log]".to_string()),
+        }
+    }
+}
+
+impl Session {
+    fn new(name: &str, hosting: bool) -> Self {
+        let (incoming_sender, incoming) = channel();
+        Self {
+            peer: Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            hosting,
+            awaiting_welcome: !hosting,
+            clock: 0,
+            synced: SharedWorld::new(),
+            synced_changes: 0,
+            stamps: HashMap::new(),
+            despawned: HashSet::new(),
+            log: VecDeque::new(),
+            peers: BTreeMap::new(),
+            presence: None,
+            connections: Vec::new(),
+            next_connection: 0,
+            incoming,
+            incoming_sender,
+            accepted: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            last_sync: None,
+        }
+    }
+
+    /// Keep a connection, reading its lines on one thread and writing queued ones on another
+    fn add_connection(&mut self, stream: TcpStream) {
+        let id = self.next_connection;
+        self.next_connection += 1;
+        let (Ok(()), Ok(()), Ok(reader)) = (
+            stream.set_nonblocking(false),
+            stream.set_write_timeout(Some(WRITE_TIMEOUT)),
+            stream.try_clone(),
+        ) else {
+            return;
+        };
+
+        let (outgoing, lines) = channel::<String>();
+        let mut writer = stream;
+        std::thread::spawn(move || {
+            for line in lines {
+                if writer.write_all(line.as_bytes()).is_err() {
+                    break;
+                }
+            }
+            // Ends the reader too, which reports the connection closed
+            let _ = writer.shutdown(std::net::Shutdown::Both);
+        });
+
+        let sender = self.incoming_sender.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(Incoming::Line(id, line)).is_err() {
+                    return;
+                }
+            }
+            let _ = sender.send(Incoming::Closed(id));
+        });
+        self.connections.push(Connection { id, outgoing, peer: None });
+    }
+
+    fn accept(&mut self) {
+        let accepted: Vec<TcpStream> = match &self.accepted {
+            Some(accepted) => accepted.try_iter().collect(),
+            None => return,
+        };
+        for stream in accepted {
+            self.add_connection(stream);
+        }
+    }
+
+    /// Queue a message for every connection except `except`; connections whose writer has stopped are dropped
+    fn broadcast(&mut self, message: &Message, except: Option<u64>) {
+        let Some(line) = encode(message) else {
+            return;
+        };
+        self.connections
+            .retain(|connection| Some(connection.id) == except || connection.outgoing.send(line.clone()).is_ok());
+    }
+
+    fn send_to(&mut self, id: u64, message: &Message) {
+        let Some(line) = encode(message) else {
+            return;
+        };
+        self.connections.retain(|connection| connection.id != id || connection.outgoing.send(line.clone()).is_ok());
+    }
+
+    /// Stop accepting and drop the connections; their writers send what's queued (a Bye) and close the sockets
+    fn close(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.connections.clear();
+    }
+
+    fn tick(&mut self) -> Stamp {
+        self.clock += 1;
+        Stamp { clock: self.clock, peer: self.peer.clone() }
+    }
+
+    /// Take the world as it is now as the synced one
+    fn take_world(&mut self) {
+        self.synced_changes = change_count();
+        self.synced = shared_world();
+    }
+
+    fn record(&mut self, op: CollabOp) {
+        self.stamps.entry(op.key()).or_default().record(&op.stamp, op.field());
+        self.log.push_back(op);
+        if self.log.len() > LOG_LENGTH {
+            self.log.pop_front();
+        }
+    }
+
+    /// Turn what changed locally since the last sync into ops and send them; only the entities written to since
+    /// are compared
+    fn send_local_edits(&mut self) {
+        let changes = persistent_changes_since(self.synced_changes, self.synced.keys());
+        self.synced_changes = changes.change_count;
+        let current: SharedWorld = changes.changed
+            .into_iter()
+            .map(|(entity_id, components)| (entity_id, shared_components(&components)))
+            .collect();
+        let mut edits = Vec::new();
+        for (entity_id, components) in &current {
+            let synced = self.synced.get(entity_id);
+            for (component, value) in components {
+                match synced.and_then(|synced| synced.get(component)) {
+                    Some(before) if before == value => {}
+                    Some(Value::Object(before)) if value.is_object() => {
+                        let fields = value.as_object().into_iter().flatten();
+                        for (field, value) in fields.filter(|(field, value)| before.get(*field) != Some(*value)) {
+                            let edit = Edit::Field {
+                                component: component.clone(),
+                                field: field.clone(),
+                                value: value.clone(),
+                            };
+                            edits.push((entity_id.clone(), edit));
+                        }
+                    }
+                    _ => {
+                        let edit = Edit::Component { component: component.clone(), value: Some(value.clone()) };
+                        edits.push((entity_id.clone(), edit));
+                    }
+                }
+            }
+            let removed = synced.into_iter().flat_map(|synced| synced.keys());
+            for component in removed.filter(|component| !components.contains_key(*component)) {
+                edits.push((entity_id.clone(), Edit::Component { component: component.clone(), value: None }));
+            }
+        }
+        for entity_id in changes.removed {
+            self.synced.remove(&entity_id);
+            edits.push((entity_id, Edit::Despawn));
+        }
+        self.synced.extend(current);
+
+        for (entity_id, edit) in edits {
+            if matches!(edit, Edit::Despawn) {
+                self.despawned.insert(entity_id.clone());
+            }
+            let op = CollabOp { stamp: self.tick(), entity_id, edit };
+            self.broadcast(&Message::Op(op.clone()), None);
+            self.record(op);
+        }
+    }
+
+    /// Handle what the connections sent; returns whether the world changed
+    fn receive(&mut self) -> bool {
+        let mut world_changed = false;
+        let incoming: Vec<Incoming> = self.incoming.try_iter().collect();
+        for incoming in incoming {
+            let (id, line) = match incoming {
+                Incoming::Line(id, line) => (id, line),
+                Incoming::Closed(id) => {
+                    let peer = self.connections
+                        .iter()
+                        .find(|connection| connection.id == id)
+                        .and_then(|connection| connection.peer.clone());
+                    self.connections.retain(|connection| connection.id != id);
+                    if let Some(peer) = peer {
+                        self.peers.remove(&peer);
+                        self.broadcast(&Message::Bye { peer }, None);
+                    }
+                    continue;
+                }
+            };
+            let Ok(message) = serde_json::from_str::<Message>(&line) else {
+                continue;
+            };
+            match message {
+                Message::Hello { peer } => {
+                    if let Some(connection) = self.connections.iter_mut().find(|connection| connection.id == id) {
+                        connection.peer = Some(peer);
+                    }
+                    if self.hosting {
+                        let welcome = Message::Welcome { clock: self.clock, world: self.synced.clone() };
+                        self.send_to(id, &welcome);
+                        let mut presences: Vec<(String, Presence)> = self.peers.clone().into_iter().collect();
+                        presences.extend(self.presence.clone().map(|presence| (self.peer.clone(), presence)));
+                        for (peer, presence) in presences {
+                            self.send_to(id, &Message::Presence { peer, presence });
+                        }
+                    }
+                }
+                Message::Welcome { clock, world } => {
+                    self.awaiting_welcome = false;
+                    self.clock = self.clock.max(clock);
+                    for entity_id in self.synced.keys().filter(|entity_id| !world.contains_key(*entity_id)) {
+                        delete_entity(entity_id);
+                        Selection::remove(entity_id);
+                    }
+                    for (entity_id, components) in &world {
+                        let components = components
+                            .values()
+                            .filter_map(|value| serde_json::from_value(value.clone()).ok())
+                            .collect();
+                        restore_entity(entity_id.clone(), components);
+                    }
+                    self.synced = world;
+                    world_changed = true;
+                }
+                // The Welcome on its way already has whatever the host applied before it
+                Message::Op(_) if self.awaiting_welcome => {}
+                Message::Op(op) => {
+                    if self.hosting {
+                        self.broadcast(&Message::Op(op.clone()), Some(id));
+                    }
+                    self.clock = self.clock.max(op.stamp.clock);
+                    world_changed |= self.apply(op);
+                }
+                Message::Presence { peer, presence } => {
+                    if self.hosting {
+                        let relayed = Message::Presence { peer: peer.clone(), presence: presence.clone() };
+                        self.broadcast(&relayed, Some(id));
+                    }
+                    self.peers.insert(peer, presence);
+                }
+                Message::Bye { peer } => {
+                    if self.hosting {
+                        self.broadcast(&Message::Bye { peer: peer.clone() }, Some(id));
+                    }
+                    self.peers.remove(&peer);
+                }
+            }
+        }
+        world_changed
+    }
+
+    /// Apply a collaborator's op unless a later one wrote the same thing; returns whether it was applied
+    fn apply(&mut self, op: CollabOp) -> bool {
+        let stamps = self.stamps.get(&op.key());
+        let superseded = stamps.is_some_and(|stamps| stamps.supersedes(&op.stamp, op.field()));
+        if superseded || self.despawned.contains(&op.entity_id) {
+            return false;
+        }
+        let entity_id = op.entity_id.clone();
+        match &op.edit {
+            Edit::Field { component, field, value } => {
+                let synced = self.synced.get_mut(&entity_id).and_then(|components| components.get_mut(component));
+                let Some(synced @ Value::Object(_)) = synced else {
+                    return false;
+                };
+                // The synced JSON only takes the field once the component accepts it
+                let mut json = synced.clone();
+                json[field.as_str()] = value.clone();
+                let Ok(updated) = serde_json::from_value::<Component>(json.clone()) else {
+                    return false;
+                };
+                insert(&entity_id, updated);
+                *synced = json;
+            }
+            Edit::Component { component, value } => {
+                let mut components = get_all_components(&entity_id);
+                components.retain(|current| component_kind(current) != *component);
+                let synced = self.synced.entry(entity_id.clone()).or_default();
+                match value {
+                    Some(value) => {
+                        // Fields collaborators wrote after this op keep their values
+                        let mut value = value.clone();
+                        if let (Some(stamps), Some(Value::Object(current)), Value::Object(json)) =
+                            (stamps, synced.get(component), &mut value)
+                        {
+                            for field in stamps.newer_fields(&op.stamp) {
+                                if let Some(written) = current.get(field) {
+                                    json.insert(field.clone(), written.clone());
+                                }
+                            }
+                        }
+                        let Ok(updated) = serde_json::from_value::<Component>(value.clone()) else {
+                            return false;
+                        };
+                        components.push(updated);
+                        synced.insert(component.clone(), value);
+                    }
+                    None => {
+                        synced.remove(component);
+                    }
+                }
+                restore_entity(entity_id, components);
+            }
+            Edit::Despawn => {
+                delete_entity(&entity_id);
+                Selection::remove(&entity_id);
+                self.synced.remove(&entity_id);
+                self.despawned.insert(entity_id);
+            }
+        }
+        self.record(op);
+        true
+    }
+
+    /// Tell the others where this editor looks and what it has selected, when that changed
+    fn send_presence(&mut self) {
+        let camera = RenderSystem::camera_position()
+            .zip(RenderSystem::camera_basis())
+            .map(|(position, (_, _, forward))| (position, forward.map(|value| -value)));
+        let presence = Presence { name: self.name.clone(), camera, selection: Selection::ids() };
+        if self.presence.as_ref() == Some(&presence) {
+            return;
+        }
+        let message = Message::Presence { peer: self.peer.clone(), presence: presence.clone() };
+        self.broadcast(&message, None);
+        self.presence = Some(presence);
+    }
+}
+
+/// A message as one protocol line
+fn encode(message: &Message) -> Option<String> {
+    serde_json::to_string(message).ok().map(|line| line + "\n")
+}
+
+/// The saved entities as they are now
+fn shared_world() -> SharedWorld {
+    get_persistent_entities()
+        .into_iter()
+        .map(|(entity_id, components)| (entity_id, shared_components(&components)))
+        .collect()
+}
+
+/// An entity's components as component type to JSON
+fn shared_components(components: &[Component]) -> BTreeMap<String, Value> {
+    components
+        .iter()
+        .filter_map(|component| Some((component_kind(component), serde_json::to_value(component).ok()?)))
+        .collect()
+}
+
+fn title(entity_id: &EntityId) -> String {
+    get_query_by_id!(entity_id.clone(), (Metadata))
+        .map(|metadata| metadata.title().to_string())
+        .unwrap_or_else(|| entity_id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamp(clock: u64, peer: &str) -> Stamp {
+        Stamp { clock, peer: peer.to_string() }
+    }
+
+    fn field_op(clock: u64, field: &str) -> CollabOp {
+        CollabOp {
+            stamp: stamp(clock, "a"),
+            entity_id: "crate".to_string(),
+            edit: Edit::Field { component: "Transform".to_string(), field: field.to_string(), value: Value::Null },
+        }
+    }
+
+    #[test]
+    fn equal_clocks_are_ordered_by_peer() {
+        assert!(stamp(2, "a") > stamp(1, "z"));
+        assert!(stamp(3, "b") > stamp(3, "a"));
+        assert_eq!(stamp(3, "a"), stamp(3, "a"));
+    }
+
+    #[test]
+    fn ops_on_one_component_share_a_key() {
+        let op = field_op(1, "position_x");
+        assert_eq!(op.key(), ("crate".to_string(), "Transform".to_string()));
+        assert_eq!(op.field(), Some("position_x"));
+
+        let component = CollabOp {
+            edit: Edit::Component { component: "Transform".to_string(), value: None },
+            ..op.clone()
+        };
+        assert_eq!(component.key(), op.key());
+        assert_eq!(component.field(), None);
+    }
+
+    #[test]
+    fn the_last_writer_of_a_field_wins() {
+        let mut stamps = ComponentStamps::default();
+        stamps.record(&stamp(5, "a"), Some("position_x"));
+
+        assert!(stamps.supersedes(&stamp(4, "b"), Some("position_x")));
+        assert!(stamps.supersedes(&stamp(5, "0"), Some("position_x")));
+        assert!(!stamps.supersedes(&stamp(6, "a"), Some("position_x")));
+        assert!(!stamps.supersedes(&stamp(5, "b"), Some("position_x")));
+        // Other fields and the whole component aren't covered by a field op
+        assert!(!stamps.supersedes(&stamp(1, "a"), Some("position_y")));
+        assert!(!stamps.supersedes(&stamp(1, "a"), None));
+    }
+
+    #[test]
+    fn a_newer_component_op_supersedes_older_field_ops() {
+        let mut stamps = ComponentStamps::default();
+        stamps.record(&stamp(2, "a"), Some("position_x"));
+        stamps.record(&stamp(3, "a"), None);
+
+        assert!(stamps.supersedes(&stamp(2, "b"), Some("position_x")));
+        assert!(stamps.supersedes(&stamp(2, "b"), Some("position_y")));
+        assert!(!stamps.supersedes(&stamp(4, "a"), Some("position_y")));
+        assert!(stamps.fields.is_empty());
+    }
+
+    #[test]
+    fn a_late_component_op_keeps_newer_fields() {
+        let mut stamps = ComponentStamps::default();
+        stamps.record(&stamp(2, "a"), Some("position_x"));
+        stamps.record(&stamp(6, "b"), Some("position_y"));
+
+        // A component op from before the second field edit arrives last
+        let late = stamp(4, "c");
+        assert!(!stamps.supersedes(&late, None));
+        let newer: Vec<&String> = stamps.newer_fields(&late).collect();
+        assert_eq!(newer, ["position_y"]);
+
+        stamps.record(&late, None);
+        assert_eq!(stamps.fields.keys().collect::<Vec<_>>(), ["position_y"]);
+        assert!(stamps.supersedes(&stamp(3, "a"), Some("position_x")));
+        assert!(!stamps.supersedes(&stamp(5, "a"), Some("position_x")));
+    }
+
+    #[test]
+    fn ops_survive_the_wire() {
+        let line = encode(&Message::Op(field_op(7, "position_x"))).unwrap();
+        assert!(line.ends_with('\n'));
+        let Ok(Message::Op(op)) = serde_json::from_str::<Message>(line.trim_end()) else {
+            panic!("not an op: {}", line);
+        };
+        assert_eq!(op.stamp, stamp(7, "a"));
+        assert_eq!(op.field(), Some("position_x"));
+    }
+}
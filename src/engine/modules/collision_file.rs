@@ -0,0 +1,146 @@
+use std::path::Path;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::{ Collider, ColliderLayer, EntityCategory, Metadata, PhysicsMaterial, Shape, Transform };
+use crate::index::engine::modules::ecs::{ entity_exists, insert, spawn };
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::modules::{ EntityId, InterfaceSystem, SceneLibrary };
+use crate::{ get_query_by_id, insert_many, query_get_all };
+
+/// Bumped when the collision file layout changes incompatibly
+const COLLISION_FILE_VERSION: u32 = 1;
+
+/// Collider shapes of a scene without any visual data, for collision geometry authored apart from the meshes
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollisionFile {
+    pub version: u32,
+    pub colliders: Vec<CollisionEntry>,
+}
+
+/// One collider and where it sits in the world
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollisionEntry {
+    // Scene entity the collider belongs to; entries without one, or with one the scene lacks, import as
+    // collider-only entities
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity_id: Option<EntityId>,
+    #[serde(default)]
+    pub name: String,
+    pub position: [f32; 3],
+    #[serde(default)]
+    pub rotation: [f32; 3], // Euler angles in radians, as in Transform
+    #[serde(default = "unit_scale")]
+    pub scale: [f32; 3],
+    pub shape: Shape,
+    pub layer: ColliderLayer,
+    #[serde(default)]
+    pub ignored_layers: Vec<ColliderLayer>,
+    #[serde(default)]
+    pub material: PhysicsMaterial,
+}
+
+fn unit_scale() -> [f32; 3] {
+    [1.0, 1.0, 1.0]
+}
+
+impl CollisionFile {
+    /// Collision file path for a scene, e.g. `scene.json` -> `scene.colliders.json`
+    pub fn path(scene_path: &str) -> String {
+        Path::new(scene_path).with_extension("colliders.json").to_string_lossy().to_string()
+    }
+
+    /// Write the collider of every saved entity; heightfields and compounds are skipped since their Terrain or
+    /// Blockout rebuilds them
+    pub fn export(path: &str) -> EngineResult<usize> {
+        let mut colliders = Vec::new();
+        for (entity_id, collider, transform) in query_get_all!(Collider, Transform) {
+            let metadata = get_query_by_id!(entity_id.clone(), (Metadata));
+            let rebuilt = matches!(collider.shape, Shape::Heightfield { .. } | Shape::Compound { .. });
+            if metadata.as_ref().is_some_and(|metadata| !metadata.is_persist) || rebuilt {
+                continue;
+            }
+            colliders.push(CollisionEntry {
+                name: metadata.map(|metadata| metadata.title().to_string()).unwrap_or_default(),
+                entity_id: Some(entity_id),
+                position: transform.get_position(),
+                rotation: transform.get_rotation(),
+                scale: transform.get_scale(),
+                shape: collider.shape,
+                layer: collider.layer,
+                ignored_layers: collider.ignored_layers,
+                material: collider.material,
+            });
+        }
+        // Stable order so re-exports diff cleanly
+        colliders.sort_by(|a, b| a.entity_id.cmp(&b.entity_id));
+
+        let count = colliders.len();
+        let file = CollisionFile { version: COLLISION_FILE_VERSION, colliders };
+        let json = serde_json::to_string(&file)
+            .map_err(|e| EngineError::scene(path, format!("Failed to serialize colliders: {}", e)))?;
+        std::fs::write(path, json).map_err(|e| EngineError::io(path, e.to_string()))?;
+        println!("🧱 Exported {} colliders to {}", count, path);
+        Ok(count)
+    }
+
+    /// Apply a collision file: colliders of existing entities are replaced (their transforms stay as they are),
+    /// the rest are spawned as collider-only entities. Returns (replaced, spawned).
+    pub fn import(path: &str) -> EngineResult<(usize, usize)> {
+        let json = std::fs::read_to_string(path).map_err(|e| EngineError::io(path, e.to_string()))?;
+        let file = serde_json::from_str::<CollisionFile>(&json)
+            .map_err(|e| EngineError::scene(path, format!("Failed to read collision file: {}", e)))?;
+        if file.version > COLLISION_FILE_VERSION {
+            return Err(EngineError::scene(path, format!("Collision file version {} is newer than {}", file.version, COLLISION_FILE_VERSION)));
+        }
+
+        let (mut replaced, mut spawned) = (0, 0);
+        for entry in file.colliders {
+            if matches!(entry.shape, Shape::Heightfield { .. }) {
+                eprintln!("⚠️ Skipping heightfield collider '{}' in {}: heightfields come from Terrain", entry.name, path);
+                continue;
+            }
+            if matches!(entry.shape, Shape::Compound { .. }) {
+                eprintln!("⚠️ Skipping compound collider '{}' in {}: compounds come from Blockout", entry.name, path);
+                continue;
+            }
+            let collider = Collider::new(entry.shape, entry.layer, entry.ignored_layers).with_material(entry.material);
+
+            match entry.entity_id.filter(|entity_id| entity_exists(entity_id)) {
+                Some(entity_id) => {
+                    insert(&entity_id, collider);
+                    replaced += 1;
+                }
+                None => {
+                    let mut transform = Transform::identity();
+                    transform.set_from_components(entry.position, entry.rotation, entry.scale);
+                    let title = if entry.name.is_empty() { "Collider" } else { entry.name.as_str() };
+                    let entity_id = spawn();
+                    insert_many!(
+                        entity_id,
+                        transform,
+                        Metadata::new(title, None, None).with_category(EntityCategory::Volume),
+                        collider
+                    );
+                    spawned += 1;
+                }
+            }
+        }
+        println!("🧱 Imported colliders from {}: {} replaced, {} spawned", path, replaced, spawned);
+        Ok((replaced, spawned))
+    }
+
+    /// Console: `export_colliders [path]`, defaulting to the file next to the open scene
+    pub fn export_command(args: &[&str]) -> Result<String, String> {
+        let path = args.first().map_or_else(|| Self::path(&SceneLibrary::current()), |path| path.to_string());
+        let count = Self::export(&path).map_err(|e| e.to_string())?;
+        Ok(format!("Exported {} colliders to {}", count, path))
+    }
+
+    /// Console: `import_colliders [path]`, defaulting to the file next to the open scene
+    pub fn import_command(args: &[&str]) -> Result<String, String> {
+        let path = args.first().map_or_else(|| Self::path(&SceneLibrary::current()), |path| path.to_string());
+        let (replaced, spawned) = Self::import(&path).map_err(|e| e.to_string())?;
+        InterfaceSystem::update_entities_list();
+        Ok(format!("Imported colliders from {}: {} replaced, {} spawned", path, replaced, spawned))
+    }
+}
@@ -0,0 +1,224 @@
+use std::collections::BTreeMap;
+use std::sync::{ Arc, RwLock };
+use once_cell::sync::Lazy;
+
+use crate::index::engine::modules::diagnostics::Diagnostics;
+
+/// Console command callback: gets the words after the command name and returns the text to print
+pub type CommandHandler = Arc<dyn Fn(&[&str]) -> Result<String, String> + Send + Sync>;
+
+/// Lines kept in the console log
+const MAX_LOG_LINES: usize = 200;
+/// Submitted lines kept for Up/Down recall
+const MAX_HISTORY: usize = 50;
+
+/// Tweakable numeric value, read by systems every time they use it
+#[derive(Clone, Debug)]
+pub struct Cvar {
+    pub value: f32,
+    pub default: f32,
+    pub min: f32,
+    pub max: f32,
+    pub help: String,
+}
+
+struct ConsoleState {
+    commands: BTreeMap<String, CommandHandler>,
+    cvars: BTreeMap<String, Cvar>,
+    saved: BTreeMap<String, f32>, // Values from the app config, applied as their cvars register
+    history: Vec<String>,
+    history_cursor: usize, // history.len() while not browsing
+    log: Vec<String>,
+}
+
+static CONSOLE: Lazy<RwLock<ConsoleState>> = Lazy::new(|| {
+    let mut commands: BTreeMap<String, CommandHandler> = BTreeMap::new();
+    commands.insert("help".to_string(), Arc::new(|_| Ok(Console::help())));
+    commands.insert(
+        "clear".to_string(),
+        Arc::new(|_| {
+            Console::clear();
+            Ok(String::new())
+        })
+    );
+    commands.insert(
+        "reset".to_string(),
+        Arc::new(|args| {
+            let name = args.first().ok_or("Usage: reset <cvar>")?;
+            let default = Console::cvars()
+                .into_iter()
+                .find(|(cvar, _)| cvar == name)
+                .map(|(_, cvar)| cvar.default)
+                .ok_or_else(|| format!("Unknown cvar {}", name))?;
+            Console::set_cvar(name, default).map(|value| format!("{} = {}", name, value))
+        })
+    );
+
+    // Engine cvars; plugins add theirs through App::add_cvar
+    let mut cvars = BTreeMap::new();
+    cvars.insert("mouse_sensitivity".to_string(), Cvar {
+        value: 0.002,
+        default: 0.002,
+        min: 0.0002,
+        max: 0.02,
+        help: "Camera radians per pixel of mouse movement".to_string(),
+    });
+
+    RwLock::new(ConsoleState { commands, cvars, saved: BTreeMap::new(), history: Vec::new(), history_cursor: 0, log: Vec::new() })
+});
+
+/// Editor console: commands registered by engine and game code, cvars, history and autocomplete
+pub struct Console;
+
+impl Console {
+    /// Add or replace a command, e.g. `Console::register_command("give_health", |args| ...)`
+    pub fn register_command(name: &str, handler: impl Fn(&[&str]) -> Result<String, String> + Send + Sync + 'static) {
+        CONSOLE.write().unwrap().commands.insert(name.to_string(), Arc::new(handler));
+    }
+
+    /// Add a cvar; registering an existing name keeps its current value, otherwise a saved value wins over the default
+    pub fn register_cvar(name: &str, default: f32, min: f32, max: f32, help: &str) {
+        let mut console = CONSOLE.write().unwrap();
+        let value = match console.cvars.get(name) {
+            Some(cvar) => cvar.value,
+            None => console.saved.get(name).map_or(default, |saved| saved.clamp(min, max)),
+        };
+        console.cvars.insert(name.to_string(), Cvar { value, default, min, max, help: help.to_string() });
+    }
+
+    /// Apply cvar values saved in the app config, including ones whose cvars register later
+    pub fn load_saved(values: &BTreeMap<String, f32>) {
+        CONSOLE.write().unwrap().saved = values.clone();
+        for (name, value) in values {
+            if Self::cvar(name).is_some() {
+                let _ = Self::set_cvar(name, *value);
+            }
+        }
+    }
+
+    /// Current value of every cvar, as saved to the app config
+    pub fn cvar_values() -> BTreeMap<String, f32> {
+        CONSOLE.read().unwrap().cvars.iter().map(|(name, cvar)| (name.clone(), cvar.value)).collect()
+    }
+
+    pub fn cvar(name: &str) -> Option<f32> {
+        CONSOLE.read().unwrap().cvars.get(name).map(|cvar| cvar.value)
+    }
+
+    /// Set a cvar clamped to its range and return the stored value
+    pub fn set_cvar(name: &str, value: f32) -> Result<f32, String> {
+        let mut console = CONSOLE.write().unwrap();
+        let cvar = console.cvars.get_mut(name).ok_or_else(|| format!("Unknown cvar {}", name))?;
+        cvar.value = value.clamp(cvar.min, cvar.max);
+        Ok(cvar.value)
+    }
+
+    pub fn cvars() -> Vec<(String, Cvar)> {
+        CONSOLE.read().unwrap().cvars.iter().map(|(name, cvar)| (name.clone(), cvar.clone())).collect()
+    }
+
+    /// Run a console line: a command with arguments, `cvar` to print it or `cvar value` to set it
+    pub fn execute(line: &str) {
+        let line = line.trim();
+        if line.is_empty() {
+            return;
+        }
+        let handler = {
+            let mut console = CONSOLE.write().unwrap();
+            if console.history.last().map(String::as_str) != Some(line) {
+                console.history.push(line.to_string());
+                if console.history.len() > MAX_HISTORY {
+                    console.history.remove(0);
+                }
+            }
+            console.history_cursor = console.history.len();
+            let name = line.split_whitespace().next().unwrap_or_default();
+            console.commands.get(name).cloned()
+        };
+        Self::print(&format!("> {}", line));
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (name, args) = (words[0], &words[1..]);
+        // The handler runs without the console lock so it can print, register or read cvars
+        let result = match handler {
+            Some(handler) => handler(args),
+            None => match (Self::cvar(name), args.first()) {
+                (None, _) => Err(format!("Unknown command {} (try help)", name)),
+                (Some(value), None) => Ok(format!("{} = {}", name, value)),
+                (Some(_), Some(value)) => value
+                    .parse::<f32>()
+                    .map_err(|_| format!("{} expects a number", name))
+                    .and_then(|value| Self::set_cvar(name, value))
+                    .map(|value| format!("{} = {}", name, value)),
+            },
+        };
+        match result {
+            Ok(output) if !output.is_empty() => Self::print(&output),
+            Ok(_) => {}
+            Err(error) => Self::print(&format!("❌ {}", error)),
+        }
+    }
+
+    /// Complete the first word against command and cvar names; ambiguous prefixes print the candidates
+    pub fn complete(line: &str) -> String {
+        if line.contains(' ') {
+            return line.to_string();
+        }
+        let candidates: Vec<String> = {
+            let console = CONSOLE.read().unwrap();
+            console.commands
+                .keys()
+                .chain(console.cvars.keys())
+                .filter(|name| name.starts_with(line))
+                .cloned()
+                .collect()
+        };
+        match candidates.as_slice() {
+            [] => line.to_string(),
+            [only] => format!("{} ", only),
+            [first, rest @ ..] => {
+                Self::print(&candidates.join("  "));
+                let common = rest.iter().fold(first.len(), |common, name| {
+                    first.chars().zip(name.chars()).take(common).take_while(|(a, b)| a == b).count()
+                });
+                first[..common].to_string()
+            }
+        }
+    }
+
+    /// Step through submitted lines (-1 older, 1 newer); past the newest returns an empty line
+    pub fn recall(step: i32) -> String {
+        let mut console = CONSOLE.write().unwrap();
+        let cursor = (console.history_cursor as i64 + step as i64).clamp(0, console.history.len() as i64) as usize;
+        console.history_cursor = cursor;
+        console.history.get(cursor).cloned().unwrap_or_default()
+    }
+
+    pub fn print(line: &str) {
+        println!("🖥️ {}", line);
+        Diagnostics::log(line);
+        let mut console = CONSOLE.write().unwrap();
+        console.log.push(line.to_string());
+        if console.log.len() > MAX_LOG_LINES {
+            console.log.remove(0);
+        }
+    }
+
+    pub fn log() -> Vec<String> {
+        CONSOLE.read().unwrap().log.clone()
+    }
+
+    fn clear() {
+        CONSOLE.write().unwrap().log.clear();
+    }
+
+    fn help() -> String {
+        let console = CONSOLE.read().unwrap();
+        let commands: Vec<&str> = console.commands.keys().map(String::as_str).collect();
+        let cvars: Vec<String> = console.cvars
+            .iter()
+            .map(|(name, cvar)| format!("  {} = {} [{}..{}] {}", name, cvar.value, cvar.min, cvar.max, cvar.help))
+            .collect();
+        format!("Commands: {}\nCvars (type a name to print it, name value to set it):\n{}", commands.join(", "), cvars.join("\n"))
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::Metadata;
+use crate::index::engine::modules::diagnostics::Diagnostics;
+use crate::index::engine::modules::ecs::{ get_all_components, get_all_entities };
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::modules::random::Random;
+use crate::get_query_by_id;
+
+/// Baseline written and read when the `audit` command isn't given a path
+pub const DEFAULT_BASELINE_PATH: &str = "determinism_baseline.json";
+
+/// A recorded run: the seed it ran with and the world state after each of its fixed ticks
+#[derive(Serialize, Deserialize)]
+struct Baseline {
+    seed: u64,
+    ticks: Vec<TickRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TickRecord {
+    world: u64, // Hash of every component
+    changed: Vec<(String, Option<u64>)>, // Components hashed differently than the tick before, None once removed
+}
+
+enum Mode {
+    Record(Baseline),
+    Compare(Baseline),
+}
+
+struct Audit {
+    mode: Mode,
+    path: String,
+    tick: usize,
+    state: BTreeMap<String, u64>, // Component hashes after the last tick, the baseline's when comparing
+    divergence: Option<String>,   // First mismatch, after which comparing stops
+}
+
+static AUDIT: Mutex<Option<Audit>> = Mutex::new(None);
+
+/// Debug mode that hashes every component after each fixed tick, to record a baseline run and check later runs
+/// against it, reporting the first tick and component that came out differently. Runs compare from the same scene
+/// and seed; entities spawned at runtime get random IDs, so they show up as divergent until spawned with fixed IDs
+pub struct DeterminismAudit;
+
+impl DeterminismAudit {
+    /// Hash each tick from now on and write them to `path` when finished; random streams start over
+    pub fn start_recording(path: &str) {
+        Random::reset();
+        let baseline = Baseline { seed: Random::world_seed(), ticks: Vec::new() };
+        Self::start(Mode::Record(baseline), path);
+        println!("🔍 Recording a determinism baseline to {}", path);
+    }
+
+    /// Check each tick from now on against the baseline at `path`, switching to the seed it was recorded with
+    pub fn start_comparing(path: &str) -> EngineResult<()> {
+        let json = std::fs::read_to_string(path).map_err(|e| EngineError::io(path, e.to_string()))?;
+        let baseline: Baseline = serde_json::from_str(&json).map_err(|e| EngineError::io(path, e.to_string()))?;
+        Random::reseed(baseline.seed);
+        println!("🔍 Comparing against the determinism baseline {} ({} ticks)", path, baseline.ticks.len());
+        Self::start(Mode::Compare(baseline), path);
+        Ok(())
+    }
+
+    fn start(mode: Mode, path: &str) {
+        *AUDIT.lock().unwrap() = Some(Audit {
+            mode,
+            path: path.to_string(),
+            tick: 0,
+            state: BTreeMap::new(),
+            divergence: None,
+        });
+    }
+
+    /// Hash the world after a fixed tick; does nothing unless recording or comparing
+    pub fn after_tick() {
+        let mut audit = AUDIT.lock().unwrap();
+        let Some(audit) = audit.as_mut() else {
+            return;
+        };
+        if audit.divergence.is_some() {
+            return;
+        }
+
+        let state = component_hashes();
+        let world = world_hash(&state);
+        match &mut audit.mode {
+            Mode::Record(baseline) => {
+                let changed = changed_hashes(&audit.state, &state);
+                baseline.ticks.push(TickRecord { world, changed });
+                audit.state = state;
+            }
+            Mode::Compare(baseline) => {
+                let Some(record) = baseline.ticks.get(audit.tick) else {
+                    return; // Ran past the end of the baseline
+                };
+                for (key, hash) in &record.changed {
+                    match hash {
+                        Some(hash) => audit.state.insert(key.clone(), *hash),
+                        None => audit.state.remove(key),
+                    };
+                }
+                if world != record.world {
+                    let message = divergence_message(audit.tick + 1, &audit.state, &state);
+                    eprintln!("🔀 {}", message);
+                    Diagnostics::log(&message);
+                    audit.divergence = Some(message);
+                }
+            }
+        }
+        audit.tick += 1;
+    }
+
+    /// Stop the audit: a recording is written to its path, a comparison fails with its first divergence
+    pub fn finish() -> Result<String, String> {
+        let audit = AUDIT.lock().unwrap().take().ok_or("No determinism audit running")?;
+        match audit.mode {
+            Mode::Record(baseline) => {
+                let json = serde_json::to_string(&baseline).map_err(|e| e.to_string())?;
+                std::fs::write(&audit.path, json).map_err(|e| EngineError::io(&audit.path, e.to_string()).to_string())?;
+                Ok(format!("Recorded {} ticks to {}", baseline.ticks.len(), audit.path))
+            }
+            Mode::Compare(baseline) => {
+                if let Some(divergence) = audit.divergence {
+                    return Err(divergence);
+                }
+                let mut summary = format!("{} ticks matched {}", audit.tick, audit.path);
+                if audit.tick != baseline.ticks.len() {
+                    summary.push_str(&format!(" (the baseline has {})", baseline.ticks.len()));
+                }
+                Ok(summary)
+            }
+        }
+    }
+
+    /// Console command `audit [record|compare [path] | stop]`: start recording or comparing, stop, or show progress
+    pub fn audit_command(args: &[&str]) -> Result<String, String> {
+        let path = args.get(1).copied().unwrap_or(DEFAULT_BASELINE_PATH);
+        match args.first().copied() {
+            Some("record") => {
+                Self::start_recording(path);
+                Ok(format!("Recording a determinism baseline; audit stop writes it to {}", path))
+            }
+            Some("compare") => {
+                Self::start_comparing(path).map_err(|e| e.to_string())?;
+                Ok(format!("Comparing every tick against {}", path))
+            }
+            Some("stop") => Self::finish(),
+            None => {
+                let audit = AUDIT.lock().unwrap();
+                let Some(audit) = audit.as_ref() else {
+                    return Ok("No determinism audit running".to_string());
+                };
+                Ok(match (&audit.mode, &audit.divergence) {
+                    (Mode::Record(_), _) => format!("Recording, {} ticks so far", audit.tick),
+                    (Mode::Compare(_), Some(divergence)) => divergence.clone(),
+                    (Mode::Compare(baseline), None) =>
+                        format!("Comparing, {} of {} ticks matched", audit.tick, baseline.ticks.len()),
+                })
+            }
+            Some(_) => Err("Usage: audit [record|compare [path] | stop]".to_string()),
+        }
+    }
+}
+
+/// Hash of each component, keyed by entity ID and component type (plugin components by kind)
+fn component_hashes() -> BTreeMap<String, u64> {
+    let mut hashes = BTreeMap::new();
+    for (entity_id, _) in get_all_entities() {
+        for component in get_all_components(&entity_id) {
+            let Ok(json) = serde_json::to_value(&component) else {
+                continue;
+            };
+            let kind = json.get("kind").or_else(|| json.get("type")).and_then(|name| name.as_str()).unwrap_or_default();
+            hashes.insert(format!("{} {}", entity_id, kind), fnv1a(json.to_string().bytes()));
+        }
+    }
+    hashes
+}
+
+fn world_hash(state: &BTreeMap<String, u64>) -> u64 {
+    fnv1a(state.iter().flat_map(|(key, hash)| key.bytes().chain(hash.to_le_bytes())))
+}
+
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+fn changed_hashes(before: &BTreeMap<String, u64>, after: &BTreeMap<String, u64>) -> Vec<(String, Option<u64>)> {
+    let removed = before.keys().filter(|key| !after.contains_key(*key)).map(|key| (key.clone(), None));
+    let changed = after
+        .iter()
+        .filter(|(key, hash)| before.get(*key) != Some(hash))
+        .map(|(key, hash)| (key.clone(), Some(*hash)));
+    removed.chain(changed).collect()
+}
+
+/// Which component differs first (in key order) between the baseline and this run
+fn divergence_message(tick: usize, baseline: &BTreeMap<String, u64>, state: &BTreeMap<String, u64>) -> String {
+    let key = baseline
+        .keys()
+        .chain(state.keys())
+        .filter(|key| baseline.get(*key) != state.get(*key))
+        .min()
+        .cloned()
+        .unwrap_or_default();
+    let (entity_id, component) = key.split_once(' ').unwrap_or((&key, ""));
+    let entity = get_query_by_id!(entity_id.to_string(), (Metadata))
+        .map_or_else(|| entity_id.to_string(), |metadata| format!("{} ({})", metadata.title, entity_id));
+    let difference = match (baseline.contains_key(&key), state.contains_key(&key)) {
+        (true, false) => "is missing from this run",
+        (false, true) => "isn't in the baseline",
+        _ => "differs from the baseline",
+    };
+    format!("Determinism audit: tick {} diverged, {} of {} {}", tick, component, entity, difference)
+}
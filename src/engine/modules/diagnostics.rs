@@ -0,0 +1,138 @@
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::sync::{ Mutex, OnceLock, RwLock };
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::time::{ SystemTime, UNIX_EPOCH };
+use glow::HasContext;
+
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+
+/// Directory reports are written to, relative to the working directory
+pub const REPORT_DIRECTORY: &str = "crash_reports";
+/// Log lines kept for the next report
+const REPORT_LOG_LINES: usize = 100;
+
+/// Called with the path and contents of every report written; see `Diagnostics::add_report_hook`
+pub type ReportHook = fn(&str, &str);
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static GL_INFO: OnceLock<String> = OnceLock::new();
+static LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static HOOKS: RwLock<Vec<ReportHook>> = RwLock::new(Vec::new());
+
+/// Opt-in crash reports (`crash_reports = true` in app_config.toml, or `--crash-reports`): panics write a plain
+/// text report with the backtrace, the GL context and the last console and error lines to `crash_reports/`, for
+/// the user to attach to bug reports. Nothing is sent anywhere; report hooks are the only way out and none are
+/// registered by default
+pub struct Diagnostics;
+
+impl Diagnostics {
+    /// Turn crash reports on and install the panic hook; the default hook still prints the panic afterwards
+    pub fn enable() {
+        if ENABLED.swap(true, Ordering::Relaxed) {
+            return;
+        }
+        let default_hook = std::panic::take_hook();
+        std::panic::set_hook(
+            Box::new(move |info| {
+                let reason = format!("Panic: {}\n\nBacktrace:\n{}", info, Backtrace::force_capture());
+                match Self::write_report(&reason) {
+                    Ok(path) => eprintln!("🧾 Crash report written to {}", path),
+                    Err(e) => eprintln!("❌ {}", e),
+                }
+                default_hook(info);
+            })
+        );
+        println!("🧾 Crash reports enabled, written to {}/", REPORT_DIRECTORY);
+    }
+
+    pub fn enabled() -> bool {
+        ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// Keep a line for the next report; only recorded while reports are enabled
+    pub fn log(line: &str) {
+        if !Self::enabled() {
+            return;
+        }
+        // A panic while the lock was held mustn't keep the report from being written
+        let mut log = LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        log.push_back(line.to_string());
+        if log.len() > REPORT_LOG_LINES {
+            log.pop_front();
+        }
+    }
+
+    /// Note the GL context the renderer runs on, called once the context is created
+    pub fn record_gl_info(gl: &glow::Context) {
+        let info = unsafe {
+            format!(
+                "Vendor: {}\nRenderer: {}\nVersion: {}\nGLSL: {}",
+                gl.get_parameter_string(glow::VENDOR),
+                gl.get_parameter_string(glow::RENDERER),
+                gl.get_parameter_string(glow::VERSION),
+                gl.get_parameter_string(glow::SHADING_LANGUAGE_VERSION)
+            )
+        };
+        let _ = GL_INFO.set(info);
+    }
+
+    /// The GL context noted by `record_gl_info`, if one was created
+    pub fn gl_info() -> Option<&'static str> {
+        GL_INFO.get().map(String::as_str)
+    }
+
+    /// Pass every written report to `hook` as well, e.g. for a game that uploads them with the player's consent
+    #[allow(dead_code)]
+    pub fn add_report_hook(hook: ReportHook) {
+        HOOKS.write().unwrap().push(hook);
+    }
+
+    /// Write a report with `reason` at the top; returns the path written
+    pub fn write_report(reason: &str) -> EngineResult<String> {
+        let written_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mut report = format!(
+            "wet-crab {} report\nWritten at: {} (unix seconds)\nPlatform: {} {}\nArguments: {}\n\n{}\n",
+            env!("CARGO_PKG_VERSION"),
+            written_at.as_secs(),
+            std::env::consts::OS,
+            std::env::consts::ARCH,
+            std::env::args().skip(1).collect::<Vec<_>>().join(" "),
+            reason.trim_end()
+        );
+
+        report.push_str("\nGL context:\n");
+        report.push_str(Self::gl_info().unwrap_or("Not created"));
+        report.push('\n');
+
+        let log = LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        report.push_str(&format!("\nLast {} log lines:\n", log.len()));
+        for line in log.iter() {
+            report.push_str(line);
+            report.push('\n');
+        }
+        drop(log);
+
+        std::fs::create_dir_all(REPORT_DIRECTORY).map_err(|e| EngineError::io(REPORT_DIRECTORY, e.to_string()))?;
+        let path = format!("{}/report-{}-{:03}.txt", REPORT_DIRECTORY, written_at.as_secs(), written_at.subsec_millis());
+        std::fs::write(&path, &report).map_err(|e| EngineError::io(&path, e.to_string()))?;
+
+        // Copied out so a hook that panics doesn't poison the list
+        let hooks = HOOKS.read().map(|hooks| hooks.clone()).unwrap_or_default();
+        for hook in hooks {
+            hook(&path, &report);
+        }
+        Ok(path)
+    }
+
+    /// Console command `report [note...]`: write a report now, e.g. right after something went wrong
+    pub fn report_command(args: &[&str]) -> Result<String, String> {
+        if !Self::enabled() {
+            return Err("Reports are off: set crash_reports = true in app_config.toml or pass --crash-reports".into());
+        }
+        let reason = if args.is_empty() { "Requested from the console".to_string() } else { args.join(" ") };
+        let path = Self::write_report(&format!("Report: {}\n\nBacktrace:\n{}", reason, Backtrace::force_capture()))
+            .map_err(|e| e.to_string())?;
+        Ok(format!("Report written to {}", path))
+    }
+}
@@ -1,19 +1,50 @@
-use std::collections::HashMap;
-use std::sync::RwLock;
+use std::collections::{ HashMap, HashSet };
+use std::marker::PhantomData;
+use std::sync::{ Mutex, RwLock };
 use once_cell::sync::Lazy;
 use serde::{ Serialize, Deserialize };
 use uuid::Uuid;
 
+use crate::index::engine::modules::ecs_storage::{ ComponentStorage, EntityIndex };
+use crate::index::engine::modules::entity_pool::EntityPool;
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::modules::scene_library::SceneHeader;
+use crate::index::engine::modules::selection::Selection;
+
 // Import all component types
 use crate::index::engine::components::{
     rigid_body::RigidBody,
+    AiAgent,
     AnimatedObject3DComponent as AnimatedObject3D,
+    BakedOcclusion,
+    Blockout,
     CameraComponent as Camera,
+    ColorGrading,
+    CustomMaterial,
     Collider,
+    Faction,
+    Health,
+    Interactable,
+    KinematicMover,
     Metadata,
+    NavAgent,
+    NavMesh,
+    PlayerController,
+    PluginComponent,
+    PointLight,
+    Projectile,
+    PropertyAnimation,
+    Ragdoll,
+    ReflectionProbe,
     Shape,
+    Spawner,
+    Spline,
     StaticObject3DComponent as StaticObject3D,
+    Terrain,
+    TimeOfDay,
     Transform,
+    Volume,
+    Water,
 };
 
 pub type EntityId = String;
@@ -32,247 +63,594 @@ pub enum Component {
     AnimatedObject3D(AnimatedObject3D),
     Shape(Shape),
     RigidBody(RigidBody),
+    NavMesh(NavMesh),
+    NavAgent(NavAgent),
+    AiAgent(AiAgent),
+    Health(Health),
+    Faction(Faction),
+    Spawner(Spawner),
+    TimeOfDay(TimeOfDay),
+    Terrain(Terrain),
+    Blockout(Blockout),
+    Spline(Spline),
+    Volume(Volume),
+    Water(Water),
+    PointLight(PointLight),
+    BakedOcclusion(BakedOcclusion),
+    ReflectionProbe(ReflectionProbe),
+    ColorGrading(ColorGrading),
+    CustomMaterial(CustomMaterial),
+    PropertyAnimation(PropertyAnimation),
+    Ragdoll(Ragdoll),
+    KinematicMover(KinematicMover),
+    Projectile(Projectile),
+    Interactable(Interactable),
+    PlayerController(PlayerController),
+    Plugin(PluginComponent),
 }
 
-// ——————————————————————————————————————————————————————————— Global Singleton ————
+// ——————————————————————————————————————————————————————————— Component Kinds ————
+
+/// A component type stored in its own sparse set (see ComponentStorage)
+pub trait StoredComponent: Clone + Into<Component> {
+    const KIND: ComponentKind;
+    fn downcast(component: &Component) -> Option<&Self>;
+    fn downcast_mut(component: &mut Component) -> Option<&mut Self>;
+}
+
+/// Declares ComponentKind, the fieldless twin of Component, and the StoredComponent and QueryParam impls of each
+/// component type
+macro_rules! stored_components {
+    ($($variant:ident($component:ty)),+ $(,)?) => {
+        /// Type of a Component, without its data
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+        pub enum ComponentKind {
+            $($variant,)+
+        }
+
+        impl Component {
+            pub fn kind(&self) -> ComponentKind {
+                match self {
+                    $(Component::$variant(_) => ComponentKind::$variant,)+
+                }
+            }
+        }
+
+        $(
+            impl StoredComponent for $component {
+                const KIND: ComponentKind = ComponentKind::$variant;
+
+                fn downcast(component: &Component) -> Option<&Self> {
+                    match component {
+                        Component::$variant(typed_component) => Some(typed_component),
+                        _ => None,
+                    }
+                }
+
+                fn downcast_mut(component: &mut Component) -> Option<&mut Self> {
+                    match component {
+                        Component::$variant(typed_component) => Some(typed_component),
+                        _ => None,
+                    }
+                }
+            }
+
+            impl QueryParam for $component {
+                type Item = Self;
+
+                fn required() -> Option<ComponentKind> {
+                    Some(Self::KIND)
+                }
+
+                fn fetch(storage: &ComponentStorage, entity: EntityIndex) -> Option<Self> {
+                    fetch_component(storage, entity)
+                }
+
+                fn write_back(entity_id: &EntityId, item: Self) {
+                    insert(entity_id, item);
+                }
 
-/// Global component map singleton - HashMap<EntityId, Vec<Component>>
-static COMPONENT_MAP: Lazy<RwLock<HashMap<String, Vec<Component>>>> = Lazy::new(||
-    RwLock::new(HashMap::new())
+                fn write_back_in(storage: &mut ComponentStorage, entity_id: &EntityId, item: Self) {
+                    insert_in(storage, entity_id, item);
+                }
+            }
+        )+
+    };
+}
+
+stored_components!(
+    Transform(Transform),
+    Metadata(Metadata),
+    Camera(Camera),
+    Collider(Collider),
+    StaticObject3D(StaticObject3D),
+    AnimatedObject3D(AnimatedObject3D),
+    Shape(Shape),
+    RigidBody(RigidBody),
+    NavMesh(NavMesh),
+    NavAgent(NavAgent),
+    AiAgent(AiAgent),
+    Health(Health),
+    Faction(Faction),
+    Spawner(Spawner),
+    TimeOfDay(TimeOfDay),
+    Terrain(Terrain),
+    Blockout(Blockout),
+    Spline(Spline),
+    Volume(Volume),
+    Water(Water),
+    PointLight(PointLight),
+    BakedOcclusion(BakedOcclusion),
+    ReflectionProbe(ReflectionProbe),
+    ColorGrading(ColorGrading),
+    CustomMaterial(CustomMaterial),
+    PropertyAnimation(PropertyAnimation),
+    Ragdoll(Ragdoll),
+    KinematicMover(KinematicMover),
+    Projectile(Projectile),
+    Interactable(Interactable),
+    PlayerController(PlayerController),
+    Plugin(PluginComponent),
 );
 
+fn fetch_component<T: StoredComponent>(storage: &ComponentStorage, entity: EntityIndex) -> Option<T> {
+    storage.get(entity, T::KIND).and_then(T::downcast).cloned()
+}
+
+// ——————————————————————————————————————————————————————————— Global Singleton ————
+
+/// Global component storage singleton - a sparse set per component type
+static STORAGE: Lazy<RwLock<ComponentStorage>> = Lazy::new(|| RwLock::new(ComponentStorage::default()));
+
 // ——————————————————————————————————————————————————————————— Core API ————
 
 /// Spawn a new entity and return its ID
 pub fn spawn() -> EntityId {
     let id = Uuid::new_v4().to_string();
-    let mut map = COMPONENT_MAP.write().unwrap();
-    map.insert(id.clone(), Vec::new());
+    let mut storage = STORAGE.write().unwrap();
+    storage.spawn(id.clone(), Vec::new());
     id
 }
 
+/// Spawn an entity under a caller-chosen ID (e.g. a saved or replicated reference); None if the ID is taken
+#[allow(dead_code)]
+pub fn spawn_with_id(id: &str) -> Option<EntityId> {
+    if id.trim().is_empty() {
+        return None;
+    }
+    STORAGE.write().unwrap().spawn(id.to_string(), Vec::new())?;
+    Some(id.to_string())
+}
+
+/// Whether an entity with this ID exists; IDs are stable across save/load, so saved references stay valid
+pub fn entity_exists(entity_id: &str) -> bool {
+    STORAGE.read().unwrap().contains(entity_id)
+}
+
+/// Whether the entity takes part in simulation and rendering (disabled via Metadata.is_enabled)
+pub fn is_enabled(entity_id: &str) -> bool {
+    let storage = STORAGE.read().unwrap();
+    storage
+        .index_of(entity_id)
+        .and_then(|entity| storage.get(entity, ComponentKind::Metadata))
+        .and_then(Metadata::downcast)
+        .is_none_or(|metadata| metadata.is_enabled)
+}
+
 /// Insert a component into an entity
 pub fn insert<T>(entity_id: &EntityId, component: T) where T: Into<Component> + Clone {
-    let mut map = COMPONENT_MAP.write().unwrap();
-    if let Some(components) = map.get_mut(entity_id) {
-        // Remove existing component of the same type if it exists
-        let new_component = component.into();
-        components.retain(|c| std::mem::discriminant(c) != std::mem::discriminant(&new_component));
-        components.push(new_component);
+    insert_in(&mut STORAGE.write().unwrap(), entity_id, component);
+}
+
+/// insert into a storage other than the world's, e.g. EcsBench's
+pub fn insert_in<T>(storage: &mut ComponentStorage, entity_id: &EntityId, component: T) where T: Into<Component> {
+    if let Some(entity) = storage.index_of(entity_id) {
+        // Replaces the existing component of the same type if it exists
+        storage.insert(entity, component.into());
     }
 }
 
 /// Get a component from an entity (read-only)
-pub fn get_component<T>(entity_id: &EntityId) -> Option<T> where T: Clone, Component: TryInto<T> {
-    let map = COMPONENT_MAP.read().unwrap();
-    if let Some(components) = map.get(entity_id) {
-        for component in components {
-            if let Ok(typed_component) = component.clone().try_into() {
-                return Some(typed_component);
-            }
-        }
-    }
-    None
+pub fn get_component<T: StoredComponent>(entity_id: &EntityId) -> Option<T> {
+    let storage = STORAGE.read().unwrap();
+    fetch_component(&storage, storage.index_of(entity_id)?)
 }
 
 /// Get a mutable reference to a component (requires write lock)
 pub fn get_component_mut<T, F, R>(entity_id: &EntityId, f: F) -> Option<R>
-    where T: Clone, Component: TryInto<T> + From<T>, F: FnOnce(&mut T) -> R
+    where T: StoredComponent, F: FnOnce(&mut T) -> R
 {
-    let mut map = COMPONENT_MAP.write().unwrap();
-    if let Some(components) = map.get_mut(entity_id) {
-        for component in components.iter_mut() {
-            if let Ok(mut typed_component) = component.clone().try_into() {
-                let result = f(&mut typed_component);
-                *component = typed_component.into();
-                return Some(result);
-            }
-        }
-    }
-    None
+    let mut storage = STORAGE.write().unwrap();
+    let entity = storage.index_of(entity_id)?;
+    storage.get_mut(entity, T::KIND).and_then(T::downcast_mut).map(f)
 }
 
 /// Query all entities with a specific component type
-pub fn query_all<T>() -> Vec<(EntityId, T)> where T: Clone, Component: TryInto<T> {
-    let map = COMPONENT_MAP.read().unwrap();
-    let mut results = Vec::new();
-
-    for (entity_id, components) in map.iter() {
-        for component in components {
-            if let Ok(typed_component) = component.clone().try_into() {
-                results.push((entity_id.clone(), typed_component));
-                break; // Only one component of each type per entity
-            }
-        }
-    }
-
-    results
+pub fn query_all<T: StoredComponent>() -> Vec<(EntityId, T)> {
+    let storage = STORAGE.read().unwrap();
+    storage.query(&[Some(T::KIND)], |entity| {
+        Some((storage.id_of(entity).clone(), fetch_component(&storage, entity)?))
+    })
 }
 
 /// Query all entities with two specific component types
-pub fn query_all2<T1, T2>() -> Vec<(EntityId, T1, T2)>
-    where T1: Clone, T2: Clone, Component: TryInto<T1> + TryInto<T2>
+pub fn query_all2<T1: StoredComponent, T2: StoredComponent>() -> Vec<(EntityId, T1, T2)> {
+    let storage = STORAGE.read().unwrap();
+    storage.query(&[Some(T1::KIND), Some(T2::KIND)], |entity| {
+        Some((storage.id_of(entity).clone(), fetch_component(&storage, entity)?, fetch_component(&storage, entity)?))
+    })
+}
+
+/// Query all entities with three specific component types
+pub fn query_all3<T1, T2, T3>() -> Vec<(EntityId, T1, T2, T3)>
+    where T1: StoredComponent, T2: StoredComponent, T3: StoredComponent
 {
-    let map = COMPONENT_MAP.read().unwrap();
-    let mut results = Vec::new();
-
-    for (entity_id, components) in map.iter() {
-        let mut comp1: Option<T1> = None;
-        let mut comp2: Option<T2> = None;
-
-        for component in components {
-            if comp1.is_none() {
-                if let Ok(typed_component) = component.clone().try_into() {
-                    comp1 = Some(typed_component);
-                    continue;
-                }
-            }
-            if comp2.is_none() {
-                if let Ok(typed_component) = component.clone().try_into() {
-                    comp2 = Some(typed_component);
-                    continue;
-                }
-            }
-        }
+    let storage = STORAGE.read().unwrap();
+    storage.query(&[Some(T1::KIND), Some(T2::KIND), Some(T3::KIND)], |entity| {
+        Some((
+            storage.id_of(entity).clone(),
+            fetch_component(&storage, entity)?,
+            fetch_component(&storage, entity)?,
+            fetch_component(&storage, entity)?,
+        ))
+    })
+}
 
-        if let (Some(c1), Some(c2)) = (comp1, comp2) {
-            results.push((entity_id.clone(), c1, c2));
-        }
+// ——————————————————————————————————————————————————————————— Query Parameters ————
+
+/// One element of a `query!`: a component the entity must have (written back after the body), `Option<T>` for
+/// one it may have, or `Without<T>` for one it must not have
+pub trait QueryParam {
+    type Item;
+    /// Component the entity must have, so the query can walk that component's column instead of every entity
+    fn required() -> Option<ComponentKind> {
+        None
+    }
+    /// The element from an entity's components, or None to leave the entity out of the query
+    fn fetch(storage: &ComponentStorage, entity: EntityIndex) -> Option<Self::Item>;
+    /// Store the element once the query body is done with it
+    fn write_back(entity_id: &EntityId, item: Self::Item);
+    /// write_back into a storage other than the world's, e.g. EcsBench's
+    fn write_back_in(storage: &mut ComponentStorage, entity_id: &EntityId, item: Self::Item);
+}
+
+/// Query filter for entities without a `T`, e.g. `(Transform, StaticObject3D, Without<Collider>)`; binds `()`
+#[allow(dead_code)] // Only named as a type parameter
+pub struct Without<T>(PhantomData<T>);
+
+impl<T: StoredComponent> QueryParam for Without<T> {
+    type Item = ();
+
+    fn fetch(storage: &ComponentStorage, entity: EntityIndex) -> Option<()> {
+        storage.get(entity, T::KIND).is_none().then_some(())
     }
 
-    results
+    fn write_back(_entity_id: &EntityId, _item: ()) {}
+
+    fn write_back_in(_storage: &mut ComponentStorage, _entity_id: &EntityId, _item: ()) {}
 }
 
-/// Query all entities with three specific component types
-pub fn query_all3<T1, T2, T3>() -> Vec<(EntityId, T1, T2, T3)>
-    where T1: Clone, T2: Clone, T3: Clone, Component: TryInto<T1> + TryInto<T2> + TryInto<T3>
-{
-    let map = COMPONENT_MAP.read().unwrap();
-    let mut results = Vec::new();
-
-    for (entity_id, components) in map.iter() {
-        let mut comp1: Option<T1> = None;
-        let mut comp2: Option<T2> = None;
-        let mut comp3: Option<T3> = None;
-
-        for component in components {
-            if comp1.is_none() {
-                if let Ok(typed_component) = component.clone().try_into() {
-                    comp1 = Some(typed_component);
-                    continue;
-                }
-            }
-            if comp2.is_none() {
-                if let Ok(typed_component) = component.clone().try_into() {
-                    comp2 = Some(typed_component);
-                    continue;
-                }
-            }
-            if comp3.is_none() {
-                if let Ok(typed_component) = component.clone().try_into() {
-                    comp3 = Some(typed_component);
-                    continue;
-                }
-            }
+impl<T: QueryParam> QueryParam for Option<T> {
+    type Item = Option<T::Item>;
+
+    fn fetch(storage: &ComponentStorage, entity: EntityIndex) -> Option<Self::Item> {
+        Some(T::fetch(storage, entity))
+    }
+
+    fn write_back(entity_id: &EntityId, item: Self::Item) {
+        if let Some(item) = item {
+            T::write_back(entity_id, item);
         }
+    }
 
-        if let (Some(c1), Some(c2), Some(c3)) = (comp1, comp2, comp3) {
-            results.push((entity_id.clone(), c1, c2, c3));
+    fn write_back_in(storage: &mut ComponentStorage, entity_id: &EntityId, item: Self::Item) {
+        if let Some(item) = item {
+            T::write_back_in(storage, entity_id, item);
         }
     }
+}
 
-    results
+/// Entities matching two query parameters, with what each fetched
+pub fn query_params2<P1: QueryParam, P2: QueryParam>() -> Vec<(EntityId, P1::Item, P2::Item)> {
+    query_params2_in::<P1, P2>(&STORAGE.read().unwrap())
 }
 
-/// Get all entity IDs that have a specific component type
-pub fn query_get_all_ids<T>() -> Vec<EntityId> where Component: TryInto<T> {
-    let map = COMPONENT_MAP.read().unwrap();
-    let mut results = Vec::new();
-
-    for (entity_id, components) in map.iter() {
-        for component in components {
-            if component.clone().try_into().is_ok() {
-                results.push(entity_id.clone());
-                break;
-            }
-        }
-    }
+/// query_params2 over a storage other than the world's, e.g. EcsBench's
+pub fn query_params2_in<P1, P2>(storage: &ComponentStorage) -> Vec<(EntityId, P1::Item, P2::Item)>
+    where P1: QueryParam, P2: QueryParam
+{
+    storage.query(&[P1::required(), P2::required()], |entity| {
+        Some((storage.id_of(entity).clone(), P1::fetch(storage, entity)?, P2::fetch(storage, entity)?))
+    })
+}
 
-    results
+/// An entity matching three query parameters, with what each fetched
+pub type QueryRow3<P1, P2, P3> = (EntityId, <P1 as QueryParam>::Item, <P2 as QueryParam>::Item, <P3 as QueryParam>::Item);
+
+pub fn query_params3<P1: QueryParam, P2: QueryParam, P3: QueryParam>() -> Vec<QueryRow3<P1, P2, P3>> {
+    let storage = STORAGE.read().unwrap();
+    storage.query(&[P1::required(), P2::required(), P3::required()], |entity| {
+        Some((
+            storage.id_of(entity).clone(),
+            P1::fetch(&storage, entity)?,
+            P2::fetch(&storage, entity)?,
+            P3::fetch(&storage, entity)?,
+        ))
+    })
+}
+
+/// Get all entity IDs that have a specific component type
+pub fn query_get_all_ids<T: StoredComponent>() -> Vec<EntityId> {
+    let storage = STORAGE.read().unwrap();
+    storage.query(&[Some(T::KIND)], |entity| {
+        storage.get(entity, T::KIND).map(|_| storage.id_of(entity).clone())
+    })
 }
 
 /// Copy an entity with all its components to a new entity
 pub fn copy_entity(source_entity_id: &EntityId) -> Option<EntityId> {
-    let mut map = COMPONENT_MAP.write().unwrap();
+    let mut storage = STORAGE.write().unwrap();
 
-    if let Some(source_components) = map.get(source_entity_id).cloned() {
-        let new_entity_id = Uuid::new_v4().to_string();
-        map.insert(new_entity_id.clone(), source_components);
-        Some(new_entity_id)
-    } else {
-        None
-    }
+    let source = storage.index_of(source_entity_id)?;
+    let source_components = storage.components(source).cloned().collect();
+    let new_entity_id = Uuid::new_v4().to_string();
+    storage.spawn(new_entity_id.clone(), source_components);
+    Some(new_entity_id)
 }
 
 /// Delete an entity and all its components
 pub fn delete_entity(entity_id: &EntityId) -> bool {
-    let mut map = COMPONENT_MAP.write().unwrap();
-    map.remove(entity_id).is_some()
+    let mut storage = STORAGE.write().unwrap();
+    storage.remove(entity_id).is_some()
+}
+
+/// Remove an entity and hand back its components, e.g. to park it in an EntityPool
+pub fn take_entity(entity_id: &EntityId) -> Option<Vec<Component>> {
+    STORAGE.write().unwrap().remove(entity_id)
+}
+
+/// Put an entity taken with `take_entity` back under its ID
+pub fn restore_entity(entity_id: EntityId, components: Vec<Component>) {
+    let mut storage = STORAGE.write().unwrap();
+    storage.remove(&entity_id);
+    storage.spawn(entity_id, components);
+}
+
+/// Delete an entity and all its components, drop it from the selection and refresh the editor's entity list.
+/// `delete_entity` leaves the refresh to the caller, for systems that remove many entities in one tick
+pub fn despawn(entity_id: &EntityId) -> bool {
+    if !delete_entity(entity_id) {
+        return false;
+    }
+    Selection::remove(entity_id);
+    InterfaceSystem::update_entities_list();
+    true
 }
 
 /// Get all entities and their component counts (for debugging/serialization)
 pub fn get_all_entities() -> Vec<(EntityId, usize)> {
-    let map = COMPONENT_MAP.read().unwrap();
-    map.iter()
-        .map(|(id, components)| (id.clone(), components.len()))
+    let storage = STORAGE.read().unwrap();
+    storage
+        .entities()
+        .map(|(entity, id)| (id.clone(), storage.component_count(entity)))
         .collect()
 }
 
 /// Get all components for a specific entity
 pub fn get_all_components(entity_id: &EntityId) -> Vec<Component> {
-    let map = COMPONENT_MAP.read().unwrap();
-    map.get(entity_id).cloned().unwrap_or_default()
+    let storage = STORAGE.read().unwrap();
+    storage
+        .index_of(entity_id)
+        .map(|entity| storage.components(entity).cloned().collect())
+        .unwrap_or_default()
+}
+
+/// The saved entities (is_persist, or without Metadata) with their components, as a scene save would write them
+pub fn get_persistent_entities() -> Vec<(EntityId, Vec<Component>)> {
+    let storage = STORAGE.read().unwrap();
+    storage
+        .entities()
+        .filter(|(entity, _)| is_persistent(&storage, *entity))
+        .map(|(entity, id)| (id.clone(), storage.components(entity).cloned().collect()))
+        .collect()
+}
+
+/// Spawns, inserts, mutable borrows and removals in the world so far, to ask `persistent_changes_since` later
+pub fn change_count() -> u64 {
+    STORAGE.read().unwrap().changes()
+}
+
+/// The first entity with a T and the change count it was last written at, to tell whether a copy of its T taken
+/// earlier is still current
+pub fn first_changed_at<T: StoredComponent>() -> Option<(EntityId, u64)> {
+    let storage = STORAGE.read().unwrap();
+    let entity = *storage.column(T::KIND)?.owners().first()?;
+    Some((storage.id_of(entity).clone(), storage.changed_at(entity)))
+}
+
+/// Persistent entities changed since a change count, see `persistent_changes_since`
+pub struct WorldChanges {
+    pub changed: Vec<(EntityId, Vec<Component>)>,
+    pub removed: Vec<EntityId>, // Of the known entities, those gone or no longer persistent
+    pub change_count: u64,      // To ask for the changes after these next time
+}
+
+/// Persistent entities spawned or written to after the change count `since` (0 for all of them), and those of
+/// `known` that are gone or no longer persistent; cheaper than diffing every entity when few change
+pub fn persistent_changes_since<'a>(since: u64, known: impl IntoIterator<Item = &'a EntityId>) -> WorldChanges {
+    let storage = STORAGE.read().unwrap();
+    let changed = storage
+        .changed_since(since)
+        .filter(|(entity, _)| is_persistent(&storage, *entity))
+        .map(|(entity, id)| (id.clone(), storage.components(entity).cloned().collect()))
+        .collect();
+    let removed = known
+        .into_iter()
+        .filter(|entity_id| storage.index_of(entity_id).is_none_or(|entity| !is_persistent(&storage, entity)))
+        .cloned()
+        .collect();
+    WorldChanges { changed, removed, change_count: storage.changes() }
+}
+
+/// Component type as tagged in scene JSON, or the kind for plugin components
+pub fn component_kind(component: &Component) -> String {
+    match component {
+        Component::Plugin(plugin) => plugin.kind.clone(),
+        _ => format!("{:?}", component.kind()),
+    }
 }
 
 /// Serialize the entire component map to JSON
 pub fn serialize_to_json() -> Result<String, serde_json::Error> {
-    let map = COMPONENT_MAP.read().unwrap();
-    serde_json::to_string_pretty(&*map)
-}
-
-/// Serialize component map to JSON, excluding entities with is_persist = false
-pub fn serialize_to_json_filtered() -> Result<String, serde_json::Error> {
-    let map = COMPONENT_MAP.read().unwrap();
-    
-    let filtered_map: HashMap<String, Vec<Component>> = map
-        .iter()
-        .filter(|(_, components)| {
-            // Check if entity should be persisted
-            for component in components.iter() {
-                if let Component::Metadata(metadata) = component {
-                    return metadata.is_persist;
-                }
-            }
-            true // Include entities without Metadata (backward compatibility)
-        })
-        .map(|(k, v)| (k.clone(), v.clone()))
-        .collect();
-    
-    serde_json::to_string_pretty(&filtered_map)
+    let storage = STORAGE.read().unwrap();
+    serde_json::to_string_pretty(&entity_component_lists(&storage, |_| true))
+}
+
+/// Each entity `keep` accepts with its components, the map scenes are saved as
+fn entity_component_lists(
+    storage: &ComponentStorage,
+    keep: impl Fn(EntityIndex) -> bool
+) -> HashMap<&EntityId, Vec<&Component>> {
+    storage
+        .entities()
+        .filter(|(entity, _)| keep(*entity))
+        .map(|(entity, id)| (id, storage.components(entity).collect()))
+        .collect()
+}
+
+/// Serialize the scene file: the header, then every entity except those with is_persist = false
+pub fn serialize_scene(mut header: SceneHeader) -> Result<String, serde_json::Error> {
+    #[derive(Serialize)]
+    struct SceneFile<'a> {
+        header: SceneHeader,
+        entities: HashMap<&'a EntityId, Vec<&'a Component>>,
+    }
+
+    let storage = STORAGE.read().unwrap();
+    // Entities without Metadata are saved
+    let entities = entity_component_lists(&storage, |entity| is_persistent(&storage, entity));
+    header.entity_count = entities.len();
+
+    serde_json::to_string_pretty(&SceneFile { header, entities })
 }
 
-/// Deserialize the entire component map from JSON
+/// Deserialize the component map from JSON, keeping the saved entity IDs.
+/// Runtime-only entities (is_persist = false) survive the load; a saved ID that is empty, repeated in the
+/// file or already used by one of them is re-keyed with a warning instead of silently dropping an entity.
 pub fn deserialize_from_json(json: &str) -> Result<(), serde_json::Error> {
-    let new_map: HashMap<String, Vec<Component>> = serde_json::from_str(json)?;
-    let mut map = COMPONENT_MAP.write().unwrap();
-    *map = new_map;
+    deserialize_scene(json, &HashSet::new())
+}
+
+/// deserialize_from_json leaving out the saved entities whose IDs are in `skipped` (safe mode)
+pub fn deserialize_scene(json: &str, skipped: &HashSet<String>) -> Result<(), serde_json::Error> {
+    let entities: Vec<(String, Vec<Component>)> = if skipped.is_empty() {
+        read_scene_entities(json)?
+    } else {
+        // Skipped entities are never parsed, so ones that don't parse can't fail the load
+        read_scene_entities::<serde_json::Value>(json)?
+            .into_iter()
+            .filter(|(id, _)| !skipped.contains(id))
+            .map(|(id, components)| Ok((id, serde_json::from_value(components)?)))
+            .collect::<Result<_, serde_json::Error>>()?
+    };
+    forget_scene_state();
+    let mut storage = STORAGE.write().unwrap();
+    remove_persistent(&mut storage);
+
+    for (id, components) in entities {
+        let id = if id.trim().is_empty() || storage.contains(&id) {
+            let new_id = Uuid::new_v4().to_string();
+            eprintln!("⚠️ Entity ID '{}' collides with another entity, loaded as {}", id, new_id);
+            new_id
+        } else {
+            id
+        };
+        storage.spawn(id, components);
+    }
+
     Ok(())
 }
 
+/// Saved entities of a scene file in file order with their components left as JSON, for SceneValidation
+pub fn scene_entities_json(json: &str) -> Result<Vec<(String, serde_json::Value)>, serde_json::Error> {
+    read_scene_entities(json)
+}
+
+fn read_scene_entities<T: for<'de> Deserialize<'de>>(json: &str) -> Result<Vec<(String, T)>, serde_json::Error> {
+    /// Scenes saved before headers existed are the bare entity map
+    #[derive(Deserialize)]
+    struct SceneFile<T> {
+        entities: Option<SceneEntities<T>>,
+    }
+    let SceneEntities(entities) = match serde_json::from_str::<SceneFile<T>>(json)?.entities {
+        Some(entities) => entities,
+        None => serde_json::from_str(json)?,
+    };
+    Ok(entities)
+}
+
+fn is_persistent(storage: &ComponentStorage, entity: EntityIndex) -> bool {
+    storage
+        .get(entity, ComponentKind::Metadata)
+        .and_then(Metadata::downcast)
+        .is_none_or(|metadata| metadata.is_persist)
+}
+
+/// Remove the saved entities, keeping the runtime-only ones (is_persist = false)
+fn remove_persistent(storage: &mut ComponentStorage) {
+    let persistent: Vec<EntityId> = storage
+        .entities()
+        .filter(|(entity, _)| is_persistent(storage, *entity))
+        .map(|(_, id)| id.clone())
+        .collect();
+    for id in persistent {
+        storage.remove(&id);
+    }
+}
+
+/// Scene entities in file order; unlike a HashMap this keeps duplicate IDs so they can be reported
+struct SceneEntities<T>(Vec<(String, T)>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for SceneEntities<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+        struct EntitiesVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> serde::de::Visitor<'de> for EntitiesVisitor<T> {
+            type Value = SceneEntities<T>;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a map of entity IDs to component lists")
+            }
+
+            fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error> where A: serde::de::MapAccess<'de> {
+                let mut entities = Vec::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some(entry) = access.next_entry::<String, T>()? {
+                    entities.push(entry);
+                }
+                Ok(SceneEntities(entities))
+            }
+        }
+
+        deserializer.deserialize_map(EntitiesVisitor(PhantomData))
+    }
+}
+
+/// Drop what refers to the entities of a scene being replaced: pooled instances and queued commands
+fn forget_scene_state() {
+    EntityPool::clear();
+    DEFERRED_COMMANDS.lock().unwrap().clear();
+}
+
 /// Clear all entities and components
 pub fn clear_world() {
-    let mut map = COMPONENT_MAP.write().unwrap();
-    map.clear();
+    forget_scene_state();
+    *STORAGE.write().unwrap() = ComponentStorage::default();
+}
+
+/// Remove the saved entities, keeping the runtime-only ones a scene load keeps
+pub fn clear_scene() {
+    forget_scene_state();
+    remove_persistent(&mut STORAGE.write().unwrap());
 }
 
 // ——————————————————————————————————————————————————————————— Conversion Traits ————
@@ -326,6 +704,150 @@ impl From<RigidBody> for Component {
     }
 }
 
+impl From<NavMesh> for Component {
+    fn from(n: NavMesh) -> Self {
+        Component::NavMesh(n)
+    }
+}
+
+impl From<NavAgent> for Component {
+    fn from(n: NavAgent) -> Self {
+        Component::NavAgent(n)
+    }
+}
+
+impl From<AiAgent> for Component {
+    fn from(a: AiAgent) -> Self {
+        Component::AiAgent(a)
+    }
+}
+
+impl From<Health> for Component {
+    fn from(h: Health) -> Self {
+        Component::Health(h)
+    }
+}
+
+impl From<Faction> for Component {
+    fn from(f: Faction) -> Self {
+        Component::Faction(f)
+    }
+}
+
+impl From<Spawner> for Component {
+    fn from(s: Spawner) -> Self {
+        Component::Spawner(s)
+    }
+}
+
+impl From<TimeOfDay> for Component {
+    fn from(t: TimeOfDay) -> Self {
+        Component::TimeOfDay(t)
+    }
+}
+
+impl From<Terrain> for Component {
+    fn from(t: Terrain) -> Self {
+        Component::Terrain(t)
+    }
+}
+
+impl From<Blockout> for Component {
+    fn from(b: Blockout) -> Self {
+        Component::Blockout(b)
+    }
+}
+
+impl From<Spline> for Component {
+    fn from(s: Spline) -> Self {
+        Component::Spline(s)
+    }
+}
+
+impl From<Volume> for Component {
+    fn from(v: Volume) -> Self {
+        Component::Volume(v)
+    }
+}
+
+impl From<Water> for Component {
+    fn from(w: Water) -> Self {
+        Component::Water(w)
+    }
+}
+
+impl From<PointLight> for Component {
+    fn from(l: PointLight) -> Self {
+        Component::PointLight(l)
+    }
+}
+
+impl From<BakedOcclusion> for Component {
+    fn from(o: BakedOcclusion) -> Self {
+        Component::BakedOcclusion(o)
+    }
+}
+
+impl From<ReflectionProbe> for Component {
+    fn from(p: ReflectionProbe) -> Self {
+        Component::ReflectionProbe(p)
+    }
+}
+
+impl From<ColorGrading> for Component {
+    fn from(g: ColorGrading) -> Self {
+        Component::ColorGrading(g)
+    }
+}
+
+impl From<CustomMaterial> for Component {
+    fn from(m: CustomMaterial) -> Self {
+        Component::CustomMaterial(m)
+    }
+}
+
+impl From<PropertyAnimation> for Component {
+    fn from(a: PropertyAnimation) -> Self {
+        Component::PropertyAnimation(a)
+    }
+}
+
+impl From<Ragdoll> for Component {
+    fn from(r: Ragdoll) -> Self {
+        Component::Ragdoll(r)
+    }
+}
+
+impl From<KinematicMover> for Component {
+    fn from(m: KinematicMover) -> Self {
+        Component::KinematicMover(m)
+    }
+}
+
+impl From<Projectile> for Component {
+    fn from(p: Projectile) -> Self {
+        Component::Projectile(p)
+    }
+}
+
+impl From<Interactable> for Component {
+    fn from(i: Interactable) -> Self {
+        Component::Interactable(i)
+    }
+}
+
+impl From<PlayerController> for Component {
+    fn from(c: PlayerController) -> Self {
+        Component::PlayerController(c)
+    }
+}
+
+impl From<PluginComponent> for Component {
+    fn from(p: PluginComponent) -> Self {
+        Component::Plugin(p)
+    }
+}
+
 // Implement TryInto<T> for Component to extract specific types
 impl TryInto<Transform> for Component {
     type Error = ();
@@ -404,6 +926,337 @@ impl TryInto<Shape> for Component {
     }
 }
 
+impl TryInto<NavMesh> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<NavMesh, Self::Error> {
+        match self {
+            Component::NavMesh(n) => Ok(n),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<NavAgent> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<NavAgent, Self::Error> {
+        match self {
+            Component::NavAgent(n) => Ok(n),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<AiAgent> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<AiAgent, Self::Error> {
+        match self {
+            Component::AiAgent(a) => Ok(a),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Health> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Health, Self::Error> {
+        match self {
+            Component::Health(h) => Ok(h),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Faction> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Faction, Self::Error> {
+        match self {
+            Component::Faction(f) => Ok(f),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Spawner> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Spawner, Self::Error> {
+        match self {
+            Component::Spawner(s) => Ok(s),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<TimeOfDay> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<TimeOfDay, Self::Error> {
+        match self {
+            Component::TimeOfDay(t) => Ok(t),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Terrain> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Terrain, Self::Error> {
+        match self {
+            Component::Terrain(t) => Ok(t),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Blockout> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Blockout, Self::Error> {
+        match self {
+            Component::Blockout(b) => Ok(b),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Spline> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Spline, Self::Error> {
+        match self {
+            Component::Spline(s) => Ok(s),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Volume> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Volume, Self::Error> {
+        match self {
+            Component::Volume(v) => Ok(v),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<RigidBody> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<RigidBody, Self::Error> {
+        match self {
+            Component::RigidBody(r) => Ok(r),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Water> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Water, Self::Error> {
+        match self {
+            Component::Water(w) => Ok(w),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<PointLight> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<PointLight, Self::Error> {
+        match self {
+            Component::PointLight(l) => Ok(l),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<BakedOcclusion> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<BakedOcclusion, Self::Error> {
+        match self {
+            Component::BakedOcclusion(o) => Ok(o),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<ReflectionProbe> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<ReflectionProbe, Self::Error> {
+        match self {
+            Component::ReflectionProbe(p) => Ok(p),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<ColorGrading> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<ColorGrading, Self::Error> {
+        match self {
+            Component::ColorGrading(g) => Ok(g),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<CustomMaterial> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<CustomMaterial, Self::Error> {
+        match self {
+            Component::CustomMaterial(m) => Ok(m),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<PropertyAnimation> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<PropertyAnimation, Self::Error> {
+        match self {
+            Component::PropertyAnimation(a) => Ok(a),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Ragdoll> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Ragdoll, Self::Error> {
+        match self {
+            Component::Ragdoll(r) => Ok(r),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<KinematicMover> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<KinematicMover, Self::Error> {
+        match self {
+            Component::KinematicMover(m) => Ok(m),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Projectile> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Projectile, Self::Error> {
+        match self {
+            Component::Projectile(p) => Ok(p),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<Interactable> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<Interactable, Self::Error> {
+        match self {
+            Component::Interactable(i) => Ok(i),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<PlayerController> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<PlayerController, Self::Error> {
+        match self {
+            Component::PlayerController(c) => Ok(c),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryInto<PluginComponent> for Component {
+    type Error = ();
+
+    fn try_into(self) -> Result<PluginComponent, Self::Error> {
+        match self {
+            Component::Plugin(p) => Ok(p),
+            _ => Err(()),
+        }
+    }
+}
+
+// ——————————————————————————————————————————————————————————— Command Buffer ————
+
+/// A structural change queued by a CommandBuffer
+pub enum Command {
+    Spawn(EntityId, Vec<Component>),
+    Insert(EntityId, Box<Component>),
+    Despawn(EntityId),
+}
+
+/// Commands submitted by systems, applied by `World::apply_commands`
+static DEFERRED_COMMANDS: Mutex<Vec<Command>> = Mutex::new(Vec::new());
+
+/// Structural changes a system collects while it iterates and submits for after the tick's systems, instead of
+/// changing the world under a running `query!` (its write-back would resurrect despawned components) or inside a
+/// `query_by_id!` closure (which holds the world's lock, so spawning or despawning there deadlocks)
+#[derive(Default)]
+pub struct CommandBuffer {
+    commands: Vec<Command>,
+}
+
+impl CommandBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a new entity with `components`; its ID is handed out now so later commands can refer to it
+    #[allow(dead_code)]
+    pub fn spawn(&mut self, components: Vec<Component>) -> EntityId {
+        let entity_id = Uuid::new_v4().to_string();
+        self.commands.push(Command::Spawn(entity_id.clone(), components));
+        entity_id
+    }
+
+    /// Queue a component insert, replacing the entity's component of that type; ignored if the entity is gone by then
+    #[allow(dead_code)]
+    pub fn insert<T>(&mut self, entity_id: &EntityId, component: T) where T: Into<Component> {
+        self.commands.push(Command::Insert(entity_id.clone(), Box::new(component.into())));
+    }
+
+    /// Queue a despawn; pooled instances go back to their EntityPool
+    pub fn despawn(&mut self, entity_id: &EntityId) {
+        self.commands.push(Command::Despawn(entity_id.clone()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Hand the commands to the world, to run at the next `World::apply_commands`
+    pub fn submit(self) {
+        if !self.is_empty() {
+            DEFERRED_COMMANDS.lock().unwrap().extend(self.commands);
+        }
+    }
+}
+
 // ——————————————————————————————————————————————————————————— Compatibility Layer ————
 
 /// Legacy World struct for compatibility (now just a wrapper)
@@ -418,29 +1271,25 @@ impl World {
         insert(entity_id, component);
     }
 
-    pub fn get_component_readonly<T>(&self, entity_id: &EntityId) -> Option<T>
-        where T: Clone, Component: TryInto<T>
-    {
+    pub fn get_component_readonly<T: StoredComponent>(&self, entity_id: &EntityId) -> Option<T> {
         get_component(entity_id)
     }
 
-    pub fn query_get_all<T>(&self) -> Vec<(EntityId, T)> where T: Clone, Component: TryInto<T> {
+    pub fn query_get_all<T: StoredComponent>(&self) -> Vec<(EntityId, T)> {
         query_all()
     }
 
-    pub fn query_get_all2<T1, T2>(&self) -> Vec<(EntityId, T1, T2)>
-        where T1: Clone, T2: Clone, Component: TryInto<T1> + TryInto<T2>
-    {
+    pub fn query_get_all2<T1: StoredComponent, T2: StoredComponent>(&self) -> Vec<(EntityId, T1, T2)> {
         query_all2()
     }
 
     pub fn query_get_all3<T1, T2, T3>(&self) -> Vec<(EntityId, T1, T2, T3)>
-        where T1: Clone, T2: Clone, T3: Clone, Component: TryInto<T1> + TryInto<T2> + TryInto<T3>
+        where T1: StoredComponent, T2: StoredComponent, T3: StoredComponent
     {
         query_all3()
     }
 
-    pub fn query_get_all_ids<T>(&self) -> Vec<EntityId> where Component: TryInto<T> {
+    pub fn query_get_all_ids<T: StoredComponent>(&self) -> Vec<EntityId> {
         query_get_all_ids::<T>()
     }
 
@@ -452,9 +1301,51 @@ impl World {
         delete_entity(entity_id)
     }
 
+    pub fn despawn(&mut self, entity_id: &EntityId) -> bool {
+        despawn(entity_id)
+    }
+
     pub fn get_all_entities(&self) -> Vec<(EntityId, usize)> {
         get_all_entities()
     }
+
+    /// Visit the components of the spawns and inserts submitted but not applied yet
+    pub fn for_each_pending_component_mut(mut visit: impl FnMut(&mut Component)) {
+        for command in DEFERRED_COMMANDS.lock().unwrap().iter_mut() {
+            match command {
+                Command::Spawn(_, components) => components.iter_mut().for_each(&mut visit),
+                Command::Insert(_, component) => visit(component),
+                Command::Despawn(_) => {}
+            }
+        }
+    }
+
+    /// Apply the commands systems submitted, in submission order, and refresh the editor's entity list once if
+    /// entities came or went; run by Program after each tick's systems. Returns how many commands ran
+    pub fn apply_commands() -> usize {
+        let commands = std::mem::take(&mut *DEFERRED_COMMANDS.lock().unwrap());
+        let count = commands.len();
+        let mut structural = false;
+        for command in commands {
+            match command {
+                Command::Spawn(entity_id, components) => {
+                    restore_entity(entity_id, components);
+                    structural = true;
+                }
+                Command::Insert(entity_id, component) => insert(&entity_id, *component),
+                Command::Despawn(entity_id) => {
+                    if EntityPool::release(&entity_id) || delete_entity(&entity_id) {
+                        Selection::remove(&entity_id);
+                        structural = true;
+                    }
+                }
+            }
+        }
+        if structural {
+            InterfaceSystem::update_entities_list();
+        }
+        count
+    }
 }
 
 impl Default for World {
@@ -480,5 +1371,7 @@ thread_local! {
 // - query_all3::<T1, T2, T3>() -> Vec<(EntityId, T1, T2, T3)>
 // - copy_entity(source_id) -> Option<EntityId>
 // - delete_entity(entity_id) -> bool
+// - despawn(entity_id) -> bool, also keeps the selection and the editor's entity list in sync
+// - CommandBuffer::despawn/spawn/insert + submit() while iterating, applied by World::apply_commands()
 // - serialize_to_json() -> Result<String, serde_json::Error>
 // - deserialize_from_json(json) -> Result<(), serde_json::Error>
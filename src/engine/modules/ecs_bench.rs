@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::{ Duration, Instant };
+use uuid::Uuid;
+
+use crate::index::engine::components::{ rigid_body::RigidBody, Health, Metadata, Transform };
+use crate::index::engine::modules::ecs::{ query_params2_in, Component, EntityId, QueryParam };
+use crate::index::engine::modules::ecs_storage::ComponentStorage;
+
+/// Entities spawned by the `ecs_bench` console command when not given a count
+const DEFAULT_ENTITIES: usize = 10_000;
+/// Runs of each query; the fastest is reported
+const ROUNDS: usize = 20;
+
+/// Benchmark of `query!` on the sparse-set ComponentStorage against the per-entity component lists the world used
+/// to be stored as, on the same generated scene: finding the rows alone, and the whole `query!` path, which also
+/// writes every row's components back with an `insert` each. Runs on its own storage, so the world isn't touched
+pub struct EcsBench;
+
+impl EcsBench {
+    /// Time two-component queries over `entities` entities, each with a Transform and Metadata, every 4th with a
+    /// RigidBody and every 10th with Health; returns a report
+    pub fn run(entities: usize) -> String {
+        let mut storage = ComponentStorage::default();
+        let mut lists: ComponentLists = HashMap::new();
+        for index in 0..entities {
+            let mut components = vec![
+                Component::Transform(Transform::new(index as f32, 0.0, 0.0)),
+                Component::Metadata(Metadata::new(&format!("Bench {}", index), None, Some(false))),
+            ];
+            if index % 4 == 0 {
+                components.push(Component::RigidBody(RigidBody::new()));
+            }
+            if index % 10 == 0 {
+                components.push(Component::Health(Health::new(100.0)));
+            }
+            let entity_id = Uuid::new_v4().to_string();
+            storage.spawn(entity_id.clone(), components.clone());
+            lists.insert(entity_id, components);
+        }
+        // Locked like the world's storage, which query! takes once to find the rows and once per write-back
+        let (storage, lists) = (RwLock::new(storage), RwLock::new(lists));
+
+        let mut lines = vec![format!("{} entities, fastest of {} runs:", entities, ROUNDS)];
+        lines.push(Self::compare::<Transform, RigidBody>("(Transform, RigidBody)", &storage, &lists));
+        lines.push(Self::compare::<Transform, Health>("(Transform, Health)", &storage, &lists));
+        lines.join("\n")
+    }
+
+    fn compare<T1, T2>(name: &str, storage: &RwLock<ComponentStorage>, lists: &RwLock<ComponentLists>) -> String
+        where
+            T1: QueryParam<Item = T1> + Into<Component>,
+            T2: QueryParam<Item = T2> + Into<Component>,
+            Component: TryInto<T1> + TryInto<T2>
+    {
+        let (matched, find) = fastest(|| query_params2_in::<T1, T2>(&storage.read().unwrap()).len());
+        let (legacy_matched, legacy_find) = fastest(|| legacy_query2::<T1, T2>(&lists.read().unwrap()).len());
+        debug_assert_eq!(matched, legacy_matched);
+
+        let (_, query) = fastest(|| {
+            let rows = query_params2_in::<T1, T2>(&storage.read().unwrap());
+            let matched = rows.len();
+            for (entity_id, a1, a2) in rows {
+                T1::write_back_in(&mut storage.write().unwrap(), &entity_id, a1);
+                T2::write_back_in(&mut storage.write().unwrap(), &entity_id, a2);
+            }
+            matched
+        });
+        let (_, legacy_query) = fastest(|| {
+            let rows = legacy_query2::<T1, T2>(&lists.read().unwrap());
+            let matched = rows.len();
+            for (entity_id, a1, a2) in rows {
+                legacy_insert(&mut lists.write().unwrap(), &entity_id, a1.into());
+                legacy_insert(&mut lists.write().unwrap(), &entity_id, a2.into());
+            }
+            matched
+        });
+
+        format!(
+            "{} matching {}: finding rows {}, query! with write-back {}",
+            name,
+            matched,
+            speedup(legacy_find, find),
+            speedup(legacy_query, query)
+        )
+    }
+
+    /// Console command `ecs_bench [entities]`
+    pub fn bench_command(args: &[&str]) -> Result<String, String> {
+        let entities = match args.first() {
+            Some(count) => count.parse().map_err(|_| format!("Invalid entity count: {}", count))?,
+            None => DEFAULT_ENTITIES,
+        };
+        Ok(Self::run(entities))
+    }
+}
+
+/// Per-entity component lists, as the world was stored before the sparse sets
+type ComponentLists = HashMap<EntityId, Vec<Component>>;
+
+/// "lists X ms, sparse sets Y ms (Zx)"
+fn speedup(legacy: Duration, sparse_set: Duration) -> String {
+    format!(
+        "lists {:.3} ms, sparse sets {:.3} ms ({:.1}x)",
+        legacy.as_secs_f64() * 1000.0,
+        sparse_set.as_secs_f64() * 1000.0,
+        legacy.as_secs_f64() / sparse_set.as_secs_f64().max(f64::EPSILON)
+    )
+}
+
+/// Result and duration of the fastest of ROUNDS runs
+fn fastest(mut run: impl FnMut() -> usize) -> (usize, Duration) {
+    let (mut result, mut best) = (0, Duration::MAX);
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        result = run();
+        best = best.min(start.elapsed());
+    }
+    (result, best)
+}
+
+/// Two-component query as it ran on per-entity component lists: every component of every entity cloned and tried
+fn legacy_query2<T1, T2>(lists: &ComponentLists) -> Vec<(EntityId, T1, T2)>
+    where Component: TryInto<T1> + TryInto<T2>
+{
+    let mut results = Vec::new();
+
+    for (entity_id, components) in lists.iter() {
+        let mut comp1: Option<T1> = None;
+        let mut comp2: Option<T2> = None;
+
+        for component in components {
+            if comp1.is_none() {
+                if let Ok(typed_component) = component.clone().try_into() {
+                    comp1 = Some(typed_component);
+                    continue;
+                }
+            }
+            if comp2.is_none() {
+                if let Ok(typed_component) = component.clone().try_into() {
+                    comp2 = Some(typed_component);
+                    continue;
+                }
+            }
+        }
+
+        if let (Some(c1), Some(c2)) = (comp1, comp2) {
+            results.push((entity_id.clone(), c1, c2));
+        }
+    }
+
+    results
+}
+
+/// Insert as it ran on per-entity component lists: replace the entity's component of the same kind, or add it
+fn legacy_insert(lists: &mut ComponentLists, entity_id: &EntityId, component: Component) {
+    let Some(components) = lists.get_mut(entity_id) else {
+        return;
+    };
+    match components.iter_mut().find(|existing| existing.kind() == component.kind()) {
+        Some(existing) => *existing = component,
+        None => components.push(component),
+    }
+}
@@ -15,6 +15,10 @@ macro_rules! insert_many {
     };
 }
 
+/// Run the body for each entity with the queried components. The sparse sets only change how the matching rows are
+/// found: the body still gets copies of the components, written back with `insert` after it (not after a
+/// `continue`), since no storage lock is held while it runs and it may spawn, insert or query freely. The
+/// `ecs_bench` console command times this whole path, write-backs included
 #[macro_export]
 macro_rules! query {
     // Single component
@@ -28,15 +32,29 @@ macro_rules! query {
             }
         }
     };
-    // Two components
+    // Two or three query parameters: components, Option<T> or Without<T> (see QueryParam)
     (($c1:ty, $c2:ty), | $id:ident, $a1:ident, $a2:ident | $body:block) => {
         {
-            let results = $crate::index::engine::modules::ecs::query_all2::<$c1, $c2>();
+            use $crate::index::engine::modules::ecs::QueryParam;
+            let results = $crate::index::engine::modules::ecs::query_params2::<$c1, $c2>();
             #[allow(unused_mut)]
             for ($id, mut $a1, mut $a2) in results {
                 $body
-                $crate::index::engine::modules::ecs::insert(&$id, $a1);
-                $crate::index::engine::modules::ecs::insert(&$id, $a2);
+                <$c1 as QueryParam>::write_back(&$id, $a1);
+                <$c2 as QueryParam>::write_back(&$id, $a2);
+            }
+        }
+    };
+    (($c1:ty, $c2:ty, $c3:ty), | $id:ident, $a1:ident, $a2:ident, $a3:ident | $body:block) => {
+        {
+            use $crate::index::engine::modules::ecs::QueryParam;
+            let results = $crate::index::engine::modules::ecs::query_params3::<$c1, $c2, $c3>();
+            #[allow(unused_mut)]
+            for ($id, mut $a1, mut $a2, mut $a3) in results {
+                $body
+                <$c1 as QueryParam>::write_back(&$id, $a1);
+                <$c2 as QueryParam>::write_back(&$id, $a2);
+                <$c3 as QueryParam>::write_back(&$id, $a3);
             }
         }
     };
@@ -125,6 +143,15 @@ macro_rules! delete_entity {
     };
 }
 
+#[macro_export]
+macro_rules! despawn {
+    ($entity_id:expr) => {
+        {
+            $crate::index::engine::modules::ecs::despawn(&$entity_id)
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! get_all_components_dyn {
     ($entity_id:expr) => {
@@ -136,43 +163,68 @@ macro_rules! get_all_components_dyn {
 
 // ——————————————————————————————————————————————————————————— Serialization Macros ————
 
-/// Save the ECS state directly to JSON using filtered serialization (excludes non-persistent entities)
+/// Save the ECS state to a scene file under a SceneHeader (excludes non-persistent entities) and queue its thumbnail.
+/// The world is serialized right away; the file is written by a background task (see SceneLibrary::write_save)
 #[macro_export]
 macro_rules! save_world {
     ($path:expr) => {
         {
-            use std::fs;
-            match $crate::index::engine::modules::ecs::serialize_to_json_filtered() {
+            let header = $crate::index::engine::modules::scene_library::SceneHeader::for_save($path);
+            match $crate::index::engine::modules::ecs::serialize_scene(header) {
                 Ok(json) => {
-                    match fs::write($path, json) {
-                        Ok(()) => println!("💾 Saved world to {} (excluding non-persistent entities)", $path),
-                        Err(e) => eprintln!("❌ Failed to write file {}: {}", $path, e),
-                    }
+                    let path = ($path).to_string();
+                    let generation = $crate::index::engine::modules::scene_library::SceneLibrary::expect_save(&path, &json);
+                    let write_path = path.clone();
+                    $crate::index::engine::modules::task_system::TaskSystem::spawn(
+                        &format!("save {}", path),
+                        move || $crate::index::engine::modules::scene_library::SceneLibrary::write_save(&write_path, &json, generation),
+                        move |result, _gl| match result {
+                            // A newer save of the path was made meanwhile and writes it
+                            Ok(false) => {}
+                            Ok(true) => {
+                                println!("💾 Saved world to {} (excluding non-persistent entities)", path);
+                                $crate::index::engine::modules::scene_library::SceneLibrary::request_thumbnail(&path);
+                                // Outliner groups and camera bookmarks live in the editor sidecar next to the scene
+                                if let Err(e) = $crate::index::engine::modules::editor_sidecar::EditorSidecar::save(&path) {
+                                    $crate::index::engine::modules::interface_system::InterfaceSystem::report_error(&e);
+                                }
+                            }
+                            Err(e) => $crate::index::engine::modules::interface_system::InterfaceSystem::report_error(&e),
+                        }
+                    );
                 }
-                Err(e) => eprintln!("❌ Failed to serialize world: {}", e),
+                Err(e) => $crate::index::engine::modules::interface_system::InterfaceSystem::report_error(
+                    &$crate::index::engine::modules::engine_error::EngineError::scene($path, format!("Failed to serialize world: {}", e))
+                ),
             }
         }
     };
 }
 
-/// Load the ECS state directly from JSON using serde_json::from_str
+/// Load the ECS state directly from JSON using serde_json::from_str. A scene that fails validation, or the first
+/// one after a crash, is held back by SafeMode with an empty world instead
 #[macro_export]
 macro_rules! load_world {
     ($path:expr) => {
         {
-            use std::fs;
-            match fs::read_to_string($path) {
+            match $crate::index::engine::managers::AssetPack::read_text($path) {
+                Ok(json) if $crate::index::engine::modules::safe_mode::SafeMode::intercept($path, &json) => {}
                 Ok(json) => {
                     match $crate::index::engine::modules::ecs::deserialize_from_json(&json) {
                         Ok(()) => {
                             println!("📂 Loaded world from {}", $path);
+                            if let Err(e) = $crate::index::engine::modules::editor_sidecar::EditorSidecar::load($path) {
+                                $crate::index::engine::modules::interface_system::InterfaceSystem::report_error(&e);
+                            }
                             // Update UI if available
                             $crate::index::engine::modules::interface_system::InterfaceSystem::update_entities_list();
                         }
-                        Err(e) => eprintln!("❌ Failed to deserialize world: {}", e),
+                        Err(e) => $crate::index::engine::modules::interface_system::InterfaceSystem::report_error(
+                            &$crate::index::engine::modules::engine_error::EngineError::scene($path, format!("Failed to deserialize world: {}", e))
+                        ),
                     }
                 }
-                Err(e) => eprintln!("❌ Failed to read file {}: {}", $path, e),
+                Err(e) => $crate::index::engine::modules::interface_system::InterfaceSystem::report_error(&e),
             }
         }
     };
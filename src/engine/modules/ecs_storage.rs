@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use crate::index::engine::modules::ecs::{ Component, ComponentKind, EntityId };
+
+/// Dense index of a live entity, reused once the entity is removed
+pub type EntityIndex = u32;
+
+/// Sparse entry of an entity without a component in the column
+const ABSENT: u32 = u32::MAX;
+
+static EMPTY_COLUMN: Column = Column::new();
+
+/// Which column stores a component: one per component type, and one per kind of plugin component
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum Slot {
+    Builtin(ComponentKind),
+    Plugin(String),
+}
+
+impl Slot {
+    pub fn of(component: &Component) -> Self {
+        match component {
+            Component::Plugin(plugin) => Slot::Plugin(plugin.kind.clone()),
+            _ => Slot::Builtin(component.kind()),
+        }
+    }
+}
+
+/// Sparse set of one component type: the components packed in a dense vector next to the entity owning each, and
+/// a sparse array from entity index to dense position. Removal swaps the last component into the hole
+pub struct Column {
+    dense: Vec<Component>,
+    owners: Vec<EntityIndex>,
+    sparse: Vec<u32>,
+}
+
+impl Column {
+    const fn new() -> Self {
+        Column { dense: Vec::new(), owners: Vec::new(), sparse: Vec::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.dense.len()
+    }
+
+    /// Entities with a component in this column, in dense order
+    pub fn owners(&self) -> &[EntityIndex] {
+        &self.owners
+    }
+
+    pub fn get(&self, entity: EntityIndex) -> Option<&Component> {
+        let position = *self.sparse.get(entity as usize)?;
+        self.dense.get(position as usize)
+    }
+
+    fn get_mut(&mut self, entity: EntityIndex) -> Option<&mut Component> {
+        let position = *self.sparse.get(entity as usize)?;
+        self.dense.get_mut(position as usize)
+    }
+
+    /// Store the entity's component; true if it had none in this column before
+    fn set(&mut self, entity: EntityIndex, component: Component) -> bool {
+        if let Some(existing) = self.get_mut(entity) {
+            *existing = component;
+            return false;
+        }
+        if self.sparse.len() <= entity as usize {
+            self.sparse.resize(entity as usize + 1, ABSENT);
+        }
+        self.sparse[entity as usize] = self.dense.len() as u32;
+        self.dense.push(component);
+        self.owners.push(entity);
+        true
+    }
+
+    fn remove(&mut self, entity: EntityIndex) -> Option<Component> {
+        let position = *self.sparse.get(entity as usize).filter(|position| **position != ABSENT)?;
+        self.sparse[entity as usize] = ABSENT;
+        let component = self.dense.swap_remove(position as usize);
+        self.owners.swap_remove(position as usize);
+        if let Some(&moved) = self.owners.get(position as usize) {
+            self.sparse[moved as usize] = position;
+        }
+        Some(component)
+    }
+}
+
+struct EntityRecord {
+    id: EntityId,
+    slots: Vec<Slot>, // Columns holding its components, in the order they were first inserted (listed and saved)
+    changed: u64,     // Storage change count of its last spawn, insert or mutable borrow
+}
+
+/// Component storage of the world: a sparse set per component type, so a query walks the packed components of its
+/// rarest required type and finds the others by index, instead of scanning every entity's component list. Only the
+/// matched components are cloned out for `query!`; they aren't borrowed in place
+#[derive(Default)]
+pub struct ComponentStorage {
+    indices: HashMap<EntityId, EntityIndex>,
+    entities: Vec<Option<EntityRecord>>,
+    free: Vec<EntityIndex>,
+    builtin: Vec<Column>, // Indexed by ComponentKind
+    plugins: HashMap<String, Column>,
+    changes: u64, // Spawns, inserts, mutable borrows and removals so far
+}
+
+impl ComponentStorage {
+    pub fn contains(&self, entity_id: &str) -> bool {
+        self.indices.contains_key(entity_id)
+    }
+
+    pub fn index_of(&self, entity_id: &str) -> Option<EntityIndex> {
+        self.indices.get(entity_id).copied()
+    }
+
+    /// ID of a live entity, e.g. one of a column's owners
+    pub fn id_of(&self, entity: EntityIndex) -> &EntityId {
+        &self.record(entity).id
+    }
+
+    fn record(&self, entity: EntityIndex) -> &EntityRecord {
+        self.entities[entity as usize].as_ref().expect("entity index of a removed entity")
+    }
+
+    /// Count of changes so far, to find the entities changed since with `changed_since`
+    pub fn changes(&self) -> u64 {
+        self.changes
+    }
+
+    /// Live entities spawned, inserted into or mutably borrowed after the change count `since`; any mutable borrow
+    /// counts, whether or not the component was then modified
+    pub fn changed_since(&self, since: u64) -> impl Iterator<Item = (EntityIndex, &EntityId)> {
+        self.entities
+            .iter()
+            .enumerate()
+            .filter_map(move |(entity, record)| {
+                let record = record.as_ref().filter(|record| record.changed > since)?;
+                Some((entity as EntityIndex, &record.id))
+            })
+    }
+
+    /// Change count of an entity's last spawn, insert or mutable borrow
+    pub fn changed_at(&self, entity: EntityIndex) -> u64 {
+        self.record(entity).changed
+    }
+
+    fn touch(&mut self, entity: EntityIndex) {
+        self.changes += 1;
+        if let Some(record) = self.entities.get_mut(entity as usize).and_then(Option::as_mut) {
+            record.changed = self.changes;
+        }
+    }
+
+    /// Every live entity
+    pub fn entities(&self) -> impl Iterator<Item = (EntityIndex, &EntityId)> {
+        self.entities
+            .iter()
+            .enumerate()
+            .filter_map(|(entity, record)| Some((entity as EntityIndex, &record.as_ref()?.id)))
+    }
+
+    /// Add an entity with `components`; None if the ID is taken
+    pub fn spawn(&mut self, entity_id: EntityId, components: Vec<Component>) -> Option<EntityIndex> {
+        if self.indices.contains_key(&entity_id) {
+            return None;
+        }
+        let record = EntityRecord { id: entity_id.clone(), slots: Vec::new(), changed: 0 };
+        let entity = match self.free.pop() {
+            Some(entity) => {
+                self.entities[entity as usize] = Some(record);
+                entity
+            }
+            None => {
+                self.entities.push(Some(record));
+                (self.entities.len() - 1) as EntityIndex
+            }
+        };
+        self.indices.insert(entity_id, entity);
+        self.touch(entity);
+        for component in components {
+            self.insert(entity, component);
+        }
+        Some(entity)
+    }
+
+    /// Store a component, replacing the entity's component of that slot
+    pub fn insert(&mut self, entity: EntityIndex, component: Component) {
+        self.touch(entity);
+        let slot = Slot::of(&component);
+        let added = match &slot {
+            Slot::Builtin(kind) => {
+                let index = *kind as usize;
+                if self.builtin.len() <= index {
+                    self.builtin.resize_with(index + 1, Column::new);
+                }
+                self.builtin[index].set(entity, component)
+            }
+            Slot::Plugin(kind) => self.plugins.entry(kind.clone()).or_insert_with(Column::new).set(entity, component),
+        };
+        if added {
+            self.entities[entity as usize].as_mut().expect("entity index of a removed entity").slots.push(slot);
+        }
+    }
+
+    /// The entity's component of `kind`; for plugin components the first one inserted
+    pub fn get(&self, entity: EntityIndex, kind: ComponentKind) -> Option<&Component> {
+        match kind {
+            ComponentKind::Plugin => self.record(entity).slots
+                .iter()
+                .filter(|slot| matches!(slot, Slot::Plugin(_)))
+                .find_map(|slot| self.slot_component(entity, slot)),
+            _ => self.builtin.get(kind as usize)?.get(entity),
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: EntityIndex, kind: ComponentKind) -> Option<&mut Component> {
+        self.touch(entity);
+        let slot = match kind {
+            ComponentKind::Plugin =>
+                self.record(entity).slots.iter().find(|slot| matches!(slot, Slot::Plugin(_)))?.clone(),
+            _ => Slot::Builtin(kind),
+        };
+        match slot {
+            Slot::Builtin(kind) => self.builtin.get_mut(kind as usize)?.get_mut(entity),
+            Slot::Plugin(kind) => self.plugins.get_mut(&kind)?.get_mut(entity),
+        }
+    }
+
+    fn slot_component(&self, entity: EntityIndex, slot: &Slot) -> Option<&Component> {
+        match slot {
+            Slot::Builtin(kind) => self.builtin.get(*kind as usize)?.get(entity),
+            Slot::Plugin(kind) => self.plugins.get(kind)?.get(entity),
+        }
+    }
+
+    /// The entity's components in insertion order
+    pub fn components(&self, entity: EntityIndex) -> impl Iterator<Item = &Component> {
+        self.record(entity).slots.iter().filter_map(move |slot| self.slot_component(entity, slot))
+    }
+
+    pub fn component_count(&self, entity: EntityIndex) -> usize {
+        self.record(entity).slots.len()
+    }
+
+    /// Column of a component type, empty if none was stored yet; None for plugin components, which have a column
+    /// per kind
+    pub fn column(&self, kind: ComponentKind) -> Option<&Column> {
+        match kind {
+            ComponentKind::Plugin => None,
+            _ => Some(self.builtin.get(kind as usize).unwrap_or(&EMPTY_COLUMN)),
+        }
+    }
+
+    /// Rows built from the entities that have every `required` component: the owners of the smallest of those
+    /// columns are walked, or every entity when none is required (or only plugin components are)
+    pub fn query<R>(
+        &self,
+        required: &[Option<ComponentKind>],
+        row: impl FnMut(EntityIndex) -> Option<R>
+    ) -> Vec<R> {
+        let driver = required
+            .iter()
+            .flatten()
+            .filter_map(|kind| self.column(*kind))
+            .min_by_key(|column| column.len());
+        match driver {
+            Some(column) => column.owners().iter().copied().filter_map(row).collect(),
+            None => self.entities().map(|(entity, _)| entity).filter_map(row).collect(),
+        }
+    }
+
+    /// Remove an entity, handing back its components in insertion order
+    pub fn remove(&mut self, entity_id: &str) -> Option<Vec<Component>> {
+        let entity = self.indices.remove(entity_id)?;
+        let record = self.entities[entity as usize].take()?;
+        self.free.push(entity);
+        self.changes += 1;
+        let components = record.slots
+            .into_iter()
+            .filter_map(|slot| match slot {
+                Slot::Builtin(kind) => self.builtin.get_mut(kind as usize)?.remove(entity),
+                Slot::Plugin(kind) => self.plugins.get_mut(&kind)?.remove(entity),
+            })
+            .collect();
+        Some(components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::engine::components::{ Metadata, PluginComponent, Transform };
+
+    fn transform(x: f32) -> Component {
+        Transform::new(x, 0.0, 0.0).into()
+    }
+
+    fn position(storage: &ComponentStorage, entity_id: &str) -> Option<f32> {
+        let entity = storage.index_of(entity_id)?;
+        match storage.get(entity, ComponentKind::Transform)? {
+            Component::Transform(transform) => Some(transform.get_position()[0]),
+            _ => None,
+        }
+    }
+
+    fn plugin(kind: &str) -> Component {
+        Component::Plugin(PluginComponent { kind: kind.to_string(), data: serde_json::Value::Null })
+    }
+
+    #[test]
+    fn spawn_rejects_a_taken_id() {
+        let mut storage = ComponentStorage::default();
+        assert_eq!(storage.spawn("a".to_string(), vec![transform(1.0)]), Some(0));
+        assert_eq!(storage.spawn("a".to_string(), vec![transform(2.0)]), None);
+        assert_eq!(position(&storage, "a"), Some(1.0));
+    }
+
+    #[test]
+    fn removal_moves_the_last_component_into_the_hole() {
+        let mut storage = ComponentStorage::default();
+        for (entity_id, x) in [("a", 1.0), ("b", 2.0), ("c", 3.0)] {
+            storage.spawn(entity_id.to_string(), vec![transform(x)]);
+        }
+
+        let removed = storage.remove("a").unwrap();
+        assert!(matches!(removed.as_slice(), [Component::Transform(_)]));
+        assert!(!storage.contains("a"));
+        assert_eq!(position(&storage, "b"), Some(2.0));
+        assert_eq!(position(&storage, "c"), Some(3.0));
+
+        let column = storage.column(ComponentKind::Transform).unwrap();
+        assert_eq!(column.len(), 2);
+        let mut owners: Vec<&str> = column.owners().iter().map(|entity| storage.id_of(*entity).as_str()).collect();
+        owners.sort();
+        assert_eq!(owners, ["b", "c"]);
+        assert!(storage.remove("a").is_none());
+    }
+
+    #[test]
+    fn a_removed_index_is_reused_without_its_components() {
+        let mut storage = ComponentStorage::default();
+        storage.spawn("a".to_string(), vec![transform(1.0), Metadata::new("a", None, None).into()]);
+        storage.spawn("b".to_string(), vec![transform(2.0)]);
+        let freed = storage.index_of("a").unwrap();
+        storage.remove("a");
+
+        let reused = storage.spawn("c".to_string(), vec![]).unwrap();
+        assert_eq!(reused, freed);
+        assert_eq!(storage.component_count(reused), 0);
+        assert!(storage.get(reused, ComponentKind::Transform).is_none());
+        assert!(storage.get(reused, ComponentKind::Metadata).is_none());
+        assert_eq!(storage.column(ComponentKind::Metadata).unwrap().len(), 0);
+        assert_eq!(position(&storage, "b"), Some(2.0));
+    }
+
+    #[test]
+    fn inserting_again_replaces_in_place() {
+        let mut storage = ComponentStorage::default();
+        let entity = storage.spawn("a".to_string(), vec![transform(1.0)]).unwrap();
+        storage.insert(entity, Metadata::new("a", None, None).into());
+        storage.insert(entity, transform(5.0));
+
+        assert_eq!(storage.component_count(entity), 2);
+        assert_eq!(storage.column(ComponentKind::Transform).unwrap().len(), 1);
+        assert_eq!(position(&storage, "a"), Some(5.0));
+        // Listed in the order first inserted
+        let kinds: Vec<ComponentKind> = storage.components(entity).map(Component::kind).collect();
+        assert_eq!(kinds, [ComponentKind::Transform, ComponentKind::Metadata]);
+    }
+
+    #[test]
+    fn query_walks_the_rarest_column() {
+        let mut storage = ComponentStorage::default();
+        for index in 0..4 {
+            storage.spawn(format!("moved {}", index), vec![transform(index as f32)]);
+        }
+        storage.spawn("named".to_string(), vec![transform(9.0), Metadata::new("named", None, None).into()]);
+
+        let mut visited = Vec::new();
+        let rows = storage.query(&[Some(ComponentKind::Transform), Some(ComponentKind::Metadata)], |entity| {
+            visited.push(entity);
+            storage.get(entity, ComponentKind::Transform).map(|_| storage.id_of(entity).clone())
+        });
+        assert_eq!(rows, ["named"]);
+        assert_eq!(visited.len(), 1);
+        assert_eq!(storage.query(&[None], Some).len(), 5);
+    }
+
+    #[test]
+    fn plugin_components_get_a_column_per_kind() {
+        let mut storage = ComponentStorage::default();
+        let entity = storage.spawn("a".to_string(), vec![plugin("spin"), plugin("glow")]).unwrap();
+        storage.insert(entity, plugin("spin"));
+
+        assert_eq!(storage.component_count(entity), 2);
+        let Some(Component::Plugin(first)) = storage.get(entity, ComponentKind::Plugin) else {
+            panic!("plugin component missing");
+        };
+        assert_eq!(first.kind, "spin");
+        assert_eq!(storage.remove("a").map(|components| components.len()), Some(2));
+    }
+
+    #[test]
+    fn changes_mark_the_touched_entities() {
+        let mut storage = ComponentStorage::default();
+        let a = storage.spawn("a".to_string(), vec![transform(1.0)]).unwrap();
+        storage.spawn("b".to_string(), vec![transform(2.0)]);
+        let since = storage.changes();
+
+        storage.get_mut(a, ComponentKind::Transform);
+        let changed: Vec<&EntityId> = storage.changed_since(since).map(|(_, entity_id)| entity_id).collect();
+        assert_eq!(changed, ["a"]);
+        assert_eq!(storage.changed_at(a), storage.changes());
+
+        storage.remove("b");
+        assert!(storage.changes() > storage.changed_at(a));
+        assert_eq!(storage.changed_since(0).count(), 1);
+    }
+}
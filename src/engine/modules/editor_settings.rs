@@ -0,0 +1,110 @@
+use std::sync::RwLock;
+use serde::{ Serialize, Deserialize };
+
+/// Smallest and largest UI scale, as multiples of the monitor's scale factor
+pub const MIN_UI_SCALE: f32 = 0.5;
+pub const MAX_UI_SCALE: f32 = 3.0;
+/// Thickest selection outline in pixels
+pub const MAX_OUTLINE_WIDTH: f32 = 16.0;
+
+/// Color theme of the editor panels
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EditorTheme {
+    Dark,
+    Light,
+}
+
+impl EditorTheme {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "dark" => Some(EditorTheme::Dark),
+            "light" => Some(EditorTheme::Light),
+            _ => None,
+        }
+    }
+}
+
+/// Viewport outlines around selected, hovered and interaction-target objects; the `[editor.outline]` table
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct OutlineSettings {
+    pub selected_color: [f32; 3],
+    pub multi_selected_color: [f32; 3], // The rest of a multi-selection
+    pub hovered_color: [f32; 3],
+    pub interaction_color: [f32; 3], // The player's interaction target, also drawn in play mode
+    pub width: f32,                  // Screen-space thickness in pixels, the same at any distance
+    pub occluded_brightness: f32,    // Color multiplier where geometry covers the outline, 0 hides those parts
+}
+
+impl OutlineSettings {
+    const DEFAULT: Self = Self {
+        selected_color: [1.0, 1.0, 0.0],
+        multi_selected_color: [1.0, 0.6, 0.0],
+        hovered_color: [1.0, 1.0, 1.0],
+        interaction_color: [0.3, 0.8, 1.0],
+        width: 3.0,
+        occluded_brightness: 0.4,
+    };
+
+    fn clamped(self) -> Self {
+        let clamp = |value: f32, max: f32, fallback: f32| if value.is_finite() { value.clamp(0.0, max) } else { fallback };
+        Self {
+            width: clamp(self.width, MAX_OUTLINE_WIDTH, Self::DEFAULT.width),
+            occluded_brightness: clamp(self.occluded_brightness, 1.0, Self::DEFAULT.occluded_brightness),
+            ..self
+        }
+    }
+}
+
+impl Default for OutlineSettings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// How the editor looks: the `[editor]` table of the app config, changed at runtime from the Graphics panel and
+/// applied to the Slint tree by `InterfaceSystem::apply_editor_settings`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct EditorSettings {
+    pub theme: EditorTheme,
+    pub ui_scale: f32, // Multiplies the monitor's scale factor, e.g. 1.5 on a 4K monitor that reports 1
+    pub outline: OutlineSettings,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+static CURRENT: RwLock<EditorSettings> = RwLock::new(EditorSettings::DEFAULT);
+/// Scale factor winit reports for the monitor the window is on
+static MONITOR_SCALE: RwLock<f32> = RwLock::new(1.0);
+
+impl EditorSettings {
+    const DEFAULT: Self = Self { theme: EditorTheme::Dark, ui_scale: 1.0, outline: OutlineSettings::DEFAULT };
+
+    pub fn current() -> Self {
+        *CURRENT.read().unwrap()
+    }
+
+    pub fn set(settings: Self) {
+        let ui_scale = if settings.ui_scale.is_finite() {
+            settings.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+        } else {
+            1.0
+        };
+        *CURRENT.write().unwrap() = Self { ui_scale, outline: settings.outline.clamped(), ..settings };
+    }
+
+    pub fn set_monitor_scale(scale_factor: f32) {
+        *MONITOR_SCALE.write().unwrap() = scale_factor;
+    }
+
+    /// Scale factor the Slint window should use: the monitor's times the UI scale
+    pub fn scale_factor() -> f32 {
+        *MONITOR_SCALE.read().unwrap() * Self::current().ui_scale
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::modules::camera_bookmarks::{ CameraBookmark, CameraBookmarks };
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::modules::outliner_groups::{ OutlinerGroup, OutlinerGroups };
+
+/// Editor state saved next to the scene file, so the scene itself stays game data only
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct EditorSidecar {
+    #[serde(default)]
+    pub groups: Vec<OutlinerGroup>,
+    #[serde(default)]
+    pub camera_bookmarks: BTreeMap<u8, CameraBookmark>,
+}
+
+impl EditorSidecar {
+    /// Sidecar path for a scene, e.g. `scene.json` -> `scene.editor.json`
+    pub fn path(scene_path: &str) -> String {
+        Path::new(scene_path).with_extension("editor.json").to_string_lossy().to_string()
+    }
+
+    /// Restore the editor state of a scene; a scene without a sidecar starts empty
+    pub fn load(scene_path: &str) -> EngineResult<()> {
+        let path = Self::path(scene_path);
+        let sidecar = match std::fs::read_to_string(&path) {
+            Ok(json) => serde_json::from_str::<EditorSidecar>(&json)
+                .map_err(|e| EngineError::scene(&path, format!("Failed to read editor sidecar: {}", e)))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => EditorSidecar::default(),
+            Err(e) => return Err(EngineError::io(&path, e.to_string())),
+        };
+        println!(
+            "📁 Loaded {} outliner groups and {} camera bookmarks from {}",
+            sidecar.groups.len(),
+            sidecar.camera_bookmarks.len(),
+            path
+        );
+        OutlinerGroups::replace(sidecar.groups);
+        CameraBookmarks::replace(sidecar.camera_bookmarks);
+        Ok(())
+    }
+
+    /// Write the current editor state next to the scene
+    pub fn save(scene_path: &str) -> EngineResult<()> {
+        let path = Self::path(scene_path);
+        OutlinerGroups::prune();
+        let sidecar = EditorSidecar {
+            groups: OutlinerGroups::groups(),
+            camera_bookmarks: CameraBookmarks::all(),
+        };
+        let json = serde_json::to_string_pretty(&sidecar)
+            .map_err(|e| EngineError::scene(&path, format!("Failed to serialize editor sidecar: {}", e)))?;
+        std::fs::write(&path, json).map_err(|e| EngineError::io(&path, e.to_string()))
+    }
+}
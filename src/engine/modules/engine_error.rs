@@ -0,0 +1,63 @@
+use std::fmt;
+
+/// Recoverable engine failures; callers report them and fall back to placeholders instead of panicking
+#[derive(Debug, Clone)]
+pub enum EngineError {
+    Io { path: String, message: String },
+    Asset { asset: String, message: String },    // GLTF parsing or missing mesh/skin data
+    Texture { asset: String, message: String },  // Image decoding or texture upload
+    Shader { program: String, message: String }, // Compile or link failure
+    Gpu { message: String },                     // GL object creation failure
+    Scene { path: String, message: String },
+    Task { name: String, message: String },      // Background task that panicked
+    NotInitialized(&'static str),
+}
+
+pub type EngineResult<T> = Result<T, EngineError>;
+
+impl EngineError {
+    pub fn asset(asset: &str, message: impl Into<String>) -> Self {
+        EngineError::Asset { asset: asset.to_string(), message: message.into() }
+    }
+
+    pub fn texture(asset: &str, message: impl Into<String>) -> Self {
+        EngineError::Texture { asset: asset.to_string(), message: message.into() }
+    }
+
+    pub fn shader(program: &str, message: impl Into<String>) -> Self {
+        EngineError::Shader { program: program.to_string(), message: message.into() }
+    }
+
+    pub fn gpu(message: impl Into<String>) -> Self {
+        EngineError::Gpu { message: message.into() }
+    }
+
+    pub fn io(path: &str, message: impl Into<String>) -> Self {
+        EngineError::Io { path: path.to_string(), message: message.into() }
+    }
+
+    pub fn scene(path: &str, message: impl Into<String>) -> Self {
+        EngineError::Scene { path: path.to_string(), message: message.into() }
+    }
+
+    pub fn task(name: &str, message: impl Into<String>) -> Self {
+        EngineError::Task { name: name.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::Io { path, message } => write!(f, "Failed to access {}: {}", path, message),
+            EngineError::Asset { asset, message } => write!(f, "Asset {}: {}", asset, message),
+            EngineError::Texture { asset, message } => write!(f, "Texture for {}: {}", asset, message),
+            EngineError::Shader { program, message } => write!(f, "Shader {}: {}", program, message),
+            EngineError::Gpu { message } => write!(f, "GPU error: {}", message),
+            EngineError::Scene { path, message } => write!(f, "Scene {}: {}", path, message),
+            EngineError::Task { name, message } => write!(f, "Task {}: {}", name, message),
+            EngineError::NotInitialized(what) => write!(f, "{} is not initialized", what),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+
+use crate::index::engine::managers::PrefabLibrary;
+use crate::index::engine::modules::ecs::{
+    despawn,
+    entity_exists,
+    get_all_components,
+    restore_entity,
+    take_entity,
+    Component,
+    EntityId,
+};
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::modules::selection::Selection;
+
+/// Entities of one instance, root first, with their components
+type InstanceEntities = Vec<(EntityId, Vec<Component>)>;
+
+struct Pool {
+    capacity: usize,
+    parked: Vec<InstanceEntities>, // Released instances waiting to be acquired, out of the world
+}
+
+/// A live instance spawned for a pool
+struct PooledInstance {
+    pool: String,
+    fresh: InstanceEntities, // Its components right after spawning, what it's reset to when released
+}
+
+static POOLS: Lazy<RwLock<HashMap<String, Pool>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+/// Keyed by root entity
+static INSTANCES: Lazy<RwLock<HashMap<EntityId, PooledInstance>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Recycles despawned instances of designated prefabs (or any other spawn function, e.g. projectiles) instead of
+/// deleting them: a released instance leaves the world with its entity IDs and component storage kept, reset to how
+/// it was spawned, and the next acquire puts it back. Parked instances aren't queried, drawn or saved
+pub struct EntityPool;
+
+impl EntityPool {
+    /// Keep up to `capacity` released instances of `name` for reuse; 0 stops pooling it and drops the parked ones
+    pub fn designate(name: &str, capacity: usize) {
+        let dropped = {
+            let mut pools = POOLS.write().unwrap();
+            let pool = pools.entry(name.to_string()).or_insert_with(|| Pool { capacity, parked: Vec::new() });
+            pool.capacity = capacity;
+            let dropped = pool.parked.split_off(capacity.min(pool.parked.len()));
+            if capacity == 0 {
+                pools.remove(name);
+            }
+            dropped
+        };
+        let mut instances = INSTANCES.write().unwrap();
+        for entities in dropped {
+            instances.remove(&entities[0].0);
+        }
+    }
+
+    /// Put a parked instance of `name` back into the world, or spawn a new one with `spawn` (remembered for reuse
+    /// if `name` is pooled); returns the root. The caller positions it like a fresh spawn
+    pub fn acquire(name: &str, spawn: impl FnOnce() -> Option<EntityId>) -> Option<EntityId> {
+        let parked = POOLS.write().unwrap().get_mut(name).and_then(|pool| pool.parked.pop());
+        if let Some(entities) = parked {
+            let root_id = entities[0].0.clone();
+            for (entity_id, components) in entities {
+                restore_entity(entity_id, components);
+            }
+            return Some(root_id);
+        }
+
+        let root_id = spawn()?;
+        if POOLS.read().unwrap().contains_key(name) {
+            let fresh = std::iter::once(root_id.clone())
+                .chain(PrefabLibrary::parts(&root_id))
+                .map(|entity_id| {
+                    let components = get_all_components(&entity_id);
+                    (entity_id, components)
+                })
+                .collect();
+            INSTANCES.write().unwrap().insert(root_id.clone(), PooledInstance { pool: name.to_string(), fresh });
+        }
+        Some(root_id)
+    }
+
+    /// Take a pooled instance out of the world and park it; false if `root_id` wasn't acquired from a pool or the
+    /// pool is full, leaving the entity for the caller to delete. Doesn't refresh the editor's entity list
+    pub fn release(root_id: &EntityId) -> bool {
+        let mut instances = INSTANCES.write().unwrap();
+        let Some(pool_name) = instances.get(root_id).map(|instance| instance.pool.clone()) else {
+            return false;
+        };
+        let mut pools = POOLS.write().unwrap();
+        let pool = pools.get_mut(&pool_name).filter(|pool| pool.parked.len() < pool.capacity);
+        let (Some(pool), true) = (pool, entity_exists(root_id)) else {
+            instances.remove(root_id);
+            return false;
+        };
+
+        let mut entities = Vec::new();
+        for (entity_id, fresh) in &instances[root_id].fresh {
+            let Some(mut components) = take_entity(entity_id) else {
+                continue;
+            };
+            // Reset into the entity's own storage
+            components.clone_from(fresh);
+            Selection::remove(entity_id);
+            entities.push((entity_id.clone(), components));
+        }
+        pool.parked.push(entities);
+        true
+    }
+
+    /// Release `root_id` to its pool, or delete it like `despawn`; refreshes the editor's entity list either way
+    pub fn despawn(root_id: &EntityId) -> bool {
+        if Self::release(root_id) {
+            InterfaceSystem::update_entities_list();
+            true
+        } else {
+            despawn(root_id)
+        }
+    }
+
+    /// Visit the components kept outside the world: parked instances and what live ones are reset to
+    pub fn for_each_component_mut(mut visit: impl FnMut(&mut Component)) {
+        let mut pools = POOLS.write().unwrap();
+        let parked = pools.values_mut().flat_map(|pool| pool.parked.iter_mut().flatten());
+        let mut instances = INSTANCES.write().unwrap();
+        let fresh = instances.values_mut().flat_map(|instance| instance.fresh.iter_mut());
+        for (_, components) in parked.chain(fresh) {
+            components.iter_mut().for_each(&mut visit);
+        }
+    }
+
+    /// Drop the parked instances and forget the pooled ones, when the scene they were spawned into is replaced;
+    /// the designations stay
+    pub fn clear() {
+        for pool in POOLS.write().unwrap().values_mut() {
+            pool.parked.clear();
+        }
+        INSTANCES.write().unwrap().clear();
+    }
+
+    /// Console command `pools [name capacity]`: pool `name` (0 stops pooling it), or list the pools
+    pub fn pools_command(args: &[&str]) -> Result<String, String> {
+        match args {
+            [] => {
+                let pools = POOLS.read().unwrap();
+                if pools.is_empty() {
+                    return Ok("No pools; designate one with pools <name> <capacity>".to_string());
+                }
+                let instances = INSTANCES.read().unwrap();
+                let mut names: Vec<&String> = pools.keys().collect();
+                names.sort();
+                let lines: Vec<String> = names
+                    .into_iter()
+                    .map(|name| {
+                        let count = instances.values().filter(|instance| &instance.pool == name).count();
+                        let pool = &pools[name];
+                        let parked = pool.parked.len();
+                        format!("{}: {} instances, {} parked (capacity {})", name, count, parked, pool.capacity)
+                    })
+                    .collect();
+                Ok(lines.join("\n"))
+            }
+            [name, capacity] => {
+                let capacity: usize = capacity.parse().map_err(|_| format!("Invalid capacity: {}", capacity))?;
+                Self::designate(name, capacity);
+                Ok(match capacity {
+                    0 => format!("Stopped pooling {}", name),
+                    _ => format!("Pooling up to {} released instances of {}", capacity, name),
+                })
+            }
+            _ => Err("Usage: pools [name capacity]".to_string()),
+        }
+    }
+}
@@ -1,11 +1,25 @@
-use std::sync::{ Arc, OnceLock };
+use std::cell::Cell;
+use std::sync::{ Arc, Mutex, OnceLock };
 use std::any::Any;
 use dashmap::DashMap;
 
 #[derive(Hash, Eq, PartialEq, Debug, Clone, Copy)]
 pub enum EventType {
-    Move,
-    RotateCamera,
+    Move,         // Payload: MoveEvent, each tick while direction keys are held
+    RotateCamera, // Payload: [pitch, yaw] radians from one mouse motion
+    Damage,      // Payload: DamageEvent
+    Death,       // Payload: DeathEvent
+    PointerDrag, // Payload: PointerEvent, sent each frame while dragging in the viewport
+    PointerRelease, // Payload: PointerEvent, the primary button was released
+    ProjectileHit, // Payload: ProjectileHitEvent
+    Use,           // Payload: (), the player pressed the use key in Play mode
+    Interacted,    // Payload: InteractEvent
+    CameraBookmark, // Payload: BookmarkEvent, number keys in Edit mode
+    FrameView,      // Payload: FrameTarget, F/A in Edit mode
+    ViewPreset,     // Payload: ViewPreset, numpad keys in Edit mode
+    Footstep,       // Payload: FootstepEvent
+    TaskCompleted,  // Payload: TaskCompletedEvent
+    AnimationMarker, // Payload: AnimationMarkerEvent, playback passed a timeline marker
 }
 
 pub struct Event {
@@ -13,19 +27,47 @@ pub struct Event {
     pub payload: Box<dyn Any + Send + Sync>,
 }
 
+impl Event {
+    /// Called from a handler: listeners with a lower priority don't receive this event
+    pub fn consume(&self) {
+        CONSUMED.with(|consumed| consumed.set(true));
+    }
+}
+
 // Import System trait from parent scope
 use crate::index::engine::components::SystemTrait;
 
+/// Listener priority of plain `subscribe`; higher priorities receive events first
+pub const DEFAULT_PRIORITY: i32 = 0;
+
 static EVENT_SYSTEM: OnceLock<EventSystem> = OnceLock::new();
 
+thread_local! {
+    /// Set by Event::consume during the innermost dispatch
+    static CONSUMED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Subscribed system and its priority
+type Listener = (i32, Arc<dyn SystemTrait>);
+
+/// Sent events wait in `writing`; a drain swaps it with the empty `spare` and delivers the swapped-out buffer,
+/// so events sent while draining land in the next drain instead of re-entering this one
+#[derive(Default)]
+struct EventBuffers {
+    writing: Vec<Event>,
+    spare: Vec<Event>,
+}
+
 pub struct EventSystem {
-    subscribers: DashMap<EventType, Vec<Arc<dyn SystemTrait>>>,
+    subscribers: DashMap<EventType, Vec<Listener>>,
+    buffers: Mutex<EventBuffers>,
 }
 
 impl EventSystem {
     pub fn initialize() {
         EVENT_SYSTEM.set(EventSystem {
             subscribers: DashMap::new(),
+            buffers: Mutex::new(EventBuffers::default()),
         }).expect("EventSystem already initialized");
     }
 
@@ -34,19 +76,61 @@ impl EventSystem {
     }
 
     pub fn subscribe(event_type: EventType, system: Arc<dyn SystemTrait>) {
-        let instance = Self::instance();
-        instance.subscribers.entry(event_type).or_insert_with(Vec::new).push(system);
+        Self::subscribe_with_priority(event_type, DEFAULT_PRIORITY, system);
     }
 
+    /// Subscribe ahead of (higher priority) or behind (lower) other listeners; equal priorities keep registration order
+    pub fn subscribe_with_priority(event_type: EventType, priority: i32, system: Arc<dyn SystemTrait>) {
+        let instance = Self::instance();
+        let mut listeners = instance.subscribers.entry(event_type).or_insert_with(Vec::new);
+        let index = listeners.partition_point(|(other, _)| *other >= priority);
+        listeners.insert(index, (priority, system));
+    }
 
+    /// Deliver an event immediately, by priority, until a handler consumes it. Meant for window input that
+    /// arrives between ticks; simulation code uses `send`
     pub fn notify(event: Event) {
         let instance = Self::instance();
 
-        if let Some(systems) = instance.subscribers.get(&event.event_type) {
-            for system in systems.iter() {
-                system.event(&event);
+        // Handlers may subscribe or notify, so don't hold the map entry while they run
+        let listeners: Vec<Arc<dyn SystemTrait>> = match instance.subscribers.get(&event.event_type) {
+            Some(listeners) => listeners.iter().map(|(_, system)| system.clone()).collect(),
+            None => Vec::new(),
+        };
+
+        let outer_consumed = CONSUMED.with(|consumed| consumed.replace(false));
+        for system in listeners {
+            system.event(&event);
+            if CONSUMED.with(|consumed| consumed.get()) {
+                break;
             }
         }
+        CONSUMED.with(|consumed| consumed.set(outer_consumed));
+    }
+
+    /// Buffer an event until the next drain point of the schedule (before and after the fixed systems of a tick)
+    pub fn send(event: Event) {
+        Self::instance().buffers.lock().unwrap().writing.push(event);
+    }
+
+    /// Deliver every event sent since the last drain, in send order; events sent by their handlers wait for
+    /// the next drain
+    pub fn drain() {
+        let instance = Self::instance();
+        let mut reading = {
+            let mut buffers = instance.buffers.lock().unwrap();
+            let spare = std::mem::take(&mut buffers.spare);
+            std::mem::replace(&mut buffers.writing, spare)
+        };
+        for event in reading.drain(..) {
+            Self::notify(event);
+        }
+
+        // Keep the allocation for the next swap
+        let mut buffers = instance.buffers.lock().unwrap();
+        if buffers.spare.capacity() < reading.capacity() {
+            buffers.spare = reading;
+        }
     }
 }
 
@@ -55,3 +139,86 @@ impl std::fmt::Debug for EventSystem {
         f.debug_struct("EventSystem").field("subscribers_count", &self.subscribers.len()).finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::MutexGuard;
+
+    /// Tests share the event system; each uses its own event type and runs alone
+    static TEST_LOCK: Mutex<()> = Mutex::new(());
+
+    /// Records the u32 payloads it receives; re-sends `resend` the first time it sees 0
+    struct Recorder {
+        seen: Mutex<Vec<u32>>,
+        resend: Option<EventType>,
+    }
+
+    impl SystemTrait for Recorder {
+        fn event(&self, event: &Event) {
+            let value = *event.payload.downcast_ref::<u32>().unwrap();
+            self.seen.lock().unwrap().push(value);
+            if let Some(event_type) = self.resend.filter(|_| value == 0) {
+                EventSystem::send(Event { event_type, payload: Box::new(1u32) });
+            }
+        }
+    }
+
+    fn listen(event_type: EventType, resend: Option<EventType>) -> (MutexGuard<'static, ()>, Arc<Recorder>) {
+        let guard = TEST_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        EVENT_SYSTEM.get_or_init(|| EventSystem {
+            subscribers: DashMap::new(),
+            buffers: Mutex::new(EventBuffers::default()),
+        });
+        EventSystem::drain();
+        let recorder = Arc::new(Recorder { seen: Mutex::new(Vec::new()), resend });
+        EventSystem::subscribe(event_type, recorder.clone());
+        (guard, recorder)
+    }
+
+    fn send(event_type: EventType, value: u32) {
+        EventSystem::send(Event { event_type, payload: Box::new(value) });
+    }
+
+    #[test]
+    fn sent_events_wait_for_the_drain_and_keep_their_order() {
+        let (_guard, recorder) = listen(EventType::Footstep, None);
+        for value in [3, 1, 2] {
+            send(EventType::Footstep, value);
+        }
+        assert!(recorder.seen.lock().unwrap().is_empty());
+
+        EventSystem::drain();
+        assert_eq!(*recorder.seen.lock().unwrap(), [3, 1, 2]);
+        EventSystem::drain();
+        assert_eq!(recorder.seen.lock().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn events_sent_while_draining_wait_for_the_next_drain() {
+        let (_guard, recorder) = listen(EventType::TaskCompleted, Some(EventType::TaskCompleted));
+        send(EventType::TaskCompleted, 0);
+
+        EventSystem::drain();
+        assert_eq!(*recorder.seen.lock().unwrap(), [0]);
+        EventSystem::drain();
+        assert_eq!(*recorder.seen.lock().unwrap(), [0, 1]);
+    }
+
+    #[test]
+    fn events_sent_from_other_threads_are_drained_on_this_one() {
+        let (_guard, recorder) = listen(EventType::ProjectileHit, None);
+        let senders: Vec<_> = (0..4)
+            .map(|value| std::thread::spawn(move || send(EventType::ProjectileHit, value)))
+            .collect();
+        for sender in senders {
+            sender.join().unwrap();
+        }
+        assert!(recorder.seen.lock().unwrap().is_empty());
+
+        EventSystem::drain();
+        let mut seen = recorder.seen.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(seen, [0, 1, 2, 3]);
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Mutex;
+use serde_json::Value;
+
+use crate::index::engine::components::Metadata;
+use crate::index::engine::modules::ecs::{ get_all_components, get_all_entities, insert, Component, EntityId };
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::get_query_by_id;
+
+/// Replacements that can be undone, oldest first
+const UNDO_DEPTH: usize = 20;
+/// Matches listed by the `find` console command
+const LISTED_MATCHES: usize = 20;
+
+/// Each replacement's original components, so `undo` can put them back
+static UNDO: Mutex<Vec<Vec<(EntityId, Component)>>> = Mutex::new(Vec::new());
+
+/// A component field that matched a search
+pub struct FieldMatch {
+    pub entity_id: EntityId,
+    pub title: String, // Metadata title, or the ID without Metadata
+    pub locked: bool,  // Locked entities are listed but not replaced
+    pub value: String,
+    pub original: Component,
+    pub replaced: Result<Component, String>, // The component with the replacement, or why it doesn't fit the field
+}
+
+/// Scene-wide search of one component field, e.g. every `Collider.layer` that is `Environment`, and batch
+/// replacement of the matches with undo
+pub struct FindReplace;
+
+impl FindReplace {
+    /// Visit the original components kept to undo replacements
+    pub fn for_each_component_mut(mut visit: impl FnMut(&mut Component)) {
+        UNDO.lock().unwrap().iter_mut().flatten().for_each(|(_, component)| visit(component));
+    }
+
+    /// Fields at `path` (`Component.field`; more dots reach into nested fields, e.g. `Collider.material.friction`)
+    /// whose value matches `pattern`, where `*` stands for any text, each with `replacement` applied
+    pub fn preview(path: &str, pattern: &str, replacement: &str) -> Result<Vec<FieldMatch>, String> {
+        let (component_type, field) = path
+            .split_once('.')
+            .ok_or_else(|| format!("Expected Component.field (e.g. Collider.layer), got {}", path))?;
+        let field: Vec<&str> = field.split('.').collect();
+
+        let mut matches = Vec::new();
+        for (entity_id, _) in get_all_entities() {
+            for component in get_all_components(&entity_id) {
+                let Ok(mut json) = serde_json::to_value(&component) else {
+                    continue;
+                };
+                // Plugin components are searched by their kind
+                let kind = json.get("kind").and_then(Value::as_str);
+                let type_name = json.get("type").and_then(Value::as_str);
+                if type_name != Some(component_type) && !(type_name == Some("Plugin") && kind == Some(component_type)) {
+                    continue;
+                }
+                let data_field = if type_name == Some("Plugin") { Some("data") } else { None };
+                let Some(slot) = data_field.into_iter().chain(field.iter().copied()).try_fold(&mut json, |value, key| {
+                    match value {
+                        Value::Object(object) => object.get_mut(key),
+                        Value::Array(array) => key.parse::<usize>().ok().and_then(|index| array.get_mut(index)),
+                        _ => None,
+                    }
+                }) else {
+                    continue;
+                };
+
+                let value = match &*slot {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                if !wildcard_match(pattern, &value) {
+                    continue;
+                }
+                // Text fields stay text, e.g. a title replaced with "2"
+                *slot = if slot.is_string() {
+                    Value::String(replacement.to_string())
+                } else {
+                    InterfaceSystem::parse_field_value(replacement)
+                };
+                let metadata = get_query_by_id!(entity_id.clone(), (Metadata));
+                matches.push(FieldMatch {
+                    title: metadata.as_ref().map_or_else(|| entity_id.clone(), |metadata| metadata.title.clone()),
+                    locked: metadata.is_some_and(|metadata| metadata.locked),
+                    entity_id: entity_id.clone(),
+                    value,
+                    original: component,
+                    replaced: serde_json::from_value(json).map_err(|e| e.to_string()),
+                });
+            }
+        }
+        matches.sort_by(|a, b| a.title.cmp(&b.title).then_with(|| a.entity_id.cmp(&b.entity_id)));
+        Ok(matches)
+    }
+
+    /// Replace every match of `preview` that fits the field, skipping locked entities, as one undo step; returns
+    /// a summary
+    pub fn replace(path: &str, pattern: &str, replacement: &str) -> Result<String, String> {
+        let matches = Self::preview(path, pattern, replacement)?;
+        let mut originals = Vec::new();
+        let (mut locked, mut invalid) = (0, Vec::new());
+        for field_match in matches {
+            match field_match.replaced {
+                _ if field_match.locked => locked += 1,
+                Ok(component) => {
+                    insert(&field_match.entity_id, component);
+                    originals.push((field_match.entity_id, field_match.original));
+                }
+                Err(e) => invalid.push(format!("{}: {}", field_match.title, e)),
+            }
+        }
+
+        let mut summary = format!("Replaced {} in {} components", path, originals.len());
+        if locked > 0 {
+            summary.push_str(&format!(", skipped {} locked", locked));
+        }
+        if !invalid.is_empty() {
+            summary.push_str(&format!(", {} didn't fit:\n{}", invalid.len(), invalid.join("\n")));
+        }
+        if !originals.is_empty() {
+            let mut undo = UNDO.lock().unwrap();
+            undo.push(originals);
+            if undo.len() > UNDO_DEPTH {
+                undo.remove(0);
+            }
+            drop(undo);
+            Self::refresh_editor();
+        }
+        Ok(summary)
+    }
+
+    /// Put back the components the last replacement changed
+    pub fn undo() -> Result<String, String> {
+        let originals = UNDO.lock().unwrap().pop().ok_or("Nothing to undo")?;
+        let count = originals.len();
+        for (entity_id, component) in originals {
+            // Deleted entities stay deleted
+            insert(&entity_id, component);
+        }
+        Self::refresh_editor();
+        Ok(format!("Undid the replacement in {} components", count))
+    }
+
+    fn refresh_editor() {
+        InterfaceSystem::update_entities_list();
+        InterfaceSystem::refresh_selection();
+    }
+
+    /// Console command `find <Component.field> <pattern>`: list the matching fields
+    pub fn find_command(args: &[&str]) -> Result<String, String> {
+        let [path, pattern] = args else {
+            return Err("Usage: find <Component.field> <pattern> (* matches any text)".to_string());
+        };
+        let matches = Self::preview(path, pattern, "")?;
+        let mut lines = vec![format!("{} matches of {} = {}", matches.len(), path, pattern)];
+        lines.extend(
+            matches
+                .iter()
+                .take(LISTED_MATCHES)
+                .map(|field_match| format!("{}: {}", field_match.title, field_match.value))
+        );
+        Ok(lines.join("\n"))
+    }
+
+    /// Console command `replace <Component.field> <pattern> <value...>`
+    pub fn replace_command(args: &[&str]) -> Result<String, String> {
+        let [path, pattern, replacement @ ..] = args else {
+            return Err("Usage: replace <Component.field> <pattern> <value>".to_string());
+        };
+        if replacement.is_empty() {
+            return Err("Usage: replace <Component.field> <pattern> <value>".to_string());
+        }
+        Self::replace(path, pattern, &replacement.join(" "))
+    }
+
+    /// Console command `undo_replace`
+    pub fn undo_command(_args: &[&str]) -> Result<String, String> {
+        Self::undo()
+    }
+}
+
+/// Whether `text` matches `pattern` as a whole, `*` matching any run of characters
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty(); // No wildcard: exact match
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
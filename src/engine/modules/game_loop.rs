@@ -0,0 +1,123 @@
+use std::sync::RwLock;
+use std::time::Instant;
+use once_cell::sync::Lazy;
+
+/// Fixed simulation rate in ticks per second
+pub const FIXED_TICK_RATE: f32 = 60.0;
+
+/// Upper bound on ticks run in a single frame so a long stall doesn't snowball
+const MAX_TICKS_PER_FRAME: u32 = 5;
+
+/// Fixed-timestep clock driving the simulation systems (input, physics, animation)
+#[derive(Debug)]
+pub struct GameLoop {
+    fixed_delta: f32,
+    accumulator: f32,
+    frame_delta: f32, // Real seconds between the last two frames
+    last_frame: Option<Instant>,
+    tick_count: u64,
+    is_paused: bool,
+    pending_steps: u32,
+}
+
+static GAME_LOOP: Lazy<RwLock<GameLoop>> = Lazy::new(|| RwLock::new(GameLoop::new()));
+
+impl GameLoop {
+    fn new() -> Self {
+        Self {
+            fixed_delta: 1.0 / FIXED_TICK_RATE,
+            accumulator: 0.0,
+            frame_delta: 0.0,
+            last_frame: None,
+            tick_count: 0,
+            is_paused: false,
+            pending_steps: 0,
+        }
+    }
+
+    /// Advance the frame clock and return how many fixed ticks should run this frame
+    pub fn begin_frame() -> u32 {
+        let mut game_loop = GAME_LOOP.write().unwrap();
+        let now = Instant::now();
+        let elapsed = game_loop.last_frame
+            .map(|last| now.duration_since(last).as_secs_f32())
+            .unwrap_or(0.0);
+        game_loop.last_frame = Some(now);
+        // Capped like the ticks, so time-based effects don't jump after a stall
+        game_loop.frame_delta = elapsed.min(game_loop.fixed_delta * MAX_TICKS_PER_FRAME as f32);
+
+        let ticks = if game_loop.is_paused {
+            // While paused only explicitly requested steps are simulated
+            game_loop.accumulator = 0.0;
+            std::mem::take(&mut game_loop.pending_steps)
+        } else {
+            game_loop.accumulator += elapsed;
+            let mut ticks = 0;
+            while game_loop.accumulator >= game_loop.fixed_delta && ticks < MAX_TICKS_PER_FRAME {
+                game_loop.accumulator -= game_loop.fixed_delta;
+                ticks += 1;
+            }
+            if ticks == MAX_TICKS_PER_FRAME {
+                game_loop.accumulator = 0.0;
+            }
+            ticks
+        };
+
+        game_loop.tick_count += ticks as u64;
+        ticks
+    }
+
+    /// Pause the simulation; rendering and the editor keep running
+    pub fn pause() {
+        GAME_LOOP.write().unwrap().is_paused = true;
+        println!("⏸️ Simulation paused");
+    }
+
+    /// Resume the simulation from where it was paused
+    pub fn resume() {
+        let mut game_loop = GAME_LOOP.write().unwrap();
+        game_loop.is_paused = false;
+        game_loop.pending_steps = 0;
+        println!("▶️ Simulation resumed");
+    }
+
+    /// Toggle between paused and running, returning the new paused state
+    pub fn toggle_pause() -> bool {
+        if Self::is_paused() {
+            Self::resume();
+            false
+        } else {
+            Self::pause();
+            true
+        }
+    }
+
+    /// Advance exactly one fixed tick on the next frame (pauses the simulation if running)
+    pub fn step() {
+        let mut game_loop = GAME_LOOP.write().unwrap();
+        game_loop.is_paused = true;
+        game_loop.pending_steps += 1;
+        println!("⏭️ Stepping one tick (tick {})", game_loop.tick_count + (game_loop.pending_steps as u64));
+    }
+
+    /// Seconds simulated by one fixed tick, the delta time systems scale per-second rates by
+    pub fn fixed_delta() -> f32 {
+        GAME_LOOP.read().unwrap().fixed_delta
+    }
+
+    /// Real seconds the last frame took, for presentation that eases over wall-clock time (exposure adaptation);
+    /// simulation steps by `fixed_delta` so it plays out the same at any frame rate
+    pub fn frame_delta() -> f32 {
+        GAME_LOOP.read().unwrap().frame_delta
+    }
+
+    /// How far the frame is into the next fixed tick, from 0 (just ticked) to 1
+    pub fn interpolation_alpha() -> f32 {
+        let game_loop = GAME_LOOP.read().unwrap();
+        (game_loop.accumulator / game_loop.fixed_delta).clamp(0.0, 1.0)
+    }
+
+    pub fn is_paused() -> bool {
+        GAME_LOOP.read().unwrap().is_paused
+    }
+}
@@ -0,0 +1,170 @@
+use std::cell::Cell;
+use std::sync::RwLock;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::managers::assets_manager::material_textures;
+
+/// Named bundle of graphics settings; changing a single setting makes the bundle Custom
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityPreset {
+    Low,
+    Medium,
+    High,
+    Custom,
+}
+
+impl QualityPreset {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "low" => Some(QualityPreset::Low),
+            "medium" => Some(QualityPreset::Medium),
+            "high" => Some(QualityPreset::High),
+            _ => None,
+        }
+    }
+}
+
+/// How the scene's edges are smoothed
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AntialiasingMode {
+    Off,
+    Fxaa, // Full-screen pass over the finished frame, cheap but softens textures a little
+    Msaa, // Multisampled scene target with `msaa_samples` samples
+}
+
+impl AntialiasingMode {
+    /// In the order `set_option("antialiasing", index)` and the Graphics panel number them
+    pub const ALL: [Self; 3] = [AntialiasingMode::Off, AntialiasingMode::Fxaa, AntialiasingMode::Msaa];
+}
+
+/// Graphics settings the render passes read every frame: the `[graphics]` table of the app config, changed at
+/// runtime from the Graphics panel
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub struct GraphicsQuality {
+    pub preset: QualityPreset,
+    pub shadow_resolution: u32, // Shadow map size in texels, for lights once they cast shadows
+    pub antialiasing: AntialiasingMode,
+    pub msaa_samples: u8,       // Samples of the target the scene renders into with Msaa antialiasing
+    pub anisotropy: f32,        // Anisotropic filtering of mesh textures, 1 disables it
+    pub ssao: bool,             // Screen-space ambient occlusion, one of the post effects
+    pub depth_prepass: bool,    // Depth-only pass over opaque static meshes before they're lit
+    pub post_effects: bool,     // Full-screen passes over the finished scene
+    pub texture_budget_mb: u32, // GPU memory mesh textures stream their finer mip levels into
+}
+
+impl GraphicsQuality {
+    pub const LOW: Self = Self {
+        preset: QualityPreset::Low,
+        shadow_resolution: 512,
+        antialiasing: AntialiasingMode::Off,
+        msaa_samples: 0,
+        anisotropy: 1.0,
+        ssao: false,
+        depth_prepass: false,
+        post_effects: false,
+        texture_budget_mb: 128,
+    };
+    pub const MEDIUM: Self = Self {
+        preset: QualityPreset::Medium,
+        shadow_resolution: 1024,
+        antialiasing: AntialiasingMode::Fxaa,
+        msaa_samples: 2,
+        anisotropy: 4.0,
+        ssao: true,
+        depth_prepass: false,
+        post_effects: true,
+        texture_budget_mb: 512,
+    };
+    pub const HIGH: Self = Self {
+        preset: QualityPreset::High,
+        shadow_resolution: 2048,
+        antialiasing: AntialiasingMode::Msaa,
+        msaa_samples: 4,
+        anisotropy: 16.0,
+        ssao: true,
+        depth_prepass: true,
+        post_effects: true,
+        texture_budget_mb: 2048,
+    };
+
+    /// Settings of a preset; Custom has none of its own
+    pub fn preset(preset: QualityPreset) -> Option<Self> {
+        match preset {
+            QualityPreset::Low => Some(Self::LOW),
+            QualityPreset::Medium => Some(Self::MEDIUM),
+            QualityPreset::High => Some(Self::HIGH),
+            QualityPreset::Custom => None,
+        }
+    }
+
+    pub fn current() -> Self {
+        *CURRENT.read().unwrap()
+    }
+
+    pub fn set(quality: Self) {
+        *CURRENT.write().unwrap() = quality;
+    }
+
+    /// Change one setting by name (as the Graphics panel names them), turning the preset into Custom
+    pub fn set_option(name: &str, value: f32) -> Result<Self, String> {
+        let mut quality = Self::current();
+        match name {
+            "shadow_resolution" => quality.shadow_resolution = (value as u32).clamp(256, 8192),
+            "antialiasing" => {
+                quality.antialiasing = *AntialiasingMode::ALL
+                    .get(value as usize)
+                    .ok_or_else(|| format!("Unknown antialiasing mode {}", value))?
+            }
+            "msaa_samples" => quality.msaa_samples = (value as u8).min(16),
+            "anisotropy" => quality.anisotropy = value.clamp(1.0, 16.0),
+            "ssao" => quality.ssao = value != 0.0,
+            "depth_prepass" => quality.depth_prepass = value != 0.0,
+            "post_effects" => quality.post_effects = value != 0.0,
+            "texture_budget_mb" => quality.texture_budget_mb = (value as u32).clamp(32, 8192),
+            _ => return Err(format!("Unknown graphics setting {}", name)),
+        }
+        if quality != Self::current() {
+            quality.preset = QualityPreset::Custom;
+        }
+        Self::set(quality);
+        Ok(quality)
+    }
+
+    /// Samples of the scene's MSAA target, None when the scene isn't multisampled
+    pub fn msaa(&self) -> Option<u8> {
+        (self.antialiasing == AntialiasingMode::Msaa && self.msaa_samples >= 2).then_some(self.msaa_samples)
+    }
+
+    /// SSAO runs as part of the post effects
+    pub fn ssao_enabled(&self) -> bool {
+        self.post_effects && self.ssao
+    }
+
+    /// Bring GL state that outlives a frame up to date with the settings, e.g. texture filtering; first render
+    /// system of the frame
+    pub fn apply(gl: &glow::Context, _width: u32, _height: u32) {
+        let anisotropy = Self::current().anisotropy;
+        if APPLIED_ANISOTROPY.with(Cell::get) != anisotropy {
+            for texture in material_textures() {
+                texture.set_anisotropy(gl, anisotropy);
+            }
+            APPLIED_ANISOTROPY.with(|applied| applied.set(anisotropy));
+        }
+    }
+}
+
+impl Default for GraphicsQuality {
+    fn default() -> Self {
+        Self::HIGH
+    }
+}
+
+static CURRENT: RwLock<GraphicsQuality> = RwLock::new(GraphicsQuality::HIGH);
+
+thread_local! {
+    // Anisotropy the loaded mesh textures were last given; textures loaded later get the current setting
+    static APPLIED_ANISOTROPY: Cell<f32> = const { Cell::new(0.0) };
+}
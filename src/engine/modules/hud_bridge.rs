@@ -0,0 +1,75 @@
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+
+/// Plain values shown by the in-game HUD, so gameplay code never touches Slint types
+#[derive(Clone, Debug, PartialEq)]
+pub struct HudValues {
+    pub health: f32,
+    pub max_health: f32,
+    pub prompt: String, // Empty hides the interaction prompt
+    pub crosshair_visible: bool,
+}
+
+impl Default for HudValues {
+    fn default() -> Self {
+        Self {
+            health: 100.0,
+            max_health: 100.0,
+            prompt: String::new(),
+            crosshair_visible: true,
+        }
+    }
+}
+
+/// Latest HUD values and whether they changed since the last flush
+static HUD_VALUES: Lazy<RwLock<(HudValues, bool)>> = Lazy::new(|| RwLock::new((HudValues::default(), true)));
+
+/// Write side of the HUD: systems set values any time, the UI picks them up once per frame
+pub struct HudBridge;
+
+impl HudBridge {
+    pub fn set_health(health: f32, max_health: f32) {
+        Self::modify(|values| {
+            values.health = health;
+            values.max_health = max_health;
+        });
+    }
+
+    /// Show an interaction prompt, or hide it with None
+    pub fn set_prompt(prompt: Option<&str>) {
+        Self::modify(|values| {
+            values.prompt = prompt.unwrap_or_default().to_string();
+        });
+    }
+
+    #[allow(dead_code)]
+    pub fn set_crosshair_visible(visible: bool) {
+        Self::modify(|values| {
+            values.crosshair_visible = visible;
+        });
+    }
+
+    /// Push pending changes to the HUD layer
+    pub fn flush() {
+        let values = {
+            let mut hud = HUD_VALUES.write().unwrap();
+            if !hud.1 {
+                return;
+            }
+            hud.1 = false;
+            hud.0.clone()
+        };
+        InterfaceSystem::sync_hud(&values);
+    }
+
+    fn modify(change: impl FnOnce(&mut HudValues)) {
+        let mut hud = HUD_VALUES.write().unwrap();
+        let mut values = hud.0.clone();
+        change(&mut values);
+        if values != hud.0 {
+            *hud = (values, true);
+        }
+    }
+}
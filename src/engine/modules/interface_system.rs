@@ -1,14 +1,69 @@
-use crate::index::engine::components::{ Metadata };
-use crate::{ copy_entity, delete_entity, LevelEditorUI, InterfaceState };
+use crate::index::engine::components::{
+    AnimatedObject3DComponent,
+    AnimatedProperty,
+    Curve,
+    Metadata,
+    PropertyAnimation,
+    PropertyTrack,
+};
+use crate::index::engine::components::AnimatedObject3D::{ AnimationClip, AnimationMarker, Animator };
+use crate::index::engine::modules::game_loop::GameLoop;
+use crate::index::engine::modules::engine_error::EngineError;
+use crate::index::engine::modules::hud_bridge::HudValues;
+use crate::index::engine::modules::{
+    App,
+    AntialiasingMode,
+    AppConfig,
+    Component,
+    Console,
+    Diagnostics,
+    FindReplace,
+    EditorSettings,
+    EditorTheme,
+    EntityId,
+    GraphicsQuality,
+    OutlinerGroups,
+    PlaySession,
+    Project,
+    QualityPreset,
+    Random,
+    SafeMode,
+    SceneLibrary,
+    Selection,
+    ShaderDiagnostics,
+};
+use crate::{ copy_entity, despawn, LevelEditorUI, InterfaceState, HudState, Colors };
 use crate::Entity; // Import the generated Slint Entity struct
-use crate::{ query_get_all, get_all_components_dyn };
+use crate::{ query_get_all, get_all_components_dyn, get_query_by_id, query_by_id };
 use crate::{KeyValuePair, ComponentData}; // Import KeyValuePair and ComponentData from Slint
-use slint::{ VecModel, ModelRc, ComponentHandle, Weak };
+use crate::MinimapMarker;
+use crate::Tweak;
+use crate::GraphicsSettings;
+use crate::ColorGradingSettings;
+use crate::{ ShaderError, ShaderSourceLine };
+use crate::{ AnimationTimeline, TimelineMarker };
+use crate::{ CurveEditorState, CurveKeyPoint };
+use crate::{ ScatterPrefab, ScatterSettings };
+use crate::index::game::systems::{ AnimationSystem, CullingDebugSystem, PropertyAnimationSystem, ScatterBrushSystem };
+use crate::index::game::systems::scatter_brush_system::ScatterMode;
+use crate::index::game::entities::prefabs::prefab_names;
+use crate::SceneEntry;
+use crate::SafeModeProblem;
+use crate::{ PluginPanel, PluginPanelAction, PluginPanelRow };
+use crate::FindReplaceMatch;
+use slint::{ VecModel, ModelRc, Model, ComponentHandle, LogicalSize, SharedString, Weak };
+use slint::platform::WindowEvent;
+use std::collections::HashSet;
 use std::sync::{ Mutex, OnceLock };
 use serde_json::{ to_string, Value };
 
 static INTERFACE_SYSTEM: OnceLock<Mutex<InterfaceSystem>> = OnceLock::new();
 
+/// Newest log lines shown in the console panel
+const CONSOLE_VISIBLE_LINES: usize = 14;
+/// Curve editor viewbox size, in path units
+const CURVE_VIEWBOX: f32 = 1000.0;
+
 pub struct InterfaceSystem {
     ui_weak: Weak<LevelEditorUI>,
 }
@@ -76,6 +131,71 @@ impl InterfaceSystem {
         ("".to_string(), "".to_string())
     }
 
+    /// Refresh the component panel of the selected entity, e.g. after it was moved in the viewport
+    pub fn refresh_selection() {
+        let (selected_id, _) = Self::get_selection_state();
+        Self::refresh_selected_entity(&selected_id);
+    }
+
+    /// Select entities picked in the viewport, replacing the selection or adding to it; the inspector keeps
+    /// its entity while it stays selected and otherwise shows the first one
+    pub fn select_entities(entity_ids: Vec<EntityId>, additive: bool) {
+        if additive {
+            Selection::extend(&entity_ids);
+        } else {
+            Selection::replace(entity_ids);
+        }
+        let selected = Selection::ids();
+
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    let primary = state.get_selected_index().to_string();
+                    match selected.first() {
+                        None => {
+                            state.set_selected_index("".into());
+                            state.set_parsed_components(ModelRc::default());
+                        }
+                        Some(first) if !selected.contains(&primary) => {
+                            let title = get_query_by_id!(first.clone(), (Metadata)).map(|metadata| metadata.title().to_string()).unwrap_or_default();
+                            state.set_selected_index(first.as_str().into());
+                            state.set_selected_title(title.into());
+                            state.invoke_entity_selected(first.as_str().into());
+                        }
+                        Some(_) => {}
+                    }
+                    Self::sync_selection_marks(&ui);
+                }
+            }
+        }
+        println!("🎯 {} entities selected", selected.len());
+    }
+
+    /// Select every unlocked member of an outliner group
+    pub fn select_group(group_id: &str) {
+        let Some(group) = OutlinerGroups::groups().into_iter().find(|group| group.id == group_id) else {
+            return;
+        };
+        let members: Vec<EntityId> = group.members
+            .into_iter()
+            .filter(|entity_id| !Self::is_locked(entity_id))
+            .collect();
+        if members.is_empty() {
+            println!("⚠️ Group {} has no unlocked members to select", group.name);
+            return;
+        }
+        Self::select_entities(members, false);
+        Selection::set_group(group_id);
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    Self::sync_selection_marks(&ui);
+                }
+            }
+        }
+    }
+
     /// Private constructor for singleton
     fn new_with_ui(ui_weak: Weak<LevelEditorUI>) -> Self {
         let ui = ui_weak.upgrade().expect("UI should be available during initialization");
@@ -88,6 +208,13 @@ impl InterfaceSystem {
             let ui_weak_clone = ui.as_weak();
             move |entity_id| {
                 let entity_id_string = entity_id.to_string();
+                if Self::is_locked(&entity_id_string) {
+                    return;
+                }
+                // A click outside the multi-selection starts a new one; inside it only changes the inspected entity
+                if !Selection::contains(&entity_id_string) {
+                    Selection::replace(vec![entity_id_string.clone()]);
+                }
                 let components = get_all_components_dyn!(entity_id_string);
 
                 println!("Entity clicked: {}", entity_id_string);
@@ -99,13 +226,16 @@ impl InterfaceSystem {
                         println!("{}", json_str);
                         let parsed_pairs = Self::parse_json_to_key_value_pairs(&json_str);
                         
-                        // Extract component type from the parsed pairs
-                        let component_type = parsed_pairs
-                            .iter()
-                            .find(|pair| pair.key.as_str() == "type")
-                            .map(|pair| pair.value.as_str())
-                            .unwrap_or("Unknown")
-                            .to_string();
+                        // Extract component type from the parsed pairs; plugin components go by their kind
+                        let component_type = match &component {
+                            Component::Plugin(plugin) => plugin.kind.clone(),
+                            _ => parsed_pairs
+                                .iter()
+                                .find(|pair| pair.key.as_str() == "type")
+                                .map(|pair| pair.value.as_str())
+                                .unwrap_or("Unknown")
+                                .to_string(),
+                        };
 
                         // Filter out the "type" field since it's used as the title
                         let filtered_pairs: Vec<KeyValuePair> = parsed_pairs
@@ -128,6 +258,7 @@ impl InterfaceSystem {
                     let state = ui.global::<InterfaceState>();
                     let components_model = VecModel::from(parsed_components);
                     state.set_parsed_components(ModelRc::new(components_model).into());
+                    Self::sync_selection_marks(&ui);
                 }
             }
         });
@@ -135,6 +266,10 @@ impl InterfaceSystem {
         // Component change callback - handle component updates using existing deserialization
         state.on_component_changed({
             move |entity_id, component_json| {
+                if Self::is_locked(&entity_id) {
+                    println!("🔒 Entity {} is locked, ignoring component change", entity_id);
+                    return;
+                }
                 println!("🔧 Component changed for entity {}: {}", entity_id, component_json);
                 Self::update_component_from_json(entity_id.to_string(), component_json.to_string());
             }
@@ -158,8 +293,13 @@ impl InterfaceSystem {
 
         // Entity deselection callback
         state.on_entity_deselected({
+            let ui_weak_clone = ui_weak.clone();
             move || {
                 println!("🎯 Entity deselected");
+                Selection::clear();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::sync_selection_marks(&ui);
+                }
             }
         });
 
@@ -179,173 +319,2017 @@ impl InterfaceSystem {
         // Delete entity callback
         state.on_delete_entity({
             move |entity_id| {
+                if Self::is_locked(&entity_id) {
+                    println!("🔒 Entity {} is locked, unlock it before deleting", entity_id);
+                    return;
+                }
                 println!("🗑️ Deleting entity: {}", entity_id);
-                if delete_entity!(entity_id.to_string()) {
+                if despawn!(entity_id.to_string()) {
                     println!("✅ Entity deleted: {}", entity_id);
-                    InterfaceSystem::update_entities_list();
                 } else {
                     println!("❌ Failed to delete entity: {}", entity_id);
                 }
             }
         });
 
-        // Save scene callback
-        state.on_save_scene({
-            move || {
-                println!("💾 Saving scene...");
-                crate::save_world!("src/assets/scenes/test_world.json");
+        // Outliner visibility toggle callback
+        state.on_toggle_entity_visible({
+            move |entity_id| {
+                query_by_id!(entity_id.to_string(), (Metadata), |metadata| {
+                    metadata.is_visible = !metadata.is_visible;
+                });
+                InterfaceSystem::update_entities_list();
+                Self::refresh_selected_entity(&entity_id);
             }
         });
 
-        // Spawn blockout platform callback
-        state.on_spawn_blockout_platform({
+        // Outliner lock toggle callback (locking also drops the selection)
+        state.on_toggle_entity_locked({
+            let ui_weak_clone = ui_weak.clone();
+            move |entity_id| {
+                let mut locked = false;
+                query_by_id!(entity_id.to_string(), (Metadata), |metadata| {
+                    metadata.locked = !metadata.locked;
+                    locked = metadata.locked;
+                });
+                if locked {
+                    Selection::remove(&entity_id);
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    if locked && state.get_selected_index() == entity_id {
+                        state.set_selected_index("".into());
+                        state.set_parsed_components(ModelRc::default());
+                    }
+                }
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        state.on_toggle_entity_bounds(move |entity_id| {
+            CullingDebugSystem::toggle(&entity_id);
+            InterfaceSystem::update_entities_list();
+        });
+
+        // Outliner group callbacks (groups are editor-only folders saved in the scene's sidecar)
+        state.on_create_group({
             move || {
-                println!("🏗️ Spawning blockout platform...");
-                crate::index::game::entities::spawn_blockout_platform();
+                let group_id = OutlinerGroups::create();
+                for entity_id in Selection::ids() {
+                    OutlinerGroups::add_member(&group_id, &entity_id);
+                }
+                println!("📁 Created outliner group {}", group_id);
                 InterfaceSystem::update_entities_list();
             }
         });
 
-        Self { ui_weak: ui.as_weak() }
-    }
+        state.on_rename_group({
+            move |group_id, name| {
+                OutlinerGroups::rename(&group_id, &name);
+                InterfaceSystem::update_entities_list();
+            }
+        });
 
-    /// Update a specific field in a component and refresh the UI
-    fn update_component_field_internal(
-        entity_id: String, 
-        component_type: String, 
-        field_key: String, 
-        new_value: String
-    ) {
-        println!("🔧 Updating component field: entity={}, component={}, field={}, value={}", 
-            entity_id, component_type, field_key, new_value);
+        state.on_delete_group({
+            move |group_id| {
+                OutlinerGroups::remove(&group_id);
+                // The members stay selected, just no longer as a group
+                if Selection::group().is_some_and(|selected_group| selected_group == group_id.as_str()) {
+                    Selection::replace(Selection::ids());
+                }
+                InterfaceSystem::update_entities_list();
+            }
+        });
 
-        // Get the current component data
-        let components = get_all_components_dyn!(entity_id);
-        
-        for component in components {
-            if let Ok(json_str) = to_string(&component) {
-                // Parse the component JSON to check if it matches the component type
-                if let Ok(mut json_value) = serde_json::from_str::<Value>(&json_str) {
-                    if let Some(Value::String(comp_type)) = json_value.get("type") {
-                        if comp_type == &component_type {
-                            // Found the matching component, update the field
-                            if let Some(obj) = json_value.as_object_mut() {
-                                // Parse the new value appropriately
-                                let parsed_value = Self::parse_field_value(&new_value);
-                                obj.insert(field_key.clone(), parsed_value);
-                                
-                                // Convert back to JSON string
-                                if let Ok(updated_json) = serde_json::to_string(&json_value) {
-                                    println!("📝 Updated component JSON: {}", updated_json);
-                                    
-                                    // Update the ECS component using existing system
-                                    Self::update_ecs_component(&entity_id, &updated_json);
-                                    
-                                    // Refresh the UI to show the updated component
-                                    Self::refresh_selected_entity(&entity_id);
-                                    return;
-                                }
-                            }
+        state.on_select_group({
+            move |group_id| {
+                Self::select_group(&group_id);
+            }
+        });
+
+        // Editor console: run a line, complete command and cvar names, browse the history
+        state.on_console_submit({
+            let ui_weak_clone = ui_weak.clone();
+            move |line| {
+                Console::execute(&line);
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_console_log(&ui);
+                    Self::show_tweaks(&ui);
+                }
+            }
+        });
+
+        state.on_console_complete({
+            let ui_weak_clone = ui_weak.clone();
+            move |line| {
+                let completed = Console::complete(&line);
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_console_log(&ui);
+                }
+                completed.into()
+            }
+        });
+
+        state.on_console_recall(move |step| Console::recall(step).into());
+
+        state.on_toggle_console(Self::toggle_console);
+
+        // Tweaks panel: cvar sliders, saved to the app config on request
+        state.on_toggle_tweaks({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_tweaks_visible(!state.get_tweaks_visible());
+                    Self::show_tweaks(&ui);
+                }
+            }
+        });
+
+        state.on_set_cvar({
+            let ui_weak_clone = ui_weak.clone();
+            move |name, value| {
+                let Ok(value) = Console::set_cvar(&name, value) else {
+                    return;
+                };
+                // Update the row in place so the slider being dragged isn't rebuilt
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let tweaks = ui.global::<InterfaceState>().get_tweaks();
+                    for row in 0..tweaks.row_count() {
+                        if let Some(mut tweak) = tweaks.row_data(row).filter(|tweak| tweak.name == name) {
+                            tweak.value = value;
+                            tweaks.set_row_data(row, tweak);
                         }
                     }
                 }
             }
-        }
-        
-        println!("❌ Failed to find component {} for entity {}", component_type, entity_id);
-    }
+        });
 
-    /// Parse a string value into the appropriate JSON value type
-    fn parse_field_value(value_str: &str) -> Value {
-        // Try to parse as number first
-        if let Ok(int_val) = value_str.parse::<i64>() {
-            return Value::Number(serde_json::Number::from(int_val));
-        }
-        
-        if let Ok(float_val) = value_str.parse::<f64>() {
-            return Value::Number(serde_json::Number::from_f64(float_val).unwrap_or(serde_json::Number::from(0)));
-        }
-        
-        // Try to parse as boolean
-        if let Ok(bool_val) = value_str.parse::<bool>() {
-            return Value::Bool(bool_val);
-        }
-        
-        // Default to string
-        Value::String(value_str.to_string())
-    }
+        state.on_save_tweaks(move || {
+            if let Err(error) = AppConfig::save_cvars(&Console::cvar_values()) {
+                Self::report_error(&error);
+            }
+        });
 
-    /// Update the ECS component using existing deserialization system
-    fn update_ecs_component(entity_id: &str, component_json: &str) {
-        println!("🔄 Updating ECS component for entity {}", entity_id);
-        println!("📝 Component JSON: {}", component_json);
-        
-        // Use the existing component-changed callback to handle deserialization
-        // This leverages whatever deserialization system is already in place
-        if let Some(system) = INTERFACE_SYSTEM.get() {
-            if let Ok(system) = system.lock() {
-                if let Some(ui) = system.ui_weak.upgrade() {
+        state.on_reset_tweaks({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                for (name, cvar) in Console::cvars() {
+                    let _ = Console::set_cvar(&name, cvar.default);
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_tweaks(&ui);
+                }
+            }
+        });
+
+        // Graphics panel: quality presets and single settings, saved to the app config on request
+        state.on_toggle_graphics({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
                     let state = ui.global::<InterfaceState>();
-                    let entity_id_slint: slint::SharedString = entity_id.into();
-                    let component_json_slint: slint::SharedString = component_json.into();
-                    
-                    // Dispatch to existing component-changed system
-                    state.invoke_component_changed(entity_id_slint, component_json_slint);
-                    println!("✅ Component update dispatched to existing system");
+                    state.set_graphics_visible(!state.get_graphics_visible());
+                    Self::show_graphics(&ui);
                 }
             }
-        }
-    }
+        });
 
-    /// Refresh the UI for the currently selected entity
-    fn refresh_selected_entity(entity_id: &str) {
-        if let Some(system) = INTERFACE_SYSTEM.get() {
-            if let Ok(system) = system.lock() {
-                if let Some(ui) = system.ui_weak.upgrade() {
+        state.on_set_graphics_preset({
+            let ui_weak_clone = ui_weak.clone();
+            move |name| {
+                let Some(quality) = QualityPreset::parse(&name).and_then(GraphicsQuality::preset) else {
+                    return;
+                };
+                println!("🎨 Graphics preset: {}", name);
+                GraphicsQuality::set(quality);
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_graphics(&ui);
+                }
+            }
+        });
+
+        state.on_set_graphics_option({
+            let ui_weak_clone = ui_weak.clone();
+            move |name, value| {
+                if let Err(error) = GraphicsQuality::set_option(&name, value) {
+                    eprintln!("❌ {}", error);
+                    return;
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_graphics(&ui);
+                }
+            }
+        });
+
+        state.on_save_graphics(move || {
+            if let Err(error) = AppConfig::save_graphics(&GraphicsQuality::current()) {
+                Self::report_error(&error);
+            }
+            if let Err(error) = AppConfig::save_editor(&EditorSettings::current()) {
+                Self::report_error(&error);
+            }
+        });
+
+        state.on_set_editor_theme(move |name| {
+            let Some(theme) = EditorTheme::parse(&name) else {
+                return;
+            };
+            EditorSettings::set(EditorSettings { theme, ..EditorSettings::current() });
+            Self::apply_editor_settings();
+        });
+
+        state.on_set_ui_scale(move |ui_scale| {
+            EditorSettings::set(EditorSettings { ui_scale, ..EditorSettings::current() });
+            Self::apply_editor_settings();
+        });
+
+        state.on_set_outline_option(move |name, value| {
+            let mut settings = EditorSettings::current();
+            match name.as_str() {
+                "width" => settings.outline.width = value,
+                "occluded_brightness" => settings.outline.occluded_brightness = value,
+                _ => return,
+            }
+            EditorSettings::set(settings);
+            Self::apply_editor_settings();
+        });
+
+        // Color Grading panel: edits the scene's ColorGrading, which saves with the scene
+        state.on_toggle_color_grading({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
                     let state = ui.global::<InterfaceState>();
-                    
-                    // Check if this entity is currently selected
-                    if state.get_selected_index().to_string() == entity_id {
-                        // Re-trigger entity selection to refresh the component display
-                        let entity_id_slint: slint::SharedString = entity_id.into();
-                        state.invoke_entity_selected(entity_id_slint);
-                    }
+                    state.set_color_grading_visible(!state.get_color_grading_visible());
+                    Self::show_color_grading(&ui);
                 }
             }
-        }
-    }
+        });
 
-    /// Update component from JSON using generic deserialization
-    fn update_component_from_json(entity_id: String, component_json: String) {
-        println!("🔄 Deserializing component JSON for entity {}", entity_id);
-        
-        // Use the generic Component enum deserialization - leverages existing serde type tagging
-        match serde_json::from_str::<crate::index::engine::modules::ecs::Component>(&component_json) {
-            Ok(component) => {
-                crate::index::engine::modules::ecs::insert(&entity_id, component);
-                println!("✅ Component updated successfully using generic deserialization");
-            },
-            Err(e) => {
-                println!("❌ Failed to deserialize component: {}", e);
+        state.on_set_color_grading({
+            let ui_weak_clone = ui_weak.clone();
+            move |name, value| {
+                if let Err(error) = crate::index::game::systems::ColorGradingSystem::set(&name, value) {
+                    eprintln!("❌ {}", error);
+                    return;
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_color_grading(&ui);
+                }
             }
-        }
-    }
+        });
 
-    fn update_entities_internal(&self) {
-        // Update entity list from ECS
-        let metadata_results = query_get_all!(Metadata);
+        state.on_set_color_grading_lut({
+            let ui_weak_clone = ui_weak.clone();
+            move |path| {
+                if let Err(error) = crate::index::game::systems::ColorGradingSystem::set_lut(&path) {
+                    eprintln!("❌ {}", error);
+                    return;
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_color_grading(&ui);
+                }
+            }
+        });
 
-        println!("🔄 Updating entity list - found {} entities", metadata_results.len());
+        // Timeline panel: playback of the selected animated entity, edited through its Animator
+        state.on_toggle_timeline({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_timeline_visible(!state.get_timeline_visible());
+                    Self::show_timeline(&ui);
+                }
+            }
+        });
 
-        // Create entities list for Slint with proper Entity struct format
-        let mut entities = Vec::new();
-        for (entity_id, metadata) in metadata_results {
-            println!("  - Entity: {} - {}", entity_id, metadata.title());
-            // Create proper Entity struct that matches the Slint definition
-            entities.push(Entity {
-                entity_id: entity_id.into(),
-                title: metadata.title().into(),
-            });
+        state.on_select_animation_clip({
+            let ui_weak_clone = ui_weak.clone();
+            move |name| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_timeline(&ui, |animator, _clips| {
+                        animator.set_clip(&name);
+                        Ok(())
+                    });
+                }
+            }
+        });
+
+        state.on_toggle_animation_playing({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_timeline(&ui, |animator, clips| {
+                        // Play restarts a clip that stopped at its end
+                        let duration = animator.current_clip(clips).map_or(0.0, |clip| clip.duration);
+                        if !animator.is_playing() && animator.get_time() >= duration {
+                            animator.seek(0.0);
+                        }
+                        animator.set_playing(!animator.is_playing());
+                        Ok(())
+                    });
+                }
+            }
+        });
+
+        state.on_set_animation_looping({
+            let ui_weak_clone = ui_weak.clone();
+            move |looping| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_timeline(&ui, |animator, _clips| {
+                        animator.set_looping(looping);
+                        Ok(())
+                    });
+                }
+            }
+        });
+
+        state.on_set_animation_speed({
+            let ui_weak_clone = ui_weak.clone();
+            move |speed| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_timeline(&ui, |animator, _clips| {
+                        animator.set_playback_rate(speed.max(0.0));
+                        Ok(())
+                    });
+                }
+            }
+        });
+
+        state.on_scrub_animation({
+            let ui_weak_clone = ui_weak.clone();
+            move |time| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_timeline(&ui, |animator, clips| {
+                        let duration = animator.current_clip(clips).map_or(0.0, |clip| clip.duration);
+                        animator.seek(time.min(duration));
+                        Ok(())
+                    });
+                }
+            }
+        });
+
+        state.on_add_animation_marker({
+            let ui_weak_clone = ui_weak.clone();
+            move |name| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_timeline(&ui, |animator, clips| {
+                        let name = name.trim();
+                        if name.is_empty() {
+                            return Err("Name the marker first".to_string());
+                        }
+                        let clip = animator.current_clip(clips).ok_or("The entity has no animation clips")?;
+                        let marker = AnimationMarker { clip: clip.name.clone(), time: animator.get_time(), name: name.to_string() };
+                        animator.add_marker(marker);
+                        Ok(())
+                    });
+                }
+            }
+        });
+
+        state.on_remove_animation_marker({
+            let ui_weak_clone = ui_weak.clone();
+            move |index| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_timeline(&ui, |animator, _clips| {
+                        animator.remove_marker(index.max(0) as usize);
+                        Ok(())
+                    });
+                }
+            }
+        });
+
+        // Curve editor: PropertyAnimation tracks of the selected entity; graph positions arrive as 0-1 of the view
+        state.on_toggle_curve_editor({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_curve_editor_visible(!state.get_curve_editor_visible());
+                    Self::show_curve_editor(&ui, false);
+                }
+            }
+        });
+
+        state.on_select_curve_track({
+            let ui_weak_clone = ui_weak.clone();
+            move |track| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_curve_track(&ui, &track);
+                }
+            }
+        });
+
+        state.on_add_curve_track({
+            let ui_weak_clone = ui_weak.clone();
+            move |name| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let entity_id = ui.global::<InterfaceState>().get_curve_editor().entity_id.to_string();
+                    let result = PropertyAnimationSystem::edit(&entity_id, |animation| {
+                        let property = AnimatedProperty::from_name(&name).ok_or_else(|| format!("No property named {}", name))?;
+                        if animation.track_mut(property).is_some() {
+                            return Ok(());
+                        }
+                        // A track starts with a key of the property's current value, so adding it changes nothing
+                        let value = PropertyAnimationSystem::read(&entity_id, property).ok_or_else(|| {
+                            format!("Entity {} has nothing for {} to drive", entity_id, name)
+                        })?;
+                        let mut curve = Curve::default();
+                        curve.insert(animation.time, value);
+                        animation.tracks.push(PropertyTrack { property, curve });
+                        Ok(())
+                    });
+                    match result {
+                        Ok(_) => Self::show_curve_track(&ui, &name),
+                        Err(error) => eprintln!("❌ {}", error),
+                    }
+                }
+            }
+        });
+
+        state.on_remove_curve_track({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let track = ui.global::<InterfaceState>().get_curve_editor().track;
+                    Self::edit_curves(&ui, |animation| {
+                        animation.tracks.retain(|existing| existing.property.name() != track.as_str());
+                        Ok(Some(-1))
+                    });
+                }
+            }
+        });
+
+        state.on_select_curve_key({
+            let ui_weak_clone = ui_weak.clone();
+            move |index| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    let mut editor = state.get_curve_editor();
+                    editor.selected_key = index;
+                    state.set_curve_editor(editor);
+                    Self::show_curve_editor(&ui, false);
+                }
+            }
+        });
+
+        state.on_add_curve_key({
+            let ui_weak_clone = ui_weak.clone();
+            move |x, y| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let (time, value) = Self::curve_point(&ui.global::<InterfaceState>().get_curve_editor(), x, y);
+                    Self::edit_curve(&ui, |curve, _| Ok(Some(curve.insert(time, value) as i32)));
+                }
+            }
+        });
+
+        state.on_move_curve_key({
+            let ui_weak_clone = ui_weak.clone();
+            move |index, x, y| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let (time, value) = Self::curve_point(&ui.global::<InterfaceState>().get_curve_editor(), x, y);
+                    Self::edit_curve(&ui, |curve, _| {
+                        curve.move_key(index.max(0) as usize, time, value);
+                        Ok(None)
+                    });
+                }
+            }
+        });
+
+        state.on_move_curve_handle({
+            let ui_weak_clone = ui_weak.clone();
+            move |index, out, x, y| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let (time, value) = Self::curve_point(&ui.global::<InterfaceState>().get_curve_editor(), x, y);
+                    Self::edit_curve(&ui, |curve, _| {
+                        let index = index.max(0) as usize;
+                        let key = curve.keys.get(index).copied().ok_or("No such key")?;
+                        curve.set_handle(index, out, [time - key.time, value - key.value]);
+                        Ok(None)
+                    });
+                }
+            }
+        });
+
+        state.on_delete_curve_key({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_curve(&ui, |curve, selected| {
+                        curve.remove(selected.ok_or("Select a key first")?);
+                        Ok(Some(-1))
+                    });
+                }
+            }
+        });
+
+        state.on_make_curve_key_linear({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_curve(&ui, |curve, selected| {
+                        curve.make_linear(selected.ok_or("Select a key first")?);
+                        Ok(None)
+                    });
+                }
+            }
+        });
+
+        state.on_key_curve_values({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let entity_id = ui.global::<InterfaceState>().get_curve_editor().entity_id.to_string();
+                    Self::edit_curves(&ui, |animation| {
+                        if animation.tracks.is_empty() {
+                            return Err("Add a track first".to_string());
+                        }
+                        let time = animation.time;
+                        for track in &mut animation.tracks {
+                            if let Some(value) = PropertyAnimationSystem::read(&entity_id, track.property) {
+                                track.curve.insert(time, value);
+                            }
+                        }
+                        Ok(Some(-1))
+                    });
+                }
+            }
+        });
+
+        state.on_toggle_property_animation_playing({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_curves(&ui, |animation| {
+                        // Play restarts an animation that stopped at its end
+                        if !animation.playing && animation.time >= animation.duration() {
+                            animation.time = 0.0;
+                        }
+                        animation.playing = !animation.playing;
+                        Ok(None)
+                    });
+                }
+            }
+        });
+
+        state.on_set_property_animation_looping({
+            let ui_weak_clone = ui_weak.clone();
+            move |looping| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::edit_curves(&ui, |animation| {
+                        animation.looping = looping;
+                        Ok(None)
+                    });
+                }
+            }
+        });
+
+        state.on_scrub_property_animation({
+            let ui_weak_clone = ui_weak.clone();
+            move |x| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let time = x * ui.global::<InterfaceState>().get_curve_editor().time_range;
+                    Self::edit_curves(&ui, |animation| {
+                        animation.time = time.clamp(0.0, animation.duration());
+                        Ok(None)
+                    });
+                }
+            }
+        });
+
+        state.on_fit_curve_view({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_curve_editor(&ui, true);
+                }
+            }
+        });
+
+        state.on_toggle_group_collapsed({
+            move |group_id| {
+                OutlinerGroups::toggle_collapsed(&group_id);
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        state.on_toggle_group_visible({
+            let ui_weak_clone = ui_weak.clone();
+            move |group_id| {
+                OutlinerGroups::toggle_visible(&group_id);
+                InterfaceSystem::update_entities_list();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let selected_id = ui.global::<InterfaceState>().get_selected_index();
+                    Self::refresh_selected_entity(&selected_id);
+                }
+            }
+        });
+
+        // Locking a group drops the selection when it is one of the members, like locking an entity
+        state.on_toggle_group_locked({
+            let ui_weak_clone = ui_weak.clone();
+            move |group_id| {
+                OutlinerGroups::toggle_locked(&group_id);
+                for entity_id in Selection::ids() {
+                    if Self::is_locked(&entity_id) {
+                        Selection::remove(&entity_id);
+                    }
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    let selected_id = state.get_selected_index();
+                    if !selected_id.is_empty() && Self::is_locked(&selected_id) {
+                        state.set_selected_index("".into());
+                        state.set_parsed_components(ModelRc::default());
+                    }
+                }
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        state.on_add_selected_to_group({
+            move |group_id| {
+                let selected = Selection::ids();
+                if selected.is_empty() {
+                    println!("⚠️ Select an entity to add it to the group");
+                    return;
+                }
+                for entity_id in &selected {
+                    OutlinerGroups::add_member(&group_id, entity_id);
+                }
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        state.on_ungroup_selected({
+            move || {
+                for entity_id in Selection::ids() {
+                    OutlinerGroups::remove_member(&entity_id);
+                }
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Save scene callback
+        state.on_save_scene({
+            move || {
+                let scene_path = SceneLibrary::current();
+                // The world is empty while safe mode holds the scene back
+                if SafeMode::active() {
+                    InterfaceSystem::report_error(
+                        &EngineError::scene(&scene_path, "Load the scene or start empty before saving in safe mode")
+                    );
+                    return;
+                }
+                println!("💾 Saving scene...");
+                crate::save_world!(&scene_path);
+            }
+        });
+
+        // Open Scene browser: scene files next to the open one, with their headers and thumbnails
+        state.on_toggle_scene_browser({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_scene_browser_visible(!state.get_scene_browser_visible());
+                    Self::show_scenes(&ui);
+                }
+            }
+        });
+
+        state.on_open_scene({
+            let ui_weak_clone = ui_weak.clone();
+            move |path| {
+                println!("📂 Opening scene {}", path);
+                Selection::clear();
+                SceneLibrary::set_current(&path);
+                crate::load_world!(path.as_str());
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_selected_index("".into());
+                    state.set_scene_browser_visible(false);
+                }
+            }
+        });
+
+        // Safe mode: pick the broken entities to skip, then load the held-back scene or keep the empty one
+        state.on_toggle_safe_mode_skip({
+            move |entity_id| {
+                SafeMode::toggle_skip(&entity_id);
+                InterfaceSystem::show_safe_mode();
+            }
+        });
+
+        state.on_load_safe_mode_scene({
+            move || {
+                Selection::clear();
+                if let Err(e) = SafeMode::load() {
+                    InterfaceSystem::report_error(&e);
+                }
+            }
+        });
+
+        state.on_start_empty_scene({
+            move || {
+                SafeMode::discard();
+            }
+        });
+
+        // Spawn blockout platform callback
+        state.on_spawn_blockout_platform({
+            move || {
+                println!("🏗️ Spawning blockout platform...");
+                crate::index::game::entities::spawn_blockout_platform();
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Spawner placement callback
+        state.on_spawn_spawner({
+            move || {
+                println!("🥚 Adding spawner...");
+                crate::index::game::entities::spawn_spawner();
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Terrain placement callback
+        state.on_spawn_terrain({
+            move || {
+                println!("⛰️ Adding terrain...");
+                crate::index::game::entities::spawn_terrain();
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Water placement callback
+        state.on_spawn_water({
+            move || {
+                println!("🌊 Adding water...");
+                crate::index::game::entities::spawn_water();
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Point light spawn callback
+        state.on_spawn_point_light({
+            move || {
+                println!("💡 Adding point light...");
+                crate::index::game::entities::spawn_point_light();
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Reflection probe spawn callback
+        state.on_spawn_reflection_probe({
+            move || {
+                println!("🪞 Adding reflection probe...");
+                crate::index::game::entities::spawn_reflection_probe();
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Moving platform spawn callback
+        state.on_spawn_moving_platform({
+            move || {
+                println!("🛗 Adding moving platform...");
+                crate::index::game::entities::spawn_moving_platform();
+                InterfaceSystem::update_entities_list();
+            }
+        });
+
+        // Terrain brush cycle callback
+        state.on_cycle_terrain_brush({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let brush = crate::index::game::systems::TerrainSystem::cycle_brush();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_terrain_brush(brush.into());
+                }
+            }
+        });
+
+        state.on_cycle_gizmo_mode({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let mode = crate::index::game::systems::TransformGizmoSystem::cycle_mode();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_gizmo_mode(mode.into());
+                }
+            }
+        });
+
+        // Kit placement mode, prefab and rotation cycle callbacks
+        state.on_cycle_kit_mode({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let mode = crate::index::game::systems::KitPlacementSystem::cycle_mode();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_kit_mode(mode.into());
+                }
+            }
+        });
+
+        state.on_cycle_kit_prefab({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let prefab = crate::index::game::systems::KitPlacementSystem::cycle_prefab();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_kit_prefab(prefab.into());
+                }
+            }
+        });
+
+        state.on_cycle_kit_rotation({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let rotation = crate::index::game::systems::KitPlacementSystem::cycle_rotation();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_kit_rotation(rotation.into());
+                }
+            }
+        });
+
+        // Scatter panel: the scatter brush's prefabs, mode and placement settings
+        state.on_toggle_scatter({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_scatter_visible(!state.get_scatter_visible());
+                    Self::show_scatter(&ui);
+                }
+            }
+        });
+
+        state.on_set_scatter_mode({
+            let ui_weak_clone = ui_weak.clone();
+            move |mode| {
+                ScatterBrushSystem::set_mode(ScatterMode::parse(&mode));
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_scatter(&ui);
+                }
+            }
+        });
+
+        state.on_toggle_scatter_prefab({
+            let ui_weak_clone = ui_weak.clone();
+            move |name| {
+                if let Err(error) = ScatterBrushSystem::toggle_prefab(&name) {
+                    eprintln!("❌ {}", error);
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_scatter(&ui);
+                }
+            }
+        });
+
+        state.on_set_scatter_option({
+            let ui_weak_clone = ui_weak.clone();
+            move |name, value| {
+                if let Err(error) = ScatterBrushSystem::set_option(&name, value) {
+                    eprintln!("❌ {}", error);
+                    return;
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_scatter(&ui);
+                }
+            }
+        });
+
+        state.on_toggle_plugin_panels({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_plugin_panels_visible(!state.get_plugin_panels_visible());
+                    Self::show_plugin_panels(&ui);
+                }
+            }
+        });
+
+        state.on_refresh_plugin_panels({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_plugin_panels(&ui);
+                }
+            }
+        });
+
+        state.on_run_plugin_action({
+            let ui_weak_clone = ui_weak.clone();
+            move |line| {
+                Console::execute(&line);
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    Self::show_console_log(&ui);
+                    Self::show_plugin_panels(&ui);
+                }
+            }
+        });
+
+        // Find & Replace panel: preview lists the matches, apply replaces them as one undo step
+        state.on_toggle_find_replace({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_find_replace_visible(!state.get_find_replace_visible());
+                }
+            }
+        });
+
+        state.on_preview_find_replace({
+            let ui_weak_clone = ui_weak.clone();
+            move |field, pattern, replacement| {
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let status = Self::show_find_replace(&ui, &field, &pattern, &replacement);
+                    ui.global::<InterfaceState>().set_find_replace_status(status.into());
+                }
+            }
+        });
+
+        state.on_apply_find_replace({
+            let ui_weak_clone = ui_weak.clone();
+            move |field, pattern, replacement| {
+                let status = FindReplace::replace(&field, &pattern, &replacement).unwrap_or_else(|e| e);
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    // The preview is out of date once replaced
+                    let state = ui.global::<InterfaceState>();
+                    state.set_find_replace_matches(ModelRc::new(VecModel::from(Vec::<FindReplaceMatch>::new())));
+                    state.set_find_replace_status(status.into());
+                }
+            }
+        });
+
+        state.on_undo_find_replace({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let status = FindReplace::undo().unwrap_or_else(|e| e);
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_find_replace_matches(ModelRc::new(VecModel::from(Vec::<FindReplaceMatch>::new())));
+                    state.set_find_replace_status(status.into());
+                }
+            }
+        });
+
+        // Pause/resume simulation callback
+        state.on_toggle_pause({
+            move || {
+                GameLoop::toggle_pause();
+                InterfaceSystem::sync_pause_state();
+            }
+        });
+
+        // Single-step simulation callback
+        state.on_step_simulation({
+            move || {
+                GameLoop::step();
+                InterfaceSystem::sync_pause_state();
+            }
+        });
+
+        // Ragdoll generation for the selected animated entity
+        state.on_generate_ragdoll({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let Some(ui) = ui_weak_clone.upgrade() else {
+                    return;
+                };
+                let selected_id = ui.global::<InterfaceState>().get_selected_index().to_string();
+                if selected_id.is_empty() || Self::is_locked(&selected_id) {
+                    println!("⚠️ Select an unlocked animated entity to generate a ragdoll");
+                    return;
+                }
+                if crate::index::game::systems::RagdollSystem::generate(&selected_id) {
+                    InterfaceSystem::update_entities_list();
+                }
+            }
+        });
+
+        // Navmesh bake callback
+        state.on_bake_navmesh({
+            move || {
+                println!("🧭 Baking navmesh...");
+                crate::index::game::systems::NavigationSystem::bake();
+            }
+        });
+
+        // Ambient occlusion bake callback
+        state.on_bake_occlusion({
+            move || {
+                println!("🌑 Baking ambient occlusion...");
+                crate::index::game::systems::AmbientOcclusionSystem::bake();
+            }
+        });
+
+        // Reflection probe rebake callback
+        state.on_bake_reflection_probes({
+            move || {
+                println!("🪞 Rebaking reflection probes...");
+                crate::index::game::systems::ReflectionProbeSystem::rebake();
+            }
+        });
+
+        // Navmesh debug overlay toggle callback
+        state.on_toggle_navmesh_debug({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let visible = crate::index::game::systems::NavigationSystem::toggle_debug_draw();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_show_navmesh(visible);
+                }
+            }
+        });
+
+        // Statistics heatmap mode callback
+        state.on_cycle_heatmap_mode({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let mode = crate::index::game::systems::HeatmapSystem::cycle();
+                if mode != crate::index::game::systems::heatmap_system::HeatmapMode::Off {
+                    println!("🌡️ Heatmap {}: {}", mode.name(), mode.legend());
+                }
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_heatmap_mode(mode.name().into());
+                }
+            }
+        });
+
+        state.on_toggle_play_mode({
+            move || {
+                InterfaceSystem::toggle_play_mode();
+            }
+        });
+
+        // Minimap visibility toggle callback
+        state.on_toggle_minimap({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let visible = crate::index::game::systems::MinimapSystem::toggle();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_show_minimap(visible);
+                }
+            }
+        });
+
+        // Time of day slider callback
+        state.on_set_time_of_day({
+            move |hour| {
+                crate::index::game::systems::TimeOfDaySystem::set_hour(hour);
+            }
+        });
+
+        // Day cycle run/stop callback
+        state.on_toggle_day_cycle({
+            let ui_weak_clone = ui_weak.clone();
+            move || {
+                let running = crate::index::game::systems::TimeOfDaySystem::toggle_running();
+                if let Some(ui) = ui_weak_clone.upgrade() {
+                    ui.global::<InterfaceState>().set_day_cycle_running(running);
+                }
+            }
+        });
+
+        Self { ui_weak: ui.as_weak() }
+    }
+
+    /// Mirror the GameLoop pause state into the UI toolbar
+    pub fn sync_pause_state() {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    ui.global::<InterfaceState>().set_is_paused(GameLoop::is_paused());
+                }
+            }
+        }
+    }
+
+    /// Mirror the scene clock into the time of day slider
+    pub fn sync_time_of_day(hour: f32) {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    ui.global::<InterfaceState>().set_time_of_day(hour);
+                }
+            }
+        }
+    }
+
+    /// Mirror the HudBridge values into the in-game HUD layer
+    pub fn sync_hud(values: &HudValues) {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let hud = ui.global::<HudState>();
+                    hud.set_health(values.health);
+                    hud.set_max_health(values.max_health);
+                    hud.set_prompt(values.prompt.as_str().into());
+                    hud.set_crosshair_visible(values.crosshair_visible);
+                }
+            }
+        }
+    }
+
+    /// Whether the UI is in Play mode (HUD instead of the editor panels)
+    pub fn is_play_mode() -> bool {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    return ui.global::<InterfaceState>().get_play_mode();
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether the editor console is open (it takes the keyboard while open)
+    pub fn is_console_open() -> bool {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    return state.get_console_visible() && !state.get_play_mode();
+                }
+            }
+        }
+        false
+    }
+
+    /// Show or hide the editor console
+    pub fn toggle_console() {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_console_visible(!state.get_console_visible());
+                    Self::show_console_log(&ui);
+                }
+            }
+        }
+    }
+
+    /// Switch between Edit mode (editor panels) and Play mode (HUD); returns whether Play mode is on. Play runs
+    /// on a copy of the authoring world (see PlaySession). Exported builds (runtime projects) stay in Play mode.
+    pub fn toggle_play_mode() -> bool {
+        if Project::get().runtime {
+            return true;
+        }
+        let mut play_mode = None;
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    play_mode = Some(!state.get_play_mode());
+                    state.set_play_mode(!state.get_play_mode());
+                }
+            }
+        }
+        // The world is swapped once the UI is unlocked, as putting it back refreshes the editor
+        match play_mode {
+            Some(true) => {
+                Random::reset();
+                PlaySession::begin();
+                true
+            }
+            Some(false) => {
+                PlaySession::end();
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Open the Plugins panel with fresh rows, for panels that have something new to show
+    pub fn open_plugin_panels() {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    ui.global::<InterfaceState>().set_plugin_panels_visible(true);
+                    Self::show_plugin_panels(&ui);
+                }
+            }
+        }
+    }
+
+    /// Show a new minimap capture (tightly packed RGBA rows, top row first) and its markers
+    pub fn sync_minimap(width: u32, height: u32, rgba: &[u8], markers: &[([f32; 2], &str)]) {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    let pixels = slint::SharedPixelBuffer::<slint::Rgba8Pixel>::clone_from_slice(rgba, width, height);
+                    state.set_minimap_image(slint::Image::from_rgba8(pixels));
+
+                    let markers: Vec<MinimapMarker> = markers
+                        .iter()
+                        .map(|(position, kind)| MinimapMarker {
+                            x: position[0],
+                            y: position[1],
+                            kind: (*kind).into(),
+                        })
+                        .collect();
+                    state.set_minimap_markers(ModelRc::new(VecModel::from(markers)));
+                }
+            }
+        }
+    }
+
+    /// Log a recoverable engine error and surface it in the editor's error panel
+    pub fn report_error(error: &EngineError) {
+        eprintln!("❌ {}", error);
+        Diagnostics::log(&format!("Error: {}", error));
+
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    let mut messages: Vec<slint::SharedString> = state
+                        .get_error_messages()
+                        .iter()
+                        .collect();
+                    messages.push(error.to_string().into());
+                    state.set_error_messages(ModelRc::new(VecModel::from(messages)));
+                }
+            }
+        }
+    }
+
+    /// Mirror the scene SafeMode holds back into the Safe Mode panel, hiding it outside safe mode
+    pub fn show_safe_mode() {
+        let pending = SafeMode::pending();
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    state.set_safe_mode_visible(pending.is_some());
+                    let Some((reason, problems)) = pending else {
+                        return;
+                    };
+                    let problems: Vec<SafeModeProblem> = problems
+                        .into_iter()
+                        .map(|(problem, skipped)| SafeModeProblem {
+                            entity_id: problem.entity_id.into(),
+                            title: problem.title.into(),
+                            message: problem.message.into(),
+                            skipped,
+                            unreadable: problem.unreadable,
+                        })
+                        .collect();
+                    state.set_safe_mode_reason(reason.into());
+                    state.set_safe_mode_problems(ModelRc::new(VecModel::from(problems)));
+                }
+            }
+        }
+    }
+
+    /// Mirror ShaderDiagnostics into the shader error overlay
+    pub fn show_shader_errors() {
+        let errors: Vec<ShaderError> = ShaderDiagnostics::failures()
+            .into_iter()
+            .map(|failure| {
+                let excerpt: Vec<ShaderSourceLine> = failure.excerpt
+                    .iter()
+                    .map(|(number, text)| ShaderSourceLine {
+                        number: *number as i32,
+                        text: text.as_str().into(),
+                        offending: Some(*number) == failure.line,
+                    })
+                    .collect();
+                ShaderError {
+                    program: failure.program.into(),
+                    stage: failure.stage.into(),
+                    log: failure.log.into(),
+                    excerpt: ModelRc::new(VecModel::from(excerpt)),
+                }
+            })
+            .collect();
+
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    ui.global::<InterfaceState>().set_shader_errors(ModelRc::new(VecModel::from(errors)));
+                }
+            }
+        }
+    }
+
+    /// Update a specific field in a component and refresh the UI
+    fn update_component_field_internal(
+        entity_id: String, 
+        component_type: String, 
+        field_key: String, 
+        new_value: String
+    ) {
+        println!("🔧 Updating component field: entity={}, component={}, field={}, value={}", 
+            entity_id, component_type, field_key, new_value);
+        if Self::is_locked(&entity_id) {
+            println!("🔒 Entity {} is locked, ignoring field update", entity_id);
+            return;
+        }
+
+        // Get the current component data
+        let components = get_all_components_dyn!(entity_id);
+        
+        for component in components {
+            if let Ok(json_str) = to_string(&component) {
+                // Parse the component JSON to check if it matches the component type
+                if let Ok(mut json_value) = serde_json::from_str::<Value>(&json_str) {
+                    if let Some(Value::String(comp_type)) = json_value.get("type") {
+                        let kind = json_value.get("kind").and_then(Value::as_str);
+                        if comp_type == &component_type || (comp_type == "Plugin" && kind == Some(&component_type)) {
+                            // Found the matching component, update the field
+                            if let Some(obj) = json_value.as_object_mut() {
+                                // Parse the new value appropriately
+                                let parsed_value = Self::parse_field_value(&new_value);
+                                obj.insert(field_key.clone(), parsed_value);
+                                
+                                // Convert back to JSON string
+                                if let Ok(updated_json) = serde_json::to_string(&json_value) {
+                                    println!("📝 Updated component JSON: {}", updated_json);
+                                    
+                                    // Update the ECS component using existing system
+                                    Self::update_ecs_component(&entity_id, &updated_json);
+                                    
+                                    // Refresh the UI to show the updated component
+                                    Self::refresh_selected_entity(&entity_id);
+                                    return;
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        
+        println!("❌ Failed to find component {} for entity {}", component_type, entity_id);
+    }
+
+    /// Whether the entity's Metadata blocks editor selection and edits
+    fn is_locked(entity_id: &str) -> bool {
+        get_query_by_id!(entity_id.to_string(), (Metadata)).is_some_and(|metadata| metadata.locked)
+    }
+
+    /// Parse a string value into the appropriate JSON value type
+    pub fn parse_field_value(value_str: &str) -> Value {
+        // Lists and nested objects (e.g. Metadata tags) are edited as JSON
+        if value_str.starts_with('[') || value_str.starts_with('{') {
+            if let Ok(json_value) = serde_json::from_str::<Value>(value_str) {
+                return json_value;
+            }
+        }
+
+        // Try to parse as number first
+        if let Ok(int_val) = value_str.parse::<i64>() {
+            return Value::Number(serde_json::Number::from(int_val));
+        }
+        
+        if let Ok(float_val) = value_str.parse::<f64>() {
+            return Value::Number(serde_json::Number::from_f64(float_val).unwrap_or(serde_json::Number::from(0)));
+        }
+        
+        // Try to parse as boolean
+        if let Ok(bool_val) = value_str.parse::<bool>() {
+            return Value::Bool(bool_val);
+        }
+        
+        // Default to string
+        Value::String(value_str.to_string())
+    }
+
+    /// Update the ECS component using existing deserialization system
+    fn update_ecs_component(entity_id: &str, component_json: &str) {
+        println!("🔄 Updating ECS component for entity {}", entity_id);
+        println!("📝 Component JSON: {}", component_json);
+        
+        // Use the existing component-changed callback to handle deserialization
+        // This leverages whatever deserialization system is already in place
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    let entity_id_slint: slint::SharedString = entity_id.into();
+                    let component_json_slint: slint::SharedString = component_json.into();
+                    
+                    // Dispatch to existing component-changed system
+                    state.invoke_component_changed(entity_id_slint, component_json_slint);
+                    println!("✅ Component update dispatched to existing system");
+                }
+            }
+        }
+    }
+
+    /// Copy the newest console lines into the console panel
+    fn show_console_log(ui: &LevelEditorUI) {
+        let lines: Vec<String> = Console::log().iter().flat_map(|entry| entry.lines().map(str::to_string).collect::<Vec<_>>()).collect();
+        let visible: Vec<SharedString> = lines[lines.len().saturating_sub(CONSOLE_VISIBLE_LINES)..]
+            .iter()
+            .map(|line| line.as_str().into())
+            .collect();
+        ui.global::<InterfaceState>().set_console_log(ModelRc::new(VecModel::from(visible)));
+    }
+
+    /// Rebuild the Open Scene rows from the scene files and their headers
+    fn show_scenes(ui: &LevelEditorUI) {
+        let current = SceneLibrary::current();
+        let scenes: Vec<SceneEntry> = SceneLibrary::scenes()
+            .into_iter()
+            .map(|(path, header)| {
+                let header = header.unwrap_or_default();
+                let thumbnail = header.thumbnail
+                    .as_ref()
+                    .and_then(|file_name| {
+                        let thumbnail_path = std::path::Path::new(&path).with_file_name(file_name);
+                        slint::Image::load_from_path(&thumbnail_path).ok()
+                    });
+                let title = if header.title.is_empty() {
+                    std::path::Path::new(&path).file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default()
+                } else {
+                    header.title.clone()
+                };
+                SceneEntry {
+                    current: std::path::Path::new(&path) == std::path::Path::new(&current),
+                    title: title.into(),
+                    author: header.author.as_str().into(),
+                    date: if header.saved_at == 0 { "".into() } else { header.date_label().into() },
+                    entity_count: header.entity_count as i32,
+                    has_thumbnail: thumbnail.is_some(),
+                    thumbnail: thumbnail.unwrap_or_default(),
+                    path: path.into(),
+                }
+            })
+            .collect();
+        ui.global::<InterfaceState>().set_scenes(ModelRc::new(VecModel::from(scenes)));
+    }
+
+    /// Rebuild the Tweaks panel rows from the registered cvars
+    fn show_tweaks(ui: &LevelEditorUI) {
+        let tweaks: Vec<Tweak> = Console::cvars()
+            .into_iter()
+            .map(|(name, cvar)| Tweak {
+                name: name.into(),
+                help: cvar.help.into(),
+                value: cvar.value,
+                minimum: cvar.min,
+                maximum: cvar.max,
+            })
+            .collect();
+        ui.global::<InterfaceState>().set_tweaks(ModelRc::new(VecModel::from(tweaks)));
+    }
+
+    /// Rebuild the Plugins panel from the panels plugins registered
+    fn show_plugin_panels(ui: &LevelEditorUI) {
+        let panels: Vec<PluginPanel> = App::editor_panels()
+            .into_iter()
+            .map(|panel| {
+                let (row_label, row_line) = panel.row_action.clone().unwrap_or_default();
+                let rows: Vec<PluginPanelRow> = (panel.rows)()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (label, value))| {
+                        let command = match row_line.is_empty() {
+                            true => String::new(),
+                            false => format!("{} {}", row_line, index + 1),
+                        };
+                        PluginPanelRow {
+                            label: label.into(),
+                            value: value.into(),
+                            action_label: row_label.clone().into(),
+                            command: command.into(),
+                        }
+                    })
+                    .collect();
+                let actions: Vec<PluginPanelAction> = panel.actions
+                    .iter()
+                    .map(|(label, command)| PluginPanelAction { label: label.into(), command: command.into() })
+                    .collect();
+                PluginPanel {
+                    title: panel.title.into(),
+                    rows: ModelRc::new(VecModel::from(rows)),
+                    actions: ModelRc::new(VecModel::from(actions)),
+                }
+            })
+            .collect();
+        ui.global::<InterfaceState>().set_plugin_panels(ModelRc::new(VecModel::from(panels)));
+    }
+
+    /// List the fields matching a search in the Find & Replace panel, with what each would become; returns the
+    /// panel's status line
+    fn show_find_replace(ui: &LevelEditorUI, field: &str, pattern: &str, replacement: &str) -> String {
+        let state = ui.global::<InterfaceState>();
+        let matches = match FindReplace::preview(field, pattern, replacement) {
+            Ok(matches) => matches,
+            Err(e) => {
+                state.set_find_replace_matches(ModelRc::new(VecModel::from(Vec::<FindReplaceMatch>::new())));
+                return e;
+            }
+        };
+        let rows: Vec<FindReplaceMatch> = matches
+            .iter()
+            .map(|field_match| {
+                let (replacement, valid) = match &field_match.replaced {
+                    _ if field_match.locked => ("locked".to_string(), false),
+                    Ok(_) => (replacement.to_string(), true),
+                    Err(e) => (e.clone(), false),
+                };
+                FindReplaceMatch {
+                    entity_id: field_match.entity_id.clone().into(),
+                    title: field_match.title.clone().into(),
+                    value: field_match.value.clone().into(),
+                    replacement: replacement.into(),
+                    valid,
+                }
+            })
+            .collect();
+        let status = format!("{} matches", rows.len());
+        state.set_find_replace_matches(ModelRc::new(VecModel::from(rows)));
+        status
+    }
+
+    /// Show the scatter brush settings in the Scatter panel, with every prefab it can pick
+    fn show_scatter(ui: &LevelEditorUI) {
+        let settings = ScatterBrushSystem::settings();
+        let prefabs: Vec<ScatterPrefab> = prefab_names()
+            .into_iter()
+            .map(|name| ScatterPrefab { picked: settings.prefabs.contains(&name), name: name.into() })
+            .collect();
+        let mode = match settings.mode {
+            None => "off",
+            Some(ScatterMode::Paint) => "paint",
+            Some(ScatterMode::Erase) => "erase",
+        };
+        ui.global::<InterfaceState>().set_scatter(ScatterSettings {
+            mode: mode.into(),
+            prefabs: ModelRc::new(VecModel::from(prefabs)),
+            radius: settings.radius,
+            density: settings.density,
+            rotation_jitter: settings.rotation_jitter,
+            scale_jitter: settings.scale_jitter,
+            max_slope: settings.max_slope,
+        });
+    }
+
+    /// Apply the editor theme, UI scale and outline settings to the Slint tree; scaling resizes the root to the window's new
+    /// logical size, so panels lay out again at the new size
+    pub fn apply_editor_settings() {
+        let settings = EditorSettings::current();
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    ui.global::<Colors>().set_light_theme(settings.theme == EditorTheme::Light);
+                    ui.global::<InterfaceState>().set_ui_scale(settings.ui_scale);
+                    ui.global::<InterfaceState>().set_outline_width(settings.outline.width);
+                    ui.global::<InterfaceState>().set_outline_occluded_brightness(settings.outline.occluded_brightness);
+
+                    let window = ui.window();
+                    let scale_factor = EditorSettings::scale_factor();
+                    if window.scale_factor() != scale_factor {
+                        let size = window.size();
+                        let logical_size =
+                            LogicalSize::new(size.width as f32 / scale_factor, size.height as f32 / scale_factor);
+                        let applied = window
+                            .try_dispatch_event(WindowEvent::ScaleFactorChanged { scale_factor })
+                            .and_then(|()| window.try_dispatch_event(WindowEvent::Resized { size: logical_size }));
+                        if let Err(e) = applied {
+                            eprintln!("⚠️ Failed to apply UI scale {}: {}", scale_factor, e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Show the current graphics settings in the Graphics panel
+    fn show_graphics(ui: &LevelEditorUI) {
+        let quality = GraphicsQuality::current();
+        ui.global::<InterfaceState>().set_graphics(GraphicsSettings {
+            preset: format!("{:?}", quality.preset).to_lowercase().into(),
+            shadow_resolution: quality.shadow_resolution as i32,
+            antialiasing: AntialiasingMode::ALL.iter().position(|&mode| mode == quality.antialiasing).unwrap_or(0) as i32,
+            msaa_samples: quality.msaa_samples as i32,
+            anisotropy: quality.anisotropy,
+            ssao: quality.ssao,
+            depth_prepass: quality.depth_prepass,
+            post_effects: quality.post_effects,
+            texture_budget_mb: quality.texture_budget_mb as i32,
+        });
+    }
+
+    /// Re-list the scenes in the Open Scene browser, if it's open, after scene files changed on disk
+    pub fn refresh_scenes() {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    if ui.global::<InterfaceState>().get_scene_browser_visible() {
+                        Self::show_scenes(&ui);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Refresh the timeline panel, if open, from the selected entity's Animator (playhead, selection changes)
+    pub fn refresh_timeline() {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    if ui.global::<InterfaceState>().get_timeline_visible() {
+                        Self::show_timeline(&ui);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply a timeline panel edit to the entity the panel shows
+    fn edit_timeline(ui: &LevelEditorUI, change: impl FnOnce(&mut Animator, &[AnimationClip]) -> Result<(), String>) {
+        let entity_id = ui.global::<InterfaceState>().get_timeline().entity_id.to_string();
+        if let Err(error) = AnimationSystem::edit_playback(&entity_id, change) {
+            eprintln!("❌ {}", error);
+        }
+        Self::show_timeline(ui);
+    }
+
+    /// Show the selected entity's clips and playback in the timeline panel; the clip and marker lists are only
+    /// replaced when they change, so refreshing the playhead doesn't rebuild the rows under the pointer
+    fn show_timeline(ui: &LevelEditorUI) {
+        let state = ui.global::<InterfaceState>();
+        let entity_id = state.get_selected_index().to_string();
+        let Some(animated_object) = get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent)) else {
+            state.set_timeline(AnimationTimeline::default());
+            return;
+        };
+        let animator = &animated_object.animator;
+        let clip = animator.current_clip(&animated_object.clips);
+        let clip_name = clip.map(|clip| clip.name.clone()).unwrap_or_default();
+        let clips: Vec<SharedString> = animated_object.clips.iter().map(|clip| clip.name.as_str().into()).collect();
+        let markers: Vec<TimelineMarker> = animator
+            .markers()
+            .iter()
+            .enumerate()
+            .filter(|(_, marker)| marker.clip == clip_name)
+            .map(|(index, marker)| TimelineMarker {
+                index: index as i32,
+                name: marker.name.as_str().into(),
+                time: marker.time,
+            })
+            .collect();
+
+        let previous = state.get_timeline();
+        let same_entity = previous.entity_id.as_str() == entity_id;
+        let clips = if same_entity && previous.clips.iter().eq(clips.iter().cloned()) {
+            previous.clips
+        } else {
+            ModelRc::new(VecModel::from(clips))
+        };
+        let markers = if same_entity && previous.markers.iter().eq(markers.iter().cloned()) {
+            previous.markers
+        } else {
+            ModelRc::new(VecModel::from(markers))
+        };
+        let title = get_query_by_id!(entity_id.clone(), (Metadata)).map(|metadata| metadata.title().to_string());
+
+        state.set_timeline(AnimationTimeline {
+            entity_id: entity_id.as_str().into(),
+            title: title.unwrap_or_else(|| entity_id.clone()).into(),
+            clips,
+            clip: clip_name.into(),
+            duration: clip.map_or(0.0, |clip| clip.duration),
+            time: animator.get_time(),
+            playing: animator.is_playing(),
+            looping: animator.is_looping(),
+            speed: animator.playback_rate(),
+            markers,
+        });
+    }
+
+    /// Refresh the curve editor, if open, from the selected entity's PropertyAnimation (playhead, selection changes)
+    pub fn refresh_curve_editor() {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    if ui.global::<InterfaceState>().get_curve_editor_visible() {
+                        Self::show_curve_editor(&ui, false);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply a curve editor edit to the PropertyAnimation it shows; the change may return a key index to select
+    /// instead (-1 for none)
+    fn edit_curves(ui: &LevelEditorUI, change: impl FnOnce(&mut PropertyAnimation) -> Result<Option<i32>, String>) {
+        let state = ui.global::<InterfaceState>();
+        let entity_id = state.get_curve_editor().entity_id.to_string();
+        let mut selection = None;
+        let result = PropertyAnimationSystem::edit(&entity_id, |animation| {
+            selection = change(animation)?;
+            Ok(())
+        });
+        if let Err(error) = result {
+            eprintln!("❌ {}", error);
+        }
+        if let Some(selected_key) = selection {
+            let mut editor = state.get_curve_editor();
+            editor.selected_key = selected_key;
+            state.set_curve_editor(editor);
+        }
+        Self::show_curve_editor(ui, false);
+    }
+
+    /// Apply a curve editor edit to the shown track's curve, which gets the selected key's index if any
+    fn edit_curve(ui: &LevelEditorUI, change: impl FnOnce(&mut Curve, Option<usize>) -> Result<Option<i32>, String>) {
+        let editor = ui.global::<InterfaceState>().get_curve_editor();
+        let property = AnimatedProperty::from_name(&editor.track);
+        let selected = usize::try_from(editor.selected_key).ok();
+        Self::edit_curves(ui, |animation| {
+            let curve = property.and_then(|property| animation.track_mut(property)).ok_or("Add a track first")?;
+            change(curve, selected.filter(|&index| index < curve.keys.len()))
+        });
+    }
+
+    /// Show another track of the curve editor's entity, fitting the view to it
+    fn show_curve_track(ui: &LevelEditorUI, track: &str) {
+        let state = ui.global::<InterfaceState>();
+        let mut editor = state.get_curve_editor();
+        editor.track = track.into();
+        editor.selected_key = -1;
+        state.set_curve_editor(editor);
+        Self::show_curve_editor(ui, true);
+    }
+
+    /// Time and value at a 0-1 graph position of the curve editor's view
+    fn curve_point(editor: &CurveEditorState, x: f32, y: f32) -> (f32, f32) {
+        (x * editor.time_range, editor.value_max - y * (editor.value_max - editor.value_min))
+    }
+
+    /// Show the selected entity's PropertyAnimation in the curve editor. The view keeps its range while the same
+    /// track is shown, so keys don't slide under a drag; a new track or entity, or `fit`, frames the curve. The key
+    /// rows are updated in place when their number doesn't change, for the same reason.
+    fn show_curve_editor(ui: &LevelEditorUI, fit: bool) {
+        let state = ui.global::<InterfaceState>();
+        let previous = state.get_curve_editor();
+        let entity_id = state.get_selected_index().to_string();
+        if entity_id.is_empty() {
+            state.set_curve_editor(CurveEditorState { selected_key: -1, ..Default::default() });
+            return;
+        }
+        let animation = get_query_by_id!(entity_id.clone(), (PropertyAnimation)).unwrap_or_default();
+        let tracks: Vec<SharedString> = animation.tracks.iter().map(|track| track.property.name().into()).collect();
+        let properties: Vec<SharedString> = AnimatedProperty::ALL.iter().map(|property| property.name().into()).collect();
+
+        let same_entity = previous.entity_id.as_str() == entity_id;
+        let track = if same_entity && tracks.contains(&previous.track) {
+            previous.track.clone()
+        } else {
+            tracks.first().cloned().unwrap_or_default()
+        };
+        let same_track = same_entity && track == previous.track;
+        let empty = Curve::default();
+        let curve = animation.tracks
+            .iter()
+            .find(|existing| existing.property.name() == track.as_str())
+            .map_or(&empty, |existing| &existing.curve);
+        let selected_key = if same_track { previous.selected_key } else { -1 };
+        let selected = usize::try_from(selected_key).ok().filter(|&index| index < curve.keys.len());
+
+        let (time_range, value_min, value_max) = if same_track && !fit && previous.time_range > 0.0 {
+            (previous.time_range, previous.value_min, previous.value_max)
+        } else {
+            Self::fit_curve(curve, AnimatedProperty::from_name(&track).and_then(|property| {
+                PropertyAnimationSystem::read(&entity_id, property)
+            }))
+        };
+        let to_graph = |time: f32, value: f32| [time / time_range, (value_max - value) / (value_max - value_min)];
+
+        let keys: Vec<CurveKeyPoint> = curve.keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let [x, y] = to_graph(key.time, key.value);
+                let [in_x, in_y] = to_graph(key.time + key.in_handle[0], key.value + key.in_handle[1]);
+                let [out_x, out_y] = to_graph(key.time + key.out_handle[0], key.value + key.out_handle[1]);
+                CurveKeyPoint { index: index as i32, x, y, in_x, in_y, out_x, out_y, selected: selected == Some(index) }
+            })
+            .collect();
+        let handle_commands = selected.map_or(String::new(), |index| {
+            let key = &keys[index];
+            format!(
+                "M {} {} L {} {} L {} {}",
+                key.in_x * CURVE_VIEWBOX, key.in_y * CURVE_VIEWBOX,
+                key.x * CURVE_VIEWBOX, key.y * CURVE_VIEWBOX,
+                key.out_x * CURVE_VIEWBOX, key.out_y * CURVE_VIEWBOX
+            )
+        });
+
+        // Flat before the first key, a cubic per segment with the handles `Curve::evaluate` uses, flat after the last
+        let point = |time: f32, value: f32| {
+            let [x, y] = to_graph(time, value);
+            format!("{} {}", x * CURVE_VIEWBOX, y * CURVE_VIEWBOX)
+        };
+        let mut commands = String::new();
+        if let (Some(first), Some(last)) = (curve.keys.first(), curve.keys.last()) {
+            commands = format!("M {} L {}", point(0.0, first.value), point(first.time, first.value));
+            for pair in curve.keys.windows(2) {
+                let (start, end) = (&pair[0], &pair[1]);
+                let span = end.time - start.time;
+                commands += &format!(
+                    " C {} {} {}",
+                    point(start.time + start.out_handle[0].clamp(0.0, span), start.value + start.out_handle[1]),
+                    point(end.time + end.in_handle[0].clamp(-span, 0.0), end.value + end.in_handle[1]),
+                    point(end.time, end.value)
+                );
+            }
+            commands += &format!(" L {}", point(time_range.max(last.time), last.value));
+        }
+
+        let properties = if previous.properties.row_count() == properties.len() {
+            previous.properties
+        } else {
+            ModelRc::new(VecModel::from(properties))
+        };
+        let tracks = if same_entity && previous.tracks.iter().eq(tracks.iter().cloned()) {
+            previous.tracks
+        } else {
+            ModelRc::new(VecModel::from(tracks))
+        };
+        let keys = if same_track && previous.keys.row_count() == keys.len() {
+            for (row, key) in keys.into_iter().enumerate() {
+                if previous.keys.row_data(row).as_ref() != Some(&key) {
+                    previous.keys.set_row_data(row, key);
+                }
+            }
+            previous.keys
+        } else {
+            ModelRc::new(VecModel::from(keys))
+        };
+        let title = get_query_by_id!(entity_id.clone(), (Metadata)).map(|metadata| metadata.title().to_string());
+        let selected_key_data = selected.map(|index| curve.keys[index]);
+
+        state.set_curve_editor(CurveEditorState {
+            entity_id: entity_id.as_str().into(),
+            title: title.unwrap_or_else(|| entity_id.clone()).into(),
+            properties,
+            tracks,
+            track,
+            commands: commands.into(),
+            handle_commands: handle_commands.into(),
+            keys,
+            selected_key: selected.map_or(-1, |index| index as i32),
+            key_time: selected_key_data.map_or(0.0, |key| key.time),
+            key_value: selected_key_data.map_or(0.0, |key| key.value),
+            time_range,
+            value_min,
+            value_max,
+            playhead: animation.time / time_range,
+            time: animation.time,
+            duration: animation.duration(),
+            playing: animation.playing,
+            looping: animation.looping,
+        });
+    }
+
+    /// View framing a curve's keys and handles with some margin: seconds across, and the values at the bottom and
+    /// top. An empty curve is framed around `current`, the property's value on the entity.
+    fn fit_curve(curve: &Curve, current: Option<f32>) -> (f32, f32, f32) {
+        let time_range = (curve.duration() * 1.1).max(1.0);
+        let values = curve.keys.iter().flat_map(|key| {
+            [key.value, key.value + key.in_handle[1], key.value + key.out_handle[1]]
+        });
+        let (low, high) = values
+            .chain(current.filter(|_| curve.keys.is_empty()))
+            .fold((f32::MAX, f32::MIN), |(low, high), value| (low.min(value), high.max(value)));
+        if low > high {
+            return (time_range, -1.0, 1.0);
+        }
+        let margin = ((high - low) * 0.1).max(0.5);
+        (time_range, low - margin, high + margin)
+    }
+
+    /// Show the scene's ColorGrading, or the defaults it would get, in the Color Grading panel
+    fn show_color_grading(ui: &LevelEditorUI) {
+        let grading = crate::index::game::systems::ColorGradingSystem::current().unwrap_or_default();
+        ui.global::<InterfaceState>().set_color_grading(ColorGradingSettings {
+            exposure: grading.exposure,
+            auto_exposure: grading.auto_exposure,
+            adaptation_speed: grading.adaptation_speed,
+            contrast: grading.contrast,
+            saturation: grading.saturation,
+            lut: grading.lut.unwrap_or_default().into(),
+            lut_strength: grading.lut_strength,
+        });
+    }
+
+    /// Highlight the multi-selection in the outliner without rebuilding the list
+    fn sync_selection_marks(ui: &LevelEditorUI) {
+        let entities = ui.global::<InterfaceState>().get_entities();
+        for row in 0..entities.row_count() {
+            let Some(mut entity) = entities.row_data(row) else {
+                continue;
+            };
+            let selected = if entity.is_group {
+                Selection::group().is_some_and(|group_id| group_id == entity.entity_id.as_str())
+            } else {
+                Selection::contains(&entity.entity_id)
+            };
+            if entity.selected != selected {
+                entity.selected = selected;
+                entities.set_row_data(row, entity);
+            }
+        }
+    }
+
+    /// Refresh the UI for the currently selected entity
+    fn refresh_selected_entity(entity_id: &str) {
+        if let Some(system) = INTERFACE_SYSTEM.get() {
+            if let Ok(system) = system.lock() {
+                if let Some(ui) = system.ui_weak.upgrade() {
+                    let state = ui.global::<InterfaceState>();
+                    
+                    // Check if this entity is currently selected
+                    if state.get_selected_index().to_string() == entity_id {
+                        // Re-trigger entity selection to refresh the component display
+                        let entity_id_slint: slint::SharedString = entity_id.into();
+                        state.invoke_entity_selected(entity_id_slint);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Update component from JSON using generic deserialization
+    fn update_component_from_json(entity_id: String, component_json: String) {
+        println!("🔄 Deserializing component JSON for entity {}", entity_id);
+        
+        // Use the generic Component enum deserialization - leverages existing serde type tagging
+        match serde_json::from_str::<crate::index::engine::modules::ecs::Component>(&component_json) {
+            Ok(component) => {
+                crate::index::engine::modules::ecs::insert(&entity_id, component);
+                println!("✅ Component updated successfully using generic deserialization");
+            },
+            Err(e) => {
+                println!("❌ Failed to deserialize component: {}", e);
+            }
+        }
+    }
+
+    fn update_entities_internal(&self) {
+        // Update entity list from ECS
+        let metadata_results = query_get_all!(Metadata);
+
+        println!("🔄 Updating entity list - found {} entities", metadata_results.len());
+
+        // Create proper Entity struct that matches the Slint definition
+        let entity_row = |entity_id: &str, metadata: &Metadata, depth: i32| {
+            println!("  - Entity: {} - {}", entity_id, metadata.title());
+            Entity {
+                entity_id: entity_id.into(),
+                title: metadata.title().into(),
+                visible: metadata.is_visible,
+                locked: metadata.locked,
+                is_group: false,
+                collapsed: false,
+                depth,
+                category: metadata.category.name().into(),
+                selected: Selection::contains(entity_id),
+                debug_bounds: CullingDebugSystem::is_debugged(entity_id),
+            }
+        };
+
+        // Outliner groups come first, each followed by its members unless collapsed
+        let mut entities = Vec::new();
+        let mut grouped = HashSet::new();
+        for group in OutlinerGroups::groups() {
+            entities.push(Entity {
+                entity_id: group.id.as_str().into(),
+                title: group.name.as_str().into(),
+                visible: OutlinerGroups::is_visible(&group),
+                locked: OutlinerGroups::is_locked(&group),
+                is_group: true,
+                collapsed: group.collapsed,
+                depth: 0,
+                category: "".into(),
+                selected: Selection::group().is_some_and(|group_id| group_id == group.id),
+                debug_bounds: false,
+            });
+            for member in &group.members {
+                let Some((entity_id, metadata)) = metadata_results.iter().find(|(entity_id, _)| entity_id == member) else {
+                    continue;
+                };
+                grouped.insert(entity_id.as_str());
+                if !group.collapsed {
+                    entities.push(entity_row(entity_id, metadata, 1));
+                }
+            }
+        }
+        for (entity_id, metadata) in &metadata_results {
+            if !grouped.contains(entity_id.as_str()) {
+                entities.push(entity_row(entity_id, metadata, 0));
+            }
         }
 
         // Get the UI instance and update entities
@@ -4,10 +4,62 @@ use winit::keyboard::KeyCode;
 use winit::event::ElementState;
 use slint::winit_030::{ WinitWindowAccessor, winit };
 use crate::index::engine::modules::event_system::{ Event, EventSystem, EventType };
+use crate::index::engine::modules::game_loop::GameLoop;
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::components::CharacterInput;
+
+/// Payload of EventType::PointerDrag/PointerRelease: cursor position in physical pixels and the window size
+#[derive(Clone, Copy, Debug)]
+pub struct PointerEvent {
+    pub position: [f32; 2],
+    pub viewport: [u32; 2],
+    pub snap: bool,     // V held: dragged entities snap to nearby surfaces and corners
+    pub additive: bool, // Shift held: viewport selection adds to the current one
+    pub lasso: bool,    // Alt held: viewport selection follows the cursor path instead of a box
+}
+
+/// Payload of EventType::Move: held direction keys joined with '-' (e.g. "forward-left"), the jump, crouch and
+/// sprint actions and the tick's delta time
+#[derive(Clone, Debug)]
+pub struct MoveEvent {
+    pub direction: String,
+    pub delta_time: f32,
+    pub input: CharacterInput,
+}
+
+/// Payload of EventType::CameraBookmark: Ctrl+1..9 stores the editor camera in a slot, 1..9 jumps to it
+#[derive(Clone, Copy, Debug)]
+pub struct BookmarkEvent {
+    pub slot: u8,
+    pub store: bool,
+}
+
+/// Payload of EventType::FrameView: what the editor camera should fly to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FrameTarget {
+    Selection,
+    All,
+}
+
+/// Payload of EventType::ViewPreset: numpad 7/1/3 look down/along the axes (Ctrl for the opposite side),
+/// numpad 5 toggles perspective
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewPreset {
+    Top,
+    Bottom,
+    Front,
+    Back,
+    Left,
+    Right,
+    TogglePerspective,
+}
 
 pub struct KeyboardInputSystem {
     pressed_keys: Mutex<HashSet<KeyCode>>,
     is_locked: Mutex<bool>,
+    modifiers: Mutex<winit::keyboard::ModifiersState>,
+    pointer: Mutex<Option<PointerEvent>>, // Last free cursor position (not tracked while locked)
+    is_primary_pressed: Mutex<bool>,
 }
 
 impl KeyboardInputSystem {
@@ -15,9 +67,17 @@ impl KeyboardInputSystem {
         Self {
             pressed_keys: Mutex::new(HashSet::new()),
             is_locked: Mutex::new(false),
+            modifiers: Mutex::new(winit::keyboard::ModifiersState::empty()),
+            pointer: Mutex::new(None),
+            is_primary_pressed: Mutex::new(false),
         }
     }
 
+    /// Public method: Track modifier keys (Shift/Ctrl/Alt/Super) for key combinations
+    pub fn receive_modifiers(&self, modifiers: &winit::event::Modifiers) {
+        *self.modifiers.lock().unwrap() = modifiers.state();
+    }
+
     /// Public method: Receive and process keyboard events
     pub fn receive_key_event(
         &self,
@@ -25,9 +85,23 @@ impl KeyboardInputSystem {
         slint_window: &slint::Window
     ) {
         if let winit::keyboard::PhysicalKey::Code(key_code) = key_event.physical_key {
+            // The open console takes the keyboard; only ` (close) and Escape still reach the editor
+            if
+                key_event.state == ElementState::Pressed &&
+                !matches!(key_code, KeyCode::Backquote | KeyCode::Escape) &&
+                InterfaceSystem::is_console_open()
+            {
+                return;
+            }
+
             match key_event.state {
                 ElementState::Pressed => {
                     match key_code {
+                        KeyCode::Backquote if !InterfaceSystem::is_play_mode() => {
+                            if !key_event.repeat {
+                                InterfaceSystem::toggle_console();
+                            }
+                        }
                         KeyCode::Tab => {
                             // Toggle cursor lock on Tab press
                             let mut is_locked = self.is_locked.lock().unwrap();
@@ -100,6 +174,104 @@ impl KeyboardInputSystem {
                                 });
                             }
                         }
+                        KeyCode::Enter if self.modifiers.lock().unwrap().alt_key() => {
+                            // Alt+Enter toggles borderless fullscreen (ignore key repeat)
+                            if !key_event.repeat {
+                                let fullscreen = !slint_window.is_fullscreen();
+                                slint_window.set_fullscreen(fullscreen);
+                                println!("[INPUT] Fullscreen toggled: {}", fullscreen);
+                            }
+                        }
+                        KeyCode::KeyP => {
+                            // Toggle simulation pause (ignore key repeat)
+                            if !key_event.repeat {
+                                GameLoop::toggle_pause();
+                                InterfaceSystem::sync_pause_state();
+                            }
+                        }
+                        KeyCode::Period => {
+                            // Advance exactly one simulation tick
+                            if !key_event.repeat {
+                                GameLoop::step();
+                                InterfaceSystem::sync_pause_state();
+                            }
+                        }
+                        KeyCode::F5 => {
+                            // Toggle between Edit and Play mode
+                            if !key_event.repeat {
+                                let play_mode = InterfaceSystem::toggle_play_mode();
+                                println!("[INPUT] Play mode: {}", play_mode);
+                            }
+                        }
+                        KeyCode::KeyE if InterfaceSystem::is_play_mode() => {
+                            // Use the aimed-at interactable instead of flying up while playing
+                            if !key_event.repeat {
+                                EventSystem::notify(Event {
+                                    event_type: EventType::Use,
+                                    payload: Box::new(()),
+                                });
+                            }
+                        }
+                        KeyCode::KeyF if !InterfaceSystem::is_play_mode() => {
+                            // Frame the selected entity (ignore key repeat)
+                            if !key_event.repeat {
+                                EventSystem::notify(Event {
+                                    event_type: EventType::FrameView,
+                                    payload: Box::new(FrameTarget::Selection),
+                                });
+                            }
+                        }
+                        KeyCode::KeyA if !InterfaceSystem::is_play_mode() && !*self.is_locked.lock().unwrap() => {
+                            // Frame the whole scene; with the cursor captured (Tab) A strafes as usual
+                            if !key_event.repeat {
+                                EventSystem::notify(Event {
+                                    event_type: EventType::FrameView,
+                                    payload: Box::new(FrameTarget::All),
+                                });
+                            }
+                        }
+                        KeyCode::Numpad1 |
+                        KeyCode::Numpad3 |
+                        KeyCode::Numpad5 |
+                        KeyCode::Numpad7 if !InterfaceSystem::is_play_mode() => {
+                            // Axis-aligned orthographic editor views (ignore key repeat)
+                            if !key_event.repeat {
+                                let opposite = self.modifiers.lock().unwrap().control_key();
+                                let preset = match (key_code, opposite) {
+                                    (KeyCode::Numpad7, false) => ViewPreset::Top,
+                                    (KeyCode::Numpad7, true) => ViewPreset::Bottom,
+                                    (KeyCode::Numpad1, false) => ViewPreset::Front,
+                                    (KeyCode::Numpad1, true) => ViewPreset::Back,
+                                    (KeyCode::Numpad3, false) => ViewPreset::Right,
+                                    (KeyCode::Numpad3, true) => ViewPreset::Left,
+                                    _ => ViewPreset::TogglePerspective,
+                                };
+                                EventSystem::notify(Event {
+                                    event_type: EventType::ViewPreset,
+                                    payload: Box::new(preset),
+                                });
+                            }
+                        }
+                        KeyCode::Digit1 |
+                        KeyCode::Digit2 |
+                        KeyCode::Digit3 |
+                        KeyCode::Digit4 |
+                        KeyCode::Digit5 |
+                        KeyCode::Digit6 |
+                        KeyCode::Digit7 |
+                        KeyCode::Digit8 |
+                        KeyCode::Digit9 if !InterfaceSystem::is_play_mode() => {
+                            // Store (with Ctrl) or recall an editor camera bookmark (ignore key repeat)
+                            if !key_event.repeat {
+                                EventSystem::notify(Event {
+                                    event_type: EventType::CameraBookmark,
+                                    payload: Box::new(BookmarkEvent {
+                                        slot: Self::bookmark_slot(key_code),
+                                        store: self.modifiers.lock().unwrap().control_key(),
+                                    }),
+                                });
+                            }
+                        }
                         _ => {
                             // Handle regular keys for movement
                             let mut pressed_keys = self.pressed_keys.lock().unwrap();
@@ -109,10 +281,10 @@ impl KeyboardInputSystem {
                     }
                 }
                 ElementState::Released => {
-                    // Only track release for movement keys (not Tab/Escape)
+                    // Only track release for movement keys (not Tab/Escape/P/Period/F5)
                     match key_code {
-                        KeyCode::Tab | KeyCode::Escape => {
-                            // Don't track Tab/Escape releases
+                        KeyCode::Tab | KeyCode::Escape | KeyCode::KeyP | KeyCode::Period | KeyCode::F5 => {
+                            // Don't track command key releases
                         }
                         _ => {
                             let mut pressed_keys = self.pressed_keys.lock().unwrap();
@@ -133,6 +305,17 @@ impl KeyboardInputSystem {
     ) {
         let is_locked = *self.is_locked.lock().unwrap();
 
+        if !is_locked {
+            let size = slint_window.size();
+            *self.pointer.lock().unwrap() = Some(PointerEvent {
+                position: [position.x as f32, position.y as f32],
+                viewport: [size.width, size.height],
+                snap: false,
+                additive: false,
+                lasso: false,
+            });
+        }
+
         if is_locked {
             // Enhanced mouse handling when cursor is locked
             slint_window.with_winit_window(|winit_window| {
@@ -166,17 +349,84 @@ impl KeyboardInputSystem {
         }
     }
 
-    /// Public method: Update called each frame
-    pub fn update(&self) {
+    /// Public method: Track the primary mouse button for viewport dragging (e.g. terrain brushes)
+    pub fn receive_mouse_button(&self, state: &ElementState, button: &winit::event::MouseButton) {
+        if *button == winit::event::MouseButton::Left {
+            let pressed = *state == ElementState::Pressed;
+            let was_pressed = std::mem::replace(&mut *self.is_primary_pressed.lock().unwrap(), pressed);
+
+            // Ends viewport drags (e.g. entity moves)
+            if was_pressed && !pressed {
+                if let Some(pointer) = self.current_pointer() {
+                    EventSystem::notify(Event {
+                        event_type: EventType::PointerRelease,
+                        payload: Box::new(pointer),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Public method: Called once per rendered frame, whether or not simulation ticks run (editor tools keep
+    /// working while the game is paused); delivers a drag event while the primary button is held with a free cursor
+    pub fn update_frame(&self) {
+        if *self.is_primary_pressed.lock().unwrap() && !*self.is_locked.lock().unwrap() {
+            if let Some(pointer) = self.current_pointer() {
+                EventSystem::notify(Event {
+                    event_type: EventType::PointerDrag,
+                    payload: Box::new(pointer),
+                });
+            }
+        }
+    }
+
+    /// Public method: Update called each fixed tick with its delta time; its events are delivered at the tick's first drain
+    pub fn update(&self, delta_time: f32) {
         let direction = self.calculate_direction();
+        let input = self.calculate_actions();
 
-        // Only send movement event if there's actual movement
-        if !direction.is_empty() {
+        // Only send movement event if there's actual movement or a held action
+        if !direction.is_empty() || input.jump || input.crouch || input.sprint {
             let move_event = Event {
                 event_type: EventType::Move,
-                payload: Box::new(direction),
+                payload: Box::new(MoveEvent { direction, delta_time, input }),
             };
-            EventSystem::notify(move_event);
+            EventSystem::send(move_event);
+        }
+    }
+
+    /// Private method: Last free cursor position with the current snap and selection modifier state
+    fn current_pointer(&self) -> Option<PointerEvent> {
+        let mut pointer = (*self.pointer.lock().unwrap())?;
+        let modifiers = *self.modifiers.lock().unwrap();
+        pointer.snap = self.pressed_keys.lock().unwrap().contains(&KeyCode::KeyV);
+        pointer.additive = modifiers.shift_key();
+        pointer.lasso = modifiers.alt_key();
+        Some(pointer)
+    }
+
+    /// Private method: Bookmark slot of a number key
+    fn bookmark_slot(key_code: KeyCode) -> u8 {
+        match key_code {
+            KeyCode::Digit1 => 1,
+            KeyCode::Digit2 => 2,
+            KeyCode::Digit3 => 3,
+            KeyCode::Digit4 => 4,
+            KeyCode::Digit5 => 5,
+            KeyCode::Digit6 => 6,
+            KeyCode::Digit7 => 7,
+            KeyCode::Digit8 => 8,
+            _ => 9,
+        }
+    }
+
+    /// Private method: Jump (Space), crouch (Ctrl/C) and sprint (Shift) from pressed keys
+    fn calculate_actions(&self) -> CharacterInput {
+        let pressed_keys = self.pressed_keys.lock().unwrap();
+        CharacterInput {
+            jump: pressed_keys.contains(&KeyCode::Space),
+            crouch: [KeyCode::ControlLeft, KeyCode::ControlRight, KeyCode::KeyC].iter().any(|key| pressed_keys.contains(key)),
+            sprint: [KeyCode::ShiftLeft, KeyCode::ShiftRight].iter().any(|key| pressed_keys.contains(key)),
         }
     }
 
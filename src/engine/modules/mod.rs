@@ -2,9 +2,41 @@ pub mod event_system;
 pub mod keyboard_input_system;
 pub mod interface_system;
 pub mod scene_format;
+pub mod game_loop;
+pub mod app_config;
+pub mod cli_args;
+pub mod engine_error;
+pub mod app;
+pub mod hud_bridge;
+pub mod outliner_groups;
+pub mod camera_bookmarks;
+pub mod editor_sidecar;
+pub mod selection;
+pub mod console;
+pub mod collision_file;
+pub mod scene_library;
+pub mod project;
+pub mod build_export;
+pub mod random;
+pub mod transform_interpolation;
+pub mod task_system;
+pub mod graphics_quality;
+pub mod editor_settings;
+pub mod shader_diagnostics;
+pub mod scene_validation;
+pub mod safe_mode;
+pub mod diagnostics;
+pub mod entity_pool;
+pub mod find_replace;
+pub mod determinism_audit;
+pub mod play_session;
+pub mod collab_session;
+pub mod scene_bench;
 
 // New ECS system
 pub mod ecs;
+pub mod ecs_storage;
+pub mod ecs_bench;
 #[macro_use]
 pub mod ecs_macros;
 
@@ -12,6 +44,35 @@ pub mod ecs_macros;
 pub use event_system::{ EventSystem, EventType };
 pub use keyboard_input_system::{ KeyboardInputSystem };
 pub use interface_system::{ InterfaceSystem };
+pub use game_loop::GameLoop;
+pub use app_config::AppConfig;
+pub use cli_args::CliArgs;
+pub use engine_error::{ EngineError, EngineResult };
+pub use app::{ App, EditorPanel, Plugin };
+pub use hud_bridge::HudBridge;
+pub use outliner_groups::OutlinerGroups;
+pub use camera_bookmarks::{ CameraBookmark, CameraBookmarks };
+pub use selection::Selection;
+pub use console::Console;
+pub use collision_file::CollisionFile;
+pub use scene_library::SceneLibrary;
+pub use project::{ Project, TransparencyMode };
+pub use build_export::BuildExport;
+pub use random::Random;
+pub use transform_interpolation::TransformInterpolation;
+pub use task_system::TaskSystem;
+pub use graphics_quality::{ AntialiasingMode, GraphicsQuality, QualityPreset };
+pub use editor_settings::{ EditorSettings, EditorTheme };
+pub use shader_diagnostics::ShaderDiagnostics;
+pub use safe_mode::SafeMode;
+pub use diagnostics::Diagnostics;
+pub use entity_pool::EntityPool;
+pub use find_replace::FindReplace;
+pub use determinism_audit::DeterminismAudit;
+pub use play_session::PlaySession;
+pub use collab_session::CollabSession;
+pub use scene_bench::SceneBench;
+pub use ecs_bench::EcsBench;
 
 // Re-export ECS functionality for clean imports
 pub use ecs::*;
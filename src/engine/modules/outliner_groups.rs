@@ -0,0 +1,134 @@
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use serde::{ Serialize, Deserialize };
+use uuid::Uuid;
+
+use crate::index::engine::components::Metadata;
+use crate::index::engine::modules::ecs::entity_exists;
+use crate::index::engine::modules::EntityId;
+use crate::{ get_query_by_id, query_by_id };
+
+/// Editor-only folder in the outliner; it organizes entities without parenting their transforms
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OutlinerGroup {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub collapsed: bool,
+    #[serde(default)]
+    pub members: Vec<EntityId>, // Outliner order; an entity belongs to at most one group
+}
+
+static GROUPS: Lazy<RwLock<Vec<OutlinerGroup>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// Outliner groups of the open scene
+pub struct OutlinerGroups;
+
+impl OutlinerGroups {
+    /// Replace every group, e.g. with the ones read from the editor sidecar
+    pub fn replace(groups: Vec<OutlinerGroup>) {
+        *GROUPS.write().unwrap() = groups;
+    }
+
+    /// Drop members that no longer exist, before the groups are saved
+    pub fn prune() {
+        for group in GROUPS.write().unwrap().iter_mut() {
+            group.members.retain(|entity_id| entity_exists(entity_id));
+        }
+    }
+
+    pub fn groups() -> Vec<OutlinerGroup> {
+        GROUPS.read().unwrap().clone()
+    }
+
+    /// Add an empty group named "Group N" and return its id
+    pub fn create() -> String {
+        let mut groups = GROUPS.write().unwrap();
+        let group = OutlinerGroup {
+            id: Uuid::new_v4().to_string(),
+            name: format!("Group {}", groups.len() + 1),
+            collapsed: false,
+            members: Vec::new(),
+        };
+        let id = group.id.clone();
+        groups.push(group);
+        id
+    }
+
+    pub fn rename(group_id: &str, name: &str) {
+        Self::modify(group_id, |group| group.name = name.to_string());
+    }
+
+    /// Delete the folder; its entities stay in the scene, ungrouped
+    pub fn remove(group_id: &str) {
+        GROUPS.write().unwrap().retain(|group| group.id != group_id);
+    }
+
+    pub fn toggle_collapsed(group_id: &str) {
+        Self::modify(group_id, |group| group.collapsed = !group.collapsed);
+    }
+
+    /// Move the entity into the group, taking it out of any other group
+    pub fn add_member(group_id: &str, entity_id: &str) {
+        Self::remove_member(entity_id);
+        Self::modify(group_id, |group| group.members.push(entity_id.to_string()));
+    }
+
+    pub fn remove_member(entity_id: &str) {
+        for group in GROUPS.write().unwrap().iter_mut() {
+            group.members.retain(|member| member != entity_id);
+        }
+    }
+
+    /// Visible while any member is visible, so hiding the group hides everything in it
+    pub fn is_visible(group: &OutlinerGroup) -> bool {
+        group.members
+            .iter()
+            .any(|entity_id| get_query_by_id!(entity_id.clone(), (Metadata)).is_some_and(|metadata| metadata.is_visible))
+    }
+
+    /// Locked only when every member is locked
+    pub fn is_locked(group: &OutlinerGroup) -> bool {
+        let mut members = group.members
+            .iter()
+            .filter_map(|entity_id| get_query_by_id!(entity_id.clone(), (Metadata)))
+            .peekable();
+        members.peek().is_some() && members.all(|metadata| metadata.locked)
+    }
+
+    /// Show or hide every member together
+    pub fn toggle_visible(group_id: &str) {
+        let Some(group) = Self::find(group_id) else {
+            return;
+        };
+        let visible = !Self::is_visible(&group);
+        for entity_id in &group.members {
+            query_by_id!(entity_id.clone(), (Metadata), |metadata| {
+                metadata.is_visible = visible;
+            });
+        }
+    }
+
+    /// Lock or unlock every member together
+    pub fn toggle_locked(group_id: &str) {
+        let Some(group) = Self::find(group_id) else {
+            return;
+        };
+        let locked = !Self::is_locked(&group);
+        for entity_id in &group.members {
+            query_by_id!(entity_id.clone(), (Metadata), |metadata| {
+                metadata.locked = locked;
+            });
+        }
+    }
+
+    fn find(group_id: &str) -> Option<OutlinerGroup> {
+        GROUPS.read().unwrap().iter().find(|group| group.id == group_id).cloned()
+    }
+
+    fn modify(group_id: &str, change: impl FnOnce(&mut OutlinerGroup)) {
+        if let Some(group) = GROUPS.write().unwrap().iter_mut().find(|group| group.id == group_id) {
+            change(group);
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::{ BTreeMap, BTreeSet };
+use std::sync::Mutex;
+use serde_json::Value;
+
+use crate::index::engine::components::Metadata;
+use crate::index::engine::modules::ecs::{
+    component_kind,
+    delete_entity,
+    entity_exists,
+    get_all_components,
+    get_persistent_entities,
+    restore_entity,
+    Component,
+    EntityId,
+};
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::modules::selection::Selection;
+use crate::get_query_by_id;
+
+/// Longest value a change row shows before cutting it short
+const VALUE_PREVIEW: usize = 40;
+
+/// Persistent entities (is_persist) and their components, by ID
+type WorldSnapshot = BTreeMap<EntityId, Vec<Component>>;
+
+/// Something that changed on a saved entity while playing
+pub struct PlayChange {
+    pub entity_id: EntityId,
+    pub title: String, // Metadata title, or the ID without Metadata
+    pub kind: PlayChangeKind,
+}
+
+pub enum PlayChangeKind {
+    /// A component changed, was added (`fields` says so) or was removed (`after` is None)
+    Changed { component: String, after: Option<Box<Component>>, fields: Vec<String> },
+    Spawned(Vec<Component>),
+    Despawned,
+}
+
+/// The authoring world as Play started
+static SNAPSHOT: Mutex<Option<WorldSnapshot>> = Mutex::new(None);
+/// What the last Play session changed, until applied or discarded
+static CHANGES: Mutex<Vec<PlayChange>> = Mutex::new(Vec::new());
+
+/// Play mode runs on a copy of the authoring world: Stop puts the saved entities back as they were at Play, and
+/// what play changed (a nudged crate, a despawned enemy) is listed in the Play Changes panel to cherry-pick back
+/// with `play_changes apply`. Runtime-only entities (is_persist = false) such as the player keep their state
+pub struct PlaySession;
+
+impl PlaySession {
+    /// Remember the authoring world as Play starts; changes left from the session before are dropped
+    pub fn begin() {
+        *SNAPSHOT.lock().unwrap() = Some(persistent_world());
+        CHANGES.lock().unwrap().clear();
+    }
+
+    /// Put the authoring world back as Play stops and keep what changed for `apply`; returns how many changes
+    pub fn end() -> usize {
+        let Some(before) = SNAPSHOT.lock().unwrap().take() else {
+            return 0;
+        };
+        let after = persistent_world();
+        let changes = diff(&before, &after);
+
+        for entity_id in after.keys().filter(|entity_id| !before.contains_key(*entity_id)) {
+            delete_entity(entity_id);
+            Selection::remove(entity_id);
+        }
+        for (entity_id, components) in before {
+            restore_entity(entity_id, components);
+        }
+        InterfaceSystem::update_entities_list();
+        InterfaceSystem::refresh_selection();
+
+        let count = changes.len();
+        *CHANGES.lock().unwrap() = changes;
+        if count > 0 {
+            println!("▶️ Play session changed {} things, see the Play Changes panel", count);
+            InterfaceSystem::open_plugin_panels();
+        }
+        count
+    }
+
+    /// Apply changes of the last session to the authoring world, by their number in the list (from 1); applied
+    /// changes leave the list. Locked entities are skipped
+    pub fn apply(numbers: &[usize]) -> Result<String, String> {
+        let mut changes = CHANGES.lock().unwrap();
+        if let Some(number) = numbers.iter().find(|number| **number == 0 || **number > changes.len()) {
+            return Err(format!("No change {} (there are {})", number, changes.len()));
+        }
+        let (mut applied, mut locked) = (0, 0);
+        let mut remaining = Vec::new();
+        for (index, change) in changes.drain(..).enumerate() {
+            if !numbers.contains(&(index + 1)) {
+                remaining.push(change);
+            } else if get_query_by_id!(change.entity_id.clone(), (Metadata)).is_some_and(|metadata| metadata.locked) {
+                locked += 1;
+                remaining.push(change);
+            } else {
+                apply_change(change);
+                applied += 1;
+            }
+        }
+        *changes = remaining;
+        drop(changes);
+        InterfaceSystem::update_entities_list();
+        InterfaceSystem::refresh_selection();
+
+        let mut summary = format!("Applied {} changes", applied);
+        if locked > 0 {
+            summary.push_str(&format!(", skipped {} on locked entities", locked));
+        }
+        Ok(summary)
+    }
+
+    /// Visit the components kept outside the world: the authoring world saved at Play and the changes to apply
+    pub fn for_each_component_mut(mut visit: impl FnMut(&mut Component)) {
+        if let Some(snapshot) = SNAPSHOT.lock().unwrap().as_mut() {
+            snapshot.values_mut().flatten().for_each(&mut visit);
+        }
+        for change in CHANGES.lock().unwrap().iter_mut() {
+            match &mut change.kind {
+                PlayChangeKind::Changed { after: Some(after), .. } => visit(after),
+                PlayChangeKind::Spawned(components) => components.iter_mut().for_each(&mut visit),
+                _ => {}
+            }
+        }
+    }
+
+    /// Rows of the Play Changes panel: each change by number, its entity and what changed. A row's number is what
+    /// its Apply button passes to `play_changes apply`, so there's no placeholder row without changes
+    pub fn panel_rows() -> Vec<(String, String)> {
+        CHANGES
+            .lock()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(index, change)| (format!("{}. {}", index + 1, change.title), describe(&change.kind)))
+            .collect()
+    }
+
+    /// Console: `play_changes` lists what the last Play session changed, `play_changes apply <numbers|all>` keeps
+    /// those changes and `play_changes discard` drops the rest
+    pub fn play_changes_command(args: &[&str]) -> Result<String, String> {
+        match args {
+            [] => {
+                let rows = Self::panel_rows();
+                if rows.is_empty() {
+                    return Ok("No changes from the last Play session".to_string());
+                }
+                let lines: Vec<String> = rows.iter().map(|(label, value)| format!("  {}: {}", label, value)).collect();
+                Ok(format!("Play changes:\n{}", lines.join("\n")))
+            }
+            ["apply", "all"] => {
+                let count = CHANGES.lock().unwrap().len();
+                Self::apply(&(1..=count).collect::<Vec<usize>>())
+            }
+            ["apply", numbers @ ..] if !numbers.is_empty() => {
+                let numbers = numbers
+                    .iter()
+                    .map(|number| number.parse::<usize>().map_err(|_| format!("Not a change number: {}", number)))
+                    .collect::<Result<Vec<usize>, String>>()?;
+                Self::apply(&numbers)
+            }
+            ["discard"] => {
+                let count = std::mem::take(&mut *CHANGES.lock().unwrap()).len();
+                Ok(format!("Discarded {} changes", count))
+            }
+            _ => Err("Usage: play_changes [apply <numbers|all> | discard]".to_string()),
+        }
+    }
+}
+
+/// The saved entities as they are now
+fn persistent_world() -> WorldSnapshot {
+    get_persistent_entities().into_iter().collect()
+}
+
+/// Component type to its JSON and itself
+fn components_by_kind(components: &[Component]) -> BTreeMap<String, (Value, &Component)> {
+    components
+        .iter()
+        .filter_map(|component| Some((component_kind(component), (serde_json::to_value(component).ok()?, component))))
+        .collect()
+}
+
+fn title_of(entity_id: &EntityId, components: &[Component]) -> String {
+    components
+        .iter()
+        .find_map(|component| match component {
+            Component::Metadata(metadata) => Some(metadata.title().to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|| entity_id.clone())
+}
+
+/// Every difference between the world at Play and at Stop, by entity then component
+fn diff(before: &WorldSnapshot, after: &WorldSnapshot) -> Vec<PlayChange> {
+    let mut changes = Vec::new();
+    for (entity_id, components) in after {
+        let title = title_of(entity_id, components);
+        let Some(original) = before.get(entity_id) else {
+            let kind = PlayChangeKind::Spawned(components.clone());
+            changes.push(PlayChange { entity_id: entity_id.clone(), title, kind });
+            continue;
+        };
+        let (original, current) = (components_by_kind(original), components_by_kind(components));
+        let kinds: BTreeSet<&String> = original.keys().chain(current.keys()).collect();
+        for component in kinds {
+            let (after, fields) = match (original.get(component), current.get(component)) {
+                (Some((json, _)), Some((changed, after))) if json != changed => {
+                    (Some(Box::new((*after).clone())), field_changes(json, changed))
+                }
+                (Some(_), None) => (None, vec!["removed".to_string()]),
+                (None, Some((_, after))) => (Some(Box::new((*after).clone())), vec!["added".to_string()]),
+                _ => continue,
+            };
+            let kind = PlayChangeKind::Changed { component: component.clone(), after, fields };
+            changes.push(PlayChange { entity_id: entity_id.clone(), title: title.clone(), kind });
+        }
+    }
+    for (entity_id, components) in before.iter().filter(|(entity_id, _)| !after.contains_key(*entity_id)) {
+        let title = title_of(entity_id, components);
+        changes.push(PlayChange { entity_id: entity_id.clone(), title, kind: PlayChangeKind::Despawned });
+    }
+    changes
+}
+
+/// `field: before → after` for each top-level field that differs
+fn field_changes(before: &Value, after: &Value) -> Vec<String> {
+    let (Value::Object(before), Value::Object(after)) = (before, after) else {
+        return vec![format!("{} → {}", preview(Some(before)), preview(Some(after)))];
+    };
+    let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .map(|key| format!("{}: {} → {}", key, preview(before.get(key)), preview(after.get(key))))
+        .collect()
+}
+
+fn preview(value: Option<&Value>) -> String {
+    let Some(value) = value else {
+        return "none".to_string();
+    };
+    let text = value.to_string();
+    if text.chars().count() <= VALUE_PREVIEW {
+        return text;
+    }
+    format!("{}…", text.chars().take(VALUE_PREVIEW).collect::<String>())
+}
+
+fn describe(kind: &PlayChangeKind) -> String {
+    match kind {
+        PlayChangeKind::Changed { component, fields, .. } => format!("{} {}", component, fields.join(", ")),
+        PlayChangeKind::Spawned(components) => format!("spawned with {} components", components.len()),
+        PlayChangeKind::Despawned => "despawned".to_string(),
+    }
+}
+
+/// Make the change to the authoring world
+fn apply_change(change: PlayChange) {
+    match change.kind {
+        PlayChangeKind::Changed { component, after, .. } => {
+            // Entities deleted in the editor since stay deleted
+            if !entity_exists(&change.entity_id) {
+                return;
+            }
+            let mut components = get_all_components(&change.entity_id);
+            components.retain(|current| component_kind(current) != component);
+            components.extend(after.map(|after| *after));
+            restore_entity(change.entity_id, components);
+        }
+        PlayChangeKind::Spawned(components) => restore_entity(change.entity_id, components),
+        PlayChangeKind::Despawned => {
+            delete_entity(&change.entity_id);
+            Selection::remove(&change.entity_id);
+        }
+    }
+}
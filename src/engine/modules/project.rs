@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use serde::{ Serialize, Deserialize };
+
+use crate::index::engine::components::{ layer_bit, EDITOR_ONLY_LAYERS };
+use crate::index::engine::managers::AssetPack;
+
+/// Default location of the project file, relative to the working directory
+pub const DEFAULT_PROJECT_PATH: &str = "project.toml";
+
+static PROJECT: OnceLock<Project> = OnceLock::new();
+
+/// Named render layer (Metadata.render_layer index)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LayerDefinition {
+    pub index: u32,
+    pub name: String,
+    #[serde(default)]
+    pub editor_only: bool, // Hidden from the Play camera and captures, like the built-in gizmo layers
+}
+
+/// How overlapping transparent surfaces are combined (`transparency` in project.toml)
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransparencyMode {
+    #[default]
+    Sorted,          // Blended back to front one surface at a time; exact, but intersecting surfaces pop
+    WeightedBlended, // Order-independent approximation, stable with many overlapping surfaces (glass, water)
+}
+
+/// Scenes, asset directories and layers of a game, loaded from `project.toml` at boot
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct Project {
+    pub name: String,
+    pub scenes: Vec<String>,            // Scene files shown by the Open Scene browser, in order
+    pub startup_scene: Option<String>,  // Opened when no scene is given on the command line
+    pub asset_directories: Vec<String>, // GLTF models in these are registered as assets on boot
+    pub layers: Vec<LayerDefinition>,
+    pub runtime: bool,                  // Exported builds: start in Play mode with the editor out of reach
+    pub pack: Option<String>,           // Asset pack read before the filesystem, so scenes and assets may live in it
+    pub transparency: TransparencyMode,
+    pub simplify: HashMap<String, f32>, // Triangle ratio asset models are decimated to on import, by file name or path
+}
+
+impl Project {
+    /// Load the project file (path may be overridden with `--project`); without one the engine keeps its defaults
+    pub fn load(args: &[String]) -> Self {
+        let path = args
+            .iter()
+            .position(|arg| arg == "--project")
+            .and_then(|index| args.get(index + 1))
+            .map_or(DEFAULT_PROJECT_PATH, |value| value.as_str());
+
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(_) => {
+                println!("⚙️ No project file at {}, using the default scene and built-in assets", path);
+                return Project::default();
+            }
+        };
+        match toml::from_str::<Project>(&contents) {
+            Ok(project) => {
+                println!(
+                    "⚙️ Loaded project '{}' from {} ({} scenes, {} asset directories, {} layers)",
+                    project.name,
+                    path,
+                    project.scenes.len(),
+                    project.asset_directories.len(),
+                    project.layers.len()
+                );
+                project.warn_about_layers();
+                project
+            }
+            Err(e) => {
+                eprintln!("❌ Failed to parse project file {}: {}", path, e);
+                Project::default()
+            }
+        }
+    }
+
+    /// Store the loaded project for the rest of the engine and mount its asset pack
+    pub fn initialize(project: Project) {
+        if let Some(pack_path) = &project.pack {
+            match AssetPack::open(pack_path) {
+                Ok(pack) => AssetPack::mount(pack),
+                Err(e) => eprintln!("❌ {}", e),
+            }
+        }
+        if PROJECT.set(project).is_err() {
+            panic!("Project should only be initialized once");
+        }
+    }
+
+    /// Loaded project (empty if the binary was started without one)
+    pub fn get() -> &'static Project {
+        PROJECT.get_or_init(Project::default)
+    }
+
+    /// Layers the Play camera and captures skip: the engine's editor layers plus project ones marked editor_only
+    pub fn editor_only_layers() -> u32 {
+        Self::get()
+            .layers
+            .iter()
+            .filter(|layer| layer.editor_only)
+            .fold(EDITOR_ONLY_LAYERS, |mask, layer| mask | layer_bit(layer.index))
+    }
+
+    /// Files in the asset directories (in the pack when one is mounted) ending in `extension`, for the asset
+    /// loaders plugins registered; sorted so imported asset ids stay the same between runs
+    pub fn files_with_extension(&self, extension: &str) -> Vec<String> {
+        let mut files: Vec<String> = self.asset_directories
+            .iter()
+            .flat_map(|directory| {
+                if let Some(packed) = AssetPack::list_mounted(directory) {
+                    return packed;
+                }
+                std::fs::read_dir(directory)
+                    .map_err(|e| eprintln!("⚠️ Asset directory {}: {}", directory, e))
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path().to_string_lossy().to_string())
+                    .collect()
+            })
+            .filter(|path| path.ends_with(extension))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Import simplification ratio of an asset model (`[simplify]` in project.toml, keyed by path or file name)
+    pub fn simplify_ratio(&self, path: &str) -> Option<f32> {
+        let file_name = std::path::Path::new(path).file_name()?.to_string_lossy();
+        self.simplify.get(path).or_else(|| self.simplify.get(file_name.as_ref())).copied()
+    }
+
+    /// Console: `layers` lists the project's render layers
+    pub fn layers_command(_args: &[&str]) -> Result<String, String> {
+        let layers = &Self::get().layers;
+        if layers.is_empty() {
+            return Ok("No layers defined in the project file".to_string());
+        }
+        let lines: Vec<String> = layers
+            .iter()
+            .map(|layer| format!("  {} {}{}", layer.index, layer.name, if layer.editor_only { " (editor only)" } else { "" }))
+            .collect();
+        Ok(format!("Layers:\n{}", lines.join("\n")))
+    }
+
+    fn warn_about_layers(&self) {
+        for (i, layer) in self.layers.iter().enumerate() {
+            if layer.index >= 32 {
+                eprintln!("⚠️ Layer '{}' uses index {}, past the 32 a culling mask holds", layer.name, layer.index);
+            }
+            if self.layers[..i].iter().any(|other| other.index == layer.index) {
+                eprintln!("⚠️ Layer index {} is defined more than once ('{}')", layer.index, layer.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::index::engine::modules::cli_args::CliArgs;
+
+/// World seed used when neither `--seed` nor the `seed` command sets one
+pub const DEFAULT_WORLD_SEED: u64 = 0x5eed_c4ab;
+
+/// World seed, once set by the `seed` command (otherwise `--seed` or the default)
+static WORLD_SEED: RwLock<Option<u64>> = RwLock::new(None);
+/// Per-system streams, created from the world seed on first use
+static STREAMS: RwLock<Option<HashMap<String, Rng>>> = RwLock::new(None);
+
+/// Small seedable generator (SplitMix64): the same seed always yields the same sequence on every platform
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1)
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in [min, max)
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        min + self.next_f32() * (max - min)
+    }
+}
+
+/// Randomness for gameplay: one world seed, with an independent stream per system (and per entity within a
+/// system) derived from it, so a replay or headless run with the same seed makes the same decisions
+pub struct Random;
+
+impl Random {
+    pub fn world_seed() -> u64 {
+        WORLD_SEED.read().unwrap().unwrap_or_else(|| CliArgs::get().seed.unwrap_or(DEFAULT_WORLD_SEED))
+    }
+
+    /// Change the world seed and restart every system stream from it
+    pub fn reseed(seed: u64) {
+        *WORLD_SEED.write().unwrap() = Some(seed);
+        Self::reset();
+    }
+
+    /// Restart every system stream from the world seed (entering Play mode, so each run starts the same)
+    pub fn reset() {
+        *STREAMS.write().unwrap() = None;
+    }
+
+    /// Draw from a system's shared stream, e.g. `Random::with_stream("particles", |rng| rng.next_f32())`
+    #[allow(dead_code)]
+    pub fn with_stream<T>(system: &str, f: impl FnOnce(&mut Rng) -> T) -> T {
+        let mut streams = STREAMS.write().unwrap();
+        let rng = streams
+            .get_or_insert_with(HashMap::new)
+            .entry(system.to_string())
+            .or_insert_with(|| Rng::new(Self::derive_seed(&[system])));
+        f(rng)
+    }
+
+    /// Stream owned by one entity of a system; it doesn't depend on how many other entities drew before it
+    pub fn entity_stream(system: &str, entity_id: &str) -> Rng {
+        Rng::new(Self::derive_seed(&[system, entity_id]))
+    }
+
+    /// FNV-1a of the world seed and the stream's names
+    fn derive_seed(names: &[&str]) -> u64 {
+        let hash = Self::world_seed()
+            .to_le_bytes()
+            .into_iter()
+            .chain(names.iter().flat_map(|name| name.bytes().chain([0])))
+            .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+        // One SplitMix step spreads nearby hashes apart
+        Rng::new(hash).next_u64()
+    }
+
+    /// Console: `seed [value]` shows or changes the world seed
+    pub fn seed_command(args: &[&str]) -> Result<String, String> {
+        let Some(value) = args.first() else {
+            return Ok(format!("World seed: {}", Self::world_seed()));
+        };
+        let seed = value.parse::<u64>().map_err(|_| format!("Invalid seed '{}'", value))?;
+        Self::reseed(seed);
+        Ok(format!("World seed set to {}; reload the scene to respawn with it", seed))
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::RwLock;
+use std::sync::atomic::{ AtomicBool, Ordering };
+
+use crate::index::engine::managers::AssetPack;
+use crate::index::engine::modules::ecs::{ clear_scene, deserialize_scene };
+use crate::index::engine::modules::editor_sidecar::EditorSidecar;
+use crate::index::engine::modules::{ CliArgs, EngineError, EngineResult, InterfaceSystem, Selection };
+use crate::index::engine::modules::scene_validation::{ SceneProblem, SceneValidation };
+
+/// Written when a windowed session starts and removed when it exits cleanly, relative to the working directory;
+/// finding it on startup means the last session crashed
+pub const SESSION_MARKER_PATH: &str = ".wet-crab-session";
+
+/// Whether the next scene load follows a crash (or `--safe-mode`)
+static AFTER_CRASH: AtomicBool = AtomicBool::new(false);
+
+/// A scene safe mode held back, waiting for the user to pick the entities to skip
+struct PendingScene {
+    path: String,
+    reason: String,
+    problems: Vec<SceneProblem>,
+    skipped: HashSet<String>, // Entity IDs left out of the load; unreadable ones always are
+}
+
+static PENDING: RwLock<Option<PendingScene>> = RwLock::new(None);
+
+/// Recovery from crashes and broken scenes: instead of loading a scene that fails validation, or the first scene
+/// after a crash, the editor starts with an empty scene and lists the problems, then loads the scene without the
+/// entities the user chose to skip
+pub struct SafeMode;
+
+impl SafeMode {
+    /// Mark the start of a windowed session; `forced` (`--safe-mode`) starts in safe mode as after a crash
+    pub fn begin_session(forced: bool) {
+        let crashed = Path::new(SESSION_MARKER_PATH).exists();
+        if crashed {
+            eprintln!("⚠️ The last session didn't exit cleanly, starting in safe mode");
+        }
+        AFTER_CRASH.store(crashed || forced, Ordering::Relaxed);
+        if let Err(e) = std::fs::write(SESSION_MARKER_PATH, std::process::id().to_string()) {
+            eprintln!("⚠️ Failed to write {} ({}), crashes of this session won't be detected", SESSION_MARKER_PATH, e);
+        }
+    }
+
+    /// Mark a clean exit
+    pub fn end_session() {
+        let _ = std::fs::remove_file(SESSION_MARKER_PATH);
+    }
+
+    /// Whether a scene is held back; saving would overwrite it with the empty scene
+    pub fn active() -> bool {
+        PENDING.read().unwrap().is_some()
+    }
+
+    /// Called by load_world! with the scene file it read. Returns true if safe mode took the load over, leaving
+    /// the world empty; headless runs have nobody to ask, so they load right away without the problem entities
+    pub fn intercept(path: &str, json: &str) -> bool {
+        let after_crash = AFTER_CRASH.swap(false, Ordering::Relaxed);
+        // Opening another scene leaves safe mode
+        let was_active = PENDING.write().unwrap().take().is_some();
+        // A file that isn't a scene at all fails the normal load, which reports it
+        let problems = SceneValidation::validate(json).unwrap_or_default();
+        if problems.is_empty() && !after_crash {
+            if was_active {
+                InterfaceSystem::show_safe_mode();
+            }
+            return false;
+        }
+
+        for problem in &problems {
+            eprintln!("⚠️ {} ({}): {}", problem.title, problem.entity_id, problem.message);
+        }
+        let reason = match (after_crash, problems.len()) {
+            (true, 0) => format!("The last session didn't exit cleanly, so {} wasn't loaded", path),
+            (true, count) => format!("The last session didn't exit cleanly and {} has {} broken entities", path, count),
+            (false, count) => format!("{} has {} broken entities", path, count),
+        };
+        let skipped = problems.iter().map(|problem| problem.entity_id.clone()).collect();
+        *PENDING.write().unwrap() = Some(PendingScene { path: path.to_string(), reason, problems, skipped });
+
+        if CliArgs::get().headless {
+            if let Err(e) = Self::load() {
+                InterfaceSystem::report_error(&e);
+            }
+            return true;
+        }
+        println!("🛟 Safe mode: started with an empty scene instead of {}", path);
+        Selection::clear();
+        clear_scene();
+        InterfaceSystem::update_entities_list();
+        InterfaceSystem::show_safe_mode();
+        true
+    }
+
+    /// Why safe mode is on and the held-back scene's problems with whether each entity is skipped
+    pub fn pending() -> Option<(String, Vec<(SceneProblem, bool)>)> {
+        let pending = PENDING.read().unwrap();
+        let pending = pending.as_ref()?;
+        let problems = pending.problems
+            .iter()
+            .map(|problem| (problem.clone(), pending.skipped.contains(&problem.entity_id)))
+            .collect();
+        Some((pending.reason.clone(), problems))
+    }
+
+    /// Skip an entity in the load or load it after all; unreadable entities stay skipped
+    pub fn toggle_skip(entity_id: &str) {
+        let mut pending = PENDING.write().unwrap();
+        let Some(pending) = pending.as_mut() else {
+            return;
+        };
+        let unreadable = pending.problems
+            .iter()
+            .any(|problem| problem.entity_id == entity_id && problem.unreadable);
+        if !unreadable && !pending.skipped.remove(entity_id) {
+            pending.skipped.insert(entity_id.to_string());
+        }
+    }
+
+    /// Load the held-back scene without the skipped entities and leave safe mode
+    pub fn load() -> EngineResult<()> {
+        let Some(pending) = PENDING.write().unwrap().take() else {
+            return Ok(());
+        };
+        let json = AssetPack::read_text(&pending.path)?;
+        deserialize_scene(&json, &pending.skipped).map_err(|e| {
+            EngineError::scene(&pending.path, format!("Failed to deserialize world: {}", e))
+        })?;
+        println!("📂 Loaded world from {} without {} skipped entities", pending.path, pending.skipped.len());
+        if let Err(e) = EditorSidecar::load(&pending.path) {
+            InterfaceSystem::report_error(&e);
+        }
+        InterfaceSystem::update_entities_list();
+        InterfaceSystem::show_safe_mode();
+        Ok(())
+    }
+
+    /// Leave safe mode keeping the empty scene; saving then replaces the held-back scene
+    pub fn discard() {
+        if PENDING.write().unwrap().take().is_some() {
+            println!("🛟 Left safe mode with an empty scene");
+        }
+        InterfaceSystem::show_safe_mode();
+    }
+}
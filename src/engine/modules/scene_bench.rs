@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use std::time::{ Duration, Instant };
+use serde::Serialize;
+
+use crate::index::engine::modules::diagnostics::Diagnostics;
+use crate::index::engine::modules::ecs::get_all_entities;
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+
+/// Frames `--bench` runs when not given `--frames`
+pub const DEFAULT_BENCH_FRAMES: u64 = 1000;
+/// Frames left out of the statistics at the start, while shaders compile and imports finish
+const WARMUP_FRAMES: u64 = 10;
+
+/// Statistics of one series of timings, in milliseconds
+#[derive(Serialize)]
+struct Stats {
+    samples: usize,
+    mean: f64,
+    min: f64,
+    median: f64,
+    p95: f64,
+    p99: f64,
+    max: f64,
+    total: f64,
+}
+
+impl Stats {
+    fn of(samples: &[f64]) -> Self {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            sorted[((sorted.len() - 1) as f64 * p).round() as usize]
+        };
+        let total: f64 = sorted.iter().sum();
+        Self {
+            samples: sorted.len(),
+            mean: total / sorted.len().max(1) as f64,
+            min: sorted.first().copied().unwrap_or_default(),
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            p99: percentile(0.99),
+            max: sorted.last().copied().unwrap_or_default(),
+            total,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SystemReport {
+    kind: &'static str, // "fixed" or "render"
+    name: String,
+    #[serde(flatten)]
+    stats: Stats, // Time per frame it ran in
+}
+
+#[derive(Serialize)]
+struct Report {
+    scene: String,
+    mode: &'static str, // "headless" (fixed ticks only) or "windowed"
+    frames: u64,
+    warmup_frames: u64,
+    entities: usize,
+    gl: Option<String>,
+    frame: Stats,
+    systems: Vec<SystemReport>, // Slowest mean first
+}
+
+struct BenchRun {
+    scene: String,
+    report_path: String,
+    headless: bool,
+    frames: u64,
+    frame: u64,
+    frame_start: Option<Instant>,
+    frame_times: Vec<f64>,
+    current: BTreeMap<(&'static str, String), Duration>, // Per system, this frame so far
+    systems: BTreeMap<(&'static str, String), Vec<f64>>,
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static BENCH: Mutex<Option<BenchRun>> = Mutex::new(None);
+
+/// Performance regression harness (`--bench scene.json [--frames N] [--bench-report report.json|.csv]`): runs a
+/// scene for a number of frames, timing every frame and every fixed and render system the App runs, and writes the
+/// statistics as JSON or CSV to compare runs before and after a change. With `--headless` a frame is one fixed tick
+/// and no GL is involved; otherwise the window renders as usual (pass `--no-vsync` so the display doesn't cap the
+/// frame rate)
+pub struct SceneBench;
+
+impl SceneBench {
+    /// Start timing; the run ends after `frames` frames past the warmup
+    pub fn start(scene: &str, frames: u64, report_path: &str, headless: bool) {
+        *BENCH.lock().unwrap() = Some(BenchRun {
+            scene: scene.to_string(),
+            report_path: report_path.to_string(),
+            headless,
+            frames,
+            frame: 0,
+            frame_start: None,
+            frame_times: Vec::new(),
+            current: BTreeMap::new(),
+            systems: BTreeMap::new(),
+        });
+        RUNNING.store(true, Ordering::Relaxed);
+        println!("⏱️ Benchmarking {} for {} frames (after {} warmup frames)", scene, frames, WARMUP_FRAMES);
+    }
+
+    pub fn is_running() -> bool {
+        RUNNING.load(Ordering::Relaxed)
+    }
+
+    /// Run a system, adding its time to the frame when a benchmark is running
+    pub fn time(kind: &'static str, name: &str, system: impl FnOnce()) {
+        if !Self::is_running() {
+            system();
+            return;
+        }
+        let start = Instant::now();
+        system();
+        let elapsed = start.elapsed();
+        if let Some(bench) = BENCH.lock().unwrap().as_mut() {
+            *bench.current.entry((kind, name.to_string())).or_default() += elapsed;
+        }
+    }
+
+    /// Close the frame since the last call; call once per frame at the same point. Returns true once the run has
+    /// all its frames, after writing the report, or the error writing it
+    pub fn frame() -> EngineResult<bool> {
+        let mut guard = BENCH.lock().unwrap();
+        let Some(bench) = guard.as_mut() else {
+            return Ok(false);
+        };
+        let now = Instant::now();
+        let current = std::mem::take(&mut bench.current);
+        if let Some(frame_start) = bench.frame_start.replace(now) {
+            bench.frame += 1;
+            if bench.frame > WARMUP_FRAMES {
+                bench.frame_times.push(milliseconds(now - frame_start));
+                for (system, elapsed) in current {
+                    bench.systems.entry(system).or_default().push(milliseconds(elapsed));
+                }
+            }
+        }
+        if bench.frame < WARMUP_FRAMES + bench.frames {
+            return Ok(false);
+        }
+
+        let bench = guard.take().unwrap();
+        RUNNING.store(false, Ordering::Relaxed);
+        drop(guard);
+        println!("⏱️ {}", Self::write_report(&bench)?);
+        Ok(true)
+    }
+
+    fn write_report(bench: &BenchRun) -> EngineResult<String> {
+        let mut systems: Vec<SystemReport> = bench.systems
+            .iter()
+            .map(|((kind, name), samples)| SystemReport { kind, name: name.clone(), stats: Stats::of(samples) })
+            .collect();
+        systems.sort_by(|a, b| b.stats.mean.total_cmp(&a.stats.mean));
+        let report = Report {
+            scene: bench.scene.clone(),
+            mode: if bench.headless { "headless" } else { "windowed" },
+            frames: bench.frame_times.len() as u64,
+            warmup_frames: WARMUP_FRAMES,
+            entities: get_all_entities().len(),
+            gl: Diagnostics::gl_info().map(str::to_string),
+            frame: Stats::of(&bench.frame_times),
+            systems,
+        };
+
+        let path = &bench.report_path;
+        let contents = if path.ends_with(".csv") {
+            csv(&report)
+        } else {
+            serde_json::to_string_pretty(&report).map_err(|e| EngineError::io(path, e.to_string()))?
+        };
+        std::fs::write(path, contents).map_err(|e| EngineError::io(path, e.to_string()))?;
+        Ok(format!(
+            "{} frames of {}: mean {:.3} ms, p95 {:.3} ms, p99 {:.3} ms; report written to {}",
+            report.frames,
+            report.scene,
+            report.frame.mean,
+            report.frame.p95,
+            report.frame.p99,
+            path
+        ))
+    }
+}
+
+/// Report path when `--bench-report` isn't given: next to the scene, as `<scene>.bench.json`
+pub fn default_report_path(scene: &str) -> String {
+    format!("{}.bench.json", scene.strip_suffix(".json").unwrap_or(scene))
+}
+
+fn milliseconds(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// One row per series: the whole frame first, then each system
+fn csv(report: &Report) -> String {
+    let mut lines = vec!["kind,name,samples,mean_ms,min_ms,median_ms,p95_ms,p99_ms,max_ms,total_ms".to_string()];
+    let rows = std::iter::once(("frame", "frame", &report.frame))
+        .chain(report.systems.iter().map(|system| (system.kind, system.name.as_str(), &system.stats)));
+    for (kind, name, stats) in rows {
+        lines.push(format!(
+            "{},{},{},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4},{:.4}",
+            kind,
+            name,
+            stats.samples,
+            stats.mean,
+            stats.min,
+            stats.median,
+            stats.p95,
+            stats.p99,
+            stats.max,
+            stats.total
+        ));
+    }
+    lines.join("\n") + "\n"
+}
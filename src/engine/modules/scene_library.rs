@@ -0,0 +1,371 @@
+use std::collections::BTreeMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{ Hash, Hasher };
+use std::path::{ Path, PathBuf };
+use std::sync::{ Mutex, RwLock };
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::atomic::{ AtomicBool, Ordering };
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::mpsc::{ self, Receiver };
+use std::time::{ Duration, Instant, SystemTime, UNIX_EPOCH };
+use serde::{ Serialize, Deserialize };
+#[cfg(not(target_arch = "wasm32"))]
+use notify::{ EventKind, RecursiveMode, Watcher };
+#[cfg(not(target_arch = "wasm32"))]
+use notify::event::{ AccessKind, AccessMode, ModifyKind };
+
+use crate::index::engine::managers::AssetPack;
+use crate::index::engine::modules::cli_args::CliArgs;
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::modules::project::Project;
+
+/// Thumbnail size rendered from the editor camera on save
+pub const THUMBNAIL_SIZE: [u32; 2] = [256, 144];
+/// How often `hot_reload` looks at the open scene file for changes
+const HOT_RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The open scene file as `hot_reload` last saw it
+struct WatchedScene {
+    path: String,
+    modified: Option<SystemTime>,
+    writing: Option<SystemTime>, // Modification time seen by the last poll, read once a poll sees it again
+    saved: Option<u64>, // Hash of what the editor last saved there, so its own saves aren't reloaded
+}
+
+/// Scene opened in the editor, once it differs from the startup scene
+static CURRENT_SCENE: RwLock<Option<String>> = RwLock::new(None);
+/// Thumbnail path waiting for the next rendered frame
+static THUMBNAIL_REQUEST: RwLock<Option<String>> = RwLock::new(None);
+static WATCHED_SCENE: Mutex<Option<WatchedScene>> = Mutex::new(None);
+static LAST_HOT_RELOAD: Mutex<Option<Instant>> = Mutex::new(None);
+/// Latest save of each scene path; a background write overtaken by a newer save of its path is dropped
+static SAVE_GENERATIONS: Mutex<BTreeMap<String, u64>> = Mutex::new(BTreeMap::new());
+/// Held while a scene file is written, so saves are written one at a time in the order they were made
+static SCENE_WRITE: Mutex<()> = Mutex::new(());
+
+/// Describes a scene without loading it; written at the top of the scene file on save
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SceneHeader {
+    pub title: String,
+    pub author: String,
+    pub saved_at: u64, // Unix seconds
+    pub entity_count: usize,
+    pub thumbnail: Option<String>, // PNG file name, next to the scene
+}
+
+impl SceneHeader {
+    /// Header for saving the current world to `scene_path` (entity_count is filled in by serialize_scene)
+    pub fn for_save(scene_path: &str) -> Self {
+        let title = Path::new(scene_path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let author = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_else(|_| "unknown".to_string());
+        let saved_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|elapsed| elapsed.as_secs()).unwrap_or_default();
+        let thumbnail = Path::new(&SceneLibrary::thumbnail_path(scene_path))
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        Self { title, author, saved_at, entity_count: 0, thumbnail }
+    }
+
+    /// Read just the header of a scene file; scenes saved before headers existed have none
+    pub fn read(scene_path: &str) -> Option<SceneHeader> {
+        #[derive(Deserialize)]
+        struct HeaderOnly {
+            header: Option<SceneHeader>,
+        }
+        let json = AssetPack::read_text(scene_path).ok()?;
+        serde_json::from_str::<HeaderOnly>(&json).ok()?.header
+    }
+
+    /// Save time as `YYYY-MM-DD HH:MM` UTC
+    pub fn date_label(&self) -> String {
+        let days = (self.saved_at / 86_400) as i64;
+        let minutes = (self.saved_at % 86_400) / 60;
+        // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z - era * 146_097;
+        let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = year_of_era + era * 400 + (month <= 2) as i64;
+        format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, minutes / 60, minutes % 60)
+    }
+}
+
+/// Scenes the editor can open, and which one is open
+pub struct SceneLibrary;
+
+impl SceneLibrary {
+    /// Scene the editor saves to: the last one opened, or the startup scene
+    pub fn current() -> String {
+        CURRENT_SCENE.read().unwrap().clone().unwrap_or_else(|| CliArgs::get().scene_path().to_string())
+    }
+
+    pub fn set_current(scene_path: &str) {
+        *CURRENT_SCENE.write().unwrap() = Some(scene_path.to_string());
+    }
+
+    /// The project's scenes, or without a project the scene files in the open scene's directory, with their headers
+    pub fn scenes() -> Vec<(String, Option<SceneHeader>)> {
+        let project_scenes = &Project::get().scenes;
+        if !project_scenes.is_empty() {
+            return project_scenes.iter().map(|path| (path.clone(), SceneHeader::read(path))).collect();
+        }
+
+        let current = Self::current();
+        let Ok(entries) = std::fs::read_dir(Self::directory(&current)) else {
+            return Vec::new();
+        };
+        let mut paths: Vec<String> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path().to_string_lossy().to_string())
+            .filter(|path| Self::is_scene_file(path))
+            .collect();
+        paths.sort();
+        paths.into_iter().map(|path| {
+            let header = SceneHeader::read(&path);
+            (path, header)
+        }).collect()
+    }
+
+    /// Directory a scene file is in, listed as the other scenes to open
+    fn directory(scene_path: &str) -> &Path {
+        Path::new(scene_path).parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."))
+    }
+
+    /// `.json` files that aren't a scene's editor sidecar or collision file
+    fn is_scene_file(path: &str) -> bool {
+        path.ends_with(".json") && !path.ends_with(".editor.json") && !path.ends_with(".colliders.json")
+    }
+
+    /// Thumbnail path for a scene, e.g. `scene.json` -> `scene.thumb.png`
+    pub fn thumbnail_path(scene_path: &str) -> String {
+        Path::new(scene_path).with_extension("thumb.png").to_string_lossy().to_string()
+    }
+
+    /// Ask the renderer to capture the scene's thumbnail on the next frame
+    pub fn request_thumbnail(scene_path: &str) {
+        *THUMBNAIL_REQUEST.write().unwrap() = Some(Self::thumbnail_path(scene_path));
+    }
+
+    /// Pending thumbnail path, cleared once taken
+    pub fn take_thumbnail_request() -> Option<String> {
+        THUMBNAIL_REQUEST.write().unwrap().take()
+    }
+
+    /// Render system: reload the open scene when its file changes on disk (edited by hand, by a script or pulled
+    /// from version control). The player and its camera aren't saved with the scene, so they stay as they are;
+    /// selected entities stay selected if the file still has them. On desktop a notify watcher follows the open
+    /// scene's directory, which also refreshes the Open Scene list when any scene file there changes; a file is
+    /// only read once completely written (see SceneWatcher). Without a watcher (wasm, or one that couldn't be
+    /// created) the open scene's modification time is polled every HOT_RELOAD_INTERVAL instead, and read once it
+    /// stopped changing. Packed builds have no file to watch. Nothing reloads while playing, since Stop puts the
+    /// pre-Play world back (see PlaySession); a change made meanwhile is picked up after Stop
+    pub fn hot_reload(_gl: &glow::Context, _width: u32, _height: u32) {
+        if InterfaceSystem::is_play_mode() {
+            return;
+        }
+        let path = Self::current();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(changed) = SceneWatcher::changed_files(&path) {
+            // Start watching a newly opened scene before anything changes, so its first save is known as ours
+            Self::watch(&path);
+            if changed.is_empty() {
+                return;
+            }
+            InterfaceSystem::refresh_scenes();
+            if changed.iter().any(|changed| changed.file_name() == Path::new(&path).file_name()) {
+                let contents = std::fs::read_to_string(&path).ok();
+                if contents.is_some_and(|contents| Self::watch(&path) != Some(Self::contents_hash(&contents))) {
+                    Self::reload(&path);
+                }
+            }
+            return;
+        }
+
+        {
+            let mut last = LAST_HOT_RELOAD.lock().unwrap();
+            if last.is_some_and(|last| last.elapsed() < HOT_RELOAD_INTERVAL) {
+                return;
+            }
+            *last = Some(Instant::now());
+        }
+        Self::watch(&path);
+        let modified = Self::modified(&path);
+        let changed = {
+            let mut watched = WATCHED_SCENE.lock().unwrap();
+            match watched.as_mut() {
+                Some(watched) if modified.is_some() && modified != watched.modified => {
+                    if modified != watched.writing {
+                        // Still being written, or just finished: read it once the next poll finds it unchanged
+                        watched.writing = modified;
+                        false
+                    } else {
+                        watched.modified = modified;
+                        let contents = std::fs::read_to_string(&path).ok();
+                        contents.is_some_and(|contents| Some(Self::contents_hash(&contents)) != watched.saved)
+                    }
+                }
+                _ => false,
+            }
+        };
+        if changed {
+            Self::reload(&path);
+        }
+    }
+
+    fn reload(path: &str) {
+        println!("🔄 {} changed on disk, reloading", path);
+        crate::load_world!(path);
+        InterfaceSystem::refresh_selection();
+    }
+
+    /// Hash of what the editor last saved to the open scene at `path`; when another scene was opened, starts
+    /// watching it from its current state
+    fn watch(path: &str) -> Option<u64> {
+        let mut watched = WATCHED_SCENE.lock().unwrap();
+        match watched.as_ref() {
+            Some(watched) if watched.path == path => watched.saved,
+            _ => {
+                let modified = Self::modified(path);
+                *watched = Some(WatchedScene { path: path.to_string(), modified, writing: modified, saved: None });
+                None
+            }
+        }
+    }
+
+    fn modified(path: &str) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+    }
+
+    /// Called by save_world! with what it's about to write, so the watcher knows the change is the editor's own;
+    /// returns the save's generation for `write_save`
+    pub fn expect_save(scene_path: &str, json: &str) -> u64 {
+        if let Some(watched) = WATCHED_SCENE.lock().unwrap().as_mut().filter(|watched| watched.path == scene_path) {
+            watched.saved = Some(Self::contents_hash(json));
+        }
+        let mut generations = SAVE_GENERATIONS.lock().unwrap();
+        let generation = generations.entry(scene_path.to_string()).or_default();
+        *generation += 1;
+        *generation
+    }
+
+    /// Write what save_world! serialized, on its background task: into a temp file next to the scene, renamed
+    /// over it, so the scene is never read half written. Ok(false) without writing if a newer save of the path was
+    /// made meanwhile, which writes it instead; an older save never lands last
+    pub fn write_save(scene_path: &str, json: &str, generation: u64) -> EngineResult<bool> {
+        let _writing = SCENE_WRITE.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if SAVE_GENERATIONS.lock().unwrap().get(scene_path) != Some(&generation) {
+            return Ok(false);
+        }
+        let temp_path = format!("{}.tmp", scene_path);
+        std::fs::write(&temp_path, json).map_err(|e| EngineError::io(&temp_path, e.to_string()))?;
+        std::fs::rename(&temp_path, scene_path).map_err(|e| {
+            let _ = std::fs::remove_file(&temp_path);
+            EngineError::io(scene_path, e.to_string())
+        })?;
+        Ok(true)
+    }
+
+    fn contents_hash(contents: &str) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        contents.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Notify watcher on the open scene's directory. Only finished files are reported: a save renamed over a scene
+/// (as save_world! and most editors do) or a file closed after writing counts at once; other writes count once
+/// the file got no events for SETTLE_TIME, so a scene copied in slowly isn't read half way
+#[cfg(not(target_arch = "wasm32"))]
+struct SceneWatcher {
+    directory: PathBuf,
+    _watcher: notify::RecommendedWatcher,
+    events: Receiver<notify::Result<notify::Event>>,
+    settling: BTreeMap<PathBuf, Instant>, // Written but not closed or renamed yet, by time of the latest event
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+const SETTLE_TIME: Duration = Duration::from_millis(500);
+
+#[cfg(not(target_arch = "wasm32"))]
+static SCENE_WATCHER: Mutex<Option<SceneWatcher>> = Mutex::new(None);
+/// Set once no watcher could be created; hot_reload polls the open scene from then on
+#[cfg(not(target_arch = "wasm32"))]
+static WATCH_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SceneWatcher {
+    fn new(directory: PathBuf) -> notify::Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)?;
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+        Ok(Self { directory, _watcher: watcher, events, settling: BTreeMap::new() })
+    }
+
+    /// Scene files in the directory of `scene_path` finished changing (or removed) since the last call; None
+    /// without a watcher, for hot_reload to poll instead
+    fn changed_files(scene_path: &str) -> Option<Vec<PathBuf>> {
+        if WATCH_UNAVAILABLE.load(Ordering::Relaxed) {
+            return None;
+        }
+        let directory = SceneLibrary::directory(scene_path);
+        let mut watcher = SCENE_WATCHER.lock().unwrap();
+        if watcher.as_ref().is_none_or(|watcher| watcher.directory != directory) {
+            match Self::new(directory.to_path_buf()) {
+                Ok(new_watcher) => *watcher = Some(new_watcher),
+                Err(e) => {
+                    eprintln!("⚠️ Can't watch {} for scene changes, polling instead: {}", directory.display(), e);
+                    WATCH_UNAVAILABLE.store(true, Ordering::Relaxed);
+                    *watcher = None;
+                    return None;
+                }
+            }
+        }
+        watcher.as_mut().map(Self::changed)
+    }
+
+    fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            match event.kind {
+                // Renamed into place (or away), closed after writing, or deleted: done
+                EventKind::Modify(ModifyKind::Name(_))
+                | EventKind::Access(AccessKind::Close(AccessMode::Write))
+                | EventKind::Remove(_) => changed.extend(event.paths),
+                EventKind::Create(_) | EventKind::Modify(_) => {
+                    for path in event.paths {
+                        self.settling.insert(path, Instant::now());
+                    }
+                }
+                _ => {}
+            }
+        }
+        for path in &changed {
+            self.settling.remove(path);
+        }
+        let settled: Vec<PathBuf> = self.settling
+            .iter()
+            .filter(|(_, written)| written.elapsed() >= SETTLE_TIME)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in settled {
+            self.settling.remove(&path);
+            changed.push(path);
+        }
+        changed.retain(|path| SceneLibrary::is_scene_file(&path.to_string_lossy()));
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
@@ -0,0 +1,97 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::index::engine::managers::assets_manager::{ is_known_asset, Assets };
+use crate::index::engine::managers::MaterialLibrary;
+use crate::index::engine::modules::App;
+use crate::index::engine::modules::ecs::{ scene_entities_json, Component };
+
+/// Something wrong with one saved entity of a scene file
+#[derive(Clone, Debug)]
+pub struct SceneProblem {
+    pub entity_id: String,
+    pub title: String,    // Metadata title, or the entity ID when it has none
+    pub message: String,
+    pub unreadable: bool, // A component doesn't parse, so loading the entity would fail the whole scene
+}
+
+/// Checks a scene file entity by entity before it is loaded, without touching the world: components that don't
+/// parse, transforms with NaN or infinite values, and assets, materials or plugin components that aren't loaded
+pub struct SceneValidation;
+
+impl SceneValidation {
+    /// Problems of every entity that has any; an error only if the file isn't a scene at all
+    pub fn validate(json: &str) -> Result<Vec<SceneProblem>, serde_json::Error> {
+        let mut problems = Vec::new();
+        for (entity_id, components) in scene_entities_json(json)? {
+            let Value::Array(components) = components else {
+                let message = "Components aren't a list".to_string();
+                problems.push(SceneProblem { title: entity_id.clone(), entity_id, message, unreadable: true });
+                continue;
+            };
+            let title = components
+                .iter()
+                .find(|component| component.get("type").and_then(Value::as_str) == Some("Metadata"))
+                .and_then(|metadata| metadata.get("title").and_then(Value::as_str))
+                .unwrap_or(&entity_id)
+                .to_string();
+
+            let (messages, unreadable) = Self::check_entity(&components);
+            if !messages.is_empty() {
+                problems.push(SceneProblem { entity_id, title, message: messages.join("; "), unreadable });
+            }
+        }
+        Ok(problems)
+    }
+
+    /// What is wrong with an entity's components, and whether any of them can't be loaded
+    fn check_entity(components: &[Value]) -> (Vec<String>, bool) {
+        let mut messages = Vec::new();
+        let mut unreadable = false;
+        for component in components {
+            let kind = component.get("type").and_then(Value::as_str).unwrap_or("untyped");
+            match kind {
+                "Transform" if !finite_fields(component) => {
+                    messages.push("Transform has NaN or infinite values".to_string());
+                    unreadable = true;
+                }
+                // Deserializing these copies the asset and reports missing ones, so only the asset is read here
+                "StaticObject3D" | "AnimatedObject3D" => {
+                    match component.get("asset_type").map(Assets::deserialize) {
+                        Some(Ok(asset)) if is_known_asset(asset) => {}
+                        Some(Ok(asset)) => messages.push(format!("Missing asset {:?}", asset)),
+                        _ => {
+                            messages.push(format!("{} has no valid asset_type", kind));
+                            unreadable = true;
+                        }
+                    }
+                }
+                _ => match serde_json::from_value::<Component>(component.clone()) {
+                    Ok(Component::CustomMaterial(material)) if MaterialLibrary::get(&material.definition).is_none() => {
+                        messages.push(format!("Missing material {}", material.definition));
+                    }
+                    Ok(Component::Plugin(plugin)) if App::component_default(&plugin.kind).is_none() => {
+                        messages.push(format!("No plugin registered the {} component", plugin.kind));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        messages.push(format!("Invalid {} component: {}", kind, e));
+                        unreadable = true;
+                    }
+                },
+            }
+        }
+        (messages, unreadable)
+    }
+}
+
+/// Whether every number of a component fits an f32; scene files store NaN and infinity as null
+fn finite_fields(component: &Value) -> bool {
+    component.as_object().is_some_and(|fields| {
+        fields.values().all(|value| match value {
+            Value::Null => false,
+            Value::Number(number) => number.as_f64().is_some_and(|number| number.abs() <= f32::MAX as f64),
+            _ => true,
+        })
+    })
+}
@@ -0,0 +1,57 @@
+use std::sync::RwLock;
+
+use crate::index::engine::modules::ecs::entity_exists;
+use crate::index::engine::modules::EntityId;
+
+/// Every selected entity; the inspector shows the primary one (InterfaceState.selected-index)
+static SELECTED: RwLock<Vec<EntityId>> = RwLock::new(Vec::new());
+/// Outliner group whose members make up the selection, until the selection changes otherwise
+static GROUP: RwLock<Option<String>> = RwLock::new(None);
+
+/// Editor multi-selection, in selection order
+pub struct Selection;
+
+impl Selection {
+    /// Selected entities that still exist
+    pub fn ids() -> Vec<EntityId> {
+        SELECTED.read().unwrap().iter().filter(|entity_id| entity_exists(entity_id)).cloned().collect()
+    }
+
+    pub fn contains(entity_id: &str) -> bool {
+        SELECTED.read().unwrap().iter().any(|selected| selected == entity_id)
+    }
+
+    pub fn replace(entity_ids: Vec<EntityId>) {
+        *SELECTED.write().unwrap() = entity_ids;
+        *GROUP.write().unwrap() = None;
+    }
+
+    /// Add entities that aren't selected yet, keeping the existing order
+    pub fn extend(entity_ids: &[EntityId]) {
+        *GROUP.write().unwrap() = None;
+        let mut selected = SELECTED.write().unwrap();
+        for entity_id in entity_ids {
+            if !selected.contains(entity_id) {
+                selected.push(entity_id.clone());
+            }
+        }
+    }
+
+    pub fn remove(entity_id: &str) {
+        SELECTED.write().unwrap().retain(|selected| selected != entity_id);
+    }
+
+    pub fn clear() {
+        SELECTED.write().unwrap().clear();
+        *GROUP.write().unwrap() = None;
+    }
+
+    pub fn group() -> Option<String> {
+        GROUP.read().unwrap().clone()
+    }
+
+    /// Mark the current selection as an outliner group's members
+    pub fn set_group(group_id: &str) {
+        *GROUP.write().unwrap() = Some(group_id.to_string());
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Mutex;
+
+use crate::index::engine::modules::InterfaceSystem;
+
+/// Source lines shown on each side of the one a compile log points at
+const EXCERPT_CONTEXT: usize = 2;
+
+/// A program's latest failed compile or link
+#[derive(Debug, Clone)]
+pub struct ShaderFailure {
+    pub program: String,
+    pub stage: &'static str, // Vertex, Fragment, Compute or Link
+    pub log: String,
+    pub line: Option<usize>,            // 1-based line of the compiled source the log blames first
+    pub excerpt: Vec<(usize, String)>, // Numbered source lines around `line`
+}
+
+static FAILURES: Mutex<Vec<ShaderFailure>> = Mutex::new(Vec::new());
+
+/// Shader compile failures kept for the viewport overlay until their program compiles again; callers keep drawing
+/// with the last good program (or the placeholder) meanwhile
+pub struct ShaderDiagnostics;
+
+impl ShaderDiagnostics {
+    /// Record a failed stage of `program`, replacing its previous failure; `source` is what the driver compiled,
+    /// so the log's line numbers index it
+    pub fn record(program: &str, stage: &'static str, source: &str, log: &str) {
+        let line = Self::error_line(log);
+        let excerpt = line
+            .map(|line| {
+                source
+                    .lines()
+                    .enumerate()
+                    .map(|(index, text)| (index + 1, text.to_string()))
+                    .skip(line.saturating_sub(EXCERPT_CONTEXT + 1))
+                    .take(EXCERPT_CONTEXT * 2 + 1)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let failure = ShaderFailure { program: program.to_string(), stage, log: log.trim().to_string(), line, excerpt };
+        {
+            let mut failures = FAILURES.lock().unwrap();
+            failures.retain(|failure| failure.program != program);
+            failures.push(failure);
+        }
+        InterfaceSystem::show_shader_errors();
+    }
+
+    /// `program` compiled and linked, so its earlier failure no longer applies
+    pub fn resolve(program: &str) {
+        let removed = {
+            let mut failures = FAILURES.lock().unwrap();
+            let count = failures.len();
+            failures.retain(|failure| failure.program != program);
+            failures.len() != count
+        };
+        if removed {
+            InterfaceSystem::show_shader_errors();
+        }
+    }
+
+    pub fn failures() -> Vec<ShaderFailure> {
+        FAILURES.lock().unwrap().clone()
+    }
+
+    /// First line number in a driver log: Mesa writes `0:12(5): error`, NVIDIA `0(12) : error` and ANGLE
+    /// `ERROR: 0:12: ...`, the source string index coming first in each
+    fn error_line(log: &str) -> Option<usize> {
+        log.lines().find_map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix("ERROR:").unwrap_or(line).trim_start();
+            let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+            if rest.len() == line.len() {
+                return None;
+            }
+            let rest = rest.strip_prefix(':').or_else(|| rest.strip_prefix('('))?;
+            let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            rest[..digits].parse().ok().filter(|&line: &usize| line > 0)
+        })
+    }
+}
@@ -0,0 +1,179 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::atomic::{ AtomicU64, Ordering };
+use once_cell::sync::Lazy;
+
+use crate::index::engine::modules::event_system::{ Event, EventSystem, EventType };
+use crate::index::engine::modules::{ EngineError, EngineResult };
+
+/// Most worker threads the native pool starts, however many cores there are
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_WORKERS: usize = 4;
+
+/// Identifies a task in its TaskCompleted event
+pub type TaskId = u64;
+
+/// Payload of EventType::TaskCompleted, sent on the main thread after the task's completion callback ran
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct TaskCompletedEvent {
+    pub task_id: TaskId,
+    pub name: String,
+    pub error: Option<String>, // None when the work succeeded
+}
+
+/// A task's `EngineResult<T>`, boxed so workers can carry results of any type
+type TaskOutput = Box<dyn Any + Send>;
+/// Work run off the main thread
+type Job = Box<dyn FnOnce() -> TaskOutput + Send>;
+/// Main-thread continuation of a task, given its output; returns the error message if the work failed
+type Completion = Box<dyn FnOnce(TaskOutput, Option<&glow::Context>) -> Option<String>>;
+
+static NEXT_TASK_ID: AtomicU64 = AtomicU64::new(1);
+/// Results handed back by the workers, waiting for the next poll
+static FINISHED: Lazy<Mutex<Vec<(TaskId, TaskOutput)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+#[cfg(not(target_arch = "wasm32"))]
+static POOL: Lazy<Mutex<std::sync::mpsc::Sender<(TaskId, Job)>>> = Lazy::new(|| Mutex::new(TaskSystem::start_workers()));
+
+thread_local! {
+    /// Name and completion of every running task; completions may hold GL handles or Rc's, so they never leave
+    /// the main thread
+    static RUNNING: RefCell<BTreeMap<TaskId, (String, Completion)>> = RefCell::new(BTreeMap::new());
+}
+
+/// Runs long engine work (file IO, parsing, baking, encoding) away from the render/UI thread. The work gets no
+/// access to the ECS or GL, so callers gather what it needs first; its result comes back to a completion
+/// callback on the main thread during the next poll, followed by a TaskCompleted event.
+pub struct TaskSystem;
+
+impl TaskSystem {
+    /// Start `work` in the background and call `on_complete` with its result on the main thread
+    pub fn spawn<T: Send + 'static>(
+        name: &str,
+        work: impl FnOnce() -> EngineResult<T> + Send + 'static,
+        on_complete: impl FnOnce(EngineResult<T>, Option<&glow::Context>) + 'static
+    ) -> TaskId {
+        let task_id = NEXT_TASK_ID.fetch_add(1, Ordering::Relaxed);
+        let task_name = name.to_string();
+        let job: Job = Box::new(move || {
+            // A panicking task fails instead of taking its worker down
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(work)).unwrap_or_else(|panic| {
+                let message = panic
+                    .downcast_ref::<&str>()
+                    .map(|message| message.to_string())
+                    .or_else(|| panic.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "panicked".to_string());
+                Err(EngineError::task(&task_name, message))
+            });
+            Box::new(result)
+        });
+        let completion: Completion = Box::new(move |output, gl| {
+            let result = *output.downcast::<EngineResult<T>>().expect("Task result has the spawned type");
+            let error = result.as_ref().err().map(|e| e.to_string());
+            on_complete(result, gl);
+            error
+        });
+
+        RUNNING.with(|running| running.borrow_mut().insert(task_id, (name.to_string(), completion)));
+        println!("⏳ Started task {} ({})", task_id, name);
+        Self::run(task_id, job);
+        task_id
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn run(task_id: TaskId, job: Job) {
+        // The workers live as long as the program, so the channel never closes
+        POOL.lock().unwrap().send((task_id, job)).expect("Task workers stopped");
+    }
+
+    /// The browser has one thread: the work runs as a separate future once the current frame returns
+    #[cfg(target_arch = "wasm32")]
+    fn run(task_id: TaskId, job: Job) {
+        wasm_bindgen_futures::spawn_local(async move {
+            let output = job();
+            FINISHED.lock().unwrap().push((task_id, output));
+        });
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn start_workers() -> std::sync::mpsc::Sender<(TaskId, Job)> {
+        let (sender, receiver) = std::sync::mpsc::channel::<(TaskId, Job)>();
+        let receiver = std::sync::Arc::new(Mutex::new(receiver));
+        // Leave a core for the main thread
+        let workers = std::thread::available_parallelism()
+            .map_or(2, |cores| cores.get())
+            .saturating_sub(1)
+            .clamp(1, MAX_WORKERS);
+        for index in 0..workers {
+            let receiver = receiver.clone();
+            std::thread::Builder
+                ::new()
+                .name(format!("task-worker-{}", index))
+                .spawn(move || {
+                    loop {
+                        let next = receiver.lock().unwrap().recv();
+                        let Ok((task_id, job)) = next else {
+                            break;
+                        };
+                        let output = job();
+                        FINISHED.lock().unwrap().push((task_id, output));
+                    }
+                })
+                .expect("Failed to start task worker");
+        }
+        println!("🧵 Started {} task workers", workers);
+        sender
+    }
+
+    /// Run the completions of finished tasks; called on the main thread every frame (`gl` is None when headless)
+    pub fn poll(gl: Option<&glow::Context>) {
+        let finished = std::mem::take(&mut *FINISHED.lock().unwrap());
+        for (task_id, output) in finished {
+            // Completions may spawn further tasks, so take it out before calling it
+            let Some((name, completion)) = RUNNING.with(|running| running.borrow_mut().remove(&task_id)) else {
+                continue;
+            };
+            let error = completion(output, gl);
+            match &error {
+                Some(message) => println!("❌ Task {} ({}) failed: {}", task_id, name, message),
+                None => println!("✅ Finished task {} ({})", task_id, name),
+            }
+            EventSystem::notify(Event {
+                event_type: EventType::TaskCompleted,
+                payload: Box::new(TaskCompletedEvent { task_id, name, error }),
+            });
+        }
+    }
+
+    /// Block until every running task has completed (headless runs, which have no frames to poll from).
+    /// Native only: on wasm the work can't progress while this waits
+    pub fn wait_all(gl: Option<&glow::Context>) {
+        while RUNNING.with(|running| !running.borrow().is_empty()) {
+            Self::poll(gl);
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+    }
+
+    /// Whether a task with this name is still running, e.g. to avoid starting a second navmesh bake
+    pub fn is_running(name: &str) -> bool {
+        RUNNING.with(|running| running.borrow().values().any(|(running_name, _)| running_name == name))
+    }
+
+    /// Console: `tasks` lists the running tasks
+    pub fn tasks_command(_args: &[&str]) -> Result<String, String> {
+        let names: Vec<String> = RUNNING.with(|running| {
+            running
+                .borrow()
+                .iter()
+                .map(|(task_id, (name, _))| format!("  {} {}", task_id, name))
+                .collect()
+        });
+        if names.is_empty() {
+            return Ok("No running tasks".to_string());
+        }
+        Ok(format!("{} running tasks:\n{}", names.len(), names.join("\n")))
+    }
+}
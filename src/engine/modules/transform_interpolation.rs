@@ -0,0 +1,89 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+
+use crate::index::engine::components::Transform;
+use crate::index::engine::modules::{ Console, EntityId, GameLoop };
+use crate::index::engine::utils::math::{ dist2, lerp, Mat4x4 };
+use crate::query_get_all;
+
+/// Default for the `render_smoothing` cvar: 0 draws tick transforms, 1 interpolates, 2 extrapolates
+pub const RENDER_SMOOTHING: f32 = 1.0;
+/// Moves longer than this within one tick are teleports and snap instead of sliding
+const TELEPORT_DISTANCE: f32 = 5.0;
+
+/// Position, rotation and scale of every Transform at the start of the current tick
+static PREVIOUS: Lazy<RwLock<HashMap<EntityId, [[f32; 3]; 3]>>> = Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Smooths rendered motion between fixed ticks: blends each entity's previous-tick and current transform
+/// by how far the frame is into the next tick
+pub struct TransformInterpolation;
+
+impl TransformInterpolation {
+    /// Remember every transform before a tick moves it
+    pub fn snapshot() {
+        let samples: HashMap<EntityId, [[f32; 3]; 3]> = query_get_all!(Transform)
+            .into_iter()
+            .map(|(entity_id, transform)| (entity_id, Self::sample(&transform)))
+            .collect();
+        *PREVIOUS.write().unwrap() = samples;
+    }
+
+    /// World matrix to draw an entity with this frame
+    pub fn matrix(entity_id: &str, transform: &mut Transform) -> Mat4x4 {
+        match Self::blend(entity_id, transform) {
+            Some([position, rotation, scale]) => {
+                let mut blended = transform.clone();
+                blended.set_from_components(position, rotation, scale);
+                blended.compute_matrix()
+            }
+            None => *transform.get_matrix(),
+        }
+    }
+
+    /// Position to draw an entity at this frame
+    pub fn position(entity_id: &str, transform: &Transform) -> [f32; 3] {
+        Self::blend(entity_id, transform).map_or_else(|| transform.get_position(), |[position, ..]| position)
+    }
+
+    /// Blended components, or None to draw the transform as the tick left it
+    fn blend(entity_id: &str, transform: &Transform) -> Option<[[f32; 3]; 3]> {
+        let mode = Console::cvar("render_smoothing").unwrap_or(RENDER_SMOOTHING).round();
+        if mode < 1.0 || GameLoop::is_paused() {
+            return None;
+        }
+        let [previous_position, previous_rotation, previous_scale] = *PREVIOUS.read().unwrap().get(entity_id)?;
+        let current = Self::sample(transform);
+        if dist2(previous_position, current[0]) > TELEPORT_DISTANCE * TELEPORT_DISTANCE {
+            return None;
+        }
+
+        // Interpolating lags a tick behind the simulation; extrapolating continues the last tick's motion
+        let alpha = GameLoop::interpolation_alpha();
+        let t = if mode >= 2.0 { 1.0 + alpha } else { alpha };
+        let blend3 = |from: [f32; 3], to: [f32; 3], angles: bool| {
+            std::array::from_fn(|i| {
+                if angles {
+                    from[i] + wrap_angle(to[i] - from[i]) * t
+                } else {
+                    lerp(from[i], to[i], t)
+                }
+            })
+        };
+        Some([
+            blend3(previous_position, current[0], false),
+            blend3(previous_rotation, current[1], true),
+            blend3(previous_scale, current[2], false),
+        ])
+    }
+
+    fn sample(transform: &Transform) -> [[f32; 3]; 3] {
+        [transform.get_position(), transform.get_rotation(), transform.get_scale()]
+    }
+}
+
+/// Shortest signed difference between two angles, in (-π, π]
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = (angle + std::f32::consts::PI).rem_euclid(std::f32::consts::TAU) - std::f32::consts::PI;
+    if wrapped == -std::f32::consts::PI { std::f32::consts::PI } else { wrapped }
+}
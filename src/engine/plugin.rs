@@ -0,0 +1,80 @@
+use crate::index::engine::components::{ Collider, PluginComponent };
+use crate::index::engine::managers::assets_manager::{ hot_reload_shaders, import_project_asset, SHADER_HOT_RELOAD };
+use crate::index::engine::managers::prefab_library::PREFAB_EXTENSION;
+use crate::index::engine::managers::{ MaterialLibrary, PrefabLibrary, TextureStreaming };
+use crate::index::engine::modules::transform_interpolation::RENDER_SMOOTHING;
+use crate::index::engine::modules::{
+    App,
+    BuildExport,
+    CollabSession,
+    CollisionFile,
+    DeterminismAudit,
+    Diagnostics,
+    EcsBench,
+    EditorPanel,
+    EntityPool,
+    FindReplace,
+    GraphicsQuality,
+    PlaySession,
+    Plugin,
+    Project,
+    Random,
+    SceneLibrary,
+    TaskSystem,
+};
+
+/// Engine modules: project asset loaders, shader, material and scene hot reload, graphics settings and the tooling
+/// commands
+pub struct EnginePlugin;
+
+impl Plugin for EnginePlugin {
+    fn name(&self) -> &str {
+        "engine"
+    }
+
+    fn build(&self, app: &mut App) {
+        app.add_asset_loader(".material.toml", MaterialLibrary::load_asset)
+            .add_asset_loader(PREFAB_EXTENSION, PrefabLibrary::load_asset)
+            .add_asset_loader(".gltf", import_project_asset)
+            .add_asset_loader(".glb", import_project_asset)
+            .add_console_command("export_colliders", CollisionFile::export_command)
+            .add_console_command("import_colliders", CollisionFile::import_command)
+            .add_console_command("fit_collider", Collider::fit_command)
+            .add_console_command("layers", Project::layers_command)
+            .add_console_command("export_build", BuildExport::export_command)
+            .add_console_command("report", Diagnostics::report_command)
+            .add_console_command("seed", Random::seed_command)
+            .add_console_command("tasks", TaskSystem::tasks_command)
+            .add_console_command("materials", MaterialLibrary::materials_command)
+            .add_console_command("material", MaterialLibrary::material_command)
+            .add_console_command("prefab", PrefabLibrary::create_command)
+            .add_console_command("prefabs", PrefabLibrary::prefabs_command)
+            .add_console_command("add_component", PluginComponent::add_command)
+            .add_console_command("pools", EntityPool::pools_command)
+            .add_console_command("find", FindReplace::find_command)
+            .add_console_command("replace", FindReplace::replace_command)
+            .add_console_command("undo_replace", FindReplace::undo_command)
+            .add_console_command("audit", DeterminismAudit::audit_command)
+            .add_console_command("ecs_bench", EcsBench::bench_command)
+            .add_console_command("textures", TextureStreaming::textures_command)
+            .add_console_command("play_changes", PlaySession::play_changes_command)
+            .add_console_command("collab", CollabSession::collab_command)
+            .add_cvar("render_smoothing", RENDER_SMOOTHING, 0.0, 2.0, "Motion between ticks: 0 off, 1 interpolate, 2 extrapolate")
+            .add_cvar("shader_hot_reload", SHADER_HOT_RELOAD, 0.0, 1.0, "Recompile shaders when their .glsl files change on disk")
+            .add_render_system("graphics_quality", GraphicsQuality::apply)
+            .add_render_system("shader_hot_reload", hot_reload_shaders)
+            .add_render_system("material_hot_reload", MaterialLibrary::hot_reload)
+            .add_render_system("scene_hot_reload", SceneLibrary::hot_reload)
+            .add_render_system("collab", CollabSession::update)
+            .add_editor_panel(EditorPanel::new("Loaded Plugins", App::plugin_rows).with_action("List Tasks", "tasks"))
+            .add_editor_panel(
+                EditorPanel::new("Play Changes", PlaySession::panel_rows)
+                    .with_row_action("Apply", "play_changes apply")
+                    .with_action("Apply All", "play_changes apply all")
+                    .with_action("Discard", "play_changes discard")
+            )
+            .add_editor_panel(
+                EditorPanel::new("Collaboration", CollabSession::panel_rows).with_action("Leave", "collab leave")
+            );
+    }
+}
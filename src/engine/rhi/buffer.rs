@@ -0,0 +1,234 @@
+use glow::HasContext;
+
+use crate::index::engine::modules::{ EngineError, EngineResult };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferKind {
+    Vertex,
+    Index(IndexFormat),
+    Storage,  // Read and written by shaders (compute passes)
+    Indirect, // Draw commands, usually written by a compute pass
+}
+
+/// Width of the indices in an index buffer; 16-bit ones address up to 65536 vertices
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexFormat {
+    U16,
+    U32,
+}
+
+impl IndexFormat {
+    pub(super) fn gl_type(self) -> u32 {
+        match self {
+            IndexFormat::U16 => glow::UNSIGNED_SHORT,
+            IndexFormat::U32 => glow::UNSIGNED_INT,
+        }
+    }
+}
+
+/// How often the contents are rewritten, so the backend can place them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferUsage {
+    Static, // Uploaded once (meshes, terrain chunks)
+    Stream, // Rewritten every frame (debug lines)
+}
+
+impl BufferUsage {
+    fn gl_usage(self) -> u32 {
+        match self {
+            BufferUsage::Static => glow::STATIC_DRAW,
+            BufferUsage::Stream => glow::STREAM_DRAW,
+        }
+    }
+}
+
+/// GPU memory holding vertex attributes or indices
+#[derive(Debug, Clone, Copy)]
+pub struct Buffer {
+    raw: glow::Buffer,
+    kind: BufferKind,
+}
+
+impl Buffer {
+    pub fn new(gl: &glow::Context, kind: BufferKind, data: &[u8], usage: BufferUsage) -> EngineResult<Self> {
+        let raw = unsafe { gl.create_buffer() }
+            .map_err(|e| EngineError::gpu(format!("Failed to create {:?} buffer: {}", kind, e)))?;
+        let buffer = Self { raw, kind };
+        buffer.write(gl, data, usage);
+        Ok(buffer)
+    }
+
+    pub fn vertex(gl: &glow::Context, vertices: &[f32], usage: BufferUsage) -> EngineResult<Self> {
+        Self::new(gl, BufferKind::Vertex, bytemuck::cast_slice(vertices), usage)
+    }
+
+    pub fn index(gl: &glow::Context, indices: &[u16]) -> EngineResult<Self> {
+        Self::new(gl, BufferKind::Index(IndexFormat::U16), bytemuck::cast_slice(indices), BufferUsage::Static)
+    }
+
+    /// Index buffer of 32-bit indices, for meshes too big for `index`
+    pub fn index32(gl: &glow::Context, indices: &[u32]) -> EngineResult<Self> {
+        Self::new(gl, BufferKind::Index(IndexFormat::U32), bytemuck::cast_slice(indices), BufferUsage::Static)
+    }
+
+    /// Buffer of `size` bytes whose contents are left for a shader to write
+    pub fn uninitialized(gl: &glow::Context, kind: BufferKind, size: usize, usage: BufferUsage) -> EngineResult<Self> {
+        let raw = unsafe { gl.create_buffer() }
+            .map_err(|e| EngineError::gpu(format!("Failed to create {:?} buffer: {}", kind, e)))?;
+        let buffer = Self { raw, kind };
+        let target = buffer.bind_target(gl);
+        unsafe {
+            gl.buffer_data_size(target, size as i32, usage.gl_usage());
+        }
+        Ok(buffer)
+    }
+
+    /// Replace the contents (and size)
+    pub fn write(&self, gl: &glow::Context, data: &[u8], usage: BufferUsage) {
+        let target = self.bind_target(gl);
+        unsafe {
+            gl.buffer_data_u8_slice(target, data, usage.gl_usage());
+        }
+    }
+
+    /// Bind to the target matching the kind and return it
+    fn bind_target(&self, gl: &glow::Context) -> u32 {
+        let target = match self.kind {
+            BufferKind::Vertex => glow::ARRAY_BUFFER,
+            BufferKind::Index(_) => glow::ELEMENT_ARRAY_BUFFER,
+            BufferKind::Storage => glow::SHADER_STORAGE_BUFFER,
+            BufferKind::Indirect => glow::DRAW_INDIRECT_BUFFER,
+        };
+        unsafe {
+            if matches!(self.kind, BufferKind::Index(_)) {
+                // Index bindings belong to the bound vertex array; don't rewire whichever one that is
+                gl.bind_vertex_array(None);
+            }
+            gl.bind_buffer(target, Some(self.raw));
+        }
+        target
+    }
+
+    /// Expose to shaders as the storage block at `binding`, whatever the kind (compute passes read vertex
+    /// buffers and write draw commands through this)
+    pub(super) fn bind_storage(&self, gl: &glow::Context, binding: u32) {
+        unsafe {
+            gl.bind_buffer_base(glow::SHADER_STORAGE_BUFFER, binding, Some(self.raw));
+        }
+    }
+
+    pub(super) fn bind_indirect(&self, gl: &glow::Context) {
+        unsafe {
+            gl.bind_buffer(glow::DRAW_INDIRECT_BUFFER, Some(self.raw));
+        }
+    }
+
+    pub fn delete(self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_buffer(self.raw);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexFormat {
+    Float32x2,
+    Float32x3,
+    Float32x4,
+    Uint8x4, // Integer attribute (joint indices)
+}
+
+impl VertexFormat {
+    fn components(self) -> i32 {
+        match self {
+            VertexFormat::Float32x2 => 2,
+            VertexFormat::Float32x3 => 3,
+            VertexFormat::Float32x4 | VertexFormat::Uint8x4 => 4,
+        }
+    }
+
+    /// Bytes per vertex
+    pub fn size(self) -> i32 {
+        match self {
+            VertexFormat::Uint8x4 => 4,
+            _ => self.components() * 4,
+        }
+    }
+}
+
+/// Where a shader input reads from: `stride` bytes per vertex, starting `offset` bytes into the buffer
+#[derive(Debug, Clone, Copy)]
+pub struct VertexAttribute<'a> {
+    pub buffer: &'a Buffer,
+    pub location: u32,
+    pub format: VertexFormat,
+    pub stride: i32,
+    pub offset: i32,
+}
+
+impl<'a> VertexAttribute<'a> {
+    /// Attribute that is the only thing in its buffer
+    pub fn packed(buffer: &'a Buffer, location: u32, format: VertexFormat) -> Self {
+        Self { buffer, location, format, stride: format.size(), offset: 0 }
+    }
+}
+
+/// Vertex attributes and optional index buffer of a mesh (a vertex array object on GL). Deleting it leaves the
+/// buffers alone.
+#[derive(Debug, Clone, Copy)]
+pub struct Geometry {
+    raw: glow::VertexArray,
+    index_format: Option<IndexFormat>, // Of the index buffer, None for unindexed geometry
+}
+
+impl Geometry {
+    pub fn new(gl: &glow::Context, attributes: &[VertexAttribute], indices: Option<&Buffer>) -> EngineResult<Self> {
+        unsafe {
+            let raw = gl.create_vertex_array().map_err(|e| EngineError::gpu(format!("Failed to create vertex array: {}", e)))?;
+            gl.bind_vertex_array(Some(raw));
+            for attribute in attributes {
+                gl.bind_buffer(glow::ARRAY_BUFFER, Some(attribute.buffer.raw));
+                gl.enable_vertex_attrib_array(attribute.location);
+                let components = attribute.format.components();
+                match attribute.format {
+                    VertexFormat::Uint8x4 =>
+                        gl.vertex_attrib_pointer_i32(attribute.location, components, glow::UNSIGNED_BYTE, attribute.stride, attribute.offset),
+                    _ =>
+                        gl.vertex_attrib_pointer_f32(attribute.location, components, glow::FLOAT, false, attribute.stride, attribute.offset),
+                }
+            }
+            if let Some(indices) = indices {
+                gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(indices.raw));
+            }
+            gl.bind_vertex_array(None);
+            let index_format = indices.map(|buffer| match buffer.kind {
+                BufferKind::Index(format) => format,
+                _ => IndexFormat::U16,
+            });
+            Ok(Self { raw, index_format })
+        }
+    }
+
+    pub fn index_format(&self) -> Option<IndexFormat> {
+        self.index_format
+    }
+
+    pub(super) fn bind(&self, gl: &glow::Context) {
+        unsafe {
+            gl.bind_vertex_array(Some(self.raw));
+        }
+    }
+
+    /// No vertex inputs bound, for shaders that build vertices from their index
+    pub(super) fn unbind(gl: &glow::Context) {
+        unsafe {
+            gl.bind_vertex_array(None);
+        }
+    }
+
+    pub fn delete(self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_vertex_array(self.raw);
+        }
+    }
+}
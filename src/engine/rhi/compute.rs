@@ -0,0 +1,66 @@
+use glow::HasContext;
+
+use crate::index::engine::rhi::{ Buffer, Pipeline, Texture, Uniform };
+
+/// One compute pass: a compute pipeline, its uniforms, textures and storage buffers, and how many work groups to run.
+/// Everything it writes is visible to later dispatches, draws and indirect draw commands.
+pub struct Dispatch<'a> {
+    pipeline: Pipeline,
+    groups: [u32; 3],
+    uniforms: Vec<(&'a str, Uniform<'a>)>,
+    textures: Vec<(u32, Texture)>,
+    storage: Vec<(u32, Buffer)>,
+}
+
+impl<'a> Dispatch<'a> {
+    pub fn new(pipeline: Pipeline, groups: [u32; 3]) -> Self {
+        Self {
+            pipeline,
+            groups,
+            uniforms: Vec::new(),
+            textures: Vec::new(),
+            storage: Vec::new(),
+        }
+    }
+
+    pub fn uniform(mut self, name: &'a str, value: Uniform<'a>) -> Self {
+        self.uniforms.push((name, value));
+        self
+    }
+
+    /// Bind a texture to a sampler unit, for `texelFetch` lookups
+    pub fn texture(mut self, unit: u32, texture: Texture) -> Self {
+        self.textures.push((unit, texture));
+        self
+    }
+
+    /// Bind a buffer to a storage block binding
+    pub fn storage(mut self, binding: u32, buffer: Buffer) -> Self {
+        self.storage.push((binding, buffer));
+        self
+    }
+
+    pub fn submit(&self, gl: &glow::Context) {
+        if self.groups.contains(&0) {
+            return;
+        }
+        self.pipeline.bind(gl);
+        for (name, value) in &self.uniforms {
+            self.pipeline.set_uniform(gl, name, *value);
+        }
+        for (unit, texture) in &self.textures {
+            texture.bind(gl, *unit);
+        }
+        for (binding, buffer) in &self.storage {
+            buffer.bind_storage(gl, *binding);
+        }
+        unsafe {
+            gl.dispatch_compute(self.groups[0], self.groups[1], self.groups[2]);
+            gl.memory_barrier(glow::SHADER_STORAGE_BARRIER_BIT | glow::COMMAND_BARRIER_BIT);
+            // Later texture uploads and raw GL code expect unit 0 to be active
+            if !self.textures.is_empty() {
+                gl.active_texture(glow::TEXTURE0);
+            }
+        }
+    }
+}
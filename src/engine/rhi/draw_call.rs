@@ -0,0 +1,195 @@
+use glow::HasContext;
+
+use crate::index::engine::rhi::{ Buffer, Cubemap, Geometry, IndexFormat, Pipeline, Texture, Uniform };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Primitive {
+    Triangles,
+    Lines,
+    LineLoop,
+}
+
+/// Depth test of a draw; the renderer's default is Less with depth writes on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthCompare {
+    Less,
+    LessEqual,
+    Greater, // Only where something nearer was drawn (occluded outlines)
+    Always,  // No depth test (screen-space overlays)
+}
+
+/// How fragments combine with the target; anything but Opaque leaves the depth buffer alone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Blend {
+    Opaque,
+    Alpha,           // Straight alpha over what's there (sorted transparency, compositing)
+    WeightedBlended, // Color and alpha weight sums plus revealage product, see TransparencySystem
+    Multiply,        // Scale what's there by the fragment color (screen-space occlusion)
+    Additive,        // Add the fragment color to what's there (overdraw counting)
+}
+
+/// One draw: a pipeline, its uniforms and textures, and the geometry to draw with it.
+/// `count` is indices for indexed geometry and vertices otherwise; without geometry the vertex shader builds the
+/// vertices from their index (collider wireframes, water quads). Indirect draws take `count` and the instance
+/// count from a command buffer instead.
+pub struct DrawCall<'a> {
+    pipeline: Pipeline,
+    primitive: Primitive,
+    count: usize,
+    geometry: Option<Geometry>,
+    depth: DepthCompare,
+    blend: Blend,
+    uniforms: Vec<(&'a str, Uniform<'a>)>,
+    textures: Vec<(u32, Texture)>,
+    cubemaps: Vec<(u32, Cubemap)>,
+    storage: Vec<(u32, Buffer)>,
+    indirect: Option<Buffer>,
+}
+
+impl<'a> DrawCall<'a> {
+    pub fn new(pipeline: Pipeline, primitive: Primitive, count: usize) -> Self {
+        Self {
+            pipeline,
+            primitive,
+            count,
+            geometry: None,
+            depth: DepthCompare::Less,
+            blend: Blend::Opaque,
+            uniforms: Vec::new(),
+            textures: Vec::new(),
+            cubemaps: Vec::new(),
+            storage: Vec::new(),
+            indirect: None,
+        }
+    }
+
+    pub fn geometry(mut self, geometry: Geometry) -> Self {
+        self.geometry = Some(geometry);
+        self
+    }
+
+    pub fn depth(mut self, depth: DepthCompare) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    pub fn blend(mut self, blend: Blend) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    pub fn uniform(mut self, name: &'a str, value: Uniform<'a>) -> Self {
+        self.uniforms.push((name, value));
+        self
+    }
+
+    /// Bind a texture to a sampler unit
+    pub fn texture(mut self, unit: u32, texture: Texture) -> Self {
+        self.textures.push((unit, texture));
+        self
+    }
+
+    /// Bind a cubemap to a `samplerCube` unit
+    pub fn cubemap(mut self, unit: u32, cubemap: Cubemap) -> Self {
+        self.cubemaps.push((unit, cubemap));
+        self
+    }
+
+    /// Bind a buffer to a storage block the shaders read (needs `supports_compute`)
+    pub fn storage(mut self, binding: u32, buffer: Buffer) -> Self {
+        self.storage.push((binding, buffer));
+        self
+    }
+
+    /// Take the index and instance counts from an indexed draw command written by a compute pass: five u32s
+    /// (count, instance count, first index, base vertex, base instance) at the start of `commands`
+    pub fn indirect(mut self, commands: Buffer) -> Self {
+        self.indirect = Some(commands);
+        self
+    }
+
+    pub fn submit(&self, gl: &glow::Context) {
+        if self.count == 0 {
+            return;
+        }
+        self.pipeline.bind(gl);
+        for (name, value) in &self.uniforms {
+            self.pipeline.set_uniform(gl, name, *value);
+        }
+        for (unit, texture) in &self.textures {
+            texture.bind(gl, *unit);
+        }
+        for (unit, cubemap) in &self.cubemaps {
+            cubemap.bind(gl, *unit);
+        }
+        for (binding, buffer) in &self.storage {
+            buffer.bind_storage(gl, *binding);
+        }
+        match &self.geometry {
+            Some(geometry) => geometry.bind(gl),
+            None => Geometry::unbind(gl),
+        }
+
+        let mode = match self.primitive {
+            Primitive::Triangles => glow::TRIANGLES,
+            Primitive::Lines => glow::LINES,
+            Primitive::LineLoop => glow::LINE_LOOP,
+        };
+        unsafe {
+            match self.depth {
+                DepthCompare::Less => {}
+                DepthCompare::LessEqual => gl.depth_func(glow::LEQUAL),
+                DepthCompare::Greater => gl.depth_func(glow::GREATER),
+                DepthCompare::Always => gl.disable(glow::DEPTH_TEST),
+            }
+            match self.blend {
+                Blend::Opaque => {}
+                Blend::Alpha => {
+                    gl.enable(glow::BLEND);
+                    gl.blend_func(glow::SRC_ALPHA, glow::ONE_MINUS_SRC_ALPHA);
+                    gl.depth_mask(false);
+                }
+                Blend::WeightedBlended => {
+                    // Same function for every attachment (WebGL2 has no per-buffer blending): colors add up,
+                    // alpha multiplies by 1 - alpha
+                    gl.enable(glow::BLEND);
+                    gl.blend_func_separate(glow::ONE, glow::ONE, glow::ZERO, glow::ONE_MINUS_SRC_ALPHA);
+                    gl.depth_mask(false);
+                }
+                Blend::Multiply => {
+                    gl.enable(glow::BLEND);
+                    gl.blend_func(glow::DST_COLOR, glow::ZERO);
+                    gl.depth_mask(false);
+                }
+                Blend::Additive => {
+                    gl.enable(glow::BLEND);
+                    gl.blend_func(glow::ONE, glow::ONE);
+                    gl.depth_mask(false);
+                }
+            }
+            let index_format = self.geometry.and_then(|geometry| geometry.index_format());
+            if let Some(commands) = self.indirect {
+                commands.bind_indirect(gl);
+                gl.draw_elements_indirect_offset(mode, index_format.unwrap_or(IndexFormat::U16).gl_type(), 0);
+            } else if let Some(index_format) = index_format {
+                gl.draw_elements(mode, self.count as i32, index_format.gl_type(), 0);
+            } else {
+                gl.draw_arrays(mode, 0, self.count as i32);
+            }
+            match self.depth {
+                DepthCompare::Less => {}
+                DepthCompare::LessEqual | DepthCompare::Greater => gl.depth_func(glow::LESS),
+                DepthCompare::Always => gl.enable(glow::DEPTH_TEST),
+            }
+            if self.blend != Blend::Opaque {
+                gl.disable(glow::BLEND);
+                gl.depth_mask(true);
+            }
+
+            // Later texture uploads and raw GL code expect unit 0 to be active
+            if !self.textures.is_empty() || !self.cubemaps.is_empty() {
+                gl.active_texture(glow::TEXTURE0);
+            }
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Thin render hardware interface: the GPU objects systems draw with, so they don't call a graphics API
+//! directly. Only the glow (OpenGL / WebGL2) backend exists; a wgpu backend would reimplement these types with
+//! the same methods. Compute passes (`Dispatch`, storage and indirect buffers) need desktop GL 4.3, so callers
+//! check `supports_compute` and keep a fallback. Render targets (framebuffers, readback) and stencil state aren't
+//! covered yet and still use glow where they're needed (minimap, scene thumbnails, selection outlines,
+//! weighted blended transparency, reflection probe captures).
+pub mod buffer;
+pub mod texture;
+pub mod pipeline;
+pub mod draw_call;
+pub mod compute;
+
+pub use buffer::{ Buffer, BufferKind, BufferUsage, Geometry, IndexFormat, VertexAttribute, VertexFormat };
+pub use texture::{ Cubemap, Texture, TextureFilter, TextureFormat, TextureWrap };
+pub use pipeline::{ supports_compute, Pipeline, Uniform };
+pub use draw_call::{ Blend, DepthCompare, DrawCall, Primitive };
+pub use compute::Dispatch;
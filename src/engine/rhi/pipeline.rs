@@ -0,0 +1,151 @@
+use glow::HasContext;
+
+use crate::index::engine::modules::{ EngineError, EngineResult, ShaderDiagnostics };
+
+/// Value for a named shader uniform; matrices are row-major like `utils::math`
+#[derive(Debug, Clone, Copy)]
+pub enum Uniform<'a> {
+    Int(i32),
+    Float(f32),
+    Vec2(&'a [f32; 2]),
+    Vec3(&'a [f32; 3]),
+    Vec4(&'a [f32; 4]),
+    Mat4(&'a [f32; 16]),
+    Mat4Array(&'a [f32]), // Consecutive matrices (bone palettes)
+    Vec4Array(&'a [f32]), // Consecutive vec4s (float arrays packed four to an element)
+}
+
+/// Linked vertex and fragment shaders (a GL program)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pipeline {
+    raw: glow::Program,
+}
+
+impl Pipeline {
+    /// Compile and link GLSL sources; `name` labels errors, which ShaderDiagnostics also keeps for the overlay
+    pub fn new(gl: &glow::Context, name: &str, vertex_source: &str, fragment_source: &str) -> EngineResult<Self> {
+        unsafe {
+            let vs = compile_shader(gl, name, "Vertex", glow::VERTEX_SHADER, vertex_source)?;
+            let fs = match compile_shader(gl, name, "Fragment", glow::FRAGMENT_SHADER, fragment_source) {
+                Ok(fs) => fs,
+                Err(e) => {
+                    gl.delete_shader(vs);
+                    return Err(e);
+                }
+            };
+
+            let program = gl
+                .create_program()
+                .map_err(|e| EngineError::gpu(format!("Failed to create {} shader program: {}", name, e)))?;
+            gl.attach_shader(program, vs);
+            gl.attach_shader(program, fs);
+            gl.link_program(program);
+
+            gl.delete_shader(vs);
+            gl.delete_shader(fs);
+
+            if !gl.get_program_link_status(program) {
+                let log = gl.get_program_info_log(program);
+                gl.delete_program(program);
+                ShaderDiagnostics::record(name, "Link", "", &log);
+                return Err(EngineError::shader(name, format!("Link error: {}", log)));
+            }
+            ShaderDiagnostics::resolve(name);
+
+            println!("✅ Created {} shader program", name);
+            Ok(Self { raw: program })
+        }
+    }
+
+    /// Free the GL program; nothing may draw with it afterwards
+    pub fn delete(self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_program(self.raw);
+        }
+    }
+
+    /// Compile and link a GLSL compute shader, run with `Dispatch`; check `supports_compute` first
+    pub fn compute(gl: &glow::Context, name: &str, source: &str) -> EngineResult<Self> {
+        unsafe {
+            let cs = compile_shader(gl, name, "Compute", glow::COMPUTE_SHADER, source)?;
+            let program = match gl.create_program() {
+                Ok(program) => program,
+                Err(e) => {
+                    gl.delete_shader(cs);
+                    return Err(EngineError::gpu(format!("Failed to create {} shader program: {}", name, e)));
+                }
+            };
+            gl.attach_shader(program, cs);
+            gl.link_program(program);
+            gl.delete_shader(cs);
+
+            if !gl.get_program_link_status(program) {
+                let log = gl.get_program_info_log(program);
+                gl.delete_program(program);
+                ShaderDiagnostics::record(name, "Link", "", &log);
+                return Err(EngineError::shader(name, format!("Link error: {}", log)));
+            }
+            ShaderDiagnostics::resolve(name);
+
+            println!("✅ Created {} compute program", name);
+            Ok(Self { raw: program })
+        }
+    }
+
+    /// Make this the pipeline later uniforms and draws use
+    pub fn bind(&self, gl: &glow::Context) {
+        unsafe {
+            gl.use_program(Some(self.raw));
+        }
+    }
+
+    /// Set a uniform of the bound pipeline; names the shader doesn't use are ignored
+    pub fn set_uniform(&self, gl: &glow::Context, name: &str, value: Uniform) {
+        unsafe {
+            let Some(location) = gl.get_uniform_location(self.raw, name) else {
+                return;
+            };
+            let location = Some(&location);
+            match value {
+                Uniform::Int(value) => gl.uniform_1_i32(location, value),
+                Uniform::Float(value) => gl.uniform_1_f32(location, value),
+                Uniform::Vec2(value) => gl.uniform_2_f32_slice(location, value),
+                Uniform::Vec3(value) => gl.uniform_3_f32_slice(location, value),
+                Uniform::Vec4(value) => gl.uniform_4_f32_slice(location, value),
+                Uniform::Mat4(value) => gl.uniform_matrix_4_f32_slice(location, true, value),
+                Uniform::Mat4Array(value) => gl.uniform_matrix_4_f32_slice(location, true, value),
+                Uniform::Vec4Array(value) => gl.uniform_4_f32_slice(location, value),
+            }
+        }
+    }
+}
+
+/// Compute shaders and shader storage need desktop GL 4.3; WebGL2 and GLES contexts don't qualify
+pub fn supports_compute(gl: &glow::Context) -> bool {
+    let version = gl.version();
+    !version.is_embedded && (version.major, version.minor) >= (4, 3)
+}
+
+fn compile_shader(
+    gl: &glow::Context,
+    name: &str,
+    stage: &'static str,
+    shader_type: u32,
+    source: &str
+) -> EngineResult<glow::Shader> {
+    unsafe {
+        let shader = gl
+            .create_shader(shader_type)
+            .map_err(|e| EngineError::shader(name, format!("{} stage: {}", stage, e)))?;
+        gl.shader_source(shader, source);
+        gl.compile_shader(shader);
+
+        if !gl.get_shader_compile_status(shader) {
+            let log = gl.get_shader_info_log(shader);
+            gl.delete_shader(shader);
+            ShaderDiagnostics::record(name, stage, source, &log);
+            return Err(EngineError::shader(name, format!("{} stage: Shader compile error: {}", stage, log)));
+        }
+        Ok(shader)
+    }
+}
@@ -0,0 +1,418 @@
+use glow::HasContext;
+
+use crate::index::engine::modules::{ EngineError, EngineResult };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFilter {
+    Nearest,
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureWrap {
+    Repeat,
+    ClampToEdge,
+}
+
+/// Storage of render targets and data textures; shaders read the Uint formats with `usampler2D` and `texelFetch`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFormat {
+    Rgba16Float,
+    R16Float,
+    Rgba32Float,
+    Rg32Uint,
+    R32Uint,
+    R8,
+    Depth24Stencil8, // Same as the window's depth buffer, so scene depth can be blitted in and sampled
+}
+
+impl TextureFormat {
+    /// Internal format, pixel format and component type
+    fn gl_formats(self) -> (u32, u32, u32) {
+        match self {
+            TextureFormat::Rgba16Float => (glow::RGBA16F, glow::RGBA, glow::HALF_FLOAT),
+            TextureFormat::R16Float => (glow::R16F, glow::RED, glow::HALF_FLOAT),
+            TextureFormat::Rgba32Float => (glow::RGBA32F, glow::RGBA, glow::FLOAT),
+            TextureFormat::Rg32Uint => (glow::RG32UI, glow::RG_INTEGER, glow::UNSIGNED_INT),
+            TextureFormat::R32Uint => (glow::R32UI, glow::RED_INTEGER, glow::UNSIGNED_INT),
+            TextureFormat::R8 => (glow::R8, glow::RED, glow::UNSIGNED_BYTE),
+            TextureFormat::Depth24Stencil8 => (glow::DEPTH24_STENCIL8, glow::DEPTH_STENCIL, glow::UNSIGNED_INT_24_8),
+        }
+    }
+}
+
+/// 2D image sampled by shaders (RGBA8 unless made as a render target)
+#[derive(Debug, Clone, Copy)]
+pub struct Texture {
+    raw: glow::Texture,
+    width: u32,
+    height: u32,
+}
+
+impl Texture {
+    /// Upload tightly packed RGBA pixels, top row first; Linear textures get mipmaps for trilinear and anisotropic
+    /// filtering
+    pub fn rgba8(
+        gl: &glow::Context,
+        width: u32,
+        height: u32,
+        pixels: &[u8],
+        filter: TextureFilter,
+        wrap: TextureWrap
+    ) -> EngineResult<Self> {
+        let texture = Self::create(gl, width, height, filter, wrap)?;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture.raw));
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                glow::RGBA as i32,
+                width as i32,
+                height as i32,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(Some(pixels))
+            );
+            if filter == TextureFilter::Linear {
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR_MIPMAP_LINEAR as i32);
+                gl.generate_mipmap(glow::TEXTURE_2D);
+            }
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+        Ok(texture)
+    }
+
+    /// RGBA8 texture whose mip levels are written one at a time (`write_level`, `write_rows`); only the levels from
+    /// `set_base_level` down are sampled, so the finer ones can be missing. Starts out sampling the 1x1 level
+    pub fn rgba8_streamed(
+        gl: &glow::Context,
+        width: u32,
+        height: u32,
+        filter: TextureFilter,
+        wrap: TextureWrap
+    ) -> EngineResult<Self> {
+        let texture = Self::create(gl, width, height, filter, wrap)?;
+        let last_level = texture.mip_levels() as i32 - 1;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture.raw));
+            if filter == TextureFilter::Linear {
+                gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR_MIPMAP_LINEAR as i32);
+            }
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAX_LEVEL, last_level);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_BASE_LEVEL, last_level);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+        Ok(texture)
+    }
+
+    /// Size of a mip level: the image halved per level, down to 1x1
+    pub fn level_size(&self, level: u32) -> [u32; 2] {
+        [(self.width >> level).max(1), (self.height >> level).max(1)]
+    }
+
+    /// Specify an RGBA8 mip level with tightly packed pixels, top row first; None allocates it with undefined
+    /// contents for `write_rows` to fill
+    pub fn write_level(&self, gl: &glow::Context, level: u32, pixels: Option<&[u8]>) {
+        let [width, height] = self.level_size(level);
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                level as i32,
+                glow::RGBA as i32,
+                width as i32,
+                height as i32,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(pixels)
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    /// Overwrite whole rows of an allocated RGBA8 mip level, so a large level can be uploaded over several frames
+    pub fn write_rows(&self, gl: &glow::Context, level: u32, first_row: u32, rows: u32, pixels: &[u8]) {
+        let [width, _] = self.level_size(level);
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.tex_sub_image_2d(
+                glow::TEXTURE_2D,
+                level as i32,
+                0,
+                first_row as i32,
+                width as i32,
+                rows as i32,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(Some(pixels))
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    /// Free a mip level's storage by making it empty; it must be finer than the base level
+    pub fn release_level(&self, gl: &glow::Context, level: u32) {
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                level as i32,
+                glow::RGBA as i32,
+                0,
+                0,
+                0,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelUnpackData::Slice(None)
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    /// Finest mip level sampled; it and every level below it have to be specified
+    pub fn set_base_level(&self, gl: &glow::Context, level: u32) {
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_BASE_LEVEL, level as i32);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    /// Anisotropic filtering level, clamped to what the driver supports; ignored without the extension
+    pub fn set_anisotropy(&self, gl: &glow::Context, level: f32) {
+        let extensions = gl.supported_extensions();
+        if !extensions.contains("GL_EXT_texture_filter_anisotropic") && !extensions.contains("EXT_texture_filter_anisotropic") {
+            return;
+        }
+        unsafe {
+            let max = gl.get_parameter_f32(glow::MAX_TEXTURE_MAX_ANISOTROPY_EXT).max(1.0);
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.tex_parameter_f32(glow::TEXTURE_2D, glow::TEXTURE_MAX_ANISOTROPY_EXT, level.clamp(1.0, max));
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    /// Texture a render pass draws into; the contents are undefined until then
+    pub fn render_target(gl: &glow::Context, width: u32, height: u32, format: TextureFormat) -> EngineResult<Self> {
+        let mut texture = Self::create(gl, width, height, TextureFilter::Nearest, TextureWrap::ClampToEdge)?;
+        texture.write(gl, width, height, format, None);
+        Ok(texture)
+    }
+
+    /// Texture of raw values shaders look up with `texelFetch`, e.g. light lists; `data` is packed rows of `format`
+    pub fn data(gl: &glow::Context, width: u32, height: u32, format: TextureFormat, data: &[u8]) -> EngineResult<Self> {
+        let mut texture = Self::create(gl, width, height, TextureFilter::Nearest, TextureWrap::ClampToEdge)?;
+        texture.write(gl, width, height, format, Some(data));
+        Ok(texture)
+    }
+
+    /// Replace the whole image, resizing it; None leaves the contents undefined
+    pub fn write(&mut self, gl: &glow::Context, width: u32, height: u32, format: TextureFormat, data: Option<&[u8]>) {
+        let (internal_format, pixel_format, component_type) = format.gl_formats();
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                internal_format as i32,
+                width as i32,
+                height as i32,
+                0,
+                pixel_format,
+                component_type,
+                glow::PixelUnpackData::Slice(data)
+            );
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+        self.width = width;
+        self.height = height;
+    }
+
+    /// Copy of the bound framebuffer's color, e.g. the scene behind refracting water
+    pub fn from_framebuffer(gl: &glow::Context, width: u32, height: u32) -> EngineResult<Self> {
+        let texture = Self::create(gl, width, height, TextureFilter::Linear, TextureWrap::ClampToEdge)?;
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(texture.raw));
+            if Self::framebuffer_multisampled(gl) {
+                let (w, h, format) = (width as i32, height as i32, glow::RGBA8 as i32);
+                let pixels = glow::PixelUnpackData::Slice(None);
+                gl.tex_image_2d(glow::TEXTURE_2D, 0, format, w, h, 0, glow::RGBA, glow::UNSIGNED_BYTE, pixels);
+                gl.bind_texture(glow::TEXTURE_2D, None);
+                texture.resolve_framebuffer(gl);
+            } else {
+                gl.copy_tex_image_2d(glow::TEXTURE_2D, 0, glow::RGBA, 0, 0, width as i32, height as i32, 0);
+                gl.bind_texture(glow::TEXTURE_2D, None);
+            }
+        }
+        Ok(texture)
+    }
+
+    /// Refresh a framebuffer copy of the same size
+    pub fn copy_framebuffer(&self, gl: &glow::Context) {
+        unsafe {
+            if Self::framebuffer_multisampled(gl) {
+                self.resolve_framebuffer(gl);
+                return;
+            }
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.copy_tex_sub_image_2d(glow::TEXTURE_2D, 0, 0, 0, 0, 0, self.width as i32, self.height as i32);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    /// Whether the bound framebuffer is multisampled (the scene's MSAA target), which can't be copied from
+    unsafe fn framebuffer_multisampled(gl: &glow::Context) -> bool {
+        gl.get_parameter_i32(glow::SAMPLE_BUFFERS) > 0
+    }
+
+    /// Copy a multisampled framebuffer's color by resolving it into the texture with a blit
+    unsafe fn resolve_framebuffer(&self, gl: &glow::Context) {
+        let framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        let Ok(resolve) = gl.create_framebuffer() else {
+            return;
+        };
+        let (w, h) = (self.width as i32, self.height as i32);
+        gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(resolve));
+        gl.framebuffer_texture_2d(glow::DRAW_FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(self.raw), 0);
+        gl.blit_framebuffer(0, 0, w, h, 0, 0, w, h, glow::COLOR_BUFFER_BIT, glow::NEAREST);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, framebuffer);
+        gl.delete_framebuffer(resolve);
+    }
+
+    /// Rebuild the smaller levels of an RGBA8 texture after drawing or copying into it, and sample them from then on
+    pub fn generate_mipmaps(&self, gl: &glow::Context) {
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR_MIPMAP_LINEAR as i32);
+            gl.generate_mipmap(glow::TEXTURE_2D);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+        }
+    }
+
+    /// Number of mipmap levels, down to 1x1
+    pub fn mip_levels(&self) -> u32 {
+        u32::BITS - self.width.max(self.height).max(1).leading_zeros()
+    }
+
+    fn create(gl: &glow::Context, width: u32, height: u32, filter: TextureFilter, wrap: TextureWrap) -> EngineResult<Self> {
+        let filter = match filter {
+            TextureFilter::Nearest => glow::NEAREST,
+            TextureFilter::Linear => glow::LINEAR,
+        } as i32;
+        let wrap = match wrap {
+            TextureWrap::Repeat => glow::REPEAT,
+            TextureWrap::ClampToEdge => glow::CLAMP_TO_EDGE,
+        } as i32;
+        unsafe {
+            let raw = gl.create_texture().map_err(|e| EngineError::gpu(format!("Failed to create texture: {}", e)))?;
+            gl.bind_texture(glow::TEXTURE_2D, Some(raw));
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, filter);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, filter);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, wrap);
+            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, wrap);
+            gl.bind_texture(glow::TEXTURE_2D, None);
+            Ok(Self { raw, width, height })
+        }
+    }
+
+    /// GL name for framebuffer attachments, until render targets are part of the RHI
+    pub fn raw(&self) -> glow::Texture {
+        self.raw
+    }
+
+    pub fn size(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+
+    pub(super) fn bind(&self, gl: &glow::Context, unit: u32) {
+        unsafe {
+            gl.active_texture(glow::TEXTURE0 + unit);
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.raw));
+        }
+    }
+
+    pub fn delete(self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_texture(self.raw);
+        }
+    }
+}
+
+/// Six square RGBA8 faces with mipmaps, sampled by direction (`samplerCube`); filled by rendering into each face
+#[derive(Debug, Clone, Copy)]
+pub struct Cubemap {
+    raw: glow::Texture,
+    size: u32,
+}
+
+impl Cubemap {
+    /// Cubemap a render pass draws into face by face; the contents are undefined until then
+    pub fn render_target(gl: &glow::Context, size: u32) -> EngineResult<Self> {
+        unsafe {
+            let raw = gl.create_texture().map_err(|e| EngineError::gpu(format!("Failed to create cubemap: {}", e)))?;
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(raw));
+            gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_MIN_FILTER, glow::LINEAR_MIPMAP_LINEAR as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+            gl.tex_parameter_i32(glow::TEXTURE_CUBE_MAP, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+            for face in 0..6 {
+                gl.tex_image_2d(
+                    Self::face_target(face),
+                    0,
+                    glow::RGBA8 as i32,
+                    size as i32,
+                    size as i32,
+                    0,
+                    glow::RGBA,
+                    glow::UNSIGNED_BYTE,
+                    glow::PixelUnpackData::Slice(None)
+                );
+            }
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, None);
+            Ok(Self { raw, size })
+        }
+    }
+
+    /// GL target of a face for framebuffer attachments, in the order +x, -x, +y, -y, +z, -z
+    pub fn face_target(face: usize) -> u32 {
+        glow::TEXTURE_CUBE_MAP_POSITIVE_X + face as u32
+    }
+
+    /// Rebuild the smaller levels after drawing the faces; rough surfaces sample those
+    pub fn generate_mipmaps(&self, gl: &glow::Context) {
+        unsafe {
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(self.raw));
+            gl.generate_mipmap(glow::TEXTURE_CUBE_MAP);
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, None);
+        }
+    }
+
+    /// Number of mipmap levels, down to 1x1
+    pub fn mip_levels(&self) -> u32 {
+        u32::BITS - self.size.max(1).leading_zeros()
+    }
+
+    /// GL name for framebuffer attachments, until render targets are part of the RHI
+    pub fn raw(&self) -> glow::Texture {
+        self.raw
+    }
+
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    pub(super) fn bind(&self, gl: &glow::Context, unit: u32) {
+        unsafe {
+            gl.active_texture(glow::TEXTURE0 + unit);
+            gl.bind_texture(glow::TEXTURE_CUBE_MAP, Some(self.raw));
+        }
+    }
+
+    pub fn delete(self, gl: &glow::Context) {
+        unsafe {
+            gl.delete_texture(self.raw);
+        }
+    }
+}
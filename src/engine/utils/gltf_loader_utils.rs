@@ -1,28 +1,46 @@
+use std::sync::Arc;
 use gltf::buffer::Data;
-use glow::HasContext;
-use crate::index::engine::components::SharedComponents::{Mesh, Material};
-use crate::index::engine::components::AnimatedObject3D::{Skeleton, Node, AnimationChannel, AnimationType};
+use crate::index::engine::components::SharedComponents::{Mesh, MeshData, Material, MorphTargets, SkinBuffers};
+use crate::index::engine::components::AnimatedObject3D::{Skeleton, Node, AnimationChannel, AnimationClip, AnimationType};
 use crate::index::engine::utils::math::mat4x4_transpose;
+use crate::index::engine::utils::mesh_simplify::{ simplify, MeshAttributes };
+use crate::index::engine::modules::engine_error::{ EngineError, EngineResult };
+use crate::index::engine::managers::TextureStreaming;
+use crate::index::game::systems::gpu_skinning_system::{ MAX_MORPH_TARGETS, MORPH_ROW };
+use crate::index::engine::rhi::{
+    Buffer,
+    BufferKind,
+    BufferUsage,
+    Geometry,
+    Pipeline,
+    Texture,
+    TextureFilter,
+    TextureFormat,
+    TextureWrap,
+    VertexAttribute,
+    VertexFormat,
+};
 
+/// The first mesh primitive, uploaded; with `simplify` (a triangle ratio below 1) a static mesh also gets a decimated
+/// LOD
 pub fn extract_mesh(
     gl: &glow::Context,
     gltf: &gltf::Gltf,
     buffers: &[Data],
+    simplify_ratio: Option<f32>,
     asset_name: &str
-) -> Mesh {
-    let primitive = gltf
-        .meshes()
-        .next()
-        .unwrap_or_else(|| panic!("No mesh found for {:?}", asset_name))
+) -> EngineResult<Mesh> {
+    let mesh = gltf.meshes().next().ok_or_else(|| EngineError::asset(asset_name, "No mesh found"))?;
+    let primitive = mesh
         .primitives()
         .next()
-        .unwrap_or_else(|| panic!("No primitive found for {:?}", asset_name));
+        .ok_or_else(|| EngineError::asset(asset_name, "No primitive found"))?;
 
     macro_rules! extract {
         ($sem:expr, $ty:ty) => {
             extract_buffer_data::<$ty>(&buffers, &primitive.get(&$sem)
-                .unwrap_or_else(|| panic!("Missing {} for {:?}", stringify!($sem), asset_name)))
-                .unwrap_or_else(|e| panic!("Failed to extract {} for {:?}: {}", stringify!($sem), asset_name, e))
+                .ok_or_else(|| EngineError::asset(asset_name, format!("Missing {}", stringify!($sem))))?)
+                .map_err(|e| EngineError::asset(asset_name, format!("Failed to extract {}: {}", stringify!($sem), e)))?
         };
     }
 
@@ -37,71 +55,208 @@ pub fn extract_mesh(
     let positions: Vec<f32> = extract!(gltf::Semantic::Positions, f32);
     let normals: Vec<f32> = extract!(gltf::Semantic::Normals, f32);
     let tex_coords: Vec<f32> = extract!(gltf::Semantic::TexCoords(0), f32);
-    let indices: Vec<u16> = extract_buffer_data(
-        &buffers,
-        &primitive.indices().unwrap_or_else(|| panic!("No indices found for {:?}", asset_name))
-    ).unwrap_or_else(|e| panic!("Failed to extract indices for {:?}: {}", asset_name, e));
+    let colors = extract_colors(&primitive, buffers, positions.len() / 3);
+    // Read whatever the accessor's component type is (u8, u16 or u32); upload_mesh narrows them back when it can
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| data.0.as_slice()));
+    let indices: Vec<u32> = reader
+        .read_indices()
+        .ok_or_else(|| EngineError::asset(asset_name, "No indices found"))?
+        .into_u32()
+        .collect();
 
     // Extract skeletal data (optional - only for animated meshes)
     let joints: Option<Vec<u8>> = extract_optional!(gltf::Semantic::Joints(0), u8);
     let weights: Option<Vec<f32>> = extract_optional!(gltf::Semantic::Weights(0), f32);
 
-    let has_skeletal_data = joints.is_some() && weights.is_some();
-
-    unsafe {
-        let vao = gl.create_vertex_array()
-            .unwrap_or_else(|e| panic!("Failed to create VAO for {:?}: {}", asset_name, e));
-        gl.bind_vertex_array(Some(vao));
-
-        let setup_attrib = |loc, data: &[u8], size, ty, stride, int| {
-            let buf = gl.create_buffer().unwrap();
-            gl.bind_buffer(glow::ARRAY_BUFFER, Some(buf));
-            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, data, glow::STATIC_DRAW);
-            gl.enable_vertex_attrib_array(loc);
-            if int {
-                gl.vertex_attrib_pointer_i32(loc, size, ty, stride, 0);
-            } else {
-                gl.vertex_attrib_pointer_f32(loc, size, ty, false, stride, 0);
-            }
-        };
+    let vertex_count = positions.len() / 3;
+    let attributes = MeshAttributes { positions, normals, tex_coords, colors, indices };
+    let mut uploaded = upload_mesh(gl, &attributes, joints.zip(weights), asset_name)?;
+    uploaded.morph = extract_morph_targets(gl, &mesh, &primitive, buffers, vertex_count, asset_name)?;
+
+    if let Some(ratio) = simplify_ratio.filter(|ratio| *ratio < 1.0) {
+        if uploaded.skin.is_some() {
+            println!("⚠️  {} is skinned, only static meshes are simplified", asset_name);
+        } else {
+            let lod = upload_mesh(gl, &simplify(&attributes, ratio), None, asset_name)?;
+            println!(
+                "🔻 Simplified {} from {} to {} triangles as its LOD",
+                asset_name,
+                uploaded.index_count / 3,
+                lod.index_count / 3
+            );
+            uploaded.lod = Some(Arc::new(lod));
+        }
+    }
+    Ok(uploaded)
+}
 
-        // Set up basic mesh attributes (always present)
-        setup_attrib(1, bytemuck::cast_slice(&positions), 3, glow::FLOAT, 12, false);  // Position
-        setup_attrib(0, bytemuck::cast_slice(&normals), 3, glow::FLOAT, 12, false);    // Normal
-        setup_attrib(4, bytemuck::cast_slice(&tex_coords), 2, glow::FLOAT, 8, false);  // TexCoord
+/// The primitive's COLOR_0 as packed RGBA floats (RGB colors get alpha 1, normalized integers are scaled to 0-1);
+/// None without one or when it doesn't cover every vertex
+fn extract_colors(primitive: &gltf::Primitive, buffers: &[Data], vertex_count: usize) -> Option<Vec<f32>> {
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| data.0.as_slice()));
+    let colors: Vec<f32> = reader.read_colors(0)?.into_rgba_f32().flatten().collect();
+    (colors.len() == vertex_count * 4).then_some(colors)
+}
 
-        // Set up skeletal attributes (only if present)
-        if has_skeletal_data {
-            if let (Some(joints_data), Some(weights_data)) = (joints, weights) {
-                setup_attrib(2, &joints_data, 4, glow::UNSIGNED_BYTE, 4, true);           // Joints
-                setup_attrib(3, bytemuck::cast_slice(&weights_data), 4, glow::FLOAT, 16, false); // Weights
+/// The primitive's morph targets packed into a data texture as the skinning shaders read them; targets past
+/// MAX_MORPH_TARGETS are dropped
+fn extract_morph_targets(
+    gl: &glow::Context,
+    mesh: &gltf::Mesh,
+    primitive: &gltf::Primitive,
+    buffers: &[Data],
+    vertex_count: usize,
+    asset_name: &str
+) -> EngineResult<Option<Arc<MorphTargets>>> {
+    // The reader also resolves sparse accessors, which exporters use for targets that move few vertices
+    let reader = primitive.reader(|buffer| buffers.get(buffer.index()).map(|data| data.0.as_slice()));
+    // Position and normal displacements of each target
+    let mut targets: Vec<[Vec<[f32; 3]>; 2]> = reader
+        .read_morph_targets()
+        .map(|(positions, normals, _tangents)| {
+            [positions.map(Iterator::collect).unwrap_or_default(), normals.map(Iterator::collect).unwrap_or_default()]
+        })
+        .collect();
+    if targets.is_empty() {
+        return Ok(None);
+    }
+    if targets.len() > MAX_MORPH_TARGETS {
+        println!("⚠️  {} has {} morph targets, using the first {}", asset_name, targets.len(), MAX_MORPH_TARGETS);
+        targets.truncate(MAX_MORPH_TARGETS);
+    }
+
+    let count = targets.len();
+    let rows = (vertex_count * count * 2).div_ceil(MORPH_ROW);
+    let mut texels = vec![0.0f32; rows * MORPH_ROW * 4];
+    for (target, attributes) in targets.iter().enumerate() {
+        for (attribute, displacements) in attributes.iter().enumerate() {
+            for (vertex, displacement) in displacements.iter().take(vertex_count).enumerate() {
+                let texel = ((vertex * count + target) * 2 + attribute) * 4;
+                texels[texel..texel + 3].copy_from_slice(displacement);
             }
         }
+    }
+    let texels = bytemuck::cast_slice(&texels);
+    let texture = Texture::data(gl, MORPH_ROW as u32, rows as u32, TextureFormat::Rgba32Float, texels)
+        .map_err(|e| EngineError::texture(asset_name, format!("Failed to upload morph targets: {}", e)))?;
+
+    let mut default_weights = mesh.weights().unwrap_or_default().to_vec();
+    default_weights.resize(count, 0.0);
+    Ok(Some(Arc::new(MorphTargets { texture, count, default_weights })))
+}
 
-        let ebo = gl.create_buffer()
-            .unwrap_or_else(|e| panic!("Failed to create EBO for {:?}: {}", asset_name, e));
-        gl.bind_buffer(glow::ELEMENT_ARRAY_BUFFER, Some(ebo));
-        gl.buffer_data_u8_slice(
-            glow::ELEMENT_ARRAY_BUFFER,
-            bytemuck::cast_slice(&indices),
-            glow::STATIC_DRAW
-        );
-
-        gl.bind_vertex_array(None);
-
-        Mesh {
-            vao,
-            index_count: indices.len(),
-            vertex_count: positions.len() / 3,
+/// Unit cube used in place of meshes that failed to load (skinned to joint 0 so it also works for animated objects)
+pub fn create_placeholder_cube_mesh(gl: &glow::Context) -> EngineResult<Mesh> {
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut tex_coords = Vec::new();
+    let mut indices: Vec<u32> = Vec::new();
+
+    // One quad per face: (normal, tangent u, tangent v)
+    let faces: [([f32; 3], [f32; 3], [f32; 3]); 6] = [
+        ([1.0, 0.0, 0.0], [0.0, 0.0, -1.0], [0.0, 1.0, 0.0]),
+        ([-1.0, 0.0, 0.0], [0.0, 0.0, 1.0], [0.0, 1.0, 0.0]),
+        ([0.0, 1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, -1.0]),
+        ([0.0, -1.0, 0.0], [1.0, 0.0, 0.0], [0.0, 0.0, 1.0]),
+        ([0.0, 0.0, 1.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+        ([0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, 1.0, 0.0]),
+    ];
+
+    for (normal, u, v) in faces {
+        let base = (positions.len() / 3) as u32;
+        for (su, sv) in [(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)] {
+            for axis in 0..3 {
+                positions.push(normal[axis] * 0.5 + u[axis] * su + v[axis] * sv);
+                normals.push(normal[axis]);
+            }
+            tex_coords.extend_from_slice(&[su + 0.5, sv + 0.5]);
         }
+        indices.extend_from_slice(&[base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    let vertex_count = positions.len() / 3;
+    let joints = vec![0u8; vertex_count * 4];
+    let weights: Vec<f32> = (0..vertex_count).flat_map(|_| [1.0, 0.0, 0.0, 0.0]).collect();
+
+    let attributes = MeshAttributes { positions, normals, tex_coords, colors: None, indices };
+    upload_mesh(gl, &attributes, Some((joints, weights)), "PlaceholderCube")
+}
+
+/// Upload a mesh's vertex buffers; meshes without vertex colors get white ones, so shaders can always multiply them in
+pub fn upload_mesh(
+    gl: &glow::Context,
+    mesh: &MeshAttributes,
+    skinning: Option<(Vec<u8>, Vec<f32>)>,
+    asset_name: &str
+) -> EngineResult<Mesh> {
+    let gpu_error = |e: EngineError| match e {
+        EngineError::Gpu { message } => EngineError::gpu(format!("{} for {}", message, asset_name)),
+        other => other,
+    };
+    let upload = |data: &[u8]| Buffer::new(gl, BufferKind::Vertex, data, BufferUsage::Static).map_err(gpu_error);
+
+    // Basic mesh attributes are always present
+    let vertex_count = mesh.positions.len() / 3;
+    let position_buffer = upload(bytemuck::cast_slice(&mesh.positions))?;
+    let normal_buffer = upload(bytemuck::cast_slice(&mesh.normals))?;
+    let tex_coord_buffer = upload(bytemuck::cast_slice(&mesh.tex_coords))?;
+    let color_buffer = match &mesh.colors {
+        Some(colors) => upload(bytemuck::cast_slice(colors))?,
+        None => upload(bytemuck::cast_slice(&vec![1.0f32; vertex_count * 4]))?,
+    };
+    let mut attributes = vec![
+        VertexAttribute::packed(&position_buffer, 1, VertexFormat::Float32x3),
+        VertexAttribute::packed(&normal_buffer, 0, VertexFormat::Float32x3),
+        VertexAttribute::packed(&tex_coord_buffer, 4, VertexFormat::Float32x2),
+        VertexAttribute::packed(&color_buffer, 5, VertexFormat::Float32x4),
+    ];
+
+    // Skeletal attributes only if present
+    let skin_buffers = match &skinning {
+        Some((joints_data, weights_data)) => Some((upload(joints_data)?, upload(bytemuck::cast_slice(weights_data))?)),
+        None => None,
+    };
+    if let Some((joint_buffer, weight_buffer)) = &skin_buffers {
+        attributes.push(VertexAttribute::packed(joint_buffer, 2, VertexFormat::Uint8x4));
+        attributes.push(VertexAttribute::packed(weight_buffer, 3, VertexFormat::Float32x4));
     }
+
+    // 16-bit indices unless a vertex past 65535 is referenced
+    let index_buffer = if mesh.indices.iter().all(|index| *index <= u16::MAX as u32) {
+        let narrow: Vec<u16> = mesh.indices.iter().map(|index| *index as u16).collect();
+        Buffer::index(gl, &narrow).map_err(gpu_error)?
+    } else {
+        Buffer::index32(gl, &mesh.indices).map_err(gpu_error)?
+    };
+    let geometry = Geometry::new(gl, &attributes, Some(&index_buffer)).map_err(gpu_error)?;
+
+    Ok(Mesh {
+        geometry,
+        index_count: mesh.indices.len(),
+        vertex_count,
+        bounds: Mesh::compute_bounds(&mesh.positions),
+        skin: skin_buffers.map(|(joints, weights)| SkinBuffers {
+            positions: position_buffer,
+            normals: normal_buffer,
+            joints,
+            weights,
+        }),
+        morph: None,
+        lod: None,
+        vertex_colors: mesh.colors.is_some(),
+        data: Some(Arc::new(MeshData {
+            positions: mesh.positions.clone(),
+            normals: mesh.normals.clone(),
+            indices: mesh.indices.clone(),
+        })),
+    })
 }
 
 pub fn extract_skeleton(
     gltf: &gltf::Gltf,
     buffers: &[Data],
     asset_name: &str
-) -> Skeleton {
+) -> EngineResult<Skeleton> {
     let mut node_parents = vec![u32::MAX; gltf.nodes().len()];
     for node in gltf.nodes() {
         for child in node.children() {
@@ -130,7 +285,7 @@ pub fn extract_skeleton(
         let mut inv_mats = Vec::new();
         if let Some(ibm) = skin.inverse_bind_matrices() {
             let data: Vec<f32> = extract_buffer_data(&buffers, &ibm)
-                .unwrap_or_else(|e| panic!("Failed to extract inverse bind matrices for {:?}: {}", asset_name, e));
+                .map_err(|e| EngineError::asset(asset_name, format!("Failed to extract inverse bind matrices: {}", e)))?;
             inv_mats = data
                 .chunks(16)
                 .map(|m| {
@@ -142,33 +297,31 @@ pub fn extract_skeleton(
         }
         (ids, inv_mats)
     } else {
-        panic!("No skeleton/skin found for animated asset {:?}", asset_name);
+        return Err(EngineError::asset(asset_name, "No skeleton/skin found for animated asset"));
     };
 
     if nodes.is_empty() {
-        panic!("No nodes found for skeleton in {:?}", asset_name);
+        return Err(EngineError::asset(asset_name, "No nodes found for skeleton"));
     }
 
-    Skeleton {
+    Ok(Skeleton {
         nodes,
         joint_ids,
         joint_inverse_mats,
-    }
+    })
 }
 
-pub fn extract_animation_channels(gltf: &gltf::Gltf, buffers: &[Data], _asset_name: &str) -> Vec<AnimationChannel> {
+/// Every animation of the file as a clip, named after the animation or its index when unnamed
+pub fn extract_animation_clips(gltf: &gltf::Gltf, buffers: &[Data], _asset_name: &str) -> Vec<AnimationClip> {
     gltf.animations()
-        .next()
         .map(|anim| {
-            anim.channels()
+            let channels: Vec<AnimationChannel> = anim.channels()
                 .filter_map(|chan| {
                     let anim_type = match chan.target().property() {
                         gltf::animation::Property::Translation => AnimationType::Translation,
                         gltf::animation::Property::Rotation => AnimationType::Rotation,
                         gltf::animation::Property::Scale => AnimationType::Scale,
-                        _ => {
-                            return None;
-                        }
+                        gltf::animation::Property::MorphTargetWeights => AnimationType::Weights,
                     };
 
                     let times = extract_buffer_data::<f32>(&buffers, &chan.sampler().input()).ok()?;
@@ -182,14 +335,63 @@ pub fn extract_animation_channels(gltf: &gltf::Gltf, buffers: &[Data], _asset_na
                         data,
                     })
                 })
-                .collect()
+                .collect();
+            let name = anim.name().map(str::to_string).unwrap_or_else(|| format!("Animation {}", anim.index()));
+            AnimationClip::new(name, channels)
         })
-        .unwrap_or_default()
+        .collect()
 }
 
 use image::io::Reader as ImageReader;
 use std::io::Cursor;
 
+/// Data of every buffer: a .glb's binary chunk for the one it embeds, and external files through `read_uri`
+pub fn read_buffers(
+    gltf: &gltf::Gltf,
+    read_uri: impl Fn(&str) -> EngineResult<Vec<u8>>,
+    asset_name: &str
+) -> EngineResult<Vec<Data>> {
+    gltf.buffers()
+        .map(|buffer| {
+            let data = match buffer.source() {
+                gltf::buffer::Source::Bin =>
+                    gltf.blob.clone().ok_or_else(|| EngineError::asset(asset_name, "Missing binary chunk"))?,
+                gltf::buffer::Source::Uri(uri) => read_uri(uri)?,
+            };
+            Ok(Data(data))
+        })
+        .collect()
+}
+
+/// Image the base color texture of the first material uses, or empty without one: from a buffer view when the file
+/// embeds it, as .glb exports do, otherwise through `read_uri`
+pub fn read_base_color_image(
+    gltf: &gltf::Gltf,
+    buffers: &[Data],
+    read_uri: impl Fn(&str) -> EngineResult<Vec<u8>>,
+    asset_name: &str
+) -> EngineResult<Vec<u8>> {
+    let image = gltf
+        .materials()
+        .next()
+        .and_then(|material| material.pbr_metallic_roughness().base_color_texture())
+        .map(|info| info.texture().source())
+        .or_else(|| gltf.images().next());
+    match image.map(|image| image.source()) {
+        Some(gltf::image::Source::Uri { uri, .. }) => read_uri(uri),
+        Some(gltf::image::Source::View { view, .. }) => {
+            let buffer = buffers
+                .get(view.buffer().index())
+                .ok_or_else(|| EngineError::texture(asset_name, "Image buffer not found"))?;
+            buffer
+                .get(view.offset()..view.offset() + view.length())
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| EngineError::texture(asset_name, "Image view is out of its buffer"))
+        }
+        None => Ok(Vec::new()),
+    }
+}
+
 // Proper PNG decoder using the image crate
 fn decode_png_with_crate(png_data: &[u8]) -> Result<(u32, u32, Vec<u8>), Box<dyn std::error::Error>> {
     let img = ImageReader::new(Cursor::new(png_data))
@@ -234,18 +436,20 @@ pub fn extract_material(
     gltf: &gltf::Gltf,
     _buffers: &[Data],
     png_data: &[u8],
-    shader_program: glow::Program,
+    pipeline: Pipeline,
     asset_name: &str
-) -> Material {
+) -> EngineResult<Material> {
     let material = gltf.materials().next()
-        .unwrap_or_else(|| panic!("No material found for {:?}", asset_name));
+        .ok_or_else(|| EngineError::asset(asset_name, "No material found"))?;
     
     let pbr = material.pbr_metallic_roughness();
     
-    let mut mat = Material::new(shader_program);
+    let mut mat = Material::new(pipeline);
     mat.metallic_factor = pbr.metallic_factor();
     mat.roughness_factor = pbr.roughness_factor();
     mat.double_sided = material.double_sided();
+    mat.transparent = material.alpha_mode() == gltf::material::AlphaMode::Blend;
+    mat.opacity = pbr.base_color_factor()[3];
 
     // Extract texture if present
     if let Some(base_color_info) = pbr.base_color_texture() {
@@ -255,45 +459,27 @@ pub fn extract_material(
                 
                 match decode_png_with_crate(png_data) {
                     Ok((width, height, rgba_pixels)) => {
-                        unsafe {
-                            let gl_texture = gl.create_texture()
-                                .unwrap_or_else(|e| panic!("Failed to create texture for {:?}: {}", asset_name, e));
-                            gl.bind_texture(glow::TEXTURE_2D, Some(gl_texture));
-                            
-                            gl.tex_image_2d(
-                                glow::TEXTURE_2D,
-                                0,
-                                glow::RGBA as i32,
-                                width as i32,
-                                height as i32,
-                                0,
-                                glow::RGBA,
-                                glow::UNSIGNED_BYTE,
-                                glow::PixelUnpackData::Slice(Some(&rgba_pixels))
-                            );
-                            
-                            // Set texture parameters
-                            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
-                            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
-                            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_S, glow::REPEAT as i32);
-                            gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_WRAP_T, glow::REPEAT as i32);
-                            
-                            gl.bind_texture(glow::TEXTURE_2D, None);
-                            
-                            mat.base_color_texture = Some(gl_texture);
-                            
-                            println!("✅ Texture loaded: {}x{} pixels", width, height);
-                        }
+                        let filter = match texture.sampler().mag_filter() {
+                            Some(gltf::texture::MagFilter::Nearest) => TextureFilter::Nearest,
+                            _ => TextureFilter::Linear,
+                        };
+                        let wrap = TextureWrap::Repeat;
+                        let texture = TextureStreaming::load(gl, width, height, &rgba_pixels, filter, wrap)
+                            .map_err(|e| EngineError::texture(asset_name, e.to_string()))?;
+                        mat.base_color_texture = Some(texture);
+
+                        println!("✅ Texture loaded: {}x{} pixels", width, height);
                     }
+                    // Only PNG decoding is built in; other images (JPEG in .glb exports) leave the mesh untextured
                     Err(e) => {
-                        panic!("Failed to decode PNG for {:?}: {}", asset_name, e);
+                        println!("⚠️ {}: base color image not decoded ({}), loading it untextured", asset_name, e);
                     }
                 }
             }
         }
     }
 
-    mat
+    Ok(mat)
 }
 
 pub fn extract_buffer_data<T: bytemuck::Pod>(
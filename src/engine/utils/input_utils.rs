@@ -1,3 +1,5 @@
+use crate::index::engine::modules::Console;
+
 #[allow(dead_code)]
 pub fn calculate_movement_direction(w: bool, a: bool, s: bool, d: bool) -> String {
     let forward = w && !s;
@@ -71,7 +73,7 @@ pub fn calculate_movement_direction_3d(w: bool, a: bool, s: bool, d: bool, up: b
 }
 
 pub fn mouse_delta_to_euler(delta_x: f64, delta_y: f64) -> [f32; 2] {
-    let sensitivity = 0.002;
+    let sensitivity = Console::cvar("mouse_sensitivity").unwrap_or(0.002) as f64;
     let yaw_delta = (delta_x * sensitivity) as f32;
     // Natural FPS camera feel: mouse up -> look up, mouse down -> look down
     let pitch_delta = (delta_y * sensitivity) as f32;
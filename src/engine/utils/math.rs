@@ -138,6 +138,18 @@ pub fn mat4x4_perspective(fov_y_radians: f32, aspect_ratio: f32, near: f32, far:
     ]
 }
 
+/// Symmetric orthographic projection centred on the view axis
+pub fn mat4x4_orthographic(half_width: f32, half_height: f32, near: f32, far: f32) -> Mat4x4 {
+    let range_inv = 1.0 / (far - near);
+
+    [
+        1.0 / half_width, 0.0,               0.0,              0.0,
+        0.0,              1.0 / half_height, 0.0,              0.0,
+        0.0,              0.0,               -2.0 * range_inv, -(far + near) * range_inv,
+        0.0,              0.0,               0.0,              1.0,
+    ]
+}
+
 // Linear interpolation utility function
 pub fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a * (1.0 - t) + b * t
@@ -166,6 +178,68 @@ pub fn build_view_matrix(pos: [f32; 3], pitch: f32, yaw: f32) -> Mat4x4 {
     ]
 }
 
+/// View matrix of a camera at `eye` looking along `forward` (normalized) with `up` towards the top of the image
+pub fn mat4x4_look_at(eye: [f32; 3], forward: [f32; 3], up: [f32; 3]) -> Mat4x4 {
+    let right = normalize(cross(forward, up));
+    let up = cross(right, forward);
+
+    [
+        right[0],    right[1],    right[2],    -dot(right, eye),
+        up[0],       up[1],       up[2],       -dot(up, eye),
+        -forward[0], -forward[1], -forward[2], dot(forward, eye),
+        0.0,         0.0,         0.0,         1.0,
+    ]
+}
+
+/// Camera position of a perspective view-projection matrix (the point clip x, y and w are all 0 at), None for
+/// orthographic ones, whose camera is infinitely far away
+pub fn view_projection_eye(view_proj: &Mat4x4) -> Option<[f32; 3]> {
+    let rows = [0, 1, 3].map(|row| [view_proj[row * 4], view_proj[row * 4 + 1], view_proj[row * 4 + 2]]);
+    let constants = [0, 1, 3].map(|row| -view_proj[row * 4 + 3]);
+    let determinant = dot(rows[0], cross(rows[1], rows[2]));
+    if determinant.abs() < 1e-8 {
+        return None;
+    }
+    // Cramer's rule
+    let solve = |column: usize| {
+        let mut replaced = rows;
+        for (row, constant) in replaced.iter_mut().zip(constants) {
+            row[column] = constant;
+        }
+        dot(replaced[0], cross(replaced[1], replaced[2])) / determinant
+    };
+    Some([solve(0), solve(1), solve(2)])
+}
+
+/// Conservative frustum test: reject only if all corners are outside one clip plane. `view_proj` may include the
+/// world transform, to test local bounds as the box they become in the world
+pub fn aabb_in_frustum(view_proj: &Mat4x4, min: Vec3, max: Vec3) -> bool {
+    let corners: Vec<[f32; 4]> = (0..8)
+        .map(|i| {
+            let corner = [
+                if i & 1 == 0 { min[0] } else { max[0] },
+                if i & 2 == 0 { min[1] } else { max[1] },
+                if i & 4 == 0 { min[2] } else { max[2] },
+            ];
+            let mut clip = [0.0; 4];
+            for (row, value) in clip.iter_mut().enumerate() {
+                *value = view_proj[row * 4] * corner[0] +
+                    view_proj[row * 4 + 1] * corner[1] +
+                    view_proj[row * 4 + 2] * corner[2] +
+                    view_proj[row * 4 + 3];
+            }
+            clip
+        })
+        .collect();
+
+    for axis in 0..3 {
+        if corners.iter().all(|clip| clip[axis] < -clip[3]) || corners.iter().all(|clip| clip[axis] > clip[3]) {
+            return false;
+        }
+    }
+    true
+}
+
 // Calculate world transform for a node in a skeleton hierarchy
 #[allow(dead_code)]
 pub fn node_world_txfm(nodes: &[crate::index::engine::components::AnimatedObject3D::Node], idx: usize) -> Mat4x4 {
@@ -232,6 +306,54 @@ pub fn mat4x4_extract_euler_angles(matrix: &Mat4x4) -> [f32; 3] {
     [pitch, yaw, roll]
 }
 
+/// Quaternion ([x, y, z, w]) rotating `angle` radians around the Y axis
+pub fn quat_from_yaw(angle: f32) -> [f32; 4] {
+    let half = angle * 0.5;
+    [0.0, half.sin(), 0.0, half.cos()]
+}
+
+/// Hamilton product of two [x, y, z, w] quaternions (applies `b` first, then `a`)
+pub fn quat_mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4] {
+    let [ax, ay, az, aw] = a;
+    let [bx, by, bz, bw] = b;
+    [
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+        aw * bw - ax * bx - ay * by - az * bz,
+    ]
+}
+
+/// Inverse of a unit [x, y, z, w] quaternion
+pub fn quat_conjugate(q: [f32; 4]) -> [f32; 4] {
+    [-q[0], -q[1], -q[2], q[3]]
+}
+
+/// Rotate a vector by a unit [x, y, z, w] quaternion
+pub fn quat_rotate(q: [f32; 4], v: Vec3) -> Vec3 {
+    let axis = [q[0], q[1], q[2]];
+    let t = cross(axis, v);
+    let t = [t[0] * 2.0, t[1] * 2.0, t[2] * 2.0];
+    let u = cross(axis, t);
+    [v[0] + q[3] * t[0] + u[0], v[1] + q[3] * t[1] + u[1], v[2] + q[3] * t[2] + u[2]]
+}
+
+/// Shortest-arc rotation taking direction `from` onto direction `to` (neither needs to be normalized)
+pub fn quat_from_to(from: Vec3, to: Vec3) -> [f32; 4] {
+    let (from, to) = (normalize(from), normalize(to));
+    let d = dot(from, to);
+    if d < -0.9999 {
+        // Opposite directions: half turn around any perpendicular axis
+        let axis = if from[0].abs() < 0.9 { cross(from, [1.0, 0.0, 0.0]) } else { cross(from, [0.0, 1.0, 0.0]) };
+        let axis = normalize(axis);
+        return [axis[0], axis[1], axis[2], 0.0];
+    }
+    let axis = cross(from, to);
+    let q = [axis[0], axis[1], axis[2], 1.0 + d];
+    let length = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    [q[0] / length, q[1] / length, q[2] / length, q[3] / length]
+}
+
 // ================================================================================================
 // COLLISION DETECTION MATH UTILITIES
 // ================================================================================================
@@ -257,31 +379,36 @@ pub fn len2(v: Vec3) -> f32 {
     dot(v, v)
 }
 
+/// Unit vector in the direction of `v` (zero vectors stay zero)
+pub fn normalize(v: Vec3) -> Vec3 {
+    let length = len2(v).sqrt();
+    if length > 1e-8 { [v[0] / length, v[1] / length, v[2] / length] } else { [0.0, 0.0, 0.0] }
+}
+
 /// Squared distance between two 3D points
 pub fn dist2(a: Vec3, b: Vec3) -> f32 {
     let d = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
     len2(d)
 }
 
-/// Computes squared distance between point p and line segment ab
-pub fn dist_point_segment2(p: Vec3, a: Vec3, b: Vec3) -> f32 {
+/// Closest point to p on line segment ab
+pub fn closest_point_segment(p: Vec3, a: Vec3, b: Vec3) -> Vec3 {
     let ab = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
     let ap = [p[0] - a[0], p[1] - a[1], p[2] - a[2]];
     let ab2 = dot(ab, ab);
     
     if ab2 < 1e-8 {
         // Degenerate segment, treat as point
-        return dist2(p, a);
+        return a;
     }
     
     let t = (dot(ap, ab) / ab2).max(0.0).min(1.0);
-    let closest = [a[0] + ab[0] * t, a[1] + ab[1] * t, a[2] + ab[2] * t];
-    dist2(p, closest)
+    [a[0] + ab[0] * t, a[1] + ab[1] * t, a[2] + ab[2] * t]
 }
 
-/// Computes squared distance between two line segments
+/// Closest points of two line segments, one on each
 /// Based on "Distance between 3D line segments" algorithm
-pub fn segment_segment_distance2(a1: Vec3, a2: Vec3, b1: Vec3, b2: Vec3) -> f32 {
+pub fn segment_segment_closest(a1: Vec3, a2: Vec3, b1: Vec3, b2: Vec3) -> (Vec3, Vec3) {
     let d1 = [a2[0] - a1[0], a2[1] - a1[1], a2[2] - a1[2]];
     let d2 = [b2[0] - b1[0], b2[1] - b1[1], b2[2] - b1[2]];
     let r = [a1[0] - b1[0], a1[1] - b1[1], a1[2] - b1[2]];
@@ -293,17 +420,17 @@ pub fn segment_segment_distance2(a1: Vec3, a2: Vec3, b1: Vec3, b2: Vec3) -> f32
     // Check if either or both segments degenerate into points
     if a <= 1e-8 && e <= 1e-8 {
         // Both segments are points
-        return dist2(a1, b1);
+        return (a1, b1);
     }
     
     if a <= 1e-8 {
         // First segment is a point
-        return dist_point_segment2(a1, b1, b2);
+        return (a1, closest_point_segment(a1, b1, b2));
     }
     
     if e <= 1e-8 {
         // Second segment is a point
-        return dist_point_segment2(b1, a1, a2);
+        return (closest_point_segment(b1, a1, a2), b1);
     }
     
     let c = dot(d1, r);
@@ -330,5 +457,61 @@ pub fn segment_segment_distance2(a1: Vec3, a2: Vec3, b1: Vec3, b2: Vec3) -> f32
     let c1 = [a1[0] + d1[0] * s, a1[1] + d1[1] * s, a1[2] + d1[2] * s];
     let c2 = [b1[0] + d2[0] * t, b1[1] + d2[1] * t, b1[2] + d2[2] * t];
     
-    dist2(c1, c2)
+    (c1, c2)
+}
+
+/// Distance along a ray (normalized `dir`) to where it enters an AABB, 0 if it starts inside, None on a miss
+pub fn ray_aabb(origin: Vec3, dir: Vec3, min: Vec3, max: Vec3) -> Option<f32> {
+    let mut t_near = 0.0f32;
+    let mut t_far = f32::MAX;
+    for axis in 0..3 {
+        if dir[axis].abs() < 1e-8 {
+            if origin[axis] < min[axis] || origin[axis] > max[axis] {
+                return None;
+            }
+            continue;
+        }
+        let inv = 1.0 / dir[axis];
+        let (t0, t1) = ((min[axis] - origin[axis]) * inv, (max[axis] - origin[axis]) * inv);
+        t_near = t_near.max(t0.min(t1));
+        t_far = t_far.min(t0.max(t1));
+        if t_near > t_far {
+            return None;
+        }
+    }
+    Some(t_near)
+}
+
+/// Distance along a ray (normalized `dir`) to a plane, None if parallel or behind the origin
+pub fn ray_plane(origin: Vec3, dir: Vec3, point: Vec3, normal: Vec3) -> Option<f32> {
+    let denom = dot(dir, normal);
+    if denom.abs() < 1e-6 {
+        return None;
+    }
+    let t = dot([point[0] - origin[0], point[1] - origin[1], point[2] - origin[2]], normal) / denom;
+    (t >= 0.0).then_some(t)
+}
+
+/// Distance along a ray (normalized `dir`) to a triangle, from either side (Möller–Trumbore), None on a miss
+pub fn ray_triangle(origin: Vec3, dir: Vec3, [a, b, c]: [Vec3; 3]) -> Option<f32> {
+    let edge1 = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let edge2 = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let p = cross(dir, edge2);
+    let det = dot(edge1, p);
+    if det.abs() < 1e-8 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    let to_origin = [origin[0] - a[0], origin[1] - a[1], origin[2] - a[2]];
+    let u = dot(to_origin, p) * inv_det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = cross(to_origin, edge1);
+    let v = dot(dir, q) * inv_det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = dot(edge2, q) * inv_det;
+    (t >= 0.0).then_some(t)
 }
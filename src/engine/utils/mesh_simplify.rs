@@ -0,0 +1,131 @@
+use std::collections::{ HashMap, HashSet };
+
+use crate::index::engine::components::SharedComponents::Mesh;
+
+/// Grid cells along the longest side of the bounds at the finest clustering tried
+const MAX_RESOLUTION: u32 = 1024;
+
+/// Vertex attributes and triangles of a mesh, packed as they're uploaded
+pub struct MeshAttributes {
+    pub positions: Vec<f32>,
+    pub normals: Vec<f32>,
+    pub tex_coords: Vec<f32>,
+    pub colors: Option<Vec<f32>>, // RGBA per vertex
+    pub indices: Vec<u32>,
+}
+
+/// Decimate a mesh to at most `ratio` of its triangles by vertex clustering: vertices are snapped to a grid and the
+/// ones sharing a cell merged (average position and normal, the first one's UV and color), dropping the triangles that
+/// collapse. The finest grid that meets the target is used, so small features and UV seams go before the silhouette
+/// does; fine for blockout stand-ins and distant LODs, not for close-ups
+pub fn simplify(mesh: &MeshAttributes, ratio: f32) -> MeshAttributes {
+    let triangle_count = mesh.indices.len() / 3;
+    let target = ((triangle_count as f32 * ratio.clamp(0.0, 1.0)) as usize).max(1);
+    let (min, max) = Mesh::compute_bounds(&mesh.positions);
+    let extent = (0..3).map(|axis| max[axis] - min[axis]).fold(0.0f32, f32::max);
+    if target >= triangle_count || extent <= 0.0 {
+        return MeshAttributes {
+            positions: mesh.positions.clone(),
+            normals: mesh.normals.clone(),
+            tex_coords: mesh.tex_coords.clone(),
+            colors: mesh.colors.clone(),
+            indices: mesh.indices.clone(),
+        };
+    }
+
+    // Finer grids keep more triangles; find the finest one within the target
+    let (mut coarse, mut fine) = (1, MAX_RESOLUTION);
+    let mut best = cluster(mesh, min, extent, coarse);
+    while coarse < fine {
+        let resolution = (coarse + fine).div_ceil(2);
+        let attempt = cluster(mesh, min, extent, resolution);
+        if attempt.1.len() <= target {
+            best = attempt;
+            coarse = resolution;
+        } else {
+            fine = resolution - 1;
+        }
+    }
+    let (clusters, triangles) = best;
+
+    // One vertex per cell a kept triangle uses, numbered in first use
+    let mut remap: HashMap<u32, u32> = HashMap::new();
+    let mut sums: Vec<([f32; 3], [f32; 3], usize, usize)> = Vec::new(); // Position and normal sums, count, first vertex
+    let indices: Vec<u32> = triangles
+        .iter()
+        .flatten()
+        .map(|cell| {
+            *remap.entry(*cell).or_insert_with(|| {
+                sums.push(([0.0; 3], [0.0; 3], 0, 0));
+                (sums.len() - 1) as u32
+            })
+        })
+        .collect();
+    for (vertex, cell) in clusters.iter().enumerate() {
+        let Some(&index) = remap.get(cell) else {
+            continue;
+        };
+        let (position, normal, count, first) = &mut sums[index as usize];
+        if *count == 0 {
+            *first = vertex;
+        }
+        for axis in 0..3 {
+            position[axis] += mesh.positions[vertex * 3 + axis];
+            normal[axis] += mesh.normals.get(vertex * 3 + axis).copied().unwrap_or_default();
+        }
+        *count += 1;
+    }
+
+    let mut simplified = MeshAttributes {
+        positions: Vec::with_capacity(sums.len() * 3),
+        normals: Vec::with_capacity(sums.len() * 3),
+        tex_coords: Vec::with_capacity(sums.len() * 2),
+        colors: mesh.colors.as_ref().map(|_| Vec::with_capacity(sums.len() * 4)),
+        indices,
+    };
+    for (position, normal, count, first) in sums {
+        simplified.positions.extend(position.map(|sum| sum / count as f32));
+        let length = (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt().max(f32::EPSILON);
+        simplified.normals.extend(normal.map(|sum| sum / length));
+        let tex_coord = mesh.tex_coords.get(first * 2..first * 2 + 2).unwrap_or(&[0.0, 0.0]);
+        simplified.tex_coords.extend_from_slice(tex_coord);
+        if let (Some(colors), Some(source)) = (&mut simplified.colors, &mesh.colors) {
+            colors.extend_from_slice(&source[first * 4..first * 4 + 4]);
+        }
+    }
+    simplified
+}
+
+/// Grid cell of each vertex with `resolution` cells along the longest side, and the triangles that don't collapse
+/// (each listed once)
+fn cluster(mesh: &MeshAttributes, min: [f32; 3], extent: f32, resolution: u32) -> (Vec<u32>, Vec<[u32; 3]>) {
+    let cell_size = extent / resolution as f32;
+    let mut cells: HashMap<[u32; 3], u32> = HashMap::new();
+    let clusters: Vec<u32> = mesh.positions
+        .chunks_exact(3)
+        .map(|position| {
+            let key = [0, 1, 2].map(|axis| (((position[axis] - min[axis]) / cell_size) as u32).min(resolution - 1));
+            let next = cells.len() as u32;
+            *cells.entry(key).or_insert(next)
+        })
+        .collect();
+
+    let mut seen = HashSet::new();
+    let triangles = mesh.indices
+        .chunks_exact(3)
+        .filter_map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|corner| clusters[triangle[corner] as usize]);
+            if a == b || b == c || a == c {
+                return None;
+            }
+            // Rotated to start at the lowest cell, so the same triangle with the same winding matches
+            let canonical = match a.min(b).min(c) {
+                lowest if lowest == a => [a, b, c],
+                lowest if lowest == b => [b, c, a],
+                _ => [c, a, b],
+            };
+            seen.insert(canonical).then_some([a, b, c])
+        })
+        .collect();
+    (clusters, triangles)
+}
@@ -1,6 +1,9 @@
 pub mod math;
 pub mod input_utils;
 pub mod gltf_loader_utils;
+pub mod triangle_bvh;
+pub mod mesh_simplify;
 
 // Re-export commonly used types
 pub use math::*;
+pub use triangle_bvh::TriangleBvh;
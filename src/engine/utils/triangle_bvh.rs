@@ -0,0 +1,88 @@
+use crate::index::engine::utils::math::{ ray_aabb, ray_triangle, Vec3 };
+
+/// Triangles per leaf; below this splitting costs more than testing them all
+const LEAF_TRIANGLES: usize = 4;
+
+/// Box node: children for inner nodes, a range of `order` for leaves
+struct Node {
+    min: Vec3,
+    max: Vec3,
+    children: Option<(usize, usize)>,
+    first: usize,
+    count: usize,
+}
+
+/// Bounding volume hierarchy over world-space triangles for ray casts against static geometry (bakes)
+pub struct TriangleBvh {
+    triangles: Vec<[Vec3; 3]>,
+    order: Vec<usize>,
+    nodes: Vec<Node>,
+}
+
+impl TriangleBvh {
+    pub fn new(triangles: Vec<[Vec3; 3]>) -> Self {
+        let mut bvh = Self { order: (0..triangles.len()).collect(), triangles, nodes: Vec::new() };
+        if !bvh.triangles.is_empty() {
+            bvh.build(0, bvh.triangles.len());
+        }
+        bvh
+    }
+
+    /// Split [first, first + count) of `order` at the median centroid of its longest axis; returns the node index
+    fn build(&mut self, first: usize, count: usize) -> usize {
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for &triangle in &self.order[first..first + count] {
+            for vertex in self.triangles[triangle] {
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(vertex[axis]);
+                    max[axis] = max[axis].max(vertex[axis]);
+                }
+            }
+        }
+        let index = self.nodes.len();
+        self.nodes.push(Node { min, max, children: None, first, count });
+        if count <= LEAF_TRIANGLES {
+            return index;
+        }
+
+        let extent = [max[0] - min[0], max[1] - min[1], max[2] - min[2]];
+        let axis = (0..3).max_by(|&a, &b| extent[a].total_cmp(&extent[b])).unwrap_or(0);
+        let triangles = &self.triangles;
+        let centroid = |triangle: usize| triangles[triangle].iter().map(|vertex| vertex[axis]).sum::<f32>();
+        let half = count / 2;
+        self.order[first..first + count].select_nth_unstable_by(half, |&a, &b| centroid(a).total_cmp(&centroid(b)));
+
+        let left = self.build(first, half);
+        let right = self.build(first + half, count - half);
+        self.nodes[index].children = Some((left, right));
+        index
+    }
+
+    /// Whether a ray (normalized `dir`) hits any triangle between `min_distance` and `max_distance`
+    pub fn occluded(&self, origin: Vec3, dir: Vec3, min_distance: f32, max_distance: f32) -> bool {
+        if self.nodes.is_empty() {
+            return false;
+        }
+        let mut stack = vec![0];
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+            if ray_aabb(origin, dir, node.min, node.max).is_none_or(|distance| distance > max_distance) {
+                continue;
+            }
+            match node.children {
+                Some((left, right)) => stack.extend_from_slice(&[left, right]),
+                None => {
+                    let hit = self.order[node.first..node.first + node.count]
+                        .iter()
+                        .filter_map(|&triangle| ray_triangle(origin, dir, self.triangles[triangle]))
+                        .any(|distance| distance > min_distance && distance <= max_distance);
+                    if hit {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
@@ -1,9 +1,9 @@
 use crate::index::engine::modules::{ spawn, EntityId };
-use crate::index::engine::components::{ Transform, Metadata, Collider, Shape, ColliderLayer };
+use crate::index::engine::components::{ Transform, Metadata, EntityCategory, Collider, Shape, ColliderLayer };
 use crate::index::engine::managers::assets_manager::{ Assets, get_static_object_copy };
 use crate::index::PLAYER_ENTITY_ID;
 
-fn get_player_position() -> [f32; 3] {
+pub fn get_player_position() -> [f32; 3] {
     let player_id_guard = PLAYER_ENTITY_ID.read().unwrap();
     if let Some(player_id) = player_id_guard.as_ref() {
         if let Some(mut transform) = crate::get_query_by_id!(player_id, (Transform)) {
@@ -23,7 +23,7 @@ pub fn spawn_blockout_platform() -> EntityId {
         block_entity_id.clone(),
         get_static_object_copy(Assets::BlockoutPlatform),
         Transform::new(player_position[0], player_position[1], player_position[2]),
-        Metadata::new("Blockout Platform", None, None),
+        Metadata::new("Blockout Platform", None, None).with_category(EntityCategory::Prop),
         Collider::new(
             Shape::Box { half_extents: [3.0, 3.0, 3.0] },
             ColliderLayer::Environment,
@@ -1,5 +1,5 @@
 use crate::index::engine::modules::{spawn, EntityId};
-use crate::index::engine::components::{Transform, Metadata};
+use crate::index::engine::components::{Transform, Metadata, EntityCategory};
 use crate::index::engine::managers::assets_manager::{Assets, get_static_object_copy};
 
 #[allow(dead_code)]
@@ -9,7 +9,7 @@ pub fn spawn_chair() -> EntityId {
         chair_entity_id.clone(),
         get_static_object_copy(Assets::Chair),
         Transform::new(2.0, -3.0, -5.0),
-        Metadata::new("Chair", None, None)
+        Metadata::new("Chair", None, None).with_category(EntityCategory::Prop)
     );
     chair_entity_id
 }
@@ -0,0 +1,28 @@
+use crate::index::engine::modules::{spawn, EntityId};
+use crate::index::engine::components::{Transform, Metadata, EntityCategory};
+use crate::index::engine::managers::assets_manager::{
+    Assets,
+    get_animated_object_copy,
+    get_static_object_copy,
+    is_animated_asset,
+};
+
+pub fn spawn_imported_model(asset_name: Assets, title: &str) -> EntityId {
+    let model_entity_id = spawn();
+    if is_animated_asset(asset_name) {
+        crate::insert_many!(
+            model_entity_id.clone(),
+            get_animated_object_copy(asset_name),
+            Transform::new(0.0, 0.0, -5.0),
+            Metadata::new(title, None, None).with_category(EntityCategory::Prop)
+        );
+    } else {
+        crate::insert_many!(
+            model_entity_id.clone(),
+            get_static_object_copy(asset_name),
+            Transform::new(0.0, 0.0, -5.0),
+            Metadata::new(title, None, None).with_category(EntityCategory::Prop)
+        );
+    }
+    model_entity_id
+}
@@ -2,10 +2,24 @@ pub mod testing_doll;
 pub mod chair;
 pub mod player;
 pub mod blockout_platform;
+pub mod imported_model;
+pub mod prefabs;
+pub mod spawner;
+pub mod terrain;
+pub mod water;
+pub mod point_light;
+pub mod reflection_probe;
+pub mod moving_platform;
 
-#[allow(unused_imports)]
 pub use testing_doll::spawn_testing_doll;
 #[allow(unused_imports)]
 pub use chair::spawn_chair;
 pub use player::spawn_player;
 pub use blockout_platform::spawn_blockout_platform;
+pub use imported_model::spawn_imported_model;
+pub use spawner::spawn_spawner;
+pub use terrain::spawn_terrain;
+pub use water::spawn_water;
+pub use point_light::spawn_point_light;
+pub use reflection_probe::spawn_reflection_probe;
+pub use moving_platform::spawn_moving_platform;
@@ -0,0 +1,27 @@
+use crate::index::engine::modules::{ spawn, EntityId };
+use crate::index::engine::components::{ Transform, Metadata, EntityCategory, Collider, Shape, ColliderLayer, KinematicMover };
+use crate::index::engine::managers::assets_manager::{ Assets, get_static_object_copy };
+use crate::index::game::entities::blockout_platform::get_player_position;
+
+/// Blockout platform shuttling back and forth in front of the player
+pub fn spawn_moving_platform() -> EntityId {
+    let platform_entity_id = spawn();
+    let [x, y, z] = get_player_position();
+    let start = [x, y - 4.0, z];
+    let end = [x + 10.0, y - 4.0, z];
+
+    crate::insert_many!(
+        platform_entity_id.clone(),
+        get_static_object_copy(Assets::BlockoutPlatform),
+        Transform::new(start[0], start[1], start[2]),
+        Metadata::new("Moving Platform", None, None).with_category(EntityCategory::Gameplay),
+        Collider::new(
+            Shape::Box { half_extents: [3.0, 3.0, 3.0] },
+            ColliderLayer::Environment,
+            vec![ColliderLayer::Environment]
+        ),
+        KinematicMover::new(vec![start, end], 2.0)
+    );
+
+    platform_entity_id
+}
@@ -1,11 +1,16 @@
-use crate::index::engine::components::rigid_body::RigidBody;
 use crate::index::engine::modules::{ spawn, EntityId };
 use crate::index::engine::components::{
     CameraComponent,
     Metadata,
+    EntityCategory,
     Transform,
     Collider,
     ColliderLayer,
+    DeathBehavior,
+    Faction,
+    Health,
+    PlayerController,
+    RigidBody,
     Shape,
 };
 use crate::index::PLAYER_ENTITY_ID;
@@ -17,13 +22,16 @@ pub fn spawn_player() -> EntityId {
         player_entity_id.clone(),
         CameraComponent::new(),
         Transform::new(0.0, 0.0, 0.0), // Transform component for position
-        Metadata::new("Player Camera", Some("player"), Some(false)),
+        Metadata::new("Player Camera", Some("player"), Some(false)).with_category(EntityCategory::Gameplay),
         Collider::new(
             Shape::Cylinder { radius: 1.0, height: 2.0 },
             ColliderLayer::Player,
             vec![ColliderLayer::Player]
         ),
-        RigidBody::new()
+        RigidBody::kinematic(), // Flown by the movement system
+        Health::new(100.0).with_death_behavior(DeathBehavior::Nothing),
+        Faction::new("player"),
+        PlayerController::default()
     );
 
     player_entity_id
@@ -0,0 +1,18 @@
+use crate::index::engine::modules::{ spawn, EntityId };
+use crate::index::engine::components::{ Transform, Metadata, EntityCategory, PointLight };
+use crate::index::game::entities::blockout_platform::get_player_position;
+
+/// Place a warm point light above the player
+pub fn spawn_point_light() -> EntityId {
+    let light_entity_id = spawn();
+    let player_position = get_player_position();
+
+    crate::insert_many!(
+        light_entity_id.clone(),
+        Transform::new(player_position[0], player_position[1] + 2.0, player_position[2]),
+        Metadata::new("Point Light", None, None).with_category(EntityCategory::Light),
+        PointLight::new([1.0, 0.8, 0.6], 2.0, 8.0)
+    );
+
+    light_entity_id
+}
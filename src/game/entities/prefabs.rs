@@ -0,0 +1,26 @@
+use crate::index::engine::managers::PrefabLibrary;
+use crate::index::engine::modules::EntityId;
+use crate::index::game::entities::{
+    spawn_blockout_platform,
+    spawn_chair,
+    spawn_testing_doll,
+};
+
+/// Built-in prefab names accepted by `Spawner.prefab`, next to the PrefabLibrary's user prefabs
+pub const PREFAB_NAMES: [&str; 3] = ["TestingDoll", "Chair", "BlockoutPlatform"];
+
+/// Spawn a built-in or user prefab by name and return its root; the caller positions it afterwards with
+/// `PrefabLibrary::place`, which brings the other entities of user prefabs along
+pub fn spawn_prefab(name: &str) -> Option<EntityId> {
+    match name {
+        "TestingDoll" => Some(spawn_testing_doll()),
+        "Chair" => Some(spawn_chair()),
+        "BlockoutPlatform" => Some(spawn_blockout_platform()),
+        _ => PrefabLibrary::instantiate(name).map(|entity_ids| entity_ids[0].clone()),
+    }
+}
+
+/// Built-in prefabs followed by the user prefabs
+pub fn prefab_names() -> Vec<String> {
+    PREFAB_NAMES.iter().map(|name| name.to_string()).chain(PrefabLibrary::names()).collect()
+}
@@ -0,0 +1,18 @@
+use crate::index::engine::modules::{ spawn, EntityId };
+use crate::index::engine::components::{ Transform, Metadata, EntityCategory, ReflectionProbe };
+use crate::index::game::entities::blockout_platform::get_player_position;
+
+/// Place a reflection probe at head height where the player stands
+pub fn spawn_reflection_probe() -> EntityId {
+    let probe_entity_id = spawn();
+    let player_position = get_player_position();
+
+    crate::insert_many!(
+        probe_entity_id.clone(),
+        Transform::new(player_position[0], player_position[1] + 1.5, player_position[2]),
+        Metadata::new("Reflection Probe", None, None).with_category(EntityCategory::Light),
+        ReflectionProbe::new([10.0, 5.0, 10.0])
+    );
+
+    probe_entity_id
+}
@@ -0,0 +1,18 @@
+use crate::index::engine::modules::{ spawn, EntityId };
+use crate::index::engine::components::{ Transform, Metadata, EntityCategory, Spawner, SpawnMode, SpawnArea };
+use crate::index::game::entities::blockout_platform::get_player_position;
+
+/// Place a Spawner at the player's position; prefab, mode and area are edited in the inspector
+pub fn spawn_spawner() -> EntityId {
+    let spawner_entity_id = spawn();
+    let player_position = get_player_position();
+
+    crate::insert_many!(
+        spawner_entity_id.clone(),
+        Transform::new(player_position[0], player_position[1], player_position[2]),
+        Metadata::new("Spawner", None, None).with_category(EntityCategory::Gameplay),
+        Spawner::new("TestingDoll", SpawnMode::Interval { seconds: 5.0 }, SpawnArea::Circle { radius: 3.0 }, 3)
+    );
+
+    spawner_entity_id
+}
@@ -0,0 +1,24 @@
+use crate::index::engine::modules::{ spawn, EntityId };
+use crate::index::engine::components::{ Transform, Metadata, EntityCategory, Collider, Shape, ColliderLayer, Terrain };
+use crate::index::game::entities::blockout_platform::get_player_position;
+
+/// Place a flat terrain just below the player; sculpt it with the viewport brushes
+pub fn spawn_terrain() -> EntityId {
+    let terrain_entity_id = spawn();
+    let player_position = get_player_position();
+    let terrain = Terrain::new_flat(64.0, 129);
+
+    crate::insert_many!(
+        terrain_entity_id.clone(),
+        Transform::new(player_position[0], player_position[1] - 2.0, player_position[2]),
+        Metadata::new("Terrain", None, None).with_category(EntityCategory::Prop),
+        Collider::new(
+            Shape::Heightfield { size: terrain.size, resolution: terrain.resolution, heights: terrain.heights.clone() },
+            ColliderLayer::Environment,
+            vec![ColliderLayer::Environment]
+        ),
+        terrain
+    );
+
+    terrain_entity_id
+}
@@ -1,15 +1,25 @@
 use crate::index::engine::modules::{spawn, EntityId};
-use crate::index::engine::components::{Transform, Metadata};
+use crate::index::engine::components::{Transform, Metadata, EntityCategory, AiAgent, AiBehavior, NavAgent, Health, DeathBehavior, Faction};
 use crate::index::engine::managers::assets_manager::{Assets, get_animated_object_copy};
 
-#[allow(dead_code)]
 pub fn spawn_testing_doll() -> EntityId {
     let doll_entity_id = spawn();
     crate::insert_many!(
         doll_entity_id.clone(),
         get_animated_object_copy(Assets::TestingDoll),
         Transform::new(-2.0, -3.0, -5.0),
-        Metadata::new("TestingDoll", None, None)
+        Metadata::new("TestingDoll", None, None).with_category(EntityCategory::Gameplay),
+        NavAgent::new(2.0),
+        Health::new(50.0).with_death_behavior(DeathBehavior::Ragdoll),
+        Faction::new("enemy"),
+        AiAgent::new(vec![
+            AiBehavior::Patrol {
+                waypoints: vec![[-2.0, -3.0, -5.0], [2.0, -3.0, -5.0], [2.0, -3.0, -1.0], [-2.0, -3.0, -1.0]],
+                wait_time: 1.5,
+            },
+            AiBehavior::Chase { radius: 6.0, give_up_radius: 10.0 },
+            AiBehavior::LookAt { head_node: 0, radius: 8.0, max_yaw: 1.2 } // Bone.001 is the doll's head
+        ])
     );
     doll_entity_id
 }
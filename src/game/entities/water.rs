@@ -0,0 +1,18 @@
+use crate::index::engine::modules::{ spawn, EntityId };
+use crate::index::engine::components::{ Transform, Metadata, EntityCategory, Water };
+use crate::index::game::entities::blockout_platform::get_player_position;
+
+/// Place a water plane just below the player
+pub fn spawn_water() -> EntityId {
+    let water_entity_id = spawn();
+    let player_position = get_player_position();
+
+    crate::insert_many!(
+        water_entity_id.clone(),
+        Transform::new(player_position[0], player_position[1] - 1.0, player_position[2]),
+        Metadata::new("Water", None, None).with_category(EntityCategory::Volume),
+        Water::new(32.0, 32.0)
+    );
+
+    water_entity_id
+}
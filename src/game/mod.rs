@@ -1,6 +1,8 @@
 pub mod systems;
 pub mod entities;
+pub mod plugin;
 
 // Re-export commonly used types
 pub use systems::*;
 pub use entities::*;
+pub use plugin::CoreGamePlugin;
@@ -0,0 +1,164 @@
+use std::sync::Arc;
+
+use crate::index::engine::modules::{ App, EventType, Plugin };
+use crate::index::game::{
+    AISystem,
+    AmbientOcclusionSystem,
+    AntialiasingSystem,
+    AnimationSystem,
+    BlockoutSystem,
+    CameraBookmarkSystem,
+    CameraRotationSystem,
+    CharacterSystem,
+    CollabSystem,
+    ColorGradingSystem,
+    CullingDebugSystem,
+    DepthPrepassSystem,
+    EditorCameraSystem,
+    GizmoSystem,
+    HealthSystem,
+    HeatmapSystem,
+    HudSystem,
+    ImpostorSystem,
+    InteractionSystem,
+    KitPlacementSystem,
+    LightClusterSystem,
+    MarqueeSelectionSystem,
+    MinimapSystem,
+    MovementSystem,
+    MoverSystem,
+    ProjectileSystem,
+    PropertyAnimationSystem,
+    NavigationSystem,
+    RagdollSystem,
+    ReflectionProbeSystem,
+    RenderSystem,
+    ScatterBrushSystem,
+    SceneThumbnailSystem,
+    SpawnSystem,
+    SplineSystem,
+    SsaoSystem,
+    TerrainSystem,
+    TextureStreamingSystem,
+    TimeOfDaySystem,
+    TransformDragSystem,
+    TransformGizmoSystem,
+    TransparencySystem,
+    VolumeSystem,
+    WaterSystem,
+    movement_system::MOVE_SPEED,
+    physics_system::{ PhysicsSystem, GRAVITY },
+    render_system::FOG_DENSITY,
+    gpu_skinning_system::GPU_SKINNING,
+    ambient_occlusion_system::{ AO_DISTANCE, AO_STRENGTH },
+    ssao_system::{ SSAO_INTENSITY, SSAO_RADIUS },
+    kit_placement_system::{ KIT_GRID_SIZE, KIT_HEIGHT },
+    heatmap_system::{ HEATMAP_BAKE_DENSITY, HEATMAP_COST_BUDGET, HEATMAP_TRIANGLE_BUDGET },
+    spawn_player,
+};
+
+/// Built-in gameplay: player controller, physics, animation and rendering
+pub struct CoreGamePlugin;
+
+impl Plugin for CoreGamePlugin {
+    fn name(&self) -> &str {
+        "core_game"
+    }
+
+    fn build(&self, app: &mut App) {
+        app.add_event_system(EventType::Move, Arc::new(MovementSystem))
+            .add_event_system(EventType::RotateCamera, Arc::new(CameraRotationSystem))
+            .add_event_system(EventType::Damage, Arc::new(HealthSystem))
+            .add_event_system_with_priority(EventType::PointerDrag, 10, Arc::new(TerrainSystem))
+            .add_event_system_with_priority(EventType::PointerDrag, 10, Arc::new(KitPlacementSystem))
+            .add_event_system_with_priority(EventType::PointerDrag, 10, Arc::new(ScatterBrushSystem))
+            .add_event_system_with_priority(EventType::PointerDrag, 6, Arc::new(SplineSystem))
+            .add_event_system_with_priority(EventType::PointerDrag, 5, Arc::new(TransformGizmoSystem))
+            .add_event_system(EventType::PointerDrag, Arc::new(TransformDragSystem))
+            .add_event_system(EventType::PointerDrag, Arc::new(MarqueeSelectionSystem))
+            .add_event_system(EventType::PointerRelease, Arc::new(SplineSystem))
+            .add_event_system(EventType::PointerRelease, Arc::new(TransformGizmoSystem))
+            .add_event_system(EventType::PointerRelease, Arc::new(TransformDragSystem))
+            .add_event_system(EventType::PointerRelease, Arc::new(MarqueeSelectionSystem))
+            .add_event_system(EventType::PointerRelease, Arc::new(KitPlacementSystem))
+            .add_event_system(EventType::PointerRelease, Arc::new(ScatterBrushSystem))
+            .add_event_system(EventType::Use, Arc::new(InteractionSystem))
+            .add_event_system(EventType::CameraBookmark, Arc::new(CameraBookmarkSystem))
+            .add_event_system(EventType::FrameView, Arc::new(EditorCameraSystem))
+            .add_event_system(EventType::ViewPreset, Arc::new(EditorCameraSystem))
+            .add_console_command("give_health", HealthSystem::give_health_command)
+            .add_console_command("overdraw", DepthPrepassSystem::overdraw_command)
+            .add_console_command("morph", AnimationSystem::morph_command)
+            .add_console_command("culling", CullingDebugSystem::culling_command)
+            .add_console_command("impostor", ImpostorSystem::impostor_command)
+            .add_console_command("csg", BlockoutSystem::csg_command)
+            .add_console_command("spline", SplineSystem::spline_command)
+            .add_console_command("volume", VolumeSystem::volume_command)
+            .add_cvar("gravity", GRAVITY, 0.0, 50.0, "Downward acceleration in units/s²")
+            .add_cvar("move_speed", MOVE_SPEED, 0.5, 50.0, "Player movement speed in units/s")
+            .add_cvar("fog_density", FOG_DENSITY, 0.0, 0.2, "Exponential distance fog, 0 disables it")
+            .add_cvar("gpu_skinning", GPU_SKINNING, 0.0, 1.0, "Skin and cull animated meshes in compute shaders (GL 4.3)")
+            .add_cvar("ao_strength", AO_STRENGTH, 0.0, 1.0, "How much baked ambient occlusion darkens ambient light")
+            .add_cvar("ao_distance", AO_DISTANCE, 0.1, 20.0, "Occluder reach of the next ambient occlusion bake in units")
+            .add_cvar("ssao_radius", SSAO_RADIUS, 0.05, 5.0, "Reach of screen-space ambient occlusion in units")
+            .add_cvar("ssao_intensity", SSAO_INTENSITY, 0.0, 4.0, "Darkening of screen-space ambient occlusion, 0 disables it")
+            .add_cvar("kit_grid_size", KIT_GRID_SIZE, 0.5, 50.0, "Cell width of the blockout kit placement grid in units")
+            .add_cvar("kit_height", KIT_HEIGHT, -500.0, 500.0, "Height of the plane blockout kit pieces are painted on")
+            .add_cvar("heatmap_cost_budget", HEATMAP_COST_BUDGET, 100.0, 500000.0, "Estimated draw cost the heatmap shows red")
+            .add_cvar("heatmap_triangle_budget", HEATMAP_TRIANGLE_BUDGET, 10.0, 500000.0, "Triangle count the heatmap shows red")
+            .add_cvar("heatmap_bake_density", HEATMAP_BAKE_DENSITY, 0.01, 100.0, "Baked samples per square unit the heatmap shows green")
+            .add_startup_system("spawn_player", || {
+                spawn_player();
+            })
+            .add_startup_system("ragdoll_hook", || {
+                HealthSystem::set_ragdoll_hook(RagdollSystem::activate);
+            })
+            .add_fixed_system("movers", MoverSystem::update)
+            .add_fixed_system("character", CharacterSystem::update)
+            .add_fixed_system("physics", PhysicsSystem::update)
+            .add_fixed_system("projectiles", ProjectileSystem::update)
+            .add_fixed_system("animation", AnimationSystem::update)
+            .add_fixed_system("property_animation", PropertyAnimationSystem::update)
+            .add_fixed_system("ragdoll", RagdollSystem::update)
+            .add_fixed_system("time_of_day", TimeOfDaySystem::update)
+            .add_fixed_system("terrain", TerrainSystem::update)
+            .add_fixed_system("water", WaterSystem::update)
+            .add_fixed_system("spawn", SpawnSystem::update)
+            .add_fixed_system("ai", AISystem::update)
+            .add_fixed_system("navigation", NavigationSystem::update)
+            .add_fixed_system("interaction", InteractionSystem::update)
+            .add_fixed_system("hud", HudSystem::update)
+            .add_render_system("editor_camera", EditorCameraSystem::update)
+            .add_render_system("volumes", VolumeSystem::update)
+            .add_render_system("blockouts", BlockoutSystem::update)
+            .add_render_system("splines", SplineSystem::update)
+            .add_render_system("impostors", ImpostorSystem::update)
+            .add_render_system("light_clusters", LightClusterSystem::render)
+            .add_render_system("ambient_occlusion", AmbientOcclusionSystem::prepare)
+            .add_render_system("texture_streaming", TextureStreamingSystem::update)
+            .add_render_system("msaa", AntialiasingSystem::begin)
+            .add_render_system("render", RenderSystem::update)
+            .add_render_system("terrain", TerrainSystem::render)
+            .add_render_system("impostor_billboards", ImpostorSystem::render)
+            .add_render_system("ssao", SsaoSystem::render)
+            .add_render_system("water", WaterSystem::render)
+            .add_render_system("transparency", TransparencySystem::render)
+            .add_render_system("msaa_resolve", AntialiasingSystem::resolve)
+            .add_render_system("color_grading", ColorGradingSystem::render)
+            .add_render_system("fxaa", AntialiasingSystem::fxaa)
+            .add_render_system("heatmap", HeatmapSystem::render)
+            .add_render_system("outlines", RenderSystem::render_outlines)
+            .add_render_system("culling_debug", CullingDebugSystem::render)
+            .add_render_system("navmesh_debug", NavigationSystem::render_debug)
+            .add_render_system("gizmos", GizmoSystem::render)
+            .add_render_system("spline_handles", SplineSystem::render)
+            .add_render_system("collab_peers", CollabSystem::render)
+            .add_render_system("transform_gizmo", TransformGizmoSystem::render)
+            .add_render_system("marquee", MarqueeSelectionSystem::render)
+            .add_render_system("minimap", MinimapSystem::render)
+            .add_render_system("animation_timeline", AnimationSystem::sync_timeline)
+            .add_render_system("curve_editor", PropertyAnimationSystem::sync_editor)
+            .add_render_system("scene_thumbnail", SceneThumbnailSystem::render)
+            .add_render_system("reflection_probes", ReflectionProbeSystem::render);
+    }
+}
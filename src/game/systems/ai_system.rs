@@ -0,0 +1,128 @@
+use std::f32::consts::PI;
+
+use crate::index::engine::components::{
+    AiAgent,
+    AiState,
+    AnimatedObject3DComponent,
+    NavAgent,
+    Transform,
+};
+use crate::index::engine::modules::ecs::{ insert, is_enabled };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::engine::utils::math::{ dist2, quat_from_yaw, quat_mul };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query_by_id, query_get_all };
+
+/// Distance the player has to move before a chasing agent re-plans its path
+const CHASE_REPLAN_DISTANCE: f32 = 0.5;
+/// Fraction of the remaining head rotation applied per tick
+const HEAD_TURN_RATE: f32 = 0.1;
+
+pub struct AISystem;
+
+impl AISystem {
+    /// Pick a behavior for every AiAgent and feed the resulting destination to its NavAgent.
+    /// Runs after animation so the head look-at is layered on top of the current pose.
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        let player_position = PLAYER_ENTITY_ID.read()
+            .unwrap()
+            .as_ref()
+            .and_then(|player_id| get_query_by_id!(player_id, (Transform)))
+            .map(|transform| transform.get_position());
+
+        for (entity_id, transform, mut ai, mut nav) in query_get_all!(Transform, AiAgent, NavAgent) {
+            if !is_enabled(&entity_id) {
+                continue;
+            }
+            let position = transform.get_position();
+            let player_distance2 = player_position.map(|player| dist2(position, player));
+
+            // Chase takes priority over patrol while the player is close enough
+            if let (Some((radius, give_up_radius)), Some(player), Some(distance2)) = (
+                ai.chase(),
+                player_position,
+                player_distance2,
+            ) {
+                let chasing = ai.state == AiState::Chasing;
+                if !chasing && distance2 <= radius * radius {
+                    println!("👁️ {} spotted the player", entity_id);
+                    ai.state = AiState::Chasing;
+                } else if chasing && distance2 > give_up_radius * give_up_radius {
+                    println!("💤 {} lost the player", entity_id);
+                    ai.state = AiState::Idle;
+                    nav.stop();
+                }
+
+                if ai.state == AiState::Chasing {
+                    let needs_replan = nav.destination.is_none_or(|destination| {
+                        dist2(destination, player) > CHASE_REPLAN_DISTANCE * CHASE_REPLAN_DISTANCE
+                    });
+                    if needs_replan {
+                        nav.set_destination(player);
+                    }
+                }
+            }
+
+            if ai.state != AiState::Chasing {
+                Self::update_patrol(&mut ai, &mut nav, delta_time);
+            }
+
+            if let Some((head_node, radius, max_yaw)) = ai.look_at() {
+                let target_yaw = match (player_position, player_distance2) {
+                    (Some(player), Some(distance2)) if distance2 <= radius * radius => {
+                        let desired = (player[0] - position[0]).atan2(player[2] - position[2]);
+                        wrap_angle(desired - transform.get_rotation()[1]).clamp(-max_yaw, max_yaw)
+                    }
+                    _ => 0.0,
+                };
+                ai.head_yaw += (target_yaw - ai.head_yaw) * HEAD_TURN_RATE;
+
+                let head_yaw = ai.head_yaw;
+                query_by_id!(entity_id, (AnimatedObject3DComponent), |animated_object| {
+                    // Yaw the head in its parent's space on top of the animated pose
+                    if let Some(node) = animated_object.skeleton.nodes.get_mut(head_node as usize) {
+                        node.rotation = quat_mul(quat_from_yaw(head_yaw), node.rotation);
+                    }
+                });
+            }
+
+            insert(&entity_id, ai);
+            insert(&entity_id, nav);
+        }
+    }
+
+    /// Walk the patrol waypoints in order, waiting at each one before moving on
+    fn update_patrol(ai: &mut AiAgent, nav: &mut NavAgent, delta_time: f32) {
+        let Some((waypoints, wait_time)) = ai.patrol().map(|(waypoints, wait_time)| (waypoints.to_vec(), wait_time)) else {
+            ai.state = AiState::Idle;
+            return;
+        };
+        let waypoint_count = waypoints.len();
+        let next_waypoint = waypoints[ai.patrol_index % waypoint_count];
+
+        if ai.state != AiState::Patrolling {
+            ai.state = AiState::Patrolling;
+            ai.wait_timer = 0.0;
+            nav.set_destination(next_waypoint);
+            return;
+        }
+
+        if !nav.has_arrived() {
+            return;
+        }
+
+        ai.wait_timer += delta_time;
+        if ai.wait_timer >= wait_time {
+            ai.wait_timer = 0.0;
+            ai.patrol_index = (ai.patrol_index + 1) % waypoint_count;
+            nav.set_destination(waypoints[ai.patrol_index]);
+        }
+    }
+}
+
+/// Wrap an angle in radians to [-PI, PI]
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = (angle + PI).rem_euclid(2.0 * PI);
+    wrapped - PI
+}
@@ -0,0 +1,130 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::index::engine::components::{ BakedOcclusion, StaticObject3DComponent };
+use crate::index::engine::components::SharedComponents::Transform;
+use crate::index::engine::modules::{ is_enabled, Console, EntityId, InterfaceSystem, TaskSystem };
+use crate::index::engine::rhi::{ DrawCall, Texture, TextureFormat, Uniform };
+use crate::index::engine::utils::TriangleBvh;
+use crate::{ get_query_by_id, query_get_all };
+
+/// Default for the `ao_strength` cvar: how far baked occlusion darkens ambient light, 0 ignores it
+pub const AO_STRENGTH: f32 = 1.0;
+/// Default for the `ao_distance` cvar: geometry closer than this along a ray occludes during a bake
+pub const AO_DISTANCE: f32 = 2.0;
+/// Vertices per row of an occlusion texture, OCCLUSION_ROW in vertex_static.glsl
+const OCCLUSION_ROW: usize = 1024;
+/// Sampler unit of the occlusion texture, after the light cluster textures
+const OCCLUSION_UNIT: u32 = 5;
+/// Task name of a running bake
+const BAKE_TASK: &str = "bake occlusion";
+
+thread_local! {
+    /// Uploaded values and texture of every entity with a bake, replaced when the values change
+    static TEXTURES: RefCell<HashMap<EntityId, (Vec<u8>, Texture)>> = RefCell::new(HashMap::new());
+}
+
+/// Editor bake of per-vertex ambient occlusion for static meshes, saved with the scene as BakedOcclusion
+/// components and applied to their ambient light, so blockout geometry gets contact shadows in corners and under
+/// ledges
+pub struct AmbientOcclusionSystem;
+
+impl AmbientOcclusionSystem {
+    /// Bake every enabled opaque static mesh against all of them in the background
+    pub fn bake() {
+        if TaskSystem::is_running(BAKE_TASK) {
+            println!("🌑 An occlusion bake is already running");
+            return;
+        }
+        let targets: Vec<_> = query_get_all!(Transform, StaticObject3DComponent)
+            .into_iter()
+            .filter(|(entity_id, _, static_object)| is_enabled(entity_id) && !static_object.material.transparent)
+            .filter_map(|(entity_id, transform, static_object)| {
+                Some((entity_id, static_object.mesh.data?, transform.compute_matrix()))
+            })
+            .collect();
+        let distance = Console::cvar("ao_distance").unwrap_or(AO_DISTANCE);
+
+        TaskSystem::spawn(
+            BAKE_TASK,
+            move || {
+                let triangles = targets
+                    .iter()
+                    .flat_map(|(_, mesh, world_txfm)| BakedOcclusion::world_triangles(mesh, world_txfm))
+                    .collect();
+                let scene = TriangleBvh::new(triangles);
+                Ok(
+                    targets
+                        .iter()
+                        .map(|(entity_id, mesh, world_txfm)| {
+                            (entity_id.clone(), BakedOcclusion::bake(mesh, world_txfm, &scene, distance))
+                        })
+                        .collect::<Vec<_>>()
+                )
+            },
+            |result, _gl| {
+                let Ok(bakes) = result else {
+                    return;
+                };
+                // Entities deleted while baking keep no bake
+                let baked: Vec<_> = bakes
+                    .into_iter()
+                    .filter(|(entity_id, _)| get_query_by_id!(entity_id, (StaticObject3DComponent)).is_some())
+                    .collect();
+                println!("🌑 Baked ambient occlusion for {} meshes", baked.len());
+                for (entity_id, occlusion) in baked {
+                    crate::index::engine::modules::ecs::insert(&entity_id, occlusion);
+                }
+            }
+        );
+    }
+
+    /// Upload new or changed bakes and drop the textures of entities that lost theirs
+    pub fn prepare(gl: &glow::Context, _width: u32, _height: u32) {
+        let bakes: HashMap<EntityId, BakedOcclusion> = query_get_all!(BakedOcclusion).into_iter().collect();
+        TEXTURES.with(|textures| {
+            let mut textures = textures.borrow_mut();
+            textures.retain(|entity_id, (values, texture)| {
+                let current = bakes.get(entity_id).is_some_and(|bake| bake.values == *values);
+                if !current {
+                    texture.delete(gl);
+                }
+                current
+            });
+
+            for (entity_id, bake) in bakes {
+                if bake.values.is_empty() || textures.contains_key(&entity_id) {
+                    continue;
+                }
+                let rows = bake.values.len().div_ceil(OCCLUSION_ROW);
+                let mut texels = bake.values.clone();
+                texels.resize(rows * OCCLUSION_ROW, u8::MAX);
+                match Texture::data(gl, OCCLUSION_ROW as u32, rows as u32, TextureFormat::R8, &texels) {
+                    Ok(texture) => {
+                        textures.insert(entity_id, (bake.values, texture));
+                    }
+                    Err(e) => InterfaceSystem::report_error(&e),
+                }
+            }
+        });
+    }
+
+    /// Add an entity's occlusion to a draw with vertex_static.glsl; without a bake that matches the mesh it stays
+    /// fully lit
+    pub fn bind<'a>(draw: DrawCall<'a>, entity_id: &str, vertex_count: usize) -> DrawCall<'a> {
+        let texture = TEXTURES.with(|textures| {
+            textures
+                .borrow()
+                .get(entity_id)
+                .filter(|(values, _)| values.len() == vertex_count)
+                .map(|(_, texture)| *texture)
+        });
+        match texture {
+            Some(texture) => draw
+                .uniform("occlusion_strength", Uniform::Float(Console::cvar("ao_strength").unwrap_or(AO_STRENGTH)))
+                .uniform("occlusion_texture", Uniform::Int(OCCLUSION_UNIT as i32))
+                .texture(OCCLUSION_UNIT, texture),
+            None => draw.uniform("occlusion_strength", Uniform::Float(0.0)),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::cell::Cell;
+
+use crate::index::engine::components::AnimatedObject3D::{ AnimationClip, AnimationMarkerEvent, Animator };
+use crate::index::engine::components::AnimatedObject3DComponent;
+use crate::index::engine::modules::ecs::insert;
+use crate::index::engine::modules::event_system::{ Event, EventSystem, EventType };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::engine::modules::{ is_enabled, EntityId, InterfaceSystem, Selection };
+use crate::index::game::systems::RagdollSystem;
+use crate::{ get_query_by_id, query };
+
+/// Frames between timeline panel refreshes
+const TIMELINE_REFRESH_FRAMES: u32 = 6;
+
+thread_local! {
+    static FRAMES_SINCE_REFRESH: Cell<u32> = const { Cell::new(0) };
+}
+
+pub struct AnimationSystem;
+
+impl AnimationSystem {
+    /// Advance every animator by one simulation tick and pose its skeleton
+    pub fn update() {
+        let mut markers = Vec::new();
+        query!((AnimatedObject3DComponent), |entity_id, animated_object| {
+            // Ragdolls pose the skeleton themselves
+            if !is_enabled(&entity_id) || RagdollSystem::is_active(&entity_id) {
+                continue;
+            }
+            let passed = animated_object.animator.update_with_data(
+                1.0 / FIXED_TICK_RATE,
+                &animated_object.clips,
+                &mut animated_object.skeleton,
+                &mut animated_object.morph_weights
+            );
+            if let Some(clip) = animated_object.animator.current_clip(&animated_object.clips) {
+                markers.extend(passed.into_iter().map(|marker| AnimationMarkerEvent {
+                    entity_id: entity_id.clone(),
+                    clip: clip.name.clone(),
+                    marker,
+                }));
+            }
+        });
+
+        for marker in markers {
+            EventSystem::send(Event {
+                event_type: EventType::AnimationMarker,
+                payload: Box::new(marker),
+            });
+        }
+    }
+
+    /// Render system: keep the timeline panel's playhead and entity current, also while the simulation is paused
+    pub fn sync_timeline(_gl: &glow::Context, _width: u32, _height: u32) {
+        let refresh = FRAMES_SINCE_REFRESH.with(|frames| {
+            frames.set((frames.get() + 1) % TIMELINE_REFRESH_FRAMES);
+            frames.get() == 0
+        });
+        if refresh {
+            InterfaceSystem::refresh_timeline();
+        }
+    }
+
+    /// Change an entity's playback from the timeline panel and pose it right away, so scrubbing shows while the
+    /// simulation is paused
+    pub fn edit_playback(
+        entity_id: &EntityId,
+        change: impl FnOnce(&mut Animator, &[AnimationClip]) -> Result<(), String>
+    ) -> Result<(), String> {
+        let mut animated_object = get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent)).ok_or_else(|| {
+            format!("Entity {} has no animated object", entity_id)
+        })?;
+        change(&mut animated_object.animator, &animated_object.clips)?;
+        if !RagdollSystem::is_active(entity_id) {
+            animated_object.animator.pose(
+                &animated_object.clips,
+                &mut animated_object.skeleton,
+                &mut animated_object.morph_weights
+            );
+        }
+        insert(entity_id, animated_object);
+        Ok(())
+    }
+
+    /// Console: `morph <target> <weight>` sets a morph target weight on the selected animated objects; `morph`
+    /// alone lists their weights
+    pub fn morph_command(args: &[&str]) -> Result<String, String> {
+        let entity_ids: Vec<_> = Selection::ids()
+            .into_iter()
+            .filter(|entity_id| get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent)).is_some())
+            .collect();
+        if entity_ids.is_empty() {
+            return Err("Select the animated objects to morph".to_string());
+        }
+        let [target, weight] = args else {
+            let lines: Vec<String> = entity_ids
+                .iter()
+                .filter_map(|entity_id| get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent)))
+                .map(|animated_object| format!("  {:?}: {:?}", animated_object.asset_type, animated_object.morph_weights))
+                .collect();
+            return Ok(format!("Morph weights (usage: morph <target> <weight>):\n{}", lines.join("\n")));
+        };
+        let target: usize = target.parse().map_err(|_| format!("Invalid target index: {}", target))?;
+        let weight: f32 = weight.parse().map_err(|_| format!("Invalid weight: {}", weight))?;
+        for entity_id in &entity_ids {
+            let mut animated_object = get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent))
+                .ok_or_else(|| format!("Entity {} has no animated object", entity_id))?;
+            animated_object.set_morph_weight(target, weight)?;
+            insert(entity_id, animated_object);
+        }
+        Ok(format!("Set morph target {} to {} on {} objects", target, weight, entity_ids.len()))
+    }
+}
@@ -0,0 +1,200 @@
+use std::cell::{ Cell, RefCell };
+use glow::HasContext;
+
+use crate::index::engine::managers::assets_manager::get_fxaa_shader;
+use crate::index::engine::modules::{ AntialiasingMode, EngineError, EngineResult, GraphicsQuality, InterfaceSystem };
+use crate::index::engine::rhi::{ Blend, DepthCompare, DrawCall, Primitive, Texture, Uniform };
+
+/// Multisampled color and depth the scene renders into, sized like the viewport
+struct MsaaTarget {
+    framebuffer: glow::Framebuffer,
+    color: glow::Renderbuffer,
+    depth: glow::Renderbuffer,
+    size: [u32; 2],
+    samples: u8,
+}
+
+impl MsaaTarget {
+    unsafe fn new(gl: &glow::Context, width: u32, height: u32, samples: u8) -> EngineResult<Self> {
+        let gpu_error = |e: String| EngineError::gpu(format!("Failed to create MSAA target: {}", e));
+        let framebuffer = gl.create_framebuffer().map_err(gpu_error)?;
+        let color = gl.create_renderbuffer().map_err(gpu_error)?;
+        let depth = gl.create_renderbuffer().map_err(gpu_error)?;
+        let target = Self { framebuffer, color, depth, size: [width, height], samples };
+
+        // Same formats as the window's buffers, which the resolve blits into
+        let (w, h, samples) = (width as i32, height as i32, samples as i32);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(color));
+        gl.renderbuffer_storage_multisample(glow::RENDERBUFFER, samples, glow::RGBA8, w, h);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+        gl.renderbuffer_storage_multisample(glow::RENDERBUFFER, samples, glow::DEPTH24_STENCIL8, w, h);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+        let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::RENDERBUFFER, Some(color));
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_STENCIL_ATTACHMENT, glow::RENDERBUFFER, Some(depth));
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            target.delete(gl);
+            return Err(EngineError::gpu(format!("MSAA target incomplete (status 0x{:x})", status)));
+        }
+        Ok(target)
+    }
+
+    unsafe fn delete(self, gl: &glow::Context) {
+        gl.delete_framebuffer(self.framebuffer);
+        gl.delete_renderbuffer(self.color);
+        gl.delete_renderbuffer(self.depth);
+    }
+}
+
+thread_local! {
+    static MSAA_TARGET: RefCell<Option<MsaaTarget>> = const { RefCell::new(None) };
+    // Framebuffer the scene would have drawn into, bound again by the resolve; set while the scene is multisampled
+    static RESOLVE_INTO: Cell<Option<Option<glow::Framebuffer>>> = const { Cell::new(None) };
+    // Set once the window's depth couldn't take the resolved depth; the overlays then draw without it
+    static DEPTH_RESOLVE_UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+    // Set once MSAA failed on this GL context; the scene renders straight into the window from then on
+    static UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+    // Copy of the finished frame FXAA filters, resized with the viewport
+    static FXAA_COPY: RefCell<Option<Texture>> = const { RefCell::new(None) };
+    // Set once FXAA failed on this GL context; the frame stays aliased
+    static FXAA_UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Anti-aliasing of the scene by the graphics settings' `antialiasing` mode. MSAA renders the scene passes into a
+/// multisampled target that is resolved into the window before the post effects and editor overlays (Slint creates
+/// the window's surface without multisampling, so it can't be requested there); FXAA filters the finished frame.
+pub struct AntialiasingSystem;
+
+impl AntialiasingSystem {
+    /// Redirect the scene into the multisampled target; runs right before the scene is cleared and drawn
+    pub fn begin(gl: &glow::Context, width: u32, height: u32) {
+        let Some(samples) = GraphicsQuality::current().msaa().filter(|_| !UNAVAILABLE.with(Cell::get)) else {
+            Self::release(gl);
+            return;
+        };
+        if width == 0 || height == 0 {
+            return;
+        }
+        let samples = unsafe { samples.min(gl.get_parameter_i32(glow::MAX_SAMPLES).clamp(1, u8::MAX as i32) as u8) };
+
+        MSAA_TARGET.with(|target| {
+            let mut target = target.borrow_mut();
+            if target.as_ref().is_none_or(|target| target.size != [width, height] || target.samples != samples) {
+                if let Some(old) = target.take() {
+                    unsafe { old.delete(gl) };
+                }
+                match unsafe { MsaaTarget::new(gl, width, height, samples) } {
+                    Ok(created) => *target = Some(created),
+                    Err(e) => {
+                        Self::disable(&e);
+                        return;
+                    }
+                }
+            }
+            let Some(target) = target.as_ref() else {
+                return;
+            };
+            unsafe {
+                RESOLVE_INTO.with(|resolve_into| {
+                    resolve_into.set(Some(gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING)))
+                });
+                gl.bind_framebuffer(glow::FRAMEBUFFER, Some(target.framebuffer));
+            }
+        });
+    }
+
+    /// Resolve the multisampled scene into the window; runs after transparency, before color grading copies the frame
+    pub fn resolve(gl: &glow::Context, width: u32, height: u32) {
+        let Some(resolve_into) = RESOLVE_INTO.with(Cell::take) else {
+            return;
+        };
+        MSAA_TARGET.with(|target| {
+            let target = target.borrow();
+            let Some(target) = target.as_ref() else {
+                return;
+            };
+            unsafe {
+                let (w, h) = (width as i32, height as i32);
+                gl.get_error(); // Don't blame the blit for earlier passes
+                gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(target.framebuffer));
+                gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, resolve_into);
+                gl.blit_framebuffer(0, 0, w, h, 0, 0, w, h, glow::COLOR_BUFFER_BIT, glow::NEAREST);
+                let color_error = gl.get_error();
+
+                // Gizmos and other overlays test against the scene's depth; a window without a matching depth
+                // buffer can't take it, and they draw over everything instead
+                if color_error == glow::NO_ERROR && !DEPTH_RESOLVE_UNAVAILABLE.with(Cell::get) {
+                    gl.blit_framebuffer(0, 0, w, h, 0, 0, w, h, glow::DEPTH_BUFFER_BIT, glow::NEAREST);
+                    if gl.get_error() != glow::NO_ERROR {
+                        DEPTH_RESOLVE_UNAVAILABLE.with(|unavailable| unavailable.set(true));
+                        eprintln!("⚠️ Can't resolve the MSAA depth into the window, overlays draw without depth");
+                    }
+                }
+                gl.bind_framebuffer(glow::FRAMEBUFFER, resolve_into);
+
+                if color_error != glow::NO_ERROR {
+                    let message = format!("Can't resolve the MSAA target (GL error 0x{:x})", color_error);
+                    Self::disable(&EngineError::gpu(message));
+                }
+            }
+        });
+    }
+
+    /// FXAA over the graded frame; runs after color grading, before the editor overlays which stay sharp
+    pub fn fxaa(gl: &glow::Context, width: u32, height: u32) {
+        if GraphicsQuality::current().antialiasing != AntialiasingMode::Fxaa || FXAA_UNAVAILABLE.with(Cell::get) {
+            return;
+        }
+        let scene_copy = FXAA_COPY.with(|scene_copy| {
+            let mut scene_copy = scene_copy.borrow_mut();
+            match *scene_copy {
+                Some(texture) if texture.size() == [width, height] => texture.copy_framebuffer(gl),
+                _ => {
+                    if let Some(old) = scene_copy.take() {
+                        old.delete(gl);
+                    }
+                    match Texture::from_framebuffer(gl, width, height) {
+                        Ok(texture) => *scene_copy = Some(texture),
+                        Err(e) => {
+                            FXAA_UNAVAILABLE.with(|unavailable| unavailable.set(true));
+                            InterfaceSystem::report_error(&e);
+                            eprintln!("⚠️ FXAA unavailable on this GPU");
+                        }
+                    }
+                }
+            }
+            *scene_copy
+        });
+        let Some(scene_copy) = scene_copy else {
+            return;
+        };
+
+        DrawCall::new(get_fxaa_shader(), Primitive::Triangles, 3)
+            .depth(DepthCompare::Always)
+            .blend(Blend::Alpha)
+            .uniform("scene_color", Uniform::Int(0))
+            .uniform("texel_size", Uniform::Vec2(&[1.0 / width as f32, 1.0 / height as f32]))
+            .texture(0, scene_copy)
+            .submit(gl);
+    }
+
+    /// Drop the target once MSAA is turned off
+    fn release(gl: &glow::Context) {
+        MSAA_TARGET.with(|target| {
+            if let Some(old) = target.borrow_mut().take() {
+                unsafe { old.delete(gl) };
+            }
+        });
+    }
+
+    fn disable(error: &EngineError) {
+        UNAVAILABLE.with(|unavailable| unavailable.set(true));
+        InterfaceSystem::report_error(error);
+        eprintln!("⚠️ MSAA unavailable on this GPU");
+    }
+}
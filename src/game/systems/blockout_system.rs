@@ -0,0 +1,198 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::index::engine::components::{
+    Blockout,
+    BlockoutBox,
+    Collider,
+    ColliderLayer,
+    Metadata,
+    Shape,
+    Spline,
+    StaticObject3DComponent,
+    Transform,
+};
+use crate::index::engine::components::SharedComponents::Mesh;
+use crate::index::engine::managers::assets_manager::{ get_static_object_copy, Assets };
+use crate::index::engine::modules::ecs::{ despawn, insert };
+use crate::index::engine::modules::{ EntityId, InterfaceSystem, Selection };
+use crate::index::engine::utils::gltf_loader_utils::upload_mesh;
+use crate::index::engine::utils::math::Vec3;
+use crate::{ get_query_by_id, query_get_all };
+
+/// How far a box corner may sit off the entity's axes and still count as lined up with them, relative to its size
+const ALIGN_TOLERANCE: f32 = 1e-3;
+
+thread_local! {
+    // Blockout each entity's mesh was last built from, and the mesh
+    static BUILT: RefCell<HashMap<EntityId, (Blockout, Mesh)>> = RefCell::new(HashMap::new());
+}
+
+/// Meshes blockouts and keeps their compound colliders in step: an entity whose Blockout changed gets a new mesh
+/// (with the blockout platform's material), and one that was reloaded gets its built mesh back. Runs before the
+/// meshes are drawn
+pub struct BlockoutSystem;
+
+impl BlockoutSystem {
+    pub fn update(gl: &glow::Context, _width: u32, _height: u32) {
+        for (entity_id, blockout) in query_get_all!(Blockout) {
+            let built = BUILT.with(|built| built.borrow().get(&entity_id).cloned());
+            let mesh = match built {
+                Some((built_blockout, mesh)) if built_blockout == blockout => mesh,
+                // A failed build is kept too, so it's reported once
+                _ => {
+                    let mesh = upload_mesh(gl, &blockout.mesh(), None, "Blockout").unwrap_or_else(|error| {
+                        InterfaceSystem::report_error(&error);
+                        Mesh::new()
+                    });
+                    BUILT.with(|built| built.borrow_mut().insert(entity_id.clone(), (blockout.clone(), mesh.clone())));
+                    mesh
+                }
+            };
+            Self::sync(&entity_id, &blockout, mesh);
+        }
+    }
+
+    /// Give the entity the built mesh and a compound collider of the boxes, unless it has them already
+    fn sync(entity_id: &EntityId, blockout: &Blockout, mesh: Mesh) {
+        let object = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent));
+        let has_mesh = object
+            .as_ref()
+            .is_some_and(|object| {
+                matches!((&object.mesh.data, &mesh.data), (Some(current), Some(built)) if Arc::ptr_eq(current, built))
+            });
+        if !has_mesh && mesh.data.is_some() {
+            let object = object.unwrap_or_else(|| get_static_object_copy(Assets::BlockoutPlatform));
+            insert(entity_id, StaticObject3DComponent { mesh, ..object });
+        }
+
+        let boxes = blockout.collider_boxes();
+        match get_query_by_id!(entity_id.clone(), (Collider)) {
+            Some(collider) if matches!(&collider.shape, Shape::Compound { boxes: current } if *current == boxes) => {}
+            Some(collider) => insert(entity_id, Collider { shape: Shape::Compound { boxes }, ..collider }),
+            None => {
+                let shape = Shape::Compound { boxes };
+                insert(entity_id, Collider::new(shape, ColliderLayer::Environment, vec![ColliderLayer::Environment]));
+            }
+        }
+    }
+
+    /// The entity's Blockout, or for a blockout platform that isn't one yet, a box around its mesh. Splines use the
+    /// platform's material but mesh themselves
+    fn blockout_of(entity_id: &EntityId) -> Option<Blockout> {
+        if let Some(blockout) = get_query_by_id!(entity_id.clone(), (Blockout)) {
+            return Some(blockout);
+        }
+        if get_query_by_id!(entity_id.clone(), (Spline)).is_some() {
+            return None;
+        }
+        let object = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent))?;
+        (object.asset_type == Assets::BlockoutPlatform).then(|| {
+            let (min, max) = object.mesh.bounds;
+            Blockout::new(vec![BlockoutBox { min, max }])
+        })
+    }
+
+    /// An operand's boxes in the target's local space; None when they don't line up with the target's axes
+    fn boxes_in(
+        operand: &Blockout,
+        operand_transform: &mut Transform,
+        target: &Transform
+    ) -> Option<Vec<BlockoutBox>> {
+        let operand_matrix = *operand_transform.get_matrix();
+        let mut target_rotation = target.clone();
+        target_rotation.set_position(0.0, 0.0, 0.0);
+        target_rotation.set_scale(1.0, 1.0, 1.0);
+        let rotation = target_rotation.compute_matrix();
+        let (position, scale) = (target.get_position(), target.get_scale());
+
+        operand.boxes
+            .iter()
+            .map(|part| {
+                let corners = [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+                    let local = [0, 1, 2].map(|axis| [part.min[axis], part.max[axis]][(i >> axis) & 1]);
+                    let world: Vec3 = [0, 1, 2].map(|row| {
+                        (0..3).map(|column| operand_matrix[row * 4 + column] * local[column]).sum::<f32>() +
+                            operand_matrix[row * 4 + 3]
+                    });
+                    let offset = [0, 1, 2].map(|axis| world[axis] - position[axis]);
+                    // Inverse rotation (the transpose), then inverse scale
+                    [0, 1, 2].map(|axis| {
+                        (0..3).map(|row| rotation[row * 4 + axis] * offset[row]).sum::<f32>() / scale[axis]
+                    })
+                });
+                let min = [0, 1, 2].map(|axis| corners.iter().map(|corner| corner[axis]).fold(f32::MAX, f32::min));
+                let max = [0, 1, 2].map(|axis| corners.iter().map(|corner| corner[axis]).fold(f32::MIN, f32::max));
+                let aligned = corners.iter().all(|corner| {
+                    (0..3).all(|axis| {
+                        let tolerance = (max[axis] - min[axis]).max(1.0) * ALIGN_TOLERANCE;
+                        (corner[axis] - min[axis]).abs() <= tolerance || (corner[axis] - max[axis]).abs() <= tolerance
+                    })
+                });
+                aligned.then_some(BlockoutBox { min, max })
+            })
+            .collect()
+    }
+
+    /// Console: `csg <union|subtract>` combines the other selected blockouts into the one the inspector shows and
+    /// deletes them: union adds their volume, subtract cuts it away (a doorway through a wall box). Blockout
+    /// platforms become blockouts on their first operation; the result is saved with the scene as the entity's
+    /// Blockout. The boxes have to line up with the entity's axes, turned by quarter turns at most
+    pub fn csg_command(args: &[&str]) -> Result<String, String> {
+        let subtract = match args.first() {
+            Some(&"union") => false,
+            Some(&"subtract") => true,
+            _ => return Err("Usage: csg <union|subtract>".to_string()),
+        };
+        let (target_id, _) = InterfaceSystem::get_selection_state();
+        let target_metadata = get_query_by_id!(target_id.clone(), (Metadata));
+        if target_metadata.as_ref().is_some_and(|metadata| metadata.locked) {
+            return Err("The selected entity is locked".to_string());
+        }
+        let (Some(target), Some(target_transform)) = (
+            Self::blockout_of(&target_id),
+            get_query_by_id!(target_id.clone(), (Transform)),
+        ) else {
+            return Err("Select a blockout platform or blockout to combine into".to_string());
+        };
+        let operand_ids: Vec<EntityId> = Selection::ids()
+            .into_iter()
+            .filter(|entity_id| *entity_id != target_id)
+            .collect();
+        if operand_ids.is_empty() {
+            return Err("Also select the blockouts to combine with it".to_string());
+        }
+
+        let mut boxes = Vec::new();
+        for operand_id in &operand_ids {
+            let title = get_query_by_id!(operand_id.clone(), (Metadata))
+                .map(|metadata| metadata.title().to_string())
+                .unwrap_or_else(|| operand_id.clone());
+            let (Some(operand), Some(mut operand_transform)) = (
+                Self::blockout_of(operand_id),
+                get_query_by_id!(operand_id.clone(), (Transform)),
+            ) else {
+                return Err(format!("{} isn't a blockout platform or blockout", title));
+            };
+            let operand_boxes = Self::boxes_in(&operand, &mut operand_transform, &target_transform)
+                .ok_or_else(|| format!("{} isn't lined up with the selected entity's axes", title))?;
+            boxes.extend(operand_boxes);
+        }
+
+        let result = if subtract { target.subtract(&boxes) } else { target.union(&boxes) };
+        if result.boxes.is_empty() {
+            return Err("Nothing would be left of the selected entity".to_string());
+        }
+        let box_count = result.boxes.len();
+        insert(&target_id, result);
+        for operand_id in &operand_ids {
+            despawn(operand_id);
+        }
+        InterfaceSystem::refresh_selection();
+
+        let title = target_metadata.map(|metadata| metadata.title().to_string()).unwrap_or(target_id);
+        let operation = if subtract { "Cut" } else { "Merged" };
+        Ok(format!("{} {} entities into {}, now {} boxes", operation, operand_ids.len(), title, box_count))
+    }
+}
@@ -0,0 +1,51 @@
+// Import types and functions from parent scope
+use crate::index::engine::components::{ SystemTrait, CameraComponent, Transform };
+use crate::index::engine::modules::event_system::Event;
+use crate::index::engine::modules::keyboard_input_system::BookmarkEvent;
+use crate::index::engine::modules::{ CameraBookmark, CameraBookmarks };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query_by_id };
+
+/// Stores and recalls the editor camera (player camera position and rotation) in numbered bookmarks
+#[derive(Debug)]
+pub struct CameraBookmarkSystem;
+
+impl SystemTrait for CameraBookmarkSystem {
+    fn event(&self, event: &Event) {
+        let Some(player_entity_id) = PLAYER_ENTITY_ID.read().unwrap().clone() else {
+            return;
+        };
+        let Some(bookmark_event) = event.payload.downcast_ref::<BookmarkEvent>() else {
+            return;
+        };
+
+        if bookmark_event.store {
+            let (Some(transform), Some(camera)) = (
+                get_query_by_id!(player_entity_id, (Transform)),
+                get_query_by_id!(player_entity_id, (CameraComponent)),
+            ) else {
+                return;
+            };
+            CameraBookmarks::set(bookmark_event.slot, CameraBookmark {
+                position: transform.get_position(),
+                pitch: camera.get_pitch(),
+                yaw: camera.get_yaw(),
+            });
+            println!("🔖 Stored camera bookmark {}", bookmark_event.slot);
+            return;
+        }
+
+        let Some(bookmark) = CameraBookmarks::get(bookmark_event.slot) else {
+            println!("🔖 Camera bookmark {} is empty (Ctrl+{} stores it)", bookmark_event.slot, bookmark_event.slot);
+            return;
+        };
+        query_by_id!(player_entity_id, (Transform), |transform| {
+            transform.set_position(bookmark.position[0], bookmark.position[1], bookmark.position[2]);
+        });
+        query_by_id!(player_entity_id, (CameraComponent), |camera| {
+            camera.set_pitch(bookmark.pitch);
+            camera.set_yaw(bookmark.yaw);
+        });
+        println!("🔖 Jumped to camera bookmark {}", bookmark_event.slot);
+    }
+}
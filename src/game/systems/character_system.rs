@@ -0,0 +1,220 @@
+use crate::index::engine::components::{ Collider, ColliderLayer, FootstepEvent, PlayerController, Shape, Transform };
+use crate::index::engine::modules::ecs::insert;
+use crate::index::engine::modules::event_system::{ Event, EventSystem, EventType };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::engine::modules::{ EntityId, InterfaceSystem };
+use crate::index::engine::utils::math::{ dot, len2, normalize };
+use crate::index::game::systems::physics_system::PhysicsSystem;
+use crate::index::PLAYER_ENTITY_ID;
+use crate::get_query_by_id;
+
+/// Ground within this distance below the feet pulls a walking player down onto it (slopes, steps down)
+const GROUND_SNAP: f32 = 0.2;
+
+/// Walks the player in Play mode: gravity and ground contact, walls, steps and steep slopes, jumping and
+/// crouching from the PlayerController input, and footsteps on the ground's surface type. Runs after the MovementSystem has applied this tick's
+/// horizontal movement.
+pub struct CharacterSystem;
+
+impl CharacterSystem {
+    pub fn update() {
+        let Some(player_id) = PLAYER_ENTITY_ID.read().unwrap().clone() else {
+            return;
+        };
+        let (Some(mut controller), Some(mut transform), Some(mut collider)) = (
+            get_query_by_id!(player_id.clone(), (PlayerController)),
+            get_query_by_id!(player_id.clone(), (Transform)),
+            get_query_by_id!(player_id.clone(), (Collider)),
+        ) else {
+            return;
+        };
+
+        // The editor camera flies; stand back up and forget any fall speed
+        if !InterfaceSystem::is_play_mode() {
+            if let Some(stand_height) = controller.stand_height.take() {
+                set_collider_height(&mut collider, stand_height);
+                insert(&player_id, collider);
+            }
+            controller.vertical_velocity = 0.0;
+            controller.grounded = false;
+            controller.slide_speed = 0.0;
+            controller.last_position = None;
+            controller.input = Default::default();
+            insert(&player_id, controller);
+            return;
+        }
+
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        Self::update_crouch(&player_id, &mut controller, &mut transform, &mut collider);
+        let half_height = collider_height(&collider).unwrap_or(0.0) * transform.get_scale()[1] * 0.5;
+        let walkable_normal = controller.slope_limit.to_radians().cos();
+        Self::block_walls(&player_id, &controller, &mut transform, &collider, half_height, walkable_normal);
+
+        if controller.grounded && controller.input.jump {
+            controller.vertical_velocity = controller.jump_speed;
+            controller.grounded = false;
+        }
+        controller.vertical_velocity -= PhysicsSystem::gravity() * delta_time;
+
+        // Land on the highest environment surface under the player that this tick's fall reaches
+        let position = transform.get_position();
+        let fall = (-controller.vertical_velocity * delta_time).max(0.0);
+        let ground = PhysicsSystem::raycast(
+            position,
+            [0.0, -1.0, 0.0],
+            half_height + fall + GROUND_SNAP,
+            &[ColliderLayer::Environment],
+            &[&player_id]
+        );
+        let feet = position[1] - half_height;
+        let was_grounded = controller.grounded;
+        let mut footstep = None;
+        match (ground, controller.last_position) {
+            // A ledge higher than a step the knee ray missed (thin edges): stay where the last tick left off
+            (Some(hit), Some(last)) if controller.grounded && hit.point[1] - feet > controller.step_height => {
+                transform.set_position(last[0], last[1], last[2]);
+                controller.vertical_velocity = 0.0;
+            }
+            (Some(hit), _) if controller.vertical_velocity <= 0.0 => {
+                transform.set_position(position[0], hit.point[1] + half_height, position[2]);
+                controller.vertical_velocity = 0.0;
+                if hit.normal[1] >= walkable_normal {
+                    controller.grounded = true;
+                    controller.slide_speed = 0.0;
+
+                    // A footstep every stride walked, and one when landing from a fall
+                    let walked = controller.last_position.map_or(0.0, |last| {
+                        let current = transform.get_position();
+                        len2([current[0] - last[0], 0.0, current[2] - last[2]]).sqrt()
+                    });
+                    controller.stride_distance += walked;
+                    let landing = !was_grounded && controller.last_position.is_some();
+                    if landing || controller.stride_distance >= controller.stride_length {
+                        controller.stride_distance = 0.0;
+                        footstep = Some(FootstepEvent {
+                            entity_id: player_id.clone(),
+                            surface: hit.material.surface,
+                            position: hit.point,
+                            sprinting: controller.input.sprint,
+                            landing,
+                        });
+                    }
+                } else {
+                    // Too steep to stand on: accelerate down the slope and don't allow jumping off it
+                    controller.grounded = false;
+                    controller.slide_speed += PhysicsSystem::gravity() * (1.0 - hit.normal[1] * hit.normal[1]).sqrt() * delta_time;
+                    let downhill = normalize([hit.normal[0], 0.0, hit.normal[2]]);
+                    let slide = controller.slide_speed * delta_time;
+                    transform.translate(downhill[0] * slide, 0.0, downhill[2] * slide);
+                }
+            }
+            _ => {
+                transform.translate(0.0, controller.vertical_velocity * delta_time, 0.0);
+                controller.grounded = false;
+                controller.slide_speed = 0.0;
+            }
+        }
+        controller.last_position = Some(transform.get_position());
+
+        // Actions only count for the tick they were held in
+        controller.input = Default::default();
+        insert(&player_id, transform);
+        insert(&player_id, collider);
+        insert(&player_id, controller);
+
+        if let Some(footstep) = footstep {
+            EventSystem::send(Event {
+                event_type: EventType::Footstep,
+                payload: Box::new(footstep),
+            });
+        }
+    }
+
+    /// Cast this tick's horizontal move at step height; anything steeper than the slope limit there is a wall,
+    /// so the move keeps only its part along the wall. Lower ledges pass under the ray and are stepped onto.
+    fn block_walls(
+        player_id: &EntityId,
+        controller: &PlayerController,
+        transform: &mut Transform,
+        collider: &Collider,
+        half_height: f32,
+        walkable_normal: f32
+    ) {
+        let Some(last) = controller.last_position else {
+            return;
+        };
+        let position = transform.get_position();
+        let moved = [position[0] - last[0], 0.0, position[2] - last[2]];
+        let distance = len2(moved).sqrt();
+        if distance < 1e-4 {
+            return;
+        }
+        let radius = collider_radius(collider).unwrap_or(0.0) * transform.get_scale()[0];
+        let knee = [last[0], last[1] - half_height + controller.step_height, last[2]];
+        let direction = [moved[0] / distance, 0.0, moved[2] / distance];
+        let Some(wall) = PhysicsSystem::raycast(knee, direction, distance + radius, &[ColliderLayer::Environment], &[player_id]) else {
+            return;
+        };
+        if wall.normal[1] >= walkable_normal {
+            return;
+        }
+
+        let wall_normal = normalize([wall.normal[0], 0.0, wall.normal[2]]);
+        let into_wall = dot(moved, wall_normal).min(0.0);
+        transform.set_position(
+            last[0] + moved[0] - wall_normal[0] * into_wall,
+            position[1],
+            last[2] + moved[2] - wall_normal[2] * into_wall
+        );
+    }
+
+    /// Shrink the collider while crouch is held, keeping the feet in place; stand up again once there is headroom
+    fn update_crouch(player_id: &EntityId, controller: &mut PlayerController, transform: &mut Transform, collider: &mut Collider) {
+        let Some(height) = collider_height(collider) else {
+            return;
+        };
+        let scale = transform.get_scale()[1];
+        let feet_offset = |from: f32, to: f32| if controller.grounded { (to - from) * scale * 0.5 } else { 0.0 };
+
+        match (controller.input.crouch, controller.stand_height) {
+            (true, None) => {
+                let crouch_height = controller.crouch_height.min(height);
+                controller.stand_height = Some(height);
+                set_collider_height(collider, crouch_height);
+                transform.translate(0.0, feet_offset(height, crouch_height), 0.0);
+            }
+            (false, Some(stand_height)) => {
+                let position = transform.get_position();
+                let head = [position[0], position[1] + height * scale * 0.5, position[2]];
+                let rise = (stand_height - height) * scale * if controller.grounded { 1.0 } else { 0.5 };
+                let blocked = PhysicsSystem::raycast(head, [0.0, 1.0, 0.0], rise, &[ColliderLayer::Environment], &[player_id]);
+                if blocked.is_none() {
+                    controller.stand_height = None;
+                    set_collider_height(collider, stand_height);
+                    transform.translate(0.0, feet_offset(height, stand_height), 0.0);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collider_height(collider: &Collider) -> Option<f32> {
+    match collider.shape {
+        Shape::Cylinder { height, .. } | Shape::Capsule { height, .. } => Some(height),
+        _ => None,
+    }
+}
+
+fn collider_radius(collider: &Collider) -> Option<f32> {
+    match collider.shape {
+        Shape::Cylinder { radius, .. } | Shape::Capsule { radius, .. } => Some(radius),
+        _ => None,
+    }
+}
+
+fn set_collider_height(collider: &mut Collider, new_height: f32) {
+    if let Shape::Cylinder { height, .. } | Shape::Capsule { height, .. } = &mut collider.shape {
+        *height = new_height;
+    }
+}
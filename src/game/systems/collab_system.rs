@@ -0,0 +1,127 @@
+use crate::index::engine::components::{ AnimatedObject3DComponent, StaticObject3DComponent, Transform, LAYER_GIZMOS };
+use crate::index::engine::managers::assets_manager::get_wireframe_shader;
+use crate::index::engine::modules::{ CollabSession, EditorSettings, InterfaceSystem, TransformInterpolation };
+use crate::index::engine::rhi::{
+    Buffer,
+    BufferKind,
+    BufferUsage,
+    DepthCompare,
+    DrawCall,
+    Geometry,
+    Primitive,
+    Uniform,
+    VertexAttribute,
+    VertexFormat,
+};
+use crate::index::engine::utils::math::{ cross, len2, normalize, Vec3 };
+use crate::index::engine::utils::mat4x4_identity;
+use crate::index::game::systems::RenderSystem;
+use crate::get_query_by_id;
+
+/// Length of a collaborator's camera marker, along their view direction
+const CAMERA_SIZE: f32 = 0.6;
+/// Half width of the marker's far end, relative to its length
+const CAMERA_SPREAD: f32 = 0.5;
+
+/// Draws the other editors of a collaboration session in the viewport: each one's camera as a pyramid pointing
+/// where they look, and the bounds of what they have selected, in a color of their own
+pub struct CollabSystem;
+
+impl CollabSystem {
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        if InterfaceSystem::is_play_mode() || !RenderSystem::layer_rendered(LAYER_GIZMOS) {
+            return;
+        }
+        let peers = CollabSession::peers();
+        if peers.is_empty() {
+            return;
+        }
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let occluded_brightness = EditorSettings::current().outline.occluded_brightness;
+
+        let mut cameras: Vec<([f32; 3], Vec<[Vec3; 2]>)> = Vec::new();
+        for (peer, presence) in &peers {
+            let color = peer_color(peer);
+            if let Some((position, direction)) = presence.camera {
+                cameras.push((color, camera_lines(position, direction)));
+            }
+            for entity_id in &presence.selection {
+                let Some(mut transform) = get_query_by_id!(entity_id.clone(), (Transform)) else {
+                    continue;
+                };
+                let mesh = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent))
+                    .map(|object| object.mesh)
+                    .or_else(|| {
+                        get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent)).map(|object| object.mesh)
+                    });
+                let Some(mesh) = mesh else {
+                    continue;
+                };
+                let world_txfm = TransformInterpolation::matrix(entity_id, &mut transform);
+                let bounds = mesh.world_bounds(&world_txfm);
+                RenderSystem::render_bounds_box(gl, &view_proj, bounds, color, occluded_brightness);
+            }
+        }
+        if cameras.is_empty() {
+            return;
+        }
+
+        let identity = mat4x4_identity();
+        let shader = get_wireframe_shader();
+        let Ok(buffer) = Buffer::new(gl, BufferKind::Vertex, &[], BufferUsage::Stream) else {
+            return;
+        };
+        let Ok(geometry) = Geometry::new(gl, &[VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)], None) else {
+            buffer.delete(gl);
+            return;
+        };
+        for (color, lines) in &cameras {
+            let vertices: Vec<f32> = lines.iter().flatten().flatten().copied().collect();
+            buffer.write(gl, bytemuck::cast_slice(&vertices), BufferUsage::Stream);
+            DrawCall::new(shader, Primitive::Lines, vertices.len() / 3)
+                .geometry(geometry)
+                .depth(DepthCompare::Always)
+                .uniform("world_txfm", Uniform::Mat4(&identity))
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                .uniform("line_color", Uniform::Vec3(color))
+                .submit(gl);
+        }
+        geometry.delete(gl);
+        buffer.delete(gl);
+    }
+}
+
+/// A bright color picked by the peer ID, so each collaborator keeps theirs across editors
+fn peer_color(peer: &str) -> [f32; 3] {
+    let hash = peer.bytes().fold(2166136261u32, |hash, byte| (hash ^ u32::from(byte)).wrapping_mul(16777619));
+    let hue = (hash % 360) as f32 / 60.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    match hue as u32 {
+        0 => [1.0, x, 0.2],
+        1 => [x, 1.0, 0.2],
+        2 => [0.2, 1.0, x],
+        3 => [0.2, x, 1.0],
+        4 => [x, 0.2, 1.0],
+        _ => [1.0, 0.2, x],
+    }
+}
+
+/// Pyramid from the camera position to a square across its view direction
+fn camera_lines(position: Vec3, direction: Vec3) -> Vec<[Vec3; 2]> {
+    if len2(direction) < 1e-6 {
+        return Vec::new();
+    }
+    let forward = normalize(direction);
+    let side = if forward[1].abs() > 0.99 { [1.0, 0.0, 0.0] } else { [0.0, 1.0, 0.0] };
+    let right = normalize(cross(forward, side));
+    let up = cross(right, forward);
+    let half = CAMERA_SIZE * CAMERA_SPREAD;
+    let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)].map(|(x, y)| {
+        [0, 1, 2].map(|axis| {
+            position[axis] + forward[axis] * CAMERA_SIZE + right[axis] * x * half + up[axis] * y * half
+        })
+    });
+    (0..4).flat_map(|i| [[position, corners[i]], [corners[i], corners[(i + 1) % 4]]]).collect()
+}
@@ -0,0 +1,308 @@
+use std::cell::{ Cell, RefCell };
+use std::collections::HashSet;
+use glow::HasContext;
+
+use crate::index::engine::components::{ ColorGrading, EntityCategory, Metadata };
+use crate::index::engine::managers::AssetPack;
+use crate::index::engine::managers::assets_manager::{ get_color_grading_shader, get_exposure_adapt_shader };
+use crate::index::engine::modules::{ spawn, EngineError, EngineResult, GameLoop, GraphicsQuality, InterfaceSystem };
+use crate::index::engine::rhi::{
+    Blend,
+    DepthCompare,
+    DrawCall,
+    Primitive,
+    Texture,
+    TextureFilter,
+    TextureFormat,
+    TextureWrap,
+    Uniform,
+};
+use crate::index::game::systems::VolumeSystem;
+use crate::{ insert_many, query, query_get_all };
+
+/// Brightness auto exposure starts from before it has adapted to anything, middle grey
+const INITIAL_LUMINANCE: f32 = 0.5;
+/// Longest frame time the adaptation steps by, so a hitch doesn't snap the exposure
+const MAX_ADAPTATION_STEP: f32 = 0.1;
+
+/// Lookup strip loaded from a ColorGrading's `lut`
+struct Lut {
+    path: String,
+    texture: Texture,
+    size: u32,
+}
+
+/// 1x1 target holding the moving average of the frame's brightness
+struct AdaptedLuminance {
+    framebuffer: glow::Framebuffer,
+    texture: Texture,
+}
+
+impl AdaptedLuminance {
+    unsafe fn new(gl: &glow::Context) -> EngineResult<Self> {
+        let texture = Texture::render_target(gl, 1, 1, TextureFormat::R16Float)?;
+        let framebuffer = gl
+            .create_framebuffer()
+            .map_err(|e| EngineError::gpu(format!("Failed to create auto exposure target: {}", e)))?;
+
+        let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(texture.raw()), 0);
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        if status == glow::FRAMEBUFFER_COMPLETE {
+            gl.clear_buffer_f32_slice(glow::COLOR, 0, &[INITIAL_LUMINANCE, 0.0, 0.0, 1.0]);
+        }
+        gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            gl.delete_framebuffer(framebuffer);
+            texture.delete(gl);
+            return Err(EngineError::gpu(format!("Auto exposure target incomplete (status 0x{:x})", status)));
+        }
+        Ok(Self { framebuffer, texture })
+    }
+}
+
+thread_local! {
+    static SCENE_COPY: RefCell<Option<Texture>> = const { RefCell::new(None) };
+    static ADAPTED: RefCell<Option<AdaptedLuminance>> = const { RefCell::new(None) };
+    static LUT: RefCell<Option<Lut>> = const { RefCell::new(None) };
+    // LUT paths that failed to load, reported once instead of every frame
+    static FAILED_LUTS: RefCell<HashSet<String>> = RefCell::new(HashSet::new());
+    // Set once the pass failed on this GL context; the frame stays ungraded
+    static UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Exposure and color grading of the finished frame by the scene's ColorGrading: manual exposure in stops, auto
+/// exposure adapting towards middle grey, contrast, saturation, then a lookup strip for the art direction. A post
+/// effect; scenes without a ColorGrading skip it unless a Volume around the camera grades them.
+pub struct ColorGradingSystem;
+
+impl ColorGradingSystem {
+    /// The scene's grading, the first ColorGrading in the world
+    pub fn current() -> Option<ColorGrading> {
+        query_get_all!(ColorGrading)
+            .into_iter()
+            .next()
+            .map(|(_, grading)| grading)
+    }
+
+    /// The scene's grading with the exposure, contrast and saturation of the Volumes around the camera blended in
+    pub fn graded() -> Option<ColorGrading> {
+        let volumes = VolumeSystem::current();
+        let blends = [volumes.exposure, volumes.contrast, volumes.saturation];
+        let grading = match Self::current() {
+            Some(grading) => grading,
+            None if blends.iter().any(|blend| blend.is_active()) => ColorGrading::default(),
+            None => return None,
+        };
+        Some(ColorGrading {
+            exposure: volumes.exposure.apply(grading.exposure),
+            contrast: volumes.contrast.apply(grading.contrast),
+            saturation: volumes.saturation.apply(grading.saturation),
+            ..grading
+        })
+    }
+
+    /// Change one setting by name (editor sliders); creates the scene's ColorGrading if it has none
+    pub fn set(name: &str, value: f32) -> Result<ColorGrading, String> {
+        Self::edit(|grading| {
+            match name {
+                "exposure" => grading.exposure = value.clamp(-8.0, 8.0),
+                "auto_exposure" => grading.auto_exposure = value != 0.0,
+                "adaptation_speed" => grading.adaptation_speed = value.clamp(0.1, 10.0),
+                "contrast" => grading.contrast = value.clamp(0.0, 3.0),
+                "saturation" => grading.saturation = value.clamp(0.0, 3.0),
+                "lut_strength" => grading.lut_strength = value.clamp(0.0, 1.0),
+                _ => return Err(format!("Unknown color grading setting {}", name)),
+            }
+            Ok(())
+        })
+    }
+
+    /// Grade through a lookup strip, or none for an empty path
+    pub fn set_lut(path: &str) -> Result<ColorGrading, String> {
+        let path = path.trim();
+        Self::edit(|grading| {
+            grading.lut = (!path.is_empty()).then(|| path.to_string());
+            Ok(())
+        })
+    }
+
+    fn edit(change: impl Fn(&mut ColorGrading) -> Result<(), String>) -> Result<ColorGrading, String> {
+        let mut edited = None;
+        let mut result = Ok(());
+        query!((ColorGrading), |_entity_id, grading| {
+            if edited.is_none() {
+                result = change(&mut grading);
+                edited = Some(grading.clone());
+            }
+        });
+        result?;
+        if let Some(grading) = edited {
+            return Ok(grading);
+        }
+
+        let mut grading = ColorGrading::default();
+        change(&mut grading)?;
+        let entity_id = spawn();
+        insert_many!(
+            entity_id,
+            grading.clone(),
+            Metadata::new("Color Grading", Some("color_grading"), None).with_category(EntityCategory::Light)
+        );
+        println!("🎞️ Added ColorGrading to the scene");
+        InterfaceSystem::update_entities_list();
+        Ok(grading)
+    }
+
+    /// Post pass for the player camera; runs after transparency, before the editor overlays which stay ungraded
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let delta_time = GameLoop::frame_delta();
+
+        let Some(grading) = Self::graded() else {
+            return;
+        };
+        if !GraphicsQuality::current().post_effects || UNAVAILABLE.with(Cell::get) {
+            return;
+        }
+        let Some(scene_copy) = Self::copy_scene(gl, width, height) else {
+            return;
+        };
+
+        let adapted = if grading.auto_exposure {
+            match Self::adapt(gl, scene_copy, delta_time, grading.adaptation_speed) {
+                Ok(adapted) => Some(adapted),
+                Err(e) => {
+                    Self::disable(&e);
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        let lut = grading.lut.as_deref().and_then(|path| Self::lut(gl, path));
+
+        let mut draw = DrawCall::new(get_color_grading_shader(), Primitive::Triangles, 3)
+            .depth(DepthCompare::Always)
+            .blend(Blend::Alpha)
+            .uniform("scene_color", Uniform::Int(0))
+            .uniform("adapted_luminance", Uniform::Int(1))
+            .uniform("lut", Uniform::Int(2))
+            .uniform("auto_exposure", Uniform::Int(adapted.is_some() as i32))
+            .uniform("exposure", Uniform::Float(grading.exposure))
+            .uniform("contrast", Uniform::Float(grading.contrast))
+            .uniform("saturation", Uniform::Float(grading.saturation))
+            .uniform("has_lut", Uniform::Int(lut.is_some() as i32))
+            .uniform("lut_strength", Uniform::Float(grading.lut_strength))
+            .texture(0, scene_copy);
+        if let Some(adapted) = adapted {
+            draw = draw.texture(1, adapted);
+        }
+        if let Some((texture, size)) = lut {
+            draw = draw.uniform("lut_size", Uniform::Float(size as f32)).texture(2, texture);
+        }
+        draw.submit(gl);
+    }
+
+    /// Copy of the frame to grade, resized with the viewport
+    fn copy_scene(gl: &glow::Context, width: u32, height: u32) -> Option<Texture> {
+        SCENE_COPY.with(|scene_copy| {
+            let mut scene_copy = scene_copy.borrow_mut();
+            match *scene_copy {
+                Some(texture) if texture.size() == [width, height] => texture.copy_framebuffer(gl),
+                _ => {
+                    if let Some(old) = scene_copy.take() {
+                        old.delete(gl);
+                    }
+                    match Texture::from_framebuffer(gl, width, height) {
+                        Ok(texture) => *scene_copy = Some(texture),
+                        Err(e) => Self::disable(&e),
+                    }
+                }
+            }
+            *scene_copy
+        })
+    }
+
+    /// Move the adapted brightness towards the frame's average, by the adaptation speed per second
+    fn adapt(gl: &glow::Context, scene_copy: Texture, delta_time: f32, speed: f32) -> EngineResult<Texture> {
+        ADAPTED.with(|adapted| {
+            let mut adapted = adapted.borrow_mut();
+            let adapted = match &mut *adapted {
+                Some(adapted) => adapted,
+                empty => empty.insert(unsafe { AdaptedLuminance::new(gl)? }),
+            };
+
+            // The smallest mip of the frame is its average color
+            scene_copy.generate_mipmaps(gl);
+            let adaptation = 1.0 - (-delta_time.min(MAX_ADAPTATION_STEP) * speed).exp();
+            unsafe {
+                let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+                let mut previous_viewport = [0i32; 4];
+                gl.get_parameter_i32_slice(glow::VIEWPORT, &mut previous_viewport);
+                gl.bind_framebuffer(glow::FRAMEBUFFER, Some(adapted.framebuffer));
+                gl.viewport(0, 0, 1, 1);
+
+                DrawCall::new(get_exposure_adapt_shader(), Primitive::Triangles, 3)
+                    .depth(DepthCompare::Always)
+                    .blend(Blend::Alpha)
+                    .uniform("scene_color", Uniform::Int(0))
+                    .uniform("mip_level", Uniform::Float((scene_copy.mip_levels() - 1) as f32))
+                    .uniform("adaptation", Uniform::Float(adaptation))
+                    .texture(0, scene_copy)
+                    .submit(gl);
+
+                gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+                gl.viewport(previous_viewport[0], previous_viewport[1], previous_viewport[2], previous_viewport[3]);
+            }
+            Ok(adapted.texture)
+        })
+    }
+
+    /// The lookup strip at `path` and its slice size, loaded on first use; None (reported once) if it can't be read
+    fn lut(gl: &glow::Context, path: &str) -> Option<(Texture, u32)> {
+        if FAILED_LUTS.with(|failed| failed.borrow().contains(path)) {
+            return None;
+        }
+        LUT.with(|lut| {
+            let mut lut = lut.borrow_mut();
+            if lut.as_ref().is_none_or(|lut| lut.path != path) {
+                if let Some(old) = lut.take() {
+                    old.texture.delete(gl);
+                }
+                match Self::load_lut(gl, path) {
+                    Ok(loaded) => {
+                        println!("🎞️ Loaded color grading LUT {} ({} slices)", path, loaded.size);
+                        *lut = Some(loaded);
+                    }
+                    Err(e) => {
+                        FAILED_LUTS.with(|failed| failed.borrow_mut().insert(path.to_string()));
+                        InterfaceSystem::report_error(&e);
+                        return None;
+                    }
+                }
+            }
+            lut.as_ref().map(|lut| (lut.texture, lut.size))
+        })
+    }
+
+    fn load_lut(gl: &glow::Context, path: &str) -> EngineResult<Lut> {
+        let bytes = AssetPack::read_file(path)?;
+        let image = image::load_from_memory(&bytes)
+            .map_err(|e| EngineError::texture(path, format!("Failed to read LUT: {}", e)))?
+            .to_rgba8();
+        let size = image.height();
+        if size < 2 || image.width() != size * size {
+            return Err(EngineError::texture(path, "LUT must be a strip of N slices of N x N, e.g. 256x16"));
+        }
+        let texture = Texture::rgba8(gl, image.width(), size, image.as_raw(), TextureFilter::Linear, TextureWrap::ClampToEdge)?;
+        Ok(Lut { path: path.to_string(), texture, size })
+    }
+
+    fn disable(error: &EngineError) {
+        UNAVAILABLE.with(|unavailable| unavailable.set(true));
+        InterfaceSystem::report_error(error);
+        eprintln!("⚠️ Color grading unavailable on this GPU");
+    }
+}
@@ -0,0 +1,151 @@
+use std::cell::{ Cell, RefCell };
+use std::collections::{ BTreeSet, HashMap };
+use std::sync::RwLock;
+
+use crate::index::engine::components::{ AnimatedObject3DComponent, Metadata, StaticObject3DComponent, LAYER_GIZMOS };
+use crate::index::engine::components::SharedComponents::Transform;
+use crate::index::engine::modules::{ entity_exists, EditorSettings, EntityId, TransformInterpolation };
+use crate::index::engine::utils::mat4x4_mul;
+use crate::index::game::systems::RenderSystem;
+use crate::get_query_by_id;
+
+/// Render AABB of an entity the camera pass drew
+const DRAWN_COLOR: [f32; 3] = [0.2, 1.0, 0.3];
+/// Render AABB of an entity frustum culled this frame
+const CULLED_COLOR: [f32; 3] = [1.0, 0.25, 0.2];
+/// Render AABB of an entity hidden by its Metadata or render layer, or not drawn by the mesh passes
+const HIDDEN_COLOR: [f32; 3] = [0.5, 0.5, 0.5];
+/// Bounds of an animated mesh in its current pose
+const SKINNED_COLOR: [f32; 3] = [0.3, 0.8, 1.0];
+
+/// Entities whose bounds and culling are shown, toggled from the outliner
+static DEBUGGED: RwLock<BTreeSet<EntityId>> = RwLock::new(BTreeSet::new());
+
+thread_local! {
+    // Set while the player camera's opaque pass runs with entities being debugged
+    static RECORDING: Cell<bool> = const { Cell::new(false) };
+    // Whether each debugged entity was frustum culled by the last camera pass
+    static CULLED: RefCell<HashMap<EntityId, bool>> = RefCell::new(HashMap::new());
+}
+
+/// Per-entity culling debug, to find objects popping in and out: draws the render AABB the culling tests (green
+/// when drawn, red when frustum culled, grey when hidden) and, for animated meshes, the bounds of the current pose,
+/// which the rest-pose AABB has to cover. Wet-crab has no occlusion culling, so the frustum and Metadata/layer
+/// hiding are the only reasons a mesh isn't drawn
+pub struct CullingDebugSystem;
+
+impl CullingDebugSystem {
+    /// Show or hide an entity's bounds; returns whether they're shown now
+    pub fn toggle(entity_id: &str) -> bool {
+        let mut debugged = DEBUGGED.write().unwrap();
+        if debugged.remove(entity_id) {
+            return false;
+        }
+        debugged.insert(entity_id.to_string());
+        true
+    }
+
+    pub fn is_debugged(entity_id: &str) -> bool {
+        DEBUGGED.read().unwrap().contains(entity_id)
+    }
+
+    /// Run the player camera's mesh pass, keeping the culling results of the debugged entities
+    pub fn record(pass: impl FnOnce()) {
+        let recording = !DEBUGGED.read().unwrap().is_empty();
+        CULLED.with(|culled| culled.borrow_mut().clear());
+        RECORDING.with(|current| current.set(recording));
+        pass();
+        RECORDING.with(|current| current.set(false));
+    }
+
+    /// Note whether a mesh pass culled an entity; ignored outside `record` and for entities not being debugged
+    pub fn culled(entity_id: &EntityId, culled: bool) {
+        if RECORDING.with(Cell::get) && Self::is_debugged(entity_id) {
+            CULLED.with(|results| results.borrow_mut().insert(entity_id.clone(), culled));
+        }
+    }
+
+    /// Render pass drawing the bounds of the debugged entities
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        if DEBUGGED.read().unwrap().is_empty() || !RenderSystem::layer_rendered(LAYER_GIZMOS) {
+            return;
+        }
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let occluded_brightness = EditorSettings::current().outline.occluded_brightness;
+
+        let debugged: Vec<EntityId> = DEBUGGED.read().unwrap().iter().cloned().collect();
+        for entity_id in debugged {
+            let Some(mut transform) = get_query_by_id!(entity_id.clone(), (Transform)) else {
+                continue;
+            };
+            let world_txfm = TransformInterpolation::matrix(&entity_id, &mut transform);
+            let animated_object = get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent));
+            let mesh = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent))
+                .map(|object| object.mesh)
+                .or_else(|| animated_object.as_ref().map(|object| object.mesh.clone()));
+            let Some(mesh) = mesh else {
+                continue;
+            };
+
+            let color = match CULLED.with(|culled| culled.borrow().get(&entity_id).copied()) {
+                _ if RenderSystem::is_hidden(&entity_id) => HIDDEN_COLOR,
+                Some(true) => CULLED_COLOR,
+                Some(false) => DRAWN_COLOR,
+                None => HIDDEN_COLOR,
+            };
+            RenderSystem::render_bounds_box(gl, &view_proj, mesh.world_bounds(&world_txfm), color, occluded_brightness);
+
+            if let Some(bounds) = animated_object.and_then(|object| Self::skinned_bounds(&object, &world_txfm)) {
+                RenderSystem::render_bounds_box(gl, &view_proj, bounds, SKINNED_COLOR, occluded_brightness);
+            }
+        }
+    }
+
+    /// World AABB of an animated mesh in its current pose: its rest bounds under every joint's skinning matrix
+    fn skinned_bounds(
+        animated_object: &AnimatedObject3DComponent,
+        world_txfm: &[f32; 16]
+    ) -> Option<([f32; 3], [f32; 3])> {
+        animated_object.mesh.skin?;
+        let (bone_matrices, inverse_bone_matrices) = RenderSystem::bone_matrices(&animated_object.skeleton);
+        bone_matrices
+            .iter()
+            .zip(&inverse_bone_matrices)
+            .map(|(bone, inverse)| {
+                let skinning = mat4x4_mul(*bone, *inverse);
+                animated_object.mesh.world_bounds(&mat4x4_mul(*world_txfm, skinning))
+            })
+            .reduce(|(a_min, a_max), (b_min, b_max)| {
+                (
+                    [a_min[0].min(b_min[0]), a_min[1].min(b_min[1]), a_min[2].min(b_min[2])],
+                    [a_max[0].max(b_max[0]), a_max[1].max(b_max[1]), a_max[2].max(b_max[2])],
+                )
+            })
+    }
+
+    /// Console: `culling` lists the debugged entities and what the last camera pass did with them
+    pub fn culling_command(_args: &[&str]) -> Result<String, String> {
+        let debugged = DEBUGGED.read().unwrap().clone();
+        if debugged.is_empty() {
+            return Ok("No entities debugged; toggle their bounds in the outliner".to_string());
+        }
+        let lines: Vec<String> = debugged
+            .iter()
+            .filter(|entity_id| entity_exists(entity_id))
+            .map(|entity_id| {
+                let title = get_query_by_id!(entity_id.clone(), (Metadata))
+                    .map_or_else(|| entity_id.clone(), |metadata| metadata.title().to_string());
+                let state = match CULLED.with(|culled| culled.borrow().get(entity_id).copied()) {
+                    _ if RenderSystem::is_hidden(entity_id) => "hidden by its Metadata or render layer",
+                    Some(true) => "frustum culled",
+                    Some(false) => "drawn",
+                    None => "not drawn by the mesh passes",
+                };
+                format!("{}: {}", title, state)
+            })
+            .collect();
+        Ok(lines.join("\n"))
+    }
+}
@@ -0,0 +1,172 @@
+use std::cell::{ Cell, RefCell };
+use glow::HasContext;
+
+use crate::index::engine::components::StaticObject3DComponent;
+use crate::index::engine::components::SharedComponents::Transform;
+use crate::index::engine::managers::MaterialLibrary;
+use crate::index::engine::managers::assets_manager::{ get_depth_only_shader, get_static_shader };
+use crate::index::engine::modules::{ GraphicsQuality, TransformInterpolation };
+use crate::index::engine::rhi::{ DepthCompare, DrawCall, Primitive, Uniform };
+use crate::index::engine::utils::view_projection_eye;
+use crate::index::game::systems::{ ImpostorSystem, RenderSystem };
+use crate::query;
+
+/// Fragment counts of the last measured frame, for the `overdraw` command
+#[derive(Clone, Copy)]
+struct FragmentStats {
+    prepass: Option<u32>, // Static mesh fragments passing the pre-pass depth test, all shaded without a pre-pass
+    shaded: u32,          // Fragments the opaque pass shaded
+    samples: u32,         // Viewport pixels times MSAA samples, what one layer of geometry would shade
+}
+
+/// Occlusion queries counting the fragments of one frame, read back a frame later so the CPU never waits on them
+struct Queries {
+    prepass: glow::Query,
+    shading: glow::Query,
+    pending: Option<(bool, u32)>, // Whether the pre-pass ran, and the sample count, of the frame being counted
+}
+
+thread_local! {
+    static QUERIES: RefCell<Option<Queries>> = const { RefCell::new(None) };
+    static STATS: Cell<Option<FragmentStats>> = const { Cell::new(None) };
+    // Set when fragment counting isn't available (GLES and WebGL only have boolean occlusion queries)
+    static UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Depth pre-pass: when the graphics settings enable it (high preset), opaque static meshes are drawn depth-only
+/// before the lit pass, which then only shades the nearest surface of each pixel instead of every overlapping one;
+/// pays off in dense scenes
+pub struct DepthPrepassSystem;
+
+impl DepthPrepassSystem {
+    /// Run the player camera's opaque pass, after the depth pre-pass when the graphics settings enable it. `pass`
+    /// gets the depth test for static meshes: LessEqual so they pass against their own pre-pass depth.
+    pub fn opaque_pass(gl: &glow::Context, width: u32, height: u32, view_proj: &[f32; 16], pass: impl FnOnce(DepthCompare)) {
+        Self::read_stats(gl);
+        let queries = Self::queries(gl);
+
+        let prepass = GraphicsQuality::current().depth_prepass;
+        unsafe {
+            if prepass {
+                if let Some((prepass_query, _)) = queries {
+                    gl.begin_query(glow::SAMPLES_PASSED, prepass_query);
+                }
+                gl.color_mask(false, false, false, false);
+                Self::draw(gl, view_proj);
+                gl.color_mask(true, true, true, true);
+                if queries.is_some() {
+                    gl.end_query(glow::SAMPLES_PASSED);
+                }
+            }
+
+            if let Some((_, shading_query)) = queries {
+                gl.begin_query(glow::SAMPLES_PASSED, shading_query);
+            }
+            pass(if prepass { DepthCompare::LessEqual } else { DepthCompare::Less });
+            if queries.is_some() {
+                gl.end_query(glow::SAMPLES_PASSED);
+                let samples = gl.get_parameter_i32(glow::SAMPLES).max(1) as u32;
+                QUERIES.with(|current| {
+                    if let Some(current) = current.borrow_mut().as_mut() {
+                        current.pending = Some((prepass, width * height * samples));
+                    }
+                });
+            }
+        }
+    }
+
+    /// Depth-only draws of the meshes the lit pass draws with the static shader, whose vertex stage is shared; user
+    /// materials may move vertices or discard fragments, so their meshes go without
+    fn draw(gl: &glow::Context, view_proj: &[f32; 16]) {
+        let static_shader = get_static_shader();
+        let depth_only_shader = get_depth_only_shader();
+        let eye = view_projection_eye(view_proj);
+        query!((Transform, StaticObject3DComponent), |entity_id, transform, static_object| {
+            if
+                RenderSystem::is_hidden(&entity_id) ||
+                ImpostorSystem::replaces(&entity_id) ||
+                static_object.material.transparent ||
+                static_object.material.pipeline != static_shader ||
+                MaterialLibrary::material_of(&entity_id).is_some()
+            {
+                continue;
+            }
+            let world_txfm = TransformInterpolation::matrix(&entity_id, &mut transform);
+            let mesh = static_object.mesh.lod_at(&world_txfm, eye);
+            DrawCall::new(depth_only_shader, Primitive::Triangles, mesh.index_count)
+                .geometry(mesh.geometry)
+                .uniform("world_txfm", Uniform::Mat4(&world_txfm))
+                .uniform("viewport_txfm", Uniform::Mat4(view_proj))
+                .submit(gl);
+        });
+    }
+
+    /// Query objects of the opaque pass, created on first use; None where fragments can't be counted
+    fn queries(gl: &glow::Context) -> Option<(glow::Query, glow::Query)> {
+        if UNAVAILABLE.with(Cell::get) {
+            return None;
+        }
+        QUERIES.with(|queries| {
+            let mut queries = queries.borrow_mut();
+            if queries.is_none() {
+                let created = (!gl.version().is_embedded)
+                    .then(|| unsafe { Some((gl.create_query().ok()?, gl.create_query().ok()?)) })
+                    .flatten();
+                let Some((prepass, shading)) = created else {
+                    UNAVAILABLE.with(|unavailable| unavailable.set(true));
+                    return None;
+                };
+                *queries = Some(Queries { prepass, shading, pending: None });
+            }
+            queries.as_ref().map(|queries| (queries.prepass, queries.shading))
+        })
+    }
+
+    /// Take the counts of the frame the queries last measured once the GPU has them
+    fn read_stats(gl: &glow::Context) {
+        QUERIES.with(|queries| {
+            let mut queries = queries.borrow_mut();
+            let Some(queries) = queries.as_mut() else {
+                return;
+            };
+            let Some((prepass, samples)) = queries.pending else {
+                return;
+            };
+            unsafe {
+                if gl.get_query_parameter_u32(queries.shading, glow::QUERY_RESULT_AVAILABLE) == 0 {
+                    return;
+                }
+                let stats = FragmentStats {
+                    prepass: prepass.then(|| gl.get_query_parameter_u32(queries.prepass, glow::QUERY_RESULT)),
+                    shaded: gl.get_query_parameter_u32(queries.shading, glow::QUERY_RESULT),
+                    samples,
+                };
+                STATS.with(|current| current.set(Some(stats)));
+            }
+            queries.pending = None;
+        });
+    }
+
+    /// Console: `overdraw` prints the fragments the last measured frame shaded, with the pre-pass's saving
+    pub fn overdraw_command(_args: &[&str]) -> Result<String, String> {
+        if UNAVAILABLE.with(Cell::get) {
+            return Err("Fragment counts need desktop GL occlusion queries".to_string());
+        }
+        let stats = STATS.with(Cell::get).ok_or("No frame measured yet")?;
+        let per_pixel = |fragments: u32| fragments as f32 / stats.samples.max(1) as f32;
+        let shaded = format!("{} fragments shaded ({:.2} per pixel)", stats.shaded, per_pixel(stats.shaded));
+        Ok(match stats.prepass {
+            Some(prepass) => {
+                let saved = prepass.saturating_sub(stats.shaded);
+                format!(
+                    "Depth pre-pass on: {}; static meshes alone would shade {} ({:.2} per pixel) without it, {} saved",
+                    shaded,
+                    prepass,
+                    per_pixel(prepass),
+                    saved
+                )
+            }
+            None => format!("Depth pre-pass off: {}", shaded),
+        })
+    }
+}
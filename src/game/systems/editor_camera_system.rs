@@ -0,0 +1,148 @@
+use std::f32::consts::{ FRAC_PI_2, PI };
+use std::sync::RwLock;
+use std::time::Instant;
+
+use crate::index::engine::components::{ CameraComponent, CameraProjection, Metadata, SystemTrait, Transform };
+use crate::index::engine::modules::event_system::Event;
+use crate::index::engine::modules::keyboard_input_system::{ FrameTarget, ViewPreset };
+use crate::index::engine::modules::{ EntityId, Selection };
+use crate::index::game::systems::render_system::{ RenderSystem, CAMERA_FOV_DEGREES };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ query_by_id, query_get_all };
+
+/// Seconds the editor camera takes to fly to a framing target
+const FRAME_DURATION: f32 = 0.35;
+/// Extra room around the framed bounds
+const FRAME_PADDING: f32 = 1.2;
+/// Smallest framed radius, so single points don't put the camera inside them
+const MIN_FRAME_RADIUS: f32 = 0.5;
+
+/// Editor camera move in progress, eased over wall-clock time so it also runs while paused
+struct CameraFlight {
+    from: [f32; 3],
+    to: [f32; 3],
+    started: Instant,
+}
+
+static FLIGHT: RwLock<Option<CameraFlight>> = RwLock::new(None);
+
+/// Editor viewport camera commands (frame selection, frame all, axis view presets)
+#[derive(Debug)]
+pub struct EditorCameraSystem;
+
+impl EditorCameraSystem {
+    /// Fly to the selected entities
+    pub fn frame_selection() {
+        let selected = Selection::ids();
+        if selected.is_empty() {
+            println!("⚠️ Select an entity to frame it");
+            return;
+        }
+        Self::frame(&selected);
+    }
+
+    /// Fly back until every visible entity is in view
+    pub fn frame_all() {
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone();
+        let entity_ids: Vec<EntityId> = query_get_all!(Metadata)
+            .into_iter()
+            .filter(|(entity_id, metadata)| metadata.is_visible && player_id.as_ref() != Some(entity_id))
+            .map(|(entity_id, _)| entity_id)
+            .collect();
+        Self::frame(&entity_ids);
+    }
+
+    /// Keep the current view direction and back off from the combined AABB until its bounding sphere fits
+    fn frame(entity_ids: &[EntityId]) {
+        let Some((min, max)) = RenderSystem::combined_bounds(entity_ids) else {
+            println!("⚠️ Nothing to frame");
+            return;
+        };
+        let (Some((_, _, forward)), Some(from)) = (RenderSystem::camera_basis(), RenderSystem::camera_position()) else {
+            return;
+        };
+
+        let center = [(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5, (min[2] + max[2]) * 0.5];
+        let extent = [max[0] - min[0], max[1] - min[1], max[2] - min[2]];
+        let radius = ((extent[0] * extent[0] + extent[1] * extent[1] + extent[2] * extent[2]).sqrt() * 0.5).max(
+            MIN_FRAME_RADIUS
+        );
+        let distance = (radius / (CAMERA_FOV_DEGREES.to_radians() * 0.5).sin()) * FRAME_PADDING;
+
+        // The view looks down -forward, so the camera sits on the +forward side of the target
+        let to = [center[0] + forward[0] * distance, center[1] + forward[1] * distance, center[2] + forward[2] * distance];
+        *FLIGHT.write().unwrap() = Some(CameraFlight { from, to, started: Instant::now() });
+
+        // Orthographic views frame by zoom instead of distance
+        if let Some(player_id) = PLAYER_ENTITY_ID.read().unwrap().clone() {
+            query_by_id!(player_id, (CameraComponent), |camera| {
+                if camera.projection == CameraProjection::Orthographic {
+                    camera.ortho_size = radius * FRAME_PADDING;
+                }
+            });
+        }
+    }
+
+    /// Look along a world axis in orthographic projection, or toggle between perspective and orthographic
+    pub fn apply_view_preset(preset: ViewPreset) {
+        let Some(player_id) = PLAYER_ENTITY_ID.read().unwrap().clone() else {
+            return;
+        };
+        // (pitch, yaw) looking along -forward, see build_view_matrix
+        let angles = match preset {
+            ViewPreset::Top => Some((FRAC_PI_2, 0.0)),
+            ViewPreset::Bottom => Some((-FRAC_PI_2, 0.0)),
+            ViewPreset::Front => Some((0.0, 0.0)),
+            ViewPreset::Back => Some((0.0, PI)),
+            ViewPreset::Left => Some((0.0, FRAC_PI_2)),
+            ViewPreset::Right => Some((0.0, -FRAC_PI_2)),
+            ViewPreset::TogglePerspective => None,
+        };
+        query_by_id!(player_id, (CameraComponent), |camera| {
+            match angles {
+                Some((pitch, yaw)) => {
+                    camera.set_view_angles(pitch, yaw);
+                    camera.projection = CameraProjection::Orthographic;
+                }
+                None => camera.toggle_projection(),
+            }
+            println!("📐 View preset {:?} ({:?})", preset, camera.projection);
+        });
+    }
+
+    /// Advance the camera flight; runs every frame before rendering
+    pub fn update(_gl: &glow::Context, _width: u32, _height: u32) {
+        let mut flight = FLIGHT.write().unwrap();
+        let Some(current) = flight.as_ref() else {
+            return;
+        };
+        let Some(player_id) = PLAYER_ENTITY_ID.read().unwrap().clone() else {
+            return;
+        };
+
+        let t = (current.started.elapsed().as_secs_f32() / FRAME_DURATION).min(1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        let position = [0, 1, 2].map(|axis| current.from[axis] + (current.to[axis] - current.from[axis]) * eased);
+        query_by_id!(player_id, (Transform), |transform| {
+            transform.set_position(position[0], position[1], position[2]);
+        });
+
+        if t >= 1.0 {
+            *flight = None;
+        }
+    }
+}
+
+impl SystemTrait for EditorCameraSystem {
+    fn event(&self, event: &Event) {
+        if let Some(preset) = event.payload.downcast_ref::<ViewPreset>() {
+            Self::apply_view_preset(*preset);
+            return;
+        }
+        match event.payload.downcast_ref::<FrameTarget>() {
+            Some(FrameTarget::Selection) => Self::frame_selection(),
+            Some(FrameTarget::All) => Self::frame_all(),
+            None => {}
+        }
+    }
+}
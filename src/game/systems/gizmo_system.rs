@@ -0,0 +1,263 @@
+use std::f32::consts::TAU;
+
+use crate::index::engine::components::{
+    CameraComponent,
+    CameraProjection,
+    Metadata,
+    PointLight,
+    Transform,
+    LAYER_EDITOR_ICONS,
+    LAYER_GIZMOS,
+};
+use crate::index::engine::managers::assets_manager::get_wireframe_shader;
+use crate::index::engine::modules::{ EntityId, InterfaceSystem, Selection };
+use crate::index::engine::rhi::{
+    Buffer,
+    BufferKind,
+    BufferUsage,
+    DrawCall,
+    Geometry,
+    Primitive,
+    Uniform,
+    VertexAttribute,
+    VertexFormat,
+};
+use crate::index::engine::utils::{ mat4x4_extract_translation, mat4x4_identity };
+use crate::index::game::systems::render_system::CAMERA_FOV_DEGREES;
+use crate::index::game::systems::RenderSystem;
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query_get_all };
+
+/// Half size of the light and camera icons in world units, also how close a click has to pass to pick one
+const ICON_SIZE: f32 = 0.3;
+/// Segments of the circles in icons and light range spheres
+const CIRCLE_SEGMENTS: usize = 32;
+/// Length of the frustum drawn for perspective cameras; orthographic ones draw their view box this deep
+const FRUSTUM_DEPTH: f32 = 2.0;
+/// Icon and frustum color of cameras
+const CAMERA_COLOR: [f32; 3] = [0.8, 0.8, 0.8];
+/// Icon and range color of lights that are switched off
+const DISABLED_LIGHT_COLOR: [f32; 3] = [0.35, 0.35, 0.35];
+
+/// Component an entity gets a gizmo for; entities with both show the light
+enum GizmoKind {
+    Light(PointLight),
+    Camera(CameraComponent),
+}
+
+/// Editor view of entities with no mesh: a camera-facing icon for every light and camera, which a click picks,
+/// and the light's range sphere or the camera's frustum while selected or hovered. The player's own camera is the
+/// view and gets none.
+pub struct GizmoSystem;
+
+impl GizmoSystem {
+    /// Render system: draw icons on the editor icon layer and shapes on the gizmo layer
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let icons = RenderSystem::layer_rendered(LAYER_EDITOR_ICONS);
+        let shapes = RenderSystem::layer_rendered(LAYER_GIZMOS);
+        if !icons && !shapes || InterfaceSystem::is_play_mode() {
+            return;
+        }
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let Some((right, up, _)) = RenderSystem::camera_basis() else {
+            return;
+        };
+        let (_, hovered_id) = InterfaceSystem::get_selection_state();
+        let aspect_ratio = width as f32 / height.max(1) as f32;
+
+        // One line batch per color so each is a single draw call
+        let mut batches: Vec<([f32; 3], Vec<f32>)> = Vec::new();
+        for (entity_id, kind, center) in Self::gizmos() {
+            let highlighted = Selection::contains(&entity_id) || entity_id == hovered_id.as_str();
+            let (color, glyph, shape) = match &kind {
+                GizmoKind::Light(light) => {
+                    let color = if light.enabled { Self::light_color(light) } else { DISABLED_LIGHT_COLOR };
+                    let shape = (shapes && highlighted).then(|| Self::sphere(center, light.range));
+                    (color, Self::bulb_glyph(), shape)
+                }
+                GizmoKind::Camera(camera) => {
+                    let shape = (shapes && highlighted).then(|| Self::frustum(center, camera, aspect_ratio));
+                    (CAMERA_COLOR, Self::camera_glyph(), shape)
+                }
+            };
+            let index = match batches.iter().position(|(batch_color, _)| *batch_color == color) {
+                Some(index) => index,
+                None => {
+                    batches.push((color, Vec::new()));
+                    batches.len() - 1
+                }
+            };
+            let vertices = &mut batches[index].1;
+            if icons {
+                for [u, v] in glyph.into_iter().flatten() {
+                    vertices.extend((0..3).map(|axis| center[axis] + (right[axis] * u + up[axis] * v) * ICON_SIZE));
+                }
+            }
+            vertices.extend(shape.into_iter().flatten().flatten().flatten());
+        }
+        batches.retain(|(_, vertices)| !vertices.is_empty());
+        if batches.is_empty() {
+            return;
+        }
+
+        let identity = mat4x4_identity();
+        let shader = get_wireframe_shader();
+        let Ok(buffer) = Buffer::new(gl, BufferKind::Vertex, &[], BufferUsage::Stream) else {
+            return;
+        };
+        let Ok(geometry) = Geometry::new(gl, &[VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)], None) else {
+            buffer.delete(gl);
+            return;
+        };
+        for (color, vertices) in &batches {
+            buffer.write(gl, bytemuck::cast_slice(vertices), BufferUsage::Stream);
+            DrawCall::new(shader, Primitive::Lines, vertices.len() / 3)
+                .geometry(geometry)
+                .uniform("world_txfm", Uniform::Mat4(&identity))
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                .uniform("line_color", Uniform::Vec3(color))
+                .submit(gl);
+        }
+        geometry.delete(gl);
+        buffer.delete(gl);
+    }
+
+    /// The unlocked gizmo entity whose icon a viewport click at `pixel` lands on, nearest first
+    pub fn pick(pixel: [f32; 2], width: u32, height: u32) -> Option<EntityId> {
+        if InterfaceSystem::is_play_mode() || !RenderSystem::layer_rendered(LAYER_EDITOR_ICONS) {
+            return None;
+        }
+        let (origin, direction) = RenderSystem::camera_ray(pixel, width, height)?;
+        Self::gizmos()
+            .into_iter()
+            .filter(|(entity_id, _, _)| {
+                !get_query_by_id!(entity_id.clone(), (Metadata)).is_some_and(|metadata| metadata.locked)
+            })
+            .filter_map(|(entity_id, _, center)| {
+                // Distance along the ray to the icon, and how far the ray passes from its center
+                let offset: [f32; 3] = [0, 1, 2].map(|axis| center[axis] - origin[axis]);
+                let along = offset[0] * direction[0] + offset[1] * direction[1] + offset[2] * direction[2];
+                let closest = [0, 1, 2].map(|axis| offset[axis] - direction[axis] * along);
+                let miss = (closest[0] * closest[0] + closest[1] * closest[1] + closest[2] * closest[2]).sqrt();
+                (along > 0.0 && miss <= ICON_SIZE).then_some((entity_id, along))
+            })
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(entity_id, _)| entity_id)
+    }
+
+    /// Visible lights and cameras, except the player's, with their world positions
+    fn gizmos() -> Vec<(EntityId, GizmoKind, [f32; 3])> {
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone();
+        let mut gizmos = Vec::new();
+        for (entity_id, mut transform) in query_get_all!(Transform) {
+            if player_id.as_ref() == Some(&entity_id) || RenderSystem::is_hidden(&entity_id) {
+                continue;
+            }
+            let kind = if let Some(light) = get_query_by_id!(entity_id.clone(), (PointLight)) {
+                GizmoKind::Light(light)
+            } else if let Some(camera) = get_query_by_id!(entity_id.clone(), (CameraComponent)) {
+                GizmoKind::Camera(camera)
+            } else {
+                continue;
+            };
+            let center = mat4x4_extract_translation(transform.get_matrix());
+            gizmos.push((entity_id, kind, center));
+        }
+        gizmos
+    }
+
+    /// The light's hue at full brightness, so dim and bright lights are equally visible
+    fn light_color(light: &PointLight) -> [f32; 3] {
+        let brightest = light.color.into_iter().fold(0.0, f32::max);
+        if brightest <= f32::EPSILON {
+            return DISABLED_LIGHT_COLOR;
+        }
+        light.color.map(|channel| channel / brightest)
+    }
+
+    /// Light bulb icon segments in camera-plane units (-1..1): a round bulb over a screw base
+    fn bulb_glyph() -> Vec<[[f32; 2]; 2]> {
+        let mut lines = Self::circle_glyph([0.0, 0.25], 0.6);
+        lines.extend([
+            [[-0.3, -0.45], [-0.3, -0.9]],
+            [[0.3, -0.45], [0.3, -0.9]],
+            [[-0.3, -0.6], [0.3, -0.6]],
+            [[-0.3, -0.75], [0.3, -0.75]],
+            [[-0.3, -0.9], [0.3, -0.9]],
+        ]);
+        lines
+    }
+
+    /// Movie camera icon segments in camera-plane units: a body, its lens and two film reels
+    fn camera_glyph() -> Vec<[[f32; 2]; 2]> {
+        let mut lines = vec![
+            [[-0.9, -0.6], [0.4, -0.6]],
+            [[0.4, -0.6], [0.4, 0.1]],
+            [[0.4, 0.1], [-0.9, 0.1]],
+            [[-0.9, 0.1], [-0.9, -0.6]],
+            [[0.4, -0.25], [0.9, -0.55]],
+            [[0.9, -0.55], [0.9, 0.05]],
+            [[0.9, 0.05], [0.4, -0.25]],
+        ];
+        lines.extend(Self::circle_glyph([-0.55, 0.4], 0.3));
+        lines.extend(Self::circle_glyph([0.05, 0.4], 0.3));
+        lines
+    }
+
+    fn circle_glyph(center: [f32; 2], radius: f32) -> Vec<[[f32; 2]; 2]> {
+        let point = |i: usize| {
+            let (s, c) = (i as f32 / CIRCLE_SEGMENTS as f32 * TAU).sin_cos();
+            [center[0] + c * radius, center[1] + s * radius]
+        };
+        (0..CIRCLE_SEGMENTS).map(|i| [point(i), point(i + 1)]).collect()
+    }
+
+    /// Line segments of three great circles around a sphere
+    fn sphere(center: [f32; 3], radius: f32) -> Vec<[[f32; 3]; 2]> {
+        let mut lines = Vec::with_capacity(CIRCLE_SEGMENTS * 3);
+        for (a, b) in [(0, 1), (1, 2), (0, 2)] {
+            let point = |i: usize| {
+                let (s, c) = (i as f32 / CIRCLE_SEGMENTS as f32 * TAU).sin_cos();
+                let mut point = center;
+                point[a] += c * radius;
+                point[b] += s * radius;
+                point
+            };
+            lines.extend((0..CIRCLE_SEGMENTS).map(|i| [point(i), point(i + 1)]));
+        }
+        lines
+    }
+
+    /// Line segments of a camera's view volume out to FRUSTUM_DEPTH: a pyramid in perspective, a box when
+    /// orthographic, with a tick on the up side of the far end
+    fn frustum(center: [f32; 3], camera: &CameraComponent, aspect_ratio: f32) -> Vec<[[f32; 3]; 2]> {
+        let (right, up, forward) = RenderSystem::basis_of(camera);
+        let (near_half, far_half) = match camera.projection {
+            CameraProjection::Perspective => (0.0, (CAMERA_FOV_DEGREES.to_radians() * 0.5).tan() * FRUSTUM_DEPTH),
+            CameraProjection::Orthographic => (camera.ortho_size, camera.ortho_size),
+        };
+        let corner = |depth: f32, half: f32, x: f32, y: f32| {
+            [0, 1, 2].map(|axis| {
+                center[axis] - forward[axis] * depth + (right[axis] * x * aspect_ratio + up[axis] * y) * half
+            })
+        };
+        let corners = [(-1.0, -1.0), (1.0, -1.0), (1.0, 1.0), (-1.0, 1.0)];
+        let near = corners.map(|(x, y)| corner(0.0, near_half, x, y));
+        let far = corners.map(|(x, y)| corner(FRUSTUM_DEPTH, far_half, x, y));
+
+        let mut lines = Vec::with_capacity(15);
+        for i in 0..4 {
+            lines.push([far[i], far[(i + 1) % 4]]);
+            lines.push([near[i], far[i]]);
+            if near_half > 0.0 {
+                lines.push([near[i], near[(i + 1) % 4]]);
+            }
+        }
+        let tick = corner(FRUSTUM_DEPTH, far_half, 0.0, 1.3);
+        lines.push([far[2], tick]);
+        lines.push([tick, far[3]]);
+        lines
+    }
+}
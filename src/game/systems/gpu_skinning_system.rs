@@ -0,0 +1,218 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use crate::index::engine::components::Lighting;
+use crate::index::engine::components::SharedComponents::{ Material, Mesh };
+use crate::index::engine::managers::assets_manager::{ preprocess_shader, Assets };
+use crate::index::engine::modules::{ Console, EngineResult, InterfaceSystem };
+use crate::index::engine::rhi::{ supports_compute, Buffer, BufferKind, BufferUsage, Dispatch, Pipeline, Uniform };
+use crate::index::game::systems::RenderSystem;
+
+/// Default for the `gpu_skinning` cvar: 1 skins and culls animated meshes in compute shaders where supported
+pub const GPU_SKINNING: f32 = 1.0;
+/// Bone palette size per instance, `MAX_JOINTS` in the skinning shaders
+pub const MAX_JOINTS: usize = 20;
+/// Morph targets blended per mesh, `MAX_MORPH_TARGETS` in the skinning shaders; a multiple of 4 since the vertex
+/// shader takes the weights as vec4s
+pub const MAX_MORPH_TARGETS: usize = 32;
+/// Texels per row of a morph target texture, `MORPH_ROW` in the skinning shaders
+pub const MORPH_ROW: usize = 2048;
+/// Texture unit of the morph target texture in both skinning paths
+pub const MORPH_UNIT: u32 = 7;
+/// `local_size_x` of both compute shaders
+const WORKGROUP_SIZE: usize = 64;
+/// The skinning pass runs one row of work groups per instance, and GL only guarantees 65535 rows
+const MAX_INSTANCES: usize = 65535;
+
+/// One animated entity drawn through the compute path
+pub struct SkinnedInstance {
+    pub world_txfm: [f32; 16],
+    pub palette: Vec<f32>, // MAX_JOINTS row-major bone * inverse bind matrices
+    pub morph_weights: [f32; MAX_MORPH_TARGETS], // Zero past the mesh's targets
+}
+
+struct Programs {
+    cull: Pipeline,
+    skin: Pipeline,
+    draw: Pipeline,
+}
+
+/// Buffers of one asset's instances; the ones shaders write are sized for `capacity` instances
+struct Batch {
+    instances: Buffer,
+    palettes: Buffer,
+    morph_weights: Buffer,
+    visible: Buffer,
+    command: Buffer,
+    skinned: Buffer,
+    capacity: usize,
+}
+
+impl Batch {
+    fn new(gl: &glow::Context, capacity: usize, vertex_count: usize) -> EngineResult<Self> {
+        Ok(Self {
+            instances: Buffer::new(gl, BufferKind::Storage, &[], BufferUsage::Stream)?,
+            palettes: Buffer::new(gl, BufferKind::Storage, &[], BufferUsage::Stream)?,
+            morph_weights: Buffer::new(gl, BufferKind::Storage, &[], BufferUsage::Stream)?,
+            visible: Buffer::uninitialized(gl, BufferKind::Storage, capacity * 4, BufferUsage::Stream)?,
+            command: Buffer::new(gl, BufferKind::Indirect, &[], BufferUsage::Stream)?,
+            // Position and normal as two vec4 per vertex
+            skinned: Buffer::uninitialized(gl, BufferKind::Storage, capacity * vertex_count * 32, BufferUsage::Stream)?,
+            capacity,
+        })
+    }
+
+    fn delete(self, gl: &glow::Context) {
+        for buffer in [self.instances, self.palettes, self.morph_weights, self.visible, self.command, self.skinned] {
+            buffer.delete(gl);
+        }
+    }
+}
+
+thread_local! {
+    /// None until the first frame checks for compute support, then the programs if they're usable
+    static PROGRAMS: RefCell<Option<Option<Programs>>> = const { RefCell::new(None) };
+    static BATCHES: RefCell<HashMap<Assets, Batch>> = RefCell::new(HashMap::new());
+}
+
+/// Skins and frustum-culls animated meshes in compute shaders and draws each asset's visible instances with one
+/// indirect draw, so large crowds cost little CPU time. Needs GL 4.3; RenderSystem keeps skinning in the vertex
+/// shader elsewhere and when the `gpu_skinning` cvar is 0.
+pub struct GpuSkinningSystem;
+
+impl GpuSkinningSystem {
+    /// Whether animated meshes go through the compute path; compiles its programs the first time
+    pub fn enabled(gl: &glow::Context) -> bool {
+        if Console::cvar("gpu_skinning").unwrap_or(GPU_SKINNING) < 0.5 {
+            return false;
+        }
+        PROGRAMS.with(|programs| programs.borrow_mut().get_or_insert_with(|| Self::create_programs(gl)).is_some())
+    }
+
+    fn create_programs(gl: &glow::Context) -> Option<Programs> {
+        if !supports_compute(gl) {
+            println!("ℹ️  No GL 4.3 compute shaders, skinning animated meshes in the vertex shader");
+            return None;
+        }
+        // The skinned vertex shader is GLSL 4.30, so the shared fragment shader has to match its version
+        let programs = (|| -> EngineResult<Programs> {
+            let fragment_source = preprocess_shader("skinned", include_str!("../../assets/shaders/fragment_animated.glsl"), &[])?
+                .replacen("#version 300 es", "#version 430 core", 1);
+            let cull_source = preprocess_shader("instance_cull", include_str!("../../assets/shaders/compute_cull.glsl"), &[])?;
+            let skin_source = preprocess_shader("skinning", include_str!("../../assets/shaders/compute_skinning.glsl"), &[])?;
+            let vertex_source = preprocess_shader("skinned", include_str!("../../assets/shaders/vertex_skinned.glsl"), &[])?;
+            Ok(Programs {
+                cull: Pipeline::compute(gl, "instance_cull", &cull_source)?,
+                skin: Pipeline::compute(gl, "skinning", &skin_source)?,
+                draw: Pipeline::new(gl, "skinned", &vertex_source, &fragment_source)?,
+            })
+        })();
+        match programs {
+            Ok(programs) => {
+                println!("✅ GPU skinning and culling enabled");
+                Some(programs)
+            }
+            Err(e) => {
+                InterfaceSystem::report_error(&e);
+                None
+            }
+        }
+    }
+
+    /// Cull, skin and draw the instances of one asset; `mesh` needs skin buffers
+    pub fn draw(
+        gl: &glow::Context,
+        asset: Assets,
+        mesh: &Mesh,
+        material: &Material,
+        instances: &[SkinnedInstance],
+        view_proj: &[f32; 16],
+        lighting: &Lighting
+    ) {
+        let Some(skin) = mesh.skin else {
+            return;
+        };
+        let instances = &instances[..instances.len().min(MAX_INSTANCES)];
+        if instances.is_empty() || mesh.vertex_count == 0 {
+            return;
+        }
+
+        PROGRAMS.with(|programs| {
+            let programs = programs.borrow();
+            let Some(Some(programs)) = programs.as_ref() else {
+                return;
+            };
+
+            BATCHES.with(|batches| {
+                let mut batches = batches.borrow_mut();
+                if batches.get(&asset).is_none_or(|batch| batch.capacity < instances.len()) {
+                    if let Some(old) = batches.remove(&asset) {
+                        old.delete(gl);
+                    }
+                    match Batch::new(gl, instances.len().next_power_of_two(), mesh.vertex_count) {
+                        Ok(batch) => {
+                            batches.insert(asset, batch);
+                        }
+                        Err(e) => {
+                            InterfaceSystem::report_error(&e);
+                            return;
+                        }
+                    }
+                }
+                let batch = &batches[&asset];
+
+                let world_txfms: Vec<f32> = instances.iter().flat_map(|instance| instance.world_txfm).collect();
+                let palettes: Vec<f32> = instances.iter().flat_map(|instance| instance.palette.iter().copied()).collect();
+                batch.instances.write(gl, bytemuck::cast_slice(&world_txfms), BufferUsage::Stream);
+                batch.palettes.write(gl, bytemuck::cast_slice(&palettes), BufferUsage::Stream);
+                if mesh.morph.is_some() {
+                    let morph_weights: Vec<f32> = instances.iter().flat_map(|instance| instance.morph_weights).collect();
+                    batch.morph_weights.write(gl, bytemuck::cast_slice(&morph_weights), BufferUsage::Stream);
+                }
+                // Indexed draw of the whole mesh; the culling pass counts the instances
+                let command: [u32; 5] = [mesh.index_count as u32, 0, 0, 0, 0];
+                batch.command.write(gl, bytemuck::cast_slice(&command), BufferUsage::Stream);
+
+                let (bounds_min, bounds_max) = mesh.bounds;
+                Dispatch::new(programs.cull, [instances.len().div_ceil(WORKGROUP_SIZE) as u32, 1, 1])
+                    .uniform("viewport_txfm", Uniform::Mat4(view_proj))
+                    .uniform("total_instances", Uniform::Int(instances.len() as i32))
+                    .uniform("bounds_min", Uniform::Vec3(&bounds_min))
+                    .uniform("bounds_max", Uniform::Vec3(&bounds_max))
+                    .storage(4, batch.instances)
+                    .storage(6, batch.visible)
+                    .storage(7, batch.command)
+                    .submit(gl);
+
+                let groups = [mesh.vertex_count.div_ceil(WORKGROUP_SIZE) as u32, instances.len() as u32, 1];
+                let skinning = Dispatch::new(programs.skin, groups)
+                    .uniform("vertex_count", Uniform::Int(mesh.vertex_count as i32))
+                    .storage(0, skin.positions)
+                    .storage(1, skin.normals)
+                    .storage(2, skin.joints)
+                    .storage(3, skin.weights)
+                    .storage(4, batch.instances)
+                    .storage(5, batch.palettes)
+                    .storage(6, batch.visible)
+                    .storage(7, batch.command)
+                    .storage(8, batch.skinned)
+                    .storage(9, batch.morph_weights);
+                let skinning = match &mesh.morph {
+                    Some(morph) => skinning
+                        .uniform("morph_target_count", Uniform::Int(morph.count as i32))
+                        .uniform("morph_texture", Uniform::Int(MORPH_UNIT as i32))
+                        .texture(MORPH_UNIT, morph.texture),
+                    None => skinning.uniform("morph_target_count", Uniform::Int(0)),
+                };
+                skinning.submit(gl);
+
+                let material = Material { pipeline: programs.draw, ..material.clone() };
+                RenderSystem::mesh_draw(mesh, &material, view_proj, lighting)
+                    .uniform("vertex_count", Uniform::Int(mesh.vertex_count as i32))
+                    .storage(8, batch.skinned)
+                    .indirect(batch.command)
+                    .submit(gl);
+            });
+        });
+    }
+}
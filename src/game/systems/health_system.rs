@@ -0,0 +1,115 @@
+use std::sync::RwLock;
+
+use crate::index::engine::components::{
+    DamageEvent,
+    DeathBehavior,
+    DeathEvent,
+    Faction,
+    Health,
+    SystemTrait,
+};
+use crate::index::engine::modules::event_system::{ Event, EventSystem, EventType };
+use crate::index::engine::modules::{ is_enabled, EntityId, EntityPool };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query_by_id };
+
+/// Called for entities dying with DeathBehavior::Ragdoll
+pub type RagdollHook = fn(&EntityId);
+
+static RAGDOLL_HOOK: RwLock<Option<RagdollHook>> = RwLock::new(None);
+
+/// Applies Damage events to Health components and handles deaths
+#[derive(Debug)]
+pub struct HealthSystem;
+
+impl HealthSystem {
+    /// Console command `give_health [amount]`: heal the player (default to full)
+    pub fn give_health_command(args: &[&str]) -> Result<String, String> {
+        let amount = match args.first() {
+            Some(amount) => amount.parse::<f32>().map_err(|_| "Usage: give_health [amount]".to_string())?,
+            None => f32::MAX,
+        };
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone().ok_or("There is no player")?;
+        if get_query_by_id!(player_id.clone(), (Health)).is_none() {
+            return Err("The player has no Health".to_string());
+        }
+        // Heal right away rather than at the next drain so the reply shows the new health
+        EventSystem::notify(Self::damage_event(&player_id, -amount, None));
+        let health = get_query_by_id!(player_id, (Health)).ok_or("The player has no Health")?;
+        Ok(format!("Player health {}/{}", health.current, health.max))
+    }
+
+    /// Send a Damage event (negative amounts heal), applied at the next event drain
+    pub fn apply_damage(target: &EntityId, amount: f32, source: Option<&EntityId>) {
+        EventSystem::send(Self::damage_event(target, amount, source));
+    }
+
+    fn damage_event(target: &EntityId, amount: f32, source: Option<&EntityId>) -> Event {
+        Event {
+            event_type: EventType::Damage,
+            payload: Box::new(DamageEvent {
+                target: target.clone(),
+                amount,
+                source: source.cloned(),
+            }),
+        }
+    }
+
+    pub fn set_ragdoll_hook(hook: RagdollHook) {
+        *RAGDOLL_HOOK.write().unwrap() = Some(hook);
+    }
+
+    fn handle_death(entity_id: &EntityId, killer: Option<EntityId>, on_death: DeathBehavior) {
+        println!("💀 Entity {} died", entity_id);
+        EventSystem::send(Event {
+            event_type: EventType::Death,
+            payload: Box::new(DeathEvent { entity: entity_id.clone(), killer }),
+        });
+
+        match on_death {
+            DeathBehavior::Despawn => {
+                EntityPool::despawn(entity_id);
+            }
+            DeathBehavior::Ragdoll => {
+                match *RAGDOLL_HOOK.read().unwrap() {
+                    Some(hook) => hook(entity_id),
+                    None => println!("⚠️ No ragdoll hook registered, leaving {} in place", entity_id),
+                }
+            }
+            DeathBehavior::Nothing => {}
+        }
+    }
+}
+
+impl SystemTrait for HealthSystem {
+    fn event(&self, event: &Event) {
+        let Some(damage) = event.payload.downcast_ref::<DamageEvent>() else {
+            return;
+        };
+        if !is_enabled(&damage.target) {
+            return;
+        }
+
+        // Friendly fire is ignored between entities of the same faction
+        if let Some(source) = damage.source.as_ref() {
+            let source_faction = get_query_by_id!(source, (Faction));
+            let target_faction = get_query_by_id!(damage.target, (Faction));
+            if let (Some(source_faction), Some(target_faction)) = (source_faction, target_faction) {
+                if damage.amount > 0.0 && !source_faction.is_hostile_to(&target_faction) {
+                    return;
+                }
+            }
+        }
+
+        let mut died = None;
+        query_by_id!(damage.target, (Health), |health| {
+            if health.apply(damage.amount) {
+                died = Some(health.on_death.clone());
+            }
+        });
+
+        if let Some(on_death) = died {
+            Self::handle_death(&damage.target, damage.source.clone(), on_death);
+        }
+    }
+}
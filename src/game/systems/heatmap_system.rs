@@ -0,0 +1,263 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use glow::HasContext;
+
+use crate::index::engine::components::{ AnimatedObject3DComponent, BakedOcclusion, StaticObject3DComponent };
+use crate::index::engine::components::SharedComponents::{ Mesh, MeshData, Transform };
+use crate::index::engine::managers::assets_manager::{ get_animated_outline_shader, get_static_outline_shader };
+use crate::index::engine::managers::MaterialLibrary;
+use crate::index::engine::modules::{ Console, EntityId, InterfaceSystem, TransformInterpolation };
+use crate::index::engine::rhi::{ Blend, DepthCompare, DrawCall, Primitive, Uniform };
+use crate::index::engine::utils::{ cross, len2 };
+use crate::index::game::systems::gpu_skinning_system::MORPH_UNIT;
+use crate::index::game::systems::RenderSystem;
+use crate::query_get_all;
+
+/// Default for the `heatmap_cost_budget` cvar: estimated draw cost of one object shown red, in triangles
+pub const HEATMAP_COST_BUDGET: f32 = 20000.0;
+/// Default for the `heatmap_triangle_budget` cvar: triangles of one object shown red
+pub const HEATMAP_TRIANGLE_BUDGET: f32 = 5000.0;
+/// Default for the `heatmap_bake_density` cvar: baked lighting samples per square unit shown green
+pub const HEATMAP_BAKE_DENSITY: f32 = 4.0;
+/// Overhead of one draw call in triangles, so many small meshes stand out as well as a few dense ones
+const DRAW_CALL_COST: f32 = 500.0;
+/// Color each mesh layer adds in the overdraw view; about six layers saturate to white
+const OVERDRAW_STEP: [f32; 3] = [0.18, 0.1, 0.05];
+/// Background the heat colors are drawn over; terrain and water keep their depth but aren't colored
+const BACKGROUND: [f32; 3] = [0.05, 0.05, 0.07];
+/// Meshes a view can't measure (lightmap density of animated or CPU-less meshes)
+const NO_DATA_COLOR: [f32; 3] = [0.3, 0.3, 0.3];
+/// Cold to hot
+const GRADIENT: [[f32; 3]; 5] = [
+    [0.0, 0.2, 1.0],
+    [0.0, 0.9, 0.9],
+    [0.1, 0.9, 0.1],
+    [1.0, 0.9, 0.0],
+    [1.0, 0.1, 0.0],
+];
+
+/// What the viewport heatmap colors objects by
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeatmapMode {
+    Off,
+    DrawCost,        // Estimated cost of drawing the object: triangles, skinning, blending and material
+    Overdraw,        // How many mesh surfaces cover each pixel, ignoring depth
+    Triangles,       // Triangle count of the object's mesh
+    LightmapDensity, // Baked lighting (per-vertex occlusion) samples per square unit of surface
+}
+
+impl HeatmapMode {
+    pub fn name(self) -> &'static str {
+        match self {
+            HeatmapMode::Off => "Off",
+            HeatmapMode::DrawCost => "Draw Cost",
+            HeatmapMode::Overdraw => "Overdraw",
+            HeatmapMode::Triangles => "Triangles",
+            HeatmapMode::LightmapDensity => "Lightmap Density",
+        }
+    }
+
+    /// How to read the colors of the mode
+    pub fn legend(self) -> String {
+        match self {
+            HeatmapMode::Off => String::new(),
+            HeatmapMode::DrawCost => format!(
+                "blue cheap to red at {} (heatmap_cost_budget)",
+                Console::cvar("heatmap_cost_budget").unwrap_or(HEATMAP_COST_BUDGET)
+            ),
+            HeatmapMode::Overdraw => "each covering surface adds red, then yellow, then white".to_string(),
+            HeatmapMode::Triangles => format!(
+                "blue few to red at {} triangles (heatmap_triangle_budget)",
+                Console::cvar("heatmap_triangle_budget").unwrap_or(HEATMAP_TRIANGLE_BUDGET)
+            ),
+            HeatmapMode::LightmapDensity => format!(
+                "green at {} samples per square unit (heatmap_bake_density), blue 4x sparser, red 4x denser, grey unbakeable",
+                Console::cvar("heatmap_bake_density").unwrap_or(HEATMAP_BAKE_DENSITY)
+            ),
+        }
+    }
+}
+
+static MODE: RwLock<HeatmapMode> = RwLock::new(HeatmapMode::Off);
+
+thread_local! {
+    // World surface area of measured meshes with the transform they were measured at
+    static AREAS: RefCell<HashMap<EntityId, ([f32; 16], f32)>> = RefCell::new(HashMap::new());
+}
+
+/// Editor statistics views: replaces the shaded scene with flat colors per object showing what is expensive to
+/// draw, so heavy scenes show where to optimize
+pub struct HeatmapSystem;
+
+impl HeatmapSystem {
+    pub fn mode() -> HeatmapMode {
+        *MODE.read().unwrap()
+    }
+
+    /// Switch to the next view (Off last) and return it
+    pub fn cycle() -> HeatmapMode {
+        let mut mode = MODE.write().unwrap();
+        *mode = match *mode {
+            HeatmapMode::Off => HeatmapMode::DrawCost,
+            HeatmapMode::DrawCost => HeatmapMode::Overdraw,
+            HeatmapMode::Overdraw => HeatmapMode::Triangles,
+            HeatmapMode::Triangles => HeatmapMode::LightmapDensity,
+            HeatmapMode::LightmapDensity => HeatmapMode::Off,
+        };
+        if *mode == HeatmapMode::Off {
+            AREAS.with(|areas| areas.borrow_mut().clear());
+        }
+        *mode
+    }
+
+    /// Render system: draw the heatmap over the finished scene, keeping its depth so hidden objects stay hidden;
+    /// outlines and gizmos still draw on top
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let mode = Self::mode();
+        if mode == HeatmapMode::Off || InterfaceSystem::is_play_mode() {
+            return;
+        }
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        unsafe {
+            let [r, g, b] = if mode == HeatmapMode::Overdraw { [0.0; 3] } else { BACKGROUND };
+            gl.clear_color(r, g, b, 1.0);
+            gl.clear(glow::COLOR_BUFFER_BIT);
+        }
+
+        for (entity_id, mut transform, static_object) in query_get_all!(Transform, StaticObject3DComponent) {
+            if RenderSystem::is_hidden(&entity_id) {
+                continue;
+            }
+            let world_txfm = TransformInterpolation::matrix(&entity_id, &mut transform);
+            let mesh = &static_object.mesh;
+            let color = match mode {
+                HeatmapMode::LightmapDensity => match &mesh.data {
+                    Some(data) => Self::density_color(&entity_id, data, &world_txfm),
+                    None => NO_DATA_COLOR,
+                },
+                _ => Self::color(mode, &entity_id, mesh, false, static_object.material.transparent),
+            };
+            let draw = DrawCall::new(get_static_outline_shader(), Primitive::Triangles, mesh.index_count)
+                .geometry(mesh.geometry)
+                .uniform("world_txfm", Uniform::Mat4(&world_txfm))
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj));
+            Self::submit(gl, mode, draw, &color);
+        }
+
+        for (entity_id, mut transform, animated_object) in query_get_all!(Transform, AnimatedObject3DComponent) {
+            if RenderSystem::is_hidden(&entity_id) {
+                continue;
+            }
+            let world_txfm = TransformInterpolation::matrix(&entity_id, &mut transform);
+            let mesh = &animated_object.mesh;
+            // Animated objects aren't baked
+            let color = match mode {
+                HeatmapMode::LightmapDensity => NO_DATA_COLOR,
+                _ => Self::color(mode, &entity_id, mesh, true, animated_object.material.transparent),
+            };
+            let (bone_matrices, inverse_bone_matrices) = RenderSystem::bone_matrices(&animated_object.skeleton);
+            let flat_bones: Vec<f32> = bone_matrices.iter().flatten().copied().collect();
+            let flat_inverse: Vec<f32> = inverse_bone_matrices.iter().flatten().copied().collect();
+            let morph_weights = RenderSystem::morph_weights(&animated_object.morph_weights);
+            let draw = DrawCall::new(get_animated_outline_shader(), Primitive::Triangles, mesh.index_count)
+                .geometry(mesh.geometry)
+                .uniform("world_txfm", Uniform::Mat4(&world_txfm))
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                .uniform("inverse_bone_matrix", Uniform::Mat4Array(&flat_inverse))
+                .uniform("bone_matrix", Uniform::Mat4Array(&flat_bones));
+            let draw = match &mesh.morph {
+                Some(morph) => draw
+                    .uniform("morph_target_count", Uniform::Int(morph.count as i32))
+                    .uniform("morph_weights", Uniform::Vec4Array(&morph_weights))
+                    .uniform("morph_texture", Uniform::Int(MORPH_UNIT as i32))
+                    .texture(MORPH_UNIT, morph.texture),
+                None => draw.uniform("morph_target_count", Uniform::Int(0)),
+            };
+            Self::submit(gl, mode, draw, &color);
+        }
+    }
+
+    /// Flat color draw with the outline shaders at their original size; overdraw adds up every surface
+    fn submit<'a>(gl: &glow::Context, mode: HeatmapMode, draw: DrawCall<'a>, color: &'a [f32; 3]) {
+        let draw = draw.uniform("outline_width", Uniform::Float(0.0)).uniform("outline_color", Uniform::Vec3(color));
+        match mode {
+            HeatmapMode::Overdraw => draw.depth(DepthCompare::Always).blend(Blend::Additive),
+            _ => draw.depth(DepthCompare::LessEqual),
+        }.submit(gl);
+    }
+
+    /// Heat color of a draw cost or triangle count view
+    fn color(mode: HeatmapMode, entity_id: &EntityId, mesh: &Mesh, animated: bool, transparent: bool) -> [f32; 3] {
+        let triangles = (mesh.index_count / 3) as f32;
+        match mode {
+            HeatmapMode::Overdraw => OVERDRAW_STEP,
+            HeatmapMode::Triangles => {
+                let budget = Console::cvar("heatmap_triangle_budget").unwrap_or(HEATMAP_TRIANGLE_BUDGET);
+                heat_color(triangles / budget.max(1.0))
+            }
+            _ => {
+                // Rough per-frame weights: skinning transforms every vertex, blended surfaces are drawn in the
+                // transparency passes too, and user materials run their own shader
+                let mut factor = 1.0;
+                if animated {
+                    factor *= 2.0;
+                }
+                if let Some(morph) = &mesh.morph {
+                    factor *= 1.0 + morph.count as f32 * 0.25;
+                }
+                if transparent {
+                    factor *= 2.0;
+                }
+                if MaterialLibrary::material_of(entity_id).is_some() {
+                    factor *= 1.5;
+                }
+                let budget = Console::cvar("heatmap_cost_budget").unwrap_or(HEATMAP_COST_BUDGET);
+                heat_color((triangles * factor + DRAW_CALL_COST) / budget.max(1.0))
+            }
+        }
+    }
+
+    /// Baked lighting is stored per vertex, so its density is vertices per square unit of world surface
+    fn density_color(entity_id: &EntityId, data: &MeshData, world_txfm: &[f32; 16]) -> [f32; 3] {
+        let area = Self::surface_area(entity_id, data, world_txfm);
+        if area <= f32::EPSILON {
+            return NO_DATA_COLOR;
+        }
+        let target = Console::cvar("heatmap_bake_density").unwrap_or(HEATMAP_BAKE_DENSITY).max(0.001);
+        let density = (data.positions.len() / 3) as f32 / area;
+        // 4x below the target is cold, 4x above hot
+        heat_color(0.5 + (density / target).log2() * 0.25)
+    }
+
+    /// World surface area of a mesh, measured again only when the entity's transform changes
+    fn surface_area(entity_id: &EntityId, data: &MeshData, world_txfm: &[f32; 16]) -> f32 {
+        AREAS.with(|areas| {
+            let mut areas = areas.borrow_mut();
+            if let Some((measured_txfm, area)) = areas.get(entity_id) {
+                if measured_txfm == world_txfm {
+                    return *area;
+                }
+            }
+            let area = BakedOcclusion::world_triangles(data, world_txfm)
+                .iter()
+                .map(|[a, b, c]| {
+                    let edge = |to: &[f32; 3]| [0, 1, 2].map(|axis| to[axis] - a[axis]);
+                    len2(cross(edge(b), edge(c))).sqrt() * 0.5
+                })
+                .sum();
+            areas.insert(entity_id.clone(), (*world_txfm, area));
+            area
+        })
+    }
+}
+
+/// Gradient color of a heat from 0 (cold) to 1 (hot), clamped
+fn heat_color(heat: f32) -> [f32; 3] {
+    let scaled = heat.clamp(0.0, 1.0) * (GRADIENT.len() - 1) as f32;
+    let index = (scaled.floor() as usize).min(GRADIENT.len() - 2);
+    let t = scaled - index as f32;
+    [0, 1, 2].map(|channel| GRADIENT[index][channel] + (GRADIENT[index + 1][channel] - GRADIENT[index][channel]) * t)
+}
@@ -0,0 +1,20 @@
+use crate::index::engine::components::Health;
+use crate::index::engine::modules::HudBridge;
+use crate::index::PLAYER_ENTITY_ID;
+use crate::get_query_by_id;
+
+pub struct HudSystem;
+
+impl HudSystem {
+    /// Feed the player's health to the HUD (the bridge skips unchanged values)
+    pub fn update() {
+        let player_health = PLAYER_ENTITY_ID.read()
+            .unwrap()
+            .as_ref()
+            .and_then(|player_id| get_query_by_id!(player_id, (Health)));
+
+        if let Some(health) = player_health {
+            HudBridge::set_health(health.current, health.max);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+use std::cell::{ Cell, RefCell };
+use std::collections::{ HashMap, HashSet };
+use glow::HasContext;
+
+use crate::index::engine::components::{ Lighting, StaticObject3DComponent };
+use crate::index::engine::components::SharedComponents::Transform;
+use crate::index::engine::managers::assets_manager::get_impostor_shader;
+use crate::index::engine::managers::PrefabLibrary;
+use crate::index::engine::modules::{ EngineError, EntityId, InterfaceSystem };
+use crate::index::engine::rhi::{ Blend, DrawCall, Primitive, Texture, TextureFilter, TextureWrap, Uniform };
+use crate::index::engine::utils::{ mat4x4_look_at, mat4x4_mul, mat4x4_orthographic };
+use crate::index::game::systems::{ AmbientOcclusionSystem, LightClusterSystem, RenderSystem };
+use crate::get_query_by_id;
+
+/// Side of the square texture a prefab's impostor is baked into
+const IMPOSTOR_RESOLUTION: u32 = 256;
+/// Fraction of a prefab's impostor distance where its impostor starts fading in over the meshes
+const FADE_START: f32 = 0.8;
+
+/// Impostor drawn this frame in place of a prefab instance
+struct Billboard {
+    center: [f32; 3],
+    half_size: [f32; 2], // Horizontal radius and half height of the instance's bounds
+    fade: f32,
+    texture: Texture,
+}
+
+thread_local! {
+    // Baked impostor of each prefab seen past its impostor distance; None when baking failed
+    static BAKED: RefCell<HashMap<String, Option<Texture>>> = RefCell::new(HashMap::new());
+    // Impostors of prefabs whose distance changed, deleted on the next update
+    static RETIRED: RefCell<Vec<Texture>> = const { RefCell::new(Vec::new()) };
+    static BILLBOARDS: RefCell<Vec<Billboard>> = const { RefCell::new(Vec::new()) };
+    // Entities of instances whose impostor is fully faded in, which the player camera's pass leaves out
+    static REPLACED: RefCell<HashSet<EntityId>> = RefCell::new(HashSet::new());
+    // Set while the player camera's mesh pass runs
+    static CAMERA_PASS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Impostors for distant props, opted in per prefab (`impostor <prefab> <distance>`): past the distance an
+/// instance draws as a billboard of its static meshes baked from the side, turned about the vertical towards the
+/// camera. The billboard fades in over the meshes from FADE_START of the distance, and the meshes stop drawing
+/// once it's opaque, so the switch doesn't pop
+pub struct ImpostorSystem;
+
+impl ImpostorSystem {
+    /// Pick the prefab instances far enough from the player camera for impostors, baking the impostor of a prefab
+    /// the first time one of its instances is; runs before the frame's light clusters are built
+    pub fn update(gl: &glow::Context, _width: u32, _height: u32) {
+        for texture in RETIRED.with(|retired| retired.take()) {
+            texture.delete(gl);
+        }
+        let mut billboards = Vec::new();
+        let mut replaced = HashSet::new();
+        let camera = RenderSystem::camera_position();
+        for (name, entity_ids) in PrefabLibrary::instances() {
+            let distance = PrefabLibrary::get(&name).and_then(|prefab| prefab.impostor_distance);
+            let (Some(camera), Some(distance)) = (camera, distance) else {
+                continue;
+            };
+            let Some((min, max)) = RenderSystem::combined_bounds(&entity_ids) else {
+                continue;
+            };
+            let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) * 0.5);
+            let to_camera = (0..3).map(|axis| (center[axis] - camera[axis]).powi(2)).sum::<f32>().sqrt();
+            let fade = ((to_camera / distance.max(f32::EPSILON) - FADE_START) / (1.0 - FADE_START)).clamp(0.0, 1.0);
+            if fade <= 0.0 {
+                continue;
+            }
+            let Some(texture) = Self::impostor(gl, &name, &entity_ids, (min, max)) else {
+                continue;
+            };
+            if fade >= 1.0 {
+                replaced.extend(entity_ids);
+            }
+            billboards.push(Billboard { center, half_size: Self::half_size((min, max)), fade, texture });
+        }
+        BILLBOARDS.with(|current| *current.borrow_mut() = billboards);
+        REPLACED.with(|current| *current.borrow_mut() = replaced);
+    }
+
+    /// Run the player camera's mesh pass, leaving out the entities impostors replace
+    pub fn camera_pass(pass: impl FnOnce()) {
+        CAMERA_PASS.with(|current| current.set(true));
+        pass();
+        CAMERA_PASS.with(|current| current.set(false));
+    }
+
+    /// Whether the player camera's pass draws an entity's impostor instead of its mesh; false in other passes
+    pub fn replaces(entity_id: &EntityId) -> bool {
+        CAMERA_PASS.with(Cell::get) && REPLACED.with(|replaced| replaced.borrow().contains(entity_id))
+    }
+
+    /// Render pass drawing this frame's impostors over the opaque meshes
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let billboards = BILLBOARDS.with(|billboards| billboards.take());
+        let view_proj = RenderSystem::view_projection(width, height);
+        let (Some(view_proj), Some(camera)) = (view_proj, RenderSystem::camera_position()) else {
+            return;
+        };
+        let lighting = RenderSystem::current_lighting();
+        for billboard in &billboards {
+            DrawCall::new(get_impostor_shader(), Primitive::Triangles, 6)
+                .blend(Blend::Alpha)
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                .uniform("center", Uniform::Vec3(&billboard.center))
+                .uniform("half_size", Uniform::Vec2(&billboard.half_size))
+                .uniform("camera_position", Uniform::Vec3(&camera))
+                .uniform("impostor", Uniform::Int(0))
+                .uniform("fade", Uniform::Float(billboard.fade))
+                .uniform("sky_color", Uniform::Vec3(&lighting.sky_color))
+                .uniform("fog_density", Uniform::Float(lighting.fog_density))
+                .texture(0, billboard.texture)
+                .submit(gl);
+        }
+    }
+
+    fn half_size((min, max): ([f32; 3], [f32; 3])) -> [f32; 2] {
+        let radius = ((max[0] - min[0]).powi(2) + (max[2] - min[2]).powi(2)).sqrt() * 0.5;
+        [radius.max(0.01), ((max[1] - min[1]) * 0.5).max(0.01)]
+    }
+
+    /// The prefab's impostor, baked from this instance if it has none yet; a failed bake is reported once
+    fn impostor(
+        gl: &glow::Context,
+        name: &str,
+        entity_ids: &[EntityId],
+        bounds: ([f32; 3], [f32; 3])
+    ) -> Option<Texture> {
+        if let Some(baked) = BAKED.with(|baked| baked.borrow().get(name).copied()) {
+            return baked;
+        }
+        let baked = match unsafe { Self::bake(gl, entity_ids, bounds) } {
+            Ok(texture) => Some(texture),
+            Err(e) => {
+                InterfaceSystem::report_error(&EngineError::gpu(format!("Impostor of {}: {}", name, e)));
+                None
+            }
+        };
+        BAKED.with(|current| current.borrow_mut().insert(name.to_string(), baked));
+        baked
+    }
+
+    /// Draw an instance's static meshes from the side (looking along -z) into a transparent texture, orthographic
+    /// and framed as the billboard is, without fog
+    unsafe fn bake(
+        gl: &glow::Context,
+        entity_ids: &[EntityId],
+        bounds: ([f32; 3], [f32; 3])
+    ) -> Result<Texture, String> {
+        let (min, max) = bounds;
+        let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) * 0.5);
+        let [radius, half_height] = Self::half_size(bounds);
+        let eye = [center[0], center[1], center[2] + radius + 1.0];
+        let view_proj = mat4x4_mul(
+            mat4x4_orthographic(radius, half_height, 0.0, radius * 2.0 + 2.0),
+            mat4x4_look_at(eye, [0.0, 0.0, -1.0], [0.0, 1.0, 0.0])
+        );
+        let lighting = Lighting { fog_density: 0.0, ..RenderSystem::current_lighting() };
+
+        let size = IMPOSTOR_RESOLUTION;
+        let clear = vec![0u8; (size * size * 4) as usize];
+        let texture = Texture::rgba8(gl, size, size, &clear, TextureFilter::Linear, TextureWrap::ClampToEdge)
+            .map_err(|e| e.to_string())?;
+        let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        let mut previous_viewport = [0i32; 4];
+        gl.get_parameter_i32_slice(glow::VIEWPORT, &mut previous_viewport);
+
+        let framebuffer = gl.create_framebuffer()?;
+        let depth = gl.create_renderbuffer()?;
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+        gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH_COMPONENT24, size as i32, size as i32);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(texture.raw()), 0);
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, glow::RENDERBUFFER, Some(depth));
+
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        if status == glow::FRAMEBUFFER_COMPLETE {
+            gl.viewport(0, 0, size as i32, size as i32);
+            gl.clear_color(0.0, 0.0, 0.0, 0.0);
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+            LightClusterSystem::build(gl, &view_proj, size, size);
+            for entity_id in entity_ids {
+                let Some(mut transform) = get_query_by_id!(entity_id.clone(), (Transform)) else {
+                    continue;
+                };
+                let Some(static_object) = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent)) else {
+                    continue;
+                };
+                if RenderSystem::is_hidden(entity_id) || static_object.material.transparent {
+                    continue;
+                }
+                let world_txfm = *transform.get_matrix();
+                let draw = RenderSystem::mesh_draw(&static_object.mesh, &static_object.material, &view_proj, &lighting)
+                    .uniform("world_txfm", Uniform::Mat4(&world_txfm));
+                AmbientOcclusionSystem::bind(draw, entity_id, static_object.mesh.vertex_count).submit(gl);
+            }
+        }
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+        gl.viewport(previous_viewport[0], previous_viewport[1], previous_viewport[2], previous_viewport[3]);
+        gl.bind_vertex_array(None);
+        gl.delete_framebuffer(framebuffer);
+        gl.delete_renderbuffer(depth);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            texture.delete(gl);
+            return Err(format!("framebuffer incomplete (status 0x{:x})", status));
+        }
+        texture.generate_mipmaps(gl);
+        Ok(texture)
+    }
+
+    /// Console: `impostor <prefab> <distance|off>` opts a user prefab in to impostors past the distance, or out,
+    /// and saves it; the impostor is baked again the next time it's needed
+    pub fn impostor_command(args: &[&str]) -> Result<String, String> {
+        let (Some(name), Some(value)) = (args.first(), args.get(1)) else {
+            return Err("Usage: impostor <prefab> <distance|off>".to_string());
+        };
+        let distance = match *value {
+            "off" => None,
+            value => Some(
+                value
+                    .parse::<f32>()
+                    .ok()
+                    .filter(|distance| *distance > 0.0)
+                    .ok_or_else(|| format!("Invalid distance: {}", value))?
+            ),
+        };
+        let path = PrefabLibrary::set_impostor_distance(name, distance).map_err(|e| e.to_string())?;
+        if let Some(Some(texture)) = BAKED.with(|baked| baked.borrow_mut().remove(*name)) {
+            RETIRED.with(|retired| retired.borrow_mut().push(texture));
+        }
+        Ok(match distance {
+            Some(distance) =>
+                format!("Instances of {} draw as impostors past {} units (saved {})", name, distance, path),
+            None => format!("Instances of {} always draw their meshes (saved {})", name, path),
+        })
+    }
+}
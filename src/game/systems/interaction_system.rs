@@ -0,0 +1,65 @@
+use std::sync::RwLock;
+
+use crate::index::engine::components::{ ColliderLayer, Interactable, InteractEvent, SystemTrait };
+use crate::index::engine::modules::event_system::{ Event, EventSystem, EventType };
+use crate::index::engine::modules::{ is_enabled, EntityId, HudBridge, InterfaceSystem };
+use crate::index::game::systems::physics_system::PhysicsSystem;
+use crate::index::game::systems::RenderSystem;
+use crate::index::PLAYER_ENTITY_ID;
+use crate::get_query_by_id;
+
+/// Longest reach checked by the aim ray; each Interactable narrows it with its own range
+const MAX_INTERACTION_RANGE: f32 = 10.0;
+
+/// Interactable the player is currently aiming at
+static TARGET: RwLock<Option<EntityId>> = RwLock::new(None);
+
+/// Aims from the player camera in Play mode, shows the use prompt and sends Interacted on the use key
+#[derive(Debug)]
+pub struct InteractionSystem;
+
+impl InteractionSystem {
+    pub fn target() -> Option<EntityId> {
+        TARGET.read().unwrap().clone()
+    }
+
+    /// Refresh the aimed-at interactable and the HUD prompt
+    pub fn update() {
+        let target = if InterfaceSystem::is_play_mode() { Self::find_target() } else { None };
+
+        let prompt = target
+            .as_ref()
+            .and_then(|(_, interactable)| (!interactable.prompt.is_empty()).then(|| format!("[E] {}", interactable.prompt)));
+        HudBridge::set_prompt(prompt.as_deref());
+        *TARGET.write().unwrap() = target.map(|(entity_id, _)| entity_id);
+    }
+
+    /// First collider under the crosshair, if it is an enabled Interactable within its range
+    fn find_target() -> Option<(EntityId, Interactable)> {
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone()?;
+        // The crosshair sits in the middle of the viewport, so any viewport size gives the same ray
+        let (origin, direction) = RenderSystem::camera_ray([0.5, 0.5], 1, 1)?;
+        let hit = PhysicsSystem::raycast(
+            origin,
+            direction,
+            MAX_INTERACTION_RANGE,
+            &[ColliderLayer::Environment, ColliderLayer::Player],
+            &[&player_id]
+        )?;
+        let interactable = get_query_by_id!(hit.entity_id, (Interactable))?;
+        (hit.distance <= interactable.range && is_enabled(&hit.entity_id)).then_some((hit.entity_id, interactable))
+    }
+}
+
+impl SystemTrait for InteractionSystem {
+    fn event(&self, _event: &Event) {
+        let (Some(target), Some(user)) = (Self::target(), PLAYER_ENTITY_ID.read().unwrap().clone()) else {
+            return;
+        };
+        println!("🖐️ {} used {}", user, target);
+        EventSystem::send(Event {
+            event_type: EventType::Interacted,
+            payload: Box::new(InteractEvent { target, user }),
+        });
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::f32::consts::FRAC_PI_2;
+use std::sync::RwLock;
+
+use crate::index::engine::components::{ Metadata, SystemTrait, Transform };
+use crate::index::engine::modules::event_system::{ Event, EventType };
+use crate::index::engine::modules::keyboard_input_system::PointerEvent;
+use crate::index::engine::modules::{ Console, EntityId, InterfaceSystem, Random };
+use crate::index::engine::managers::PrefabLibrary;
+use crate::index::game::entities::prefabs::{ prefab_names, spawn_prefab };
+use crate::index::game::systems::RenderSystem;
+use crate::{ get_query_by_id, query_by_id, query_get_all };
+
+/// Default width of a kit grid cell; the blockout platform is this wide
+pub const KIT_GRID_SIZE: f32 = 6.0;
+/// Default height of the plane kit pieces are painted on
+pub const KIT_HEIGHT: f32 = 0.0;
+/// Longest row one stroke fills, so a drag towards the horizon doesn't spawn thousands of pieces
+const MAX_ROW_CELLS: i32 = 64;
+/// Metadata tag prefix of painted pieces, followed by the prefab name; erasing only removes tagged pieces
+const KIT_TAG: &str = "kit:";
+
+/// What a stroke does to the cells it covers
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KitMode {
+    Paint,
+    Erase,
+}
+
+/// How painted pieces are turned about the vertical axis
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum KitRotation {
+    AlongRow, // Quarter turn on rows along Z, so wall-like pieces follow the stroke
+    Random,   // Random quarter turns, breaking up repeated pieces
+}
+
+/// Cells covered by the current drag: a row from the press cell along the axis the cursor moved furthest on
+struct KitStroke {
+    anchor: [i32; 2],
+    placed: Vec<EntityId>, // Pieces painted by this stroke, turned again when the row changes axis
+    changed: bool,
+}
+
+struct KitBrush {
+    mode: Option<KitMode>,
+    prefab: usize, // Index into prefab_names()
+    rotation: KitRotation,
+    stroke: Option<KitStroke>,
+}
+
+static KIT_BRUSH: RwLock<KitBrush> = RwLock::new(KitBrush {
+    mode: None,
+    prefab: 2,
+    rotation: KitRotation::AlongRow,
+    stroke: None,
+});
+
+/// Blockout kit placement: paints the chosen prefab onto a world grid by dragging in the viewport, filling a row
+/// of cells per stroke, or erases painted pieces the same way. Cell size and plane height are the `kit_grid_size`
+/// and `kit_height` cvars.
+#[derive(Debug)]
+pub struct KitPlacementSystem;
+
+impl KitPlacementSystem {
+    /// Cycle Off -> Paint -> Erase and return the new mode name
+    pub fn cycle_mode() -> &'static str {
+        let mut brush = KIT_BRUSH.write().unwrap();
+        brush.mode = match brush.mode {
+            None => Some(KitMode::Paint),
+            Some(KitMode::Paint) => Some(KitMode::Erase),
+            Some(KitMode::Erase) => None,
+        };
+        brush.stroke = None;
+        match brush.mode {
+            None => "Off",
+            Some(KitMode::Paint) => "Paint",
+            Some(KitMode::Erase) => "Erase",
+        }
+    }
+
+    /// Select the next prefab to paint and return its name
+    pub fn cycle_prefab() -> String {
+        let names = prefab_names();
+        let mut brush = KIT_BRUSH.write().unwrap();
+        brush.prefab = (brush.prefab + 1) % names.len();
+        names[brush.prefab].clone()
+    }
+
+    /// Toggle between turning pieces along the row and random quarter turns, and return the new name
+    pub fn cycle_rotation() -> &'static str {
+        let mut brush = KIT_BRUSH.write().unwrap();
+        brush.rotation = match brush.rotation {
+            KitRotation::AlongRow => KitRotation::Random,
+            KitRotation::Random => KitRotation::AlongRow,
+        };
+        match brush.rotation {
+            KitRotation::AlongRow => "Along Row",
+            KitRotation::Random => "Random",
+        }
+    }
+
+    /// Whether kit painting or erasing owns viewport drags
+    pub fn active() -> bool {
+        KIT_BRUSH.read().unwrap().mode.is_some()
+    }
+
+    fn drag(pointer: &PointerEvent) {
+        let Some(cell) = Self::cell_under(pointer) else {
+            return;
+        };
+        let names = prefab_names();
+        let mut brush = KIT_BRUSH.write().unwrap();
+        let (Some(mode), prefab, rotation) = (brush.mode, &names[brush.prefab % names.len()], brush.rotation) else {
+            return;
+        };
+        let stroke = brush.stroke.get_or_insert(KitStroke { anchor: cell, placed: Vec::new(), changed: false });
+
+        let delta = [cell[0] - stroke.anchor[0], cell[1] - stroke.anchor[1]];
+        let along_z = delta[1].abs() > delta[0].abs();
+        let steps = if along_z { delta[1] } else { delta[0] }.clamp(-MAX_ROW_CELLS, MAX_ROW_CELLS);
+        let row: Vec<[i32; 2]> = (0..=steps.abs())
+            .map(|step| {
+                let offset = step * steps.signum();
+                if along_z {
+                    [stroke.anchor[0], stroke.anchor[1] + offset]
+                } else {
+                    [stroke.anchor[0] + offset, stroke.anchor[1]]
+                }
+            })
+            .collect();
+
+        let mut pieces = Self::pieces();
+        let row_yaw = if along_z { FRAC_PI_2 } else { 0.0 };
+        for cell in row {
+            match mode {
+                KitMode::Paint if !pieces.contains_key(&cell) => {
+                    let Some(entity_id) = Self::paint(prefab, cell) else {
+                        continue;
+                    };
+                    if rotation == KitRotation::Random {
+                        let turns = Random::entity_stream("kit", &entity_id).next_u64() % 4;
+                        Self::set_yaw(&entity_id, turns as f32 * FRAC_PI_2);
+                    }
+                    pieces.insert(cell, (entity_id.clone(), false));
+                    stroke.placed.push(entity_id);
+                    stroke.changed = true;
+                }
+                KitMode::Erase => {
+                    let Some((entity_id, locked)) = pieces.remove(&cell) else {
+                        continue;
+                    };
+                    if !locked && PrefabLibrary::delete_instance(&entity_id) {
+                        stroke.changed = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // The first cell is painted before the stroke has a direction
+        if rotation == KitRotation::AlongRow {
+            for entity_id in &stroke.placed {
+                Self::set_yaw(entity_id, row_yaw);
+            }
+        }
+    }
+
+    /// End the stroke and show its pieces in the outliner
+    fn release() {
+        let stroke = KIT_BRUSH.write().unwrap().stroke.take();
+        if stroke.is_some_and(|stroke| stroke.changed) {
+            InterfaceSystem::update_entities_list();
+        }
+    }
+
+    /// Grid cell where the cursor ray meets the kit plane
+    fn cell_under(pointer: &PointerEvent) -> Option<[i32; 2]> {
+        let (origin, direction) = RenderSystem::camera_ray(pointer.position, pointer.viewport[0], pointer.viewport[1])?;
+        let height = Console::cvar("kit_height").unwrap_or(KIT_HEIGHT);
+        if direction[1].abs() <= f32::EPSILON {
+            return None;
+        }
+        let distance = (height - origin[1]) / direction[1];
+        if distance <= 0.0 {
+            return None;
+        }
+        let size = Self::grid_size();
+        let hit = [origin[0] + direction[0] * distance, origin[2] + direction[2] * distance];
+        Some(hit.map(|coordinate| (coordinate / size).floor() as i32))
+    }
+
+    /// Painted pieces on the kit plane by cell, with whether each is locked
+    fn pieces() -> HashMap<[i32; 2], (EntityId, bool)> {
+        let size = Self::grid_size();
+        let height = Console::cvar("kit_height").unwrap_or(KIT_HEIGHT);
+        query_get_all!(Metadata, Transform)
+            .into_iter()
+            .filter(|(_, metadata, _)| metadata.tags.iter().any(|tag| tag.starts_with(KIT_TAG)))
+            .filter_map(|(entity_id, metadata, transform)| {
+                let position = transform.get_position();
+                // Pieces on other floors belong to other planes
+                if (position[1] - height).abs() >= size * 0.5 {
+                    return None;
+                }
+                let cell = [(position[0] / size).floor() as i32, (position[2] / size).floor() as i32];
+                Some((cell, (entity_id, metadata.locked)))
+            })
+            .collect()
+    }
+
+    /// Spawn a prefab at the center of a cell, tagged as a kit piece
+    fn paint(prefab: &str, cell: [i32; 2]) -> Option<EntityId> {
+        let entity_id = spawn_prefab(prefab)?;
+        let size = Self::grid_size();
+        let height = Console::cvar("kit_height").unwrap_or(KIT_HEIGHT);
+        let position = [(cell[0] as f32 + 0.5) * size, height, (cell[1] as f32 + 0.5) * size];
+        PrefabLibrary::place(&entity_id, position, 0.0, 1.0);
+        query_by_id!(entity_id.clone(), (Metadata), |metadata| {
+            metadata.tags.push(format!("{}{}", KIT_TAG, prefab));
+        });
+        Some(entity_id)
+    }
+
+    /// Turn a piece, with the rest of its prefab instance, to face `yaw`
+    fn set_yaw(entity_id: &EntityId, yaw: f32) {
+        let Some(transform) = get_query_by_id!(entity_id.clone(), (Transform)) else {
+            return;
+        };
+        let turn = yaw - transform.get_rotation()[1];
+        if turn != 0.0 {
+            PrefabLibrary::place(entity_id, transform.get_position(), turn, 1.0);
+        }
+    }
+
+    fn grid_size() -> f32 {
+        Console::cvar("kit_grid_size").unwrap_or(KIT_GRID_SIZE).max(0.1)
+    }
+}
+
+impl SystemTrait for KitPlacementSystem {
+    /// While painting or erasing, drags fill rows of grid cells and aren't passed on to moving or box-selecting
+    /// entities
+    fn event(&self, event: &Event) {
+        if event.event_type == EventType::PointerRelease {
+            Self::release();
+            return;
+        }
+        if !Self::active() || InterfaceSystem::is_play_mode() {
+            return;
+        }
+        event.consume();
+        if let Some(pointer) = event.payload.downcast_ref::<PointerEvent>() {
+            Self::drag(pointer);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use std::cell::{ Cell, RefCell };
+
+use crate::index::engine::components::PointLight;
+use crate::index::engine::components::SharedComponents::Transform;
+use crate::index::engine::modules::{ EngineResult, InterfaceSystem };
+use crate::index::engine::rhi::{ DrawCall, Texture, TextureFormat, Uniform };
+use crate::index::game::systems::RenderSystem;
+use crate::query_get_all;
+
+/// Screen tiles across and down, and depth slices; the same constants are in point_lights.glsl
+const CLUSTERS_X: usize = 16;
+const CLUSTERS_Y: usize = 9;
+const CLUSTERS_Z: usize = 24;
+/// Lights per row of the index texture, LIGHT_INDEX_ROW in point_lights.glsl
+const LIGHT_INDEX_ROW: usize = 2048;
+/// View depths of the first and last slice boundary; nearer and farther fragments use the end slices
+const CLUSTER_NEAR: f32 = 1.0;
+const CLUSTER_FAR: f32 = 100.0;
+/// Two texels per light in a single row, within WebGL2's minimum texture size of 2048
+pub const MAX_POINT_LIGHTS: usize = 1024;
+/// Sampler units of the cluster textures, after the base color (0) and the water's scene copy (1)
+const LIGHTS_UNIT: u32 = 2;
+const CLUSTERS_UNIT: u32 = 3;
+const INDICES_UNIT: u32 = 4;
+
+/// Textures point_lights.glsl reads, rewritten for every camera
+#[derive(Clone, Copy)]
+struct ClusterTextures {
+    lights: Texture,
+    clusters: Texture,
+    indices: Texture,
+}
+
+impl ClusterTextures {
+    fn new(gl: &glow::Context) -> EngineResult<Self> {
+        Ok(Self {
+            lights: Texture::data(gl, 1, 1, TextureFormat::Rgba32Float, &[0; 16])?,
+            clusters: Texture::data(gl, 1, 1, TextureFormat::Rg32Uint, &[0; 8])?,
+            indices: Texture::data(gl, 1, 1, TextureFormat::R32Uint, &[0; 4])?,
+        })
+    }
+}
+
+/// Light assignment of the camera being drawn
+#[derive(Clone, Copy)]
+struct Clusters {
+    textures: Option<ClusterTextures>,
+    light_count: usize,
+    tile_size: [f32; 2],
+}
+
+thread_local! {
+    static CLUSTERS: Cell<Clusters> = const {
+        Cell::new(Clusters { textures: None, light_count: 0, tile_size: [1.0, 1.0] })
+    };
+    // Set once the textures couldn't be created; point lights stay off on this GL context
+    static UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+    static SCRATCH: RefCell<Vec<Vec<u32>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Clustered forward lighting: sorts point lights into a grid of screen tiles and depth slices on the CPU so each
+/// fragment only loops over the few lights that can reach its cluster, keeping hundreds of small lights affordable
+pub struct LightClusterSystem;
+
+impl LightClusterSystem {
+    /// Assign lights for the player camera, before the passes that draw lit surfaces with it
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        if let Some(view_proj) = RenderSystem::view_projection(width, height) {
+            Self::build(gl, &view_proj, width, height);
+        }
+    }
+
+    /// Assign lights for a camera drawing into a `width` by `height` viewport; draws through `bind` use the result
+    /// until the next call
+    pub fn build(gl: &glow::Context, view_proj: &[f32; 16], width: u32, height: u32) {
+        let lights: Vec<([f32; 3], PointLight)> = query_get_all!(Transform, PointLight)
+            .into_iter()
+            .filter(|(entity_id, _, light)| light.enabled && light.range > 0.0 && !RenderSystem::is_hidden(entity_id))
+            .map(|(_, transform, light)| (transform.get_position(), light))
+            .take(MAX_POINT_LIGHTS)
+            .collect();
+
+        let mut clusters = CLUSTERS.with(Cell::get);
+        clusters.light_count = 0;
+        clusters.tile_size = [width.max(1) as f32 / CLUSTERS_X as f32, height.max(1) as f32 / CLUSTERS_Y as f32];
+        if !lights.is_empty() && clusters.textures.is_none() && !UNAVAILABLE.with(Cell::get) {
+            match ClusterTextures::new(gl) {
+                Ok(textures) => clusters.textures = Some(textures),
+                Err(e) => {
+                    UNAVAILABLE.with(|unavailable| unavailable.set(true));
+                    InterfaceSystem::report_error(&e);
+                    eprintln!("⚠️ Light cluster textures unavailable, point lights disabled");
+                }
+            }
+        }
+        if let (Some(textures), false) = (clusters.textures.as_mut(), lights.is_empty()) {
+            Self::upload(gl, textures, view_proj, &lights);
+            clusters.light_count = lights.len();
+        }
+        CLUSTERS.with(|current| current.set(clusters));
+    }
+
+    fn upload(gl: &glow::Context, textures: &mut ClusterTextures, view_proj: &[f32; 16], lights: &[([f32; 3], PointLight)]) {
+        SCRATCH.with(|scratch| {
+            let mut lists = scratch.borrow_mut();
+            lists.resize(CLUSTERS_X * CLUSTERS_Y * CLUSTERS_Z, Vec::new());
+            lists.iter_mut().for_each(Vec::clear);
+
+            let mut light_data = Vec::with_capacity(lights.len() * 8);
+            for (index, (position, light)) in lights.iter().enumerate() {
+                let [r, g, b] = light.radiance();
+                light_data.extend_from_slice(&[position[0], position[1], position[2], light.range, r, g, b, 0.0]);
+
+                let Some([x0, x1, y0, y1, z0, z1]) = Self::cluster_range(view_proj, *position, light.range) else {
+                    continue;
+                };
+                for z in z0..=z1 {
+                    for y in y0..=y1 {
+                        for x in x0..=x1 {
+                            lists[x + y * CLUSTERS_X + z * CLUSTERS_X * CLUSTERS_Y].push(index as u32);
+                        }
+                    }
+                }
+            }
+
+            // Each cluster's lights are a run of the index list: (first index, count) per cluster
+            let mut cluster_data = Vec::with_capacity(lists.len() * 2);
+            let mut indices = Vec::new();
+            for list in lists.iter() {
+                cluster_data.extend_from_slice(&[indices.len() as u32, list.len() as u32]);
+                indices.extend_from_slice(list);
+            }
+            let index_rows = indices.len().div_ceil(LIGHT_INDEX_ROW).max(1);
+            indices.resize(index_rows * LIGHT_INDEX_ROW, 0);
+
+            let light_texels = (lights.len() * 2) as u32;
+            textures.lights.write(gl, light_texels, 1, TextureFormat::Rgba32Float, Some(bytemuck::cast_slice(&light_data)));
+            textures.clusters.write(
+                gl,
+                (CLUSTERS_X * CLUSTERS_Y) as u32,
+                CLUSTERS_Z as u32,
+                TextureFormat::Rg32Uint,
+                Some(bytemuck::cast_slice(&cluster_data))
+            );
+            textures.indices.write(
+                gl,
+                LIGHT_INDEX_ROW as u32,
+                index_rows as u32,
+                TextureFormat::R32Uint,
+                Some(bytemuck::cast_slice(&indices))
+            );
+        });
+    }
+
+    /// Clusters the box around a light's range overlaps, as inclusive [x0, x1, y0, y1, z0, z1]; None off screen
+    fn cluster_range(view_proj: &[f32; 16], center: [f32; 3], range: f32) -> Option<[usize; 6]> {
+        let mut ndc_min = [f32::MAX; 2];
+        let mut ndc_max = [f32::MIN; 2];
+        let mut depth_min = f32::MAX;
+        let mut depth_max = f32::MIN;
+        let mut spans_camera = false;
+        for corner in 0..8 {
+            let offset = |bit: usize| if corner & bit == 0 { -range } else { range };
+            let point = [center[0] + offset(1), center[1] + offset(2), center[2] + offset(4)];
+            let clip = |row: usize| {
+                view_proj[row * 4] * point[0] + view_proj[row * 4 + 1] * point[1] + view_proj[row * 4 + 2] * point[2] +
+                    view_proj[row * 4 + 3]
+            };
+            // Clip-space w is the view depth (1 everywhere for orthographic cameras, which get a single slice)
+            let w = clip(3);
+            depth_min = depth_min.min(w);
+            depth_max = depth_max.max(w);
+            if w <= 0.0001 {
+                spans_camera = true;
+                continue;
+            }
+            for axis in 0..2 {
+                let ndc = clip(axis) / w;
+                ndc_min[axis] = ndc_min[axis].min(ndc);
+                ndc_max[axis] = ndc_max[axis].max(ndc);
+            }
+        }
+        if depth_max <= 0.0 {
+            return None;
+        }
+
+        // Corners behind the camera don't project, so a light around it may touch any tile
+        let (x_span, y_span) = if spans_camera {
+            ((0, CLUSTERS_X - 1), (0, CLUSTERS_Y - 1))
+        } else {
+            if ndc_min[0] > 1.0 || ndc_max[0] < -1.0 || ndc_min[1] > 1.0 || ndc_max[1] < -1.0 {
+                return None;
+            }
+            (Self::tile_span(ndc_min[0], ndc_max[0], CLUSTERS_X), Self::tile_span(ndc_min[1], ndc_max[1], CLUSTERS_Y))
+        };
+        let (z0, z1) = (Self::depth_slice(depth_min), Self::depth_slice(depth_max));
+        Some([x_span.0, x_span.1, y_span.0, y_span.1, z0, z1])
+    }
+
+    fn tile_span(ndc_min: f32, ndc_max: f32, tiles: usize) -> (usize, usize) {
+        let tile = |ndc: f32| (((ndc * 0.5 + 0.5) * tiles as f32).floor().max(0.0) as usize).min(tiles - 1);
+        (tile(ndc_min), tile(ndc_max))
+    }
+
+    /// Slice of a view depth, split like `clusterSlice` in point_lights.glsl
+    fn depth_slice(depth: f32) -> usize {
+        if depth <= CLUSTER_NEAR {
+            return 0;
+        }
+        let slice = (depth / CLUSTER_NEAR).ln() / (CLUSTER_FAR / CLUSTER_NEAR).ln() * CLUSTERS_Z as f32;
+        (slice as usize).min(CLUSTERS_Z - 1)
+    }
+
+    /// Add the current light clusters to a draw whose fragment shader includes point_lights.glsl
+    pub fn bind(draw: DrawCall<'_>) -> DrawCall<'_> {
+        let clusters = CLUSTERS.with(Cell::get);
+        // Samplers always get their own units: integer and float samplers must not share one
+        let draw = draw
+            .uniform("point_lights", Uniform::Int(LIGHTS_UNIT as i32))
+            .uniform("light_clusters", Uniform::Int(CLUSTERS_UNIT as i32))
+            .uniform("light_indices", Uniform::Int(INDICES_UNIT as i32))
+            .uniform("point_light_count", Uniform::Int(clusters.light_count as i32))
+            .uniform("cluster_tile_width", Uniform::Float(clusters.tile_size[0]))
+            .uniform("cluster_tile_height", Uniform::Float(clusters.tile_size[1]))
+            .uniform("cluster_near", Uniform::Float(CLUSTER_NEAR))
+            .uniform("cluster_far", Uniform::Float(CLUSTER_FAR));
+        match clusters.textures {
+            Some(textures) => draw
+                .texture(LIGHTS_UNIT, textures.lights)
+                .texture(CLUSTERS_UNIT, textures.clusters)
+                .texture(INDICES_UNIT, textures.indices),
+            None => draw,
+        }
+    }
+}
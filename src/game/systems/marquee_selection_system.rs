@@ -0,0 +1,213 @@
+use std::sync::RwLock;
+
+use crate::index::engine::components::{ Metadata, SystemTrait };
+use crate::index::engine::managers::assets_manager::get_wireframe_shader;
+use crate::index::engine::modules::event_system::{ Event, EventType };
+use crate::index::engine::modules::keyboard_input_system::PointerEvent;
+use crate::index::engine::modules::{ EntityId, InterfaceSystem };
+use crate::index::engine::rhi::{
+    Buffer,
+    BufferUsage,
+    DepthCompare,
+    DrawCall,
+    Geometry,
+    Primitive,
+    Uniform,
+    VertexAttribute,
+    VertexFormat,
+};
+use crate::index::engine::utils::mat4x4_identity;
+use crate::index::game::systems::{ GizmoSystem, RenderSystem, TerrainSystem, TransformDragSystem };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::query_get_all;
+
+/// Cursor travel in pixels below which a press counts as a click, not a selection
+const MIN_MARQUEE_SIZE: f32 = 4.0;
+/// Cursor travel in pixels between recorded lasso points
+const LASSO_POINT_SPACING: f32 = 3.0;
+
+/// Selection shape being dragged, in window pixels
+struct Marquee {
+    points: Vec<[f32; 2]>, // Box: press and current cursor; lasso: the cursor path
+    lasso: bool,
+    additive: bool,
+    viewport: [u32; 2],
+}
+
+enum MarqueeState {
+    Idle,
+    Ignored, // The press grabbed the selection or a brush owns it; wait for the release
+    Selecting(Marquee),
+}
+
+static MARQUEE: RwLock<MarqueeState> = RwLock::new(MarqueeState::Idle);
+
+/// Selects entities by dragging a box (or an Alt lasso) in the Edit viewport, or by clicking a light or camera icon;
+/// Shift adds to the selection
+#[derive(Debug)]
+pub struct MarqueeSelectionSystem;
+
+impl MarqueeSelectionSystem {
+    fn drag(pointer: &PointerEvent) {
+        let mut state = MARQUEE.write().unwrap();
+        match &mut *state {
+            MarqueeState::Idle => {
+                let owned_elsewhere = InterfaceSystem::is_play_mode() || TerrainSystem::brush_active() ||
+                    TransformDragSystem::is_dragging();
+                *state = if owned_elsewhere {
+                    MarqueeState::Ignored
+                } else {
+                    MarqueeState::Selecting(Marquee {
+                        points: vec![pointer.position],
+                        lasso: pointer.lasso,
+                        additive: pointer.additive,
+                        viewport: pointer.viewport,
+                    })
+                };
+            }
+            MarqueeState::Ignored => {}
+            MarqueeState::Selecting(marquee) => {
+                marquee.additive = pointer.additive;
+                marquee.viewport = pointer.viewport;
+                let last = marquee.points[marquee.points.len() - 1];
+                let travel = (pointer.position[0] - last[0]).hypot(pointer.position[1] - last[1]);
+                if !marquee.lasso {
+                    marquee.points.truncate(1);
+                    marquee.points.push(pointer.position);
+                } else if travel >= LASSO_POINT_SPACING {
+                    marquee.points.push(pointer.position);
+                }
+            }
+        }
+    }
+
+    fn release(pointer: &PointerEvent) {
+        let state = std::mem::replace(&mut *MARQUEE.write().unwrap(), MarqueeState::Idle);
+        let MarqueeState::Selecting(mut marquee) = state else {
+            return;
+        };
+        marquee.additive = pointer.additive;
+
+        let [width, height] = marquee.viewport;
+        let (min, max) = screen_extent(&marquee.points);
+        if max[0] - min[0] < MIN_MARQUEE_SIZE && max[1] - min[1] < MIN_MARQUEE_SIZE {
+            // Clicks pick light and camera icons, which have no mesh to click on
+            if let Some(entity_id) = GizmoSystem::pick(pointer.position, width, height) {
+                InterfaceSystem::select_entities(vec![entity_id], marquee.additive);
+            }
+            return;
+        }
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone();
+        let mut picked: Vec<EntityId> = Vec::new();
+        for (entity_id, metadata) in query_get_all!(Metadata) {
+            if !metadata.is_visible || metadata.locked || player_id.as_ref() == Some(&entity_id) {
+                continue;
+            }
+            let Some((bounds_min, bounds_max)) = RenderSystem::entity_bounds(&entity_id) else {
+                continue;
+            };
+
+            let inside = if marquee.lasso {
+                // Lasso paths are rough, so the bounds center decides
+                let center = [0, 1, 2].map(|axis| (bounds_min[axis] + bounds_max[axis]) * 0.5);
+                RenderSystem::world_to_screen(&view_proj, center, width, height).is_some_and(|point| {
+                    polygon_contains(&marquee.points, point)
+                })
+            } else {
+                // Boxes take entities whose whole projected bounds fit inside
+                (0..8).all(|i| {
+                    let corner = [
+                        if i & 1 == 0 { bounds_min[0] } else { bounds_max[0] },
+                        if i & 2 == 0 { bounds_min[1] } else { bounds_max[1] },
+                        if i & 4 == 0 { bounds_min[2] } else { bounds_max[2] },
+                    ];
+                    RenderSystem::world_to_screen(&view_proj, corner, width, height).is_some_and(|point| {
+                        point[0] >= min[0] && point[0] <= max[0] && point[1] >= min[1] && point[1] <= max[1]
+                    })
+                })
+            };
+            if inside {
+                picked.push(entity_id);
+            }
+        }
+
+        InterfaceSystem::select_entities(picked, marquee.additive);
+    }
+
+    /// Draw the box or lasso outline over the scene
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let state = MARQUEE.read().unwrap();
+        let MarqueeState::Selecting(marquee) = &*state else {
+            return;
+        };
+        let outline: Vec<[f32; 2]> = if marquee.lasso {
+            marquee.points.clone()
+        } else {
+            let (min, max) = screen_extent(&marquee.points);
+            vec![min, [max[0], min[1]], max, [min[0], max[1]]]
+        };
+        if outline.len() < 2 {
+            return;
+        }
+
+        // Window pixels to clip space, drawn with identity transforms
+        let vertices: Vec<f32> = outline
+            .iter()
+            .flat_map(|point| [(point[0] / width as f32) * 2.0 - 1.0, 1.0 - (point[1] / height as f32) * 2.0, 0.0])
+            .collect();
+        let identity = mat4x4_identity();
+        let Ok(buffer) = Buffer::vertex(gl, &vertices, BufferUsage::Stream) else {
+            return;
+        };
+        let Ok(geometry) = Geometry::new(gl, &[VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)], None) else {
+            buffer.delete(gl);
+            return;
+        };
+        DrawCall::new(get_wireframe_shader(), Primitive::LineLoop, outline.len())
+            .geometry(geometry)
+            .depth(DepthCompare::Always)
+            .uniform("world_txfm", Uniform::Mat4(&identity))
+            .uniform("viewport_txfm", Uniform::Mat4(&identity))
+            .uniform("line_color", Uniform::Vec3(&[1.0, 1.0, 1.0]))
+            .submit(gl);
+        geometry.delete(gl);
+        buffer.delete(gl);
+    }
+}
+
+impl SystemTrait for MarqueeSelectionSystem {
+    fn event(&self, event: &Event) {
+        let Some(pointer) = event.payload.downcast_ref::<PointerEvent>() else {
+            return;
+        };
+        match event.event_type {
+            EventType::PointerDrag => Self::drag(pointer),
+            EventType::PointerRelease => Self::release(pointer),
+            _ => {}
+        }
+    }
+}
+
+/// Pixel rectangle around a set of points
+fn screen_extent(points: &[[f32; 2]]) -> ([f32; 2], [f32; 2]) {
+    points.iter().fold(([f32::MAX, f32::MAX], [f32::MIN, f32::MIN]), |(min, max), point| {
+        ([min[0].min(point[0]), min[1].min(point[1])], [max[0].max(point[0]), max[1].max(point[1])])
+    })
+}
+
+/// Even-odd point in polygon test; the polygon closes from its last point back to the first
+fn polygon_contains(polygon: &[[f32; 2]], point: [f32; 2]) -> bool {
+    let mut inside = false;
+    for i in 0..polygon.len() {
+        let a = polygon[i];
+        let b = polygon[(i + polygon.len() - 1) % polygon.len()];
+        if (a[1] > point[1]) != (b[1] > point[1]) && point[0] < a[0] + ((point[1] - a[1]) / (b[1] - a[1])) * (b[0] - a[0]) {
+            inside = !inside;
+        }
+    }
+    inside
+}
@@ -0,0 +1,180 @@
+use std::cell::{ Cell, RefCell };
+use std::f32::consts::FRAC_PI_2;
+use std::sync::atomic::{ AtomicBool, Ordering };
+use glow::HasContext;
+
+use crate::index::engine::components::{ Faction, Lighting, Spawner, Transform };
+use crate::index::engine::modules::{ InterfaceSystem, Project };
+use crate::index::engine::utils::math::{ build_view_matrix, mat4x4_mul, mat4x4_orthographic };
+use crate::index::game::systems::{ LightClusterSystem, RenderSystem, TerrainSystem };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query_get_all };
+
+/// Minimap texture resolution (square)
+const MINIMAP_SIZE: u32 = 256;
+/// Half the world width shown on the minimap
+const MINIMAP_EXTENT: f32 = 30.0;
+/// Height of the top camera above the player
+const MINIMAP_CAMERA_HEIGHT: f32 = 100.0;
+/// Re-render the minimap every N frames; reading pixels back every frame would stall the GPU
+const MINIMAP_INTERVAL: u32 = 10;
+
+static SHOW_MINIMAP: AtomicBool = AtomicBool::new(true);
+
+thread_local! {
+    // Offscreen framebuffer the top camera renders into (its renderbuffers live as long as it does)
+    static MINIMAP_TARGET: RefCell<Option<glow::Framebuffer>> = const { RefCell::new(None) };
+    static FRAMES_SINCE_CAPTURE: Cell<u32> = const { Cell::new(MINIMAP_INTERVAL) };
+}
+
+pub struct MinimapSystem;
+
+impl MinimapSystem {
+    /// Toggle the minimap; returns whether it is now visible
+    pub fn toggle() -> bool {
+        !SHOW_MINIMAP.fetch_xor(true, Ordering::Relaxed)
+    }
+
+    /// Capture the scene from an orthographic camera above the player every few frames
+    pub fn render(gl: &glow::Context, _width: u32, _height: u32) {
+        if !SHOW_MINIMAP.load(Ordering::Relaxed) {
+            return;
+        }
+        let frames = FRAMES_SINCE_CAPTURE.with(|frames| {
+            frames.set(frames.get() + 1);
+            frames.get()
+        });
+        if frames < MINIMAP_INTERVAL {
+            return;
+        }
+        FRAMES_SINCE_CAPTURE.with(|frames| frames.set(0));
+
+        let Some(center) = RenderSystem::camera_position() else {
+            return;
+        };
+        let Some(framebuffer) = Self::target(gl) else {
+            return;
+        };
+
+        let view = build_view_matrix([center[0], center[1] + MINIMAP_CAMERA_HEIGHT, center[2]], FRAC_PI_2, 0.0);
+        let projection = mat4x4_orthographic(MINIMAP_EXTENT, MINIMAP_EXTENT, 0.1, MINIMAP_CAMERA_HEIGHT * 2.0);
+        let view_proj = mat4x4_mul(projection, view);
+        // Flat bright light so the map stays readable at night
+        let lighting = Lighting { ambient: 0.6, ..Lighting::default() };
+
+        let mut pixels = vec![0u8; (MINIMAP_SIZE * MINIMAP_SIZE * 4) as usize];
+        unsafe {
+            let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+            let mut previous_viewport = [0i32; 4];
+            gl.get_parameter_i32_slice(glow::VIEWPORT, &mut previous_viewport);
+
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            gl.viewport(0, 0, MINIMAP_SIZE as i32, MINIMAP_SIZE as i32);
+            gl.clear_color(0.08, 0.09, 0.1, 1.0);
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+
+            // Captures show what the Play camera sees, without editor-only layers
+            RenderSystem::with_layer_mask(!Project::editor_only_layers(), || {
+                LightClusterSystem::build(gl, &view_proj, MINIMAP_SIZE, MINIMAP_SIZE);
+                RenderSystem::render_meshes(gl, &view_proj, &lighting);
+                TerrainSystem::draw(gl, &view_proj, &lighting);
+            });
+
+            gl.read_pixels(
+                0,
+                0,
+                MINIMAP_SIZE as i32,
+                MINIMAP_SIZE as i32,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(Some(&mut pixels))
+            );
+
+            gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+            gl.viewport(previous_viewport[0], previous_viewport[1], previous_viewport[2], previous_viewport[3]);
+            gl.bind_vertex_array(None);
+        }
+
+        // GL rows start at the bottom, images at the top
+        let row_size = (MINIMAP_SIZE * 4) as usize;
+        let flipped: Vec<u8> = pixels.chunks_exact(row_size).rev().flatten().copied().collect();
+
+        InterfaceSystem::sync_minimap(MINIMAP_SIZE, MINIMAP_SIZE, &flipped, &Self::markers(center));
+    }
+
+    /// Player, hostile and spawner positions mapped to 0..1 minimap coordinates (north is -z)
+    fn markers(center: [f32; 3]) -> Vec<([f32; 2], &'static str)> {
+        let to_map = |position: [f32; 3]| -> Option<[f32; 2]> {
+            let x = (position[0] - center[0]) / (MINIMAP_EXTENT * 2.0) + 0.5;
+            let y = (position[2] - center[2]) / (MINIMAP_EXTENT * 2.0) + 0.5;
+            ((0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y)).then_some([x, y])
+        };
+
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone();
+        let player_faction = player_id.as_ref().and_then(|player_id| get_query_by_id!(player_id, (Faction)));
+        let mut markers = Vec::new();
+
+        for (entity_id, transform, faction) in query_get_all!(Transform, Faction) {
+            if Some(&entity_id) == player_id.as_ref() || RenderSystem::is_hidden(&entity_id) {
+                continue;
+            }
+            let hostile = player_faction.as_ref().is_some_and(|player_faction| faction.is_hostile_to(player_faction));
+            if let (true, Some(position)) = (hostile, to_map(transform.get_position())) {
+                markers.push((position, "enemy"));
+            }
+        }
+        for (entity_id, transform, _spawner) in query_get_all!(Transform, Spawner) {
+            if RenderSystem::is_hidden(&entity_id) {
+                continue;
+            }
+            if let Some(position) = to_map(transform.get_position()) {
+                markers.push((position, "spawner"));
+            }
+        }
+        // Player last so it is drawn on top
+        if let Some(position) = to_map(center) {
+            markers.push((position, "player"));
+        }
+        markers
+    }
+
+    /// Lazily create the offscreen framebuffer
+    fn target(gl: &glow::Context) -> Option<glow::Framebuffer> {
+        MINIMAP_TARGET.with(|target| {
+            let mut target = target.borrow_mut();
+            if target.is_none() {
+                *target = unsafe { Self::create_target(gl) }
+                    .map_err(|e| eprintln!("❌ Failed to create minimap framebuffer: {}", e))
+                    .ok();
+            }
+            *target
+        })
+    }
+
+    unsafe fn create_target(gl: &glow::Context) -> Result<glow::Framebuffer, String> {
+        let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        let framebuffer = gl.create_framebuffer()?;
+        let color = gl.create_renderbuffer()?;
+        let depth = gl.create_renderbuffer()?;
+
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(color));
+        gl.renderbuffer_storage(glow::RENDERBUFFER, glow::RGBA8, MINIMAP_SIZE as i32, MINIMAP_SIZE as i32);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+        gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH_COMPONENT24, MINIMAP_SIZE as i32, MINIMAP_SIZE as i32);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::RENDERBUFFER, Some(color));
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, glow::RENDERBUFFER, Some(depth));
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_renderbuffer(color);
+            gl.delete_renderbuffer(depth);
+            return Err(format!("framebuffer incomplete (status 0x{:x})", status));
+        }
+        Ok(framebuffer)
+    }
+}
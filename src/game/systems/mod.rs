@@ -1,7 +1,91 @@
 pub mod render_system;
 pub mod movement_system;
 pub mod physics_system;
+pub mod animation_system;
+pub mod navigation_system;
+pub mod ai_system;
+pub mod health_system;
+pub mod spawn_system;
+pub mod time_of_day_system;
+pub mod terrain_system;
+pub mod water_system;
+pub mod minimap_system;
+pub mod hud_system;
+pub mod ragdoll_system;
+pub mod mover_system;
+pub mod projectile_system;
+pub mod interaction_system;
+pub mod camera_bookmark_system;
+pub mod editor_camera_system;
+pub mod transform_drag_system;
+pub mod marquee_selection_system;
+pub mod scene_thumbnail_system;
+pub mod character_system;
+pub mod gpu_skinning_system;
+pub mod transparency_system;
+pub mod light_cluster_system;
+pub mod ambient_occlusion_system;
+pub mod reflection_probe_system;
+pub mod ssao_system;
+pub mod depth_prepass_system;
+pub mod color_grading_system;
+pub mod property_animation_system;
+pub mod gizmo_system;
+pub mod kit_placement_system;
+pub mod scatter_brush_system;
+pub mod heatmap_system;
+pub mod culling_debug_system;
+pub mod collab_system;
+pub mod texture_streaming_system;
+pub mod impostor_system;
+pub mod antialiasing_system;
+pub mod transform_gizmo_system;
+pub mod blockout_system;
+pub mod spline_system;
+pub mod volume_system;
 
 // Re-export commonly used types
 pub use render_system::RenderSystem;
 pub use movement_system::{ MovementSystem, CameraRotationSystem };
+pub use animation_system::AnimationSystem;
+pub use navigation_system::NavigationSystem;
+pub use ai_system::AISystem;
+pub use health_system::HealthSystem;
+pub use spawn_system::SpawnSystem;
+pub use time_of_day_system::TimeOfDaySystem;
+pub use terrain_system::TerrainSystem;
+pub use water_system::WaterSystem;
+pub use minimap_system::MinimapSystem;
+pub use hud_system::HudSystem;
+pub use ragdoll_system::RagdollSystem;
+pub use mover_system::MoverSystem;
+pub use projectile_system::ProjectileSystem;
+pub use interaction_system::InteractionSystem;
+pub use camera_bookmark_system::CameraBookmarkSystem;
+pub use editor_camera_system::EditorCameraSystem;
+pub use transform_drag_system::TransformDragSystem;
+pub use marquee_selection_system::MarqueeSelectionSystem;
+pub use scene_thumbnail_system::SceneThumbnailSystem;
+pub use character_system::CharacterSystem;
+pub use gpu_skinning_system::GpuSkinningSystem;
+pub use transparency_system::TransparencySystem;
+pub use light_cluster_system::LightClusterSystem;
+pub use ambient_occlusion_system::AmbientOcclusionSystem;
+pub use reflection_probe_system::ReflectionProbeSystem;
+pub use ssao_system::SsaoSystem;
+pub use depth_prepass_system::DepthPrepassSystem;
+pub use color_grading_system::ColorGradingSystem;
+pub use antialiasing_system::AntialiasingSystem;
+pub use property_animation_system::PropertyAnimationSystem;
+pub use gizmo_system::GizmoSystem;
+pub use kit_placement_system::KitPlacementSystem;
+pub use scatter_brush_system::ScatterBrushSystem;
+pub use heatmap_system::HeatmapSystem;
+pub use culling_debug_system::CullingDebugSystem;
+pub use collab_system::CollabSystem;
+pub use texture_streaming_system::TextureStreamingSystem;
+pub use impostor_system::ImpostorSystem;
+pub use transform_gizmo_system::TransformGizmoSystem;
+pub use blockout_system::BlockoutSystem;
+pub use spline_system::SplineSystem;
+pub use volume_system::VolumeSystem;
@@ -1,8 +1,17 @@
 // Import types and functions from parent scope
-use crate::index::engine::components::{SystemTrait, CameraComponent, Transform};
+use crate::index::engine::components::{SystemTrait, CameraComponent, CameraProjection, PlayerController, Transform};
 use crate::index::engine::modules::event_system::Event;
+use crate::index::engine::modules::keyboard_input_system::MoveEvent;
+use crate::index::engine::modules::{ Console, InterfaceSystem };
 use crate::index::PLAYER_ENTITY_ID;
 
+/// Orthographic view zoom speed while W/S are held (the view size shrinks by e^-rate per second)
+const ORTHO_ZOOM_RATE: f32 = 1.8;
+const MIN_ORTHO_SIZE: f32 = 0.5;
+/// Default for the `move_speed` cvar, in units per second
+pub const MOVE_SPEED: f32 = 5.0;
+
+/// Mouse look; rotation deltas come from mouse motion, so they don't depend on the frame or tick rate
 #[derive(Debug)]
 pub struct CameraRotationSystem;
 
@@ -34,21 +43,38 @@ impl SystemTrait for MovementSystem {
             None => return,
         };
         
-        let direction_string = match event.payload.downcast_ref::<String>() {
-            Some(s) => s.as_str(),
-            None => return,
+        let Some(MoveEvent { direction, delta_time, input }) = event.payload.downcast_ref::<MoveEvent>() else {
+            return;
         };
 
-        if direction_string.is_empty() { return; }
+        // Jump and crouch are applied by the CharacterSystem on this tick
+        let mut movement_speed = Console::cvar("move_speed").unwrap_or(MOVE_SPEED);
+        crate::query_by_id!(player_entity_id, (PlayerController), |controller| {
+            controller.input = *input;
+            movement_speed = controller.speed(controller.move_speed.unwrap_or(movement_speed));
+        });
+        if direction.is_empty() { return; }
+
+        // In Play mode the player walks, so only the CharacterSystem moves it vertically
+        let walking = InterfaceSystem::is_play_mode();
 
         // Parse direction string and apply transforms directly
         crate::query_by_id!(player_entity_id, (CameraComponent, Transform), |camera, transform| {
             let (forward_vec, right_vec, up_vec) = camera.get_basis_vectors();
             let mut total_movement = [0.0, 0.0, 0.0];
 
+            // Moving along the view axis does nothing in an orthographic editor view, so W/S zoom instead
+            let orthographic = camera.projection == CameraProjection::Orthographic && !InterfaceSystem::is_play_mode();
+
             // Process each direction token
-            for token in direction_string.split('-') {
+            for token in direction.split('-') {
                 match token {
+                    "forward" if orthographic => {
+                        camera.ortho_size = (camera.ortho_size * (-ORTHO_ZOOM_RATE * delta_time).exp()).max(MIN_ORTHO_SIZE);
+                    },
+                    "backward" if orthographic => {
+                        camera.ortho_size *= (ORTHO_ZOOM_RATE * delta_time).exp();
+                    },
                     "forward" => {
                         total_movement[0] += -forward_vec[0];
                         total_movement[1] += -forward_vec[1];
@@ -69,6 +95,7 @@ impl SystemTrait for MovementSystem {
                         total_movement[1] += right_vec[1];
                         total_movement[2] += right_vec[2];
                     },
+                    "up" | "down" if walking => {},
                     "up" => {
                         total_movement[0] += up_vec[0];
                         total_movement[1] += up_vec[1];
@@ -83,9 +110,7 @@ impl SystemTrait for MovementSystem {
                 }
             }
 
-            // Apply movement with speed and timing
-            let movement_speed = 5.0;
-            let delta_time = 1.0 / 60.0;
+            // Speed is in units per second, so scale by the tick's delta time
             let movement_distance = movement_speed * delta_time;
             
             total_movement[0] *= movement_distance;
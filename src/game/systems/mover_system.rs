@@ -0,0 +1,65 @@
+use crate::index::engine::components::{ Collider, KinematicMover, RigidBody, Spline, Transform };
+use crate::index::engine::modules::ecs::{ insert, is_enabled };
+use crate::index::engine::modules::EntityId;
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::{ get_query_by_id, query_get_all };
+
+/// How far a rider's feet may be from the platform top and still count as standing on it
+const RIDE_TOLERANCE: f32 = 0.15;
+
+pub struct MoverSystem;
+
+impl MoverSystem {
+    /// Move every KinematicMover along its waypoints or path, dragging its riders along. Runs before physics
+    /// so carried bodies are resolved against the platform's new position
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+
+        for (entity_id, mut transform, mut mover) in query_get_all!(Transform, KinematicMover) {
+            if !is_enabled(&entity_id) {
+                continue;
+            }
+            let target = match mover.path.clone() {
+                Some(path_id) => Self::along_path(&mut mover, &path_id, delta_time),
+                None => mover.advance(delta_time),
+            };
+            if let Some(target) = target {
+                let position = transform.get_position();
+                let delta = [target[0] - position[0], target[1] - position[1], target[2] - position[2]];
+                if let Some(platform) = get_query_by_id!(entity_id, (Collider)) {
+                    Self::carry_riders(&entity_id, &platform, &transform, delta);
+                }
+                transform.set_position(target[0], target[1], target[2]);
+                insert(&entity_id, transform);
+            }
+            insert(&entity_id, mover);
+        }
+    }
+
+    /// Advance a mover following a Spline entity and return its new world position; movers whose path entity
+    /// has no spline stand still
+    fn along_path(mover: &mut KinematicMover, path_id: &EntityId, delta_time: f32) -> Option<[f32; 3]> {
+        let spline = get_query_by_id!(path_id.clone(), (Spline))?;
+        let path_transform = get_query_by_id!(path_id.clone(), (Transform))?;
+        let distance = mover.advance_path(delta_time, spline.length())?;
+        spline.world_sample(&path_transform, distance).map(|sample| sample.position)
+    }
+
+    /// Move the bodies standing on the platform's top surface by the platform's own displacement
+    fn carry_riders(platform_id: &EntityId, platform: &Collider, platform_transform: &Transform, delta: [f32; 3]) {
+        for (rider_id, mut rider_transform, rider_collider) in query_get_all!(Transform, Collider) {
+            // Static geometry never rides; players and rigid bodies do
+            if rider_id == *platform_id || !is_enabled(&rider_id) || get_query_by_id!(rider_id, (RigidBody)).is_none() {
+                continue;
+            }
+            let feet = rider_collider.lowest_point(&rider_transform);
+            let Some(top) = platform.surface_height(platform_transform, feet[0], feet[2]) else {
+                continue;
+            };
+            if (feet[1] - top).abs() <= RIDE_TOLERANCE {
+                rider_transform.translate(delta[0], delta[1], delta[2]);
+                insert(&rider_id, rider_transform);
+            }
+        }
+    }
+}
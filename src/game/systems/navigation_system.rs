@@ -0,0 +1,268 @@
+use std::cell::RefCell;
+use std::sync::{ Arc, Mutex };
+use std::sync::atomic::{ AtomicBool, Ordering };
+
+use crate::index::engine::components::{
+    Collider,
+    ColliderLayer,
+    EntityCategory,
+    Metadata,
+    NavAgent,
+    NavMesh,
+    NavMeshSettings,
+    Transform,
+    LAYER_GIZMOS,
+};
+use crate::index::engine::managers::assets_manager::get_wireframe_shader;
+use crate::index::engine::modules::{
+    first_changed_at,
+    get_component,
+    is_enabled,
+    spawn,
+    EntityId,
+    InterfaceSystem,
+    TaskSystem,
+};
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::engine::rhi::{ Buffer, BufferUsage, DrawCall, Geometry, Primitive, Uniform, VertexAttribute, VertexFormat };
+use crate::index::engine::utils::mat4x4_identity;
+use crate::index::game::systems::RenderSystem;
+use crate::{ query, query_get_all, insert_many };
+
+/// Whether the baked navmesh is drawn in the viewport
+static SHOW_NAVMESH: AtomicBool = AtomicBool::new(false);
+/// Task name of a running bake
+const BAKE_TASK: &str = "bake navmesh";
+
+/// The scene navmesh as last read, with its entity and the change count it was read at; shared by path queries and
+/// the debug draw so the navmesh is only copied out of the world after it changed
+static NAVMESH: Mutex<Option<(EntityId, u64, Arc<NavMesh>)>> = Mutex::new(None);
+
+/// Navmesh debug lines kept on the GPU, rewritten only when the navmesh changes
+struct DebugLines {
+    navmesh: Arc<NavMesh>,
+    buffer: Buffer,
+    geometry: Geometry,
+    vertex_count: usize,
+}
+
+thread_local! {
+    static DEBUG_LINES: RefCell<Option<DebugLines>> = const { RefCell::new(None) };
+}
+
+pub struct NavigationSystem;
+
+impl NavigationSystem {
+    /// Bake a navmesh from the Environment colliders in the background and store it on the scene's navmesh entity
+    /// (settings of an existing navmesh are reused so they can be tweaked in the inspector)
+    pub fn bake() {
+        if TaskSystem::is_running(BAKE_TASK) {
+            println!("🧭 A navmesh bake is already running");
+            return;
+        }
+        let obstacles: Vec<(Collider, Transform)> = query_get_all!(Collider, Transform)
+            .into_iter()
+            .filter(|(entity_id, collider, _)| collider.layer == ColliderLayer::Environment && is_enabled(entity_id))
+            .map(|(_, collider, transform)| (collider, transform))
+            .collect();
+
+        let settings = query_get_all!(NavMesh)
+            .into_iter()
+            .next()
+            .map(|(_, navmesh)| navmesh.settings)
+            .unwrap_or_else(NavMeshSettings::default);
+        TaskSystem::spawn(
+            BAKE_TASK,
+            move || Ok(NavMesh::bake(settings, &obstacles)),
+            |result, _gl| {
+                if let Ok(navmesh) = result {
+                    Self::store(navmesh);
+                    InterfaceSystem::update_entities_list();
+                }
+            }
+        );
+    }
+
+    /// Put a baked navmesh on the scene's navmesh entity, creating it if the scene has none (yet)
+    fn store(navmesh: NavMesh) {
+        match query_get_all!(NavMesh).into_iter().next() {
+            Some((entity_id, _)) => crate::index::engine::modules::ecs::insert(&entity_id, navmesh),
+            None => {
+                let entity_id = spawn();
+                insert_many!(
+                    entity_id,
+                    navmesh,
+                    Metadata::new("NavMesh", Some("navmesh"), None).with_category(EntityCategory::Volume)
+                );
+            }
+        }
+    }
+
+    /// Shortest walkable route on the scene navmesh (waypoints after `from`, ending at `to`)
+    pub fn find_path(from: [f32; 3], to: [f32; 3]) -> Option<Vec<[f32; 3]>> {
+        Self::navmesh()?.find_path(from, to)
+    }
+
+    /// The scene navmesh, read again from the world only if it changed since the last call
+    fn navmesh() -> Option<Arc<NavMesh>> {
+        let Some((entity_id, changed)) = first_changed_at::<NavMesh>() else {
+            *NAVMESH.lock().unwrap() = None;
+            return None;
+        };
+        let mut cached = NAVMESH.lock().unwrap();
+        if let Some((cached_id, cached_changed, navmesh)) = cached.as_ref() {
+            if *cached_id == entity_id && *cached_changed == changed {
+                return Some(navmesh.clone());
+            }
+        }
+        let navmesh = Arc::new(get_component::<NavMesh>(&entity_id)?);
+        *cached = Some((entity_id, changed, navmesh.clone()));
+        Some(navmesh)
+    }
+
+    pub fn toggle_debug_draw() -> bool {
+        !SHOW_NAVMESH.fetch_xor(true, Ordering::Relaxed)
+    }
+
+    /// Plan paths for agents with a new destination and steer every agent one fixed tick along its path
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+
+        query!((Transform, NavAgent), |entity_id, transform, agent| {
+            if !is_enabled(&entity_id) {
+                continue;
+            }
+            Self::steer(&mut transform, &mut agent, delta_time);
+        });
+    }
+
+    /// Plan a path if the destination changed and move the agent one step along it
+    fn steer(transform: &mut Transform, agent: &mut NavAgent, delta_time: f32) {
+        let Some(destination) = agent.destination else {
+            return;
+        };
+
+        let position = transform.get_position();
+        if agent.path_destination != Some(destination) {
+            match Self::find_path([position[0], position[1] - agent.ground_offset, position[2]], destination) {
+                Some(path) => {
+                    agent.path = path;
+                    agent.path_index = 0;
+                    agent.path_destination = Some(destination);
+                }
+                None => {
+                    println!("🧭 No path to {:?}, stopping agent", destination);
+                    agent.stop();
+                    return;
+                }
+            }
+        }
+
+        let Some(waypoint) = agent.path.get(agent.path_index).copied() else {
+            agent.stop();
+            return;
+        };
+        let target = [waypoint[0], waypoint[1] + agent.ground_offset, waypoint[2]];
+        let offset = [target[0] - position[0], target[1] - position[1], target[2] - position[2]];
+        let distance = (offset[0] * offset[0] + offset[1] * offset[1] + offset[2] * offset[2]).sqrt();
+
+        if distance <= agent.arrival_radius {
+            agent.path_index += 1;
+            if agent.path_index >= agent.path.len() {
+                agent.stop();
+            }
+            return;
+        }
+
+        let step = (agent.speed * delta_time).min(distance);
+        transform.set_position(
+            position[0] + (offset[0] / distance) * step,
+            position[1] + (offset[1] / distance) * step,
+            position[2] + (offset[2] / distance) * step
+        );
+
+        // Face the direction of travel
+        if offset[0].abs() + offset[2].abs() > 1e-4 {
+            let rotation = transform.get_rotation();
+            transform.set_rotation(rotation[0], offset[0].atan2(offset[2]), rotation[2]);
+        }
+    }
+
+    /// Draw the outline of every walkable cell when navmesh debug drawing is enabled
+    pub fn render_debug(gl: &glow::Context, width: u32, height: u32) {
+        if !SHOW_NAVMESH.load(Ordering::Relaxed) || !RenderSystem::layer_rendered(LAYER_GIZMOS) {
+            return;
+        }
+        let Some(navmesh) = Self::navmesh() else {
+            Self::release_debug_lines(gl);
+            return;
+        };
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+
+        DEBUG_LINES.with(|lines| {
+            let mut lines = lines.borrow_mut();
+            if lines.as_ref().is_none_or(|lines| !Arc::ptr_eq(&lines.navmesh, &navmesh)) {
+                let vertices = Self::debug_vertices(&navmesh);
+                match lines.as_mut() {
+                    Some(lines) => {
+                        lines.buffer.write(gl, bytemuck::cast_slice(&vertices), BufferUsage::Stream);
+                        lines.navmesh = navmesh;
+                        lines.vertex_count = vertices.len() / 3;
+                    }
+                    None => {
+                        let Ok(buffer) = Buffer::vertex(gl, &vertices, BufferUsage::Stream) else {
+                            return;
+                        };
+                        let attributes = [VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)];
+                        let Ok(geometry) = Geometry::new(gl, &attributes, None) else {
+                            buffer.delete(gl);
+                            return;
+                        };
+                        *lines = Some(DebugLines { navmesh, buffer, geometry, vertex_count: vertices.len() / 3 });
+                    }
+                }
+            }
+            let Some(lines) = lines.as_ref().filter(|lines| lines.vertex_count > 0) else {
+                return;
+            };
+
+            let identity = mat4x4_identity();
+            DrawCall::new(get_wireframe_shader(), Primitive::Lines, lines.vertex_count)
+                .geometry(lines.geometry)
+                .uniform("world_txfm", Uniform::Mat4(&identity))
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                .uniform("line_color", Uniform::Vec3(&[0.0, 1.0, 0.0]))
+                .submit(gl);
+        });
+    }
+
+    /// Line list outlining every walkable cell, lifted slightly to avoid z-fighting with the floor
+    fn debug_vertices(navmesh: &NavMesh) -> Vec<f32> {
+        let half = navmesh.settings.cell_size * 0.45;
+        let mut vertices: Vec<f32> = Vec::with_capacity(navmesh.cells.len() * 24);
+        for cell in &navmesh.cells {
+            let [x, y, z] = navmesh.cell_position(cell);
+            let y = y + 0.02;
+            let corners = [
+                [x - half, y, z - half],
+                [x + half, y, z - half],
+                [x + half, y, z + half],
+                [x - half, y, z + half],
+            ];
+            for i in 0..4 {
+                vertices.extend_from_slice(&corners[i]);
+                vertices.extend_from_slice(&corners[(i + 1) % 4]);
+            }
+        }
+        vertices
+    }
+
+    fn release_debug_lines(gl: &glow::Context) {
+        if let Some(lines) = DEBUG_LINES.with(|lines| lines.borrow_mut().take()) {
+            lines.geometry.delete(gl);
+            lines.buffer.delete(gl);
+        }
+    }
+}
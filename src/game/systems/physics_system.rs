@@ -1,34 +1,275 @@
-use crate::index::engine::components::{ Collider, Transform };
-use crate::{ query, query_get_all };
+use std::collections::{ HashMap, HashSet };
+
+use crate::index::engine::components::{ Collider, ColliderLayer, PhysicsMaterial, RigidBody, Transform, Water };
+use crate::index::engine::modules::ecs::insert;
+use crate::index::engine::modules::{ is_enabled, Console, EntityId };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::engine::utils::math::{ dot, Vec3 };
+use crate::index::game::systems::VolumeSystem;
+use crate::query_get_all;
+
+/// Default of the `gravity` cvar
+pub const GRAVITY: f32 = 9.81;
+/// Impact speeds below this don't bounce, so resting bodies settle instead of jittering
+const BOUNCE_THRESHOLD: f32 = 0.5;
+/// Velocity fraction lost per second while fully submerged
+const WATER_DRAG: f32 = 1.5;
+/// Side of a broadphase grid cell
+const BROADPHASE_CELL: f32 = 4.0;
+/// Colliders covering more grid cells than this (terrain, large floors) skip the grid and are candidates for every
+/// query
+const MAX_BROADPHASE_CELLS: usize = 64;
+/// Penetration left unresolved, so bodies resting against each other keep touching instead of jittering apart
+const CONTACT_SLOP: f32 = 0.005;
+
+/// Closest collider hit along a ray
+#[derive(Clone, Debug)]
+pub struct RaycastHit {
+    pub entity_id: EntityId,
+    pub distance: f32,
+    pub point: [f32; 3],
+    pub normal: [f32; 3],
+    pub layer: ColliderLayer,
+    pub material: PhysicsMaterial,
+}
+
+/// Uniform grid over the world AABBs of the colliders, so a body is only tested against the colliders sharing a cell
+/// with it instead of every collider in the scene
+struct Broadphase {
+    bounds: Vec<(Vec3, Vec3)>,
+    cells: HashMap<[i32; 3], Vec<usize>>,
+    oversized: Vec<usize>,
+}
+
+impl Broadphase {
+    fn build(colliders: &[(EntityId, Collider, Transform)]) -> Self {
+        let mut broadphase = Broadphase {
+            bounds: colliders.iter().map(|(_, collider, transform)| collider.world_bounds(transform)).collect(),
+            cells: HashMap::new(),
+            oversized: Vec::new(),
+        };
+        for index in 0..broadphase.bounds.len() {
+            let (min, max) = broadphase.bounds[index];
+            match Self::cells_of(min, max) {
+                Some(cells) => {
+                    for cell in cells {
+                        broadphase.cells.entry(cell).or_default().push(index);
+                    }
+                }
+                None => broadphase.oversized.push(index),
+            }
+        }
+        broadphase
+    }
+
+    /// Grid cells an AABB touches, None past MAX_BROADPHASE_CELLS
+    fn cells_of(min: Vec3, max: Vec3) -> Option<Vec<[i32; 3]>> {
+        let low = min.map(|value| (value / BROADPHASE_CELL).floor() as i32);
+        let high = max.map(|value| (value / BROADPHASE_CELL).floor() as i32);
+        let count = (0..3).map(|axis| (high[axis] as i64 - low[axis] as i64 + 1).max(0) as usize).product::<usize>();
+        if count > MAX_BROADPHASE_CELLS {
+            return None;
+        }
+        let mut cells = Vec::with_capacity(count);
+        for x in low[0]..=high[0] {
+            for y in low[1]..=high[1] {
+                for z in low[2]..=high[2] {
+                    cells.push([x, y, z]);
+                }
+            }
+        }
+        Some(cells)
+    }
+
+    /// Colliders whose AABB overlaps (min, max), each listed once; the candidate pairs the narrowphase tests
+    fn candidates(&self, (min, max): (Vec3, Vec3)) -> Vec<usize> {
+        let mut found: Vec<usize> = match Self::cells_of(min, max) {
+            Some(cells) => cells
+                .iter()
+                .filter_map(|cell| self.cells.get(cell))
+                .flatten()
+                .chain(&self.oversized)
+                .copied()
+                .collect(),
+            None => (0..self.bounds.len()).collect(),
+        };
+        found.sort_unstable();
+        found.dedup();
+        found.retain(|&index| {
+            let (other_min, other_max) = self.bounds[index];
+            (0..3).all(|axis| min[axis] <= other_max[axis] && other_min[axis] <= max[axis])
+        });
+        found
+    }
+}
 
 pub struct PhysicsSystem;
 
 impl PhysicsSystem {
+    /// Cast a ray (normalized `direction`) against the enabled colliders on `layers`, skipping `ignore`
+    pub fn raycast(
+        origin: [f32; 3],
+        direction: [f32; 3],
+        max_distance: f32,
+        layers: &[ColliderLayer],
+        ignore: &[&EntityId]
+    ) -> Option<RaycastHit> {
+        query_get_all!(Collider, Transform)
+            .into_iter()
+            .filter(|(entity_id, collider, _)| {
+                layers.contains(&collider.layer) && !ignore.contains(&entity_id) && is_enabled(entity_id)
+            })
+            .filter_map(|(entity_id, collider, transform)| {
+                let (distance, point, normal) = collider.raycast(&transform, origin, direction, max_distance)?;
+                Some(RaycastHit {
+                    entity_id,
+                    distance,
+                    point,
+                    normal,
+                    layer: collider.layer,
+                    material: collider.material,
+                })
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+
+    /// Downward acceleration from the `gravity` cvar, or what the Volumes around the camera make of it
+    pub fn gravity() -> f32 {
+        VolumeSystem::current().gravity.apply(Console::cvar("gravity").unwrap_or(GRAVITY))
+    }
+
     pub fn update() {
-        let all_colliders = query_get_all!(Collider, Transform);
+        let all_colliders: Vec<_> = query_get_all!(Collider, Transform)
+            .into_iter()
+            .filter(|(entity_id, _, _)| is_enabled(entity_id))
+            .collect();
+
+        Self::simulate_bodies(&all_colliders);
+        Self::resolve_penetrations();
+    }
+
+    /// Push dynamic bodies out of the colliders they overlap along the contact normals and stop their velocity into
+    /// them; of two dynamic bodies, each moves half the way
+    fn resolve_penetrations() {
+        let colliders: Vec<_> = query_get_all!(Collider, Transform)
+            .into_iter()
+            .filter(|(entity_id, _, _)| is_enabled(entity_id))
+            .collect();
+        let broadphase = Broadphase::build(&colliders);
+        let dynamic: HashSet<EntityId> = query_get_all!(RigidBody)
+            .into_iter()
+            .filter(|(_, body)| !body.is_kinematic)
+            .map(|(entity_id, _)| entity_id)
+            .collect();
 
-        // Query entities that have both Transform and Collider components
-        query!((Transform, Collider), |current_entity_id, current_transform, current_collider| {
-            for (other_entity_id, other_collider, other_transform) in &all_colliders {
-                if current_entity_id == *other_entity_id {
+        for (entity_id, mut transform, mut body, collider) in query_get_all!(Transform, RigidBody, Collider) {
+            if body.is_kinematic || !is_enabled(&entity_id) {
+                continue;
+            }
+            let mut resolved = false;
+            for index in broadphase.candidates(collider.world_bounds(&transform)) {
+                let (other_id, other, other_transform) = &colliders[index];
+                if *other_id == entity_id || collider.ignored_layers.contains(&other.layer) {
                     continue;
                 }
-
-                if current_collider.ignored_layers.contains(&other_collider.layer) {
+                let Some(contact) = collider.contact(other, &transform, other_transform) else {
+                    continue;
+                };
+                if contact.depth <= CONTACT_SLOP {
                     continue;
                 }
 
-                if
-                    current_collider
-                        .clone()
-                        .is_collides(
-                            other_collider.clone(),
-                            current_transform.clone(),
-                            other_transform.clone()
-                        )
-                {
+                // The normal points into the other collider, so the body backs off against it
+                let share = if dynamic.contains(other_id) { 0.5 } else { 1.0 };
+                let push = (contact.depth - CONTACT_SLOP) * share;
+                transform.translate(-contact.normal[0] * push, -contact.normal[1] * push, -contact.normal[2] * push);
+                let approach = dot(body.velocity, contact.normal);
+                if approach > 0.0 {
+                    body.velocity = [0, 1, 2].map(|i| body.velocity[i] - contact.normal[i] * approach);
                 }
+                resolved = true;
+            }
+
+            if resolved {
+                insert(&entity_id, transform);
+                insert(&entity_id, body);
+            }
+        }
+    }
+
+    /// Integrate dynamic rigid bodies and resolve their ground contacts using the combined physics materials
+    fn simulate_bodies(colliders: &[(EntityId, Collider, Transform)]) {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        let gravity = Self::gravity();
+        let waters: Vec<(Transform, Water)> = query_get_all!(Transform, Water)
+            .into_iter()
+            .filter(|(entity_id, _, _)| is_enabled(entity_id))
+            .map(|(_, transform, water)| (transform, water))
+            .collect();
+
+        let broadphase = Broadphase::build(colliders);
+
+        for (entity_id, mut transform, mut body, collider) in query_get_all!(Transform, RigidBody, Collider) {
+            if body.is_kinematic || !is_enabled(&entity_id) {
+                continue;
             }
-        })
+
+            body.velocity[1] -= gravity * body.gravity_scale * delta_time;
+
+            // Buoyancy pushes up by the submerged fraction relative to the body's density
+            let lowest = collider.lowest_point(&transform);
+            let body_height = (transform.get_position()[1] - lowest[1]).max(0.05) * 2.0;
+            for (water_transform, water) in &waters {
+                let surface = water_transform.get_position();
+                let inside = (lowest[0] - surface[0]).abs() <= water.size[0] * 0.5 &&
+                    (lowest[2] - surface[2]).abs() <= water.size[1] * 0.5;
+                if inside && lowest[1] < surface[1] {
+                    let submerged = ((surface[1] - lowest[1]) / body_height).min(1.0);
+                    body.velocity[1] += gravity * submerged / collider.material.density.max(0.01) * delta_time;
+                    let drag = (1.0 - WATER_DRAG * submerged * delta_time).max(0.0);
+                    body.velocity = [body.velocity[0] * drag, body.velocity[1] * drag, body.velocity[2] * drag];
+                }
+            }
+
+            transform.translate(body.velocity[0] * delta_time, body.velocity[1] * delta_time, body.velocity[2] * delta_time);
+
+            // Rest on the highest surface the body sank into this tick
+            let lowest = collider.lowest_point(&transform);
+            let max_penetration = body.velocity[1].abs() * delta_time + (transform.get_position()[1] - lowest[1]).max(0.05);
+            // Only colliders whose bounds reach between the lowest point and the deepest surface it can sink to
+            let reach = (lowest, [lowest[0], lowest[1] + max_penetration, lowest[2]]);
+            let contact = broadphase
+                .candidates(reach)
+                .into_iter()
+                .map(|index| &colliders[index])
+                .filter(|(other_id, other, _)| *other_id != entity_id && !collider.ignored_layers.contains(&other.layer))
+                .filter_map(|(_, other, other_transform)| {
+                    let surface = other.surface_height(other_transform, lowest[0], lowest[2])?;
+                    let penetration = surface - lowest[1];
+                    (penetration > 0.0 && penetration <= max_penetration).then_some((penetration, other))
+                })
+                .max_by(|a, b| a.0.total_cmp(&b.0));
+
+            if let Some((penetration, other)) = contact {
+                let material = collider.material.combine(&other.material);
+                transform.translate(0.0, penetration, 0.0);
+
+                if body.velocity[1] < 0.0 {
+                    let impact = -body.velocity[1];
+                    body.velocity[1] = if impact > BOUNCE_THRESHOLD { impact * material.restitution } else { 0.0 };
+                }
+
+                // Coulomb friction slows the sliding by friction * g
+                let speed = (body.velocity[0] * body.velocity[0] + body.velocity[2] * body.velocity[2]).sqrt();
+                if speed > 0.0 {
+                    let slowed = (speed - material.friction * gravity * delta_time).max(0.0) / speed;
+                    body.velocity[0] *= slowed;
+                    body.velocity[2] *= slowed;
+                }
+            }
+
+            insert(&entity_id, transform);
+            insert(&entity_id, body);
+        }
     }
 }
@@ -0,0 +1,91 @@
+use crate::index::engine::components::{ Health, Projectile, ProjectileHitEvent, Transform };
+use crate::index::engine::modules::ecs::{ insert, is_enabled, CommandBuffer };
+use crate::index::engine::modules::event_system::{ Event, EventSystem, EventType };
+use crate::index::engine::modules::{ spawn, EntityId, EntityPool };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::game::systems::physics_system::PhysicsSystem;
+use crate::index::game::systems::HealthSystem;
+use crate::{ get_query_by_id, insert_many, query_get_all };
+
+/// EntityPool name of fired projectiles; pooled once a game designates it (e.g. `pools projectile 64`)
+pub const PROJECTILE_POOL: &str = "projectile";
+
+pub struct ProjectileSystem;
+
+impl ProjectileSystem {
+    /// Spawn a projectile at `origin`; it starts moving on the next fixed tick
+    #[allow(dead_code)]
+    pub fn fire(origin: [f32; 3], projectile: Projectile) -> EntityId {
+        let projectile_entity_id = EntityPool::acquire(PROJECTILE_POOL, || Some(spawn())).unwrap_or_else(spawn);
+        insert_many!(
+            projectile_entity_id.clone(),
+            Transform::new(origin[0], origin[1], origin[2]),
+            projectile
+        );
+        projectile_entity_id
+    }
+
+    /// Sweep every projectile along this tick's path, stopping at the first collider it crosses. Spent projectiles
+    /// are despawned after the tick, back to the pool when projectiles are pooled
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        let gravity = PhysicsSystem::gravity();
+        let mut commands = CommandBuffer::new();
+
+        for (entity_id, mut transform, mut projectile) in query_get_all!(Transform, Projectile) {
+            if !is_enabled(&entity_id) {
+                continue;
+            }
+            projectile.age += delta_time;
+            if projectile.age >= projectile.lifetime {
+                commands.despawn(&entity_id);
+                continue;
+            }
+
+            projectile.velocity[1] -= gravity * projectile.gravity_factor * delta_time;
+            let step = projectile.velocity.map(|v| v * delta_time);
+            let distance = (step[0] * step[0] + step[1] * step[1] + step[2] * step[2]).sqrt();
+            if distance <= 1e-6 {
+                insert(&entity_id, projectile);
+                continue;
+            }
+
+            let origin = transform.get_position();
+            let direction = step.map(|s| s / distance);
+            let mut ignore = vec![&entity_id];
+            if let Some(owner) = projectile.owner.as_ref() {
+                ignore.push(owner);
+            }
+
+            match PhysicsSystem::raycast(origin, direction, distance, &projectile.hit_layers, &ignore) {
+                Some(hit) => {
+                    println!("🎯 Projectile {} hit {}", entity_id, hit.entity_id);
+                    if projectile.damage != 0.0 && get_query_by_id!(hit.entity_id, (Health)).is_some() {
+                        HealthSystem::apply_damage(&hit.entity_id, projectile.damage, projectile.owner.as_ref());
+                    }
+                    EventSystem::send(Event {
+                        event_type: EventType::ProjectileHit,
+                        payload: Box::new(ProjectileHitEvent {
+                            projectile: entity_id.clone(),
+                            target: hit.entity_id,
+                            owner: projectile.owner.clone(),
+                            point: hit.point,
+                            normal: hit.normal,
+                            layer: hit.layer,
+                            material: hit.material,
+                            velocity: projectile.velocity,
+                        }),
+                    });
+                    commands.despawn(&entity_id);
+                }
+                None => {
+                    transform.translate(step[0], step[1], step[2]);
+                    insert(&entity_id, transform);
+                    insert(&entity_id, projectile);
+                }
+            }
+        }
+
+        commands.submit();
+    }
+}
@@ -0,0 +1,148 @@
+use std::cell::Cell;
+
+use crate::index::engine::components::{ AnimatedProperty, CameraComponent, PointLight, PropertyAnimation, Transform };
+use crate::index::engine::modules::ecs::{ insert, is_enabled };
+use crate::index::engine::modules::{ EntityId, InterfaceSystem };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::{ get_query_by_id, query_get_all };
+
+/// Frames between curve editor refreshes
+const EDITOR_REFRESH_FRAMES: u32 = 6;
+
+thread_local! {
+    static FRAMES_SINCE_REFRESH: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Plays PropertyAnimation tracks into the components they drive: Transform, the Camera (camera paths) and
+/// PointLight. Tracks of components the entity doesn't have are skipped.
+pub struct PropertyAnimationSystem;
+
+impl PropertyAnimationSystem {
+    /// Advance every playing PropertyAnimation by one tick and apply its values
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+
+        for (entity_id, mut animation) in query_get_all!(PropertyAnimation) {
+            // Paused animations leave the properties to the editor
+            if !is_enabled(&entity_id) || !animation.playing {
+                continue;
+            }
+            animation.advance(delta_time);
+            Self::apply(&entity_id, &animation.sample());
+            insert(&entity_id, animation);
+        }
+    }
+
+    /// Render system: keep the curve editor's playhead and entity current, also while the simulation is paused
+    pub fn sync_editor(_gl: &glow::Context, _width: u32, _height: u32) {
+        let refresh = FRAMES_SINCE_REFRESH.with(|frames| {
+            frames.set((frames.get() + 1) % EDITOR_REFRESH_FRAMES);
+            frames.get() == 0
+        });
+        if refresh {
+            InterfaceSystem::refresh_curve_editor();
+        }
+    }
+
+    /// Write property values into the entity's components
+    pub fn apply(entity_id: &EntityId, values: &[(AnimatedProperty, f32)]) {
+        if let Some(mut transform) = get_query_by_id!(entity_id.clone(), (Transform)) {
+            let (mut position, mut rotation, mut scale) = (
+                transform.get_position(),
+                transform.get_rotation(),
+                transform.get_scale(),
+            );
+            let mut changed = false;
+            for &(property, value) in values {
+                let field = match property {
+                    AnimatedProperty::PositionX => &mut position[0],
+                    AnimatedProperty::PositionY => &mut position[1],
+                    AnimatedProperty::PositionZ => &mut position[2],
+                    AnimatedProperty::RotationX => &mut rotation[0],
+                    AnimatedProperty::RotationY => &mut rotation[1],
+                    AnimatedProperty::RotationZ => &mut rotation[2],
+                    AnimatedProperty::ScaleX => &mut scale[0],
+                    AnimatedProperty::ScaleY => &mut scale[1],
+                    AnimatedProperty::ScaleZ => &mut scale[2],
+                    _ => continue,
+                };
+                *field = value;
+                changed = true;
+            }
+            if changed {
+                transform.set_from_components(position, rotation, scale);
+                insert(entity_id, transform);
+            }
+        }
+
+        let camera_values = values.iter().filter(|(property, _)| {
+            matches!(property, AnimatedProperty::CameraPitch | AnimatedProperty::CameraYaw)
+        });
+        if camera_values.clone().next().is_some() {
+            if let Some(mut camera) = get_query_by_id!(entity_id.clone(), (CameraComponent)) {
+                for &(property, value) in camera_values {
+                    if property == AnimatedProperty::CameraPitch {
+                        camera.set_pitch(value);
+                    } else {
+                        camera.set_yaw(value);
+                    }
+                }
+                insert(entity_id, camera);
+            }
+        }
+
+        let intensity = values.iter().find(|(property, _)| *property == AnimatedProperty::LightIntensity);
+        if let Some(&(_, intensity)) = intensity {
+            if let Some(mut light) = get_query_by_id!(entity_id.clone(), (PointLight)) {
+                light.intensity = intensity.max(0.0);
+                insert(entity_id, light);
+            }
+        }
+    }
+
+    /// A property's current value on the entity, or None if it lacks the component; keys start from it
+    pub fn read(entity_id: &EntityId, property: AnimatedProperty) -> Option<f32> {
+        match property {
+            AnimatedProperty::CameraPitch => {
+                get_query_by_id!(entity_id.clone(), (CameraComponent)).map(|camera| camera.get_pitch())
+            }
+            AnimatedProperty::CameraYaw => {
+                get_query_by_id!(entity_id.clone(), (CameraComponent)).map(|camera| camera.get_yaw())
+            }
+            AnimatedProperty::LightIntensity => {
+                get_query_by_id!(entity_id.clone(), (PointLight)).map(|light| light.intensity)
+            }
+            _ => {
+                let transform = get_query_by_id!(entity_id.clone(), (Transform))?;
+                Some(match property {
+                    AnimatedProperty::PositionX => transform.get_position()[0],
+                    AnimatedProperty::PositionY => transform.get_position()[1],
+                    AnimatedProperty::PositionZ => transform.get_position()[2],
+                    AnimatedProperty::RotationX => transform.get_rotation()[0],
+                    AnimatedProperty::RotationY => transform.get_rotation()[1],
+                    AnimatedProperty::RotationZ => transform.get_rotation()[2],
+                    AnimatedProperty::ScaleX => transform.get_scale()[0],
+                    AnimatedProperty::ScaleY => transform.get_scale()[1],
+                    _ => transform.get_scale()[2],
+                })
+            }
+        }
+    }
+
+    /// Change an entity's PropertyAnimation from the curve editor, adding one on the first edit, then show the
+    /// values at its playhead so edits are visible while paused
+    pub fn edit(
+        entity_id: &EntityId,
+        change: impl FnOnce(&mut PropertyAnimation) -> Result<(), String>
+    ) -> Result<PropertyAnimation, String> {
+        if entity_id.is_empty() {
+            return Err("Select an entity to animate".to_string());
+        }
+        let mut animation = get_query_by_id!(entity_id.clone(), (PropertyAnimation))
+            .unwrap_or_else(|| PropertyAnimation { playing: false, ..Default::default() });
+        change(&mut animation)?;
+        Self::apply(entity_id, &animation.sample());
+        insert(entity_id, animation.clone());
+        Ok(animation)
+    }
+}
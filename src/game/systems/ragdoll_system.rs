@@ -0,0 +1,92 @@
+use crate::index::engine::components::{
+    AiAgent,
+    AnimatedObject3DComponent,
+    NavAgent,
+    Ragdoll,
+    Terrain,
+    Transform,
+};
+use crate::index::engine::modules::ecs::{ insert, is_enabled };
+use crate::index::engine::modules::EntityId;
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::{ get_query_by_id, query_by_id, query_get_all };
+
+pub struct RagdollSystem;
+
+impl RagdollSystem {
+    /// Editor tool: (re)build the capsules and joints of an animated entity from its skeleton
+    pub fn generate(entity_id: &EntityId) -> bool {
+        let Some(animated_object) = get_query_by_id!(entity_id, (AnimatedObject3DComponent)) else {
+            println!("⚠️ {} has no animated skeleton to build a ragdoll from", entity_id);
+            return false;
+        };
+        let ragdoll = Ragdoll::generate(&animated_object.skeleton);
+        println!("🦴 Generated ragdoll for {}: {} bones, {} joints", entity_id, ragdoll.bones.len(), ragdoll.joints.len());
+        insert(entity_id, ragdoll);
+        true
+    }
+
+    /// Death hook: hand the skeleton from the animator to the ragdoll simulation
+    pub fn activate(entity_id: &EntityId) {
+        let (Some(mut transform), Some(animated_object)) = (
+            get_query_by_id!(entity_id, (Transform)),
+            get_query_by_id!(entity_id, (AnimatedObject3DComponent)),
+        ) else {
+            println!("⚠️ {} can't ragdoll without a Transform and an animated skeleton", entity_id);
+            return;
+        };
+        let mut ragdoll = get_query_by_id!(entity_id, (Ragdoll))
+            .unwrap_or_else(|| Ragdoll::generate(&animated_object.skeleton));
+        if !ragdoll.activate(&animated_object.skeleton, transform.get_matrix()) {
+            println!("⚠️ Ragdoll of {} doesn't match its skeleton, regenerate it", entity_id);
+            return;
+        }
+        println!("🪆 {} went limp", entity_id);
+        insert(entity_id, ragdoll);
+
+        // The body no longer walks or looks around
+        query_by_id!(entity_id, (NavAgent), |nav| {
+            nav.stop();
+        });
+        query_by_id!(entity_id, (AiAgent), |ai| {
+            ai.behaviors.clear();
+        });
+    }
+
+    /// Whether an entity's skeleton is currently driven by its ragdoll
+    pub fn is_active(entity_id: &EntityId) -> bool {
+        get_query_by_id!(entity_id, (Ragdoll)).is_some_and(|ragdoll| ragdoll.active)
+    }
+
+    /// Simulate every active ragdoll and pose its skeleton. Runs after animation so it has the last word
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        let terrains: Vec<(Transform, Terrain)> = query_get_all!(Transform, Terrain)
+            .into_iter()
+            .filter(|(entity_id, _, _)| is_enabled(entity_id))
+            .map(|(_, transform, terrain)| (transform, terrain))
+            .collect();
+        let ground = |x: f32, z: f32| -> Option<f32> {
+            terrains
+                .iter()
+                .filter_map(|(transform, terrain)| {
+                    let origin = transform.get_position();
+                    terrain.sample_height(x - origin[0], z - origin[2]).map(|height| origin[1] + height)
+                })
+                .reduce(f32::max)
+        };
+
+        for (entity_id, mut transform, mut ragdoll) in query_get_all!(Transform, Ragdoll) {
+            if !ragdoll.active || !is_enabled(&entity_id) {
+                continue;
+            }
+            ragdoll.step(delta_time, ground);
+
+            let entity_matrix = *transform.get_matrix();
+            query_by_id!(entity_id, (AnimatedObject3DComponent), |animated_object| {
+                ragdoll.apply_pose(&mut animated_object.skeleton, &entity_matrix);
+            });
+            insert(&entity_id, ragdoll);
+        }
+    }
+}
@@ -0,0 +1,222 @@
+use std::cell::{ Cell, RefCell };
+use std::collections::HashMap;
+use glow::HasContext;
+
+use crate::index::engine::components::{ Lighting, ReflectionProbe };
+use crate::index::engine::components::SharedComponents::{ Material, Transform };
+use crate::index::engine::modules::{ EngineError, EngineResult, EntityId, InterfaceSystem, Project };
+use crate::index::engine::rhi::{ Cubemap, DrawCall, Uniform };
+use crate::index::engine::utils::{ mat4x4_look_at, mat4x4_mul, mat4x4_perspective };
+use crate::index::game::systems::{ LightClusterSystem, RenderSystem, TerrainSystem };
+use crate::query_get_all;
+
+/// Face size of probe cubemaps in pixels
+const PROBE_SIZE: u32 = 128;
+/// Sampler unit of the probe cubemap, after the baked occlusion
+const PROBE_UNIT: u32 = 6;
+/// Capture camera direction and image up per cubemap face (+x, -x, +y, -y, +z, -z), GL's face orientation
+const FACES: [([f32; 3], [f32; 3]); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, -1.0, 0.0], [0.0, 0.0, -1.0]),
+    ([0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [0.0, -1.0, 0.0]),
+];
+
+/// A captured probe as meshes sample it
+#[derive(Clone, Copy)]
+pub struct ProbeBinding {
+    cubemap: Cubemap,
+    position: [f32; 3],
+    box_min: [f32; 3],
+    box_max: [f32; 3],
+    box_projection: bool,
+}
+
+impl ProbeBinding {
+    fn contains(&self, point: [f32; 3]) -> bool {
+        (0..3).all(|axis| point[axis] >= self.box_min[axis] && point[axis] <= self.box_max[axis])
+    }
+
+    fn volume(&self) -> f32 {
+        (0..3).map(|axis| self.box_max[axis] - self.box_min[axis]).product()
+    }
+}
+
+thread_local! {
+    static CUBEMAPS: RefCell<HashMap<EntityId, Cubemap>> = RefCell::new(HashMap::new());
+    /// Captured probes of the last frame, which the next frame's meshes reflect
+    static BINDINGS: RefCell<Vec<ProbeBinding>> = const { RefCell::new(Vec::new()) };
+    static REBAKE: Cell<bool> = const { Cell::new(false) };
+    // Meshes drawn into a probe don't sample probes, least of all the one being drawn
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+    // Set once a capture failed on this GL context; probes stay uncaptured
+    static UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Reflection probes: captures a cubemap per ReflectionProbe and gives static meshes inside a probe's box specular
+/// reflections of it, by their material's metallic and roughness factors
+pub struct ReflectionProbeSystem;
+
+impl ReflectionProbeSystem {
+    /// Capture every probe again, e.g. after moving probes or the scene around them
+    pub fn rebake() {
+        REBAKE.with(|rebake| rebake.set(true));
+    }
+
+    /// Capture probes that have no cubemap yet, one per frame so placing several doesn't stall the editor; runs
+    /// after the frame's other passes
+    pub fn render(gl: &glow::Context, _width: u32, _height: u32) {
+        let probes: Vec<(EntityId, [f32; 3], ReflectionProbe)> = query_get_all!(Transform, ReflectionProbe)
+            .into_iter()
+            .filter(|(entity_id, _, _)| !RenderSystem::is_hidden(entity_id))
+            .map(|(entity_id, transform, probe)| (entity_id, transform.get_position(), probe))
+            .collect();
+
+        CUBEMAPS.with(|cubemaps| {
+            let mut cubemaps = cubemaps.borrow_mut();
+            let rebake = REBAKE.with(|rebake| rebake.replace(false));
+            cubemaps.retain(|entity_id, cubemap| {
+                let keep = !rebake && probes.iter().any(|(probe_id, _, _)| probe_id == entity_id);
+                if !keep {
+                    cubemap.delete(gl);
+                }
+                keep
+            });
+
+            let missing = probes.iter().find(|(entity_id, _, _)| !cubemaps.contains_key(entity_id));
+            if let (Some((entity_id, position, _)), false) = (missing, UNAVAILABLE.with(Cell::get)) {
+                match Self::capture(gl, *position, &RenderSystem::current_lighting()) {
+                    Ok(cubemap) => {
+                        cubemaps.insert(entity_id.clone(), cubemap);
+                    }
+                    Err(e) => {
+                        UNAVAILABLE.with(|unavailable| unavailable.set(true));
+                        InterfaceSystem::report_error(&e);
+                    }
+                }
+            }
+
+            let bindings = probes
+                .iter()
+                .filter_map(|(entity_id, position, probe)| {
+                    let (box_min, box_max) = probe.bounds(*position);
+                    Some(ProbeBinding {
+                        cubemap: *cubemaps.get(entity_id)?,
+                        position: *position,
+                        box_min,
+                        box_max,
+                        box_projection: probe.box_projection,
+                    })
+                })
+                .collect();
+            BINDINGS.with(|current| *current.borrow_mut() = bindings);
+        });
+    }
+
+    /// Draw the scene around `position` into the six faces of a new cubemap
+    fn capture(gl: &glow::Context, position: [f32; 3], lighting: &Lighting) -> EngineResult<Cubemap> {
+        let cubemap = Cubemap::render_target(gl, PROBE_SIZE)?;
+        let size = cubemap.size() as i32;
+        let gpu_error = |e: String| EngineError::gpu(format!("Failed to create reflection probe target: {}", e));
+        unsafe {
+            let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+            let mut previous_viewport = [0i32; 4];
+            gl.get_parameter_i32_slice(glow::VIEWPORT, &mut previous_viewport);
+
+            let framebuffer = gl.create_framebuffer().map_err(gpu_error)?;
+            let depth = gl.create_renderbuffer().map_err(gpu_error)?;
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+            gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH_COMPONENT24, size, size);
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+            gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+            gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, glow::RENDERBUFFER, Some(depth));
+            gl.viewport(0, 0, size, size);
+
+            let projection = mat4x4_perspective(90f32.to_radians(), 1.0, 0.1, 100.0);
+            let mut status = glow::FRAMEBUFFER_COMPLETE;
+            CAPTURING.with(|capturing| capturing.set(true));
+            for (face, (forward, up)) in FACES.iter().enumerate() {
+                gl.framebuffer_texture_2d(
+                    glow::FRAMEBUFFER,
+                    glow::COLOR_ATTACHMENT0,
+                    Cubemap::face_target(face),
+                    Some(cubemap.raw()),
+                    0
+                );
+                status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+                if status != glow::FRAMEBUFFER_COMPLETE {
+                    break;
+                }
+                let [sky_r, sky_g, sky_b] = lighting.sky_color;
+                gl.clear_color(sky_r, sky_g, sky_b, 1.0);
+                gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+
+                // Probes see the scene as played, without editor-only layers
+                let view_proj = mat4x4_mul(projection, mat4x4_look_at(position, *forward, *up));
+                RenderSystem::with_layer_mask(!Project::editor_only_layers(), || {
+                    LightClusterSystem::build(gl, &view_proj, PROBE_SIZE, PROBE_SIZE);
+                    RenderSystem::render_meshes(gl, &view_proj, lighting);
+                    TerrainSystem::draw(gl, &view_proj, lighting);
+                });
+            }
+            CAPTURING.with(|capturing| capturing.set(false));
+
+            gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+            gl.viewport(previous_viewport[0], previous_viewport[1], previous_viewport[2], previous_viewport[3]);
+            gl.bind_vertex_array(None);
+            gl.delete_framebuffer(framebuffer);
+            gl.delete_renderbuffer(depth);
+
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                cubemap.delete(gl);
+                return Err(EngineError::gpu(format!("Reflection probe target incomplete (status 0x{:x})", status)));
+            }
+        }
+        cubemap.generate_mipmaps(gl);
+        println!("🪞 Captured reflection probe at {:?}", position);
+        Ok(cubemap)
+    }
+
+    /// Probe a mesh centred at `point` reflects: the smallest whose box holds it
+    pub fn probe_at(point: [f32; 3]) -> Option<ProbeBinding> {
+        if CAPTURING.with(Cell::get) {
+            return None;
+        }
+        BINDINGS.with(|bindings| {
+            bindings
+                .borrow()
+                .iter()
+                .filter(|binding| binding.contains(point))
+                .min_by(|a, b| a.volume().total_cmp(&b.volume()))
+                .copied()
+        })
+    }
+
+    /// Add a probe's reflections to a draw with fragment_static.glsl; without a probe, or a camera position
+    /// (orthographic views), the mesh is only lit
+    pub fn bind<'a>(
+        draw: DrawCall<'a>,
+        probe: Option<&'a ProbeBinding>,
+        camera_position: Option<&'a [f32; 3]>,
+        material: &Material
+    ) -> DrawCall<'a> {
+        // The cube sampler needs its own unit even unused: it can't share unit 0 with the 2D base color sampler
+        let draw = draw
+            .uniform("probe_cubemap", Uniform::Int(PROBE_UNIT as i32))
+            .uniform("metallic", Uniform::Float(material.metallic_factor))
+            .uniform("roughness", Uniform::Float(material.roughness_factor));
+        let (Some(probe), Some(camera_position)) = (probe, camera_position) else {
+            return draw.uniform("has_probe", Uniform::Int(0));
+        };
+        draw.uniform("has_probe", Uniform::Int(1))
+            .uniform("camera_position", Uniform::Vec3(camera_position))
+            .uniform("probe_position", Uniform::Vec3(&probe.position))
+            .uniform("probe_box_min", Uniform::Vec3(&probe.box_min))
+            .uniform("probe_box_max", Uniform::Vec3(&probe.box_max))
+            .uniform("box_projection", Uniform::Int(probe.box_projection as i32))
+            .uniform("probe_mip_levels", Uniform::Float(probe.cubemap.mip_levels() as f32))
+            .cubemap(PROBE_UNIT, probe.cubemap)
+    }
+}
@@ -1,29 +1,99 @@
+use std::cell::Cell;
+use std::collections::HashMap;
 use glow::HasContext;
 
 // Import types and functions from parent scope
 use crate::index::engine::components::{
     AnimatedObject3DComponent,
+    layer_bit,
     CameraComponent,
+    CameraProjection,
     Collider,
+    Lighting,
+    Metadata,
+    Ragdoll,
     Shape,
     StaticObject3DComponent,
     SystemTrait,
+    TimeOfDay,
+    Volume,
+    EntityCategory,
+    LAYER_EDITOR_ICONS,
+    LAYER_GIZMOS,
 };
-use crate::index::engine::components::SharedComponents::Transform;
-use crate::index::engine::components::AnimatedObject3D::AnimationType;
+use crate::index::engine::components::SharedComponents::{ Material, Mesh, Transform };
+use crate::index::engine::components::AnimatedObject3D::Skeleton;
 use crate::index::engine::utils::{
+    aabb_in_frustum,
+    mat4x4_extract_translation,
+    mat4x4_identity,
+    mat4x4_orthographic,
     mat4x4_perspective,
     mat4x4_mul,
+    mat4x4_translate,
+    view_projection_eye,
 };
 use crate::index::engine::managers::assets_manager::{
+    Assets,
     get_box_shader,
     get_sphere_shader,
     get_capsule_shader,
     get_cylinder_shader,
+    get_static_outline_shader,
+    get_wireframe_shader,
 };
+use crate::index::engine::managers::MaterialLibrary;
 use crate::index::engine::modules::interface_system::InterfaceSystem;
+use crate::index::engine::rhi::{
+    Buffer,
+    BufferKind,
+    BufferUsage,
+    DepthCompare,
+    DrawCall,
+    Geometry,
+    Primitive,
+    Uniform,
+    VertexAttribute,
+    VertexFormat,
+};
+use crate::index::engine::modules::{ Console, EditorSettings, EntityId, Project, Selection, TransformInterpolation };
+use crate::index::engine::modules::editor_settings::OutlineSettings;
+use crate::index::game::systems::InteractionSystem;
+use crate::index::game::systems::{
+    AmbientOcclusionSystem,
+    CullingDebugSystem,
+    DepthPrepassSystem,
+    GpuSkinningSystem,
+    ImpostorSystem,
+    LightClusterSystem,
+    ReflectionProbeSystem,
+    TransparencySystem,
+    VolumeSystem,
+};
+use crate::index::game::systems::gpu_skinning_system::{ SkinnedInstance, MAX_JOINTS, MAX_MORPH_TARGETS, MORPH_UNIT };
 use crate::index::PLAYER_ENTITY_ID;
-use crate::{ query, get_query_by_id };
+use crate::{ query, query_get_all, get_query_by_id };
+
+/// Vertical field of view of the player camera
+pub const CAMERA_FOV_DEGREES: f32 = 90.0;
+/// Near and far clip distances of the perspective player camera
+pub const CAMERA_NEAR: f32 = 0.1;
+pub const CAMERA_FAR: f32 = 100.0;
+/// Default for the `fog_density` cvar; no fog until it's tweaked
+pub const FOG_DENSITY: f32 = 0.0;
+/// Half size of the bounds used for entities without a mesh
+const POINT_BOUNDS_EXTENT: f32 = 0.5;
+/// Distance in front of and behind the camera drawn by orthographic views
+const ORTHO_DEPTH: f32 = 200.0;
+/// Box around the members of a selected outliner group
+const GROUP_BOUNDS_COLOR: [f32; 3] = [1.0, 0.6, 0.0];
+/// Half size of the viewport category icons in world units
+const CATEGORY_ICON_SIZE: f32 = 0.35;
+
+thread_local! {
+    // Render layers drawn by the current pass, refreshed from the player camera every frame
+    static LAYER_MASK: Cell<u32> = const { Cell::new(u32::MAX) };
+}
 
 #[derive(Debug)]
 pub struct RenderSystem;
@@ -35,24 +105,211 @@ impl RenderSystem {
         InterfaceSystem::get_selection_state()
     }
 
-    /// Determine outline color based on selection state
-    fn get_outline_info(entity_id: &str, selected_id: &str, hovered_id: &str) -> Option<[f32; 3]> {
-        if entity_id == selected_id && !selected_id.is_empty() {
-            Some([1.0, 1.0, 0.0]) // Yellow for selected
-        } else if entity_id == hovered_id && !hovered_id.is_empty() {
-            Some([1.0, 1.0, 1.0]) // White for hovered
+    /// Determine outline color based on selection state and the player's interaction target, from the editor's
+    /// outline settings
+    fn get_outline_info(entity_id: &str, selected_id: &str, hovered_id: &str, outline: &OutlineSettings) -> Option<[f32; 3]> {
+        // Selection outlines are editor gizmos, the interaction highlight is gameplay
+        let gizmos = Self::layer_rendered(LAYER_GIZMOS);
+        if gizmos && entity_id == selected_id && !selected_id.is_empty() {
+            Some(outline.selected_color)
+        } else if gizmos && Selection::contains(entity_id) {
+            Some(outline.multi_selected_color) // The rest of the multi-selection
+        } else if gizmos && entity_id == hovered_id && !hovered_id.is_empty() {
+            Some(outline.hovered_color)
+        } else if InteractionSystem::target().is_some_and(|target| target == entity_id) {
+            Some(outline.interaction_color)
         } else {
             None // No outline
         }
     }
 
+    /// Whether the entity's Metadata made it invisible, disabled or put it on a layer the current pass
+    /// doesn't draw (entities without Metadata are drawn)
+    pub fn is_hidden(entity_id: &EntityId) -> bool {
+        Self::hidden_by(get_query_by_id!(entity_id, (Metadata)).as_ref())
+    }
+
+    /// `is_hidden` for Metadata a query already fetched, e.g. with `Option<Metadata>`
+    fn hidden_by(metadata: Option<&Metadata>) -> bool {
+        metadata.is_some_and(|metadata| {
+            !metadata.is_visible || !metadata.is_enabled || !Self::layer_rendered(metadata.render_layer)
+        })
+    }
+
+    /// Whether the current pass draws a render layer
+    pub fn layer_rendered(layer: u32) -> bool {
+        LAYER_MASK.with(|mask| mask.get() & layer_bit(layer) != 0)
+    }
+
+    /// Player camera culling mask, without the editor-only layers in Play mode
+    fn frame_layer_mask() -> u32 {
+        let camera_mask = PLAYER_ENTITY_ID.read()
+            .unwrap()
+            .as_ref()
+            .and_then(|player_id| get_query_by_id!(player_id, (CameraComponent)))
+            .map_or(u32::MAX, |camera| camera.culling_mask);
+        if InterfaceSystem::is_play_mode() {
+            camera_mask & !Project::editor_only_layers()
+        } else {
+            camera_mask
+        }
+    }
+
+    /// Run a pass with a different layer mask (captures use the Play view), restoring the frame mask after
+    pub fn with_layer_mask<R>(mask: u32, pass: impl FnOnce() -> R) -> R {
+        let previous = LAYER_MASK.with(|current| current.replace(mask));
+        let result = pass();
+        LAYER_MASK.with(|current| current.set(previous));
+        result
+    }
+
+    /// Player camera view-projection matrix, or None if there is no player camera yet
+    pub fn view_projection(width: u32, height: u32) -> Option<[f32; 16]> {
+        // Get player ID and camera in one scope to avoid lifetime issues
+        let (view_matrix, ortho_size) = {
+            let player_id_guard = PLAYER_ENTITY_ID.read().unwrap();
+            let player_id = player_id_guard.as_ref()?;
+
+            // Get camera - early return if None
+            let camera = get_query_by_id!(player_id, (CameraComponent))?;
+
+            // Get view matrix while we have the camera reference
+            (camera.get_view_matrix(player_id), Self::ortho_size(&camera))
+        };
+        let fov = CAMERA_FOV_DEGREES.to_radians();
+        let aspect_ratio = (width as f32) / (height as f32);
+        let projection_matrix = match ortho_size {
+            // The depth range reaches behind the camera so axis views show the whole scene around it
+            Some(size) => mat4x4_orthographic(size * aspect_ratio, size, -ORTHO_DEPTH, ORTHO_DEPTH),
+            None => mat4x4_perspective(fov, aspect_ratio, CAMERA_NEAR, CAMERA_FAR),
+        };
+        Some(mat4x4_mul(projection_matrix, view_matrix))
+    }
+
+    /// Half view height when the camera renders orthographic (editor view presets, never in Play mode)
+    fn ortho_size(camera: &CameraComponent) -> Option<f32> {
+        (camera.projection == CameraProjection::Orthographic && !InterfaceSystem::is_play_mode()).then_some(
+            camera.ortho_size
+        )
+    }
+
+    /// World-space position of the player camera
+    pub fn camera_position() -> Option<[f32; 3]> {
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone()?;
+        let mut transform = get_query_by_id!(player_id, (Transform))?;
+        Some(mat4x4_extract_translation(transform.get_matrix()))
+    }
+
+    /// Player camera basis (right, up, forward) from build_view_matrix; the view looks down -forward
+    pub fn camera_basis() -> Option<([f32; 3], [f32; 3], [f32; 3])> {
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone()?;
+        let camera = get_query_by_id!(player_id, (CameraComponent))?;
+        Some(Self::basis_of(&camera))
+    }
+
+    /// Basis (right, up, forward) of any camera from its pitch and yaw, as camera_basis
+    pub fn basis_of(camera: &CameraComponent) -> ([f32; 3], [f32; 3], [f32; 3]) {
+        let (cp, sp) = (camera.pitch.cos(), camera.pitch.sin());
+        let (cy, sy) = (camera.yaw.cos(), camera.yaw.sin());
+        ([cy, 0.0, sy], [sy * sp, cp, -cy * sp], [-sy * cp, sp, cy * cp])
+    }
+
+    /// World-space ray (origin, normalized direction) through a pixel of the viewport
+    pub fn camera_ray(pixel: [f32; 2], width: u32, height: u32) -> Option<([f32; 3], [f32; 3])> {
+        let (right, up, forward) = Self::camera_basis()?;
+        let origin = Self::camera_position()?;
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone()?;
+        let camera = get_query_by_id!(player_id, (CameraComponent))?;
+
+        // Invert the projection: view space looks down -forward with the basis from build_view_matrix
+        let tan_half_fov = (CAMERA_FOV_DEGREES.to_radians() * 0.5).tan();
+        let aspect_ratio = (width as f32) / (height as f32);
+        let ndc_x = (pixel[0] / width as f32) * 2.0 - 1.0;
+        let ndc_y = 1.0 - (pixel[1] / height as f32) * 2.0;
+
+        // Orthographic rays are parallel, starting on the camera plane
+        if let Some(size) = Self::ortho_size(&camera) {
+            let (offset_x, offset_y) = (ndc_x * size * aspect_ratio, ndc_y * size);
+            let origin = [0, 1, 2].map(|axis| origin[axis] + right[axis] * offset_x + up[axis] * offset_y);
+            return Some((origin, forward.map(|f| -f)));
+        }
+
+        let view_x = ndc_x * tan_half_fov * aspect_ratio;
+        let view_y = ndc_y * tan_half_fov;
+
+        let direction = [
+            right[0] * view_x + up[0] * view_y - forward[0],
+            right[1] * view_x + up[1] * view_y - forward[1],
+            right[2] * view_x + up[2] * view_y - forward[2],
+        ];
+        let length = (direction[0] * direction[0] + direction[1] * direction[1] + direction[2] * direction[2]).sqrt();
+        Some((origin, [direction[0] / length, direction[1] / length, direction[2] / length]))
+    }
+
+    /// Pixel position of a world point under a view-projection, None behind the camera
+    pub fn world_to_screen(view_proj: &[f32; 16], point: [f32; 3], width: u32, height: u32) -> Option<[f32; 2]> {
+        let row = |r: usize| view_proj[r * 4] * point[0] + view_proj[r * 4 + 1] * point[1] + view_proj[r * 4 + 2] * point[2] + view_proj[r * 4 + 3];
+        let w = row(3);
+        if w <= 1e-6 {
+            return None;
+        }
+        Some([(row(0) / w * 0.5 + 0.5) * width as f32, (0.5 - row(1) / w * 0.5) * height as f32])
+    }
+
+    /// AABB around the bounds of several entities
+    pub fn combined_bounds(entity_ids: &[EntityId]) -> Option<([f32; 3], [f32; 3])> {
+        entity_ids
+            .iter()
+            .filter_map(Self::entity_bounds)
+            .reduce(|(a_min, a_max), (b_min, b_max)| {
+                (
+                    [a_min[0].min(b_min[0]), a_min[1].min(b_min[1]), a_min[2].min(b_min[2])],
+                    [a_max[0].max(b_max[0]), a_max[1].max(b_max[1]), a_max[2].max(b_max[2])],
+                )
+            })
+    }
+
+    /// World AABB of the entity's mesh, or a small box around its position
+    pub fn entity_bounds(entity_id: &EntityId) -> Option<([f32; 3], [f32; 3])> {
+        let mut transform = get_query_by_id!(entity_id.clone(), (Transform))?;
+        let world_txfm = *transform.get_matrix();
+        let mesh = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent))
+            .map(|object| object.mesh)
+            .or_else(|| get_query_by_id!(entity_id.clone(), (AnimatedObject3DComponent)).map(|object| object.mesh));
+
+        match mesh {
+            Some(mesh) if mesh.index_count > 0 => Some(mesh.world_bounds(&world_txfm)),
+            _ => {
+                let position = mat4x4_extract_translation(&world_txfm);
+                Some((position.map(|p| p - POINT_BOUNDS_EXTENT), position.map(|p| p + POINT_BOUNDS_EXTENT)))
+            }
+        }
+    }
+
+    /// Lighting from the scene's TimeOfDay, or the default top-down light, fogged by the `fog_density` cvar or the
+    /// Volumes around the camera
+    pub fn current_lighting() -> Lighting {
+        let lighting: Lighting = query_get_all!(TimeOfDay)
+            .into_iter()
+            .next()
+            .map(|(_, time_of_day)| time_of_day.lighting())
+            .unwrap_or_default();
+        let fog_density = Console::cvar("fog_density").unwrap_or(FOG_DENSITY);
+        Lighting { fog_density: VolumeSystem::current().fog_density.apply(fog_density), ..lighting }
+    }
+
     pub fn update(gl: &glow::Context, width: u32, height: u32) {
+        let lighting = Self::current_lighting();
+        let layer_mask = Self::frame_layer_mask();
+        LAYER_MASK.with(|mask| mask.set(layer_mask));
+
         unsafe {
             // Set viewport for current frame
             gl.viewport(0, 0, width as i32, height as i32);
 
-            // Clear both color and depth buffers
-            gl.clear_color(0.1, 0.1, 0.1, 1.0);
+            // Clear both color and depth buffers (sky color follows the time of day)
+            let [sky_r, sky_g, sky_b] = lighting.sky_color;
+            gl.clear_color(sky_r, sky_g, sky_b, 1.0);
             gl.clear_depth_f32(1.0); // Clear depth to far plane
             gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
 
@@ -75,331 +332,483 @@ impl RenderSystem {
             }
         }
 
-        // Get player ID and camera in one scope to avoid lifetime issues
-        let view_matrix = {
-            let player_id_guard = PLAYER_ENTITY_ID.read().unwrap();
-            let player_id = match player_id_guard.as_ref() {
-                Some(id) => id,
-                None => {
-                    return;
-                }
-            };
-
-            // Get camera - early return if None
-            let camera = match get_query_by_id!(player_id, (CameraComponent)) {
-                Some(cam) => cam,
-                None => {
-                    return;
-                }
-            };
-
-            // Get view matrix while we have the camera reference
-            camera.get_view_matrix(player_id)
+        let Some(view_proj) = Self::view_projection(width, height) else {
+            return;
         };
-        let fov = (90.0_f32).to_radians();
-        let aspect_ratio = (width as f32) / (height as f32);
-        let projection_matrix = mat4x4_perspective(fov, aspect_ratio, 0.1, 100.0);
-        let view_proj = mat4x4_mul(projection_matrix, view_matrix);
 
         // Get selection state for outline rendering
         let (selected_id, hovered_id) = Self::get_selection_state();
 
-        Self::render_animated_objects(gl, &view_proj, &selected_id, &hovered_id);
-        Self::render_static_objects(gl, &view_proj, &selected_id, &hovered_id);
+        ImpostorSystem::camera_pass(|| {
+            CullingDebugSystem::record(|| {
+                DepthPrepassSystem::opaque_pass(gl, width, height, &view_proj, |static_depth| {
+                    Self::render_animated_objects(gl, &view_proj, &lighting, &selected_id, &hovered_id);
+                    Self::render_static_objects(gl, &view_proj, &lighting, static_depth);
+                });
+            });
+        });
         Self::render_shapes(gl, &view_proj);
+        Self::render_category_icons(gl, &view_proj);
 
         unsafe {
             gl.bind_vertex_array(None);
         }
     }
 
+    /// Draw the scene meshes from an arbitrary camera without selection outlines (minimap, captures)
+    pub fn render_meshes(gl: &glow::Context, view_proj: &[f32; 16], lighting: &Lighting) {
+        Self::render_animated_objects(gl, view_proj, lighting, "", "");
+        Self::render_static_objects(gl, view_proj, lighting, DepthCompare::Less);
+        TransparencySystem::draw_meshes(gl, view_proj, lighting);
+    }
+
+    /// Outline selected, hovered and interaction-target objects and box the selected group; runs after the
+    /// terrain and water so parts hidden behind any geometry are drawn dimmer (or not at all, per the settings)
+    pub fn render_outlines(gl: &glow::Context, width: u32, height: u32) {
+        let Some(view_proj) = Self::view_projection(width, height) else {
+            return;
+        };
+        let (selected_id, hovered_id) = Self::get_selection_state();
+        let outline = EditorSettings::current().outline;
+        let viewport_size = [width as f32, height as f32];
+
+        query!((Transform, StaticObject3DComponent, Option<Metadata>), |entity_id, transform, static_object, metadata| {
+            if Self::hidden_by(metadata.as_ref()) {
+                continue;
+            }
+            if let Some(outline_color) = Self::get_outline_info(&entity_id, &selected_id, &hovered_id, &outline) {
+                let world_txfm = TransformInterpolation::matrix(&entity_id, &mut transform);
+                let mesh_draw = || {
+                    DrawCall::new(get_static_outline_shader(), Primitive::Triangles, static_object.mesh.index_count)
+                        .geometry(static_object.mesh.geometry)
+                        .uniform("world_txfm", Uniform::Mat4(&world_txfm))
+                        .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                        .uniform("viewport_size", Uniform::Vec2(&viewport_size))
+                };
+                Self::render_outline(gl, mesh_draw, outline_color, &outline);
+            }
+        });
+
+        if Selection::group().is_some() && Self::layer_rendered(LAYER_GIZMOS) {
+            if let Some(bounds) = Self::combined_bounds(&Selection::ids()) {
+                Self::render_bounds_box(gl, &view_proj, bounds, GROUP_BOUNDS_COLOR, outline.occluded_brightness);
+            }
+        }
+    }
+
     fn render_shapes(gl: &glow::Context, view_proj: &[f32; 16]) {
-        query!((Transform, Collider), |_entity_id, transform, collider| {
-            if !collider.is_hidden {
+        // Collider wireframes are gizmos regardless of the owning entity's layer
+        if !Self::layer_rendered(LAYER_GIZMOS) {
+            return;
+        }
+        query!((Transform, Collider), |entity_id, transform, collider| {
+            if !collider.is_hidden && !Self::is_hidden(&entity_id) {
                 let world_txfm = transform.get_matrix();
                 Self::render_shape(gl, &collider.shape, world_txfm, view_proj);
             }
         });
+        query!((Transform, Volume), |entity_id, transform, volume| {
+            if !Self::is_hidden(&entity_id) {
+                Self::render_shape(gl, &volume.shape.wireframe(), transform.get_matrix(), view_proj);
+            }
+        });
+
+        // Generated ragdoll capsules follow the skeleton's current pose
+        for (entity_id, mut transform, ragdoll) in query_get_all!(Transform, Ragdoll) {
+            if Self::is_hidden(&entity_id) {
+                continue;
+            }
+            let Some(animated_object) = get_query_by_id!(entity_id, (AnimatedObject3DComponent)) else {
+                continue;
+            };
+            for (shape, world_txfm) in ragdoll.bone_colliders(&animated_object.skeleton, transform.get_matrix()) {
+                Self::render_shape(gl, &shape, &world_txfm, view_proj);
+            }
+        }
     }
 
-    fn render_shape(
-        gl: &glow::Context,
-        shape: &Shape,
-        world_txfm: &[f32; 16],
-        view_proj: &[f32; 16]
-    ) {
-        unsafe {
-            let (shader, vertex_count) = match shape {
-                Shape::Box { half_extents } => {
-                    let shader = get_box_shader();
-                    gl.use_program(Some(shader));
-
-                    // Set shape-specific uniforms
-                    if let Some(loc) = gl.get_uniform_location(shader, "half_extents") {
-                        gl.uniform_3_f32_slice(Some(&loc), half_extents);
-                    }
-                    (shader, 24) // 12 edges * 2 vertices each
+    /// Camera-facing line icons for categorized entities, tinted by their EntityCategory
+    fn render_category_icons(gl: &glow::Context, view_proj: &[f32; 16]) {
+        if !Self::layer_rendered(LAYER_EDITOR_ICONS) {
+            return;
+        }
+        let Some((right, up, _)) = Self::camera_basis() else {
+            return;
+        };
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone();
+
+        // One line batch per category so each is a single draw call
+        let mut batches: Vec<(EntityCategory, Vec<f32>)> = Vec::new();
+        for (entity_id, metadata, mut transform) in query_get_all!(Metadata, Transform) {
+            if
+                metadata.category == EntityCategory::Uncategorized ||
+                !metadata.is_visible ||
+                !metadata.is_enabled ||
+                player_id.as_ref() == Some(&entity_id)
+            {
+                continue;
+            }
+            let center = mat4x4_extract_translation(transform.get_matrix());
+            let index = match batches.iter().position(|(category, _)| *category == metadata.category) {
+                Some(index) => index,
+                None => {
+                    batches.push((metadata.category, Vec::new()));
+                    batches.len() - 1
                 }
-                Shape::Sphere { radius } => {
-                    let shader = get_sphere_shader();
-                    gl.use_program(Some(shader));
-
-                    // Set shape-specific uniforms
-                    if let Some(loc) = gl.get_uniform_location(shader, "radius") {
-                        gl.uniform_1_f32(Some(&loc), *radius);
+            };
+            for [a, b] in Self::category_glyph(metadata.category) {
+                for [u, v] in [a, b] {
+                    for axis in 0..3 {
+                        batches[index].1.push(center[axis] + (right[axis] * u + up[axis] * v) * CATEGORY_ICON_SIZE);
                     }
-                    (shader, 192) // 3 circles * 32 segments * 2 vertices each
                 }
-                Shape::Capsule { radius, height } => {
-                    let shader = get_capsule_shader();
-                    gl.use_program(Some(shader));
+            }
+        }
+        if batches.is_empty() {
+            return;
+        }
 
-                    // Set shape-specific uniforms
-                    if let Some(loc) = gl.get_uniform_location(shader, "radius") {
-                        gl.uniform_1_f32(Some(&loc), *radius);
-                    }
-                    if let Some(loc) = gl.get_uniform_location(shader, "height") {
-                        gl.uniform_1_f32(Some(&loc), *height);
-                    }
-                    (shader, 516) // 258 lines * 2 vertices each (complete hemispheres)
-                }
-                Shape::Cylinder { radius, height } => {
-                    let shader = get_cylinder_shader();
-                    gl.use_program(Some(shader));
+        let identity = mat4x4_identity();
+        let shader = get_wireframe_shader();
+        let Ok(buffer) = Buffer::new(gl, BufferKind::Vertex, &[], BufferUsage::Stream) else {
+            return;
+        };
+        let Ok(geometry) = Geometry::new(gl, &[VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)], None) else {
+            buffer.delete(gl);
+            return;
+        };
+        for (category, vertices) in &batches {
+            buffer.write(gl, bytemuck::cast_slice(vertices), BufferUsage::Stream);
+            let color = category.color();
+            DrawCall::new(shader, Primitive::Lines, vertices.len() / 3)
+                .geometry(geometry)
+                .uniform("world_txfm", Uniform::Mat4(&identity))
+                .uniform("viewport_txfm", Uniform::Mat4(view_proj))
+                .uniform("line_color", Uniform::Vec3(&color))
+                .submit(gl);
+        }
+        geometry.delete(gl);
+        buffer.delete(gl);
+    }
 
-                    // Set shape-specific uniforms
-                    if let Some(loc) = gl.get_uniform_location(shader, "radius") {
-                        gl.uniform_1_f32(Some(&loc), *radius);
-                    }
-                    if let Some(loc) = gl.get_uniform_location(shader, "height") {
-                        gl.uniform_1_f32(Some(&loc), *height);
-                    }
-                    (shader, 136) // 68 lines * 2 vertices each (64 circle lines + 4 vertical lines)
-                }
-            };
+    /// Icon line segments in camera-plane units (-1..1)
+    fn category_glyph(category: EntityCategory) -> Vec<[[f32; 2]; 2]> {
+        let square = |h: f32| vec![[[-h, -h], [h, -h]], [[h, -h], [h, h]], [[h, h], [-h, h]], [[-h, h], [-h, -h]]];
+        match category {
+            // Sun rays
+            EntityCategory::Light => (0..8)
+                .map(|i| {
+                    let angle = (i as f32) * std::f32::consts::FRAC_PI_4;
+                    let (s, c) = angle.sin_cos();
+                    [[c * 0.4, s * 0.4], [c, s]]
+                })
+                .collect(),
+            EntityCategory::Prop => square(0.7),
+            // Diamond
+            EntityCategory::Gameplay => vec![
+                [[0.0, 1.0], [1.0, 0.0]],
+                [[1.0, 0.0], [0.0, -1.0]],
+                [[0.0, -1.0], [-1.0, 0.0]],
+                [[-1.0, 0.0], [0.0, 1.0]],
+            ],
+            // Crossed box
+            EntityCategory::Volume => {
+                let mut lines = square(0.8);
+                lines.push([[-0.8, -0.8], [0.8, 0.8]]);
+                lines.push([[-0.8, 0.8], [0.8, -0.8]]);
+                lines
+            }
+            EntityCategory::Uncategorized => Vec::new(),
+        }
+    }
 
-            // Set common uniforms
-            if let Some(loc) = gl.get_uniform_location(shader, "world_txfm") {
-                gl.uniform_matrix_4_f32_slice(Some(&loc), true, world_txfm);
+    fn render_shape(
+        gl: &glow::Context,
+        shape: &Shape,
+        world_txfm: &[f32; 16],
+        view_proj: &[f32; 16]
+    ) {
+        // The vertex shaders build each wireframe from the vertex index and the shape uniforms
+        let (shader, vertex_count) = match shape {
+            Shape::Box { .. } => (get_box_shader(), 24), // 12 edges * 2 vertices each
+            Shape::Sphere { .. } => (get_sphere_shader(), 192), // 3 circles * 32 segments * 2 vertices each
+            Shape::Capsule { .. } => (get_capsule_shader(), 516), // 258 lines * 2 vertices each (complete hemispheres)
+            Shape::Cylinder { .. } => (get_cylinder_shader(), 136), // 68 lines * 2 vertices each (64 circle lines + 4 vertical lines)
+            Shape::Heightfield { .. } => {
+                return; // Visible through the terrain mesh itself
             }
-            if let Some(loc) = gl.get_uniform_location(shader, "viewport_txfm") {
-                gl.uniform_matrix_4_f32_slice(Some(&loc), true, view_proj);
+            Shape::Compound { boxes } => {
+                for [min, max] in boxes {
+                    let center = [0, 1, 2].map(|axis| (min[axis] + max[axis]) * 0.5);
+                    let box_txfm = mat4x4_mul(*world_txfm, mat4x4_translate(center[0], center[1], center[2]));
+                    let half_extents = [0, 1, 2].map(|axis| (max[axis] - min[axis]) * 0.5);
+                    Self::render_shape(gl, &Shape::Box { half_extents }, &box_txfm, view_proj);
+                }
+                return;
             }
-
-            // Draw using GL_LINES for clean separate line segments
-            gl.draw_arrays(glow::LINES, 0, vertex_count);
-        }
+        };
+        let draw = DrawCall::new(shader, Primitive::Lines, vertex_count)
+            .uniform("world_txfm", Uniform::Mat4(world_txfm))
+            .uniform("viewport_txfm", Uniform::Mat4(view_proj));
+
+        // Shape-specific uniforms
+        let draw = match shape {
+            Shape::Box { half_extents } => draw.uniform("half_extents", Uniform::Vec3(half_extents)),
+            Shape::Sphere { radius } => draw.uniform("radius", Uniform::Float(*radius)),
+            Shape::Capsule { radius, height } | Shape::Cylinder { radius, height } =>
+                draw.uniform("radius", Uniform::Float(*radius)).uniform("height", Uniform::Float(*height)),
+            Shape::Heightfield { .. } | Shape::Compound { .. } => draw,
+        };
+        draw.submit(gl);
     }
 
     fn render_animated_objects(
         gl: &glow::Context,
         view_proj: &[f32; 16],
+        lighting: &Lighting,
         _selected_id: &str,
         _hovered_id: &str
     ) {
-        query!((Transform, AnimatedObject3DComponent), |_id, transform, animated_object| {
-            Self::setup_viewport_uniform(gl, view_proj, animated_object.material.shader_program);
-
-            unsafe {
-                gl.use_program(Some(animated_object.material.shader_program));
-            }
+        // Instances for the compute path, batched per asset since they share mesh and material
+        let gpu_skinning = GpuSkinningSystem::enabled(gl);
+        let mut batches: HashMap<Assets, (Mesh, Material, Vec<SkinnedInstance>)> = HashMap::new();
 
-            {
-                let animation_channels: Vec<crate::index::engine::components::AnimatedObject3D::AnimationChannel> =
-                    animated_object.animation_channels
-                        .iter()
-                        .map(
-                            |
-                                ch
-                            | crate::index::engine::components::AnimatedObject3D::AnimationChannel {
-                                target: ch.target,
-                                animation_type: match ch.animation_type {
-                                    AnimationType::Translation =>
-                                        crate::index::engine::components::AnimatedObject3D::AnimationType::Translation,
-                                    AnimationType::Rotation =>
-                                        crate::index::engine::components::AnimatedObject3D::AnimationType::Rotation,
-                                    AnimationType::Scale =>
-                                        crate::index::engine::components::AnimatedObject3D::AnimationType::Scale,
-                                },
-                                num_timesteps: ch.num_timesteps,
-                                times: ch.times.clone(),
-                                data: ch.data.clone(),
-                            }
-                        )
-                        .collect();
-
-                // Convert skeleton to the expected type
-                let mut skeleton_converted =
-                    crate::index::engine::components::AnimatedObject3D::Skeleton {
-                        nodes: animated_object.skeleton.nodes
-                            .iter()
-                            .map(|n| crate::index::engine::components::AnimatedObject3D::Node {
-                                translation: n.translation,
-                                rotation: n.rotation,
-                                scale: n.scale,
-                                parent: n.parent,
-                            })
-                            .collect(),
-                        joint_ids: animated_object.skeleton.joint_ids.clone(),
-                        joint_inverse_mats: animated_object.skeleton.joint_inverse_mats.clone(),
-                    };
-
-                animated_object.animator.update_with_data(
-                    &animation_channels[..],
-                    &mut skeleton_converted
-                );
-
-                // Copy back the updated nodes
-                for (i, node) in skeleton_converted.nodes.iter().enumerate() {
-                    if i < animated_object.skeleton.nodes.len() {
-                        animated_object.skeleton.nodes[i].translation = node.translation;
-                        animated_object.skeleton.nodes[i].rotation = node.rotation;
-                        animated_object.skeleton.nodes[i].scale = node.scale;
-                    }
-                }
+        query!((Transform, AnimatedObject3DComponent), |entity_id, transform, animated_object| {
+            if Self::is_hidden(&entity_id) {
+                continue;
             }
 
-            // Bind material (texture)
-            animated_object.material.bind(gl);
-
-            unsafe {
-                // Get world transform matrix
-                let world_txfm = transform.get_matrix();
-
-                // Bind vertex array
-                gl.bind_vertex_array(Some(animated_object.mesh.vao));
-
-                // Calculate bone matrices - simplified for now
-                let identity_matrix = [
-                    1.0, 0.0, 0.0, 0.0,
-                    0.0, 1.0, 0.0, 0.0,
-                    0.0, 0.0, 1.0, 0.0,
-                    0.0, 0.0, 0.0, 1.0,
-                ];
-                let mut bone_matrices = vec![identity_matrix; 20];
-                let mut inverse_bone_matrices = vec![identity_matrix; 20];
-
-                for (i, _joint_id) in animated_object.skeleton.joint_ids.iter().enumerate() {
-                    if i >= 20 {
-                        break;
-                    }
-                    inverse_bone_matrices[i] = animated_object.skeleton.joint_inverse_mats[i];
-                    // TODO: Re-implement node_world_txfm calculation
-                    bone_matrices[i] = identity_matrix;
-                }
+            // World transform, blended between the last two ticks
+            let world_txfm = TransformInterpolation::matrix(&entity_id, &mut transform);
+            let (bone_matrices, inverse_bone_matrices) = Self::bone_matrices(&animated_object.skeleton);
+            let morph_weights = Self::morph_weights(&animated_object.morph_weights);
 
-                // Upload world transform uniform
-                if
-                    let Some(loc) = gl.get_uniform_location(
-                        animated_object.material.shader_program,
-                        "world_txfm"
-                    )
-                {
-                    gl.uniform_matrix_4_f32_slice(Some(&loc), true, world_txfm);
-                }
+            let gpu_instance = gpu_skinning && animated_object.mesh.skin.is_some();
+            // The compute path culls against the rest-pose bounds; the vertex path doesn't cull
+            let (rest_min, rest_max) = animated_object.mesh.bounds;
+            let culled = gpu_instance && !aabb_in_frustum(&mat4x4_mul(*view_proj, world_txfm), rest_min, rest_max);
+            CullingDebugSystem::culled(&entity_id, culled);
 
-                // Upload bone matrices
-                let flat_inverse: Vec<f32> = inverse_bone_matrices
+            if gpu_instance {
+                let palette: Vec<f32> = bone_matrices
                     .iter()
-                    .flatten()
-                    .copied()
+                    .zip(&inverse_bone_matrices)
+                    .flat_map(|(bone, inverse)| mat4x4_mul(*bone, *inverse))
                     .collect();
-                let flat_bones: Vec<f32> = bone_matrices.iter().flatten().copied().collect();
-
-                if
-                    let Some(loc) = gl.get_uniform_location(
-                        animated_object.material.shader_program,
-                        "inverse_bone_matrix"
-                    )
-                {
-                    gl.uniform_matrix_4_f32_slice(Some(&loc), true, &flat_inverse);
-                }
-                if
-                    let Some(loc) = gl.get_uniform_location(
-                        animated_object.material.shader_program,
-                        "bone_matrix"
-                    )
-                {
-                    gl.uniform_matrix_4_f32_slice(Some(&loc), true, &flat_bones);
-                }
-
-                // Draw the mesh
-                gl.draw_elements(
-                    glow::TRIANGLES,
-                    animated_object.mesh.index_count as i32,
-                    glow::UNSIGNED_SHORT,
-                    0
-                );
+                batches
+                    .entry(animated_object.asset_type)
+                    .or_insert_with(|| (animated_object.mesh.clone(), animated_object.material.clone(), Vec::new()))
+                    .2.push(SkinnedInstance { world_txfm, palette, morph_weights });
+                continue;
             }
+
+            let flat_inverse: Vec<f32> = inverse_bone_matrices
+                .iter()
+                .flatten()
+                .copied()
+                .collect();
+            let flat_bones: Vec<f32> = bone_matrices.iter().flatten().copied().collect();
+
+            let draw = Self::mesh_draw(&animated_object.mesh, &animated_object.material, view_proj, lighting)
+                .uniform("world_txfm", Uniform::Mat4(&world_txfm))
+                .uniform("inverse_bone_matrix", Uniform::Mat4Array(&flat_inverse))
+                .uniform("bone_matrix", Uniform::Mat4Array(&flat_bones));
+            let draw = match &animated_object.mesh.morph {
+                Some(morph) => draw
+                    .uniform("morph_target_count", Uniform::Int(morph.count as i32))
+                    .uniform("morph_weights", Uniform::Vec4Array(&morph_weights))
+                    .uniform("morph_texture", Uniform::Int(MORPH_UNIT as i32))
+                    .texture(MORPH_UNIT, morph.texture),
+                None => draw.uniform("morph_target_count", Uniform::Int(0)),
+            };
+            draw.submit(gl);
         });
+
+        for (asset, (mesh, material, instances)) in batches {
+            GpuSkinningSystem::draw(gl, asset, &mesh, &material, &instances, view_proj, lighting);
+        }
     }
 
-    fn render_static_objects(
-        gl: &glow::Context,
-        view_proj: &[f32; 16],
-        selected_id: &str,
-        hovered_id: &str
-    ) {
-        query!((Transform, StaticObject3DComponent), |entity_id, transform, static_object| {
-            // TODO: Re-implement outline rendering when get_static_outline_shader is available
-            let _outline_color = Self::get_outline_info(&entity_id, selected_id, hovered_id);
+    /// An object's morph weights padded to MAX_MORPH_TARGETS, as both skinning paths take them
+    pub fn morph_weights(weights: &[f32]) -> [f32; MAX_MORPH_TARGETS] {
+        let mut padded = [0.0; MAX_MORPH_TARGETS];
+        for (slot, weight) in padded.iter_mut().zip(weights) {
+            *slot = *weight;
+        }
+        padded
+    }
 
-            // PASS 2: Render normal object
-            Self::setup_viewport_uniform(gl, view_proj, static_object.material.shader_program);
+    /// Bone and inverse bind matrices of the first MAX_JOINTS joints, identity for the rest
+    pub fn bone_matrices(skeleton: &Skeleton) -> (Vec<[f32; 16]>, Vec<[f32; 16]>) {
+        // Calculate bone matrices - simplified for now
+        let identity_matrix = mat4x4_identity();
+        let mut bone_matrices = vec![identity_matrix; MAX_JOINTS];
+        let mut inverse_bone_matrices = vec![identity_matrix; MAX_JOINTS];
 
-            // Use normal shader
-            unsafe {
-                gl.use_program(Some(static_object.material.shader_program));
+        for (i, _joint_id) in skeleton.joint_ids.iter().enumerate() {
+            if i >= MAX_JOINTS {
+                break;
             }
+            inverse_bone_matrices[i] = skeleton.joint_inverse_mats[i];
+            // TODO: Re-implement node_world_txfm calculation
+            bone_matrices[i] = identity_matrix;
+        }
+        (bone_matrices, inverse_bone_matrices)
+    }
 
-            // Bind material (texture)
-            static_object.material.bind(gl);
-
-            unsafe {
-                let world_txfm = transform.get_matrix();
-
-                // Bind vertex array
-                gl.bind_vertex_array(Some(static_object.mesh.vao));
-
-                // Upload world transform uniform
-                if
-                    let Some(loc) = gl.get_uniform_location(
-                        static_object.material.shader_program,
-                        "world_txfm"
-                    )
-                {
-                    gl.uniform_matrix_4_f32_slice(Some(&loc), true, world_txfm);
-                }
+    /// Opaque static meshes inside the frustum; `depth` is LessEqual after a depth pre-pass so they pass against
+    /// their own depth
+    fn render_static_objects(gl: &glow::Context, view_proj: &[f32; 16], lighting: &Lighting, depth: DepthCompare) {
+        let camera_position = view_projection_eye(view_proj);
+        query!((Transform, StaticObject3DComponent, Option<Metadata>), |entity_id, transform, static_object, metadata| {
+            // Blended materials are drawn by TransparencySystem once everything opaque is there
+            let replaced = ImpostorSystem::replaces(&entity_id);
+            if Self::hidden_by(metadata.as_ref()) || static_object.material.transparent || replaced {
+                continue;
+            }
 
-                // Draw the mesh (normal rendering)
-                gl.draw_elements(
-                    glow::TRIANGLES,
-                    static_object.mesh.index_count as i32,
-                    glow::UNSIGNED_SHORT,
-                    0
-                );
+            let world_txfm = TransformInterpolation::matrix(&entity_id, &mut transform);
+            let (local_min, local_max) = static_object.mesh.bounds;
+            let culled = !aabb_in_frustum(&mat4x4_mul(*view_proj, world_txfm), local_min, local_max);
+            CullingDebugSystem::culled(&entity_id, culled);
+            if culled {
+                continue;
             }
+            let (min, max) = static_object.mesh.world_bounds(&world_txfm);
+            let probe = ReflectionProbeSystem::probe_at([(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5, (min[2] + max[2]) * 0.5]);
+            // A user material swaps in its pipeline and parameters; the mesh keeps its texture and the scene uniforms
+            let custom_material = MaterialLibrary::material_of(&entity_id);
+            let material = match &custom_material {
+                Some((_, definition)) => Material { pipeline: definition.pipeline, ..static_object.material.clone() },
+                None => static_object.material.clone(),
+            };
+            // Same LOD as the depth prepass drew, which this pass depth tests against
+            let mesh = static_object.mesh.lod_at(&world_txfm, camera_position);
+            let draw = Self::mesh_draw(mesh, &material, view_proj, lighting)
+                .depth(depth)
+                .uniform("world_txfm", Uniform::Mat4(&world_txfm));
+            let draw = match &custom_material {
+                Some((custom_material, definition)) =>
+                    MaterialLibrary::bind(draw, custom_material, definition, camera_position.as_ref()),
+                None => draw,
+            };
+            let draw = AmbientOcclusionSystem::bind(draw, &entity_id, mesh.vertex_count);
+            ReflectionProbeSystem::bind(draw, probe.as_ref(), camera_position.as_ref(), &static_object.material).submit(gl);
         });
     }
 
-    fn setup_viewport_uniform(
+    /// Draw an outline around the mesh `mesh_draw` builds: an outline shader draw with its transforms and the
+    /// viewport size set
+    fn render_outline<'a>(
         gl: &glow::Context,
-        viewport_txfm: &[f32; 16],
-        shader_program: glow::Program
+        mesh_draw: impl Fn() -> DrawCall<'a>,
+        outline_color: [f32; 3],
+        outline: &OutlineSettings
     ) {
-        unsafe {
-            gl.use_program(Some(shader_program));
+        let occluded_color = outline_color.map(|channel| channel * outline.occluded_brightness);
+        let draw = |width: f32, color: &[f32; 3], depth: DepthCompare| {
+            mesh_draw()
+                .depth(depth)
+                .uniform("outline_width", Uniform::Float(width))
+                .uniform("outline_color", Uniform::Vec3(color))
+                .submit(gl);
+        };
 
-            if let Some(loc) = gl.get_uniform_location(shader_program, "viewport_txfm") {
-                gl.uniform_matrix_4_f32_slice(Some(&loc), true, viewport_txfm);
+        // Stencil state isn't part of the RHI yet
+        unsafe {
+            // Mark the whole silhouette in the stencil buffer, occluded or not
+            gl.enable(glow::STENCIL_TEST);
+            gl.stencil_mask(0xff);
+            gl.clear_stencil(0);
+            gl.clear(glow::STENCIL_BUFFER_BIT);
+            gl.stencil_func(glow::ALWAYS, 1, 0xff);
+            gl.stencil_op(glow::KEEP, glow::KEEP, glow::REPLACE);
+            gl.color_mask(false, false, false, false);
+            gl.depth_mask(false);
+            draw(0.0, &outline_color, DepthCompare::Always);
+
+            // The enlarged mesh outside the silhouette is the outline: full color where it is in front of
+            // the scene, dimmed where geometry covers it
+            gl.color_mask(true, true, true, true);
+            gl.stencil_func(glow::NOTEQUAL, 1, 0xff);
+            gl.stencil_mask(0x00);
+            draw(outline.width, &outline_color, DepthCompare::LessEqual);
+            if outline.occluded_brightness > 0.0 {
+                draw(outline.width, &occluded_color, DepthCompare::Greater);
             }
 
-            if let Some(loc) = gl.get_uniform_location(shader_program, "baseColorTexture") {
-                gl.uniform_1_i32(Some(&loc), 0);
+            gl.depth_mask(true);
+            gl.stencil_mask(0xff);
+            gl.disable(glow::STENCIL_TEST);
+        }
+    }
+
+    /// Wireframe AABB, dimmed where geometry covers it like the outlines
+    pub fn render_bounds_box(
+        gl: &glow::Context,
+        view_proj: &[f32; 16],
+        (min, max): ([f32; 3], [f32; 3]),
+        color: [f32; 3],
+        occluded_brightness: f32
+    ) {
+        let corner = |i: usize| [
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        ];
+        // Each edge joins two corners that differ in one axis bit
+        let mut vertices: Vec<f32> = Vec::with_capacity(12 * 6);
+        for i in 0..8 {
+            for bit in [1, 2, 4] {
+                if i & bit == 0 {
+                    vertices.extend_from_slice(&corner(i));
+                    vertices.extend_from_slice(&corner(i | bit));
+                }
             }
-            if let Some(loc) = gl.get_uniform_location(shader_program, "hasTexture") {
-                gl.uniform_1_i32(Some(&loc), 1);
+        }
+        let occluded_color = color.map(|channel| channel * occluded_brightness);
+        let identity = mat4x4_identity();
+
+        let Ok(buffer) = Buffer::vertex(gl, &vertices, BufferUsage::Stream) else {
+            return;
+        };
+        let Ok(geometry) = Geometry::new(gl, &[VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)], None) else {
+            buffer.delete(gl);
+            return;
+        };
+        for (depth, color) in [(DepthCompare::LessEqual, &color), (DepthCompare::Greater, &occluded_color)] {
+            if depth == DepthCompare::Greater && occluded_brightness <= 0.0 {
+                continue;
             }
+            DrawCall::new(get_wireframe_shader(), Primitive::Lines, vertices.len() / 3)
+                .geometry(geometry)
+                .depth(depth)
+                .uniform("world_txfm", Uniform::Mat4(&identity))
+                .uniform("viewport_txfm", Uniform::Mat4(view_proj))
+                .uniform("line_color", Uniform::Vec3(color))
+                .submit(gl);
+        }
+        geometry.delete(gl);
+        buffer.delete(gl);
+    }
+
+    /// Draw of a lit mesh with its material; the caller adds the world transform (and bones)
+    pub fn mesh_draw<'a>(mesh: &Mesh, material: &Material, viewport_txfm: &'a [f32; 16], lighting: &'a Lighting) -> DrawCall<'a> {
+        let draw = DrawCall::new(material.pipeline, Primitive::Triangles, mesh.index_count)
+            .geometry(mesh.geometry)
+            .uniform("viewport_txfm", Uniform::Mat4(viewport_txfm))
+            .uniform("baseColorTexture", Uniform::Int(0))
+            .uniform("hasTexture", Uniform::Int(material.base_color_texture.is_some() as i32))
+            .uniform("hasVertexColors", Uniform::Int(mesh.vertex_colors as i32))
+            .uniform("light_direction", Uniform::Vec3(&lighting.light_direction))
+            .uniform("light_color", Uniform::Vec3(&lighting.light_color))
+            .uniform("ambient_strength", Uniform::Float(lighting.ambient))
+            .uniform("sky_color", Uniform::Vec3(&lighting.sky_color))
+            .uniform("fog_density", Uniform::Float(lighting.fog_density));
+        let draw = LightClusterSystem::bind(draw);
+        match material.base_color_texture {
+            Some(texture) => draw.texture(0, texture),
+            None => draw,
         }
     }
 }
@@ -0,0 +1,247 @@
+use std::sync::RwLock;
+
+use crate::index::engine::components::{ ColliderLayer, Metadata, SystemTrait, Transform };
+use crate::index::engine::modules::event_system::{ Event, EventType };
+use crate::index::engine::modules::keyboard_input_system::PointerEvent;
+use crate::index::engine::modules::{ EntityId, InterfaceSystem, Random };
+use crate::index::engine::managers::PrefabLibrary;
+use crate::index::game::entities::prefabs::{ prefab_names, spawn_prefab };
+use crate::index::game::systems::physics_system::PhysicsSystem;
+use crate::index::game::systems::RenderSystem;
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ query_by_id, query_get_all };
+
+/// How far the cursor ray looks for a surface to paint on
+const BRUSH_REACH: f32 = 500.0;
+/// Placement tries per drag tick; misses (steep or empty ground, crowded spots) just wait for the next tick
+const ATTEMPTS_PER_TICK: usize = 8;
+/// Metadata tag prefix of scattered pieces, followed by the prefab name; the eraser only removes tagged pieces
+const SCATTER_TAG: &str = "scatter:";
+
+/// What dragging with the scatter brush does
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScatterMode {
+    Paint,
+    Erase,
+}
+
+impl ScatterMode {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "paint" => Some(ScatterMode::Paint),
+            "erase" => Some(ScatterMode::Erase),
+            _ => None,
+        }
+    }
+}
+
+/// Scatter brush settings, edited in the Scatter panel (shown there as ScatterSettings)
+#[derive(Clone, Debug)]
+pub struct ScatterBrush {
+    pub mode: Option<ScatterMode>,  // None: the brush is off and drags select and move entities
+    pub prefabs: Vec<String>,       // Picked at random for each piece
+    pub radius: f32,
+    pub density: f32,               // Pieces per square unit the brush fills its disc to
+    pub rotation_jitter: f32,       // Largest random turn about the vertical axis, in degrees
+    pub scale_jitter: f32,          // Largest random scale change, as a fraction of the prefab's scale
+    pub max_slope: f32,             // Steepest surface pieces land on, in degrees from flat
+}
+
+static BRUSH: RwLock<ScatterBrush> = RwLock::new(ScatterBrush {
+    mode: None,
+    prefabs: Vec::new(),
+    radius: 4.0,
+    density: 0.1,
+    rotation_jitter: 180.0,
+    scale_jitter: 0.2,
+    max_slope: 30.0,
+});
+
+/// Dresses levels with props: painting drops random picks of the chosen prefabs onto the surfaces under the brush
+/// until its disc holds the set density, with random turns and scales; erasing removes scattered pieces under it
+#[derive(Debug)]
+pub struct ScatterBrushSystem;
+
+impl ScatterBrushSystem {
+    pub fn settings() -> ScatterBrush {
+        BRUSH.read().unwrap().clone()
+    }
+
+    pub fn set_mode(mode: Option<ScatterMode>) {
+        BRUSH.write().unwrap().mode = mode;
+    }
+
+    /// Add a prefab to the brush's picks, or remove it if already picked
+    pub fn toggle_prefab(name: &str) -> Result<(), String> {
+        let names = prefab_names();
+        if !names.iter().any(|prefab| prefab == name) {
+            return Err(format!("Unknown prefab: {} (expected one of {:?})", name, names));
+        }
+        let mut settings = BRUSH.write().unwrap();
+        if let Some(index) = settings.prefabs.iter().position(|picked| picked == name) {
+            settings.prefabs.remove(index);
+        } else {
+            settings.prefabs.push(name.to_string());
+        }
+        Ok(())
+    }
+
+    /// Change one numeric setting by its field name, clamped to a usable range
+    pub fn set_option(name: &str, value: f32) -> Result<(), String> {
+        let mut settings = BRUSH.write().unwrap();
+        match name {
+            "radius" => settings.radius = value.clamp(0.5, 50.0),
+            "density" => settings.density = value.clamp(0.005, 4.0),
+            "rotation_jitter" => settings.rotation_jitter = value.clamp(0.0, 180.0),
+            "scale_jitter" => settings.scale_jitter = value.clamp(0.0, 0.9),
+            "max_slope" => settings.max_slope = value.clamp(0.0, 90.0),
+            _ => return Err(format!("Unknown scatter setting: {}", name)),
+        }
+        Ok(())
+    }
+
+    /// Whether the scatter brush owns viewport drags
+    pub fn active() -> bool {
+        BRUSH.read().unwrap().mode.is_some()
+    }
+
+    fn drag(pointer: &PointerEvent) {
+        let settings = Self::settings();
+        let Some(mode) = settings.mode else {
+            return;
+        };
+        let Some((origin, direction)) = RenderSystem::camera_ray(pointer.position, pointer.viewport[0], pointer.viewport[1]) else {
+            return;
+        };
+        let pieces = Self::pieces();
+        let Some((center, _)) = Self::surface_hit(origin, direction, BRUSH_REACH, &pieces) else {
+            return;
+        };
+        let in_brush: Vec<&(EntityId, [f32; 3], bool)> = pieces
+            .iter()
+            .filter(|(_, position, _)| horizontal_distance(*position, center) <= settings.radius)
+            .collect();
+
+        match mode {
+            ScatterMode::Erase => {
+                for (entity_id, _, locked) in in_brush {
+                    if !locked {
+                        PrefabLibrary::delete_instance(entity_id);
+                    }
+                }
+            }
+            ScatterMode::Paint => {
+                if settings.prefabs.is_empty() {
+                    return;
+                }
+                let area = std::f32::consts::PI * settings.radius * settings.radius;
+                let wanted = (area * settings.density).round() as usize;
+                // Half the mean spacing keeps pieces from landing in each other while still looking random
+                let spacing = 0.5 / settings.density.sqrt();
+                let mut positions: Vec<[f32; 3]> = in_brush.iter().map(|(_, position, _)| *position).collect();
+                for _ in 0..ATTEMPTS_PER_TICK {
+                    if positions.len() >= wanted {
+                        break;
+                    }
+                    if let Some(position) = Self::scatter_one(&settings, center, &positions, spacing, &pieces) {
+                        positions.push(position);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Try one random spot in the brush disc: drop a ray onto it and place a random pick if the surface is flat
+    /// enough and no piece is closer than `spacing`
+    fn scatter_one(
+        settings: &ScatterBrush,
+        center: [f32; 3],
+        positions: &[[f32; 3]],
+        spacing: f32,
+        pieces: &[(EntityId, [f32; 3], bool)]
+    ) -> Option<[f32; 3]> {
+        let (angle, distance, pick, yaw, scale) = Random::with_stream("scatter", |rng| {
+            (
+                rng.range(0.0, std::f32::consts::TAU),
+                // Square root spreads samples evenly over the disc's area
+                rng.next_f32().sqrt() * settings.radius,
+                (rng.next_u64() % settings.prefabs.len() as u64) as usize,
+                rng.range(-settings.rotation_jitter, settings.rotation_jitter).to_radians(),
+                1.0 + rng.range(-settings.scale_jitter, settings.scale_jitter),
+            )
+        });
+        let x = center[0] + angle.cos() * distance;
+        let z = center[2] + angle.sin() * distance;
+        if positions.iter().any(|position| horizontal_distance(*position, [x, 0.0, z]) < spacing) {
+            return None;
+        }
+
+        // Drop from above the brush so hills and ledges inside the disc are found
+        let drop = settings.radius * 2.0;
+        let (position, normal) = Self::surface_hit([x, center[1] + drop, z], [0.0, -1.0, 0.0], drop * 2.0, pieces)?;
+        if normal[1].clamp(-1.0, 1.0).acos().to_degrees() > settings.max_slope {
+            return None;
+        }
+
+        let prefab = &settings.prefabs[pick];
+        let entity_id = spawn_prefab(prefab)?;
+        PrefabLibrary::place(&entity_id, position, yaw, scale);
+        query_by_id!(entity_id.clone(), (Metadata), |metadata| {
+            metadata.tags.push(format!("{}{}", SCATTER_TAG, prefab));
+        });
+        Some(position)
+    }
+
+    /// Scattered pieces with their positions and whether each is locked
+    fn pieces() -> Vec<(EntityId, [f32; 3], bool)> {
+        query_get_all!(Metadata, Transform)
+            .into_iter()
+            .filter(|(_, metadata, _)| metadata.tags.iter().any(|tag| tag.starts_with(SCATTER_TAG)))
+            .map(|(entity_id, metadata, transform)| (entity_id, transform.get_position(), metadata.locked))
+            .collect()
+    }
+
+    /// First environment surface along a ray and its normal, looking through scattered pieces so they don't pile
+    /// up on each other
+    fn surface_hit(
+        origin: [f32; 3],
+        direction: [f32; 3],
+        reach: f32,
+        pieces: &[(EntityId, [f32; 3], bool)]
+    ) -> Option<([f32; 3], [f32; 3])> {
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone().unwrap_or_default();
+        let ignore: Vec<&EntityId> = std::iter::once(&player_id)
+            .chain(pieces.iter().map(|(entity_id, _, _)| entity_id))
+            .collect();
+        let hit = PhysicsSystem::raycast(origin, direction, reach, &[ColliderLayer::Environment], &ignore)?;
+        Some((hit.point, hit.normal))
+    }
+
+    /// End a stroke: show the new or erased pieces in the outliner
+    fn release() {
+        if Self::active() {
+            InterfaceSystem::update_entities_list();
+        }
+    }
+}
+
+fn horizontal_distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    (a[0] - b[0]).hypot(a[2] - b[2])
+}
+
+impl SystemTrait for ScatterBrushSystem {
+    /// While the brush is on, drags paint or erase and aren't passed on to moving or box-selecting entities
+    fn event(&self, event: &Event) {
+        if !Self::active() || InterfaceSystem::is_play_mode() {
+            return;
+        }
+        if event.event_type == EventType::PointerRelease {
+            Self::release();
+            return;
+        }
+        event.consume();
+        if let Some(pointer) = event.payload.downcast_ref::<PointerEvent>() {
+            Self::drag(pointer);
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use glow::HasContext;
+
+use crate::index::engine::components::Lighting;
+use crate::index::engine::modules::engine_error::EngineError;
+use crate::index::engine::modules::scene_library::THUMBNAIL_SIZE;
+use crate::index::engine::modules::{ InterfaceSystem, Project, SceneLibrary, TaskSystem };
+use crate::index::game::systems::{ LightClusterSystem, RenderSystem, TerrainSystem };
+
+/// Renders the thumbnail a scene save asks for from the editor camera; a task encodes it next to the scene
+pub struct SceneThumbnailSystem;
+
+impl SceneThumbnailSystem {
+    pub fn render(gl: &glow::Context, _width: u32, _height: u32) {
+        let Some(path) = SceneLibrary::take_thumbnail_request() else {
+            return;
+        };
+        let [width, height] = THUMBNAIL_SIZE;
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let lighting = RenderSystem::current_lighting();
+
+        let pixels = match unsafe { Self::capture(gl, &view_proj, &lighting) } {
+            Ok(pixels) => pixels,
+            Err(e) => {
+                InterfaceSystem::report_error(&EngineError::Gpu { message: format!("Scene thumbnail: {}", e) });
+                return;
+            }
+        };
+
+        // PNG encoding and the write happen on a task worker; only the readback needs this thread
+        let encode_path = path.clone();
+        TaskSystem::spawn(
+            &format!("encode {}", path),
+            move || {
+                // GL rows start at the bottom, images at the top
+                let row_size = (width * 4) as usize;
+                let flipped: Vec<u8> = pixels.chunks_exact(row_size).rev().flatten().copied().collect();
+                image::save_buffer(&encode_path, &flipped, width, height, image::ColorType::Rgba8)
+                    .map_err(|e| EngineError::io(&encode_path, e.to_string()))
+            },
+            move |result, _gl| {
+                match result {
+                    Ok(()) => println!("🖼️ Saved scene thumbnail {}", path),
+                    Err(e) => InterfaceSystem::report_error(&e),
+                }
+            }
+        );
+    }
+
+    /// Draw the scene into a temporary framebuffer and read it back; saves are rare, so nothing is kept
+    unsafe fn capture(gl: &glow::Context, view_proj: &[f32; 16], lighting: &Lighting) -> Result<Vec<u8>, String> {
+        let [width, height] = THUMBNAIL_SIZE;
+        let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        let mut previous_viewport = [0i32; 4];
+        gl.get_parameter_i32_slice(glow::VIEWPORT, &mut previous_viewport);
+
+        let framebuffer = gl.create_framebuffer()?;
+        let color = gl.create_renderbuffer()?;
+        let depth = gl.create_renderbuffer()?;
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(color));
+        gl.renderbuffer_storage(glow::RENDERBUFFER, glow::RGBA8, width as i32, height as i32);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+        gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH_COMPONENT24, width as i32, height as i32);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::RENDERBUFFER, Some(color));
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_ATTACHMENT, glow::RENDERBUFFER, Some(depth));
+
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        if status == glow::FRAMEBUFFER_COMPLETE {
+            gl.viewport(0, 0, width as i32, height as i32);
+            let [sky_r, sky_g, sky_b] = lighting.sky_color;
+            gl.clear_color(sky_r, sky_g, sky_b, 1.0);
+            gl.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+
+            // Thumbnails show the scene as played, without editor-only layers
+            RenderSystem::with_layer_mask(!Project::editor_only_layers(), || {
+                LightClusterSystem::build(gl, view_proj, width, height);
+                RenderSystem::render_meshes(gl, view_proj, lighting);
+                TerrainSystem::draw(gl, view_proj, lighting);
+            });
+
+            gl.read_pixels(
+                0,
+                0,
+                width as i32,
+                height as i32,
+                glow::RGBA,
+                glow::UNSIGNED_BYTE,
+                glow::PixelPackData::Slice(Some(&mut pixels))
+            );
+        }
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+        gl.viewport(previous_viewport[0], previous_viewport[1], previous_viewport[2], previous_viewport[3]);
+        gl.bind_vertex_array(None);
+        gl.delete_framebuffer(framebuffer);
+        gl.delete_renderbuffer(color);
+        gl.delete_renderbuffer(depth);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            return Err(format!("framebuffer incomplete (status 0x{:x})", status));
+        }
+        Ok(pixels)
+    }
+}
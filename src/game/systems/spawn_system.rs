@@ -0,0 +1,113 @@
+use crate::index::engine::components::{ SpawnMode, Spawner, Transform };
+use crate::index::engine::modules::{ entity_exists, is_enabled, EntityId, EntityPool, InterfaceSystem, Random };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::engine::utils::math::dist2;
+use crate::index::engine::managers::PrefabLibrary;
+use crate::index::game::entities::prefabs::{ prefab_names, spawn_prefab };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query, query_by_id };
+
+/// Processes Spawner components: timers, triggers and alive limits
+pub struct SpawnSystem;
+
+impl SpawnSystem {
+    /// Request one spawn from a Triggered spawner (e.g. from a gameplay event)
+    #[allow(dead_code)]
+    pub fn trigger(spawner_id: &EntityId) {
+        query_by_id!(spawner_id, (Spawner), |spawner| {
+            spawner.pending_triggers += 1;
+        });
+    }
+
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        let player_position = PLAYER_ENTITY_ID.read()
+            .unwrap()
+            .as_ref()
+            .and_then(|player_id| get_query_by_id!(player_id, (Transform)))
+            .map(|transform| transform.get_position());
+        let mut spawned_any = false;
+
+        query!((Transform, Spawner), |entity_id, transform, spawner| {
+            if !is_enabled(&entity_id) {
+                continue;
+            }
+            spawned_any |= Self::tick(&entity_id, &transform, &mut spawner, player_position, delta_time);
+        });
+
+        if spawned_any {
+            InterfaceSystem::update_entities_list();
+        }
+    }
+
+    /// Advance one spawner's timer or trigger and spawn its due prefabs; returns whether anything spawned
+    fn tick(entity_id: &EntityId, transform: &Transform, spawner: &mut Spawner, player_position: Option<[f32; 3]>, delta_time: f32) -> bool {
+        // Forget spawned entities that were despawned since the last tick
+        spawner.alive.retain(|id| entity_exists(id));
+
+        if !spawner.enabled {
+            return false;
+        }
+
+        let free_slots = spawner.max_alive.saturating_sub(spawner.alive.len() as u32);
+        let requested = match spawner.mode.clone() {
+            SpawnMode::Interval { seconds } => {
+                spawner.timer += delta_time;
+                if spawner.timer >= seconds && free_slots > 0 {
+                    spawner.timer = 0.0;
+                    1
+                } else {
+                    0
+                }
+            }
+            SpawnMode::OneShot => {
+                if spawner.has_fired {
+                    0
+                } else {
+                    spawner.has_fired = true;
+                    free_slots
+                }
+            }
+            SpawnMode::Triggered { radius } => {
+                let inside = player_position
+                    .is_some_and(|player| dist2(transform.get_position(), player) <= radius * radius);
+                let entered = inside && !spawner.has_fired;
+                spawner.has_fired = inside;
+                let triggers = spawner.pending_triggers + entered as u32;
+                spawner.pending_triggers = 0;
+                triggers
+            }
+        };
+
+        let count = requested.min(free_slots);
+        if count == 0 {
+            return false;
+        }
+        if spawner.rng.is_none() {
+            spawner.rng = Some(Random::entity_stream("spawn", entity_id));
+        }
+
+        // Pooled spawns come back from the pool once something despawns them with EntityPool::despawn
+        if spawner.pool_size > 0 {
+            EntityPool::designate(&spawner.prefab, spawner.pool_size as usize);
+        }
+
+        let origin = transform.get_position();
+        let mut spawned_any = false;
+        for _ in 0..count {
+            let Some(spawned_id) = EntityPool::acquire(&spawner.prefab, || spawn_prefab(&spawner.prefab)) else {
+                println!("⚠️ Spawner {} references unknown prefab '{}' (expected one of {:?})",
+                    entity_id, spawner.prefab, prefab_names());
+                spawner.enabled = false;
+                break;
+            };
+
+            let offset = spawner.sample_offset();
+            PrefabLibrary::place(&spawned_id, [0, 1, 2].map(|axis| origin[axis] + offset[axis]), 0.0, 1.0);
+            println!("🥚 Spawner {} spawned {} ({})", entity_id, spawner.prefab, spawned_id);
+            spawner.alive.push(spawned_id);
+            spawned_any = true;
+        }
+        spawned_any
+    }
+}
@@ -0,0 +1,573 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{ Arc, RwLock };
+use std::time::Instant;
+
+use crate::index::engine::components::{
+    CameraComponent,
+    EntityCategory,
+    KinematicMover,
+    Metadata,
+    Spline,
+    SplinePoint,
+    SplineProfile,
+    StaticObject3DComponent,
+    SystemTrait,
+    Transform,
+    LAYER_GIZMOS,
+};
+use crate::index::engine::components::SharedComponents::Mesh;
+use crate::index::engine::managers::assets_manager::{ get_static_object_copy, get_wireframe_shader, Assets };
+use crate::index::engine::modules::ecs::insert;
+use crate::index::engine::modules::event_system::{ Event, EventType };
+use crate::index::engine::modules::keyboard_input_system::PointerEvent;
+use crate::index::engine::modules::{ spawn, EntityId, InterfaceSystem, Selection };
+use crate::index::engine::rhi::{
+    Buffer,
+    BufferKind,
+    BufferUsage,
+    DepthCompare,
+    DrawCall,
+    Geometry,
+    Primitive,
+    Uniform,
+    VertexAttribute,
+    VertexFormat,
+};
+use crate::index::engine::utils::gltf_loader_utils::upload_mesh;
+use crate::index::engine::utils::math::{ dist2, len2, normalize, ray_plane, Vec3 };
+use crate::index::engine::utils::mat4x4_identity;
+use crate::index::game::systems::RenderSystem;
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, insert_many, query_by_id, query_get_all };
+
+/// Size of the point and handle markers as a fraction of the camera distance
+const MARKER_SCALE: f32 = 0.012;
+/// How close a click has to pass to a point or handle, as a fraction of the camera distance
+const PICK_TOLERANCE: f32 = 0.02;
+/// Grid a dragged point or handle snaps to with V held, in world units
+const DRAG_SNAP: f32 = 0.5;
+/// Straight pieces each segment is drawn with
+const CURVE_SEGMENTS: usize = 24;
+/// Length of a new spline, and of the segment `spline add` appends
+const NEW_SEGMENT_LENGTH: f32 = 4.0;
+/// How far in front of the camera `spline new` puts a spline
+const NEW_SPLINE_DISTANCE: f32 = 10.0;
+const CURVE_COLOR: [f32; 3] = [0.3, 0.7, 1.0];
+const SELECTED_CURVE_COLOR: [f32; 3] = [0.6, 0.9, 1.0];
+const HANDLE_COLOR: [f32; 3] = [0.85, 0.85, 0.85];
+/// Color of the point or handle being dragged
+const ACTIVE_COLOR: [f32; 3] = [1.0, 0.9, 0.1];
+
+/// Part of a control point a drag moves
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Grip {
+    Point,
+    InHandle,
+    OutHandle,
+}
+
+/// Point or handle being dragged, in the plane facing the camera through where it was grabbed
+struct SplineDrag {
+    entity_id: EntityId,
+    point: usize,
+    grip: Grip,
+    offset: Vec3, // From the press on the plane to the grip
+    normal: Vec3,
+    moved: bool,
+}
+
+enum DragState {
+    Idle,
+    Ignored, // The press didn't land on a point or handle; wait for the release
+    Dragging(SplineDrag),
+}
+
+/// Editor camera flying along a spline over `duration` seconds
+struct CameraPath {
+    entity_id: EntityId,
+    started: Instant,
+    duration: f32,
+}
+
+static DRAG: RwLock<DragState> = RwLock::new(DragState::Idle);
+static CAMERA_PATH: RwLock<Option<CameraPath>> = RwLock::new(None);
+
+thread_local! {
+    // Spline each entity's mesh was last swept from, and the mesh
+    static BUILT: RefCell<HashMap<EntityId, (Spline, Mesh)>> = RefCell::new(HashMap::new());
+}
+
+/// Splines in the editor: draws every curve on the gizmo layer with the selected one's points and handles, which
+/// are dragged in the viewport (V snaps them to a grid), sweeps profiled splines into meshes once a drag ends, and
+/// flies the editor camera along a spline for `spline camera`
+#[derive(Debug)]
+pub struct SplineSystem;
+
+impl SplineSystem {
+    /// Render system: advance the camera path and rebuild the meshes of changed splines; runs before the meshes
+    /// are drawn
+    pub fn update(gl: &glow::Context, _width: u32, _height: u32) {
+        Self::fly_camera();
+
+        let dragged = match &*DRAG.read().unwrap() {
+            DragState::Dragging(drag) => Some(drag.entity_id.clone()),
+            _ => None,
+        };
+        for (entity_id, spline) in query_get_all!(Spline) {
+            // Swept once the drag ends rather than on every move
+            if dragged.as_ref() == Some(&entity_id) {
+                continue;
+            }
+            let built = BUILT.with(|built| built.borrow().get(&entity_id).cloned());
+            let mesh = match built {
+                Some((built_spline, mesh)) if built_spline == spline => mesh,
+                // A failed build is kept too, so it's reported once
+                _ => {
+                    let mesh = upload_mesh(gl, &spline.mesh(), None, "Spline").unwrap_or_else(|error| {
+                        InterfaceSystem::report_error(&error);
+                        Mesh::new()
+                    });
+                    BUILT.with(|built| built.borrow_mut().insert(entity_id.clone(), (spline.clone(), mesh.clone())));
+                    mesh
+                }
+            };
+            Self::sync(&entity_id, mesh);
+        }
+    }
+
+    /// Give the entity the built mesh unless it has it already; a spline without a mesh (no profile) gets one
+    /// only to replace an earlier extrusion
+    fn sync(entity_id: &EntityId, mesh: Mesh) {
+        let object = get_query_by_id!(entity_id.clone(), (StaticObject3DComponent));
+        let has_mesh = object
+            .as_ref()
+            .is_some_and(|object| {
+                matches!((&object.mesh.data, &mesh.data), (Some(current), Some(built)) if Arc::ptr_eq(current, built))
+            });
+        if has_mesh || mesh.data.is_none() || (object.is_none() && mesh.index_count == 0) {
+            return;
+        }
+        let object = object.unwrap_or_else(|| get_static_object_copy(Assets::BlockoutPlatform));
+        insert(entity_id, StaticObject3DComponent { mesh, ..object });
+    }
+
+    fn fly_camera() {
+        let mut camera_path = CAMERA_PATH.write().unwrap();
+        let Some(path) = camera_path.as_ref() else {
+            return;
+        };
+        let (Some(spline), Some(transform), Some(player_id)) = (
+            get_query_by_id!(path.entity_id.clone(), (Spline)),
+            get_query_by_id!(path.entity_id.clone(), (Transform)),
+            PLAYER_ENTITY_ID.read().unwrap().clone(),
+        ) else {
+            *camera_path = None;
+            return;
+        };
+
+        let t = (path.started.elapsed().as_secs_f32() / path.duration).min(1.0);
+        let Some(sample) = spline.world_sample(&transform, spline.length() * t) else {
+            *camera_path = None;
+            return;
+        };
+        let position = sample.position;
+        query_by_id!(player_id.clone(), (Transform), |player_transform| {
+            player_transform.set_position(position[0], position[1], position[2]);
+        });
+        // The view looks down -forward, see RenderSystem::basis_of
+        let direction = sample.tangent;
+        query_by_id!(player_id, (CameraComponent), |camera| {
+            camera.set_view_angles((-direction[1]).clamp(-1.0, 1.0).asin(), direction[0].atan2(-direction[2]));
+        });
+        if t >= 1.0 {
+            *camera_path = None;
+        }
+    }
+
+    /// Render system: the curves of visible splines and the selected spline's points and handles, over the scene
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        if InterfaceSystem::is_play_mode() || !RenderSystem::layer_rendered(LAYER_GIZMOS) {
+            return;
+        }
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let (selected_id, _) = InterfaceSystem::get_selection_state();
+        let active = match &*DRAG.read().unwrap() {
+            DragState::Dragging(drag) => Some((drag.point, drag.grip)),
+            _ => None,
+        };
+
+        let mut batches: Vec<([f32; 3], Vec<[Vec3; 2]>)> = vec![
+            (CURVE_COLOR, Vec::new()),
+            (SELECTED_CURVE_COLOR, Vec::new()),
+            (HANDLE_COLOR, Vec::new()),
+            (ACTIVE_COLOR, Vec::new()),
+        ];
+        for (entity_id, spline) in query_get_all!(Spline) {
+            if get_query_by_id!(entity_id.clone(), (Metadata)).is_some_and(|metadata| !metadata.is_visible) {
+                continue;
+            }
+            let Some(transform) = get_query_by_id!(entity_id.clone(), (Transform)) else {
+                continue;
+            };
+            let matrix = transform.compute_matrix();
+            let to_world = |local: Vec3| transform_point(&matrix, local);
+            let selected = entity_id == selected_id;
+
+            let curve = &mut batches[usize::from(selected)].1;
+            for segment in 0..spline.segment_count() {
+                let point = |i: usize| to_world(spline.point(segment, i as f32 / CURVE_SEGMENTS as f32));
+                curve.extend((0..CURVE_SEGMENTS).map(|i| [point(i), point(i + 1)]));
+            }
+
+            if !selected || Self::locked(&entity_id) {
+                continue;
+            }
+            let Some(camera) = RenderSystem::camera_position() else {
+                continue;
+            };
+            for (index, point) in spline.points.iter().enumerate() {
+                for grip in [Grip::Point, Grip::InHandle, Grip::OutHandle] {
+                    let world = to_world(grip_position(point, grip));
+                    let size = dist2(world, camera).sqrt() * MARKER_SCALE;
+                    let batch = if active == Some((index, grip)) { 3 } else { 2 };
+                    let lines = &mut batches[batch].1;
+                    if grip != Grip::Point {
+                        lines.push([to_world(point.position), world]);
+                    }
+                    for axis in 0..3 {
+                        let (mut from, mut to) = (world, world);
+                        from[axis] -= size;
+                        to[axis] += size;
+                        lines.push([from, to]);
+                    }
+                }
+            }
+        }
+
+        let identity = mat4x4_identity();
+        let shader = get_wireframe_shader();
+        let Ok(buffer) = Buffer::new(gl, BufferKind::Vertex, &[], BufferUsage::Stream) else {
+            return;
+        };
+        let Ok(geometry) = Geometry::new(gl, &[VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)], None) else {
+            buffer.delete(gl);
+            return;
+        };
+        for (color, lines) in batches.iter().filter(|(_, lines)| !lines.is_empty()) {
+            let vertices: Vec<f32> = lines.iter().flatten().flatten().copied().collect();
+            buffer.write(gl, bytemuck::cast_slice(&vertices), BufferUsage::Stream);
+            DrawCall::new(shader, Primitive::Lines, vertices.len() / 3)
+                .geometry(geometry)
+                .depth(DepthCompare::Always)
+                .uniform("world_txfm", Uniform::Mat4(&identity))
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                .uniform("line_color", Uniform::Vec3(color))
+                .submit(gl);
+        }
+        geometry.delete(gl);
+        buffer.delete(gl);
+    }
+
+    fn locked(entity_id: &EntityId) -> bool {
+        get_query_by_id!(entity_id.clone(), (Metadata)).is_some_and(|metadata| metadata.locked)
+    }
+
+    /// Grab the selected spline's point or handle nearest the camera along the press ray
+    fn begin(origin: Vec3, direction: Vec3) -> Option<SplineDrag> {
+        if InterfaceSystem::is_play_mode() || !RenderSystem::layer_rendered(LAYER_GIZMOS) {
+            return None;
+        }
+        let (entity_id, _) = InterfaceSystem::get_selection_state();
+        if Self::locked(&entity_id) {
+            return None;
+        }
+        let spline = get_query_by_id!(entity_id.clone(), (Spline))?;
+        let matrix = get_query_by_id!(entity_id.clone(), (Transform))?.compute_matrix();
+        let (_, _, forward) = RenderSystem::camera_basis()?;
+
+        let (point, grip, world, _) = spline.points
+            .iter()
+            .enumerate()
+            .flat_map(|(index, point)| {
+                [Grip::Point, Grip::InHandle, Grip::OutHandle].map(|grip| {
+                    (index, grip, transform_point(&matrix, grip_position(point, grip)))
+                })
+            })
+            .filter_map(|(index, grip, world)| {
+                let offset = [0, 1, 2].map(|i| world[i] - origin[i]);
+                let along_ray = offset.iter().zip(direction).map(|(a, b)| a * b).sum::<f32>();
+                let closest = [0, 1, 2].map(|i| origin[i] + direction[i] * along_ray);
+                let miss = dist2(closest, world).sqrt();
+                (along_ray > 0.0 && miss <= along_ray * PICK_TOLERANCE).then_some((index, grip, world, along_ray))
+            })
+            .min_by(|a, b| a.3.total_cmp(&b.3))?;
+
+        let normal = forward;
+        let distance = ray_plane(origin, direction, world, normal)?;
+        let press = [0, 1, 2].map(|i| origin[i] + direction[i] * distance);
+        Some(SplineDrag {
+            entity_id,
+            point,
+            grip,
+            offset: [0, 1, 2].map(|i| world[i] - press[i]),
+            normal,
+            moved: false,
+        })
+    }
+
+    /// Move the grip to where the cursor ray meets its plane
+    fn apply(drag: &mut SplineDrag, origin: Vec3, direction: Vec3, snap: bool) {
+        let (Some(mut spline), Some(transform)) = (
+            get_query_by_id!(drag.entity_id.clone(), (Spline)),
+            get_query_by_id!(drag.entity_id.clone(), (Transform)),
+        ) else {
+            return;
+        };
+        let Some(point) = spline.points.get_mut(drag.point) else {
+            return;
+        };
+        let grip_world = transform_point(&transform.compute_matrix(), grip_position(point, drag.grip));
+        let Some(distance) = ray_plane(origin, direction, grip_world, drag.normal) else {
+            return;
+        };
+        let world = [0, 1, 2].map(|i| origin[i] + direction[i] * distance + drag.offset[i]);
+        let world = if snap { world.map(|value| (value / DRAG_SNAP).round() * DRAG_SNAP) } else { world };
+        let local = to_local(&transform, world);
+
+        let handle = [0, 1, 2].map(|i| local[i] - point.position[i]);
+        // The opposite handle turns with the dragged one, keeping its length, so the curve stays smooth
+        let mirror = |opposite: Vec3| {
+            let length = dist2(opposite, [0.0; 3]).sqrt();
+            normalize(handle).map(|value| -value * length)
+        };
+        match drag.grip {
+            Grip::Point => point.position = local,
+            Grip::InHandle => {
+                point.in_handle = handle;
+                point.out_handle = mirror(point.out_handle);
+            }
+            Grip::OutHandle => {
+                point.out_handle = handle;
+                point.in_handle = mirror(point.in_handle);
+            }
+        }
+        insert(&drag.entity_id, spline);
+        drag.moved = true;
+    }
+
+    fn drag(pointer: &PointerEvent, event: &Event) {
+        let Some((origin, direction)) = RenderSystem::camera_ray(pointer.position, pointer.viewport[0], pointer.viewport[1]) else {
+            return;
+        };
+        let mut state = DRAG.write().unwrap();
+        if let DragState::Idle = *state {
+            *state = match Self::begin(origin, direction) {
+                Some(drag) => DragState::Dragging(drag),
+                None => DragState::Ignored,
+            };
+        }
+        if let DragState::Dragging(drag) = &mut *state {
+            event.consume();
+            Self::apply(drag, origin, direction, pointer.snap);
+        }
+    }
+
+    fn release() {
+        let state = std::mem::replace(&mut *DRAG.write().unwrap(), DragState::Idle);
+        if let DragState::Dragging(drag) = state {
+            if drag.moved {
+                InterfaceSystem::refresh_selection();
+            }
+        }
+    }
+
+    /// Console: `spline new` adds a spline in front of the camera and selects it. On the spline the inspector
+    /// shows: `spline add` extends it by a point, `spline remove [index]` takes one out (the last by default),
+    /// `spline closed` joins or opens its ends, `spline profile <none|road|pipe|railing> [size]` sets what's swept
+    /// along it (road width, pipe radius or railing height), `spline follow` makes the other selected entities
+    /// movers along it and `spline camera [seconds]` flies the editor camera along it (`spline stop` ends that)
+    pub fn spline_command(args: &[&str]) -> Result<String, String> {
+        const USAGE: &str =
+            "Usage: spline <new|add|remove [index]|closed|profile <kind> [size]|follow|camera [seconds]|stop>";
+        match args {
+            ["new"] => Self::new_spline(),
+            ["add"] => Self::edit(|spline| {
+                let last = *spline.points.last().ok_or("The spline has no points")?;
+                // On along the way the curve leaves the last point
+                let direction = Some(normalize(last.out_handle))
+                    .filter(|direction| len2(*direction) > 0.0)
+                    .unwrap_or([1.0, 0.0, 0.0]);
+                let position = [0, 1, 2].map(|i| last.position[i] + direction[i] * NEW_SEGMENT_LENGTH);
+                let handle = direction.map(|value| value * NEW_SEGMENT_LENGTH / 3.0);
+                spline.points.push(SplinePoint::smooth(position, handle));
+                Ok(format!("Added point {}", spline.points.len() - 1))
+            }),
+            ["remove", rest @ ..] => Self::edit(|spline| {
+                let index = match rest.first() {
+                    Some(index) => index.parse::<usize>().map_err(|_| format!("Not a point index: {}", index))?,
+                    None => spline.points.len().saturating_sub(1),
+                };
+                if index >= spline.points.len() {
+                    return Err(format!("The spline has no point {}", index));
+                }
+                if spline.points.len() <= 2 {
+                    return Err("A spline keeps at least two points".to_string());
+                }
+                spline.points.remove(index);
+                Ok(format!("Removed point {}", index))
+            }),
+            ["closed"] => Self::edit(|spline| {
+                spline.closed = !spline.closed;
+                Ok(if spline.closed { "Closed the spline" } else { "Opened the spline" }.to_string())
+            }),
+            ["profile", kind, rest @ ..] => {
+                let size = match rest.first() {
+                    Some(size) => Some(positive(size).ok_or_else(|| format!("Not a size: {}", size))?),
+                    None => None,
+                };
+                let profile = match *kind {
+                    "none" => SplineProfile::None,
+                    "road" => SplineProfile::Road { width: size.unwrap_or(4.0), thickness: 0.3 },
+                    "pipe" => SplineProfile::Pipe { radius: size.unwrap_or(0.5), sides: 12 },
+                    "railing" => SplineProfile::Railing { height: size.unwrap_or(1.0), post_spacing: 2.0 },
+                    _ => return Err(USAGE.to_string()),
+                };
+                Self::edit(|spline| {
+                    spline.profile = profile;
+                    Ok(format!("Profile set to {}", kind))
+                })
+            }
+            ["follow"] => Self::follow(),
+            ["camera", rest @ ..] => {
+                let duration = match rest.first() {
+                    Some(seconds) => positive(seconds).ok_or_else(|| format!("Not a duration: {}", seconds))?,
+                    None => 10.0,
+                };
+                let (entity_id, _) = InterfaceSystem::get_selection_state();
+                let spline = get_query_by_id!(entity_id.clone(), (Spline)).ok_or("Select a spline to fly along")?;
+                if spline.segment_count() == 0 {
+                    return Err("The spline needs two points to fly along".to_string());
+                }
+                *CAMERA_PATH.write().unwrap() = Some(CameraPath { entity_id, started: Instant::now(), duration });
+                Ok(format!("Flying along the spline for {} seconds", duration))
+            }
+            ["stop"] => match CAMERA_PATH.write().unwrap().take() {
+                Some(_) => Ok("Stopped the camera path".to_string()),
+                None => Err("The camera isn't flying along a spline".to_string()),
+            },
+            _ => Err(USAGE.to_string()),
+        }
+    }
+
+    fn new_spline() -> Result<String, String> {
+        let (Some(camera), Some((_, _, forward))) = (
+            RenderSystem::camera_position(),
+            RenderSystem::camera_basis(),
+        ) else {
+            return Err("No camera to put the spline in front of".to_string());
+        };
+        // The view looks down -forward
+        let position = [0, 1, 2].map(|i| camera[i] - forward[i] * NEW_SPLINE_DISTANCE);
+        let handle = [NEW_SEGMENT_LENGTH / 3.0, 0.0, 0.0];
+        let spline = Spline::new(vec![
+            SplinePoint::smooth([0.0, 0.0, 0.0], handle),
+            SplinePoint::smooth([NEW_SEGMENT_LENGTH, 0.0, 0.0], handle),
+        ]);
+
+        let entity_id = spawn();
+        insert_many!(
+            entity_id.clone(),
+            Transform::new(position[0], position[1], position[2]),
+            Metadata::new("Spline", None, None).with_category(EntityCategory::Gameplay),
+            spline
+        );
+        InterfaceSystem::update_entities_list();
+        InterfaceSystem::select_entities(vec![entity_id], false);
+        Ok("Added a spline".to_string())
+    }
+
+    /// Change the spline the inspector shows
+    fn edit(change: impl FnOnce(&mut Spline) -> Result<String, String>) -> Result<String, String> {
+        let (entity_id, _) = InterfaceSystem::get_selection_state();
+        let mut spline = get_query_by_id!(entity_id.clone(), (Spline)).ok_or("Select a spline")?;
+        if Self::locked(&entity_id) {
+            return Err("The selected spline is locked".to_string());
+        }
+        let message = change(&mut spline)?;
+        insert(&entity_id, spline);
+        InterfaceSystem::refresh_selection();
+        Ok(message)
+    }
+
+    /// The other selected entities move along the inspector's spline, as KinematicMovers following its path
+    fn follow() -> Result<String, String> {
+        let (path_id, _) = InterfaceSystem::get_selection_state();
+        if get_query_by_id!(path_id.clone(), (Spline)).is_none() {
+            return Err("Select the spline to follow, with the entities to move along it".to_string());
+        }
+        let followers: Vec<EntityId> = Selection::ids()
+            .into_iter()
+            .filter(|entity_id| *entity_id != path_id && !Self::locked(entity_id))
+            .filter(|entity_id| get_query_by_id!(entity_id.clone(), (Transform)).is_some())
+            .collect();
+        if followers.is_empty() {
+            return Err("Also select the entities to move along the spline".to_string());
+        }
+        for entity_id in &followers {
+            let mover = get_query_by_id!(entity_id.clone(), (KinematicMover))
+                .unwrap_or_else(|| KinematicMover::new(Vec::new(), 2.0));
+            insert(entity_id, KinematicMover { path: Some(path_id.clone()), ..mover });
+        }
+        InterfaceSystem::refresh_selection();
+        Ok(format!("{} entities follow the spline", followers.len()))
+    }
+}
+
+impl SystemTrait for SplineSystem {
+    fn event(&self, event: &Event) {
+        let Some(pointer) = event.payload.downcast_ref::<PointerEvent>() else {
+            return;
+        };
+        match event.event_type {
+            EventType::PointerDrag => Self::drag(pointer, event),
+            EventType::PointerRelease => Self::release(),
+            _ => {}
+        }
+    }
+}
+
+fn positive(arg: &str) -> Option<f32> {
+    arg.parse::<f32>().ok().filter(|value| *value > 0.0)
+}
+
+/// Local position of a control point's grip
+fn grip_position(point: &SplinePoint, grip: Grip) -> Vec3 {
+    let offset = match grip {
+        Grip::Point => [0.0; 3],
+        Grip::InHandle => point.in_handle,
+        Grip::OutHandle => point.out_handle,
+    };
+    [0, 1, 2].map(|i| point.position[i] + offset[i])
+}
+
+fn transform_point(matrix: &[f32; 16], point: Vec3) -> Vec3 {
+    [0, 1, 2].map(|row| {
+        (0..3).map(|column| matrix[row * 4 + column] * point[column]).sum::<f32>() + matrix[row * 4 + 3]
+    })
+}
+
+/// A world position in the entity's local space: inverse rotation (the transpose), then inverse scale
+fn to_local(transform: &Transform, world: Vec3) -> Vec3 {
+    let mut rotation = transform.clone();
+    rotation.set_position(0.0, 0.0, 0.0);
+    rotation.set_scale(1.0, 1.0, 1.0);
+    let rotation = rotation.compute_matrix();
+    let (position, scale) = (transform.get_position(), transform.get_scale());
+    let offset = [0, 1, 2].map(|axis| world[axis] - position[axis]);
+    [0, 1, 2].map(|axis| {
+        (0..3).map(|row| rotation[row * 4 + axis] * offset[row]).sum::<f32>() / scale[axis]
+    })
+}
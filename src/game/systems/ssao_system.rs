@@ -0,0 +1,150 @@
+use std::cell::{ Cell, RefCell };
+use glow::HasContext;
+
+use crate::index::engine::managers::assets_manager::{ get_ssao_composite_shader, get_ssao_shader };
+use crate::index::engine::modules::{ Console, EngineError, GraphicsQuality, InterfaceSystem };
+use crate::index::engine::rhi::{ Blend, DepthCompare, DrawCall, Primitive, Texture, TextureFormat, Uniform };
+use crate::index::engine::utils::view_projection_eye;
+use crate::index::game::systems::RenderSystem;
+use crate::index::game::systems::render_system::{ CAMERA_FAR, CAMERA_FOV_DEGREES, CAMERA_NEAR };
+
+/// Default for the `ssao_radius` cvar: view-space reach of the occlusion samples in units
+pub const SSAO_RADIUS: f32 = 0.5;
+/// Default for the `ssao_intensity` cvar: 1 darkens fully enclosed points to black, 0 skips the pass
+pub const SSAO_INTENSITY: f32 = 1.0;
+
+/// Offscreen targets of the occlusion pass, sized like the viewport: a copy of the scene depth to sample, and the
+/// unblurred occlusion drawn from it
+struct SsaoTarget {
+    depth_framebuffer: glow::Framebuffer,
+    occlusion_framebuffer: glow::Framebuffer,
+    depth: Texture,
+    occlusion: Texture,
+}
+
+impl SsaoTarget {
+    unsafe fn new(gl: &glow::Context, width: u32, height: u32) -> Result<Self, EngineError> {
+        let depth = Texture::render_target(gl, width, height, TextureFormat::Depth24Stencil8)?;
+        let occlusion = Texture::render_target(gl, width, height, TextureFormat::R8)?;
+        let gpu_error = |e: String| EngineError::gpu(format!("Failed to create SSAO target: {}", e));
+        let depth_framebuffer = gl.create_framebuffer().map_err(gpu_error)?;
+        let occlusion_framebuffer = gl.create_framebuffer().map_err(gpu_error)?;
+        let target = Self { depth_framebuffer, occlusion_framebuffer, depth, occlusion };
+
+        // Separate framebuffers: the occlusion pass samples the depth texture, so it can't be attached there
+        let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(depth_framebuffer));
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::DEPTH_STENCIL_ATTACHMENT, glow::TEXTURE_2D, Some(depth.raw()), 0);
+        let depth_status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(occlusion_framebuffer));
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(occlusion.raw()), 0);
+        let occlusion_status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+
+        for status in [depth_status, occlusion_status] {
+            if status != glow::FRAMEBUFFER_COMPLETE {
+                target.delete(gl);
+                return Err(EngineError::gpu(format!("SSAO target incomplete (status 0x{:x})", status)));
+            }
+        }
+        Ok(target)
+    }
+
+    unsafe fn delete(self, gl: &glow::Context) {
+        gl.delete_framebuffer(self.depth_framebuffer);
+        gl.delete_framebuffer(self.occlusion_framebuffer);
+        self.depth.delete(gl);
+        self.occlusion.delete(gl);
+    }
+}
+
+thread_local! {
+    static SSAO_TARGET: RefCell<Option<SsaoTarget>> = const { RefCell::new(None) };
+    // Set once the pass failed on this GL context; the scene stays without screen-space occlusion
+    static UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Screen-space ambient occlusion: rebuilds view-space positions and normals from the opaque scene's depth, darkens
+/// creases and contact points by how much nearby geometry covers a hemisphere around them, and multiplies the
+/// blurred result over the frame. A post effect, on from the medium graphics preset up.
+pub struct SsaoSystem;
+
+impl SsaoSystem {
+    /// Post pass for the player camera; runs after the opaque and terrain passes, before water copies the frame
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let intensity = Console::cvar("ssao_intensity").unwrap_or(SSAO_INTENSITY);
+        if !GraphicsQuality::current().ssao_enabled() || intensity <= 0.0 || UNAVAILABLE.with(Cell::get) {
+            return;
+        }
+        // Positions are rebuilt with the perspective projection; orthographic editor views go without
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        if view_projection_eye(&view_proj).is_none() {
+            return;
+        }
+        let radius = Console::cvar("ssao_radius").unwrap_or(SSAO_RADIUS);
+
+        SSAO_TARGET.with(|target| {
+            let mut target = target.borrow_mut();
+            if target.as_ref().is_none_or(|target| target.occlusion.size() != [width, height]) {
+                if let Some(old) = target.take() {
+                    unsafe { old.delete(gl) };
+                }
+                match unsafe { SsaoTarget::new(gl, width, height) } {
+                    Ok(created) => *target = Some(created),
+                    Err(e) => {
+                        Self::disable(&e);
+                        return;
+                    }
+                }
+            }
+            let Some(target) = target.as_ref() else {
+                return;
+            };
+
+            unsafe {
+                let scene_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+
+                gl.get_error(); // Don't blame the blit for earlier passes
+                gl.bind_framebuffer(glow::READ_FRAMEBUFFER, scene_framebuffer);
+                gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(target.depth_framebuffer));
+                let (w, h) = (width as i32, height as i32);
+                gl.blit_framebuffer(0, 0, w, h, 0, 0, w, h, glow::DEPTH_BUFFER_BIT, glow::NEAREST);
+                if gl.get_error() != glow::NO_ERROR {
+                    gl.bind_framebuffer(glow::FRAMEBUFFER, scene_framebuffer);
+                    Self::disable(&EngineError::gpu("Can't copy the scene depth into the SSAO target"));
+                    return;
+                }
+
+                // Full-screen triangles built in the vertex shader
+                gl.bind_framebuffer(glow::FRAMEBUFFER, Some(target.occlusion_framebuffer));
+                DrawCall::new(get_ssao_shader(), Primitive::Triangles, 3)
+                    .depth(DepthCompare::Always)
+                    .uniform("scene_depth", Uniform::Int(0))
+                    .uniform("near", Uniform::Float(CAMERA_NEAR))
+                    .uniform("far", Uniform::Float(CAMERA_FAR))
+                    .uniform("tan_half_fov", Uniform::Float((CAMERA_FOV_DEGREES.to_radians() * 0.5).tan()))
+                    .uniform("aspect", Uniform::Float(width as f32 / height.max(1) as f32))
+                    .uniform("radius", Uniform::Float(radius))
+                    .uniform("intensity", Uniform::Float(intensity))
+                    .texture(0, target.depth)
+                    .submit(gl);
+                gl.bind_framebuffer(glow::FRAMEBUFFER, scene_framebuffer);
+            }
+
+            DrawCall::new(get_ssao_composite_shader(), Primitive::Triangles, 3)
+                .depth(DepthCompare::Always)
+                .blend(Blend::Multiply)
+                .uniform("occlusion", Uniform::Int(0))
+                .texture(0, target.occlusion)
+                .submit(gl);
+        });
+    }
+
+    fn disable(error: &EngineError) {
+        UNAVAILABLE.with(|unavailable| unavailable.set(true));
+        InterfaceSystem::report_error(error);
+        eprintln!("⚠️ Screen-space ambient occlusion unavailable on this GPU");
+    }
+}
@@ -0,0 +1,302 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::index::engine::components::{
+    Collider,
+    Lighting,
+    Shape,
+    SystemTrait,
+    Terrain,
+    TerrainBrush,
+    Transform,
+};
+use crate::index::engine::managers::assets_manager::get_terrain_shader;
+use crate::index::engine::modules::event_system::Event;
+use crate::index::engine::modules::keyboard_input_system::PointerEvent;
+use crate::index::engine::modules::{ EngineResult, EntityId, InterfaceSystem };
+use crate::index::engine::rhi::{ Buffer, BufferUsage, DrawCall, Geometry, Primitive, Uniform, VertexAttribute, VertexFormat };
+use crate::index::engine::utils::math::{ aabb_in_frustum, mat4x4_translate, Vec3 };
+use crate::index::game::systems::{ LightClusterSystem, RenderSystem };
+use crate::{ insert_many, query_by_id, query_get_all };
+
+/// World-space radius of the editing brushes
+const BRUSH_RADIUS: f32 = 3.0;
+/// Height change per tick at the brush centre
+const BRUSH_STRENGTH: f32 = 0.05;
+/// How far the brush ray searches for the terrain surface
+const BRUSH_MAX_DISTANCE: f32 = 200.0;
+
+static ACTIVE_BRUSH: RwLock<Option<TerrainBrush>> = RwLock::new(None);
+
+/// GPU buffers for one terrain chunk and its local-space bounds for culling
+struct TerrainChunk {
+    geometry: Geometry,
+    vertices: Buffer,
+    indices: Buffer,
+    index_count: usize,
+    bounds: (Vec3, Vec3),
+}
+
+/// Chunks built for a terrain, tagged with the data they were built from
+struct TerrainMesh {
+    key: (u64, u32, u32, u32),
+    chunks: Vec<TerrainChunk>,
+}
+
+thread_local! {
+    static TERRAIN_MESHES: RefCell<HashMap<EntityId, TerrainMesh>> = RefCell::new(HashMap::new());
+}
+
+/// Keeps terrain data, heightfield colliders and meshes in sync and applies editor brushes
+#[derive(Debug)]
+pub struct TerrainSystem;
+
+impl TerrainSystem {
+    /// Cycle Off -> Raise -> Lower -> Smooth and return the new brush name
+    pub fn cycle_brush() -> &'static str {
+        let mut brush = ACTIVE_BRUSH.write().unwrap();
+        *brush = match *brush {
+            None => Some(TerrainBrush::Raise),
+            Some(TerrainBrush::Raise) => Some(TerrainBrush::Lower),
+            Some(TerrainBrush::Lower) => Some(TerrainBrush::Smooth),
+            Some(TerrainBrush::Smooth) => None,
+        };
+        match *brush {
+            None => "Off",
+            Some(TerrainBrush::Raise) => "Raise",
+            Some(TerrainBrush::Lower) => "Lower",
+            Some(TerrainBrush::Smooth) => "Smooth",
+        }
+    }
+
+    /// Whether a sculpt brush owns viewport drags
+    pub fn brush_active() -> bool {
+        ACTIVE_BRUSH.read().unwrap().is_some()
+    }
+
+    /// Fill in heights for freshly loaded terrains and copy them into heightfield colliders
+    pub fn update() {
+        for (entity_id, mut terrain) in query_get_all!(Terrain) {
+            let expected = (terrain.resolution.max(2) * terrain.resolution.max(2)) as usize;
+            if terrain.heights.len() != expected {
+                if let Err(error) = terrain.ensure_heights() {
+                    InterfaceSystem::report_error(&error);
+                    terrain.heightmap = None;
+                    let _ = terrain.ensure_heights();
+                }
+                insert_many!(entity_id.clone(), terrain.clone());
+            }
+            Self::sync_collider(&entity_id, &terrain);
+        }
+    }
+
+    fn sync_collider(entity_id: &EntityId, terrain: &Terrain) {
+        query_by_id!(entity_id, (Collider), |collider| {
+            if let Shape::Heightfield { size, resolution, heights } = &mut collider.shape {
+                if *size != terrain.size || *resolution != terrain.resolution || *heights != terrain.heights {
+                    *size = terrain.size;
+                    *resolution = terrain.resolution;
+                    *heights = terrain.heights.clone();
+                }
+            }
+        });
+    }
+
+    /// Render pass for the player camera
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        if let Some(view_proj) = RenderSystem::view_projection(width, height) {
+            Self::draw(gl, &view_proj, &RenderSystem::current_lighting());
+        }
+    }
+
+    /// Draw every terrain chunk inside the frustum of `view_proj`
+    pub fn draw(gl: &glow::Context, view_proj: &[f32; 16], lighting: &Lighting) {
+        let terrains = query_get_all!(Transform, Terrain);
+        TERRAIN_MESHES.with(|meshes| {
+            let mut meshes = meshes.borrow_mut();
+
+            // Free meshes of terrains that no longer exist
+            meshes.retain(|entity_id, mesh| {
+                let alive = terrains.iter().any(|(id, _, _)| id == entity_id);
+                if !alive {
+                    Self::delete_chunks(gl, &mesh.chunks);
+                }
+                alive
+            });
+
+            let pipeline = get_terrain_shader();
+
+            for (entity_id, transform, terrain) in terrains {
+                if RenderSystem::is_hidden(&entity_id) {
+                    continue;
+                }
+                if terrain.heights.len() != (terrain.resolution * terrain.resolution) as usize {
+                    continue; // Not synced yet
+                }
+
+                let key = (terrain.revision, terrain.size.to_bits(), terrain.resolution, terrain.chunk_cells);
+                let needs_rebuild = meshes.get(&entity_id).is_none_or(|mesh| mesh.key != key);
+                if needs_rebuild {
+                    if let Some(old) = meshes.remove(&entity_id) {
+                        Self::delete_chunks(gl, &old.chunks);
+                    }
+                    let chunks = Self::build_chunks(gl, &terrain);
+                    meshes.insert(entity_id.clone(), TerrainMesh { key, chunks });
+                }
+
+                let origin = transform.get_position();
+                let world_txfm = mat4x4_translate(origin[0], origin[1], origin[2]);
+                let Some(mesh) = meshes.get(&entity_id) else {
+                    continue;
+                };
+
+                for chunk in &mesh.chunks {
+                    let (min, max) = chunk.bounds;
+                    let world_min = [min[0] + origin[0], min[1] + origin[1], min[2] + origin[2]];
+                    let world_max = [max[0] + origin[0], max[1] + origin[1], max[2] + origin[2]];
+                    if !aabb_in_frustum(view_proj, world_min, world_max) {
+                        continue;
+                    }
+                    let draw = DrawCall::new(pipeline, Primitive::Triangles, chunk.index_count)
+                        .geometry(chunk.geometry)
+                        .uniform("viewport_txfm", Uniform::Mat4(view_proj))
+                        .uniform("world_txfm", Uniform::Mat4(&world_txfm))
+                        .uniform("light_direction", Uniform::Vec3(&lighting.light_direction))
+                        .uniform("light_color", Uniform::Vec3(&lighting.light_color))
+                        .uniform("ambient_strength", Uniform::Float(lighting.ambient))
+                        .uniform("sky_color", Uniform::Vec3(&lighting.sky_color))
+                        .uniform("fog_density", Uniform::Float(lighting.fog_density));
+                    LightClusterSystem::bind(draw).submit(gl);
+                }
+            }
+        });
+    }
+
+    fn build_chunks(gl: &glow::Context, terrain: &Terrain) -> Vec<TerrainChunk> {
+        // Keep chunk vertex counts within 16-bit indices
+        let chunk_cells = terrain.chunk_cells.clamp(1, 128);
+        let cells = terrain.resolution - 1;
+        let mut chunks = Vec::new();
+
+        for chunk_z in (0..cells).step_by(chunk_cells as usize) {
+            for chunk_x in (0..cells).step_by(chunk_cells as usize) {
+                let end_x = (chunk_x + chunk_cells).min(cells);
+                let end_z = (chunk_z + chunk_cells).min(cells);
+                let columns = end_x - chunk_x + 1;
+
+                let mut vertices: Vec<f32> = Vec::new();
+                let mut min = [f32::INFINITY; 3];
+                let mut max = [f32::NEG_INFINITY; 3];
+                for z in chunk_z..=end_z {
+                    for x in chunk_x..=end_x {
+                        let position = terrain.vertex_position(x, z);
+                        let normal = terrain.vertex_normal(x, z);
+                        vertices.extend_from_slice(&position);
+                        vertices.extend_from_slice(&normal);
+                        for axis in 0..3 {
+                            min[axis] = min[axis].min(position[axis]);
+                            max[axis] = max[axis].max(position[axis]);
+                        }
+                    }
+                }
+
+                let mut indices: Vec<u16> = Vec::new();
+                for z in 0..(end_z - chunk_z) {
+                    for x in 0..(end_x - chunk_x) {
+                        let top_left = (z * columns + x) as u16;
+                        let top_right = top_left + 1;
+                        let bottom_left = top_left + columns as u16;
+                        let bottom_right = bottom_left + 1;
+                        indices.extend_from_slice(&[top_left, bottom_left, top_right, top_right, bottom_left, bottom_right]);
+                    }
+                }
+
+                match Self::upload_chunk(gl, &vertices, &indices, (min, max)) {
+                    Ok(chunk) => chunks.push(chunk),
+                    Err(error) => {
+                        InterfaceSystem::report_error(&error);
+                        break;
+                    }
+                }
+            }
+        }
+
+        chunks
+    }
+
+    fn upload_chunk(gl: &glow::Context, vertices: &[f32], indices: &[u16], bounds: (Vec3, Vec3)) -> EngineResult<TerrainChunk> {
+        let vertex_buffer = Buffer::vertex(gl, vertices, BufferUsage::Static)?;
+        let index_buffer = Buffer::index(gl, indices)?;
+        let attribute = |location, offset| VertexAttribute {
+            buffer: &vertex_buffer,
+            location,
+            format: VertexFormat::Float32x3,
+            stride: 24,
+            offset,
+        };
+        let geometry = Geometry::new(
+            gl,
+            &[
+                attribute(0, 0), // Position
+                attribute(1, 12), // Normal
+            ],
+            Some(&index_buffer)
+        )?;
+
+        Ok(TerrainChunk {
+            geometry,
+            vertices: vertex_buffer,
+            indices: index_buffer,
+            index_count: indices.len(),
+            bounds,
+        })
+    }
+
+    fn delete_chunks(gl: &glow::Context, chunks: &[TerrainChunk]) {
+        for chunk in chunks {
+            chunk.geometry.delete(gl);
+            chunk.vertices.delete(gl);
+            chunk.indices.delete(gl);
+        }
+    }
+}
+
+impl SystemTrait for TerrainSystem {
+    /// Apply the active brush where the dragged cursor ray hits a terrain; while sculpting the drag isn't
+    /// passed on to moving or box-selecting entities
+    fn event(&self, event: &Event) {
+        let Some(brush) = *ACTIVE_BRUSH.read().unwrap() else {
+            return;
+        };
+        event.consume();
+        let Some(pointer) = event.payload.downcast_ref::<PointerEvent>() else {
+            return;
+        };
+        let Some((origin, direction)) = RenderSystem::camera_ray(pointer.position, pointer.viewport[0], pointer.viewport[1]) else {
+            return;
+        };
+
+        // Closest terrain hit along the ray
+        let mut closest: Option<(EntityId, [f32; 2], f32)> = None;
+        for (entity_id, transform, terrain) in query_get_all!(Transform, Terrain) {
+            if RenderSystem::is_hidden(&entity_id) {
+                continue;
+            }
+            let position = transform.get_position();
+            let local_origin = [origin[0] - position[0], origin[1] - position[1], origin[2] - position[2]];
+            if let Some(hit) = terrain.raycast(local_origin, direction, BRUSH_MAX_DISTANCE) {
+                let distance = (0..3).map(|axis| (hit[axis] - local_origin[axis]).powi(2)).sum::<f32>();
+                if closest.as_ref().is_none_or(|(_, _, best)| distance < *best) {
+                    closest = Some((entity_id, [hit[0], hit[2]], distance));
+                }
+            }
+        }
+
+        if let Some((entity_id, center, _)) = closest {
+            query_by_id!(entity_id, (Terrain), |terrain| {
+                terrain.apply_brush(brush, center, BRUSH_RADIUS, BRUSH_STRENGTH);
+            });
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::collections::HashMap;
+
+use crate::index::engine::components::{ AnimatedObject3DComponent, StaticObject3DComponent };
+use crate::index::engine::components::SharedComponents::{ Material, Mesh, Transform };
+use crate::index::engine::managers::TextureStreaming;
+use crate::index::game::systems::render_system::CAMERA_FOV_DEGREES;
+use crate::index::game::systems::RenderSystem;
+use crate::query;
+
+/// Picks the mip level each mesh texture streams to from the player camera: a texture needs about as many texels
+/// across as the object drawn with it covers pixels on screen (as if it's mapped over the object once), for the
+/// closest object using it. Runs before the meshes are drawn
+pub struct TextureStreamingSystem;
+
+impl TextureStreamingSystem {
+    pub fn update(gl: &glow::Context, _width: u32, height: u32) {
+        let Some(camera) = RenderSystem::camera_position() else {
+            return;
+        };
+        // Screen pixels covered by one unit one unit away
+        let pixels_per_unit = height as f32 * 0.5 / (CAMERA_FOV_DEGREES.to_radians() * 0.5).tan();
+
+        let mut wanted: HashMap<glow::Texture, u32> = HashMap::new();
+        let mut want = |transform: &mut Transform, mesh: &Mesh, material: &Material| {
+            let Some(texture) = material.base_color_texture else {
+                return;
+            };
+            let (min, max) = mesh.world_bounds(transform.get_matrix());
+            let extent = (0..3).map(|axis| max[axis] - min[axis]).fold(0.0f32, f32::max);
+            let distance = (0..3)
+                .map(|axis| (min[axis] - camera[axis]).max(camera[axis] - max[axis]).max(0.0))
+                .map(|gap| gap * gap)
+                .sum::<f32>()
+                .sqrt();
+            let level = TextureStreaming::level_for(&texture, extent * pixels_per_unit / distance.max(1e-3));
+            wanted
+                .entry(texture.raw())
+                .and_modify(|finest| *finest = (*finest).min(level))
+                .or_insert(level);
+        };
+        query!((Transform, StaticObject3DComponent), |_entity_id, transform, static_object| {
+            want(&mut transform, &static_object.mesh, &static_object.material);
+        });
+        query!((Transform, AnimatedObject3DComponent), |_entity_id, transform, animated_object| {
+            want(&mut transform, &animated_object.mesh, &animated_object.material);
+        });
+
+        TextureStreaming::stream(gl, &wanted);
+    }
+}
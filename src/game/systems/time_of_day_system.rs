@@ -0,0 +1,69 @@
+use crate::index::engine::components::{ Metadata, EntityCategory, TimeOfDay };
+use crate::index::engine::modules::{ spawn, InterfaceSystem };
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::{ insert_many, query };
+
+/// Hours shown when a scene gets its first TimeOfDay
+const DEFAULT_HOUR: f32 = 12.0;
+/// Ten real minutes per in-game day
+const DEFAULT_DAY_LENGTH: f32 = 600.0;
+
+pub struct TimeOfDaySystem;
+
+impl TimeOfDaySystem {
+    /// Advance the scene clock one fixed tick and mirror it into the editor slider
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        let mut current_hour = None;
+
+        query!((TimeOfDay), |_entity_id, time_of_day| {
+            time_of_day.advance(delta_time);
+            current_hour.get_or_insert(time_of_day.hour);
+        });
+
+        if let Some(hour) = current_hour {
+            InterfaceSystem::sync_time_of_day(hour);
+        }
+    }
+
+    /// Jump to an hour (editor slider); creates the scene's TimeOfDay if it has none
+    pub fn set_hour(hour: f32) {
+        let mut found = false;
+        query!((TimeOfDay), |_entity_id, time_of_day| {
+            if !found {
+                time_of_day.hour = hour.rem_euclid(24.0);
+                found = true;
+            }
+        });
+
+        if !found {
+            let entity_id = spawn();
+            insert_many!(
+                entity_id,
+                TimeOfDay::new(hour, DEFAULT_DAY_LENGTH),
+                Metadata::new("Time Of Day", Some("time_of_day"), None).with_category(EntityCategory::Light)
+            );
+            println!("🌅 Added TimeOfDay to the scene at {:.1}h", hour);
+            InterfaceSystem::update_entities_list();
+        }
+    }
+
+    /// Pause or resume the day cycle, returning whether it is now running
+    pub fn toggle_running() -> bool {
+        let mut running = None;
+        query!((TimeOfDay), |_entity_id, time_of_day| {
+            if running.is_none() {
+                time_of_day.running = !time_of_day.running;
+                running = Some(time_of_day.running);
+            }
+        });
+
+        match running {
+            Some(running) => running,
+            None => {
+                Self::set_hour(DEFAULT_HOUR);
+                true
+            }
+        }
+    }
+}
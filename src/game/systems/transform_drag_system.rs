@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::index::engine::components::{ ColliderLayer, Metadata, SystemTrait, Transform };
+use crate::index::engine::modules::event_system::{ Event, EventType };
+use crate::index::engine::modules::keyboard_input_system::PointerEvent;
+use crate::index::engine::modules::{ EntityId, InterfaceSystem, Selection };
+use crate::index::engine::utils::math::{ ray_aabb, ray_plane };
+use crate::index::game::systems::physics_system::PhysicsSystem;
+use crate::index::game::systems::{ RenderSystem, TerrainSystem };
+use crate::index::PLAYER_ENTITY_ID;
+use crate::{ get_query_by_id, query_by_id, query_get_all };
+
+/// Largest gap a dragged bounds corner jumps across to meet another entity's corner
+const CORNER_SNAP_RADIUS: f32 = 1.0;
+/// Reach of the surface snap ray under the cursor
+const SURFACE_SNAP_DISTANCE: f32 = 500.0;
+
+/// Bounds corners of every entity that isn't being dragged, bucketed by CORNER_SNAP_RADIUS cells
+struct CornerGrid {
+    cells: HashMap<[i32; 3], Vec<[f32; 3]>>,
+}
+
+impl CornerGrid {
+    fn build(skip: &[EntityId]) -> Self {
+        let mut cells: HashMap<[i32; 3], Vec<[f32; 3]>> = HashMap::new();
+        for (entity_id, metadata) in query_get_all!(Metadata) {
+            if skip.contains(&entity_id) || !metadata.is_visible {
+                continue;
+            }
+            if let Some((min, max)) = RenderSystem::entity_bounds(&entity_id) {
+                for corner in corners(min, max) {
+                    cells.entry(Self::cell(corner)).or_default().push(corner);
+                }
+            }
+        }
+        Self { cells }
+    }
+
+    fn cell(point: [f32; 3]) -> [i32; 3] {
+        point.map(|p| (p / CORNER_SNAP_RADIUS).floor() as i32)
+    }
+
+    /// Closest stored corner within CORNER_SNAP_RADIUS, with its squared distance
+    fn nearest(&self, point: [f32; 3]) -> Option<([f32; 3], f32)> {
+        let center = Self::cell(point);
+        let mut best: Option<([f32; 3], f32)> = None;
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(candidates) = self.cells.get(&[center[0] + dx, center[1] + dy, center[2] + dz]) else {
+                        continue;
+                    };
+                    for candidate in candidates {
+                        let d = [0, 1, 2].map(|axis| candidate[axis] - point[axis]);
+                        let dist2 = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+                        if dist2 <= CORNER_SNAP_RADIUS * CORNER_SNAP_RADIUS && best.is_none_or(|(_, b)| dist2 < b) {
+                            best = Some((*candidate, dist2));
+                        }
+                    }
+                }
+            }
+        }
+        best
+    }
+}
+
+/// Entity being dragged and where it was grabbed; the rest of the selection follows it
+struct Drag {
+    entity_id: EntityId,
+    start: [f32; 3],
+    followers: Vec<(EntityId, [f32; 3])>, // Other selected entities and their start positions
+    offset: [f32; 3],      // Position minus the grab point
+    plane_point: [f32; 3], // Camera-facing plane through the grab point that the cursor moves on
+    plane_normal: [f32; 3],
+    bounds_min: [f32; 3],  // Bounds relative to the position
+    bounds_max: [f32; 3],
+    moved: bool,
+    corners: CornerGrid,
+}
+
+enum DragState {
+    Idle,
+    Ignored, // The press didn't land on the selection; wait for the release
+    Moving(Drag),
+}
+
+static DRAG: RwLock<DragState> = RwLock::new(DragState::Idle);
+
+/// Moves the selection by dragging one of its entities in the Edit viewport; holding V snaps the grabbed
+/// entity to other entities (nearest bounds corner) or to the surface under the cursor
+#[derive(Debug)]
+pub struct TransformDragSystem;
+
+impl TransformDragSystem {
+    /// Whether a press grabbed the selection and is moving it
+    pub fn is_dragging() -> bool {
+        matches!(*DRAG.read().unwrap(), DragState::Moving(_))
+    }
+
+    /// Grab the closest selected entity whose bounds the press lands on
+    fn begin(origin: [f32; 3], direction: [f32; 3]) -> Option<Drag> {
+        if InterfaceSystem::is_play_mode() || TerrainSystem::brush_active() {
+            return None;
+        }
+        let selected: Vec<(EntityId, [f32; 3])> = Selection::ids()
+            .into_iter()
+            .filter(|entity_id| !get_query_by_id!(entity_id.clone(), (Metadata)).is_some_and(|metadata| metadata.locked))
+            .filter_map(|entity_id| {
+                let position = get_query_by_id!(entity_id.clone(), (Transform))?.get_position();
+                Some((entity_id, position))
+            })
+            .collect();
+        let (entity_id, position, (min, max), distance) = selected
+            .iter()
+            .filter_map(|(entity_id, position)| {
+                let bounds = RenderSystem::entity_bounds(entity_id)?;
+                let distance = ray_aabb(origin, direction, bounds.0, bounds.1)?;
+                Some((entity_id.clone(), *position, bounds, distance))
+            })
+            .min_by(|a, b| a.3.total_cmp(&b.3))?;
+        let (_, _, forward) = RenderSystem::camera_basis()?;
+
+        let grab = [0, 1, 2].map(|axis| origin[axis] + direction[axis] * distance);
+        let selected_ids: Vec<EntityId> = selected.iter().map(|(entity_id, _)| entity_id.clone()).collect();
+        Some(Drag {
+            start: position,
+            offset: [0, 1, 2].map(|axis| position[axis] - grab[axis]),
+            plane_point: grab,
+            plane_normal: forward,
+            bounds_min: [0, 1, 2].map(|axis| min[axis] - position[axis]),
+            bounds_max: [0, 1, 2].map(|axis| max[axis] - position[axis]),
+            moved: false,
+            corners: CornerGrid::build(&selected_ids),
+            followers: selected.into_iter().filter(|(follower, _)| *follower != entity_id).collect(),
+            entity_id,
+        })
+    }
+
+    /// New position for the cursor ray, snapped when requested
+    fn target_position(drag: &Drag, origin: [f32; 3], direction: [f32; 3], snap: bool) -> Option<[f32; 3]> {
+        let distance = ray_plane(origin, direction, drag.plane_point, drag.plane_normal)?;
+        let position = [0, 1, 2].map(|axis| origin[axis] + direction[axis] * distance + drag.offset[axis]);
+        if !snap {
+            return Some(position);
+        }
+
+        // Corner to corner beats the surface, so boxes line up flush with their neighbours
+        let snapped_corner = corners(
+            [0, 1, 2].map(|axis| position[axis] + drag.bounds_min[axis]),
+            [0, 1, 2].map(|axis| position[axis] + drag.bounds_max[axis])
+        )
+            .into_iter()
+            .filter_map(|corner| drag.corners.nearest(corner).map(|(target, dist2)| (corner, target, dist2)))
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+        if let Some((corner, target, _)) = snapped_corner {
+            return Some([0, 1, 2].map(|axis| position[axis] + target[axis] - corner[axis]));
+        }
+
+        let player_id = PLAYER_ENTITY_ID.read().unwrap().clone().unwrap_or_default();
+        let ignore: Vec<&EntityId> = std::iter::once(&player_id)
+            .chain(std::iter::once(&drag.entity_id))
+            .chain(drag.followers.iter().map(|(entity_id, _)| entity_id))
+            .collect();
+        let Some(hit) = PhysicsSystem::raycast(origin, direction, SURFACE_SNAP_DISTANCE, &[ColliderLayer::Environment], &ignore) else {
+            return Some(position);
+        };
+
+        // Rest the bounds side facing away from the normal on the hit point, centered on the other axes
+        let support = [0, 1, 2].map(|axis| {
+            if hit.normal[axis] > 0.5 {
+                drag.bounds_min[axis]
+            } else if hit.normal[axis] < -0.5 {
+                drag.bounds_max[axis]
+            } else {
+                (drag.bounds_min[axis] + drag.bounds_max[axis]) * 0.5
+            }
+        });
+        Some([0, 1, 2].map(|axis| hit.point[axis] - support[axis]))
+    }
+
+    fn drag(pointer: &PointerEvent) {
+        let Some((origin, direction)) = RenderSystem::camera_ray(pointer.position, pointer.viewport[0], pointer.viewport[1]) else {
+            return;
+        };
+        let mut state = DRAG.write().unwrap();
+        if let DragState::Idle = *state {
+            *state = match Self::begin(origin, direction) {
+                Some(drag) => DragState::Moving(drag),
+                None => DragState::Ignored,
+            };
+            // The grab itself doesn't move anything
+            return;
+        }
+        let DragState::Moving(drag) = &mut *state else {
+            return;
+        };
+
+        if let Some(position) = Self::target_position(drag, origin, direction, pointer.snap) {
+            drag.moved = true;
+            query_by_id!(drag.entity_id.clone(), (Transform), |transform| {
+                transform.set_position(position[0], position[1], position[2]);
+            });
+            let delta = [0, 1, 2].map(|axis| position[axis] - drag.start[axis]);
+            for (entity_id, start) in &drag.followers {
+                query_by_id!(entity_id.clone(), (Transform), |transform| {
+                    transform.set_position(start[0] + delta[0], start[1] + delta[1], start[2] + delta[2]);
+                });
+            }
+        }
+    }
+
+    fn release() {
+        let state = std::mem::replace(&mut *DRAG.write().unwrap(), DragState::Idle);
+        if let DragState::Moving(drag) = state {
+            if drag.moved {
+                println!("📦 Moved {} entities", drag.followers.len() + 1);
+                InterfaceSystem::refresh_selection();
+            }
+        }
+    }
+}
+
+impl SystemTrait for TransformDragSystem {
+    fn event(&self, event: &Event) {
+        let Some(pointer) = event.payload.downcast_ref::<PointerEvent>() else {
+            return;
+        };
+        match event.event_type {
+            EventType::PointerDrag => Self::drag(pointer),
+            EventType::PointerRelease => Self::release(),
+            _ => {}
+        }
+    }
+}
+
+fn corners(min: [f32; 3], max: [f32; 3]) -> [[f32; 3]; 8] {
+    [0, 1, 2, 3, 4, 5, 6, 7].map(|i| {
+        [
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        ]
+    })
+}
@@ -0,0 +1,377 @@
+use std::f32::consts::TAU;
+use std::sync::RwLock;
+
+use crate::index::engine::components::{ Metadata, SystemTrait, Transform, LAYER_GIZMOS };
+use crate::index::engine::managers::assets_manager::get_wireframe_shader;
+use crate::index::engine::modules::event_system::{ Event, EventType };
+use crate::index::engine::modules::keyboard_input_system::PointerEvent;
+use crate::index::engine::modules::{ EntityId, InterfaceSystem };
+use crate::index::engine::rhi::{
+    Buffer,
+    BufferKind,
+    BufferUsage,
+    DepthCompare,
+    DrawCall,
+    Geometry,
+    Primitive,
+    Uniform,
+    VertexAttribute,
+    VertexFormat,
+};
+use crate::index::engine::utils::math::{ cross, dot, normalize, ray_plane, Vec3 };
+use crate::index::engine::utils::{ mat4x4_identity, mat4x4_mul, mat4x4_rot_x, mat4x4_rot_y, mat4x4_rot_z };
+use crate::index::game::systems::RenderSystem;
+use crate::{ get_query_by_id, query_by_id };
+
+/// Handle length as a fraction of the camera distance, so the gizmo keeps its size on screen
+const HANDLE_SCALE: f32 = 0.15;
+/// How close a click has to pass to a handle, as a fraction of the handle length
+const PICK_TOLERANCE: f32 = 0.08;
+/// Segments of the rotate rings
+const RING_SEGMENTS: usize = 48;
+/// Smallest scale a drag leaves on an axis, so it can't flip or collapse the entity
+const MIN_SCALE: f32 = 0.01;
+/// Steps of a drag with V held: world units when moving, radians when turning, scale when scaling
+const TRANSLATE_SNAP: f32 = 0.5;
+const ROTATE_SNAP: f32 = TAU / 24.0;
+const SCALE_SNAP: f32 = 0.1;
+const AXIS_COLORS: [[f32; 3]; 3] = [[0.9, 0.2, 0.2], [0.2, 0.85, 0.2], [0.25, 0.4, 1.0]];
+/// Color of the axis being dragged
+const ACTIVE_COLOR: [f32; 3] = [1.0, 0.9, 0.1];
+
+/// What dragging a handle does to the selected entity
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum GizmoMode {
+    Translate, // Arrows along the world axes
+    Rotate,    // Rings about the world axes
+    Scale,     // Box-ended lines along the entity's own axes
+}
+
+/// Handle being dragged and the entity's transform when it was grabbed
+struct GizmoDrag {
+    entity_id: EntityId,
+    axis: usize,
+    direction: Vec3,   // Axis of the handle in world space
+    grab: Vec3,        // Where the press met the handle: along the axis line, or on the ring's plane
+    position: Vec3,
+    rotation: [f32; 3],
+    scale: [f32; 3],
+    moved: bool,
+}
+
+enum GizmoState {
+    Idle,
+    Ignored, // The press didn't land on a handle; wait for the release
+    Dragging(GizmoDrag),
+}
+
+static GIZMO_MODE: RwLock<Option<GizmoMode>> = RwLock::new(Some(GizmoMode::Translate));
+static GIZMO: RwLock<GizmoState> = RwLock::new(GizmoState::Idle);
+
+/// Translate, rotate and scale handles on the selected entity in the Edit viewport: pressing on a handle drags
+/// the entity along or about its axis (V snaps the change to steps), and the press doesn't reach the selection
+/// drag or the marquee. The mode is cycled from the top bar
+#[derive(Debug)]
+pub struct TransformGizmoSystem;
+
+impl TransformGizmoSystem {
+    /// Cycle Translate -> Rotate -> Scale -> Off and return the new mode name
+    pub fn cycle_mode() -> &'static str {
+        let mut mode = GIZMO_MODE.write().unwrap();
+        *mode = match *mode {
+            Some(GizmoMode::Translate) => Some(GizmoMode::Rotate),
+            Some(GizmoMode::Rotate) => Some(GizmoMode::Scale),
+            Some(GizmoMode::Scale) => None,
+            None => Some(GizmoMode::Translate),
+        };
+        *GIZMO.write().unwrap() = GizmoState::Idle;
+        match *mode {
+            Some(GizmoMode::Translate) => "Translate",
+            Some(GizmoMode::Rotate) => "Rotate",
+            Some(GizmoMode::Scale) => "Scale",
+            None => "Off",
+        }
+    }
+
+    /// Render system: draw the handles of the selected entity on the gizmo layer, over the scene
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let Some((mode, _, center, axes, size)) = Self::target() else {
+            return;
+        };
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let active = match &*GIZMO.read().unwrap() {
+            GizmoState::Dragging(drag) => Some(drag.axis),
+            _ => None,
+        };
+
+        let identity = mat4x4_identity();
+        let shader = get_wireframe_shader();
+        let Ok(buffer) = Buffer::new(gl, BufferKind::Vertex, &[], BufferUsage::Stream) else {
+            return;
+        };
+        let Ok(geometry) = Geometry::new(gl, &[VertexAttribute::packed(&buffer, 0, VertexFormat::Float32x3)], None) else {
+            buffer.delete(gl);
+            return;
+        };
+        for (axis, axis_color) in AXIS_COLORS.into_iter().enumerate() {
+            let lines = Self::handle(mode, center, axes, axis, size);
+            let vertices: Vec<f32> = lines.into_iter().flatten().flatten().collect();
+            let color = if active == Some(axis) { ACTIVE_COLOR } else { axis_color };
+            buffer.write(gl, bytemuck::cast_slice(&vertices), BufferUsage::Stream);
+            DrawCall::new(shader, Primitive::Lines, vertices.len() / 3)
+                .geometry(geometry)
+                .depth(DepthCompare::Always)
+                .uniform("world_txfm", Uniform::Mat4(&identity))
+                .uniform("viewport_txfm", Uniform::Mat4(&view_proj))
+                .uniform("line_color", Uniform::Vec3(&color))
+                .submit(gl);
+        }
+        geometry.delete(gl);
+        buffer.delete(gl);
+    }
+
+    /// Mode, selected entity, pivot, handle axes and handle length of the gizmo to show; None in Play mode, with
+    /// the gizmo off or hidden, or without an unlocked selection
+    fn target() -> Option<(GizmoMode, EntityId, Vec3, [Vec3; 3], f32)> {
+        let mode = (*GIZMO_MODE.read().unwrap())?;
+        if InterfaceSystem::is_play_mode() || !RenderSystem::layer_rendered(LAYER_GIZMOS) {
+            return None;
+        }
+        let (entity_id, _) = InterfaceSystem::get_selection_state();
+        if get_query_by_id!(entity_id.clone(), (Metadata)).is_some_and(|metadata| metadata.locked) {
+            return None;
+        }
+        let transform = get_query_by_id!(entity_id.clone(), (Transform))?;
+        let center = transform.get_position();
+        let axes = match mode {
+            GizmoMode::Scale => {
+                let rotation = rotation_matrix(transform.get_rotation());
+                [0, 1, 2].map(|column| normalize([0, 1, 2].map(|row| rotation[row][column])))
+            }
+            _ => [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]],
+        };
+        let camera = RenderSystem::camera_position()?;
+        let distance = dot(sub(center, camera), sub(center, camera)).sqrt();
+        Some((mode, entity_id, center, axes, (distance * HANDLE_SCALE).max(0.01)))
+    }
+
+    /// Line segments of one axis' handle
+    fn handle(mode: GizmoMode, center: Vec3, axes: [Vec3; 3], axis: usize, size: f32) -> Vec<[Vec3; 2]> {
+        let [u, v] = [axes[(axis + 1) % 3], axes[(axis + 2) % 3]];
+        let point = |along: f32, a: f32, b: f32| {
+            [0, 1, 2].map(|i| center[i] + axes[axis][i] * along + u[i] * a + v[i] * b)
+        };
+        let tip = size;
+        match mode {
+            GizmoMode::Translate => {
+                let head = size * 0.1;
+                let mut lines = vec![[center, point(tip, 0.0, 0.0)]];
+                for [a, b] in [[head, 0.0], [-head, 0.0], [0.0, head], [0.0, -head]] {
+                    lines.push([point(tip, 0.0, 0.0), point(tip - head * 2.0, a, b)]);
+                }
+                lines
+            }
+            GizmoMode::Rotate => {
+                let ring = |i: usize| {
+                    let angle = i as f32 / RING_SEGMENTS as f32 * TAU;
+                    point(0.0, angle.cos() * size, angle.sin() * size)
+                };
+                (0..RING_SEGMENTS).map(|i| [ring(i), ring(i + 1)]).collect()
+            }
+            GizmoMode::Scale => {
+                let half = size * 0.06;
+                let corners = [[-half, -half], [half, -half], [half, half], [-half, half]];
+                let mut lines = vec![[center, point(tip - half, 0.0, 0.0)]];
+                for (i, [a, b]) in corners.iter().enumerate() {
+                    let [c, d] = corners[(i + 1) % 4];
+                    lines.push([point(tip - half, *a, *b), point(tip - half, c, d)]);
+                    lines.push([point(tip + half, *a, *b), point(tip + half, c, d)]);
+                    lines.push([point(tip - half, *a, *b), point(tip + half, *a, *b)]);
+                }
+                lines
+            }
+        }
+    }
+
+    /// Grab the handle the press lands on, nearest the camera first
+    fn begin(origin: Vec3, direction: Vec3) -> Option<GizmoDrag> {
+        let (mode, entity_id, center, axes, size) = Self::target()?;
+        let tolerance = size * PICK_TOLERANCE;
+        let (axis, grab, _) = (0..3)
+            .filter_map(|axis| {
+                let (grab, along_ray) = match mode {
+                    GizmoMode::Rotate => {
+                        let distance = ray_plane(origin, direction, center, axes[axis])?;
+                        let hit = [0, 1, 2].map(|i| origin[i] + direction[i] * distance);
+                        let radius = dot(sub(hit, center), sub(hit, center)).sqrt();
+                        ((radius - size).abs() <= tolerance).then_some((hit, distance))?
+                    }
+                    _ => {
+                        let (along, along_ray, miss) = line_ray_closest(center, axes[axis], origin, direction)?;
+                        let on_handle = (0.0..=size * 1.1).contains(&along) && miss <= tolerance && along_ray > 0.0;
+                        on_handle.then_some(([0, 1, 2].map(|i| center[i] + axes[axis][i] * along), along_ray))?
+                    }
+                };
+                Some((axis, grab, along_ray))
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2))?;
+
+        let transform = get_query_by_id!(entity_id.clone(), (Transform))?;
+        Some(GizmoDrag {
+            entity_id,
+            axis,
+            direction: axes[axis],
+            grab,
+            position: transform.get_position(),
+            rotation: transform.get_rotation(),
+            scale: transform.get_scale(),
+            moved: false,
+        })
+    }
+
+    /// Apply the handle's change for the cursor ray, measured from the grab
+    fn apply(drag: &mut GizmoDrag, origin: Vec3, direction: Vec3, snap: bool) {
+        let Some(mode) = *GIZMO_MODE.read().unwrap() else {
+            return;
+        };
+        let pivot = drag.position;
+        match mode {
+            GizmoMode::Translate => {
+                let Some((along, _, _)) = line_ray_closest(drag.grab, drag.direction, origin, direction) else {
+                    return;
+                };
+                let along = if snap { (along / TRANSLATE_SNAP).round() * TRANSLATE_SNAP } else { along };
+                let position = [0, 1, 2].map(|i| pivot[i] + drag.direction[i] * along);
+                query_by_id!(drag.entity_id.clone(), (Transform), |transform| {
+                    transform.set_position(position[0], position[1], position[2]);
+                });
+            }
+            GizmoMode::Rotate => {
+                let Some(distance) = ray_plane(origin, direction, pivot, drag.direction) else {
+                    return;
+                };
+                let hit = [0, 1, 2].map(|i| origin[i] + direction[i] * distance);
+                let (from, to) = (sub(drag.grab, pivot), sub(hit, pivot));
+                let angle = dot(cross(from, to), drag.direction).atan2(dot(from, to));
+                let angle = if snap { (angle / ROTATE_SNAP).round() * ROTATE_SNAP } else { angle };
+                let rotation = mul3(axis_rotation(drag.direction, angle), rotation_matrix(drag.rotation));
+                let [pitch, yaw, roll] = euler_angles(rotation);
+                query_by_id!(drag.entity_id.clone(), (Transform), |transform| {
+                    transform.set_rotation(pitch, yaw, roll);
+                });
+            }
+            GizmoMode::Scale => {
+                let Some((along, _, _)) = line_ray_closest(pivot, drag.direction, origin, direction) else {
+                    return;
+                };
+                let start = dot(sub(drag.grab, pivot), drag.direction);
+                if start.abs() < f32::EPSILON {
+                    return;
+                }
+                let mut scale = drag.scale;
+                let factor = drag.scale[drag.axis] * along / start;
+                let factor = if snap { (factor / SCALE_SNAP).round() * SCALE_SNAP } else { factor };
+                scale[drag.axis] = factor.max(MIN_SCALE);
+                query_by_id!(drag.entity_id.clone(), (Transform), |transform| {
+                    transform.set_scale(scale[0], scale[1], scale[2]);
+                });
+            }
+        }
+        drag.moved = true;
+    }
+
+    fn drag(pointer: &PointerEvent, event: &Event) {
+        let Some((origin, direction)) = RenderSystem::camera_ray(pointer.position, pointer.viewport[0], pointer.viewport[1]) else {
+            return;
+        };
+        let mut state = GIZMO.write().unwrap();
+        if let GizmoState::Idle = *state {
+            *state = match Self::begin(origin, direction) {
+                Some(drag) => GizmoState::Dragging(drag),
+                None => GizmoState::Ignored,
+            };
+        }
+        if let GizmoState::Dragging(drag) = &mut *state {
+            event.consume();
+            Self::apply(drag, origin, direction, pointer.snap);
+        }
+    }
+
+    fn release() {
+        let state = std::mem::replace(&mut *GIZMO.write().unwrap(), GizmoState::Idle);
+        if let GizmoState::Dragging(drag) = state {
+            if drag.moved {
+                InterfaceSystem::refresh_selection();
+            }
+        }
+    }
+}
+
+impl SystemTrait for TransformGizmoSystem {
+    fn event(&self, event: &Event) {
+        let Some(pointer) = event.payload.downcast_ref::<PointerEvent>() else {
+            return;
+        };
+        match event.event_type {
+            EventType::PointerDrag => Self::drag(pointer, event),
+            EventType::PointerRelease => Self::release(),
+            _ => {}
+        }
+    }
+}
+
+fn sub(a: Vec3, b: Vec3) -> Vec3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Closest approach of the line `point + axis * along` to a ray: `along`, the distance along the ray and how far
+/// apart the two are there; None when they're parallel. Both directions are unit length
+fn line_ray_closest(point: Vec3, axis: Vec3, origin: Vec3, direction: Vec3) -> Option<(f32, f32, f32)> {
+    let offset = sub(point, origin);
+    let cosine = dot(axis, direction);
+    let denominator = 1.0 - cosine * cosine;
+    if denominator < 1e-6 {
+        return None;
+    }
+    let along = (cosine * dot(direction, offset) - dot(axis, offset)) / denominator;
+    let along_ray = (dot(direction, offset) - cosine * dot(axis, offset)) / denominator;
+    let gap = [0, 1, 2].map(|i| point[i] + axis[i] * along - origin[i] - direction[i] * along_ray);
+    Some((along, along_ray, dot(gap, gap).sqrt()))
+}
+
+/// Rotation part of a Transform with these Euler angles (Ry * Rx * Rz), rows first
+fn rotation_matrix([pitch, yaw, roll]: [f32; 3]) -> [Vec3; 3] {
+    let matrix = mat4x4_mul(mat4x4_mul(mat4x4_rot_y(yaw), mat4x4_rot_x(pitch)), mat4x4_rot_z(roll));
+    [0, 1, 2].map(|row| [0, 1, 2].map(|column| matrix[row * 4 + column]))
+}
+
+/// Euler angles [pitch, yaw, roll] that rotation_matrix turns back into `rotation`
+fn euler_angles(rotation: [Vec3; 3]) -> [f32; 3] {
+    let pitch = (-rotation[1][2]).clamp(-1.0, 1.0).asin();
+    if pitch.cos() > 1e-4 {
+        let yaw = (-rotation[0][2]).atan2(rotation[2][2]);
+        let roll = rotation[1][0].atan2(rotation[1][1]);
+        [pitch, yaw, roll]
+    } else {
+        // Looking straight up or down, yaw and roll turn about the same axis
+        [pitch, rotation[2][0].atan2(rotation[0][0]), 0.0]
+    }
+}
+
+/// Right-handed rotation by `angle` about a unit axis (Rodrigues)
+fn axis_rotation(axis: Vec3, angle: f32) -> [Vec3; 3] {
+    let (sin, cos) = angle.sin_cos();
+    let [x, y, z] = axis;
+    let t = 1.0 - cos;
+    [
+        [t * x * x + cos, t * x * y - sin * z, t * x * z + sin * y],
+        [t * x * y + sin * z, t * y * y + cos, t * y * z - sin * x],
+        [t * x * z - sin * y, t * y * z + sin * x, t * z * z + cos],
+    ]
+}
+
+fn mul3(a: [Vec3; 3], b: [Vec3; 3]) -> [Vec3; 3] {
+    [0, 1, 2].map(|row| [0, 1, 2].map(|column| (0..3).map(|k| a[row][k] * b[k][column]).sum()))
+}
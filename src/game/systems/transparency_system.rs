@@ -0,0 +1,237 @@
+use std::cell::{ Cell, RefCell };
+use glow::HasContext;
+
+use crate::index::engine::components::{ Lighting, StaticObject3DComponent, Water };
+use crate::index::engine::components::SharedComponents::{ Material, Mesh, Transform };
+use crate::index::engine::managers::assets_manager::{ get_oit_composite_shader, get_transparent_shader };
+use crate::index::engine::modules::{ EngineError, InterfaceSystem, Project, TransformInterpolation, TransparencyMode };
+use crate::index::engine::rhi::{ Blend, DepthCompare, DrawCall, Primitive, Texture, TextureFormat, Uniform };
+use crate::index::game::systems::{ RenderSystem, WaterSystem };
+use crate::{ query, query_get_all };
+
+/// Something drawn by the transparency pass
+enum Surface {
+    Mesh {
+        mesh: Mesh,
+        material: Material,
+        world_txfm: [f32; 16],
+    },
+    Water {
+        water: Water,
+        position: [f32; 3],
+    },
+}
+
+/// Offscreen targets of weighted blended transparency, sized like the viewport. The accumulation texture sums
+/// weighted colors in rgb and multiplies revealage (1 - alpha) in a; the weight texture sums alpha weights.
+struct OitTarget {
+    framebuffer: glow::Framebuffer,
+    depth: glow::Renderbuffer,
+    accumulation: Texture,
+    weights: Texture,
+}
+
+impl OitTarget {
+    unsafe fn new(gl: &glow::Context, width: u32, height: u32) -> Result<Self, EngineError> {
+        let accumulation = Texture::render_target(gl, width, height, TextureFormat::Rgba16Float)?;
+        let weights = Texture::render_target(gl, width, height, TextureFormat::R16Float)?;
+        let gpu_error = |e: String| EngineError::gpu(format!("Failed to create transparency target: {}", e));
+        let framebuffer = gl.create_framebuffer().map_err(gpu_error)?;
+        let depth = gl.create_renderbuffer().map_err(gpu_error)?;
+        let target = Self { framebuffer, depth, accumulation, weights };
+
+        // Same format as the window's depth buffer, so the opaque depth can be blitted in
+        gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+        gl.renderbuffer_storage(glow::RENDERBUFFER, glow::DEPTH24_STENCIL8, width as i32, height as i32);
+        gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+
+        let previous_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT0, glow::TEXTURE_2D, Some(accumulation.raw()), 0);
+        gl.framebuffer_texture_2d(glow::FRAMEBUFFER, glow::COLOR_ATTACHMENT1, glow::TEXTURE_2D, Some(weights.raw()), 0);
+        gl.framebuffer_renderbuffer(glow::FRAMEBUFFER, glow::DEPTH_STENCIL_ATTACHMENT, glow::RENDERBUFFER, Some(depth));
+        gl.draw_buffers(&[glow::COLOR_ATTACHMENT0, glow::COLOR_ATTACHMENT1]);
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, previous_framebuffer);
+
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            target.delete(gl);
+            return Err(EngineError::gpu(format!("Transparency target incomplete (status 0x{:x}), float color buffers unsupported?", status)));
+        }
+        Ok(target)
+    }
+
+    unsafe fn delete(self, gl: &glow::Context) {
+        gl.delete_framebuffer(self.framebuffer);
+        gl.delete_renderbuffer(self.depth);
+        self.accumulation.delete(gl);
+        self.weights.delete(gl);
+    }
+}
+
+thread_local! {
+    static OIT_TARGET: RefCell<Option<OitTarget>> = const { RefCell::new(None) };
+    // Set once weighted blending failed on this GL context; the project falls back to sorting
+    static OIT_UNAVAILABLE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Draws transparent surfaces (meshes with blended materials, water with opacity below 1) over the opaque
+/// scene, either sorted back to front or order-independently as the project's `transparency` setting chooses
+pub struct TransparencySystem;
+
+impl TransparencySystem {
+    /// Transparency pass for the player camera; runs after the water pass, whose frame copy the water reuses
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let lighting = RenderSystem::current_lighting();
+        let mut surfaces = Self::mesh_surfaces();
+        for (entity_id, transform, water) in query_get_all!(Transform, Water) {
+            if water.is_transparent() && !RenderSystem::is_hidden(&entity_id) {
+                surfaces.push(Surface::Water { water, position: transform.get_position() });
+            }
+        }
+        if surfaces.is_empty() {
+            return;
+        }
+
+        let weighted_blended = Project::get().transparency == TransparencyMode::WeightedBlended &&
+            !OIT_UNAVAILABLE.with(Cell::get);
+        if weighted_blended && Self::draw_weighted_blended(gl, width, height, &view_proj, &lighting, &surfaces) {
+            return;
+        }
+        Self::draw_sorted(gl, &view_proj, &lighting, surfaces);
+    }
+
+    /// Sorted transparent meshes for other cameras (minimap, captures), which skip the water pass
+    pub fn draw_meshes(gl: &glow::Context, view_proj: &[f32; 16], lighting: &Lighting) {
+        Self::draw_sorted(gl, view_proj, lighting, Self::mesh_surfaces());
+    }
+
+    fn mesh_surfaces() -> Vec<Surface> {
+        let mut surfaces = Vec::new();
+        query!((Transform, StaticObject3DComponent), |entity_id, transform, static_object| {
+            if !static_object.material.transparent || RenderSystem::is_hidden(&entity_id) {
+                continue;
+            }
+            surfaces.push(Surface::Mesh {
+                mesh: static_object.mesh.clone(),
+                material: static_object.material.clone(),
+                world_txfm: TransformInterpolation::matrix(&entity_id, &mut transform),
+            });
+        });
+        surfaces
+    }
+
+    /// Alpha blend back to front, by the view depth of each surface's centre
+    fn draw_sorted(gl: &glow::Context, view_proj: &[f32; 16], lighting: &Lighting, surfaces: Vec<Surface>) {
+        let mut surfaces: Vec<(f32, Surface)> = surfaces
+            .into_iter()
+            .map(|surface| {
+                let center = match &surface {
+                    Surface::Mesh { mesh, world_txfm, .. } => {
+                        let (min, max) = mesh.world_bounds(world_txfm);
+                        [(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5, (min[2] + max[2]) * 0.5]
+                    }
+                    Surface::Water { position, .. } => *position,
+                };
+                // Clip-space w is the distance along the view direction
+                let depth = view_proj[12] * center[0] + view_proj[13] * center[1] + view_proj[14] * center[2] + view_proj[15];
+                (depth, surface)
+            })
+            .collect();
+        surfaces.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+
+        for (_, surface) in &surfaces {
+            Self::draw_surface(gl, surface, view_proj, lighting, Blend::Alpha);
+        }
+    }
+
+    /// Weighted blended order-independent transparency (McGuire & Bavoil 2013): accumulate every surface into
+    /// float targets in any order, then composite the weighted average over the scene. False if the targets
+    /// can't be used, leaving the caller to sort instead.
+    fn draw_weighted_blended(
+        gl: &glow::Context,
+        width: u32,
+        height: u32,
+        view_proj: &[f32; 16],
+        lighting: &Lighting,
+        surfaces: &[Surface]
+    ) -> bool {
+        OIT_TARGET.with(|target| {
+            let mut target = target.borrow_mut();
+            if target.as_ref().is_none_or(|target| target.accumulation.size() != [width, height]) {
+                if let Some(old) = target.take() {
+                    unsafe { old.delete(gl) };
+                }
+                match unsafe { OitTarget::new(gl, width, height) } {
+                    Ok(created) => *target = Some(created),
+                    Err(e) => {
+                        Self::disable_weighted_blended(&e);
+                        return false;
+                    }
+                }
+            }
+            let Some(target) = target.as_ref() else {
+                return false;
+            };
+
+            unsafe {
+                let scene_framebuffer = gl.get_parameter_framebuffer(glow::FRAMEBUFFER_BINDING);
+
+                // Surfaces behind opaque geometry stay hidden: start from the scene's depth
+                gl.get_error(); // Don't blame the blit for earlier passes
+                gl.bind_framebuffer(glow::READ_FRAMEBUFFER, scene_framebuffer);
+                gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(target.framebuffer));
+                let (w, h) = (width as i32, height as i32);
+                gl.blit_framebuffer(0, 0, w, h, 0, 0, w, h, glow::DEPTH_BUFFER_BIT, glow::NEAREST);
+                if gl.get_error() != glow::NO_ERROR {
+                    gl.bind_framebuffer(glow::FRAMEBUFFER, scene_framebuffer);
+                    Self::disable_weighted_blended(&EngineError::gpu("Can't copy the scene depth into the transparency target"));
+                    return false;
+                }
+
+                gl.bind_framebuffer(glow::FRAMEBUFFER, Some(target.framebuffer));
+                gl.clear_buffer_f32_slice(glow::COLOR, 0, &[0.0, 0.0, 0.0, 1.0]);
+                gl.clear_buffer_f32_slice(glow::COLOR, 1, &[0.0; 4]);
+                for surface in surfaces {
+                    Self::draw_surface(gl, surface, view_proj, lighting, Blend::WeightedBlended);
+                }
+                gl.bind_framebuffer(glow::FRAMEBUFFER, scene_framebuffer);
+            }
+
+            // Full-screen triangle built in the vertex shader
+            DrawCall::new(get_oit_composite_shader(), Primitive::Triangles, 3)
+                .depth(DepthCompare::Always)
+                .blend(Blend::Alpha)
+                .uniform("accumulation", Uniform::Int(0))
+                .uniform("weights", Uniform::Int(1))
+                .texture(0, target.accumulation)
+                .texture(1, target.weights)
+                .submit(gl);
+            true
+        })
+    }
+
+    fn disable_weighted_blended(error: &EngineError) {
+        OIT_UNAVAILABLE.with(|unavailable| unavailable.set(true));
+        InterfaceSystem::report_error(error);
+        eprintln!("⚠️ Weighted blended transparency unavailable, sorting transparent surfaces instead");
+    }
+
+    fn draw_surface(gl: &glow::Context, surface: &Surface, view_proj: &[f32; 16], lighting: &Lighting, blend: Blend) {
+        match surface {
+            Surface::Mesh { mesh, material, world_txfm } => {
+                let material = Material { pipeline: get_transparent_shader(), ..material.clone() };
+                RenderSystem::mesh_draw(mesh, &material, view_proj, lighting)
+                    .blend(blend)
+                    .uniform("world_txfm", Uniform::Mat4(world_txfm))
+                    .uniform("opacity", Uniform::Float(material.opacity))
+                    .uniform("weighted_blended", Uniform::Int((blend == Blend::WeightedBlended) as i32))
+                    .submit(gl);
+            }
+            Surface::Water { water, position } => WaterSystem::draw(gl, water, *position, view_proj, lighting, blend),
+        }
+    }
+}
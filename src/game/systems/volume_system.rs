@@ -0,0 +1,186 @@
+use std::sync::RwLock;
+
+use crate::index::engine::components::{
+    EntityCategory,
+    Metadata,
+    Transform,
+    Volume,
+    VolumeBlend,
+    VolumeShape,
+};
+use crate::index::engine::modules::ecs::insert;
+use crate::index::engine::modules::{ is_enabled, spawn, InterfaceSystem };
+use crate::index::engine::utils::math::Vec3;
+use crate::index::game::systems::RenderSystem;
+use crate::{ get_query_by_id, insert_many, query_get_all };
+
+/// How far in front of the camera `volume box|sphere` puts a volume
+const NEW_VOLUME_DISTANCE: f32 = 10.0;
+/// Half size of a new box volume, and radius of a new sphere
+const NEW_VOLUME_SIZE: f32 = 5.0;
+
+/// The volumes' settings at the player camera this frame
+#[derive(Clone, Debug)]
+pub struct VolumeState {
+    pub exposure: VolumeBlend,
+    pub contrast: VolumeBlend,
+    pub saturation: VolumeBlend,
+    pub fog_density: VolumeBlend,
+    pub gravity: VolumeBlend,
+    pub reverb: VolumeBlend,
+    pub ambient_sound: Option<(String, f32)>, // Of the highest priority volume with one, and its weight
+}
+
+impl VolumeState {
+    const NONE: Self = Self {
+        exposure: VolumeBlend::NONE,
+        contrast: VolumeBlend::NONE,
+        saturation: VolumeBlend::NONE,
+        fog_density: VolumeBlend::NONE,
+        gravity: VolumeBlend::NONE,
+        reverb: VolumeBlend::NONE,
+        ambient_sound: None,
+    };
+}
+
+static STATE: RwLock<VolumeState> = RwLock::new(VolumeState::NONE);
+
+/// Blends the enabled Volumes around the player camera once a frame; color grading, fog and gravity read the
+/// result through `current`
+pub struct VolumeSystem;
+
+impl VolumeSystem {
+    /// Render system: evaluate the volumes at the camera; runs after the camera moved, before anything draws
+    pub fn update(_gl: &glow::Context, _width: u32, _height: u32) {
+        let state = RenderSystem::camera_position().map_or(VolumeState::NONE, Self::evaluate);
+        *STATE.write().unwrap() = state;
+    }
+
+    /// The volumes at a world point, lowest priority first so higher ones are blended over them
+    pub fn evaluate(point: Vec3) -> VolumeState {
+        let mut volumes: Vec<(Volume, f32)> = query_get_all!(Transform, Volume)
+            .into_iter()
+            .filter(|(entity_id, _, _)| is_enabled(entity_id))
+            .map(|(_, transform, volume)| {
+                let weight = volume.weight(&transform, point);
+                (volume, weight)
+            })
+            .filter(|(_, weight)| *weight > 0.0)
+            .collect();
+        volumes.sort_by_key(|(volume, _)| volume.priority);
+
+        let mut state = VolumeState::NONE;
+        for (volume, weight) in volumes {
+            let settings = volume.settings;
+            state.exposure = state.exposure.towards(settings.exposure, weight);
+            state.contrast = state.contrast.towards(settings.contrast, weight);
+            state.saturation = state.saturation.towards(settings.saturation, weight);
+            state.fog_density = state.fog_density.towards(settings.fog_density, weight);
+            state.gravity = state.gravity.towards(settings.gravity, weight);
+            state.reverb = state.reverb.towards(settings.reverb, weight);
+            if let Some(sound) = settings.ambient_sound {
+                state.ambient_sound = Some((sound, weight));
+            }
+        }
+        state
+    }
+
+    /// The settings blended by the last `update`
+    pub fn current() -> VolumeState {
+        STATE.read().unwrap().clone()
+    }
+
+    /// Console: `volume <box|sphere>` adds a volume in front of the camera and selects it, `volume set <setting>
+    /// <value|off>` changes one of the inspector's volume's overrides, and `volume` alone shows what applies at
+    /// the camera
+    pub fn volume_command(args: &[&str]) -> Result<String, String> {
+        match args {
+            [] => Ok(Self::describe(&Self::current())),
+            [shape] => {
+                let shape = match *shape {
+                    "box" => VolumeShape::Box { half_extents: [NEW_VOLUME_SIZE; 3] },
+                    "sphere" => VolumeShape::Sphere { radius: NEW_VOLUME_SIZE },
+                    _ => return Err("Usage: volume [box|sphere|set <setting> <value|off>]".to_string()),
+                };
+                Self::add(shape)
+            }
+            ["set", setting, value] => Self::set(setting, value),
+            _ => Err("Usage: volume [box|sphere|set <setting> <value|off>]".to_string()),
+        }
+    }
+
+    fn add(shape: VolumeShape) -> Result<String, String> {
+        let (Some(camera), Some((_, _, forward))) = (
+            RenderSystem::camera_position(),
+            RenderSystem::camera_basis(),
+        ) else {
+            return Err("No camera to put the volume in front of".to_string());
+        };
+        // The view looks down -forward
+        let position = [0, 1, 2].map(|i| camera[i] - forward[i] * NEW_VOLUME_DISTANCE);
+        let entity_id = spawn();
+        insert_many!(
+            entity_id.clone(),
+            Transform::new(position[0], position[1], position[2]),
+            Metadata::new("Volume", None, None).with_category(EntityCategory::Volume),
+            Volume::new(shape)
+        );
+        InterfaceSystem::update_entities_list();
+        InterfaceSystem::select_entities(vec![entity_id], false);
+        Ok("Added a volume; set what it overrides with `volume set`".to_string())
+    }
+
+    fn set(setting: &str, value: &str) -> Result<String, String> {
+        let (entity_id, _) = InterfaceSystem::get_selection_state();
+        let mut volume = get_query_by_id!(entity_id.clone(), (Volume)).ok_or("Select a volume")?;
+        if get_query_by_id!(entity_id.clone(), (Metadata)).is_some_and(|metadata| metadata.locked) {
+            return Err("The selected volume is locked".to_string());
+        }
+        let settings = &mut volume.settings;
+        if setting == "ambient_sound" {
+            settings.ambient_sound = (value != "off").then(|| value.to_string());
+        } else {
+            let number = match value {
+                "off" => None,
+                _ => Some(value.parse::<f32>().map_err(|_| format!("Not a number: {}", value))?),
+            };
+            let field = match setting {
+                "exposure" => &mut settings.exposure,
+                "contrast" => &mut settings.contrast,
+                "saturation" => &mut settings.saturation,
+                "fog_density" => &mut settings.fog_density,
+                "gravity" => &mut settings.gravity,
+                "reverb" => &mut settings.reverb,
+                _ => return Err(format!("Unknown volume setting {} (see VolumeSettings)", setting)),
+            };
+            *field = number;
+        }
+        insert(&entity_id, volume);
+        InterfaceSystem::refresh_selection();
+        Ok(format!("{} set to {}", setting, value))
+    }
+
+    /// The overridden settings at the camera with the volumes' share of each
+    fn describe(state: &VolumeState) -> String {
+        let blends = [
+            ("exposure", state.exposure),
+            ("contrast", state.contrast),
+            ("saturation", state.saturation),
+            ("fog_density", state.fog_density),
+            ("gravity", state.gravity),
+            ("reverb", state.reverb),
+        ];
+        let mut lines: Vec<String> = blends
+            .iter()
+            .filter(|(_, blend)| blend.is_active())
+            .map(|(name, blend)| format!("{}: {:.3} at {:.0}%", name, blend.value(), blend.weight() * 100.0))
+            .collect();
+        if let Some((sound, weight)) = &state.ambient_sound {
+            lines.push(format!("ambient_sound: {} at {:.0}%", sound, weight * 100.0));
+        }
+        if lines.is_empty() {
+            return "No volume applies at the camera".to_string();
+        }
+        format!("Volumes at the camera:\n{}", lines.join("\n"))
+    }
+}
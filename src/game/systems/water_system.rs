@@ -0,0 +1,105 @@
+use std::cell::RefCell;
+
+use crate::index::engine::components::{ Lighting, Transform, Water };
+use crate::index::engine::managers::assets_manager::get_water_shader;
+use crate::index::engine::modules::game_loop::FIXED_TICK_RATE;
+use crate::index::engine::modules::is_enabled;
+use crate::index::engine::rhi::{ Blend, DrawCall, Primitive, Texture, Uniform };
+use crate::index::engine::utils::math::mat4x4_translate;
+use crate::index::game::systems::RenderSystem;
+use crate::{ query, query_get_all };
+
+thread_local! {
+    /// Copy of the frame taken before the water pass, sampled for reflections
+    static SCENE_COPY: RefCell<Option<Texture>> = const { RefCell::new(None) };
+}
+
+pub struct WaterSystem;
+
+impl WaterSystem {
+    /// Advance the wave animation one fixed tick
+    pub fn update() {
+        let delta_time = 1.0 / FIXED_TICK_RATE;
+        query!((Water), |entity_id, water| {
+            if !is_enabled(&entity_id) {
+                continue;
+            }
+            water.advance(delta_time);
+        });
+    }
+
+    /// Water pass: runs after the opaque passes so reflections can reuse the rendered frame. Transparent water is
+    /// left to TransparencySystem, which runs next and uses the same copy of the frame.
+    pub fn render(gl: &glow::Context, width: u32, height: u32) {
+        let waters = query_get_all!(Transform, Water);
+        if waters.is_empty() {
+            return;
+        }
+        let Some(view_proj) = RenderSystem::view_projection(width, height) else {
+            return;
+        };
+        let lighting = RenderSystem::current_lighting();
+        Self::copy_scene(gl, width, height);
+
+        for (entity_id, transform, water) in waters {
+            if RenderSystem::is_hidden(&entity_id) || water.is_transparent() {
+                continue;
+            }
+            Self::draw(gl, &water, transform.get_position(), &view_proj, &lighting, Blend::Opaque);
+        }
+    }
+
+    /// Draw one water plane centred on `position`
+    pub fn draw(gl: &glow::Context, water: &Water, position: [f32; 3], view_proj: &[f32; 16], lighting: &Lighting, blend: Blend) {
+        let Some(camera_position) = RenderSystem::camera_position() else {
+            return;
+        };
+        let scene_color = SCENE_COPY.with(|scene_copy| *scene_copy.borrow());
+        // Water stays horizontal: only the translation is used
+        let world_txfm = mat4x4_translate(position[0], position[1], position[2]);
+
+        // The quad is generated in the vertex shader, no geometry needed
+        let mut draw = DrawCall::new(get_water_shader(), Primitive::Triangles, 6)
+            .blend(blend)
+            .uniform("viewport_txfm", Uniform::Mat4(view_proj))
+            .uniform("camera_position", Uniform::Vec3(&camera_position))
+            .uniform("light_direction", Uniform::Vec3(&lighting.light_direction))
+            .uniform("light_color", Uniform::Vec3(&lighting.light_color))
+            .uniform("ambient_strength", Uniform::Float(lighting.ambient))
+            .uniform("sky_color", Uniform::Vec3(&lighting.sky_color))
+            .uniform("fog_density", Uniform::Float(lighting.fog_density))
+            .uniform("scene_color", Uniform::Int(1))
+            .uniform("has_scene_color", Uniform::Int(scene_color.is_some() as i32))
+            .uniform("world_txfm", Uniform::Mat4(&world_txfm))
+            .uniform("size", Uniform::Vec2(&water.size))
+            .uniform("water_color", Uniform::Vec3(&water.color))
+            .uniform("time", Uniform::Float(water.time))
+            .uniform("wave_scale", Uniform::Float(water.wave_scale))
+            .uniform("reflectivity", Uniform::Float(water.reflectivity.clamp(0.0, 1.0)))
+            .uniform("opacity", Uniform::Float(water.opacity.clamp(0.0, 1.0)))
+            .uniform("weighted_blended", Uniform::Int((blend == Blend::WeightedBlended) as i32));
+        if let Some(texture) = scene_color {
+            draw = draw.texture(1, texture);
+        }
+        draw.submit(gl);
+    }
+
+    /// Copy the current framebuffer into a texture, reallocating it when the viewport resizes
+    fn copy_scene(gl: &glow::Context, width: u32, height: u32) {
+        SCENE_COPY.with(|scene_copy| {
+            let mut scene_copy = scene_copy.borrow_mut();
+            match *scene_copy {
+                Some(texture) if texture.size() == [width, height] => texture.copy_framebuffer(gl),
+                _ => {
+                    if let Some(old) = scene_copy.take() {
+                        old.delete(gl);
+                    }
+                    match Texture::from_framebuffer(gl, width, height) {
+                        Ok(texture) => *scene_copy = Some(texture),
+                        Err(e) => eprintln!("❌ Failed to create water reflection texture: {}", e),
+                    }
+                }
+            }
+        })
+    }
+}
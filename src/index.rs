@@ -1,4 +1,4 @@
-use std::sync::{ Arc, RwLock };
+use std::sync::RwLock;
 
 use glow::HasContext;
 use once_cell::sync::Lazy;
@@ -12,8 +12,6 @@ pub mod game;
 use engine::*;
 use game::*;
 
-use crate::index::game::physics_system::PhysicsSystem;
-
 pub static PLAYER_ENTITY_ID: Lazy<RwLock<Option<EntityId>>> = Lazy::new(|| RwLock::new(None));
 
 pub struct Program {
@@ -21,17 +19,10 @@ pub struct Program {
 }
 
 impl Program {
-    pub fn new(gl: glow::Context) -> Result<Self, String> {
-        initialize_asset_manager(&gl);
-
-        EventSystem::subscribe(EventType::Move, Arc::new(MovementSystem));
-        EventSystem::subscribe(EventType::RotateCamera, Arc::new(CameraRotationSystem));
-
-        load_world!("src/assets/scenes/test_world.json");
-
-        spawn_player();
-
-        InterfaceSystem::update_entities_list();
+    pub fn new(gl: glow::Context) -> EngineResult<Self> {
+        Diagnostics::record_gl_info(&gl);
+        initialize_asset_manager(&gl)?;
+        Self::initialize_world(Some(&gl));
 
         unsafe {
             gl.enable(glow::DEPTH_TEST);
@@ -56,6 +47,60 @@ impl Program {
         Ok(Self { gl })
     }
 
+    /// Register the engine and gameplay plugins, load project assets with their loaders and start importing
+    /// `--import` assets (imported models are spawned as their imports finish), load the startup scene and apply
+    /// `--import-colliders` files. Headless runs have no GL context
+    pub fn initialize_world(gl: Option<&glow::Context>) {
+        let cli_args = CliArgs::get();
+
+        App::add_plugin(&EnginePlugin);
+        App::add_plugin(&CoreGamePlugin);
+
+        // Project assets, then the command line's imports
+        App::load_project_assets(gl);
+
+        for path in &cli_args.imports {
+            let title = std::path::Path
+                ::new(path)
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone());
+            import_gltf_asset(path, cli_args.import_simplify, move |result| {
+                match result {
+                    Ok(asset_name) => {
+                        spawn_imported_model(asset_name, &title);
+                        InterfaceSystem::update_entities_list();
+                    }
+                    Err(e) => InterfaceSystem::report_error(&e),
+                }
+            });
+        }
+
+        // After the imports started, so scene validation knows the imported assets the scene uses
+        load_world!(cli_args.scene_path());
+
+        for path in &cli_args.collider_imports {
+            if let Err(e) = CollisionFile::import(path) {
+                InterfaceSystem::report_error(&e);
+            }
+        }
+
+        App::run_startup();
+
+        InterfaceSystem::update_entities_list();
+    }
+
+    /// Run one fixed simulation tick of the registered gameplay systems. Sent events are drained before the
+    /// systems run (this tick's input) and after them (what the systems sent), so every handler sees a whole batch
+    pub fn update() {
+        EventSystem::drain();
+        App::run_fixed_update();
+        EventSystem::drain();
+        // Structural changes systems deferred while iterating
+        World::apply_commands();
+        DeterminismAudit::after_tick();
+    }
+
     pub fn render(&mut self, width: u32, height: u32, _delta_time: f32) {
         let mut viewport = [0i32; 4];
         let mut program = 0i32;
@@ -84,8 +129,9 @@ impl Program {
             self.gl.viewport(0, 0, width as i32, height as i32);
         }
 
-        RenderSystem::update(&self.gl, width, height);
-        PhysicsSystem::update();
+        TaskSystem::poll(Some(&self.gl));
+        App::run_render(&self.gl, width, height);
+        HudBridge::flush();
 
         unsafe {
             self.gl.viewport(viewport[0], viewport[1], viewport[2], viewport[3]);
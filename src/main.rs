@@ -16,7 +16,7 @@ use std::time::Instant;
 // Import our game engine
 mod index;
 use index::{ Program };
-use index::engine::modules::{ EventSystem, KeyboardInputSystem, InterfaceSystem };
+use index::engine::modules::{ EventSystem, KeyboardInputSystem, InterfaceSystem, GameLoop, AppConfig, CliArgs, Console, DeterminismAudit, Diagnostics, EditorSettings, EngineError, GraphicsQuality, Project, SafeMode, SceneBench, TaskSystem, TransformInterpolation };
 
 slint::include_modules!();
 
@@ -31,12 +31,86 @@ fn create_glow_context(
     }
 }
 
+/// Simulate `--run-ticks` fixed ticks without a window or GL context (for CI); fails if the run diverges from the
+/// `--audit-compare` baseline. With `--bench`, ticks are benchmarked as frames instead
+fn run_headless() -> Result<(), Box<dyn std::error::Error>> {
+    let cli_args = CliArgs::get();
+    let ticks = if cli_args.bench { cli_args.bench_frames() } else { cli_args.run_ticks };
+    println!("[HEADLESS] Loading {} and running {} ticks", cli_args.scene_path(), ticks);
+
+    // Before the scene loads, so it spawns with the baseline's seed
+    if let Some(path) = &cli_args.audit_compare {
+        DeterminismAudit::start_comparing(path)?;
+    } else if let Some(path) = &cli_args.audit_record {
+        DeterminismAudit::start_recording(path);
+    }
+
+    EventSystem::initialize();
+    index::engine::initialize_headless_asset_manager();
+    Program::initialize_world(None);
+    // Start from the fully imported world, as a windowed run does after its first frames
+    TaskSystem::wait_all(None);
+
+    if cli_args.bench {
+        SceneBench::start(cli_args.scene_path(), ticks, &cli_args.bench_report_path(), true);
+        // The first call starts the first frame
+        while !SceneBench::frame()? {
+            Program::update();
+            TaskSystem::poll(None);
+        }
+    } else {
+        for _ in 0..ticks {
+            Program::update();
+            TaskSystem::poll(None);
+        }
+    }
+
+    println!(
+        "[HEADLESS] Finished {} ticks with {} entities",
+        ticks,
+        index::engine::get_all_entities().len()
+    );
+
+    if cli_args.audit_compare.is_some() || cli_args.audit_record.is_some() {
+        println!("[HEADLESS] {}", DeterminismAudit::finish()?);
+    }
+    Ok(())
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("[HYBRID] Starting runst-poc with Slint + OpenGL integration");
 
-    // Ensure Winit backend is selected for Slint
+    // Parse startup arguments (scene, play/headless modes, imports)
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    CliArgs::initialize(CliArgs::parse(&args));
+    Project::initialize(Project::load(&args));
+
+    if CliArgs::get().headless {
+        return run_headless();
+    }
+
+    // Load window configuration (app_config.toml + CLI overrides)
+    let app_config = AppConfig::load(&args);
+    if app_config.crash_reports {
+        Diagnostics::enable();
+    }
+    Console::load_saved(&app_config.cvars);
+    GraphicsQuality::set(app_config.graphics);
+    EditorSettings::set(app_config.editor);
+
+    // A session marker left behind by a crash makes the first scene load go through safe mode
+    SafeMode::begin_session(CliArgs::get().safe_mode);
+
+    // Ensure Winit backend is selected for Slint, applying the window config before creation
     println!("[DEBUG] Selecting Winit backend for Slint...");
-    slint::platform::set_platform(Box::new(i_slint_backend_winit::Backend::new().unwrap()))?;
+    let config_for_window = app_config.clone();
+    let backend = i_slint_backend_winit::Backend
+        ::builder()
+        .with_window_attributes_hook(move |attributes| {
+            config_for_window.apply_to_window_attributes(attributes)
+        })
+        .build()?;
+    slint::platform::set_platform(Box::new(backend))?;
     println!("[DEBUG] Winit backend selected");
 
     // Create Slint UI using Winit backend
@@ -44,10 +118,30 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let ui_app = LevelEditorUI::new().expect("Failed to create Slint UI");
     println!("[DEBUG] Slint UI created successfully");
 
+    ui_app.set_window_title(app_config.title.clone().into());
+    ui_app.window().set_size(slint::PhysicalSize::new(app_config.width, app_config.height));
+
+    // Monitor placement and the UI scale need the live winit window, so defer them to the first event loop turn
+    let ui_app_weak_for_monitor = ui_app.as_weak();
+    let config_for_monitor = app_config.clone();
+    slint::Timer::single_shot(std::time::Duration::ZERO, move || {
+        if let Some(app) = ui_app_weak_for_monitor.upgrade() {
+            app.window().with_winit_window(|winit_window| {
+                config_for_monitor.apply_monitor(winit_window);
+                EditorSettings::set_monitor_scale(winit_window.scale_factor() as f32);
+            });
+            InterfaceSystem::apply_editor_settings();
+        }
+    });
+
     // Initialize systems
     EventSystem::initialize();
     let keyboard_input_system = Rc::new(KeyboardInputSystem::new());
     InterfaceSystem::initialize(ui_app.as_weak());
+    if CliArgs::get().play || Project::get().runtime {
+        println!("[DEBUG] --play or runtime project: skipping the editor UI");
+        ui_app.global::<InterfaceState>().set_play_mode(true);
+    }
     println!("[DEBUG] KeyboardInputSystem and InterfaceSystem initialized");
 
     // Set up simplified event handling with KeyboardInputSystem
@@ -60,6 +154,22 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 keyboard_system_for_events.receive_mouse_event(position, slint_window);
                 WinitWindowEventResult::Propagate
             }
+            // Track the primary button for viewport drags
+            WindowEvent::MouseInput { state, button, .. } => {
+                keyboard_system_for_events.receive_mouse_button(state, button);
+                WinitWindowEventResult::Propagate
+            }
+            // Keep the editor UI scale on top of the new monitor's scale factor
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                EditorSettings::set_monitor_scale(*scale_factor as f32);
+                InterfaceSystem::apply_editor_settings();
+                WinitWindowEventResult::PreventDefault
+            }
+            // Track modifier keys for combinations such as Alt+Enter
+            WindowEvent::ModifiersChanged(modifiers) => {
+                keyboard_system_for_events.receive_modifiers(modifiers);
+                WinitWindowEventResult::Propagate
+            }
             // Handle keyboard input for movement
             WindowEvent::KeyboardInput { event: keyboard_event, .. } => {
                 keyboard_system_for_events.receive_key_event(keyboard_event, slint_window);
@@ -76,12 +186,17 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let game_program_for_callback = game_program.clone();
     let start_time = Rc::new(RefCell::new(None::<Instant>));
     let start_time_for_callback = start_time.clone();
+    // A benchmark whose report couldn't be written fails the run once the window closes
+    let bench_error = Rc::new(RefCell::new(None::<EngineError>));
+    let bench_error_for_callback = bench_error.clone();
 
     // Get weak references for different callbacks
     let ui_app_weak_for_rendering = ui_app.as_weak();
     let ui_app_weak_for_animation = ui_app.as_weak();
     let ui_window = ui_app.window();
 
+    let config_for_rendering = app_config.clone();
+
     println!("[DEBUG] Setting up OpenGL rendering notifier...");
     ui_window
         .set_rendering_notifier({
@@ -95,6 +210,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                             // Initialize glow context
                             let gl = create_glow_context(get_proc_address);
+                            config_for_rendering.apply_vsync(get_proc_address);
 
                             // Initialize game program
                             println!("[DEBUG] Creating game program...");
@@ -103,9 +219,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                                     *game_program_for_callback.borrow_mut() = Some(program);
                                     *start_time_for_callback.borrow_mut() = Some(Instant::now());
                                     println!("[UNDERLAY] Game engine initialized successfully");
+                                    let cli_args = CliArgs::get();
+                                    if cli_args.bench {
+                                        let (scene, frames) = (cli_args.scene_path(), cli_args.bench_frames());
+                                        SceneBench::start(scene, frames, &cli_args.bench_report_path(), false);
+                                    }
                                 }
                                 Err(e) => {
-                                    eprintln!("[UNDERLAY] Failed to create game program: {e}");
+                                    eprintln!("[UNDERLAY] Failed to create game program");
+                                    InterfaceSystem::report_error(&e);
                                 }
                             }
                         } else {
@@ -128,8 +250,28 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                         if let Some(program) = program_borrow.as_mut() {
                             program.render(size.width, size.height, elapsed_time);
                         }
+                        // Benchmark frames are closed at the same point each frame, and the app exits after the last
+                        if SceneBench::is_running() {
+                            match SceneBench::frame() {
+                                Ok(false) => {}
+                                Ok(true) => {
+                                    let _ = slint::quit_event_loop();
+                                }
+                                Err(e) => {
+                                    InterfaceSystem::report_error(&e);
+                                    *bench_error_for_callback.borrow_mut() = Some(e);
+                                    let _ = slint::quit_event_loop();
+                                }
+                            }
+                        }
                     }
 
+                    // Benchmarks render as fast as they can rather than at the animation timer's rate
+                    RenderingState::AfterRendering if SceneBench::is_running() => {
+                        if let Some(app) = ui_app_weak_for_rendering.upgrade() {
+                            app.window().request_redraw();
+                        }
+                    }
                     RenderingState::AfterRendering => {
                         // Nothing needed after UI rendering
                     }
@@ -156,15 +298,23 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         slint::TimerMode::Repeated,
         std::time::Duration::from_millis(16), // ~60 FPS
         move || {
+            // Run as many fixed simulation ticks as the elapsed time calls for (none while paused, unless a single
+            // step was requested), so the game runs at the same speed whatever the timer or frame rate manages;
+            // RenderSystem interpolates transforms by the leftover fraction of a tick
+            let ticks = GameLoop::begin_frame();
+            for _ in 0..ticks {
+                TransformInterpolation::snapshot();
+                keyboard_system_for_timer.update(GameLoop::fixed_delta());
+                Program::update();
+            }
+            keyboard_system_for_timer.update_frame();
+
             // Update UI with current game state
             if let Some(app) = ui_app_weak_for_animation.upgrade() {
                 // Request redraw for animation
                 app.window().request_redraw();
             }
 
-            // Call system update methods each frame
-            keyboard_system_for_timer.update();
-            
             // InterfaceSystem is now event-driven, no need for constant updates
         }
     );
@@ -173,11 +323,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("🎮 Game Controls:");
     println!("   - WASD: Movement (logged to console)");
     println!("   - Mouse: Camera look (position logged)");
+    println!("   - Space / Ctrl or C / Shift: Jump, crouch, sprint (Play mode)");
     println!("   - Tab: Toggle cursor lock for FPS mode");
     println!("   - Escape: Unlock cursor");
+    println!("   - P: Pause/resume simulation");
+    println!("   - Period: Step one simulation tick while paused");
+    println!("   - Alt+Enter: Toggle fullscreen");
+    println!("🧰 CLI: wet-crab [scene.json] [--play] [--safe-mode] [--headless --run-ticks N] [--import model.gltf] [--bench scene.json --frames N]");
     println!("📱 Debug UI: Real-time game state overlay");
 
     ui_app.run()?;
+    SafeMode::end_session();
 
+    if let Some(e) = bench_error.take() {
+        return Err(e.into());
+    }
     Ok(())
 }